@@ -0,0 +1,64 @@
+//! Sharpe^2 computation of the SP1 guest, in plain Rust.
+//!
+//! Kept free of `sp1_zkvm` so the STARK prover's tests can compile this file
+//! directly (`#[path]`) and check the guest against the mock bots.
+
+pub const SHARPE_SCALE: i128 = 10000;
+
+/// Largest accepted |return| in basis points. Mirrors
+/// `stark_prover::mock_data::MAX_RETURN_BPS`.
+const MAX_RETURN_BPS: i64 = 1 << 31;
+
+/// Signed trade return in basis points. Mirrors
+/// `stark_prover::mock_data::TradeReturn` (the guest cannot depend on the
+/// prover crate), so both sides reject the same out-of-range inputs.
+#[derive(Clone, Copy)]
+struct TradeReturn(i64);
+
+impl TradeReturn {
+    fn new(bps: i64) -> Option<Self> {
+        if bps.unsigned_abs() > MAX_RETURN_BPS as u64 {
+            return None;
+        }
+        Some(TradeReturn(bps))
+    }
+
+    fn bps(self) -> i64 {
+        self.0
+    }
+
+    fn checked_square(self) -> Option<i128> {
+        (self.0 as i128).checked_mul(self.0 as i128)
+    }
+}
+
+/// Public outputs: (trade_count, total_return, sharpe_sq_scaled).
+///
+/// Panics on fewer than 2 trades, out-of-range returns or zero variance,
+/// which aborts the guest.
+pub fn compute(returns_bps: &[i64]) -> (u64, i64, u64) {
+    let n = returns_bps.len() as i128;
+    assert!(n > 1, "need at least 2 trades");
+
+    // Accumulate cumulative return and cumulative squared return
+    let mut cum_ret: i128 = 0;
+    let mut cum_sq: i128 = 0;
+
+    for &bps in returns_bps {
+        let r = TradeReturn::new(bps).expect("trade return out of range");
+        cum_ret += r.bps() as i128;
+        cum_sq = cum_sq
+            .checked_add(r.checked_square().expect("square overflow"))
+            .expect("cum_sq overflow");
+    }
+
+    // Sharpe^2 equation (integer):
+    //   sharpe_sq_scaled = cum_ret^2 * SCALE / (N * cum_sq - cum_ret^2)
+    let cum_ret_sq = cum_ret * cum_ret;
+    let denom = n * cum_sq - cum_ret_sq;
+    assert!(denom > 0, "degenerate: zero variance");
+
+    let sharpe_sq_scaled = (cum_ret_sq * SHARPE_SCALE) / denom;
+
+    (returns_bps.len() as u64, cum_ret as i64, sharpe_sq_scaled as u64)
+}
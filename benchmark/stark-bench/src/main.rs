@@ -2,11 +2,21 @@
 //!
 //! Wraps the existing prover crate to measure wall-clock proof generation time
 //! and proof size. Outputs JSON compatible with the benchmark results format.
+//!
+//! `--compare-hashes` instead proves the bot once per transcript hash (Keccak
+//! and Poseidon), timing the commit stage and the whole proof, plus the cost
+//! of building the trace-LDE Merkle tree with each hash.
 
 use alloy_primitives::U256;
 use clap::Parser;
-use stark_prover::mock_data::{bot_a_aggressive_eth, bot_b_safe_hedger};
-use stark_prover::prove_sharpe;
+use stark_prover::channel::TranscriptKind;
+use stark_prover::domain::{fft, ifft};
+use stark_prover::keccak::keccak_hash_two;
+use stark_prover::mock_data::{bot_a_aggressive_eth, bot_b_safe_hedger, MockBot};
+use stark_prover::poseidon::poseidon_hash_two;
+use stark_prover::proof::SerializedProof;
+use stark_prover::sharpe_trace::SharpeTrace;
+use stark_prover::{prove_sharpe, prove_sharpe_step, ProveOptions, ProverState, StepResult, BLOWUP};
 use std::time::Instant;
 
 #[derive(Parser)]
@@ -27,6 +37,10 @@ struct Cli {
     /// Warmup iterations (excluded from results)
     #[arg(long, default_value = "2")]
     warmup: usize,
+
+    /// Compare Keccak and Poseidon hashing instead of the default timing run
+    #[arg(long)]
+    compare_hashes: bool,
 }
 
 fn main() {
@@ -40,6 +54,17 @@ fn main() {
 
     let claimed = U256::from(bot.expected_sharpe_sq_scaled);
 
+    if cli.compare_hashes {
+        println!(
+            "=== STARK Hash Comparison: {} ({} warmup + {} measured, {} queries) ===",
+            bot.name, cli.warmup, cli.iterations, cli.num_queries
+        );
+        let (mut result, _) = hash_comparison(&bot, cli.num_queries, cli.warmup, cli.iterations);
+        result["bot"] = serde_json::json!(cli.bot);
+        write_result(&format!("stark-hashes-{}", cli.bot), &result);
+        return;
+    }
+
     println!(
         "=== STARK Benchmark: {} ({} warmup + {} measured, {} queries) ===",
         bot.name, cli.warmup, cli.iterations, cli.num_queries
@@ -95,10 +120,170 @@ fn main() {
         "setup": "Transparent"
     });
 
-    println!("\n{}", serde_json::to_string_pretty(&result).unwrap());
+    write_result(&format!("stark-{}", cli.bot), &result);
+}
+
+/// Print `result` and write it to `benchmark/results/<name>.json`.
+fn write_result(name: &str, result: &serde_json::Value) {
+    println!("\n{}", serde_json::to_string_pretty(result).unwrap());
 
-    // Write to file
-    let path = format!("{}/../../benchmark/results/stark-{}.json", env!("CARGO_MANIFEST_DIR"), cli.bot);
-    std::fs::write(&path, serde_json::to_string_pretty(&result).unwrap())
+    let path = format!("{}/../../benchmark/results/{}.json", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::write(&path, serde_json::to_string_pretty(result).unwrap())
         .unwrap_or_else(|e| eprintln!("warning: could not write {path}: {e}"));
 }
+
+/// Prove `bot` under each transcript hash and report average timings (ms)
+/// per hash under `keccak` / `poseidon`. Also returns the last proof of each
+/// mode, in that order.
+///
+/// Trace and FRI trees are Keccak in both modes, so `merkle_commit_ms`
+/// separately times the trace-LDE tree built with each hash.
+fn hash_comparison(
+    bot: &MockBot,
+    num_queries: usize,
+    warmup: usize,
+    iterations: usize,
+) -> (serde_json::Value, Vec<SerializedProof>) {
+    let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+    let ldes = trace_ldes(bot);
+    let mut result = serde_json::json!({
+        "system": "stark",
+        "mode": "hash-comparison",
+        "trade_count": bot.trades.len(),
+        "num_queries": num_queries,
+        "iterations": iterations,
+    });
+    let mut proofs = Vec::new();
+
+    for (name, transcript, hash) in [
+        ("keccak", TranscriptKind::Keccak, keccak_hash_two as fn(U256, U256) -> U256),
+        ("poseidon", TranscriptKind::Poseidon, poseidon_hash_two),
+    ] {
+        for _ in 0..warmup {
+            let _ = timed_proof(bot, claimed, num_queries, transcript);
+        }
+
+        let (mut commit_ms, mut total_ms, mut merkle_ms) = (0.0, 0.0, 0.0);
+        let mut proof = None;
+        for _ in 0..iterations.max(1) {
+            let (commit, total, p) = timed_proof(bot, claimed, num_queries, transcript);
+            commit_ms += commit;
+            total_ms += total;
+            proof = Some(p);
+
+            let start = Instant::now();
+            let _ = trace_lde_merkle_root(&ldes, hash);
+            merkle_ms += start.elapsed().as_secs_f64() * 1000.0;
+        }
+
+        let runs = iterations.max(1) as f64;
+        println!("  {}: commit {:.2}ms, total {:.2}ms", name, commit_ms / runs, total_ms / runs);
+        result[name] = serde_json::json!({
+            "commit_phase_ms": commit_ms / runs,
+            "total_proof_ms": total_ms / runs,
+            "merkle_commit_ms": merkle_ms / runs,
+        });
+        proofs.push(proof.unwrap());
+    }
+
+    (result, proofs)
+}
+
+/// Step through one proof, returning (commit-stage ms, total ms, proof).
+fn timed_proof(
+    bot: &MockBot,
+    claimed: U256,
+    num_queries: usize,
+    transcript: TranscriptKind,
+) -> (f64, f64, SerializedProof) {
+    let options = ProveOptions { transcript, ..ProveOptions::default() };
+    let start = Instant::now();
+    let mut state = ProverState::new(&bot.trades, claimed, num_queries, None, &options);
+    let mut commit_ms = 0.0;
+
+    loop {
+        let stage = state.next_stage();
+        let stage_start = Instant::now();
+        match prove_sharpe_step(state) {
+            StepResult::InProgress(next) => {
+                if stage == "commit" {
+                    commit_ms = stage_start.elapsed().as_secs_f64() * 1000.0;
+                }
+                state = next;
+            }
+            StepResult::Done(proof) => {
+                return (commit_ms, start.elapsed().as_secs_f64() * 1000.0, proof);
+            }
+        }
+    }
+}
+
+/// The bot's Sharpe trace columns extended to the LDE domain.
+fn trace_ldes(bot: &MockBot) -> Vec<Vec<U256>> {
+    let trace = SharpeTrace::generate(&bot.trades, None);
+    let log_trace_len = trace.log_len();
+    let log_lde_size = log_trace_len + BLOWUP.trailing_zeros();
+
+    trace
+        .into_columns()
+        .into_iter()
+        .map(|mut column| {
+            ifft(&mut column, log_trace_len);
+            column.resize(1 << log_lde_size, U256::ZERO);
+            fft(&mut column, log_lde_size);
+            column
+        })
+        .collect()
+}
+
+/// Root of the trace-LDE tree laid out as `commit_trace_multi` builds it
+/// (rows chain-hashed into leaves, then paired), with `hash` at every node.
+fn trace_lde_merkle_root(ldes: &[Vec<U256>], hash: fn(U256, U256) -> U256) -> U256 {
+    let mut level: Vec<U256> = (0..ldes[0].len())
+        .map(|row| ldes[1..].iter().fold(ldes[0][row], |acc, column| hash(acc, column[row])))
+        .collect();
+    while level.len() > 1 {
+        level = level.chunks(2).map(|pair| hash(pair[0], pair[1])).collect();
+    }
+    level[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stark_prover::commit::commit_trace_multi;
+
+    #[test]
+    fn test_keccak_tree_matches_prover_commitment() {
+        let ldes = trace_ldes(&bot_a_aggressive_eth());
+        let refs: Vec<&[U256]> = ldes.iter().map(|c| &c[..]).collect();
+        assert_eq!(trace_lde_merkle_root(&ldes, keccak_hash_two), commit_trace_multi(&refs, None).root());
+        assert_ne!(trace_lde_merkle_root(&ldes, poseidon_hash_two), commit_trace_multi(&refs, None).root());
+    }
+
+    #[test]
+    fn test_hash_comparison_reports_both_modes() {
+        let (result, proofs) = hash_comparison(&bot_a_aggressive_eth(), 4, 0, 1);
+
+        for mode in ["keccak", "poseidon"] {
+            for field in ["commit_phase_ms", "total_proof_ms", "merkle_commit_ms"] {
+                assert!(result[mode][field].is_number(), "missing {}.{}", mode, field);
+            }
+        }
+
+        // Both modes produce proofs the on-chain verifier accepts
+        assert_eq!(proofs.len(), 2);
+        for proof in &proofs {
+            assert!(stark_verifier::stark::verify_sharpe_stark(
+                &proof.public_inputs,
+                &proof.commitments,
+                &proof.ood_values,
+                &proof.fri_final_poly,
+                &proof.query_values,
+                &proof.query_paths,
+                &proof.query_metadata,
+            ));
+        }
+        assert_ne!(proofs[0].query_metadata[0], proofs[1].query_metadata[0]);
+    }
+}
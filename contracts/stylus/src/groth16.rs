@@ -0,0 +1,705 @@
+//! Groth16 Verification over the BN254 Precompiles
+//!
+//! Verifies a Groth16 proof that wraps a Sharpe STARK proof: the SNARK attests
+//! "the STARK verifier accepts these public inputs", trading the transparent
+//! verifier's ~1.25M gas for a pairing check of a few hundred thousand. The
+//! curve arithmetic runs in the EIP-196/197 precompiles (0x06 ecAdd, 0x07 ecMul,
+//! 0x08 ecPairing); the recursion circuit itself lives off-chain.
+//!
+//! Points use the precompile encoding: G1 = [x, y], G2 = [x_im, x_re, y_im, y_re],
+//! with all-zero coordinates for the point at infinity.
+
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+
+use crate::field::BN254_PRIME;
+
+/// BN254 base field modulus q (curve coordinates live in F_q and F_q²).
+/// q = 21888242871839275222246405745257275088696311157297823662689037894645226208583
+pub const FQ_MODULUS: U256 = U256::from_limbs([
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+/// Precompile addresses (EIP-196 / EIP-197).
+pub const EC_ADD: u8 = 0x06;
+pub const EC_MUL: u8 = 0x07;
+pub const EC_PAIRING: u8 = 0x08;
+
+/// Proof words: [A.x, A.y, B.x_im, B.x_re, B.y_im, B.y_re, C.x, C.y].
+pub const GROTH16_PROOF_WORDS: usize = 8;
+
+pub type G1 = [U256; 2];
+pub type G2 = [U256; 4];
+
+/// Verifying-key words before the IC points: α (G1), then β, γ, δ (G2).
+pub const VK_HEADER_WORDS: usize = 2 + 3 * 4;
+
+/// Groth16 verifying key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    pub alpha_g1: G1,
+    pub beta_g2: G2,
+    pub gamma_g2: G2,
+    pub delta_g2: G2,
+    /// Public-input bases: vk_x = ic[0] + Σ input_i · ic[i + 1].
+    pub ic: Vec<G1>,
+}
+
+impl VerifyingKey {
+    /// Decode `[α, β, γ, δ, ic[0], ic[1], ...]` in the precompile encoding,
+    /// the layout the contract keeps in storage. `None` unless the words hold
+    /// the header and at least one whole IC point.
+    pub fn from_words(words: &[U256]) -> Option<Self> {
+        let (header, ic) = (words.get(..VK_HEADER_WORDS)?, &words[VK_HEADER_WORDS..]);
+        if ic.is_empty() || ic.len() % 2 != 0 {
+            return None;
+        }
+        let g2 = |i: usize| [header[i], header[i + 1], header[i + 2], header[i + 3]];
+        Some(VerifyingKey {
+            alpha_g1: [header[0], header[1]],
+            beta_g2: g2(2),
+            gamma_g2: g2(6),
+            delta_g2: g2(10),
+            ic: ic.chunks_exact(2).map(|p| [p[0], p[1]]).collect(),
+        })
+    }
+
+    /// Encode in the `from_words` layout.
+    pub fn to_words(&self) -> Vec<U256> {
+        let mut words = Vec::with_capacity(VK_HEADER_WORDS + 2 * self.ic.len());
+        words.extend_from_slice(&self.alpha_g1);
+        for g2 in [self.beta_g2, self.gamma_g2, self.delta_g2] {
+            words.extend_from_slice(&g2);
+        }
+        words.extend(self.ic.iter().flatten());
+        words
+    }
+}
+
+/// Access to the BN254 precompiles.
+pub trait Bn254Precompiles {
+    /// Static-call precompile `address` with `input`; `None` if it fails.
+    fn call_precompile(&self, address: u8, input: &[u8]) -> Option<Vec<u8>>;
+}
+
+/// Verify a Groth16 `proof` for `public_inputs` under `vk`.
+///
+/// Checks e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) == 1. Public inputs must be
+/// reduced scalars and there must be exactly one per non-constant IC point.
+pub fn verify<P: Bn254Precompiles>(
+    precompiles: &P,
+    vk: &VerifyingKey,
+    proof: &[U256],
+    public_inputs: &[U256],
+) -> bool {
+    if proof.len() != GROTH16_PROOF_WORDS || public_inputs.len() + 1 != vk.ic.len() {
+        return false;
+    }
+    if public_inputs.iter().any(|x| *x >= BN254_PRIME) || proof.iter().any(|w| *w >= FQ_MODULUS) {
+        return false;
+    }
+
+    let vk_x = match public_input_commitment(precompiles, vk, public_inputs) {
+        Some(point) => point,
+        None => return false,
+    };
+
+    let a = [proof[0], proof[1]];
+    let b = [proof[2], proof[3], proof[4], proof[5]];
+    let c = [proof[6], proof[7]];
+
+    let mut input = Vec::with_capacity(4 * 6 * 32);
+    for (g1, g2) in [
+        (negate(a), b),
+        (vk.alpha_g1, vk.beta_g2),
+        (vk_x, vk.gamma_g2),
+        (c, vk.delta_g2),
+    ] {
+        push_words(&mut input, &g1);
+        push_words(&mut input, &g2);
+    }
+
+    match precompiles.call_precompile(EC_PAIRING, &input) {
+        Some(out) => out.len() == 32 && U256::from_be_slice(&out) == U256::from(1u64),
+        None => false,
+    }
+}
+
+/// vk_x = ic[0] + Σ input_i · ic[i + 1] via ecMul / ecAdd.
+fn public_input_commitment<P: Bn254Precompiles>(
+    precompiles: &P,
+    vk: &VerifyingKey,
+    public_inputs: &[U256],
+) -> Option<G1> {
+    let mut acc = vk.ic[0];
+    for (x, base) in public_inputs.iter().zip(&vk.ic[1..]) {
+        let mut input = Vec::with_capacity(3 * 32);
+        push_words(&mut input, base);
+        push_words(&mut input, &[*x]);
+        let term = decode_g1(&precompiles.call_precompile(EC_MUL, &input)?)?;
+
+        let mut input = Vec::with_capacity(4 * 32);
+        push_words(&mut input, &acc);
+        push_words(&mut input, &term);
+        acc = decode_g1(&precompiles.call_precompile(EC_ADD, &input)?)?;
+    }
+    Some(acc)
+}
+
+/// -P = (x, q - y); infinity is its own negation.
+fn negate(p: G1) -> G1 {
+    if p[1].is_zero() {
+        p
+    } else {
+        [p[0], FQ_MODULUS - p[1]]
+    }
+}
+
+fn push_words(buf: &mut Vec<u8>, words: &[U256]) {
+    for w in words {
+        buf.extend_from_slice(&w.to_be_bytes::<32>());
+    }
+}
+
+fn decode_g1(out: &[u8]) -> Option<G1> {
+    if out.len() != 64 {
+        return None;
+    }
+    Some([U256::from_be_slice(&out[..32]), U256::from_be_slice(&out[32..])])
+}
+
+#[cfg(test)]
+mod tests {
+    //! Reference BN254 backend: a direct (slow) implementation of the three
+    //! precompiles, used to check the verifier's encoding end to end.
+
+    use super::*;
+    use alloc::vec;
+    use ruint::Uint;
+
+    const Q: U256 = FQ_MODULUS;
+    /// 6u + 2, the optimal ate loop count (65 bits; the top bit is implicit).
+    const ATE_LOOP_COUNT: u128 = 29793968203157093288;
+
+    fn add(a: U256, b: U256) -> U256 { a.add_mod(b, Q) }
+    fn sub(a: U256, b: U256) -> U256 { a.add_mod(Q - b, Q) }
+    fn mul(a: U256, b: U256) -> U256 { a.mul_mod(b, Q) }
+    fn inv(a: U256) -> U256 { a.pow_mod(Q - U256::from(2u64), Q) }
+    fn k(v: u64) -> U256 { U256::from(v) }
+
+    // ---- F_q² = F_q[i] / (i² + 1) ----
+
+    type Fq2 = [U256; 2];
+
+    fn add2(a: Fq2, b: Fq2) -> Fq2 { [add(a[0], b[0]), add(a[1], b[1])] }
+    fn sub2(a: Fq2, b: Fq2) -> Fq2 { [sub(a[0], b[0]), sub(a[1], b[1])] }
+    fn mul2(a: Fq2, b: Fq2) -> Fq2 {
+        [
+            sub(mul(a[0], b[0]), mul(a[1], b[1])),
+            add(mul(a[0], b[1]), mul(a[1], b[0])),
+        ]
+    }
+    fn scale2(a: Fq2, s: U256) -> Fq2 { [mul(a[0], s), mul(a[1], s)] }
+    fn inv2(a: Fq2) -> Fq2 {
+        let n = inv(add(mul(a[0], a[0]), mul(a[1], a[1])));
+        [mul(a[0], n), mul(sub(U256::ZERO, a[1]), n)]
+    }
+    fn conj2(a: Fq2) -> Fq2 { [a[0], sub(U256::ZERO, a[1])] }
+    fn pow2(a: Fq2, e: U256) -> Fq2 {
+        let mut result = [k(1), U256::ZERO];
+        for i in (0..e.bit_len()).rev() {
+            result = mul2(result, result);
+            if e.bit(i) {
+                result = mul2(result, a);
+            }
+        }
+        result
+    }
+
+    // ---- Affine curve arithmetic, generic over the coordinate field ----
+
+    type P1 = Option<(U256, U256)>;
+    type P2 = Option<(Fq2, Fq2)>;
+
+    fn g1_add(p: P1, r: P1) -> P1 {
+        let ((x1, y1), (x2, y2)) = match (p, r) {
+            (None, _) => return r,
+            (_, None) => return p,
+            (Some(a), Some(b)) => (a, b),
+        };
+        let m = if x1 == x2 {
+            if add(y1, y2).is_zero() {
+                return None;
+            }
+            mul(mul(k(3), mul(x1, x1)), inv(add(y1, y1)))
+        } else {
+            mul(sub(y2, y1), inv(sub(x2, x1)))
+        };
+        let x3 = sub(sub(mul(m, m), x1), x2);
+        Some((x3, sub(mul(m, sub(x1, x3)), y1)))
+    }
+
+    fn g1_mul(p: P1, s: U256) -> P1 {
+        let mut acc = None;
+        for i in (0..s.bit_len()).rev() {
+            acc = g1_add(acc, acc);
+            if s.bit(i) {
+                acc = g1_add(acc, p);
+            }
+        }
+        acc
+    }
+
+    fn g2_add(p: P2, r: P2) -> P2 {
+        let ((x1, y1), (x2, y2)) = match (p, r) {
+            (None, _) => return r,
+            (_, None) => return p,
+            (Some(a), Some(b)) => (a, b),
+        };
+        let m = if x1 == x2 {
+            if add2(y1, y2) == [U256::ZERO; 2] {
+                return None;
+            }
+            mul2(scale2(mul2(x1, x1), k(3)), inv2(add2(y1, y1)))
+        } else {
+            mul2(sub2(y2, y1), inv2(sub2(x2, x1)))
+        };
+        let x3 = sub2(sub2(mul2(m, m), x1), x2);
+        Some((x3, sub2(mul2(m, sub2(x1, x3)), y1)))
+    }
+
+    fn g2_mul(p: P2, s: U256) -> P2 {
+        let mut acc = None;
+        for i in (0..s.bit_len()).rev() {
+            acc = g2_add(acc, acc);
+            if s.bit(i) {
+                acc = g2_add(acc, p);
+            }
+        }
+        acc
+    }
+
+    fn on_g1(p: P1) -> bool {
+        p.is_none_or(|(x, y)| mul(y, y) == add(mul(mul(x, x), x), k(3)))
+    }
+
+    /// Twist curve y² = x³ + 3 / (9 + i).
+    fn on_g2(p: P2) -> bool {
+        let b2 = mul2([k(3), U256::ZERO], inv2([k(9), k(1)]));
+        p.is_none_or(|(x, y)| mul2(y, y) == add2(mul2(mul2(x, x), x), b2))
+    }
+
+    // ---- F_q¹² = F_q[w] / (w¹² - 18 w⁶ + 82), with w⁶ = 9 + i ----
+
+    type Fq12 = [U256; 12];
+
+    fn one12() -> Fq12 {
+        let mut r = [U256::ZERO; 12];
+        r[0] = k(1);
+        r
+    }
+
+    fn mul12(a: &Fq12, b: &Fq12) -> Fq12 {
+        let mut t = [U256::ZERO; 23];
+        for i in 0..12 {
+            if a[i].is_zero() {
+                continue;
+            }
+            for j in 0..12 {
+                t[i + j] = add(t[i + j], mul(a[i], b[j]));
+            }
+        }
+        for i in (12..23).rev() {
+            let top = t[i];
+            t[i - 6] = add(t[i - 6], mul(top, k(18)));
+            t[i - 12] = sub(t[i - 12], mul(top, k(82)));
+        }
+        let mut r = [U256::ZERO; 12];
+        r.copy_from_slice(&t[..12]);
+        r
+    }
+
+    fn pow12(a: &Fq12, e: &[bool]) -> Fq12 {
+        let mut result = one12();
+        for &bit in e {
+            result = mul12(&result, &result);
+            if bit {
+                result = mul12(&result, a);
+            }
+        }
+        result
+    }
+
+    /// Big-endian bits of a 1024-bit exponent.
+    fn bits(e: Uint<1024, 16>) -> Vec<bool> {
+        (0..e.bit_len()).rev().map(|i| e.bit(i)).collect()
+    }
+
+    fn u1024(v: U256) -> Uint<1024, 16> {
+        let mut limbs = [0u64; 16];
+        limbs[..4].copy_from_slice(v.as_limbs());
+        Uint::from_limbs(limbs)
+    }
+
+    /// Powers (w^q)^j, so that f^q = Σ f_j (w^q)^j (the f_j lie in F_q).
+    fn frobenius_basis() -> [Fq12; 12] {
+        let mut w = [U256::ZERO; 12];
+        w[1] = k(1);
+        let wq = pow12(&w, &bits(u1024(Q)));
+        let mut basis = [one12(); 12];
+        for j in 1..12 {
+            basis[j] = mul12(&basis[j - 1], &wq);
+        }
+        basis
+    }
+
+    fn frobenius(a: &Fq12, basis: &[Fq12; 12]) -> Fq12 {
+        let mut r = [U256::ZERO; 12];
+        for (c, b) in a.iter().zip(basis) {
+            for (ri, bi) in r.iter_mut().zip(b) {
+                *ri = add(*ri, mul(*c, *bi));
+            }
+        }
+        r
+    }
+
+    /// a^-1 = (a^q · a^q² ··· a^q¹¹) / N(a), with the norm N(a) in F_q.
+    fn inv12(a: &Fq12, basis: &[Fq12; 12]) -> Fq12 {
+        let mut conj = *a;
+        let mut prod = one12();
+        for _ in 1..12 {
+            conj = frobenius(&conj, basis);
+            prod = mul12(&prod, &conj);
+        }
+        let norm = mul12(&prod, a)[0];
+        prod.map(|c| mul(c, inv(norm)))
+    }
+
+    /// f^((q¹² - 1) / r) = (f^((q⁶ - 1)(q² + 1)))^((q⁴ - q² + 1) / r).
+    fn final_exponentiation(f: &Fq12) -> Fq12 {
+        let basis = frobenius_basis();
+        let frob_n = |a: &Fq12, n: usize| (0..n).fold(*a, |acc, _| frobenius(&acc, &basis));
+
+        let f1 = mul12(&frob_n(f, 6), &inv12(f, &basis));
+        let f2 = mul12(&frob_n(&f1, 2), &f1);
+
+        let q = u1024(Q);
+        let q2 = q * q;
+        let hard = (q2 * q2 - q2 + Uint::from(1u64)) / u1024(BN254_PRIME);
+        pow12(&f2, &bits(hard))
+    }
+
+    /// F_q² element a + b·i embedded as (a - 9b) + b·w⁶, shifted by w^shift.
+    fn embed(r: &mut Fq12, a: Fq2, shift: usize, coeff: U256) {
+        r[shift] = add(r[shift], mul(sub(a[0], mul(k(9), a[1])), coeff));
+        r[shift + 6] = add(r[shift + 6], mul(a[1], coeff));
+    }
+
+    /// Line through twisted points R, S evaluated at P; the twist maps
+    /// (x, y) ↦ (x·w², y·w³), so a slope m over F_q² becomes m·w.
+    fn line(r: (Fq2, Fq2), s: (Fq2, Fq2), p: (U256, U256)) -> Fq12 {
+        let ((x1, y1), (x2, y2)) = (r, s);
+        let (xp, yp) = p;
+        let mut out = [U256::ZERO; 12];
+        let minus_one = Q - k(1);
+
+        let m = if x1 != x2 {
+            mul2(sub2(y2, y1), inv2(sub2(x2, x1)))
+        } else if y1 == y2 {
+            mul2(scale2(mul2(x1, x1), k(3)), inv2(add2(y1, y1)))
+        } else {
+            // Vertical line: xp - x1·w²
+            out[0] = xp;
+            embed(&mut out, x1, 2, minus_one);
+            return out;
+        };
+
+        // m·w·(xp - x1·w²) - (yp - y1·w³)
+        embed(&mut out, m, 1, xp);
+        embed(&mut out, sub2(y1, mul2(m, x1)), 3, k(1));
+        out[0] = sub(out[0], yp);
+        out
+    }
+
+    fn miller_loop(q: (Fq2, Fq2), p: (U256, U256)) -> Fq12 {
+        let mut r = q;
+        let mut f = one12();
+        for i in (0..64).rev() {
+            f = mul12(&mul12(&f, &f), &line(r, r, p));
+            r = g2_add(Some(r), Some(r)).unwrap();
+            if ATE_LOOP_COUNT & (1 << i) != 0 {
+                f = mul12(&f, &line(r, q, p));
+                r = g2_add(Some(r), Some(q)).unwrap();
+            }
+        }
+
+        // Frobenius on the twist: (x, y) ↦ (x̄·ξ^((q-1)/3), ȳ·ξ^((q-1)/2)), ξ = 9 + i
+        let xi = [k(9), k(1)];
+        let gx = pow2(xi, (Q - k(1)) / k(3));
+        let gy = pow2(xi, (Q - k(1)) / k(2));
+        let frob = |(x, y): (Fq2, Fq2)| (mul2(conj2(x), gx), mul2(conj2(y), gy));
+        let q1 = frob(q);
+        let (x2, y2) = frob(q1);
+        let neg_q2 = (x2, sub2([U256::ZERO; 2], y2));
+
+        f = mul12(&f, &line(r, q1, p));
+        r = g2_add(Some(r), Some(q1)).unwrap();
+        mul12(&f, &line(r, neg_q2, p))
+    }
+
+    fn pairing_product_is_one(pairs: &[(P1, P2)]) -> bool {
+        let mut f = one12();
+        for (p, q) in pairs {
+            if let (Some(p), Some(q)) = (p, q) {
+                f = mul12(&f, &miller_loop(*q, *p));
+            }
+        }
+        final_exponentiation(&f) == one12()
+    }
+
+    // ---- Precompile encoding ----
+
+    fn word(input: &[u8], i: usize) -> U256 {
+        U256::from_be_slice(&input[32 * i..32 * (i + 1)])
+    }
+
+    fn read_g1(input: &[u8], i: usize) -> Option<P1> {
+        let (x, y) = (word(input, i), word(input, i + 1));
+        let p = if x.is_zero() && y.is_zero() { None } else { Some((x, y)) };
+        (x < Q && y < Q && on_g1(p)).then_some(p)
+    }
+
+    fn read_g2(input: &[u8], i: usize) -> Option<P2> {
+        let w: Vec<U256> = (i..i + 4).map(|j| word(input, j)).collect();
+        let p = if w.iter().all(|v| v.is_zero()) {
+            None
+        } else {
+            Some(([w[1], w[0]], [w[3], w[2]]))
+        };
+        (w.iter().all(|v| *v < Q) && on_g2(p)).then_some(p)
+    }
+
+    fn write_g1(p: P1) -> Vec<u8> {
+        let (x, y) = p.unwrap_or((U256::ZERO, U256::ZERO));
+        [x.to_be_bytes::<32>(), y.to_be_bytes::<32>()].concat()
+    }
+
+    fn to_g1(p: P1) -> G1 {
+        let (x, y) = p.unwrap();
+        [x, y]
+    }
+
+    struct ReferenceBn254;
+
+    impl Bn254Precompiles for ReferenceBn254 {
+        fn call_precompile(&self, address: u8, input: &[u8]) -> Option<Vec<u8>> {
+            match address {
+                EC_ADD if input.len() == 128 => {
+                    Some(write_g1(g1_add(read_g1(input, 0)?, read_g1(input, 2)?)))
+                }
+                EC_MUL if input.len() == 96 => {
+                    Some(write_g1(g1_mul(read_g1(input, 0)?, word(input, 2))))
+                }
+                EC_PAIRING if input.len().is_multiple_of(192) => {
+                    let pairs = (0..input.len() / 192)
+                        .map(|j| Some((read_g1(input, 6 * j)?, read_g2(input, 6 * j + 2)?)))
+                        .collect::<Option<Vec<_>>>()?;
+                    let mut out = vec![0u8; 32];
+                    out[31] = pairing_product_is_one(&pairs) as u8;
+                    Some(out)
+                }
+                _ => None,
+            }
+        }
+    }
+
+    fn u(dec: &str) -> U256 {
+        U256::from_str_radix(dec, 10).unwrap()
+    }
+
+    fn g1_generator() -> P1 {
+        Some((k(1), k(2)))
+    }
+
+    fn g2_generator() -> P2 {
+        Some((
+            [
+                u("10857046999023057135944570762232829481370756359578518086990519993285655852781"),
+                u("11559732032986387107991004021392285783925812861821192530917403151452391805634"),
+            ],
+            [
+                u("8495653923123431417604973247489272438418190587263600148770280649306958101930"),
+                u("4082367875863433681332203403145435568316851327593401208105741076214120093531"),
+            ],
+        ))
+    }
+
+    #[test]
+    fn test_reference_pairing_is_bilinear() {
+        let (g1, g2) = (g1_generator(), g2_generator());
+        assert!(on_g1(g1) && on_g2(g2));
+        assert!(g2_mul(g2, BN254_PRIME).is_none(), "G2 generator must have order r");
+
+        let neg = |p: P1| p.map(|(x, y)| (x, Q - y));
+        // e(2P, 3Q) · e(-6P, Q) == 1
+        assert!(pairing_product_is_one(&[
+            (g1_mul(g1, k(2)), g2_mul(g2, k(3))),
+            (neg(g1_mul(g1, k(6))), g2),
+        ]));
+        // ...but e(2P, 3Q) · e(-5P, Q) != 1 (non-degenerate)
+        assert!(!pairing_product_is_one(&[
+            (g1_mul(g1, k(2)), g2_mul(g2, k(3))),
+            (neg(g1_mul(g1, k(5))), g2),
+        ]));
+    }
+
+    /// Development key for the wrapped Sharpe statement over the four AIR public
+    /// inputs `[trade_count, total_return, sharpe_sq_scaled, merkle_root]`.
+    ///
+    /// Generated from a known trapdoor, so anyone holding it can forge proofs:
+    /// test-only. Deployments store the recursion circuit's key instead.
+    fn dev_wrapped_sharpe_vk() -> VerifyingKey {
+        VerifyingKey {
+            alpha_g1: [
+                U256::from_limbs([0x983a6b86abffe078, 0xcb6fc6ecb801bd76, 0x9a5325f477629386, 0x17072b2ed3bb8d75]),
+                U256::from_limbs([0x77809f7f60d4af9e, 0xadfe3bf05d18f41b, 0x017bb54bfa19377a, 0x168ada6cd130dd52]),
+            ],
+            beta_g2: [
+                U256::from_limbs([0xfebf1cfdedc5ce88, 0xfc05582debaf3184, 0x920873207477f8c7, 0x228b515a17f28b89]),
+                U256::from_limbs([0x0b20d2df91fe9cd3, 0x07f2c699dc69ca83, 0xfcb2614e15d8a3ff, 0x12bb1156a9f6b360]),
+                U256::from_limbs([0xf9578ad08e058f92, 0x6a8eabe45c8d3ccc, 0x97914ddbbfde4880, 0x2b15dc62a5c9e365]),
+                U256::from_limbs([0x04e07152b4d1fa94, 0x55d6b8b077eefeaa, 0x2fcfff325fb9692f, 0x02a4fd764f52470e]),
+            ],
+            gamma_g2: [
+                U256::from_limbs([0xab830aae529097d1, 0xee3c37d427bb6d2e, 0xf51139588acc094c, 0x009edaf0698a8c56]),
+                U256::from_limbs([0x64cd151572a905f7, 0x316244b91de5fb27, 0x75049635faebd124, 0x23ad66f3a7cca9dc]),
+                U256::from_limbs([0x3b4ad21578fcf410, 0x0254559e17cce64e, 0x3022a18a07bdc66d, 0x2700e8a29b7bb45f]),
+                U256::from_limbs([0xa8905f607cf950f8, 0x7c0c715678e4c2be, 0x9988ac099b042b1e, 0x1ad4f87d3b4375a3]),
+            ],
+            delta_g2: [
+                U256::from_limbs([0xf1a4164eae499c2c, 0x701d7a3259b933bf, 0xed8b504bb5b215bc, 0x227071bba5ff3b47]),
+                U256::from_limbs([0x16e299c1b41f8098, 0x8002b5558d8f49ff, 0x677b29739ddccbb7, 0x0c51a367b61d3119]),
+                U256::from_limbs([0x5ea339dd77fac415, 0x763d65b98febf2c4, 0x1e87834c85a6a917, 0x08bb188b2a6187bb]),
+                U256::from_limbs([0x8bc9e09d9be87769, 0xa7d1a205d10788e3, 0x39e8a91325d1ef3b, 0x18fd2fd13be8494c]),
+            ],
+            ic: vec![
+                [
+                    U256::from_limbs([0x57d44f4c71edc28d, 0x743bc401edd63ae8, 0xf7b2bc22bed59866, 0x15514de6a136158e]),
+                    U256::from_limbs([0xe9168b6868d8952e, 0xedb9387320817b62, 0x0c0e504b624afabf, 0x095e28f5ba5d7344]),
+                ],
+                [
+                    U256::from_limbs([0xc970620c29b811f1, 0xd314d2cfb9ef81d4, 0xc1e84cf81ff2776a, 0x1e28260f0ee971de]),
+                    U256::from_limbs([0x9fcd3e7ac918b396, 0x638d28de73895977, 0x4c3c39dab54eaef9, 0x28fc8a72d4ff1265]),
+                ],
+                [
+                    U256::from_limbs([0x8562b0c7ddf00a7d, 0x0c5cc454b19b5555, 0x15d774d320717642, 0x1605ffc1ea2e1aef]),
+                    U256::from_limbs([0x91fe87f6bb7354b3, 0xc67f1776338d6754, 0xdf38ec2d0800d5dd, 0x0cf605873faa8028]),
+                ],
+                [
+                    U256::from_limbs([0x677ea093bdad853c, 0x641f58a4a2bffd3b, 0xcbbfe379a152a976, 0x14b4fa251277a6f4]),
+                    U256::from_limbs([0x5885ce0580ebee36, 0x511819dd87d8da25, 0xbcf8d84efa062c85, 0x28ce094a6d16280a]),
+                ],
+                [
+                    U256::from_limbs([0xedb49c9d9e45d063, 0x3ecb3a3892640ed1, 0x5f105ae102f14cf2, 0x24f253a56d4badbe]),
+                    U256::from_limbs([0x2a0ea2b7ec03f6b1, 0x4ebe686a3063ce39, 0xc6069f16bf09d1ac, 0x1392ab50e020ade3]),
+                ],
+            ],
+        }
+    }
+
+    /// Proof for the Bot A statement (`sharpe_proof_bot_a` public inputs)
+    /// under `dev_wrapped_sharpe_vk`.
+    fn wrapped_proof_bot_a() -> Vec<U256> {
+        let mut proof = Vec::with_capacity(GROTH16_PROOF_WORDS);
+        proof.extend_from_slice(&[
+            U256::from_limbs([0xf0cfa5b4a76589b3, 0x4192b73534cc667d, 0xc544c746269813ac, 0x235658752a7ef475]),
+            U256::from_limbs([0xd396b46fdc166d18, 0x319fd3dd3bad23e3, 0xf40aa8d0ae719339, 0x0106c4ad7d200e59]),
+        ]);
+        proof.extend_from_slice(&[
+            U256::from_limbs([0x9ac218dfab472690, 0xb079e1480365c897, 0x3f784c01aa9bd891, 0x1158386a608a2a7a]),
+            U256::from_limbs([0x158a58605dc0dcd8, 0x2b37149a0fb46857, 0xdb0658cf3d8eccb8, 0x19d48b804e83ca1c]),
+            U256::from_limbs([0xa1d73835d96ef656, 0x369baa323bd118c0, 0xb6d15deb6f9a7226, 0x1a9cef365ac54913]),
+            U256::from_limbs([0x414893bf3712e556, 0xa34f7d8b7c88bf86, 0x9dd46dde96e622e8, 0x000871aa985de8ef]),
+        ]);
+        proof.extend_from_slice(&[
+            U256::from_limbs([0x3c7bf6c2b528db74, 0x86714d7969bd160b, 0xab074d1b96e46c94, 0x0cfc2ae7d818818f]),
+            U256::from_limbs([0x1ec289b16be44fe1, 0x540fd7c9508458ba, 0x5b06296e823345fa, 0x2b1df53d6e053c19]),
+        ]);
+        proof
+    }
+
+    fn bot_a_public_inputs() -> Vec<U256> {
+        vec![
+            U256::from(0xfu64),
+            U256::from(0xbb8u64),
+            U256::from(0xea60u64),
+            U256::from_str_radix("19dcd5ea3705cc53d3063136623f6d5b1585ef6e74614338b52e74d7e138f6c0", 16).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_wrapped_proof_verifies() {
+        let vk = &dev_wrapped_sharpe_vk();
+        for point in vk.ic.iter().chain([&vk.alpha_g1]) {
+            assert!(on_g1(Some((point[0], point[1]))));
+        }
+        for point in [vk.beta_g2, vk.gamma_g2, vk.delta_g2] {
+            assert!(on_g2(Some(([point[1], point[0]], [point[3], point[2]]))));
+        }
+
+        assert!(verify(&ReferenceBn254, vk, &wrapped_proof_bot_a(), &bot_a_public_inputs()));
+    }
+
+    #[test]
+    fn test_verifying_key_words_round_trip() {
+        let vk = dev_wrapped_sharpe_vk();
+        let words = vk.to_words();
+        assert_eq!(words.len(), VK_HEADER_WORDS + 2 * 5);
+        assert_eq!(words[..2], vk.alpha_g1);
+        assert_eq!(words[VK_HEADER_WORDS..VK_HEADER_WORDS + 2], vk.ic[0]);
+        assert_eq!(VerifyingKey::from_words(&words), Some(vk));
+
+        // No IC point, half a point, or a truncated header
+        assert_eq!(VerifyingKey::from_words(&words[..VK_HEADER_WORDS]), None);
+        assert_eq!(VerifyingKey::from_words(&words[..words.len() - 1]), None);
+        assert_eq!(VerifyingKey::from_words(&words[..VK_HEADER_WORDS - 1]), None);
+        assert_eq!(VerifyingKey::from_words(&[]), None);
+    }
+
+    #[test]
+    fn test_wrapped_proof_rejects_tampering() {
+        let vk = &dev_wrapped_sharpe_vk();
+        let proof = wrapped_proof_bot_a();
+        let pi = bot_a_public_inputs();
+
+        // Different claimed Sharpe
+        let mut bad_pi = pi.clone();
+        bad_pi[2] += U256::from(1u64);
+        assert!(!verify(&ReferenceBn254, vk, &proof, &bad_pi));
+
+        // A replaced by another curve point
+        let mut bad_proof = proof.clone();
+        bad_proof[..2].copy_from_slice(&to_g1(g1_mul(g1_generator(), k(42))));
+        assert!(!verify(&ReferenceBn254, vk, &bad_proof, &pi));
+
+        // A off the curve: the precompile rejects the input
+        let mut bad_proof = proof.clone();
+        bad_proof[1] = add(bad_proof[1], k(1));
+        assert!(!verify(&ReferenceBn254, vk, &bad_proof, &pi));
+
+        // Unreduced coordinate or scalar
+        let mut bad_proof = proof.clone();
+        bad_proof[6] += Q;
+        assert!(!verify(&ReferenceBn254, vk, &bad_proof, &pi));
+        let mut bad_pi = pi.clone();
+        bad_pi[0] += BN254_PRIME;
+        assert!(!verify(&ReferenceBn254, vk, &proof, &bad_pi));
+
+        // Wrong lengths
+        assert!(!verify(&ReferenceBn254, vk, &proof[..7], &pi));
+        assert!(!verify(&ReferenceBn254, vk, &proof, &pi[..3]));
+    }
+}
@@ -8,14 +8,21 @@ extern crate alloc;
 
 use alloc::vec;
 use alloc::vec::Vec;
-use stylus_sdk::{alloy_primitives::U256, prelude::*};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    prelude::*,
+};
+use stylus_sdk::prelude::calls::context::Call;
 
 pub mod field;
+pub mod groth16;
 pub mod merkle;
 pub mod mpt;
+pub mod poseidon;
 pub mod stark;
 
 use field::Fp;
+use stark::proof::QueryMetadata;
 
 /// Keccak-based hash of two field elements.
 ///
@@ -25,25 +32,60 @@ use field::Fp;
 /// as a big-endian U256 and converted to Fp (which applies mod BN254_PRIME via
 /// Montgomery conversion).
 ///
-/// This must produce identical output on both the on-chain verifier and off-chain prover.
+/// The encoding is `stark_core::keccak`'s, so it matches the off-chain prover.
 #[inline]
 pub fn keccak_hash_two(a: Fp, b: Fp) -> Fp {
-    let mut buf = [0u8; 64];
-    buf[..32].copy_from_slice(&a.to_be_bytes());
-    buf[32..].copy_from_slice(&b.to_be_bytes());
-    let hash = stylus_sdk::crypto::keccak(&buf);
-    Fp::from_u256(U256::from_be_bytes(hash.0))
+    let buf = stark_core::keccak::hash_two_preimage(&a.to_canonical_bytes(), &b.to_canonical_bytes());
+    Fp::from_u256(U256::from_be_bytes(keccak256(&buf)))
+}
+
+/// `keccak_hash_two` with a one-byte domain tag (`stark_core::keccak::TAG_*`)
+/// prepended to the preimage: Merkle nodes and the Fiat-Shamir channel hash
+/// under distinct tags. Matches the prover's `keccak_hash_two_tagged`.
+#[inline]
+pub fn keccak_hash_two_tagged(tag: u8, a: Fp, b: Fp) -> Fp {
+    let buf = stark_core::keccak::hash_two_tagged_preimage(tag, &a.to_canonical_bytes(), &b.to_canonical_bytes());
+    Fp::from_u256(U256::from_be_bytes(keccak256(&buf)))
+}
+
+/// keccak256 through the Stylus host precompile (on-chain builds).
+#[cfg(not(any(test, feature = "native-keccak")))]
+#[inline]
+pub(crate) fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    stylus_sdk::crypto::keccak(bytes).0
+}
+
+/// keccak256 in software via `stark_core` (tests and ABI export), pinned to
+/// the on-chain digests by `test_keccak_backends_match_onchain_vectors`.
+#[cfg(any(test, feature = "native-keccak"))]
+#[inline]
+pub(crate) fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    stark_core::keccak::keccak256(bytes)
 }
 
 sol_storage! {
     #[entrypoint]
     pub struct StarkVerifier {
+        /// Verifying key of the wrapped Sharpe statement
+        /// (`groth16::VerifyingKey::to_words`), set once by the deployer.
+        uint256[] wrapped_sharpe_vk;
     }
 }
 
 #[public]
 impl StarkVerifier {
+    /// Store the Groth16 key `verify_sharpe_wrapped` checks against: the
+    /// recursion circuit's, as `groth16::VerifyingKey::to_words`. Nothing
+    /// else can set it; deploying with an empty key disables wrapped proofs.
+    #[constructor]
+    pub fn constructor(&mut self, wrapped_sharpe_vk: Vec<U256>) {
+        for word in wrapped_sharpe_vk {
+            self.wrapped_sharpe_vk.push(word);
+        }
+    }
+
     /// Verify a full STARK proof of Sharpe ratio verification.
+    #[allow(clippy::too_many_arguments)]
     pub fn verify_sharpe_proof(
         &self,
         public_inputs: Vec<U256>,
@@ -65,6 +107,97 @@ impl StarkVerifier {
         )
     }
 
+    /// Verify a Sharpe STARK proof passed as one packed byte blob.
+    ///
+    /// Same verdict as `verify_sharpe_proof` on the unpacked arrays, without
+    /// the ABI offset/length words of seven separate arrays. Layout: seven
+    /// big-endian u32 word counts, then the 32-byte big-endian words.
+    pub fn verify_sharpe_packed(&self, proof: Vec<u8>) -> bool {
+        stark::verify_sharpe_packed(&proof)
+    }
+
+    /// Verify a full STARK proof of Sortino ratio verification.
+    ///
+    /// Same layout as `verify_sharpe_proof`; `public_inputs[2]` is the claimed
+    /// `sortino_sq_scaled` (Sortino^2 * 10000).
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_sortino_proof(
+        &self,
+        public_inputs: Vec<U256>,
+        commitments: Vec<U256>,
+        ood_values: Vec<U256>,
+        fri_final_poly: Vec<U256>,
+        query_values: Vec<U256>,
+        query_paths: Vec<U256>,
+        query_metadata: Vec<U256>,
+    ) -> bool {
+        stark::verify_sortino_stark(
+            &public_inputs,
+            &commitments,
+            &ood_values,
+            &fri_final_poly,
+            &query_values,
+            &query_paths,
+            &query_metadata,
+        )
+    }
+
+    /// Verify a STARK proof and return its Fiat-Shamir transcript digest.
+    ///
+    /// The digest is a function of the public inputs and commitments only, so
+    /// an auditor can confirm two proofs of the same statement drew the same
+    /// OOD point and challenges.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_sharpe_and_transcript(
+        &self,
+        public_inputs: Vec<U256>,
+        commitments: Vec<U256>,
+        ood_values: Vec<U256>,
+        fri_final_poly: Vec<U256>,
+        query_values: Vec<U256>,
+        query_paths: Vec<U256>,
+        query_metadata: Vec<U256>,
+    ) -> (bool, U256) {
+        stark::verify_sharpe_stark_with_transcript(
+            &public_inputs,
+            &commitments,
+            &ood_values,
+            &fri_final_poly,
+            &query_values,
+            &query_paths,
+            &query_metadata,
+        )
+    }
+
+    /// Verify a Sharpe STARK proof, requiring at least `min_queries` queries,
+    /// and return (verdict, num_queries, num_fri_layers) from its metadata.
+    ///
+    /// Lets an integrator enforce its own query-count policy and price the
+    /// verification in one call.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_sharpe_with_stats(
+        &self,
+        public_inputs: Vec<U256>,
+        commitments: Vec<U256>,
+        ood_values: Vec<U256>,
+        fri_final_poly: Vec<U256>,
+        query_values: Vec<U256>,
+        query_paths: Vec<U256>,
+        query_metadata: Vec<U256>,
+        min_queries: U256,
+    ) -> (bool, U256, U256) {
+        stark::verify_sharpe_stark_with_stats(
+            &public_inputs,
+            &commitments,
+            &ood_values,
+            &fri_final_poly,
+            &query_values,
+            &query_paths,
+            &query_metadata,
+            min_queries,
+        )
+    }
+
     /// Verify a STARK proof with commitment binding (Phase A — no large calldata).
     ///
     /// On-chain verification:
@@ -82,6 +215,7 @@ impl StarkVerifier {
     /// Ethereum transactions. A malicious client could submit fabricated hashes
     /// and a matching STARK proof. On-chain MPT inclusion proof (Phase B) is
     /// required to close this trust assumption.
+    #[allow(clippy::too_many_arguments)]
     pub fn verify_sharpe_with_commitment(
         &self,
         public_inputs: Vec<U256>,
@@ -120,19 +254,183 @@ impl StarkVerifier {
         }
 
         // Step 3: Cross-check — pi[3] == merkle_root of constant commitment column
-        if public_inputs.len() < 4 || query_metadata.len() < 3 {
+        commitment_binding_holds(&public_inputs, &query_metadata, expected_commitment)
+    }
+
+    /// Verify a STARK proof against an aggregate commitment supplied by the caller.
+    ///
+    /// Same as `verify_sharpe_with_commitment`, but skips the on-chain keccak
+    /// hash chain over receipt hashes. Only the `pi[3]` cross-check against the
+    /// constant-column Merkle root of `aggregate_commitment` is performed.
+    ///
+    /// ## Security
+    ///
+    /// The caller vouches for `aggregate_commitment`. Nothing here ties it to any
+    /// receipt hashes; use this only when the aggregate was already established
+    /// on-chain (e.g. in a prior `verify_sharpe_with_commitment` call) or by a
+    /// separate attestation the integrating contract trusts.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_sharpe_with_precommitted(
+        &self,
+        public_inputs: Vec<U256>,
+        commitments: Vec<U256>,
+        ood_values: Vec<U256>,
+        fri_final_poly: Vec<U256>,
+        query_values: Vec<U256>,
+        query_paths: Vec<U256>,
+        query_metadata: Vec<U256>,
+        aggregate_commitment: U256,
+    ) -> bool {
+        let commitment = Fp::from_u256(aggregate_commitment);
+        if commitment == Fp::ZERO {
             return false;
         }
 
-        let pi3 = Fp::from_u256(public_inputs[3]);
-        let log_trace_len = query_metadata[2].as_limbs()[0] as u32;
+        let stark_valid = stark::verify_sharpe_stark(
+            &public_inputs,
+            &commitments,
+            &ood_values,
+            &fri_final_poly,
+            &query_values,
+            &query_paths,
+            &query_metadata,
+        );
 
-        let expected_merkle_root = mpt::compute_constant_merkle_root(
-            expected_commitment,
-            log_trace_len,
+        if !stark_valid {
+            return false;
+        }
+
+        commitment_binding_holds(&public_inputs, &query_metadata, commitment)
+    }
+
+    /// Verify a STARK proof whose `pi[3]` (dataset merkle_root) is one of
+    /// `allowed_roots`.
+    ///
+    /// A policy hook for protocols that only accept pre-registered data
+    /// sources (e.g. audited GMX subaccounts): the caller supplies the roots
+    /// those datasets commit to. An empty allowlist rejects every proof.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_sharpe_against_allowlist(
+        &self,
+        public_inputs: Vec<U256>,
+        commitments: Vec<U256>,
+        ood_values: Vec<U256>,
+        fri_final_poly: Vec<U256>,
+        query_values: Vec<U256>,
+        query_paths: Vec<U256>,
+        query_metadata: Vec<U256>,
+        allowed_roots: Vec<U256>,
+    ) -> bool {
+        // Step 1: Allowlist lookup (cheap, before the STARK)
+        if !root_is_allowed(&public_inputs, &allowed_roots) {
+            return false;
+        }
+
+        // Step 2: Verify STARK proof
+        stark::verify_sharpe_stark(
+            &public_inputs,
+            &commitments,
+            &ood_values,
+            &fri_final_poly,
+            &query_values,
+            &query_paths,
+            &query_metadata,
+        )
+    }
+
+    /// Verify a STARK proof whose public inputs hash to `input_hash`.
+    ///
+    /// For callers that precommit to a statement by its hash (stored by an
+    /// integrating contract, or passed along as one word) rather than by its
+    /// inputs. `public_inputs` carries the values the AIR constraints read,
+    /// and must hash to `input_hash` (see `public_input_hash`); for the Sharpe
+    /// AIR that is all of them, but the check generalises to AIRs where the
+    /// constraints read only a few of many committed inputs.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_sharpe_by_input_hash(
+        &self,
+        input_hash: U256,
+        public_inputs: Vec<U256>,
+        commitments: Vec<U256>,
+        ood_values: Vec<U256>,
+        fri_final_poly: Vec<U256>,
+        query_values: Vec<U256>,
+        query_paths: Vec<U256>,
+        query_metadata: Vec<U256>,
+    ) -> bool {
+        // Step 1: Hash check (cheap, before the STARK)
+        if public_input_hash(&public_inputs) != input_hash {
+            return false;
+        }
+
+        // Step 2: Verify STARK proof
+        stark::verify_sharpe_stark(
+            &public_inputs,
+            &commitments,
+            &ood_values,
+            &fri_final_poly,
+            &query_values,
+            &query_paths,
+            &query_metadata,
+        )
+    }
+
+    /// Verify a STARK proof whose dataset is bound to a recent block.
+    ///
+    /// Same as `verify_sharpe_with_commitment`, but the proof must carry
+    /// `pi[4] = as_of_block`, and:
+    ///   1. `as_of_block` must lie within `max_age_blocks` of the current block
+    ///   2. The commitment column holds `keccak(aggregate, as_of_block)`, so
+    ///      `pi[3]` only matches for the block the prover actually bound
+    ///
+    /// `pi[4]` is also absorbed into the Fiat-Shamir seed, so rewriting it
+    /// after proving invalidates the STARK as well as the binding.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_sharpe_with_freshness(
+        &self,
+        public_inputs: Vec<U256>,
+        commitments: Vec<U256>,
+        ood_values: Vec<U256>,
+        fri_final_poly: Vec<U256>,
+        query_values: Vec<U256>,
+        query_paths: Vec<U256>,
+        query_metadata: Vec<U256>,
+        receipt_hashes: Vec<U256>,
+        max_age_blocks: U256,
+    ) -> bool {
+        // Step 1: Freshness window (cheap, before any hashing)
+        if !as_of_block_is_fresh(&public_inputs, self.vm().block_number(), max_age_blocks) {
+            return false;
+        }
+
+        // Step 2: Aggregate commitment bound to as_of_block
+        if receipt_hashes.is_empty() {
+            return false;
+        }
+        let fps: Vec<Fp> = receipt_hashes.iter().map(|h| Fp::from_u256(*h)).collect();
+        let aggregate = mpt::compute_commitment_from_hashes(&fps);
+        if aggregate == Fp::ZERO {
+            return false;
+        }
+        let expected_commitment = mpt::bind_as_of_block(aggregate, Fp::from_u256(public_inputs[4]));
+
+        // Step 3: Verify STARK proof
+        let stark_valid = stark::verify_sharpe_stark(
+            &public_inputs,
+            &commitments,
+            &ood_values,
+            &fri_final_poly,
+            &query_values,
+            &query_paths,
+            &query_metadata,
         );
 
-        pi3 == expected_merkle_root
+        if !stark_valid {
+            return false;
+        }
+
+        // Step 4: Cross-check — pi[3] == merkle_root of constant commitment column
+        commitment_binding_holds(&public_inputs, &query_metadata, expected_commitment)
     }
 
     /// Verify a STARK proof with receipt-based data provenance.
@@ -145,6 +443,12 @@ impl StarkVerifier {
     ///
     /// The receipt RLP is NOT passed separately — it is extracted directly from the
     /// MPT proof leaf, eliminating data redundancy and reducing calldata size.
+    ///
+    /// `receipts_root` must be exactly one word (the 32-byte root); `block_hash`
+    /// is a full 32-byte word, so every value is in range. The receipt key
+    /// must be a canonical RLP tx index, and the verified leaf must be a
+    /// well-formed receipt.
+    #[allow(clippy::too_many_arguments)]
     pub fn verify_sharpe_proof_with_receipt(
         &self,
         // STARK proof params (same as verify_sharpe_proof)
@@ -163,47 +467,53 @@ impl StarkVerifier {
         receipt_key: Vec<U256>,
         receipt_key_len: U256,
     ) -> bool {
-        // Step 1: Decode parameters
-        if receipts_root.is_empty() {
-            return false;
-        }
-        let receipts_root_bytes: [u8; 32] = receipts_root[0].to_be_bytes();
-
-        let nodes_len = receipt_proof_nodes_len.as_limbs()[0] as usize;
-        let key_len = receipt_key_len.as_limbs()[0] as usize;
-        let key_bytes = mpt::decode_u256_words(&receipt_key, key_len);
-
-        // Step 2: Decode and verify MPT proof — receipt ∈ receiptsRoot
-        let proof_nodes = match mpt::decode_proof_nodes(&receipt_proof_nodes, nodes_len) {
-            Some(nodes) => nodes,
-            None => return false,
-        };
-
-        let verified_value = mpt::verify_mpt_proof(
-            &receipts_root_bytes,
-            &key_bytes,
-            &proof_nodes,
-        );
-
-        // Extract receipt RLP directly from the MPT leaf — no separate parameter needed
-        let receipt_rlp_bytes = match verified_value {
-            None => return false,
-            Some(leaf) => leaf,
-        };
-
-        // Step 3: Compute expected dataset_commitment from MPT-verified receipt
-        let expected_commitment = mpt::compute_dataset_commitment_onchain(
+        verify_sharpe_with_receipt_proof(
+            &public_inputs,
+            &commitments,
+            &ood_values,
+            &fri_final_poly,
+            &query_values,
+            &query_paths,
+            &query_metadata,
             block_hash,
-            &receipts_root_bytes,
-            &receipt_rlp_bytes,
-        );
-
-        if expected_commitment == Fp::ZERO {
-            return false;
-        }
+            &receipts_root,
+            &receipt_proof_nodes,
+            receipt_proof_nodes_len,
+            &receipt_key,
+            receipt_key_len,
+            None,
+        )
+    }
 
-        // Step 4: Verify STARK proof
-        let stark_valid = stark::verify_sharpe_stark(
+    /// Verify a STARK proof with receipt provenance, binding an explicit receipt.
+    ///
+    /// Same as `verify_sharpe_proof_with_receipt`, except the receipt RLP is
+    /// passed in `receipt_rlp` (packed 32-byte words, `receipt_rlp_len` bytes)
+    /// rather than extracted from the MPT leaf. The proof must yield a leaf
+    /// whose keccak equals `keccak(receipt_rlp)`, and the dataset commitment is
+    /// computed over the explicit bytes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_sharpe_proof_with_receipt_explicit(
+        &self,
+        // STARK proof params (same as verify_sharpe_proof)
+        public_inputs: Vec<U256>,
+        commitments: Vec<U256>,
+        ood_values: Vec<U256>,
+        fri_final_poly: Vec<U256>,
+        query_values: Vec<U256>,
+        query_paths: Vec<U256>,
+        query_metadata: Vec<U256>,
+        // Receipt proof params
+        block_hash: U256,
+        receipts_root: Vec<U256>,
+        receipt_proof_nodes: Vec<U256>,
+        receipt_proof_nodes_len: U256,
+        receipt_key: Vec<U256>,
+        receipt_key_len: U256,
+        receipt_rlp: Vec<U256>,
+        receipt_rlp_len: U256,
+    ) -> bool {
+        verify_sharpe_with_receipt_proof(
             &public_inputs,
             &commitments,
             &ood_values,
@@ -211,45 +521,276 @@ impl StarkVerifier {
             &query_values,
             &query_paths,
             &query_metadata,
-        );
+            block_hash,
+            &receipts_root,
+            &receipt_proof_nodes,
+            receipt_proof_nodes_len,
+            &receipt_key,
+            receipt_key_len,
+            Some((&receipt_rlp, receipt_rlp_len)),
+        )
+    }
 
-        if !stark_valid {
+    /// Hash of the protocol parameters (field, domain generator, AIR shapes,
+    /// hash functions, calldata format) this verifier was built with.
+    ///
+    /// Proofs carry their prover's hash in `query_metadata[3]`; one built
+    /// with a different hash is always rejected.
+    pub fn protocol_hash(&self) -> U256 {
+        stark::VERIFIER_PROTOCOL_HASH
+    }
+
+    /// Compare a proof's protocol hash with this verifier's, so a client can
+    /// tell a version mismatch apart from an invalid proof.
+    ///
+    /// Returns 0 on a match, 1 if `query_metadata` is too short to carry a
+    /// hash, and 2 if the hash differs.
+    pub fn check_protocol_hash(&self, query_metadata: Vec<U256>) -> u8 {
+        stark::proof::check_protocol_hash(&query_metadata) as u8
+    }
+
+    /// Post-deploy health check: verify the proof embedded in the contract
+    /// (`stark::self_test`). `true` means the deployed verifier accepts a
+    /// known-good proof; callable with a plain `eth_call`.
+    pub fn self_test(&self) -> bool {
+        stark::self_test::verify_embedded_proof()
+    }
+
+    /// Analytical gas estimate for verifying a proof of this shape (see
+    /// `stark::gas`), so a frontend can preflight without `estimateGas`.
+    ///
+    /// The arguments are the shape fields of `query_metadata`; out-of-range
+    /// values saturate to a huge estimate.
+    pub fn estimate_verify_gas(
+        &self,
+        log_trace_len: U256,
+        num_queries: U256,
+        num_fri_layers: U256,
+        num_columns: U256,
+    ) -> U256 {
+        U256::from(stark::gas::estimate_verify_gas(
+            log_trace_len.saturating_to(),
+            num_queries.saturating_to(),
+            num_fri_layers.saturating_to(),
+            num_columns.saturating_to(),
+        ))
+    }
+
+    /// Verify a Groth16 proof that wraps a Sharpe STARK proof.
+    ///
+    /// The SNARK attests that the STARK verifier accepts `public_inputs`
+    /// (`[trade_count, total_return, sharpe_sq_scaled, merkle_root]`), so the
+    /// on-chain work is one pairing check via the BN254 precompiles instead of
+    /// FRI and Merkle verification. The key is the deployer's (see
+    /// `constructor`); without one every proof is rejected.
+    ///
+    /// groth16_proof layout: [A.x, A.y, B.x_im, B.x_re, B.y_im, B.y_re, C.x, C.y]
+    pub fn verify_sharpe_wrapped(&self, groth16_proof: Vec<U256>, public_inputs: Vec<U256>) -> bool {
+        if public_inputs.len() != stark::NUM_PUBLIC_INPUTS || !stark::sharpe_sq_in_range(public_inputs[2]) {
             return false;
         }
 
-        // Step 5: Cross-check — pi[3] == merkle_root of constant commitment column
-        if public_inputs.len() < 4 || query_metadata.is_empty() {
+        let vk_words: Vec<U256> = (0..self.wrapped_sharpe_vk.len())
+            .filter_map(|i| self.wrapped_sharpe_vk.get(i))
+            .collect();
+        let Some(vk) = groth16::VerifyingKey::from_words(&vk_words) else {
             return false;
-        }
+        };
+        groth16::verify(self, &vk, &groth16_proof, &public_inputs)
+    }
+}
 
-        let pi3 = Fp::from_u256(public_inputs[3]);
+impl groth16::Bn254Precompiles for StarkVerifier {
+    fn call_precompile(&self, address: u8, input: &[u8]) -> Option<Vec<u8>> {
+        self.vm()
+            .static_call(&Call::new(), Address::with_last_byte(address), input)
+            .ok()
+    }
+}
 
-        // Extract log_trace_len from query_metadata[2]
-        // query_metadata layout: [num_queries, num_fri_layers, log_trace_len, ...]
-        if query_metadata.len() < 3 {
-            return false;
-        }
-        let log_trace_len = query_metadata[2].as_limbs()[0] as u32;
+/// Decode the `receipts_root` parameter of the receipt entrypoints.
+///
+/// A root is exactly one 32-byte word. Anything else is rejected rather than
+/// reading the first word, so a root split across words is never mistaken
+/// for its prefix.
+fn decode_receipts_root(receipts_root: &[U256]) -> Option<[u8; 32]> {
+    match receipts_root {
+        [root] => Some(root.to_be_bytes()),
+        _ => None,
+    }
+}
 
-        // Compute expected merkle root: for a column where every leaf = expected_commitment,
-        // the merkle root is deterministic and can be computed in O(log n) hashes.
-        let expected_merkle_root = mpt::compute_constant_merkle_root(
-            expected_commitment,
-            log_trace_len,
-        );
+/// Decode the receipt trie key and proof nodes shared by the receipt entrypoints.
+///
+/// The key must exactly fill `receipt_key_len` bytes of `receipt_key` and
+/// canonically decode to a tx index (see `mpt::decode_receipt_key`). That the
+/// index exists in the block is shown by the MPT proof against `receipts_root`.
+fn decode_receipt_proof(
+    receipt_proof_nodes: &[U256],
+    receipt_proof_nodes_len: U256,
+    receipt_key: &[U256],
+    receipt_key_len: U256,
+) -> Option<(Vec<u8>, Vec<Vec<u8>>)> {
+    let nodes_len = usize::try_from(receipt_proof_nodes_len).ok()?;
+    let (key_bytes, _tx_index) = mpt::decode_receipt_key(receipt_key, receipt_key_len.saturating_to())?;
+    let proof_nodes = mpt::decode_proof_nodes(receipt_proof_nodes, nodes_len)?;
+    Some((key_bytes, proof_nodes))
+}
 
-        if pi3 != expected_merkle_root {
-            return false;
+/// Decode the explicit `receipt_rlp` parameter of
+/// `verify_sharpe_proof_with_receipt_explicit`.
+///
+/// `receipt_rlp_len` must fit in the packed words (a length with any high
+/// limb set is rejected, not truncated), and the bytes must be a well-formed
+/// receipt.
+fn decode_explicit_receipt(receipt_rlp: &[U256], receipt_rlp_len: U256) -> Option<Vec<u8>> {
+    let rlp_len = usize::try_from(receipt_rlp_len).ok()?;
+    if rlp_len > receipt_rlp.len() * 32 {
+        return None;
+    }
+    let receipt_rlp_bytes = mpt::decode_u256_words(receipt_rlp, rlp_len);
+    mpt::is_well_formed_receipt(&receipt_rlp_bytes).then_some(receipt_rlp_bytes)
+}
+
+/// Shared body of the receipt entrypoints.
+///
+/// With `explicit_receipt` unset the receipt RLP is the MPT-verified leaf;
+/// with it set to `(receipt_rlp, receipt_rlp_len)` the explicit bytes must
+/// be the proven leaf. Either way the receipt must be well-formed, and the
+/// dataset commitment over it is bound to the STARK proof's `pi[3]`.
+#[allow(clippy::too_many_arguments)]
+fn verify_sharpe_with_receipt_proof(
+    public_inputs: &[U256],
+    commitments: &[U256],
+    ood_values: &[U256],
+    fri_final_poly: &[U256],
+    query_values: &[U256],
+    query_paths: &[U256],
+    query_metadata: &[U256],
+    block_hash: U256,
+    receipts_root: &[U256],
+    receipt_proof_nodes: &[U256],
+    receipt_proof_nodes_len: U256,
+    receipt_key: &[U256],
+    receipt_key_len: U256,
+    explicit_receipt: Option<(&[U256], U256)>,
+) -> bool {
+    // Step 1: Decode parameters
+    let Some(receipts_root_bytes) = decode_receipts_root(receipts_root) else {
+        return false;
+    };
+
+    let (key_bytes, proof_nodes) = match decode_receipt_proof(
+        receipt_proof_nodes,
+        receipt_proof_nodes_len,
+        receipt_key,
+        receipt_key_len,
+    ) {
+        Some(decoded) => decoded,
+        None => return false,
+    };
+
+    // Step 2: Verify MPT proof — receipt ∈ receiptsRoot
+    let receipt_rlp_bytes = match explicit_receipt {
+        // Extract receipt RLP directly from the MPT leaf
+        None => match mpt::verify_mpt_proof(&receipts_root_bytes, &key_bytes, &proof_nodes) {
+            Some(leaf) if mpt::is_well_formed_receipt(&leaf) => leaf,
+            _ => return false,
+        },
+        // The proven leaf must be the explicit receipt
+        Some((receipt_rlp, receipt_rlp_len)) => {
+            let Some(receipt_rlp_bytes) = decode_explicit_receipt(receipt_rlp, receipt_rlp_len) else {
+                return false;
+            };
+            if !mpt::verify_mpt_proof_for_receipt(
+                &receipts_root_bytes,
+                &key_bytes,
+                &proof_nodes,
+                &receipt_rlp_bytes,
+            ) {
+                return false;
+            }
+            receipt_rlp_bytes
         }
+    };
+
+    // Step 3: Compute expected dataset_commitment from the verified receipt
+    let expected_commitment = mpt::compute_dataset_commitment_onchain(
+        block_hash,
+        &receipts_root_bytes,
+        &receipt_rlp_bytes,
+    );
+
+    if expected_commitment == Fp::ZERO {
+        return false;
+    }
+
+    // Step 4: Verify STARK proof
+    let stark_valid = stark::verify_sharpe_stark(
+        public_inputs,
+        commitments,
+        ood_values,
+        fri_final_poly,
+        query_values,
+        query_paths,
+        query_metadata,
+    );
+
+    if !stark_valid {
+        return false;
+    }
+
+    // Step 5: Cross-check — pi[3] == merkle_root of constant commitment column
+    commitment_binding_holds(public_inputs, query_metadata, expected_commitment)
+}
+
+/// Check that `pi[4]` (as_of_block) is present, not in the future, and at
+/// most `max_age_blocks` behind `current_block`.
+fn as_of_block_is_fresh(public_inputs: &[U256], current_block: u64, max_age_blocks: U256) -> bool {
+    if public_inputs.len() != stark::NUM_PUBLIC_INPUTS + 1 {
+        return false;
+    }
+
+    let as_of_block = public_inputs[4];
+    let current = U256::from(current_block);
+    as_of_block <= current && current - as_of_block <= max_age_blocks
+}
+
+/// keccak256 of the public inputs as packed 32-byte big-endian words, i.e.
+/// Solidity's `keccak256(abi.encodePacked(publicInputs))`. Not reduced mod p.
+pub fn public_input_hash(public_inputs: &[U256]) -> U256 {
+    U256::from_be_bytes(keccak256(&stark_core::keccak::packed_words(public_inputs)))
+}
 
-        true
+/// Check that `pi[3]` (merkle_root) is present and listed in `allowed_roots`.
+fn root_is_allowed(public_inputs: &[U256], allowed_roots: &[U256]) -> bool {
+    stark::public_input_count_ok(public_inputs.len()) && allowed_roots.contains(&public_inputs[3])
+}
+
+/// Cross-check that `pi[3]` equals the Merkle root of a trace column whose
+/// every leaf is `commitment`, paired under the proof's `merkle_scheme`
+/// (see `QueryMetadata`).
+fn commitment_binding_holds(
+    public_inputs: &[U256],
+    query_metadata: &[U256],
+    commitment: Fp,
+) -> bool {
+    if !stark::public_input_count_ok(public_inputs.len()) {
+        return false;
     }
+    let Some(metadata) = QueryMetadata::from_words(query_metadata) else {
+        return false;
+    };
+
+    let pi3 = Fp::from_u256(public_inputs[3]);
+    pi3 == mpt::compute_constant_merkle_root(commitment, metadata.log_trace_len, metadata.merkle_scheme)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::field::BN254_PRIME;
+    use crate::merkle::MerkleScheme;
 
     // =====================================================================
     // Cross-validation test vectors for keccak_hash_two
@@ -258,6 +799,50 @@ mod tests {
     // Encoding: big-endian 32-byte per Fp, concat, keccak256, mod BN254.
     // =====================================================================
 
+    /// keccak256 digests as computed on-chain (EVM `keccak256`, Stylus
+    /// precompile): both the software backend the tests hash with and the
+    /// SDK's `crypto::keccak` must reproduce them.
+    #[test]
+    fn test_keccak_backends_match_onchain_vectors() {
+        let mut one_two = [0u8; 64];
+        one_two[31] = 1;
+        one_two[63] = 2;
+        let vectors = [
+            // keccak256(abi.encode(uint256(0), uint256(0)))
+            ([0u8; 64], "ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5"),
+            // keccak256(abi.encode(uint256(1), uint256(2)))
+            (one_two, "e90b7bceb6e7df5418fb78d8ee546e97c83a08bbccc01a0644d599ccd2a7c2e0"),
+        ];
+        for (preimage, digest) in vectors {
+            let digest = U256::from_str_radix(digest, 16).unwrap().to_be_bytes::<32>();
+            assert_eq!(keccak256(&preimage), digest);
+            assert_eq!(stylus_sdk::crypto::keccak(preimage).0, digest);
+        }
+
+        // And keccak_hash_two is that digest reduced into the field
+        let expected = Fp::from_u256(U256::from_be_bytes(keccak256(&one_two)));
+        assert_eq!(keccak_hash_two(Fp::ONE, Fp::from_u256(U256::from(2u64))), expected);
+    }
+
+    /// keccak_hash_two_tagged(tag, 1, 2) for each tag. Must match the
+    /// prover's `test_keccak_tagged_cross_validate`.
+    #[test]
+    fn test_keccak_tagged_cross_validate() {
+        use stark_core::keccak::{TAG_CHANNEL_COMMIT, TAG_CHANNEL_DRAW, TAG_MERKLE_NODE};
+        let vectors = [
+            (TAG_MERKLE_NODE, "24b0b80b6d5d449b8bb22be5c97de764f49121eacb0f202f5e8a6d54b706e674"),
+            (TAG_CHANNEL_COMMIT, "1f45aac4afbb2ef5b88e133eac3a50d4530ca4334591c40b860475ba688e045b"),
+            (TAG_CHANNEL_DRAW, "9e67384c1b91f16f8b39611930c4a54475b247e1da760f49537f8e0c5e34cd"),
+        ];
+        let (a, b) = (Fp::ONE, Fp::from_u256(U256::from(2u64)));
+        for (tag, expected) in vectors {
+            let h = keccak_hash_two_tagged(tag, a, b);
+            assert_eq!(h.to_u256(), U256::from_str_radix(expected, 16).unwrap(), "tag {}", tag);
+            assert_eq!(h.to_u256(), stark_core::keccak::keccak_hash_two_tagged(tag, U256::from(1u64), U256::from(2u64)));
+            assert_ne!(h, keccak_hash_two(a, b));
+        }
+    }
+
     /// Test vector 1: keccak_hash_two(0, 0)
     #[test]
     fn test_keccak_vector_zero_zero() {
@@ -360,18 +945,19 @@ mod tests {
         assert_ne!(commitment, Fp::ZERO);
 
         let log_trace_len: u32 = 4;
-        let expected_pi3 = mpt::compute_constant_merkle_root(commitment, log_trace_len);
+        let expected_pi3 = mpt::compute_constant_merkle_root(commitment, log_trace_len, MerkleScheme::Ordered);
 
         // The same inputs should always produce the same expected pi[3]
         let expected_pi3_again = mpt::compute_constant_merkle_root(
             mpt::compute_commitment_from_hashes(&[h0, h1, h2]),
             log_trace_len,
+            MerkleScheme::Ordered,
         );
         assert_eq!(expected_pi3, expected_pi3_again);
 
         // Different hashes → different expected pi[3]
         let other_commitment = mpt::compute_commitment_from_hashes(&[h2, h1, h0]);
-        let other_pi3 = mpt::compute_constant_merkle_root(other_commitment, log_trace_len);
+        let other_pi3 = mpt::compute_constant_merkle_root(other_commitment, log_trace_len, MerkleScheme::Ordered);
         assert_ne!(expected_pi3, other_pi3);
     }
 
@@ -386,7 +972,7 @@ mod tests {
         let log_trace_len: u32 = 4;
 
         let commitment = mpt::compute_commitment_from_hashes(&[receipt_hash]);
-        let expected_root = mpt::compute_constant_merkle_root(commitment, log_trace_len);
+        let expected_root = mpt::compute_constant_merkle_root(commitment, log_trace_len, MerkleScheme::Ordered);
 
         // Correct pi[3] should match
         assert_eq!(expected_root, expected_root);
@@ -403,6 +989,99 @@ mod tests {
         assert_eq!(commitment, Fp::ZERO, "Empty hashes must produce ZERO commitment");
     }
 
+    // =====================================================================
+    // verify_sharpe_with_precommitted — pi[3] cross-check tests
+    // =====================================================================
+
+    /// Well-formed four-query Sharpe metadata for the pi[3] cross-check.
+    fn binding_metadata(log_trace_len: u32, merkle_scheme: MerkleScheme) -> Vec<U256> {
+        QueryMetadata {
+            transcript: stark::channel::TranscriptKind::Keccak,
+            num_segments: 1,
+            merkle_scheme,
+            num_fri_layers: 4,
+            num_columns: 7,
+            log_trace_len,
+            blowup: stark::BLOWUP_FACTOR,
+            grinding_bits: 0,
+            composition_lde_len: 1 << (log_trace_len + 2),
+            protocol_hash: stark::VERIFIER_PROTOCOL_HASH,
+            indices: vec![0; 4],
+            leaf_salt: None,
+        }
+        .to_words()
+    }
+
+    /// A correct pre-aggregated commitment passes the pi[3] cross-check.
+    #[test]
+    fn test_precommitted_correct_aggregate_passes() {
+        let hashes = [
+            Fp::from_u256(U256::from(111u64)),
+            Fp::from_u256(U256::from(222u64)),
+        ];
+        let aggregate = mpt::compute_commitment_from_hashes(&hashes);
+        let log_trace_len: u32 = 4;
+        let pi3 = mpt::compute_constant_merkle_root(aggregate, log_trace_len, MerkleScheme::Ordered);
+
+        let public_inputs = vec![
+            U256::from(15u64),
+            U256::from(3000u64),
+            U256::from(60000u64),
+            pi3.to_u256(),
+        ];
+        let query_metadata = binding_metadata(log_trace_len, MerkleScheme::Ordered);
+
+        assert!(commitment_binding_holds(&public_inputs, &query_metadata, aggregate));
+    }
+
+    /// A wrong pre-aggregated commitment fails the pi[3] cross-check.
+    #[test]
+    fn test_precommitted_wrong_aggregate_fails() {
+        let aggregate = mpt::compute_commitment_from_hashes(&[Fp::from_u256(U256::from(42u64))]);
+        let log_trace_len: u32 = 4;
+        let pi3 = mpt::compute_constant_merkle_root(aggregate, log_trace_len, MerkleScheme::Ordered);
+
+        let public_inputs = vec![
+            U256::from(15u64),
+            U256::from(3000u64),
+            U256::from(60000u64),
+            pi3.to_u256(),
+        ];
+        let query_metadata = binding_metadata(log_trace_len, MerkleScheme::Ordered);
+
+        let wrong = Fp::from_u256(U256::from(43u64));
+        assert!(!commitment_binding_holds(&public_inputs, &query_metadata, wrong));
+
+        // Truncated metadata (no log_trace_len) is rejected
+        assert!(!commitment_binding_holds(&public_inputs, &query_metadata[..2], aggregate));
+        // So is metadata whose length disagrees with its query count
+        assert!(!commitment_binding_holds(&public_inputs, &query_metadata[..5], aggregate));
+    }
+
+    /// The scheme tag in query_metadata[0] selects how pi[3] is recomputed.
+    #[test]
+    fn test_commitment_binding_follows_merkle_scheme() {
+        let aggregate = mpt::compute_commitment_from_hashes(&[Fp::from_u256(U256::from(42u64))]);
+        let log_trace_len: u32 = 4;
+        let ordered_pi3 = mpt::compute_constant_merkle_root(aggregate, log_trace_len, MerkleScheme::Ordered);
+        let sorted_pi3 = mpt::compute_constant_merkle_root(aggregate, log_trace_len, MerkleScheme::Sorted);
+        assert_ne!(ordered_pi3, sorted_pi3);
+
+        let inputs_with = |pi3: Fp| vec![U256::from(15u64), U256::from(3000u64), U256::from(60000u64), pi3.to_u256()];
+        let metadata_with = |tag: u64| {
+            let mut metadata = binding_metadata(log_trace_len, MerkleScheme::Ordered);
+            metadata[0] |= U256::from(tag) << 192;
+            metadata
+        };
+
+        assert!(commitment_binding_holds(&inputs_with(ordered_pi3), &metadata_with(0), aggregate));
+        assert!(commitment_binding_holds(&inputs_with(sorted_pi3), &metadata_with(1), aggregate));
+        assert!(!commitment_binding_holds(&inputs_with(sorted_pi3), &metadata_with(0), aggregate));
+        assert!(!commitment_binding_holds(&inputs_with(ordered_pi3), &metadata_with(1), aggregate));
+        // Unknown scheme tag is rejected
+        assert!(!commitment_binding_holds(&inputs_with(ordered_pi3), &metadata_with(2), aggregate));
+    }
+
     /// Field range: 100 consecutive hashes all produce values < BN254_PRIME.
     #[test]
     fn test_keccak_output_in_field() {
@@ -414,4 +1093,299 @@ mod tests {
             a = h;
         }
     }
+
+    // =====================================================================
+    // verify_sharpe_with_freshness — as_of_block window and binding tests
+    // =====================================================================
+
+    fn u(hex: &str) -> U256 {
+        U256::from_str_radix(hex, 16).unwrap()
+    }
+
+    /// Proof with `pi[4] = as_of_block = 1000` and receipt hash 0x2a.
+    /// Generated with `ProveOptions { as_of_block: Some(1000), .. }`,
+    /// dataset_commitment = 0x2a, trades [100, 200, 300, 200] bps, 2 queries.
+    fn fresh_proof() -> [Vec<U256>; 7] {
+        [
+            vec![
+                U256::from(0x4u64),
+                U256::from(0x320u64),
+                U256::from(0x13880u64),
+                u("2fecb74fa69a1be8c981bb2e2b584c0d21af08a28be83b68b37266bdd7d764ad"),
+                U256::from(0x3e8u64),
+            ],
+            vec![
+                u("0cb0f92c97e59c6ae220a66641d380ab96d8184ecc7efd5e078fb70eba61e773"),
+                u("047567c07384829976d4c8ab4e5b0fbff7b04820788149a8effbdee61040ac73"),
+                u("117dd44555e094b601861498d99e2f25cbf2bb6ed6e998df3eed81ed397f5cfb"),
+                u("0b3ea2094fda5b31ff506b7992cf25e529eacab49bd2cd81d3489dfacd3dc528"),
+                u("1a9cfe73838ccb69180f0ce176064cc955b544fcdd3cb78e8ec9b38315c71610"),
+            ],
+            vec![
+                u("089011d8cb23a35bbd9d8676718599c45ae3677557e45a6ffd2582b89331a3e5"),
+                u("27ab9c09c3bc1b0400ed669869b2e62fa116db6a7c5058daf7e4a7fa6a24c1c0"),
+                u("09279eac927edde0ef665ea9acaca231a8c17171aaa9a4b7e6d7edfe720ee13a"),
+                u("01bf7c716da7bf5378a261c49e73818e65a56fc6761dd72b63f594d3c635db7e"),
+                U256::from(0x4u64),
+                u("0429e091287e7c38bf6ec1fdd7391ea84884b50e4694fe0ee98620a08e0e01f9"),
+                U256::from(0x1u64),
+                u("0d03c8abc0be9d858ae4e464d4c02076b67e0db697d1a29ddf6fd6e8838ccd28"),
+                u("19ff95a31a798824db595cd5d8d1fdb2bb4c9f1fe6d4385dc23c98702f6ad06f"),
+                u("21a8ba8f653352e9c4ad41a68aef5ea41daf93d90ccc1f780ae39f9c6de951db"),
+                u("3020f7b2b1ed89398b7e137203494c3a2eb52746fa9bb79465658f2201dd5911"),
+                U256::from(0x4u64),
+                u("0429e091287e7c38bf6ec1fdd7391ea84884b50e4694fe0ee98620a08e0e01f9"),
+                U256::from(0x1u64),
+                u("3035249251271bcec736c69659404ec23647880e1740f62f3c33aa381312740f"),
+                u("20515d810d00b70c541be82e96722e443c91b4d2fa63f4456d6897e5a2003e01"),
+            ],
+            vec![
+                u("1fd96f5c2e29946f6614aefa376953ed8a9e6408a5e965a05695aa4519019a2c"),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("185a56c62e0340034eb6ea0d030c13e85c694c266cde3484ab12d76c636bb1b6"),
+                u("19f4588c5dd715e0000d13bab83e5eee5bc3151ffafa47f5715a7e505fb2f42c"),
+                u("17b0b185cb5f1a39e6f924086f336c04ce9744e69e13d271094173007dd97478"),
+                u("16637e558521df3f0189cca777778a39b163cfff1c99bee3de69d5b3268341b1"),
+                u("1b6316883e936ee39740c33ed1e030260d8149f4118a366a10f326ea4ae90153"),
+                u("29e84cb01fd40245e4e7b11f979ae77d58b20f0698ec9759718e5f8ef0218ec6"),
+                u("08fce43f440cd7c821833c78858b123c9180cc9c828642cd1a5a9094c1cf6993"),
+                u("25174b9c0c7421b0c6ffb437611fe401ee7a484938274e87cd50b81ee28d4c96"),
+                u("0d2a435143040c417d603503505cabe58b61e3e6ededeccbf8341250f80c4d0f"),
+                u("153792f4ed029c0c0fa8fd57c9ec9621642b14fe0e1136fd899c77442f6979f4"),
+                u("1c4dbae98fb895e3b4aa2280337980d0adcef73dc92ff840e8f1d2fed90a1e8c"),
+                u("0a386bcf724424f962c2e442b7efe6913a6fe7fa504c9c4caadc32228682373f"),
+                U256::from(0x4u64),
+                u("0429e091287e7c38bf6ec1fdd7391ea84884b50e4694fe0ee98620a08e0e01f9"),
+                U256::from(0x1u64),
+                u("245aac2fbeba2822c8f7cda8d35ae31cb01e623abd263d5c3119074cec4305b2"),
+                u("22cd2ac4c67ea2a64a10677a0362c3ce3c4c179a8f33439c2d89de2e3e000524"),
+                U256::from(0xc8u64),
+                U256::from(0x9c40u64),
+                U256::from(0x320u64),
+                U256::from(0x2bf20u64),
+                U256::from(0x4u64),
+                u("0429e091287e7c38bf6ec1fdd7391ea84884b50e4694fe0ee98620a08e0e01f9"),
+                U256::from(0x1u64),
+                u("05a1021bffdb50b560fccc55d70ca482ab1f84165803043238f82842dc94033f"),
+                u("01312ce8e3e6c4cb4aacde393a5170a4c2fd7d3389851d948a63611bf155c972"),
+            ],
+            vec![
+                u("0a8ca27c4dd5ad21d0e877fefa8c26344bf7fbde4a356ced1ab2a698b12d297d"),
+                u("200abbeb53e52ac194068725618bb2932d6e023b7e108a3804bfb5dd260e3665"),
+                u("1cf2a6c2fbf9f2312ea045586a7198a9330dbe295130d6c6f7659541564eda44"),
+                u("2973aa29fedd897d623c6e0d91d54d957809a48cefc8daf36e8b1b6455dbe317"),
+                u("19edff821cd99e3830dc4f0d15fffea7eb130c4c7f4a8f4869556853f82e5c23"),
+                u("2e10a129e4b37da6d616f190efa0d34d3adc809ce8ff8c3429b32859115659be"),
+                u("06a81d352f2c197a13524c6c16abf94c0f1bb8c4e462c7a75aa398c3c7088947"),
+                u("28957372c1364b23cd01cf57021df13fddc5ef88a6451e6323c784da4bf294c1"),
+                u("00b8d90db9417a2b6c41f3abee4449991916138177772480e26cf979091e5099"),
+                u("0d98733e78ad799997403ee970de18b698e977eef88c244151592e3015ac5ddc"),
+                u("2e6ec3d1f4d80b2396b92963e2e86f802420c981b1c50731218e1b222b64b58b"),
+                u("10c898a7371fb0923e391b93b7c013aafc1940e35e6aca5d7088d7a38d2b29c6"),
+                u("24ac096430577cb038ae81f88fcef0bd5408bb5cf9238493563133f3317e6b37"),
+                u("06a81d352f2c197a13524c6c16abf94c0f1bb8c4e462c7a75aa398c3c7088947"),
+                u("13836098a0f465032db1e7b9b6344e6007340a3a3c7ebb60ff018f07cd2f65fe"),
+                u("09cdeca7b031c5e182612d21bcce3dea57ce986243b9e01315a41de7ad4a96ef"),
+                u("02ed89f1e8f017aae1b251b1a532a65850a924974791769e3961c659663d5681"),
+                u("27ba9a2ba98de5d83ded0914990a8f4c68e34df7fb85b8ae969e8715f368ec6f"),
+                u("2ec8d00115f8be687914981abf3d3af0fd790732e1dd7878866b85a01161f651"),
+                u("2dbbb66e4bfbce98561470cc077c0daa8b2a87d51811240ae41e718b4095e5d5"),
+                u("221576f95cff651e13c5d6be3d99c8f6c298759adaa5c8a67944e00c2694a7aa"),
+                u("132419095aa120a6e57c4d5eeafd3d2c7915f76dd6ba1efcb965127933e06439"),
+                u("09b8345c4fef5d0625f1555fce0a48689e916567d3cf6442815f746183197aa7"),
+                u("1a127c8bcdd42915f5f374d9cb929c6ab157c43ed253fd2f22684d0fedf73aaa"),
+                u("09427325f160e5f9ccc0c0e473a47c3b5d3e8511923ce44806498c2812e68c2e"),
+                u("2889e450b42a2d4c94b48d1e9331441bcf87eb02f1b06018e7a3e7a9c7c92f74"),
+                u("2d0ef3a0a193dd6a11a13c90a6354d64dd024dd61bd39cf5caeddd8d9a4f3775"),
+                u("0fda9bb058da4c608f4358f0b75801ac5cf89e9dab53b7ccbb61688751b9e4ba"),
+                u("232df661bc01114810c7dd2c0fc3a7cc987a9a1ba266f2a01b85e191d0ad29fc"),
+                u("03f963eacc5e4e2ea0cad0583c22ae0b124deb70b3f3520d5fe8f3d28f0f3658"),
+                u("1c9855146869400a83c10989b98f76ab7b4aa40551378a52747231a138f3c45a"),
+                u("11ba657771b88d70b30855fbcc8009600b15866b83392aa713afac3ae4d92597"),
+                u("1c7de66d4fb73bdf96733b4f0ef223deae4af0ede7ac4319d3d45a384ee29b70"),
+                u("27f22515f7b7eb46fdaca3a7cbd143391c25c732b67df09701a7f9151ea15dd0"),
+                u("0803b2bc3297e26622ad9737861f9b3bbafcbf45ad0fa7b7e0acfaa55d42f377"),
+                u("2368a0d338fa6e81ca676b23a6f30836a418a85445e053966dc736dd7e8798f6"),
+                u("2695c019ad0e568043026665d3c6285121ab6c099998694d2bf006a615861bbd"),
+                u("0f3ddec9cfaa2767a1518429738e641629a727027cce4cbd7391a86f7ad63449"),
+            ],
+            vec![
+                u("0000000000000000000000000000000200000000000000000000000000000002"),
+                u("0000000000000000000000000000000000000000000000070000000000000002"),
+                U256::from(0x2u64),
+                u("46526ea6a38c6fadf6787155d7229c6be7556243855adcdffefa75555e8d5969"),
+                U256::from(0x7u64),
+                U256::from(0xcu64),
+            ],
+        ]
+    }
+
+    /// Replays steps 2-4 of `verify_sharpe_with_freshness` (everything but the VM block read).
+    fn fresh_proof_binds(p: &[Vec<U256>; 7], receipt_hashes: &[U256]) -> bool {
+        let fps: Vec<Fp> = receipt_hashes.iter().map(|h| Fp::from_u256(*h)).collect();
+        let aggregate = mpt::compute_commitment_from_hashes(&fps);
+        let commitment = mpt::bind_as_of_block(aggregate, Fp::from_u256(p[0][4]));
+
+        stark::verify_sharpe_stark(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6])
+            && commitment_binding_holds(&p[0], &p[6], commitment)
+    }
+
+    #[test]
+    fn test_freshness_in_window_passes() {
+        let proof = fresh_proof();
+        assert!(as_of_block_is_fresh(&proof[0], 1_000, U256::ZERO));
+        assert!(as_of_block_is_fresh(&proof[0], 1_100, U256::from(100u64)));
+        assert!(fresh_proof_binds(&proof, &[U256::from(0x2au64)]));
+    }
+
+    #[test]
+    fn test_freshness_expired_rejected() {
+        let proof = fresh_proof();
+        assert!(!as_of_block_is_fresh(&proof[0], 1_101, U256::from(100u64)));
+
+        // Future as_of_block is rejected too
+        assert!(!as_of_block_is_fresh(&proof[0], 999, U256::from(100u64)));
+
+        // Proofs without pi[4] cannot satisfy a freshness check
+        assert!(!as_of_block_is_fresh(&proof[0][..4], 1_000, U256::from(100u64)));
+    }
+
+    #[test]
+    fn test_freshness_tampered_as_of_block_breaks_binding() {
+        let mut proof = fresh_proof();
+
+        // Bump as_of_block into a fresher window after proving
+        proof[0][4] = U256::from(1_050u64);
+        assert!(as_of_block_is_fresh(&proof[0], 1_100, U256::from(50u64)));
+        assert!(!fresh_proof_binds(&proof, &[U256::from(0x2au64)]));
+
+        // The pi[3] cross-check alone also fails for the rewritten block
+        let aggregate = Fp::from_u256(U256::from(0x2au64));
+        let rebound = mpt::bind_as_of_block(aggregate, Fp::from_u256(proof[0][4]));
+        assert!(!commitment_binding_holds(&proof[0], &proof[6], rebound));
+    }
+
+    // =====================================================================
+    // verify_sharpe_against_allowlist — pi[3] allowlist tests
+    // =====================================================================
+
+    /// Replays `verify_sharpe_against_allowlist`.
+    fn allowlisted_proof_verifies(p: &[Vec<U256>; 7], allowed_roots: &[U256]) -> bool {
+        root_is_allowed(&p[0], allowed_roots)
+            && stark::verify_sharpe_stark(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6])
+    }
+
+    fn verifies_by_input_hash(input_hash: U256, p: &[Vec<U256>; 7]) -> bool {
+        public_input_hash(&p[0]) == input_hash
+            && stark::verify_sharpe_stark(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6])
+    }
+
+    #[test]
+    fn test_verify_by_input_hash() {
+        let bot = stark_prover::mock_data::bot_a_aggressive_eth();
+        let proof = stark_prover::prove_sharpe(&bot.trades, U256::from(bot.expected_sharpe_sq_scaled), 4, None);
+        let input_hash = proof.public_input_hash();
+        let p = [
+            proof.public_inputs,
+            proof.commitments,
+            proof.ood_values,
+            proof.fri_final_poly,
+            proof.query_values,
+            proof.query_paths,
+            proof.query_metadata,
+        ];
+
+        // Prover and verifier agree on the hash, and the verdict matches
+        assert_eq!(public_input_hash(&p[0]), input_hash);
+        assert!(verifies_by_input_hash(input_hash, &p));
+
+        assert!(!verifies_by_input_hash(input_hash ^ U256::from(1u64), &p));
+        // Five inputs (with as_of_block) hash differently from their first four
+        let fresh = fresh_proof();
+        assert!(verifies_by_input_hash(public_input_hash(&fresh[0]), &fresh));
+        assert_ne!(public_input_hash(&fresh[0]), public_input_hash(&fresh[0][..4]));
+    }
+
+    #[test]
+    fn test_allowlist_listed_root_passes() {
+        let proof = fresh_proof();
+        let allowed = [U256::from(0x1234u64), proof[0][3]];
+        assert!(allowlisted_proof_verifies(&proof, &allowed));
+    }
+
+    #[test]
+    fn test_allowlist_unlisted_root_rejected() {
+        let proof = fresh_proof();
+        assert!(stark::verify_sharpe_stark(
+            &proof[0], &proof[1], &proof[2], &proof[3], &proof[4], &proof[5], &proof[6]
+        ));
+
+        // Valid proof, but its dataset is not registered
+        let allowed = [U256::from(0x1234u64), proof[0][3] + U256::from(1u64)];
+        assert!(!allowlisted_proof_verifies(&proof, &allowed));
+    }
+
+    #[test]
+    fn test_allowlist_empty_rejects_everything() {
+        let proof = fresh_proof();
+        assert!(!allowlisted_proof_verifies(&proof, &[]));
+        assert!(!root_is_allowed(&proof[0], &[]));
+        assert!(!root_is_allowed(&proof[0][..3], &[proof[0][3]]));
+    }
+
+    #[test]
+    fn test_decode_receipts_root_single_word() {
+        let root = U256::from_be_bytes([0xab; 32]);
+        assert_eq!(decode_receipts_root(&[root]), Some([0xab; 32]));
+
+        // Extra words are rejected, not ignored
+        assert!(decode_receipts_root(&[root, U256::from(1u64)]).is_none());
+        assert!(decode_receipts_root(&[]).is_none());
+    }
+
+    #[test]
+    fn test_decode_receipt_proof_key_validation() {
+        // One 3-byte proof node: [num_nodes=1, len_0=3, packed data]
+        let mut node_word = [0u8; 32];
+        node_word[..3].copy_from_slice(&[0xc2, 0x01, 0x02]);
+        let nodes = [U256::from(1u64), U256::from(3u64), U256::from_be_bytes(node_word)];
+        let decode = |key: &[u8], key_len: u64| {
+            let mut key_word = [0u8; 32];
+            key_word[..key.len()].copy_from_slice(key);
+            let key_words = [U256::from_be_bytes(key_word)];
+            decode_receipt_proof(&nodes, U256::from(3u64), &key_words, U256::from(key_len))
+        };
+
+        // RLP(0x80) addresses tx 128
+        let (key, proof_nodes) = decode(&[0x81, 0x80], 2).unwrap();
+        assert_eq!(key, vec![0x81, 0x80]);
+        assert_eq!(proof_nodes, vec![vec![0xc2, 0x01, 0x02]]);
+
+        // Zero-padded over-length key: nibble path [8,1,8,0,0,0] would be walked
+        assert!(decode(&[0x81, 0x80], 3).is_none());
+        assert!(decode(&[0x81, 0x80], U256::MAX.saturating_to()).is_none());
+        // Not an RLP integer
+        assert!(decode(&[0xc1, 0x80], 2).is_none());
+    }
+
+    #[test]
+    fn test_decode_explicit_receipt_length() {
+        let receipt = stark_prover::receipt_proof::rlp_encode_receipt(0, 1, 21_000, &[0u8; 256], &[]);
+        let mut padded = receipt.clone();
+        padded.resize(receipt.len().div_ceil(32) * 32, 0);
+        let words: Vec<U256> = padded.chunks(32).map(U256::from_be_slice).collect();
+        let len = U256::from(receipt.len());
+
+        assert_eq!(decode_explicit_receipt(&words, len), Some(receipt.clone()));
+        // Longer than the packed words
+        assert!(decode_explicit_receipt(&words, U256::from(words.len() * 32 + 1)).is_none());
+        // A high limb is rejected, not truncated to the valid low limb
+        assert!(decode_explicit_receipt(&words, len | (U256::from(1u64) << 64)).is_none());
+        // Truncated bytes are not a well-formed receipt
+        assert!(decode_explicit_receipt(&words, len - U256::from(1u64)).is_none());
+    }
 }
@@ -3,8 +3,98 @@
 //! Implements Merkle tree path verification using Keccak256 hash.
 //! Supports verification of membership proofs for trees of any depth.
 
-use crate::field::Fp;
-use crate::keccak_hash_two;
+use alloy_primitives::U256;
+
+use crate::field::{Fp, BN254_PRIME};
+use crate::{keccak_hash_two, keccak_hash_two_tagged};
+use stark_core::keccak::{fold_row, TAG_MERKLE_NODE};
+
+/// Leaf encoding for committed evaluations; must match the prover's
+/// `commit::hash_leaf`.
+///
+/// Unsalted leaves are the value itself; salted leaves are `keccak(salt, value)`.
+/// Multi-column (trace) leaves are the column chain-hash passed through here.
+#[inline]
+pub fn hash_leaf(value: Fp, leaf_salt: Option<Fp>) -> Fp {
+    match leaf_salt {
+        Some(salt) => keccak_hash_two(salt, value),
+        None => value,
+    }
+}
+
+/// Combine a trace row into its leaf value, keccak(...keccak(c0, c1)..., cN):
+/// `stark_core::keccak::fold_row`, as the prover's `commit::combine_row_leaf`.
+pub fn combine_row_leaf(row: &[Fp]) -> Fp {
+    fold_row(row.iter().copied(), keccak_hash_two)
+}
+
+/// Leaf encoding for a multi-column trace row; must match the prover's
+/// `commit_trace_multi`: `hash_leaf(combine_row_leaf(columns), salt)`.
+pub fn hash_row(columns: &[Fp], leaf_salt: Option<Fp>) -> Fp {
+    hash_leaf(combine_row_leaf(columns), leaf_salt)
+}
+
+/// Pairing rule for the dataset-commitment column tree behind `pi[3]`; must
+/// match the prover's `commit::MerkleScheme`.
+///
+/// STARK trees always use `Ordered`. `Sorted` follows OpenZeppelin's
+/// `MerkleProof` convention: leaves are hashed (`keccak(value, 0)`) and each
+/// parent hashes its two children in ascending order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleScheme {
+    Ordered,
+    Sorted,
+}
+
+impl MerkleScheme {
+    /// Decode the tag carried in bits 192..256 of `query_metadata[0]`.
+    pub fn from_tag(tag: u64) -> Option<Self> {
+        match tag {
+            0 => Some(MerkleScheme::Ordered),
+            1 => Some(MerkleScheme::Sorted),
+            _ => None,
+        }
+    }
+
+    /// Inverse of `from_tag`.
+    pub fn tag(self) -> u64 {
+        match self {
+            MerkleScheme::Ordered => 0,
+            MerkleScheme::Sorted => 1,
+        }
+    }
+
+    /// Leaf node for a committed value.
+    pub fn leaf(self, value: Fp) -> Fp {
+        match self {
+            MerkleScheme::Ordered => value,
+            MerkleScheme::Sorted => keccak_hash_two(value, Fp::ZERO),
+        }
+    }
+
+    /// Parent of two child nodes: tagged under `TAG_MERKLE_NODE` for
+    /// `Ordered`; `Sorted` keeps the untagged OpenZeppelin pair hash.
+    pub fn hash_pair(self, left: Fp, right: Fp) -> Fp {
+        match self {
+            MerkleScheme::Ordered => keccak_hash_two_tagged(TAG_MERKLE_NODE, left, right),
+            MerkleScheme::Sorted if left.to_u256() > right.to_u256() => keccak_hash_two(right, left),
+            MerkleScheme::Sorted => keccak_hash_two(left, right),
+        }
+    }
+}
+
+/// Parent node `keccak(TAG_MERKLE_NODE || left || right) mod p` over
+/// canonical words: the digest `keccak_hash_two_tagged` computes, without a
+/// Montgomery round trip per level.
+#[inline]
+fn hash_nodes(left: U256, right: U256) -> U256 {
+    let buf = stark_core::keccak::hash_two_tagged_preimage(
+        TAG_MERKLE_NODE,
+        &left.to_be_bytes::<32>(),
+        &right.to_be_bytes::<32>(),
+    );
+    U256::from_be_bytes(crate::keccak256(&buf)).reduce_mod(BN254_PRIME)
+}
 
 /// Merkle path verifier using Keccak256 hash
 pub struct MerkleVerifier;
@@ -18,13 +108,15 @@ impl MerkleVerifier {
     /// # Arguments
     /// * `root` - Expected Merkle root
     /// * `leaf` - Leaf value to verify
-    /// * `path` - Array of sibling hashes along the path from leaf to root
+    /// * `path` - Array of sibling hashes along the path from leaf to root, as
+    ///   raw calldata words: nodes are only ever hashed, so they never enter
+    ///   Montgomery form
     /// * `indices` - Position indicators for each level (false=left, true=right)
     ///
     /// # Returns
     /// `true` if the computed root matches the expected root
     #[inline]
-    pub fn verify(root: Fp, leaf: Fp, path: &[Fp], indices: &[bool]) -> bool {
+    pub fn verify(root: Fp, leaf: Fp, path: &[U256], indices: &[bool]) -> bool {
         // Path and indices must have same length
         if path.len() != indices.len() {
             return false;
@@ -35,18 +127,47 @@ impl MerkleVerifier {
             return leaf == root;
         }
 
-        let mut current = leaf;
+        let mut current = leaf.to_u256();
 
         // Walk up the tree
         for (sibling, is_right) in path.iter().zip(indices.iter()) {
             current = if *is_right {
-                keccak_hash_two(*sibling, current)
+                hash_nodes(*sibling, current)
             } else {
-                keccak_hash_two(current, *sibling)
+                hash_nodes(current, *sibling)
             };
         }
 
-        current == root
+        current == root.to_u256()
+    }
+
+    /// Verify that `leaf` sits at position `index` of the tree with `root`.
+    ///
+    /// The left/right position at each level is taken from the bits of
+    /// `index` (bit 0 for the leaf level), so a path cannot be replayed at a
+    /// different index. Indices beyond the `2^path.len()` leaves are rejected.
+    /// Matches the prover's `MerkleTree::open`.
+    pub fn verify_inclusion(root: Fp, leaf: Fp, index: usize, path: &[U256]) -> bool {
+        if index.checked_shr(path.len() as u32).unwrap_or(0) != 0 {
+            return false;
+        }
+
+        let indices: alloc::vec::Vec<bool> = (0..path.len())
+            .map(|level| index.checked_shr(level as u32).unwrap_or(0) & 1 == 1)
+            .collect();
+        Self::verify(root, leaf, path, &indices)
+    }
+
+    /// Verify a Merkle proof for a committed value, encoding it via `hash_leaf`.
+    #[inline]
+    pub fn verify_value(
+        root: Fp,
+        value: Fp,
+        leaf_salt: Option<Fp>,
+        path: &[U256],
+        indices: &[bool],
+    ) -> bool {
+        Self::verify(root, hash_leaf(value, leaf_salt), path, indices)
     }
 
     /// Compute Merkle root from leaves (test helper)
@@ -67,7 +188,7 @@ impl MerkleVerifier {
             for chunk in current_level.chunks(2) {
                 let left = chunk[0];
                 let right = if chunk.len() > 1 { chunk[1] } else { chunk[0] };
-                next_level.push(keccak_hash_two(left, right));
+                next_level.push(MerkleScheme::Ordered.hash_pair(left, right));
             }
 
             current_level = next_level;
@@ -81,7 +202,10 @@ impl MerkleVerifier {
 mod tests {
     use super::*;
     use alloc::vec;
-    use alloy_primitives::U256;
+
+    fn node(left: Fp, right: Fp) -> Fp {
+        MerkleScheme::Ordered.hash_pair(left, right)
+    }
 
     #[test]
     fn test_empty_path() {
@@ -95,10 +219,10 @@ mod tests {
         let leaf0 = Fp::from_u256(U256::from(100u64));
         let leaf1 = Fp::from_u256(U256::from(200u64));
 
-        let root = keccak_hash_two(leaf0, leaf1);
+        let root = node(leaf0, leaf1);
 
-        assert!(MerkleVerifier::verify(root, leaf0, &[leaf1], &[false]));
-        assert!(MerkleVerifier::verify(root, leaf1, &[leaf0], &[true]));
+        assert!(MerkleVerifier::verify(root, leaf0, &[leaf1.to_u256()], &[false]));
+        assert!(MerkleVerifier::verify(root, leaf1, &[leaf0.to_u256()], &[true]));
     }
 
     #[test]
@@ -110,15 +234,15 @@ mod tests {
             Fp::from_u256(U256::from(4u64)),
         ];
 
-        let h01 = keccak_hash_two(leaves[0], leaves[1]);
-        let h23 = keccak_hash_two(leaves[2], leaves[3]);
-        let root = keccak_hash_two(h01, h23);
+        let h01 = node(leaves[0], leaves[1]);
+        let h23 = node(leaves[2], leaves[3]);
+        let root = node(h01, h23);
 
         assert!(MerkleVerifier::verify(
-            root, leaves[0], &[leaves[1], h23], &[false, false]
+            root, leaves[0], &[leaves[1].to_u256(), h23.to_u256()], &[false, false]
         ));
         assert!(MerkleVerifier::verify(
-            root, leaves[3], &[leaves[2], h01], &[true, true]
+            root, leaves[3], &[leaves[2].to_u256(), h01.to_u256()], &[true, true]
         ));
     }
 
@@ -126,14 +250,14 @@ mod tests {
     fn test_invalid_proof() {
         let leaf0 = Fp::from_u256(U256::from(100u64));
         let leaf1 = Fp::from_u256(U256::from(200u64));
-        let root = keccak_hash_two(leaf0, leaf1);
+        let root = node(leaf0, leaf1);
 
         // Wrong sibling → wrong root
         assert!(!MerkleVerifier::verify(
-            root, leaf0, &[Fp::from_u256(U256::from(999u64))], &[false]
+            root, leaf0, &[U256::from(999u64)], &[false]
         ));
         // Wrong position → wrong root
-        assert!(!MerkleVerifier::verify(root, leaf0, &[leaf1], &[true]));
+        assert!(!MerkleVerifier::verify(root, leaf0, &[leaf1.to_u256()], &[true]));
     }
 
     #[test]
@@ -143,11 +267,58 @@ mod tests {
 
         assert!(!MerkleVerifier::verify(
             root, leaf,
-            &[Fp::from_u256(U256::from(3u64)), Fp::from_u256(U256::from(4u64))],
+            &[U256::from(3u64), U256::from(4u64)],
             &[false]
         ));
     }
 
+    #[test]
+    fn test_verify_inclusion() {
+        let values: alloc::vec::Vec<U256> = (0..8u64).map(|i| U256::from(1000 + i)).collect();
+        let tree = stark_prover::commit::MerkleTree::build(&values);
+        let root = Fp::from_u256(tree.root());
+
+        let (leaf, path) = tree.open(5);
+        let leaf = Fp::from_u256(leaf);
+        assert!(MerkleVerifier::verify_inclusion(root, leaf, 5, &path));
+
+        // Same leaf and path claimed at another index
+        for index in [0, 4, 7, 13] {
+            assert!(!MerkleVerifier::verify_inclusion(root, leaf, index, &path));
+        }
+        // Another leaf at index 5
+        let other = Fp::from_u256(values[4]);
+        assert!(!MerkleVerifier::verify_inclusion(root, other, 5, &path));
+    }
+
+    #[test]
+    fn test_debug_trees_match_proof_commitments() {
+        let bot = stark_prover::mock_data::bot_a_aggressive_eth();
+        let (proof, trees) = stark_prover::debug_trees::prove_sharpe_with_trees(
+            &bot.trades,
+            U256::from(bot.expected_sharpe_sq_scaled),
+            4,
+            None,
+            &Default::default(),
+        );
+
+        let roots: alloc::vec::Vec<U256> = core::iter::once(trees.trace.root())
+            .chain(trees.composition.iter().map(|t| t.root()))
+            .chain(trees.fri_layer_roots.iter().copied())
+            .collect();
+        assert_eq!(roots, proof.commitments);
+        assert_eq!(trees.composition.len(), 2);
+
+        // A queried trace row and a composition leaf open against their roots
+        let index = proof.query_metadata[4].as_limbs()[0] as usize;
+        for tree in core::iter::once(&trees.trace).chain(&trees.composition) {
+            let root = Fp::from_u256(tree.root());
+            let (path, indices) = tree.auth_path(index);
+            assert!(MerkleVerifier::verify(root, Fp::from_u256(tree.leaf(index)), &path, &indices));
+            assert!(!MerkleVerifier::verify(root, Fp::from_u256(tree.leaf(index ^ 1)), &path, &indices));
+        }
+    }
+
     #[test]
     fn test_depth_8_tree() {
         let leaves: alloc::vec::Vec<Fp> = (0..256u64)
@@ -168,9 +339,9 @@ mod tests {
             };
 
             if sibling_index < current_level.len() {
-                path.push(current_level[sibling_index]);
+                path.push(current_level[sibling_index].to_u256());
             } else {
-                path.push(current_level[target_index]);
+                path.push(current_level[target_index].to_u256());
             }
             indices.push(target_index % 2 == 1);
 
@@ -178,7 +349,7 @@ mod tests {
             for chunk in current_level.chunks(2) {
                 let left = chunk[0];
                 let right = if chunk.len() > 1 { chunk[1] } else { chunk[0] };
-                next_level.push(keccak_hash_two(left, right));
+                next_level.push(node(left, right));
             }
 
             target_index /= 2;
@@ -187,4 +358,59 @@ mod tests {
 
         assert!(MerkleVerifier::verify(root, leaves[0], &path, &indices));
     }
+
+    #[test]
+    fn test_hash_leaf_round_trip() {
+        let values: alloc::vec::Vec<Fp> = (0..4u64).map(|i| Fp::from_u256(U256::from(i + 7))).collect();
+        let salt = Fp::from_u256(U256::from(0xa11ceu64));
+
+        for leaf_salt in [None, Some(salt)] {
+            let leaves: alloc::vec::Vec<Fp> = values.iter().map(|&v| hash_leaf(v, leaf_salt)).collect();
+            let root = MerkleVerifier::compute_root(&leaves);
+            let h23 = node(leaves[2], leaves[3]);
+
+            // Open leaf 1 by value: sibling leaf 0, then h23
+            assert!(MerkleVerifier::verify_value(
+                root, values[1], leaf_salt, &[leaves[0].to_u256(), h23.to_u256()], &[true, false]
+            ));
+        }
+
+        assert_eq!(hash_leaf(values[0], None), values[0]);
+        assert_eq!(hash_leaf(values[0], Some(salt)), keccak_hash_two(salt, values[0]));
+
+        // A salted commitment does not open under the wrong salt
+        let leaves: alloc::vec::Vec<Fp> = values.iter().map(|&v| hash_leaf(v, Some(salt))).collect();
+        let root = MerkleVerifier::compute_root(&leaves);
+        let h23 = node(leaves[2], leaves[3]);
+        assert!(!MerkleVerifier::verify_value(
+            root, values[1], None, &[leaves[0].to_u256(), h23.to_u256()], &[true, false]
+        ));
+    }
+
+    #[test]
+    fn test_hash_nodes_matches_keccak_hash_two_tagged() {
+        let words = [U256::ZERO, U256::from(42u64), BN254_PRIME - U256::from(1u64)];
+        for &a in &words {
+            for &b in &words {
+                let expected = keccak_hash_two_tagged(TAG_MERKLE_NODE, Fp::from_u256(a), Fp::from_u256(b));
+                assert_eq!(hash_nodes(a, b), expected.to_u256());
+            }
+        }
+    }
+
+    #[test]
+    fn test_merkle_scheme_pairing() {
+        let a = Fp::from_u256(U256::from(3u64));
+        let b = Fp::from_u256(U256::from(5u64));
+
+        // Sorted pairing is order-independent; ordered pairing is not
+        assert_eq!(MerkleScheme::Sorted.hash_pair(a, b), MerkleScheme::Sorted.hash_pair(b, a));
+        assert_eq!(MerkleScheme::Sorted.hash_pair(b, a), keccak_hash_two(a, b));
+        assert_ne!(MerkleScheme::Ordered.hash_pair(a, b), MerkleScheme::Ordered.hash_pair(b, a));
+
+        assert_eq!(MerkleScheme::Ordered.leaf(a), a);
+        assert_eq!(MerkleScheme::Sorted.leaf(a), keccak_hash_two(a, Fp::ZERO));
+        assert_eq!(MerkleScheme::from_tag(1), Some(MerkleScheme::Sorted));
+        assert_eq!(MerkleScheme::from_tag(2), None);
+    }
 }
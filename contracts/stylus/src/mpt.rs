@@ -1,18 +1,16 @@
 //! MPT (Merkle Patricia Trie) Proof Verification
 //!
-//! Verifies Ethereum receipt MPT proofs on-chain using native Keccak precompile.
+//! Verifies Ethereum receipt MPT proofs on-chain, hashing through the crate's
+//! `keccak256` (the native Keccak precompile in on-chain builds).
 //! Used to verify that a transaction receipt exists in a block's receiptsRoot.
 
-use alloc::vec;
 use alloc::vec::Vec;
 use alloy_primitives::U256;
 use crate::field::Fp;
+use crate::keccak256;
+use crate::merkle::MerkleScheme;
 
-/// Keccak256 hash using the Stylus native precompile.
-#[inline]
-fn keccak256(data: &[u8]) -> [u8; 32] {
-    stylus_sdk::crypto::keccak(data).0
-}
+pub use stark_core::mpt::{decode_tx_index_key, is_well_formed_receipt};
 
 /// Verify an MPT proof: verify that a key maps to a value under the given root.
 ///
@@ -27,82 +25,23 @@ pub fn verify_mpt_proof(
     key: &[u8],
     proof_nodes: &[Vec<u8>],
 ) -> Option<Vec<u8>> {
-    if proof_nodes.is_empty() {
-        return None;
-    }
-
-    let key_nibbles = bytes_to_nibbles(key);
-    let mut key_offset = 0;
-    let mut expected_hash = *root;
-
-    for node_rlp in proof_nodes {
-        // Verify the node hash matches expected
-        if node_rlp.len() >= 32 {
-            let node_hash = keccak256(node_rlp);
-            if node_hash != expected_hash {
-                return None;
-            }
-        }
+    stark_core::mpt::verify_proof(root, key, proof_nodes, keccak256)
+}
 
-        let items = rlp_decode_list(node_rlp)?;
-
-        match items.len() {
-            17 => {
-                // Branch node: 16 children + value
-                if key_offset >= key_nibbles.len() {
-                    return Some(items[16].clone());
-                }
-                let nibble = key_nibbles[key_offset] as usize;
-                if nibble >= 16 {
-                    return None;
-                }
-                key_offset += 1;
-
-                let child = &items[nibble];
-                if child.is_empty() {
-                    return None;
-                }
-                if child.len() == 32 {
-                    let mut hash = [0u8; 32];
-                    hash.copy_from_slice(child);
-                    expected_hash = hash;
-                } else {
-                    expected_hash = [0u8; 32];
-                }
-            }
-            2 => {
-                // Extension or Leaf node
-                let (prefix_nibbles, is_leaf) = decode_hp_prefix(&items[0])?;
-
-                for nibble in &prefix_nibbles {
-                    if key_offset >= key_nibbles.len() || key_nibbles[key_offset] != *nibble {
-                        return None;
-                    }
-                    key_offset += 1;
-                }
-
-                if is_leaf {
-                    if key_offset == key_nibbles.len() {
-                        return Some(items[1].clone());
-                    }
-                    return None;
-                }
-
-                // Extension node
-                let child = &items[1];
-                if child.len() == 32 {
-                    let mut hash = [0u8; 32];
-                    hash.copy_from_slice(child);
-                    expected_hash = hash;
-                } else {
-                    expected_hash = [0u8; 32];
-                }
-            }
-            _ => return None,
-        }
+/// Verify an MPT proof against an explicitly supplied receipt.
+///
+/// Accepts when the proven leaf hashes to `keccak(receipt_rlp)`, binding the
+/// caller's receipt bytes to the trie without extracting them from the leaf.
+pub fn verify_mpt_proof_for_receipt(
+    root: &[u8; 32],
+    key: &[u8],
+    proof_nodes: &[Vec<u8>],
+    receipt_rlp: &[u8],
+) -> bool {
+    match verify_mpt_proof(root, key, proof_nodes) {
+        Some(leaf) => keccak256(&leaf) == keccak256(receipt_rlp),
+        None => false,
     }
-
-    None
 }
 
 /// Compute dataset_commitment = keccak(blockHash, keccak(receiptsRoot, receiptHash))
@@ -184,18 +123,25 @@ pub fn decode_proof_nodes(words: &[U256], total_len: usize) -> Option<Vec<Vec<u8
 ///
 /// Uses O(log n) keccak hashes (constant-leaf tree optimization).
 /// For a tree of size 2^log_size, if every leaf = v, then:
-///   level 0: leaf = v
-///   level 1: hash(v, v)
-///   level 2: hash(hash(v,v), hash(v,v))
+///   level 0: leaf = scheme.leaf(v)
+///   level 1: hash(leaf, leaf)
+///   level 2: hash(hash(leaf,leaf), hash(leaf,leaf))
 ///   ...
-pub fn compute_constant_merkle_root(leaf_value: Fp, log_size: u32) -> Fp {
-    let mut current = leaf_value;
+pub fn compute_constant_merkle_root(leaf_value: Fp, log_size: u32, scheme: MerkleScheme) -> Fp {
+    let mut current = scheme.leaf(leaf_value);
     for _ in 0..log_size {
-        current = crate::keccak_hash_two(current, current);
+        current = scheme.hash_pair(current, current);
     }
     current
 }
 
+/// Bind an `as_of_block` into a dataset commitment: `keccak(commitment, as_of_block)`.
+///
+/// Must match the prover's `bind_as_of_block`.
+pub fn bind_as_of_block(commitment: Fp, as_of_block: Fp) -> Fp {
+    crate::keccak_hash_two(commitment, as_of_block)
+}
+
 /// Compute aggregate commitment from multiple receipt hashes.
 ///
 /// Uses a left-fold keccak hash chain:
@@ -220,6 +166,24 @@ pub fn compute_commitment_from_hashes(receipt_hashes: &[Fp]) -> Fp {
     }
 }
 
+/// Decode a receipt trie key from calldata words into its bytes and tx index.
+///
+/// `key_len` must fit the supplied words exactly (no spare words, zero bytes
+/// after the key), and the key bytes must be a canonical RLP integer, so an
+/// over-long `key_len` cannot pad the nibble path with zero bytes.
+pub fn decode_receipt_key(words: &[U256], key_len: usize) -> Option<(Vec<u8>, u64)> {
+    if key_len == 0 || words.len() != key_len.div_ceil(32) {
+        return None;
+    }
+    let bytes = decode_u256_words(words, words.len() * 32);
+    if bytes[key_len..].iter().any(|&b| b != 0) {
+        return None;
+    }
+    let key = bytes[..key_len].to_vec();
+    let tx_index = decode_tx_index_key(&key)?;
+    Some((key, tx_index))
+}
+
 /// Decode U256 words to a flat byte array, truncating to actual_len.
 pub fn decode_u256_words(words: &[U256], actual_len: usize) -> Vec<u8> {
     let mut result = Vec::with_capacity(actual_len);
@@ -231,160 +195,86 @@ pub fn decode_u256_words(words: &[U256], actual_len: usize) -> Vec<u8> {
     result
 }
 
-/// Convert bytes to nibbles (half-bytes).
-fn bytes_to_nibbles(data: &[u8]) -> Vec<u8> {
-    let mut nibbles = Vec::with_capacity(data.len() * 2);
-    for byte in data {
-        nibbles.push(byte >> 4);
-        nibbles.push(byte & 0x0f);
-    }
-    nibbles
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stark_core::keccak::TAG_MERKLE_NODE;
+    use stark_core::rlp::{encode_bytes, encode_list};
+    use stark_prover::receipt_proof::rlp_encode_receipt;
 
-/// Decode hex prefix encoding used in MPT leaf/extension nodes.
-fn decode_hp_prefix(encoded: &[u8]) -> Option<(Vec<u8>, bool)> {
-    if encoded.is_empty() {
-        return None;
+    /// Legacy receipt with a distinguishing cumulativeGasUsed.
+    fn test_receipt(gas: u64) -> Vec<u8> {
+        rlp_encode_receipt(0, 1, gas, &[0u8; 256], &[])
     }
-    let first_nibble = encoded[0] >> 4;
-    let is_leaf = first_nibble >= 2;
-    let is_odd = first_nibble & 1 == 1;
 
-    let mut nibbles = Vec::new();
-    if is_odd {
-        nibbles.push(encoded[0] & 0x0f);
-    }
-    for byte in &encoded[1..] {
-        nibbles.push(byte >> 4);
-        nibbles.push(byte & 0x0f);
-    }
+    /// Two-receipt trie (tx 0 and tx 1): returns (root, [branch, leaf_1]).
+    fn tx1_trie_proof() -> ([u8; 32], Vec<Vec<u8>>) {
+        // key 0x80 → nibbles [8, 0]; key 0x01 → nibbles [0, 1]
+        let leaf_0 = encode_list(&[encode_bytes(&[0x30]), encode_bytes(&test_receipt(0x10))]);
+        let leaf_1 = encode_list(&[encode_bytes(&[0x31]), encode_bytes(&test_receipt(0x20))]);
 
-    Some((nibbles, is_leaf))
-}
+        let mut children = vec![encode_bytes(&[]); 17];
+        children[0] = encode_bytes(&keccak256(&leaf_1));
+        children[8] = encode_bytes(&keccak256(&leaf_0));
+        let branch = encode_list(&children);
 
-/// Decode an RLP list into its items (raw bytes).
-fn rlp_decode_list(data: &[u8]) -> Option<Vec<Vec<u8>>> {
-    if data.is_empty() {
-        return None;
+        (keccak256(&branch), vec![branch, leaf_1])
     }
 
-    let (payload, _) = decode_rlp_length(data)?;
-    let mut items = Vec::new();
-    let mut offset = 0;
-
-    while offset < payload.len() {
-        let (item, consumed) = decode_rlp_item(&payload[offset..])?;
-        items.push(item);
-        offset += consumed;
+    #[test]
+    fn test_verify_mpt_proof_receipt_leaf() {
+        let (root, nodes) = tx1_trie_proof();
+        let leaf = verify_mpt_proof(&root, &[0x01], &nodes).unwrap();
+        assert_eq!(leaf, test_receipt(0x20));
+        assert!(is_well_formed_receipt(&leaf));
     }
 
-    Some(items)
-}
+    /// Extension root whose branch child and both leaves are embedded inline.
+    /// Keys 0xab10 / 0xab20 share the extension path [a, b]; returns (root, [extension]).
+    fn inline_trie_proof() -> ([u8; 32], Vec<Vec<u8>>) {
+        let leaf_1 = encode_list(&[encode_bytes(&[0x30]), encode_bytes(&[0x01, 0x11])]);
+        let leaf_2 = encode_list(&[encode_bytes(&[0x30]), encode_bytes(&[0x02, 0x22])]);
 
-fn decode_rlp_length(data: &[u8]) -> Option<(&[u8], usize)> {
-    if data.is_empty() {
-        return None;
-    }
-    let prefix = data[0];
-
-    if prefix <= 0x7f {
-        Some((&data[0..1], 1))
-    } else if prefix <= 0xb7 {
-        let len = (prefix - 0x80) as usize;
-        if data.len() < 1 + len { return None; }
-        Some((&data[1..1 + len], 1 + len))
-    } else if prefix <= 0xbf {
-        let len_of_len = (prefix - 0xb7) as usize;
-        if data.len() < 1 + len_of_len { return None; }
-        let mut len = 0usize;
-        for i in 0..len_of_len {
-            len = (len << 8) | (data[1 + i] as usize);
-        }
-        if data.len() < 1 + len_of_len + len { return None; }
-        Some((&data[1 + len_of_len..1 + len_of_len + len], 1 + len_of_len + len))
-    } else if prefix <= 0xf7 {
-        let len = (prefix - 0xc0) as usize;
-        if data.len() < 1 + len { return None; }
-        Some((&data[1..1 + len], 1 + len))
-    } else {
-        let len_of_len = (prefix - 0xf7) as usize;
-        if data.len() < 1 + len_of_len { return None; }
-        let mut len = 0usize;
-        for i in 0..len_of_len {
-            len = (len << 8) | (data[1 + i] as usize);
-        }
-        if data.len() < 1 + len_of_len + len { return None; }
-        Some((&data[1 + len_of_len..1 + len_of_len + len], 1 + len_of_len + len))
-    }
-}
+        let mut children = vec![encode_bytes(&[]); 17];
+        children[1] = leaf_1;
+        children[2] = leaf_2;
+        let branch = encode_list(&children);
+        assert!(branch.len() < 32);
 
-fn decode_rlp_item(data: &[u8]) -> Option<(Vec<u8>, usize)> {
-    if data.is_empty() {
-        return None;
-    }
-    let prefix = data[0];
-
-    if prefix <= 0x7f {
-        Some((vec![prefix], 1))
-    } else if prefix <= 0xb7 {
-        let len = (prefix - 0x80) as usize;
-        if data.len() < 1 + len { return None; }
-        Some((data[1..1 + len].to_vec(), 1 + len))
-    } else if prefix <= 0xbf {
-        let len_of_len = (prefix - 0xb7) as usize;
-        if data.len() < 1 + len_of_len { return None; }
-        let mut len = 0usize;
-        for i in 0..len_of_len {
-            len = (len << 8) | (data[1 + i] as usize);
-        }
-        if data.len() < 1 + len_of_len + len { return None; }
-        Some((data[1 + len_of_len..1 + len_of_len + len].to_vec(), 1 + len_of_len + len))
-    } else if prefix <= 0xf7 {
-        let len = (prefix - 0xc0) as usize;
-        if data.len() < 1 + len { return None; }
-        Some((data[..1 + len].to_vec(), 1 + len))
-    } else {
-        let len_of_len = (prefix - 0xf7) as usize;
-        if data.len() < 1 + len_of_len { return None; }
-        let mut len = 0usize;
-        for i in 0..len_of_len {
-            len = (len << 8) | (data[1 + i] as usize);
-        }
-        if data.len() < 1 + len_of_len + len { return None; }
-        Some((data[..1 + len_of_len + len].to_vec(), 1 + len_of_len + len))
+        let extension = encode_list(&[encode_bytes(&[0x00, 0xab]), branch]);
+        (keccak256(&extension), vec![extension])
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_bytes_to_nibbles() {
-        assert_eq!(bytes_to_nibbles(&[0xab, 0xcd]), vec![0xa, 0xb, 0xc, 0xd]);
-    }
+    fn test_verify_mpt_proof_inline_children() {
+        let (root, nodes) = inline_trie_proof();
 
-    #[test]
-    fn test_decode_hp_prefix_leaf() {
-        let (nibbles, is_leaf) = decode_hp_prefix(&[0x20, 0xab]).unwrap();
-        assert!(is_leaf);
-        assert_eq!(nibbles, vec![0xa, 0xb]);
+        assert_eq!(verify_mpt_proof(&root, &[0xab, 0x10], &nodes), Some(vec![0x01, 0x11]));
+        assert_eq!(verify_mpt_proof(&root, &[0xab, 0x20], &nodes), Some(vec![0x02, 0x22]));
+        assert!(verify_mpt_proof(&root, &[0xab, 0x30], &nodes).is_none());
+
+        // The root node is hash-checked even though it is shorter than 32 bytes
+        assert!(nodes[0].len() < 32);
+        let mut bad_root = root;
+        bad_root[0] ^= 1;
+        assert!(verify_mpt_proof(&bad_root, &[0xab, 0x10], &nodes).is_none());
     }
 
     #[test]
-    fn test_decode_hp_prefix_extension() {
-        let (nibbles, is_leaf) = decode_hp_prefix(&[0x00, 0xab]).unwrap();
-        assert!(!is_leaf);
-        assert_eq!(nibbles, vec![0xa, 0xb]);
+    fn test_verify_mpt_proof_for_receipt() {
+        let (root, nodes) = tx1_trie_proof();
+        assert!(verify_mpt_proof_for_receipt(&root, &[0x01], &nodes, &test_receipt(0x20)));
+
+        // tx 0's receipt is well formed but is not the proven leaf
+        assert!(!verify_mpt_proof_for_receipt(&root, &[0x01], &nodes, &test_receipt(0x10)));
+        assert!(!verify_mpt_proof_for_receipt(&root, &[0x01], &nodes[..1], &test_receipt(0x20)));
     }
 
     #[test]
-    fn test_rlp_decode_simple_list() {
-        let data = vec![0xc2, 0x01, 0x02];
-        let items = rlp_decode_list(&data).unwrap();
-        assert_eq!(items.len(), 2);
-        assert_eq!(items[0], vec![0x01]);
-        assert_eq!(items[1], vec![0x02]);
+    fn test_verify_mpt_proof_mismatched_key_rejected() {
+        // Proof nodes are for tx 1, but the key points at tx 0
+        let (root, nodes) = tx1_trie_proof();
+        assert!(verify_mpt_proof(&root, &[0x80], &nodes).is_none());
     }
 
     #[test]
@@ -398,6 +288,28 @@ mod tests {
         assert_eq!(c1, c2);
     }
 
+    #[test]
+    fn test_prover_dataset_commitment_matches() {
+        use stark_prover::receipt_proof::compute_dataset_commitment;
+
+        // The prover reduces with `mul_mod`, the verifier through Montgomery
+        // form. p < 2^254, so most keccak digests exceed it and take the
+        // reduction; the last block hash is not a canonical field element.
+        let cases: [(U256, [u8; 32], &[u8]); 5] = [
+            (U256::ZERO, [0u8; 32], b""),
+            (U256::from(0x1234u64), [0xabu8; 32], b"test_data"),
+            (U256::from(18_000_000u64), [0x5au8; 32], &[0xf9, 0x01, 0x02, 0x03]),
+            (crate::field::BN254_PRIME, [0xffu8; 32], &[0u8; 300]),
+            (U256::MAX, [0x01u8; 32], b"receipt"),
+        ];
+        for (block_hash, receipts_root, receipt_rlp) in cases {
+            let prover = compute_dataset_commitment(block_hash, &receipts_root, receipt_rlp);
+            let onchain = compute_dataset_commitment_onchain(block_hash, &receipts_root, receipt_rlp);
+            assert_eq!(Fp::from_u256(prover), onchain, "block hash {:#x}", block_hash);
+            assert_eq!(prover, onchain.to_u256(), "block hash {:#x}", block_hash);
+        }
+    }
+
     #[test]
     fn test_decode_proof_nodes_basic() {
         // Create a simple proof with 2 nodes: [0x01, 0x02] and [0x03, 0x04, 0x05]
@@ -443,7 +355,7 @@ mod tests {
     fn test_compute_constant_merkle_root_log0() {
         // log_size=0 → single leaf, root = leaf
         let leaf = Fp::from_u256(U256::from(42u64));
-        let root = compute_constant_merkle_root(leaf, 0);
+        let root = compute_constant_merkle_root(leaf, 0, MerkleScheme::Ordered);
         assert_eq!(root, leaf);
     }
 
@@ -451,8 +363,8 @@ mod tests {
     fn test_compute_constant_merkle_root_log1() {
         // log_size=1 → 2 leaves, root = hash(leaf, leaf)
         let leaf = Fp::from_u256(U256::from(42u64));
-        let root = compute_constant_merkle_root(leaf, 1);
-        let expected = crate::keccak_hash_two(leaf, leaf);
+        let root = compute_constant_merkle_root(leaf, 1, MerkleScheme::Ordered);
+        let expected = MerkleScheme::Ordered.hash_pair(leaf, leaf);
         assert_eq!(root, expected);
     }
 
@@ -460,12 +372,23 @@ mod tests {
     fn test_compute_constant_merkle_root_log2() {
         // log_size=2 → 4 leaves, root = hash(hash(leaf,leaf), hash(leaf,leaf))
         let leaf = Fp::from_u256(U256::from(42u64));
-        let root = compute_constant_merkle_root(leaf, 2);
-        let l1 = crate::keccak_hash_two(leaf, leaf);
-        let expected = crate::keccak_hash_two(l1, l1);
+        let root = compute_constant_merkle_root(leaf, 2, MerkleScheme::Ordered);
+        let l1 = crate::keccak_hash_two_tagged(TAG_MERKLE_NODE, leaf, leaf);
+        let expected = crate::keccak_hash_two_tagged(TAG_MERKLE_NODE, l1, l1);
         assert_eq!(root, expected);
     }
 
+    #[test]
+    fn test_compute_constant_merkle_root_sorted() {
+        // Sorted scheme hashes the leaf first; equal siblings need no reordering
+        let leaf = Fp::from_u256(U256::from(42u64));
+        let hashed = crate::keccak_hash_two(leaf, Fp::ZERO);
+        let root = compute_constant_merkle_root(leaf, 2, MerkleScheme::Sorted);
+        let l1 = crate::keccak_hash_two(hashed, hashed);
+        assert_eq!(root, crate::keccak_hash_two(l1, l1));
+        assert_ne!(root, compute_constant_merkle_root(leaf, 2, MerkleScheme::Ordered));
+    }
+
     #[test]
     fn test_decode_u256_words() {
         let mut word_bytes = [0u8; 32];
@@ -477,6 +400,36 @@ mod tests {
         assert_eq!(result, vec![0xAB, 0xCD, 0xEF]);
     }
 
+    /// Left-align `bytes` in one calldata word, as the prover packs keys.
+    fn key_word(bytes: &[u8]) -> U256 {
+        let mut word = [0u8; 32];
+        word[..bytes.len()].copy_from_slice(bytes);
+        U256::from_be_bytes(word)
+    }
+
+    #[test]
+    fn test_decode_receipt_key() {
+        // Canonical keys for tx 0, 1 and 0x80
+        assert_eq!(decode_receipt_key(&[key_word(&[0x80])], 1), Some((vec![0x80], 0)));
+        assert_eq!(decode_receipt_key(&[key_word(&[0x01])], 1), Some((vec![0x01], 1)));
+        assert_eq!(decode_receipt_key(&[key_word(&[0x81, 0x80])], 2), Some((vec![0x81, 0x80], 0x80)));
+
+        // Over-long key_len pads the key with zero bytes
+        assert_eq!(decode_receipt_key(&[key_word(&[0x01])], 2), None);
+        assert_eq!(decode_receipt_key(&[key_word(&[0x81, 0x80])], 3), None);
+        assert_eq!(decode_receipt_key(&[key_word(&[0x01])], 40), None);
+        // Short key_len drops key bytes into the ignored padding
+        assert_eq!(decode_receipt_key(&[key_word(&[0x81, 0x80])], 1), None);
+        // Spare words or an empty key
+        assert_eq!(decode_receipt_key(&[key_word(&[0x01]), U256::ZERO], 1), None);
+        assert_eq!(decode_receipt_key(&[], 0), None);
+
+        // Not an RLP integer
+        assert_eq!(decode_receipt_key(&[key_word(&[0x00])], 1), None);
+        assert_eq!(decode_receipt_key(&[key_word(&[0xc1, 0x80])], 2), None);
+        assert_eq!(decode_receipt_key(&[key_word(&[0x82, 0x01])], 2), None);
+    }
+
     #[test]
     fn test_commitment_from_hashes_empty() {
         assert_eq!(compute_commitment_from_hashes(&[]), Fp::ZERO);
@@ -506,6 +459,24 @@ mod tests {
         assert_eq!(compute_commitment_from_hashes(&[h0, h1, h2]), expected);
     }
 
+    #[test]
+    fn test_prover_hash_chain_matches() {
+        use stark_prover::receipt_proof::{DatasetCommitment, ReceiptHashChain};
+
+        // The last hash is not a canonical field element
+        let hashes = [U256::from(100u64), U256::from(200u64), U256::from(300u64), U256::MAX];
+        for n in 1..=hashes.len() {
+            let fps: Vec<Fp> = hashes[..n].iter().map(|h| Fp::from_u256(*h)).collect();
+            assert_eq!(
+                ReceiptHashChain(hashes[..n].to_vec()).compute(),
+                compute_commitment_from_hashes(&fps).to_u256(),
+                "{} hashes",
+                n
+            );
+        }
+        assert_eq!(ReceiptHashChain(vec![]).compute(), U256::ZERO);
+    }
+
     #[test]
     fn test_commitment_from_hashes_deterministic() {
         let hashes: Vec<Fp> = (1..=5)
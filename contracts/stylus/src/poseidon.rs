@@ -0,0 +1,7 @@
+//! Poseidon hash over the BN254 scalar field
+//!
+//! circomlib-compatible Poseidon (t = 3, x^5, 8 full + 57 partial rounds).
+//! The constants and permutation live in `stark_core::poseidon`, shared with
+//! the prover.
+
+pub use stark_core::poseidon::poseidon_hash_two;
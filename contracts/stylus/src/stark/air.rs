@@ -0,0 +1,199 @@
+//! AIR Registration
+//!
+//! `define_stark_air!` declares a proof kind in one place: given the AIR's
+//! trace width, public input count, composition segment minimum, alpha
+//! count, immutable and dataset columns, and its evaluation functions, it
+//! generates
+//!
+//! - the typed proof (`StarkProof<W>`, the OOD layout [W at z, W at zg,
+//!   one per segment]) and its parser,
+//! - the composition C(z), at given alphas and drawn from the channel,
+//! - `verify_*_with_transcript` and the boolean `verify_*` entry point, with
+//!   the public-input prechecks and the Keccak/Poseidon dispatch.
+//!
+//! The generated verifier runs the shared `verify_parsed_proof` pipeline, so
+//! a new AIR only adds its constraint module and one invocation; the
+//! contract's `#[public]` method then calls the generated `verify_*`. The
+//! prover registers the same AIR with its `define_prover_air!`, which
+//! generates the proof constructor. Sharpe and Sortino are both registered
+//! this way (see `stark/mod.rs`).
+//!
+//! The AIR module supplies `evaluate_transition_ood([Fp; W], [Fp; W])`,
+//! `evaluate_boundary_quotients(trace_at_z, z, first, last_trade, [Fp; P])`
+//! and `combine_quotients(tqs, bqs, &alphas)`, as `sharpe_air` does. The P
+//! public inputs start with [trade_count, total_return, claim]; a proof may
+//! append one more, the as_of_block, which only feeds the transcript seed.
+//! Transitions are divided by `domain::transition_zerofier_at`.
+
+/// Generate the parser, composition and verify functions of one AIR; see
+/// the module docs. Each generated item takes the doc comments written
+/// before its name.
+macro_rules! define_stark_air {
+    (
+        kind: $kind:expr,
+        columns: $columns:expr,
+        public_inputs: $num_public_inputs:expr,
+        min_segments: $min_segments:expr,
+        alphas: $alphas:expr,
+        immutable_columns: $immutable:expr,
+        dataset_column: $dataset_column:expr,
+        claim_in_range: $claim_in_range:path,
+        transition: $transition:path,
+        boundary: $boundary:path,
+        combine: $combine:path,
+        $(#[$proof_meta:meta])* proof: $proof:ident,
+        $(#[$parse_meta:meta])* parse: $parse:ident,
+        $(#[$with_alphas_meta:meta])* composition_with_alphas: $with_alphas:ident,
+        $(#[$at_z_meta:meta])* composition_at_z: $at_z:ident,
+        $(#[$transcript_meta:meta])* verify_with_transcript: $verify_with_transcript:ident,
+        $(#[$verify_meta:meta])* verify: $verify:ident $(,)?
+    ) => {
+        $(#[$proof_meta])*
+        pub type $proof = $crate::stark::proof::StarkProof<{ $columns }>;
+
+        $(#[$parse_meta])*
+        pub fn $parse(
+            commitments: &[alloy_primitives::U256],
+            ood_values: &[alloy_primitives::U256],
+            fri_final_poly: &[alloy_primitives::U256],
+            query_values: &[alloy_primitives::U256],
+            query_paths: &[alloy_primitives::U256],
+            query_metadata: &[alloy_primitives::U256],
+        ) -> Option<$proof> {
+            $crate::stark::proof::parse_stark_proof(
+                commitments,
+                ood_values,
+                fri_final_poly,
+                query_values,
+                query_paths,
+                query_metadata,
+            )
+        }
+
+        $(#[$with_alphas_meta])*
+        fn $with_alphas(
+            proof: &$proof,
+            public_inputs: &[$crate::field::Fp],
+            z: $crate::field::Fp,
+            alphas: &[$crate::field::Fp; $alphas],
+        ) -> $crate::field::Fp {
+            use $crate::field::{BN254Field, Fp};
+
+            let trace_len = 1u64 << proof.log_trace_len;
+            let trace_gen = $crate::stark::domain::domain_generator(proof.log_trace_len);
+
+            // Step 3: transition quotients at the OOD point z
+            let transition_evals = $transition(proof.trace_ood_evals, proof.trace_ood_evals_next);
+            let zerofier = $crate::stark::domain::transition_zerofier_at(z, trace_len, trace_gen);
+            let tqs = transition_evals.map(|tc| BN254Field::div(tc, zerofier));
+
+            // Step 4: boundary quotients (first row and last trade row)
+            let boundary_quotients = $boundary(
+                proof.trace_ood_evals,
+                z,
+                Fp::ONE,
+                $crate::stark::last_trade_point(trace_gen, public_inputs[0]),
+                core::array::from_fn::<_, { $num_public_inputs }, _>(|i| public_inputs[i]),
+            );
+
+            // Step 5: compose
+            $combine(tqs, boundary_quotients, alphas)
+        }
+
+        $(#[$at_z_meta])*
+        fn $at_z<H: $crate::stark::channel::TranscriptHash>(
+            proof: &$proof,
+            public_inputs: &[$crate::field::Fp],
+            z: $crate::field::Fp,
+            channel: &mut $crate::stark::channel::Channel<H>,
+        ) -> $crate::field::Fp {
+            let alphas = channel.draw_alphas::<{ $alphas }>();
+            $with_alphas(proof, public_inputs, z, &alphas)
+        }
+
+        $(#[$transcript_meta])*
+        pub fn $verify_with_transcript(
+            public_inputs: &[alloy_primitives::U256],
+            commitments: &[alloy_primitives::U256],
+            ood_values: &[alloy_primitives::U256],
+            fri_final_poly: &[alloy_primitives::U256],
+            query_values: &[alloy_primitives::U256],
+            query_paths: &[alloy_primitives::U256],
+            query_metadata: &[alloy_primitives::U256],
+        ) -> (bool, alloy_primitives::U256) {
+            use alloy_primitives::U256;
+            use $crate::field::Fp;
+            use $crate::stark::channel::{KeccakTranscript, PoseidonTranscript, TranscriptKind};
+
+            // Cheap range precheck before any hashing
+            if !$crate::stark::air_input_count_ok(public_inputs.len(), $num_public_inputs)
+                || !$claim_in_range(public_inputs[2])
+                || !$crate::stark::total_return_in_range(public_inputs[1])
+            {
+                return (false, U256::ZERO);
+            }
+
+            let Some(proof) = $parse(
+                commitments,
+                ood_values,
+                fri_final_poly,
+                query_values,
+                query_paths,
+                query_metadata,
+            ) else {
+                return (false, U256::ZERO);
+            };
+            if proof.composition_commitments.len() < $min_segments
+                || !$crate::stark::trade_count_fits(public_inputs[0], proof.log_trace_len)
+                || !$crate::stark::immutable_ood_consistent(&proof, &$immutable)
+            {
+                return (false, U256::ZERO);
+            }
+
+            // The as_of_block, when present, only feeds the Fiat-Shamir seed
+            let pub_fp: alloc::vec::Vec<Fp> = public_inputs.iter().map(|v| Fp::from_u256(*v)).collect();
+
+            let (valid, digest) = match proof.transcript {
+                TranscriptKind::Keccak => $crate::stark::verify_parsed_proof::<KeccakTranscript, { $columns }>(
+                    &proof,
+                    $kind,
+                    &pub_fp,
+                    $dataset_column,
+                    $at_z::<KeccakTranscript>,
+                ),
+                TranscriptKind::Poseidon => $crate::stark::verify_parsed_proof::<PoseidonTranscript, { $columns }>(
+                    &proof,
+                    $kind,
+                    &pub_fp,
+                    $dataset_column,
+                    $at_z::<PoseidonTranscript>,
+                ),
+            };
+            (valid, digest.to_u256())
+        }
+
+        $(#[$verify_meta])*
+        pub fn $verify(
+            public_inputs: &[alloy_primitives::U256],
+            commitments: &[alloy_primitives::U256],
+            ood_values: &[alloy_primitives::U256],
+            fri_final_poly: &[alloy_primitives::U256],
+            query_values: &[alloy_primitives::U256],
+            query_paths: &[alloy_primitives::U256],
+            query_metadata: &[alloy_primitives::U256],
+        ) -> bool {
+            $verify_with_transcript(
+                public_inputs,
+                commitments,
+                ood_values,
+                fri_final_poly,
+                query_values,
+                query_paths,
+                query_metadata,
+            )
+            .0
+        }
+    };
+}
+
+pub(crate) use define_stark_air;
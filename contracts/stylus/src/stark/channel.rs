@@ -1,54 +1,126 @@
-//! Fiat-Shamir Channel using Keccak256
+//! Fiat-Shamir Channel
 //!
 //! Implements a deterministic transcript for non-interactive proofs.
-//! All randomness is derived from Keccak256 hashing of the protocol transcript.
+//! All randomness is derived from hashing the protocol transcript with a
+//! pluggable `TranscriptHash`: Keccak256 (default, cheap via the Stylus
+//! precompile) or Poseidon (cheap inside recursive circuits).
+//!
+//! Keccak transcripts absorb under `TAG_CHANNEL_COMMIT` and draw under
+//! `TAG_CHANNEL_DRAW`, domain-separated from Merkle nodes (`TAG_MERKLE_NODE`).
+
+use core::marker::PhantomData;
 
 use alloy_primitives::U256;
 
 use crate::field::Fp;
-use crate::keccak_hash_two;
+use crate::{keccak_hash_two, keccak_hash_two_tagged};
+use crate::poseidon::poseidon_hash_two;
+
+pub use stark_core::channel::{ProofKind, TranscriptKind};
+use stark_core::channel::{public_input_seed, query_index};
+use stark_core::keccak::{TAG_CHANNEL_COMMIT, TAG_CHANNEL_DRAW};
+
+/// Two-to-one hash driving the Fiat-Shamir transcript.
+pub trait TranscriptHash {
+    fn hash_two(a: Fp, b: Fp) -> Fp;
+
+    /// `hash_two` under a domain tag. Defaults to ignoring the tag.
+    #[inline]
+    fn hash_two_tagged(_tag: u8, a: Fp, b: Fp) -> Fp {
+        Self::hash_two(a, b)
+    }
+}
+
+/// Keccak256 transcript (default).
+pub struct KeccakTranscript;
+
+impl TranscriptHash for KeccakTranscript {
+    #[inline]
+    fn hash_two(a: Fp, b: Fp) -> Fp {
+        keccak_hash_two(a, b)
+    }
+
+    #[inline]
+    fn hash_two_tagged(tag: u8, a: Fp, b: Fp) -> Fp {
+        keccak_hash_two_tagged(tag, a, b)
+    }
+}
+
+/// Poseidon transcript. Untagged: trees are committed with Keccak, so a
+/// Poseidon transcript never shares a preimage with a Merkle node.
+pub struct PoseidonTranscript;
+
+impl TranscriptHash for PoseidonTranscript {
+    #[inline]
+    fn hash_two(a: Fp, b: Fp) -> Fp {
+        poseidon_hash_two(a, b)
+    }
+}
 
 /// Fiat-Shamir channel for deterministic challenge generation.
-pub struct Channel {
+pub struct Channel<H: TranscriptHash = KeccakTranscript> {
     /// Current channel state
     state: Fp,
     /// Counter for unique challenge derivation
     counter: u64,
+    _hash: PhantomData<H>,
 }
 
 impl Channel {
-    /// Create a new channel with an initial seed.
+    /// Create a new Keccak channel with an initial seed.
     pub fn new(seed: Fp) -> Self {
+        Channel::with_transcript(seed)
+    }
+}
+
+impl<H: TranscriptHash> Channel<H> {
+    /// Create a new channel over transcript hash `H` with an initial seed.
+    pub fn with_transcript(seed: Fp) -> Self {
         Channel {
             state: seed,
             counter: 0,
+            _hash: PhantomData,
         }
     }
 
+    /// Create a channel seeded with a `kind` proof's public inputs, framed by
+    /// kind and count (`stark_core::channel::public_input_seed`).
+    pub fn with_public_inputs(kind: ProofKind, public_inputs: &[Fp]) -> Self {
+        let frame = Fp::from_u256(kind.seed_frame(public_inputs.len()));
+        Self::with_transcript(public_input_seed(frame, public_inputs.iter().copied(), |h, pi| {
+            H::hash_two_tagged(TAG_CHANNEL_COMMIT, h, pi)
+        }))
+    }
+
     /// Commit a value to the channel transcript.
     pub fn commit(&mut self, value: Fp) {
-        self.state = keccak_hash_two(self.state, value);
+        self.state = H::hash_two_tagged(TAG_CHANNEL_COMMIT, self.state, value);
         self.counter = 0;
     }
 
     /// Draw a random field element from the channel.
     pub fn draw_felt(&mut self) -> Fp {
         let counter_fp = Fp::from_u256(U256::from(self.counter));
-        let challenge = keccak_hash_two(self.state, counter_fp);
+        let challenge = H::hash_two_tagged(TAG_CHANNEL_DRAW, self.state, counter_fp);
         self.counter += 1;
         challenge
     }
 
+    /// Draw `N` constraint-composition coefficients in one call.
+    ///
+    /// Identical to `N` sequential `draw_felt` calls, so AIRs switching to it
+    /// keep their transcripts; the count is fixed by the AIR at compile time.
+    pub fn draw_alphas<const N: usize>(&mut self) -> [Fp; N] {
+        core::array::from_fn(|_| self.draw_felt())
+    }
+
     /// Draw multiple random query indices from the channel (test only).
     #[cfg(test)]
     pub fn draw_queries(&mut self, count: usize, domain_size: usize) -> alloc::vec::Vec<usize> {
         let mut indices = alloc::vec::Vec::with_capacity(count);
 
         while indices.len() < count {
-            let raw = self.draw_felt();
-            let raw_u256 = raw.to_u256();
-            let mask = U256::from((domain_size - 1) as u64);
-            let index = (raw_u256 & mask).as_limbs()[0] as usize;
+            let index = query_index(self.draw_felt().to_u256(), domain_size);
 
             if !indices.contains(&index) {
                 indices.push(index);
@@ -64,10 +136,7 @@ impl Channel {
         let mut written = 0;
 
         while written < count {
-            let raw = self.draw_felt();
-            let raw_u256 = raw.to_u256();
-            let mask = U256::from((domain_size - 1) as u64);
-            let index = (raw_u256 & mask).as_limbs()[0] as usize;
+            let index = query_index(self.draw_felt().to_u256(), domain_size);
 
             let mut unique = true;
             for i in 0..written {
@@ -163,6 +232,73 @@ mod tests {
         assert_eq!(v1, v2);
     }
 
+    #[test]
+    fn test_poseidon_transcript_diverges_from_keccak() {
+        let seed = Fp::from_u256(U256::from(42u64));
+
+        let mut keccak = Channel::new(seed);
+        let mut poseidon = Channel::<PoseidonTranscript>::with_transcript(seed);
+        keccak.commit(Fp::ONE);
+        poseidon.commit(Fp::ONE);
+
+        assert_ne!(keccak.draw_felt(), poseidon.draw_felt());
+        assert_eq!(
+            poseidon.state(),
+            poseidon_hash_two(seed, Fp::ONE),
+        );
+    }
+
+    #[test]
+    fn test_transcript_kind_tags() {
+        assert_eq!(TranscriptKind::from_tag(0), Some(TranscriptKind::Keccak));
+        assert_eq!(TranscriptKind::from_tag(1), Some(TranscriptKind::Poseidon));
+        assert_eq!(TranscriptKind::from_tag(2), None);
+    }
+
+    #[test]
+    fn test_draw_alphas_matches_sequential_draws() {
+        fn check<H: TranscriptHash>() {
+            let mut ch1 = Channel::<H>::with_transcript(Fp::from_u256(U256::from(15u64)));
+            let mut ch2 = Channel::<H>::with_transcript(Fp::from_u256(U256::from(15u64)));
+            ch1.commit(Fp::from_u256(U256::from(0xabcdu64)));
+            ch2.commit(Fp::from_u256(U256::from(0xabcdu64)));
+
+            let bulk: [Fp; 9] = ch1.draw_alphas();
+            let sequential: [Fp; 9] = core::array::from_fn(|_| ch2.draw_felt());
+            assert_eq!(bulk, sequential);
+
+            // The channel advances exactly as far as the sequential draws
+            assert_eq!(ch1.draw_felt(), ch2.draw_felt());
+            let large: [Fp; 40] = ch1.draw_alphas();
+            assert_eq!(large[39], (0..40).map(|_| ch2.draw_felt()).last().unwrap());
+        }
+        check::<KeccakTranscript>();
+        check::<PoseidonTranscript>();
+    }
+
+    /// The prover's `draw_queries` and `draw_queries_into` agree index for
+    /// index from the same transcript, duplicates redrawn alike.
+    #[test]
+    fn test_draw_queries_into_matches_prover() {
+        use stark_prover::channel::Channel as ProverChannel;
+
+        fn check<H: TranscriptHash, P: stark_prover::channel::TranscriptHash>() {
+            let mut prover = ProverChannel::<P>::with_transcript(U256::from(7u64));
+            prover.commit(U256::from(0xc0ffeeu64));
+            let expected = prover.draw_queries(20, 64);
+
+            let mut verifier = Channel::<H>::with_transcript(Fp::from_u256(U256::from(7u64)));
+            verifier.commit(Fp::from_u256(U256::from(0xc0ffeeu64)));
+            let mut output = [0usize; 20];
+            assert_eq!(verifier.draw_queries_into(&mut output, 20, 64), 20);
+
+            assert_eq!(output[..], expected[..]);
+            assert_eq!(verifier.state().to_u256(), prover.state());
+        }
+        check::<KeccakTranscript, stark_prover::channel::KeccakTranscript>();
+        check::<PoseidonTranscript, stark_prover::channel::PoseidonTranscript>();
+    }
+
     #[test]
     fn test_draw_queries_into() {
         let mut ch = Channel::new(Fp::from_u256(U256::from(42u64)));
@@ -0,0 +1,135 @@
+//! DEEP Composition Check
+//!
+//! FRI runs over the DEEP polynomial
+//!
+//!   D(x) = Σ_j γ_j        (t_j(x) - t_j(z))  / (x - z)
+//!        + Σ_j γ_{W+j}    (t_j(x) - t_j(zg)) / (x - zg)
+//!        + Σ_i γ_{2W+i}   (S_i(x) - S_i(z))  / (x - z)
+//!
+//! over the W trace columns (7 for Sharpe, 8 for Sortino), where S_i are the
+//! composition segments (at least two; see each AIR's `MIN_SEGMENTS`). At
+//! each query the prover opens the trace row and every segment value; the
+//! verifier checks all Merkle paths and that D(x) recomputed from them
+//! equals the FRI layer-0 value. This binds the OOD evaluations to the
+//! committed trace and composition.
+
+use alloc::vec::Vec;
+
+use crate::field::{BN254Field, Fp};
+use crate::merkle::{hash_row, MerkleVerifier};
+
+use super::domain;
+use super::proof::{QueryEvals, StarkProof, MAX_COMPOSITION_SEGMENTS};
+
+/// Widest trace any AIR commits (Sortino).
+pub const MAX_TRACE_COLUMNS: usize = 8;
+
+/// DEEP coefficients spent on a `num_columns`-wide trace (each column at z and zg).
+pub const fn num_trace_deep_coeffs(num_columns: usize) -> usize {
+    2 * num_columns
+}
+
+/// Upper bound on DEEP coefficients (trace + every composition segment).
+pub const MAX_DEEP_COEFFS: usize = num_trace_deep_coeffs(MAX_TRACE_COLUMNS) + MAX_COMPOSITION_SEGMENTS;
+
+/// Values opened per query: every trace column + one per composition segment.
+pub fn opening_values_per_query(num_columns: usize, num_segments: usize) -> usize {
+    num_columns + num_segments
+}
+
+/// Out-of-domain point and the evaluations claimed there.
+pub struct DeepPoint<const W: usize> {
+    pub z: Fp,
+    pub zg: Fp,
+    pub trace_at_z: [Fp; W],
+    pub trace_at_zg: [Fp; W],
+    /// S_i(z) for each composition segment.
+    pub composition_at_z: Vec<Fp>,
+}
+
+impl<const W: usize> DeepPoint<W> {
+    /// Evaluate D(x) from the trace row and composition segment values at x.
+    pub fn evaluate(
+        &self,
+        x: Fp,
+        trace_at_x: &[Fp],
+        composition_at_x: &[Fp],
+        gammas: &[Fp],
+    ) -> Fp {
+        let mut at_z = Fp::ZERO;
+        for (i, (&s_x, &s_z)) in composition_at_x.iter().zip(&self.composition_at_z).enumerate() {
+            let ds = BN254Field::sub(s_x, s_z);
+            at_z = BN254Field::add(at_z, BN254Field::mul(gammas[num_trace_deep_coeffs(W) + i], ds));
+        }
+        let mut at_zg = Fp::ZERO;
+        for j in 0..W {
+            let dz = BN254Field::sub(trace_at_x[j], self.trace_at_z[j]);
+            let dzg = BN254Field::sub(trace_at_x[j], self.trace_at_zg[j]);
+            at_z = BN254Field::add(at_z, BN254Field::mul(gammas[j], dz));
+            at_zg = BN254Field::add(at_zg, BN254Field::mul(gammas[W + j], dzg));
+        }
+        BN254Field::add(
+            BN254Field::div(at_z, BN254Field::sub(x, self.z)),
+            BN254Field::div(at_zg, BN254Field::sub(x, self.zg)),
+        )
+    }
+}
+
+/// Verify every query's trace/composition opening against its DEEP value.
+///
+/// Per query, `queries.openings` holds [t_0..t_{W-1}, S_0..S_{k-1}] at the
+/// query index and `proof.query_opening_paths` holds [trace path, S_0 path, ...].
+pub fn verify_query_openings<const W: usize>(
+    proof: &StarkProof<W>,
+    queries: &QueryEvals,
+    point: &DeepPoint<W>,
+    gammas: &[Fp],
+) -> bool {
+    let log_domain_size = proof.log_trace_len as usize + 2;
+    let lde_gen = domain::domain_generator(log_domain_size as u32);
+    let fri_values_per_query = proof.num_fri_layers * 2;
+    let num_segments = proof.composition_commitments.len();
+    let values_per_query = opening_values_per_query(W, num_segments);
+    let paths_per_query = (1 + num_segments) * log_domain_size;
+
+    for (q, &query_idx) in proof.query_indices.iter().enumerate() {
+        let opened = &queries.openings[q * values_per_query..(q + 1) * values_per_query];
+        let (trace_row, composition_at_x) = (&opened[..W], &opened[W..]);
+
+        let path_start = q * paths_per_query;
+        let trace_path = &proof.query_opening_paths[path_start..path_start + log_domain_size];
+
+        let mut indices_buf = [false; 32];
+        for (k, bit) in indices_buf.iter_mut().take(log_domain_size).enumerate() {
+            *bit = ((query_idx >> k) & 1) == 1;
+        }
+        let indices = &indices_buf[..log_domain_size];
+
+        if !MerkleVerifier::verify(
+            proof.trace_commitment,
+            hash_row(trace_row, proof.leaf_salt),
+            trace_path,
+            indices,
+        ) {
+            return false;
+        }
+        for (i, (&root, &value)) in proof.composition_commitments.iter()
+            .zip(composition_at_x)
+            .enumerate()
+        {
+            let start = path_start + (1 + i) * log_domain_size;
+            let segment_path = &proof.query_opening_paths[start..start + log_domain_size];
+            if !MerkleVerifier::verify_value(root, value, proof.leaf_salt, segment_path, indices) {
+                return false;
+            }
+        }
+
+        let x = domain::evaluate_at(lde_gen, query_idx as u64);
+        let deep_at_x = point.evaluate(x, trace_row, composition_at_x, gammas);
+        if deep_at_x != queries.values[q * fri_values_per_query] {
+            return false;
+        }
+    }
+
+    true
+}
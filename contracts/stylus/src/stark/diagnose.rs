@@ -0,0 +1,178 @@
+//! Composition Check Diagnosis (off-chain)
+//!
+//! The most common rejection is the composition check at z: C(z) from the
+//! AIR disagrees with the recombined segments S_i(z). `verify_sharpe_stark`
+//! only returns false there. `diagnose_sharpe_composition` reruns steps 1-6,
+//! reports the z and alphas the verifier derived, and repeats the check at
+//! the prover's own challenges to tell an AIR bug from a Fiat-Shamir
+//! divergence.
+//!
+//! Built for tests and with the `diagnostics` feature; never part of the
+//! contract.
+
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+
+use crate::field::{BN254Field, Fp};
+use super::channel::{Channel, KeccakTranscript, PoseidonTranscript, ProofKind, TranscriptHash, TranscriptKind};
+use super::{
+    parse_sharpe_proof, public_input_count_ok, recombine_segments, sharpe_air, sharpe_composition_with_alphas,
+    SharpeStarkProof,
+};
+
+/// Outcome of the composition check, as a stable code for support tooling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CompositionDiagnosis {
+    /// C(z) matches the segments at the verifier's challenges.
+    Consistent = 0,
+    /// The proof does not parse, or z landed on the trace domain.
+    Malformed = 1,
+    /// Mismatch at the prover's challenges too: the prover's constraint
+    /// evaluation disagrees with the verifier's AIR.
+    AirMismatch = 2,
+    /// Holds at the prover's challenges but not at the verifier's: the two
+    /// transcripts diverged before z or the alphas were drawn.
+    TranscriptMismatch = 3,
+}
+
+/// OOD point and composition coefficients of a Sharpe proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SharpeChallenges {
+    pub z: Fp,
+    pub alphas: [Fp; sharpe_air::NUM_ALPHAS],
+}
+
+/// Diagnosis plus the challenges the verifier derived (`None` if the proof
+/// did not parse).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompositionReport {
+    pub diagnosis: CompositionDiagnosis,
+    pub verifier: Option<SharpeChallenges>,
+}
+
+/// Diagnose the composition check of a Sharpe proof given as the
+/// `verify_sharpe_stark` arrays. `prover` holds the z and alphas the prover
+/// drew, used only when the verifier's own challenges fail the check.
+#[allow(clippy::too_many_arguments)]
+pub fn diagnose_sharpe_composition(
+    public_inputs: &[U256],
+    commitments: &[U256],
+    ood_values: &[U256],
+    fri_final_poly: &[U256],
+    query_values: &[U256],
+    query_paths: &[U256],
+    query_metadata: &[U256],
+    prover: &SharpeChallenges,
+) -> CompositionReport {
+    let malformed = CompositionReport { diagnosis: CompositionDiagnosis::Malformed, verifier: None };
+    if !public_input_count_ok(public_inputs.len()) {
+        return malformed;
+    }
+    let Some(proof) = parse_sharpe_proof(commitments, ood_values, fri_final_poly, query_values, query_paths, query_metadata)
+    else {
+        return malformed;
+    };
+    let pub_fp: Vec<Fp> = public_inputs.iter().map(|v| Fp::from_u256(*v)).collect();
+
+    let verifier = match proof.transcript {
+        TranscriptKind::Keccak => derive_challenges::<KeccakTranscript>(&proof, &pub_fp),
+        TranscriptKind::Poseidon => derive_challenges::<PoseidonTranscript>(&proof, &pub_fp),
+    };
+    let diagnosis = match composition_holds(&proof, &pub_fp, &verifier) {
+        None => CompositionDiagnosis::Malformed,
+        Some(true) => CompositionDiagnosis::Consistent,
+        Some(false) if composition_holds(&proof, &pub_fp, prover) == Some(true) => {
+            CompositionDiagnosis::TranscriptMismatch
+        }
+        Some(false) => CompositionDiagnosis::AirMismatch,
+    };
+    CompositionReport { diagnosis, verifier: Some(verifier) }
+}
+
+/// Steps 1-5 of `verify_parsed_proof`: z, then the Sharpe alphas.
+fn derive_challenges<H: TranscriptHash>(proof: &SharpeStarkProof, public_inputs: &[Fp]) -> SharpeChallenges {
+    let mut channel = Channel::<H>::with_public_inputs(ProofKind::Sharpe, public_inputs);
+    if let Some(salt) = proof.leaf_salt {
+        channel.commit(salt);
+    }
+    channel.commit(proof.trace_commitment);
+    let z = channel.draw_felt();
+    SharpeChallenges { z, alphas: channel.draw_alphas() }
+}
+
+/// Step 6 at the given challenges; `None` when z is on the trace domain.
+fn composition_holds(proof: &SharpeStarkProof, public_inputs: &[Fp], challenges: &SharpeChallenges) -> Option<bool> {
+    let z_n = BN254Field::pow(challenges.z, U256::from(1u64 << proof.log_trace_len));
+    if z_n == Fp::ONE {
+        return None;
+    }
+    let composition_at_z = sharpe_composition_with_alphas(proof, public_inputs, challenges.z, &challenges.alphas);
+    Some(composition_at_z == recombine_segments(&proof.composition_ood_evals, z_n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bot_a_parts() -> [Vec<U256>; 7] {
+        let bot = stark_prover::mock_data::bot_a_aggressive_eth();
+        let p = stark_prover::prove_sharpe(&bot.trades, U256::from(bot.expected_sharpe_sq_scaled), 4, None);
+        [p.public_inputs, p.commitments, p.ood_values, p.fri_final_poly, p.query_values, p.query_paths, p.query_metadata]
+    }
+
+    fn diagnose(p: &[Vec<U256>; 7], prover: &SharpeChallenges) -> CompositionReport {
+        diagnose_sharpe_composition(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6], prover)
+    }
+
+    /// The challenges the honest prover drew: those the verifier derives
+    /// from the untampered proof.
+    fn prover_challenges(p: &[Vec<U256>; 7]) -> SharpeChallenges {
+        let unused = SharpeChallenges { z: Fp::ZERO, alphas: [Fp::ZERO; sharpe_air::NUM_ALPHAS] };
+        let report = diagnose(p, &unused);
+        assert_eq!(report.diagnosis, CompositionDiagnosis::Consistent);
+        report.verifier.unwrap()
+    }
+
+    #[test]
+    fn test_diagnose_air_mismatch() {
+        let p = bot_a_parts();
+        let prover = prover_challenges(&p);
+
+        // t_0(z) is absorbed only after the alphas, so both sides still draw
+        // the same challenges and C(z) fails at either
+        let mut bad = p.clone();
+        bad[2][0] = U256::from(12345u64);
+        assert!(!crate::stark::verify_sharpe_stark(&bad[0], &bad[1], &bad[2], &bad[3], &bad[4], &bad[5], &bad[6]));
+        let report = diagnose(&bad, &prover);
+        assert_eq!(report.diagnosis, CompositionDiagnosis::AirMismatch);
+        assert_eq!(report.diagnosis as u8, 2);
+        assert_eq!(report.verifier, Some(prover));
+    }
+
+    #[test]
+    fn test_diagnose_transcript_mismatch() {
+        let p = bot_a_parts();
+        let prover = prover_challenges(&p);
+
+        // An as_of_block the prover never absorbed: the verifier's seed, and
+        // so its z and alphas, diverge while the proof is AIR-consistent
+        let mut bad = p.clone();
+        bad[0].push(U256::from(1_000u64));
+        let report = diagnose(&bad, &prover);
+        assert_eq!(report.diagnosis, CompositionDiagnosis::TranscriptMismatch);
+        assert_eq!(report.diagnosis as u8, 3);
+        let verifier = report.verifier.unwrap();
+        assert_ne!(verifier.z, prover.z);
+        assert_ne!(verifier.alphas, prover.alphas);
+    }
+
+    #[test]
+    fn test_diagnose_malformed() {
+        let p = bot_a_parts();
+        let prover = prover_challenges(&p);
+        let mut bad = p.clone();
+        bad[6].clear();
+        assert_eq!(diagnose(&bad, &prover).diagnosis, CompositionDiagnosis::Malformed);
+    }
+}
@@ -1,25 +1,14 @@
 //! Evaluation Domain for FRI Protocol
 //!
-//! Provides roots of unity for the BN254 scalar field.
-//! BN254 scalar field order: p - 1 = 2^28 × 3^2 × 13 × 29 × ...
-//! This gives us a multiplicative subgroup of order 2^28.
+//! Provides roots of unity for the BN254 scalar field. The 2^28 generator,
+//! two-adicity and supported blowups come from `stark_core::domain`.
 
 use alloy_primitives::U256;
 
 use crate::field::Fp;
 use crate::field::BN254Field;
 
-/// Generator of the 2^28 multiplicative subgroup of BN254 scalar field (Montgomery form).
-pub const GENERATOR_2_28: Fp = Fp::from_raw([
-    0x636e735580d13d9c,
-    0xa22bf3742445ffd6,
-    0x56452ac01eb203d8,
-    0x1860ef942963f9e7,
-]);
-
-/// TWO_ADICITY: The largest k such that 2^k divides (p-1).
-/// For BN254 scalar field, this is 28.
-pub const TWO_ADICITY: u32 = 28;
+pub use stark_core::domain::{log_blowup, GENERATOR_2_28, TWO_ADICITY};
 
 /// Get the generator of a 2^k-sized domain.
 pub fn domain_generator(log_size: u32) -> Fp {
@@ -34,29 +23,24 @@ pub fn evaluate_at(gen: Fp, index: u64) -> Fp {
     BN254Field::pow(gen, U256::from(index))
 }
 
+/// Compute the transition constraint zerofier at OOD point z, shared by
+/// every AIR whose transitions hold on all rows but the last.
+/// Z_T(z) = (z^n - 1) / (z - g^{n-1})
+pub fn transition_zerofier_at(z: Fp, trace_len: u64, trace_generator: Fp) -> Fp {
+    let z_n = BN254Field::pow(z, U256::from(trace_len));
+    let numerator = BN254Field::sub(z_n, Fp::ONE);
+
+    let g_last = BN254Field::pow(trace_generator, U256::from(trace_len - 1));
+    let denominator = BN254Field::sub(z, g_last);
+
+    BN254Field::div(numerator, denominator)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::field::BN254_PRIME;
 
-    #[test]
-    fn test_generator_2_28_is_root_of_unity() {
-        let mut val = GENERATOR_2_28;
-        for _ in 0..28 {
-            val = BN254Field::mul(val, val);
-        }
-        assert_eq!(val, Fp::ONE, "g^(2^28) != 1");
-    }
-
-    #[test]
-    fn test_generator_2_28_is_primitive() {
-        let mut val = GENERATOR_2_28;
-        for _ in 0..27 {
-            val = BN254Field::mul(val, val);
-        }
-        assert_ne!(val, Fp::ONE, "g^(2^27) == 1, not primitive");
-    }
-
     #[test]
     fn test_domain_generator_k() {
         let g4 = domain_generator(4);
@@ -1,10 +1,14 @@
 //! FRI (Fast Reed-Solomon Interactive Oracle Proof) Verifier
 
+use alloc::{vec, vec::Vec};
+use alloy_primitives::U256;
+
 use crate::field::Fp;
 use crate::field::BN254Field;
 use crate::merkle::MerkleVerifier;
-use super::channel::Channel;
+use super::channel::{Channel, TranscriptHash};
 use super::domain;
+use stark_core::fri::{qv_index, query_values_len, LayerValue};
 
 /// Precomputed inverse of 2 in BN254 scalar field (Montgomery form).
 const INV_TWO: Fp = Fp::from_raw([
@@ -20,23 +24,31 @@ pub struct FriParams {
     pub num_layers: usize,
     pub num_queries: usize,
     pub blowup_factor: u32,
+    /// Per-proof Merkle leaf salt; `None` for unsalted proofs.
+    pub leaf_salt: Option<Fp>,
 }
 
 impl FriParams {
-    pub fn new(log_trace_len: u32, num_layers: usize, num_queries: usize, blowup_factor: u32) -> Self {
-        let log_blowup = match blowup_factor {
-            2 => 1,
-            4 => 2,
-            8 => 3,
-            16 => 4,
-            _ => 2,
-        };
-        FriParams {
-            log_domain_size: log_trace_len + log_blowup,
+    /// `None` for a blowup factor `domain::log_blowup` does not support, or
+    /// for `num_layers` folding the domain below size 2.
+    pub fn new(log_trace_len: u32, num_layers: usize, num_queries: usize, blowup_factor: u32) -> Option<Self> {
+        let log_blowup = domain::log_blowup(blowup_factor)?;
+        let log_domain_size = log_trace_len + log_blowup;
+        if num_layers >= log_domain_size as usize {
+            return None;
+        }
+        Some(FriParams {
+            log_domain_size,
             num_layers,
             num_queries,
             blowup_factor,
-        }
+            leaf_salt: None,
+        })
+    }
+
+    pub fn with_leaf_salt(mut self, leaf_salt: Option<Fp>) -> Self {
+        self.leaf_salt = leaf_salt;
+        self
     }
 }
 
@@ -66,18 +78,170 @@ pub fn evaluate_polynomial(coeffs: &[Fp], x: Fp) -> Fp {
     result
 }
 
-/// Verify FRI proof.
-pub fn verify_fri(
-    channel: &mut Channel,
+/// One query's FRI decommitment.
+///
+/// `layer_values[l]` is (f(x), f(-x)) on layer l and `layer_paths[l]` the Merkle
+/// path of f(x), `log_domain_size - l` nodes long.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryOpening {
+    pub index: usize,
+    pub layer_values: Vec<(Fp, Fp)>,
+    pub layer_paths: Vec<Vec<U256>>,
+}
+
+impl QueryOpening {
+    /// Split the flat `query_values` / `query_paths` calldata arrays into one
+    /// opening per entry of `query_indices`.
+    ///
+    /// `query_values` is laid out by `stark_core::fri::qv_index` and, per
+    /// query, `query_paths` holds the layer paths back to back. `None` unless both arrays
+    /// have exactly the expected length.
+    pub fn from_flat(
+        query_values: &[Fp],
+        query_paths: &[U256],
+        query_indices: &[usize],
+        num_layers: usize,
+        log_domain_size: u32,
+    ) -> Option<Vec<Self>> {
+        if num_layers >= log_domain_size as usize {
+            return None;
+        }
+        let depths = (0..num_layers).map(|layer| log_domain_size as usize - layer);
+        let path_elements_per_query: usize = depths.clone().sum();
+        let num_queries = query_indices.len();
+        if query_values.len() != query_values_len(num_queries, num_layers)
+            || query_paths.len() != num_queries * path_elements_per_query
+        {
+            return None;
+        }
+
+        let mut path_cursor = 0;
+        let mut openings = Vec::with_capacity(num_queries);
+        for (query, &index) in query_indices.iter().enumerate() {
+            let mut opening = QueryOpening {
+                index,
+                layer_values: Vec::with_capacity(num_layers),
+                layer_paths: Vec::with_capacity(num_layers),
+            };
+            for (layer, depth) in depths.clone().enumerate() {
+                let value = |which| query_values.get(qv_index(num_layers, query, layer, which)).copied();
+                opening.layer_values.push((value(LayerValue::X)?, value(LayerValue::NegX)?));
+                opening.layer_paths.push(query_paths[path_cursor..path_cursor + depth].to_vec());
+                path_cursor += depth;
+            }
+            openings.push(opening);
+        }
+        Some(openings)
+    }
+
+    /// Flatten openings back into (`query_values`, `query_paths`, `query_indices`).
+    pub fn to_flat(openings: &[Self]) -> (Vec<Fp>, Vec<U256>, Vec<usize>) {
+        let num_layers = openings.first().map_or(0, |o| o.layer_values.len());
+        let mut query_values = vec![Fp::ZERO; query_values_len(openings.len(), num_layers)];
+        let mut query_paths = Vec::new();
+        let mut query_indices = Vec::with_capacity(openings.len());
+        for (query, opening) in openings.iter().enumerate() {
+            query_indices.push(opening.index);
+            for (layer, &(fx, f_neg_x)) in opening.layer_values.iter().enumerate() {
+                query_values[qv_index(num_layers, query, layer, LayerValue::X)] = fx;
+                query_values[qv_index(num_layers, query, layer, LayerValue::NegX)] = f_neg_x;
+            }
+            for path in &opening.layer_paths {
+                query_paths.extend_from_slice(path);
+            }
+        }
+        (query_values, query_paths, query_indices)
+    }
+}
+
+/// Verify FRI proof given as the flat calldata arrays.
+pub fn verify_fri<H: TranscriptHash>(
+    channel: &mut Channel<H>,
     layer_commitments: &[Fp],
     query_values: &[Fp],
-    query_auth_paths: &[Fp],
+    query_auth_paths: &[U256],
     query_indices: &[usize],
     final_poly_coeffs: &[Fp],
     params: &FriParams,
+) -> bool {
+    let Some(openings) = QueryOpening::from_flat(
+        query_values,
+        query_auth_paths,
+        query_indices,
+        params.num_layers,
+        params.log_domain_size,
+    ) else {
+        return false;
+    };
+    verify_fri_openings(channel, layer_commitments, &openings, final_poly_coeffs, params)
+}
+
+/// Verify FRI proof given as per-query openings.
+pub fn verify_fri_openings<H: TranscriptHash>(
+    channel: &mut Channel<H>,
+    layer_commitments: &[Fp],
+    openings: &[QueryOpening],
+    final_poly_coeffs: &[Fp],
+    params: &FriParams,
+) -> bool {
+    verify_fri_layers(channel, layer_commitments, openings, FinalLayer::Coefficients(final_poly_coeffs), params)
+}
+
+/// One query's opening of a Merkle-committed final layer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinalLayerOpening {
+    /// Final-layer evaluation at the query's final-domain index.
+    pub value: Fp,
+    /// Merkle path of `value`, `log_domain_size - num_layers` nodes long.
+    pub path: Vec<U256>,
+}
+
+/// How the last FRI layer reaches the verifier.
+#[derive(Clone, Copy)]
+enum FinalLayer<'a> {
+    /// Coefficients in the clear, each absorbed into the transcript.
+    Coefficients(&'a [Fp]),
+    /// Merkle root over the final-domain evaluations plus one opening per query.
+    Committed { root: Fp, openings: &'a [FinalLayerOpening] },
+}
+
+/// Verify FRI proof whose final layer is Merkle-committed rather than sent
+/// in the clear (the prover's `fri_commit_final_tree`).
+///
+/// The root is absorbed where the coefficients would be, so it binds the
+/// query indices, and each query's last fold must match its authenticated
+/// final-layer value. Calldata scales with the number of queries instead of
+/// the final degree. Tradeoff: no degree check on the final layer, so an
+/// entry no query lands on is never constrained.
+pub fn verify_fri_committed_final<H: TranscriptHash>(
+    channel: &mut Channel<H>,
+    layer_commitments: &[Fp],
+    openings: &[QueryOpening],
+    final_root: Fp,
+    final_openings: &[FinalLayerOpening],
+    params: &FriParams,
+) -> bool {
+    if final_openings.len() != openings.len() {
+        return false;
+    }
+    let final_layer = FinalLayer::Committed { root: final_root, openings: final_openings };
+    verify_fri_layers(channel, layer_commitments, openings, final_layer, params)
+}
+
+fn verify_fri_layers<H: TranscriptHash>(
+    channel: &mut Channel<H>,
+    layer_commitments: &[Fp],
+    openings: &[QueryOpening],
+    final_layer: FinalLayer,
+    params: &FriParams,
 ) -> bool {
     let num_layers = params.num_layers;
     let num_queries = params.num_queries;
+    // FriParams is plain data: recheck the layer bound before the domain
+    // arithmetic below relies on it
+    if openings.len() != num_queries || num_layers > 32 || num_layers >= params.log_domain_size as usize {
+        return false;
+    }
 
     let mut alphas = [Fp::ZERO; 32];
     for i in 0..num_layers {
@@ -85,8 +249,13 @@ pub fn verify_fri(
         alphas[i] = channel.draw_felt();
     }
 
-    for coeff in final_poly_coeffs {
-        channel.commit(*coeff);
+    match final_layer {
+        FinalLayer::Coefficients(coeffs) => {
+            for coeff in coeffs {
+                channel.commit(*coeff);
+            }
+        }
+        FinalLayer::Committed { root, .. } => channel.commit(root),
     }
 
     let lde_domain_size = 1usize << params.log_domain_size;
@@ -96,16 +265,11 @@ pub fn verify_fri(
         return false;
     }
     for i in 0..num_queries {
-        if derived_indices[i] != query_indices[i] {
+        if derived_indices[i] != openings[i].index {
             return false;
         }
     }
 
-    let mut path_elements_per_query = 0usize;
-    for layer in 0..num_layers {
-        path_elements_per_query += (params.log_domain_size - layer as u32) as usize;
-    }
-
     let mut layer_generators = [Fp::ZERO; 32];
     for layer in 0..num_layers {
         let layer_log_domain = params.log_domain_size - layer as u32;
@@ -114,14 +278,11 @@ pub fn verify_fri(
     let final_log_domain = params.log_domain_size - num_layers as u32;
     let final_gen = domain::domain_generator(final_log_domain);
 
-    let values_per_query = num_layers * 2;
-
-    for q in 0..num_queries {
-        let mut query_idx = query_indices[q];
-        let value_offset = q * values_per_query;
-        let query_path_start = q * path_elements_per_query;
-        let mut path_cursor = query_path_start;
-
+    for (q, opening) in openings.iter().enumerate() {
+        if opening.layer_values.len() != num_layers || opening.layer_paths.len() != num_layers {
+            return false;
+        }
+        let mut query_idx = opening.index;
         let mut last_folded = Fp::ZERO;
 
         for layer in 0..num_layers {
@@ -130,33 +291,32 @@ pub fn verify_fri(
             let half_domain = (layer_domain_size / 2) as usize;
             let depth = layer_log_domain as usize;
 
-            let pair_offset = value_offset + layer * 2;
-            let fx = query_values[pair_offset];
-            let f_neg_x = query_values[pair_offset + 1];
-
-            let path_slice = &query_auth_paths[path_cursor..path_cursor + depth];
+            let (fx, f_neg_x) = opening.layer_values[layer];
+            let path_slice = &opening.layer_paths[layer];
+            if path_slice.len() != depth {
+                return false;
+            }
 
             let mut indices_buf = [false; 32];
             for k in 0..depth {
                 indices_buf[k] = ((query_idx >> k) & 1) == 1;
             }
 
-            if !MerkleVerifier::verify(
+            if !MerkleVerifier::verify_value(
                 layer_commitments[layer],
                 fx,
+                params.leaf_salt,
                 path_slice,
                 &indices_buf[..depth],
             ) {
                 return false;
             }
 
-            path_cursor += depth;
-
             let x = domain::evaluate_at(layer_generators[layer], query_idx as u64);
             let folded = fri_fold(fx, f_neg_x, alphas[layer], x);
 
             if layer < num_layers - 1 {
-                let next_fx = query_values[value_offset + (layer + 1) * 2];
+                let next_fx = opening.layer_values[layer + 1].0;
                 if folded != next_fx {
                     return false;
                 }
@@ -167,8 +327,35 @@ pub fn verify_fri(
             query_idx = query_idx % half_domain;
         }
 
-        let final_x = domain::evaluate_at(final_gen, query_idx as u64);
-        let expected = evaluate_polynomial(final_poly_coeffs, final_x);
+        let expected = match final_layer {
+            // Folded down to a constant: every query must land on it
+            FinalLayer::Coefficients(&[constant]) => constant,
+            FinalLayer::Coefficients(coeffs) => {
+                let final_x = domain::evaluate_at(final_gen, query_idx as u64);
+                evaluate_polynomial(coeffs, final_x)
+            }
+            FinalLayer::Committed { root, openings: final_openings } => {
+                let final_opening = &final_openings[q];
+                let depth = final_log_domain as usize;
+                if final_opening.path.len() != depth {
+                    return false;
+                }
+                let mut indices_buf = [false; 32];
+                for (k, bit) in indices_buf[..depth].iter_mut().enumerate() {
+                    *bit = ((query_idx >> k) & 1) == 1;
+                }
+                if !MerkleVerifier::verify_value(
+                    root,
+                    final_opening.value,
+                    params.leaf_salt,
+                    &final_opening.path,
+                    &indices_buf[..depth],
+                ) {
+                    return false;
+                }
+                final_opening.value
+            }
+        };
 
         if last_folded != expected {
             return false;
@@ -178,10 +365,462 @@ pub fn verify_fri(
     true
 }
 
+/// One query's openings of the batched input polynomials (see
+/// `verify_fri_batched`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchedInputOpening {
+    /// (f_i(x), f_i(-x)) for each input polynomial i.
+    pub values: Vec<(Fp, Fp)>,
+    /// Merkle paths of f_i(x) and f_i(-x), `log_domain_size` nodes each.
+    pub paths: Vec<(Vec<U256>, Vec<U256>)>,
+}
+
+/// Verify one FRI instance over the random linear combination Σ_i λ^i · f_i
+/// of several committed polynomials.
+///
+/// Absorbs the input roots and draws λ before the usual FRI transcript
+/// (mirroring the prover's `fri_commit_batched`), runs `verify_fri_openings`,
+/// then checks that each query's first-layer pair (f(x), f(-x)) equals the
+/// combination of the inputs opened at x and -x against their roots.
+pub fn verify_fri_batched<H: TranscriptHash>(
+    channel: &mut Channel<H>,
+    input_commitments: &[Fp],
+    input_openings: &[BatchedInputOpening],
+    layer_commitments: &[Fp],
+    openings: &[QueryOpening],
+    final_poly_coeffs: &[Fp],
+    params: &FriParams,
+) -> bool {
+    if input_commitments.is_empty() || params.num_layers == 0 || input_openings.len() != openings.len() {
+        return false;
+    }
+
+    for root in input_commitments {
+        channel.commit(*root);
+    }
+    let lambda = channel.draw_felt();
+
+    // Also checks every opening's index against the transcript
+    if !verify_fri_openings(channel, layer_commitments, openings, final_poly_coeffs, params) {
+        return false;
+    }
+
+    let depth = params.log_domain_size as usize;
+    let half = 1usize << (depth - 1);
+    for (opening, inputs) in openings.iter().zip(input_openings) {
+        if inputs.values.len() != input_commitments.len() || inputs.paths.len() != input_commitments.len() {
+            return false;
+        }
+
+        let mut x_bits = [false; 32];
+        let mut neg_x_bits = [false; 32];
+        let neg_index = opening.index ^ half;
+        for k in 0..depth {
+            x_bits[k] = ((opening.index >> k) & 1) == 1;
+            neg_x_bits[k] = ((neg_index >> k) & 1) == 1;
+        }
+
+        let mut combined = (Fp::ZERO, Fp::ZERO);
+        for ((&root, &(fx, f_neg_x)), (x_path, neg_x_path)) in input_commitments
+            .iter()
+            .zip(&inputs.values)
+            .zip(&inputs.paths)
+            .rev()
+        {
+            if !MerkleVerifier::verify_value(root, fx, params.leaf_salt, x_path, &x_bits[..depth])
+                || !MerkleVerifier::verify_value(root, f_neg_x, params.leaf_salt, neg_x_path, &neg_x_bits[..depth])
+            {
+                return false;
+            }
+            // Horner in λ: f = f_0 + λ(f_1 + λ(f_2 + ...))
+            combined.0 = BN254Field::add(BN254Field::mul(combined.0, lambda), fx);
+            combined.1 = BN254Field::add(BN254Field::mul(combined.1, lambda), f_neg_x);
+        }
+
+        if combined != opening.layer_values[0] {
+            return false;
+        }
+    }
+
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use alloy_primitives::U256;
+    use crate::stark::channel::KeccakTranscript;
+
+    fn fp(hex: &str) -> Fp {
+        Fp::from_u256(U256::from_str_radix(hex, 16).unwrap())
+    }
+
+    struct BatchedFixture {
+        input_roots: Vec<Fp>,
+        input_openings: Vec<BatchedInputOpening>,
+        layer_roots: Vec<Fp>,
+        openings: Vec<QueryOpening>,
+        final_poly: Vec<Fp>,
+    }
+
+    /// Batched FRI over f_0 = 3 + x + 4x^2 + x^3 and f_1 = 5 + 9x + 2x^2 + 6x^3
+    /// on a 16-point domain: 2 layers, 2 queries, seed 7. Generated with the
+    /// prover's `fri_commit_batched` and `fri_batched_input_openings`.
+    fn batched_fixture() -> BatchedFixture {
+        let input_roots = [
+            "0ee3f72a944ceaa5f8f2f1728f84e189f3673666d0df2cf9a6809534fd2a63fd",
+            "0c123798bb94372d22b8039886e5ce373384c059944ef18162b1914a8c5157a8",
+        ];
+        let layer_roots = [
+            "0b63c153e63edfc6475cf66fd28a38f204a6e1e877e751e78861acb906210400",
+            "1fc0afe4273805ef1100e146da028f040ae09f16286ca2dc364538f8a1e06786",
+        ];
+        let final_poly = [
+            "09c050de3abaa2ce18a1da245937bb9c912d2bd3c9012039b61806be687d5510",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        ];
+        let query_values = [
+            "0dae778b982d9a5114f40df4c099fc90ad734f737c4fe48c67375e825a9060a2",
+            "049de8c968c3633bd86ca59ac3b5b5f4dc9789f0b6901e62407f4bda90a562c7",
+            "0e8fafa1fcb3cb77cdbecb2f9e899c180801b92189b3f46bb2b6808c792e13f1",
+            "28cdff73a786705c21dbc749aa0c4a8729396dd427dd1793cf9237e0ca297020",
+            "02924df3d73176cd1b4ce01ae3226e1819e2920ec2e8c9feb71b293a1e6fcc90",
+            "07dd093094dfc35fe909e9ba5096a236b81423aab7fb9c77f84dc0916662fb77",
+            "28cdff73a786705c21dbc749aa0c4a8729396dd427dd1793cf9237e0ca297020",
+            "0e8fafa1fcb3cb77cdbecb2f9e899c180801b92189b3f46bb2b6808c792e13f1",
+        ];
+        let query_paths = [
+            "276e8996015e642a1c12e5b744d038277f77454981fc54f72c24c6cad8537ec4",
+            "183e3d98800d3d92f17123409adca56d397799bb3d20ff4e420bced21db73897",
+            "3014be15a2cd2fce100e4506b9664bffdce8cbf216dcf26d17853d30af9584df",
+            "01648d97b4ba760a6d70d6195df417c4c410ed38ff930c99b6f0750220d30a0c",
+            "1f183fa9f4e144a4747bb52faf4f5f2669572236b1b351f90ea46c10d131f84f",
+            "0502703a7d229375bf81f3a6659f56502b1d81fbbeb99bc2b98e65cafac0db8d",
+            "15200f04416106cc608c2ccd6f383a525382de8211238e55355458800f7eff33",
+            "1f4c134e617cf0c4b9bad76154d8a46010c9a2cf3227665ec483e30c55ac8a3e",
+            "04ab2c9b98953cc81e0b9b52d806f9f51b3634593f8c538354f4068434b0e613",
+            "2ab330dc53c7006729c3ee3965530cc761ec66a28f6ba3156ff2d32157800421",
+            "15f39330161df094e82445aa2fcefd1573bd120fa2a75f34604f1fee0d6c9b8f",
+            "18456f6baf58f72f7b1edd4999468778c7e404beffddba0673a44c5c72258bc2",
+            "1f960f6ed2f83eb3689d8740cc69ce6a2c007bcfdba4fed4615fcad12f8262ff",
+            "0256ad065b18413946c93d057358ce315102f2b916145eb6f8d3b53eed71172f",
+        ];
+        let input_values = [
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000",
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000",
+            "00000000000000021b4e86d7c4fb460a61dd49f474d20def626fd36a21af5d64",
+            "30644e72e131a0279d01bedebc861252c6569e5404e762a1e1722229ce50a2a3",
+            "0000000000000000000000000000000000000000000000000000000000000009",
+            "0000000000000000000000000000000000000000000000000000000000000005",
+            "0000000000000000000000000000000000000000000000000000000000000016",
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593effffff9",
+        ];
+        let input_paths = [
+            "1fb294d63ddabdf2038b44a22176f8f9a785de98f6069b90357458e33fea2157",
+            "13907c3a89ab3a29a7dcd9164e41f81e8a6dbe92fab157f3f1d5249b5b3230d6",
+            "08e864b609ad02838c9ee3f74cf921cb9f5bdcefcd921d64bede8068cae96e67",
+            "2e007a99b19225df99eb5db6a4938db04178242383f3716ef8eae2ef1a4aa360",
+            "09d3efb285a7ca445a19f7953514503d0705c873170c98a5452de966322aeab7",
+            "1db16a43e413a5b3b800e922114180082de42ad84ae4983e7b425d26474b8d25",
+            "14905a71e56ecec6486b819a373bbc3e5c06fa229e077072fe74be654d295e1f",
+            "05b0cc67d0f30bf29a7051dd9bde5990c0cb699a3d9692b849c17a79b0408955",
+            "14af146aed22ae88253c301c8af8210a8b3b646e874793883930f0cfdffe336f",
+            "135fedd2d1b4de217d01bc9a29cb6334b43ad0ae9f122ffd6c1d702e507ba657",
+            "2c24d514975b84efad128ef84a681626fc1bcf9cf7123792abece0003f1d4d28",
+            "0651d0733b3b923137e54bfcd0c062d7916f4aeacb715c2cd1402776d9bcf640",
+            "00142dd9749e959309966dff204d8390cc0a6f177f42069284203054d90c529f",
+            "2deb1c76cfa4d15b4c20a0b0ab019c29bfab173999b39694f43eb60fd1d889f0",
+            "0a8ba1ab2cb3a2b66fa183ad591e1efe338fa26c2281bc1eb55f2379291ceabe",
+            "27b7ee5de08b58cdcc16ad24f153bbb0ce5ac364e850ca19d1af7d36f044054c",
+            "1cbf95a79b64e32cc83883d5e1238f7d1c0445b8c464e35c2a80e2fd74ff504d",
+            "1b5743852a00f04aa35bdeb57f9e2f426c878e71ee154436b3d48084ffc68f6a",
+            "03c602d32328990a1738fe1e99b8e0699c61ff7b2b62faa6cbce96fd76fcfc2b",
+            "05b0cc67d0f30bf29a7051dd9bde5990c0cb699a3d9692b849c17a79b0408955",
+            "1a8282b5637bd4f04ac2cb5fcb53d80685d7e3cc21fac990e2a0c5e0f8eba3b3",
+            "1fb2c050f9e4442598c7c61d3333959953de71b93b93aa011b88c4bd335bbdc8",
+            "302e701b11764ea0e66400948915615ad9d94b696a7336b2da0583faa7076beb",
+            "2e007a99b19225df99eb5db6a4938db04178242383f3716ef8eae2ef1a4aa360",
+            "2f09cf27da0c26687df2826ea8e2ed5ebaf8475db02d028d4854ee83d0c894f1",
+            "14ca792a24f0feccadd06bc073e9517697067838fd91996ba4448a26eb83f215",
+            "1ae44c52427231f70d84faa5eaecf860c70c7d3750be117cdd482449e4c628e5",
+            "27b7ee5de08b58cdcc16ad24f153bbb0ce5ac364e850ca19d1af7d36f044054c",
+            "1cfb8b798695d5cfc3db6ae2aeda1ec03e29b5ad3cbc447a821ddb7f562ce517",
+            "165d2578c7d20e1ffbb419fe8eee3b560665ce8cf18aeec63f7afff1dfef526a",
+            "0458397fa3e1f2ffb6a1fe1cc01a54a50617795bbbd4396c129be278cf498d1f",
+            "0651d0733b3b923137e54bfcd0c062d7916f4aeacb715c2cd1402776d9bcf640",
+        ];
+        let query_indices = [12, 0];
+
+        let fps = |hexes: &[&str]| hexes.iter().map(|h| fp(h)).collect::<Vec<Fp>>();
+        let words = |hexes: &[&str]| hexes.iter().map(|h| U256::from_str_radix(h, 16).unwrap()).collect::<Vec<U256>>();
+        let openings =
+            QueryOpening::from_flat(&fps(&query_values), &words(&query_paths), &query_indices, 2, 4).unwrap();
+
+        // Per query and input: f_i(x), f_i(-x), then their two 4-node paths
+        let values = fps(&input_values);
+        let paths = words(&input_paths);
+        let input_openings = (0..2)
+            .map(|q| BatchedInputOpening {
+                values: (0..2).map(|i| (values[4 * q + 2 * i], values[4 * q + 2 * i + 1])).collect(),
+                paths: (0..2)
+                    .map(|i| {
+                        let start = 16 * q + 8 * i;
+                        (paths[start..start + 4].to_vec(), paths[start + 4..start + 8].to_vec())
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        BatchedFixture {
+            input_roots: fps(&input_roots),
+            input_openings,
+            layer_roots: fps(&layer_roots),
+            openings,
+            final_poly: fps(&final_poly),
+        }
+    }
+
+    #[test]
+    fn test_verify_fri_batched() {
+        let BatchedFixture { input_roots, input_openings, layer_roots, openings, final_poly } = batched_fixture();
+        let params = FriParams::new(2, 2, 2, 4).unwrap();
+        let verify = |input_openings: &[BatchedInputOpening], openings: &[QueryOpening]| {
+            let mut channel = Channel::<KeccakTranscript>::with_transcript(Fp::from_u256(U256::from(7u64)));
+            verify_fri_batched(
+                &mut channel, &input_roots, input_openings, &layer_roots, openings, &final_poly, &params,
+            )
+        };
+        assert!(verify(&input_openings, &openings));
+
+        // Corrupting f_1's contribution at x or -x breaks its Merkle opening
+        for neg in [false, true] {
+            let mut bad = input_openings.clone();
+            let pair = &mut bad[1].values[1];
+            let v = if neg { &mut pair.1 } else { &mut pair.0 };
+            *v = BN254Field::add(*v, Fp::ONE);
+            assert!(!verify(&bad, &openings));
+        }
+
+        // Dropping an input changes λ and the combination
+        let mut fewer = input_openings.clone();
+        for o in fewer.iter_mut() {
+            o.values.pop();
+            o.paths.pop();
+        }
+        let mut channel = Channel::<KeccakTranscript>::with_transcript(Fp::from_u256(U256::from(7u64)));
+        assert!(!verify_fri_batched(
+            &mut channel, &input_roots[..1], &fewer, &layer_roots, &openings, &final_poly, &params,
+        ));
+
+        // Openings for a different query set do not line up
+        assert!(!verify(&input_openings[..1], &openings));
+    }
+
+    /// Committed-final FRI over f = 3 + x + 4x^2 + x^3 on a 16-point domain:
+    /// 1 layer, 2 queries, seed 7, so the final layer has 8 evaluations.
+    /// Generated with the prover's `fri_commit_final_tree`; `tampered` bumps
+    /// final entry 1 before committing, and its queries land on entries 4 and 3.
+    fn final_tree_fixture(tampered: bool) -> (Vec<Fp>, Vec<QueryOpening>, Fp, Vec<FinalLayerOpening>) {
+        let layer_roots = ["0ee3f72a944ceaa5f8f2f1728f84e189f3673666d0df2cf9a6809534fd2a63fd"];
+        let (final_root, query_indices, query_values, query_paths, final_values, final_paths) = if tampered {
+            (
+                "11204bd44d1c56ab955af03af0d8f9822824225bbb66f5f3445b22dcce87ac79",
+                [12, 3],
+                [
+                    "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000",
+                    "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000",
+                    "040ebf473410cf8b41e3e31aa1d0cc010f11b7588a26ea0de12475102917dfd1",
+                    "2529c1f794f37e33c586dc4ddc73a6028e35745f5185dd82d5d493e7591d4b61",
+                ],
+                [
+                    "1fb294d63ddabdf2038b44a22176f8f9a785de98f6069b90357458e33fea2157",
+                    "13907c3a89ab3a29a7dcd9164e41f81e8a6dbe92fab157f3f1d5249b5b3230d6",
+                    "08e864b609ad02838c9ee3f74cf921cb9f5bdcefcd921d64bede8068cae96e67",
+                    "2e007a99b19225df99eb5db6a4938db04178242383f3716ef8eae2ef1a4aa360",
+                    "182866d0314908c3224d74cc5f1373e504ca5773c597d81f863c0f1237c4dcbc",
+                    "2c619538aad485a6cc071a9e789377eba30d1e204301e8148a42f4aece0ce307",
+                    "03c602d32328990a1738fe1e99b8e0699c61ff7b2b62faa6cbce96fd76fcfc2b",
+                    "05b0cc67d0f30bf29a7051dd9bde5990c0cb699a3d9692b849c17a79b0408955",
+                ],
+                [
+                    "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000",
+                    "28197e58eee62f8f1df79f2b93d3b303bc7c0a7027eb4d88d9107c033d17241d",
+                ],
+                [
+                    "27e67b34ec2d2f2dd30f80cd3ae239ad0f2a664f9ea44b3f94862c11fdee42ea",
+                    "125835506711cdfb40c8b2ab6271351ea332ba3bc3491792e28698d25eeef099",
+                    "0ac9d8d2a87afeee6f5c5071116a487791307ad66e347a47d5fbbf60c3ae13e6",
+                    "0508a62a288039919a64696a50f5ac87dfb8c39a09a72c41b15155a92660e379",
+                    "0c7d22e804a2911f2d87daf6c2b3da973b256a7505af88c21ef2d6a1c05eb709",
+                    "2de007940af88d87754c1fcacc1bac6526b133de1ea327470e6229ffd6740f98",
+                ],
+            )
+        } else {
+            (
+                "1d8695a7e8847e23efb4e8ffd4c1da584da7d91793255b89ab04fc41345c36f5",
+                [6, 4],
+                [
+                    "182866d0314908c8c07431b66c5c2eab5f1871ffa7c7fd488cbb982d3c432b14",
+                    "183be7a2afe897669602d0ea226de478236990d4b4219871bda5e681b83b234b",
+                    "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000",
+                    "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000",
+                ],
+                [
+                    "0b109fe31f6b51f307e933a56bc5c58e2025acf83f05a455ff8d558d399d7d62",
+                    "233142a9032936b0906af243c2ede1166e29ebd1dba677fe444ff0998cd5b05a",
+                    "14905a71e56ecec6486b819a373bbc3e5c06fa229e077072fe74be654d295e1f",
+                    "05b0cc67d0f30bf29a7051dd9bde5990c0cb699a3d9692b849c17a79b0408955",
+                    "09d3efb285a7ca445a19f7953514503d0705c873170c98a5452de966322aeab7",
+                    "1db16a43e413a5b3b800e922114180082de42ad84ae4983e7b425d26474b8d25",
+                    "14905a71e56ecec6486b819a373bbc3e5c06fa229e077072fe74be654d295e1f",
+                    "05b0cc67d0f30bf29a7051dd9bde5990c0cb699a3d9692b849c17a79b0408955",
+                ],
+                [
+                    "08be5d83be52d3daba6a465155f77e840ade608df6038cd6ad04f08c42a1112e",
+                    "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000",
+                ],
+                [
+                    "1611d3c7d91e7e06ef275646949ad065564f02005178dc20c927bfc61bead08b",
+                    "0626e2bc07dcbac89af396e4939b48a61143355bcd792a89de11b16361e04e97",
+                    "08400215070d9589b0f19d88c1dd25182f8601d587f2b4c7ac477dcfd52ac76e",
+                    "27e67b34ec2d2f2dd30f80cd3ae239ad0f2a664f9ea44b3f94862c11fdee42ea",
+                    "125835506711cdfb40c8b2ab6271351ea332ba3bc3491792e28698d25eeef099",
+                    "08400215070d9589b0f19d88c1dd25182f8601d587f2b4c7ac477dcfd52ac76e",
+                ],
+            )
+        };
+
+        let fps = |hexes: &[&str]| hexes.iter().map(|h| fp(h)).collect::<Vec<Fp>>();
+        let words = |hexes: &[&str]| hexes.iter().map(|h| U256::from_str_radix(h, 16).unwrap()).collect::<Vec<U256>>();
+        let openings =
+            QueryOpening::from_flat(&fps(&query_values), &words(&query_paths), &query_indices, 1, 4).unwrap();
+        let paths = words(&final_paths);
+        let final_openings = fps(&final_values)
+            .into_iter()
+            .zip(paths.chunks_exact(3))
+            .map(|(value, path)| FinalLayerOpening { value, path: path.to_vec() })
+            .collect();
+        (fps(&layer_roots), openings, fp(final_root), final_openings)
+    }
+
+    #[test]
+    fn test_verify_fri_committed_final() {
+        let params = FriParams::new(2, 1, 2, 4).unwrap();
+        let verify = |layer_roots: &[Fp], openings: &[QueryOpening], root: Fp, finals: &[FinalLayerOpening]| {
+            let mut channel = Channel::<KeccakTranscript>::with_transcript(Fp::from_u256(U256::from(7u64)));
+            verify_fri_committed_final(&mut channel, layer_roots, openings, root, finals, &params)
+        };
+
+        let (layer_roots, openings, final_root, final_openings) = final_tree_fixture(false);
+        assert!(verify(&layer_roots, &openings, final_root, &final_openings));
+
+        // Tampering an opened final value breaks its Merkle opening
+        let mut bad = final_openings.clone();
+        bad[1].value = BN254Field::add(bad[1].value, Fp::ONE);
+        assert!(!verify(&layer_roots, &openings, final_root, &bad));
+
+        // The root is transcript-bound: a different root derives other queries
+        let other_root = BN254Field::add(final_root, Fp::ONE);
+        assert!(!verify(&layer_roots, &openings, other_root, &final_openings));
+
+        // The committed final layer is not sent in the clear
+        let clear_final: Vec<Fp> = final_openings.iter().map(|o| o.value).collect();
+        let mut channel = Channel::<KeccakTranscript>::with_transcript(Fp::from_u256(U256::from(7u64)));
+        assert!(!verify_fri_openings(&mut channel, &layer_roots, &openings, &clear_final, &params));
+
+        // Documented tradeoff: a final layer with an unopened entry tampered
+        // (no longer low degree) still verifies when no query lands on it.
+        let (layer_roots, openings, final_root, final_openings) = final_tree_fixture(true);
+        assert!(openings.iter().all(|o| o.index % 8 != 1));
+        assert!(verify(&layer_roots, &openings, final_root, &final_openings));
+    }
+
+    /// Maximal folding of 64 evaluations with 4 layers and a single final
+    /// coefficient, proved with the prover's `fri_commit_constant_final`.
+    /// `cheat` starts from degree < 32, so the final layer is linear, and
+    /// sends its first entry as the "constant" with an otherwise honest
+    /// transcript.
+    fn constant_final_fixture(cheat: bool) -> (Vec<Fp>, Vec<QueryOpening>, Fp) {
+        use stark_prover::channel::Channel as ProverChannel;
+        use stark_prover::fri as pfri;
+
+        let mut evals = vec![U256::ZERO; 64];
+        for (i, c) in evals.iter_mut().take(if cheat { 32 } else { 16 }).enumerate() {
+            *c = U256::from(3 * i as u64 + 7);
+        }
+        stark_prover::domain::fft(&mut evals, 6);
+
+        let mut channel = ProverChannel::new(U256::from(7u64));
+        let (commitment, constant) = if cheat {
+            let committed = pfri::fri_commit_final_tree(&evals, &mut channel, 6, 4, None);
+            // Replay the layer transcript, then absorb the claimed constant
+            let mut channel_replay = ProverChannel::new(U256::from(7u64));
+            for layer in &committed.fri.layers {
+                channel_replay.commit(layer.tree.root());
+                channel_replay.draw_felt();
+            }
+            let constant = committed.final_evaluations[0];
+            channel_replay.commit(constant);
+            channel = channel_replay;
+            (committed.fri, constant)
+        } else {
+            let commitment = pfri::fri_commit_constant_final(&evals, &mut channel, 6, 4, None).unwrap();
+            let constant = commitment.final_poly[0];
+            (commitment, constant)
+        };
+        let query_indices = channel.draw_queries(4, 64);
+        let (values, paths, indices) = pfri::QueryOpening::to_flat(&pfri::fri_query_openings(&commitment, &query_indices));
+
+        let values: Vec<Fp> = values.iter().map(|v| Fp::from_u256(*v)).collect();
+        let openings = QueryOpening::from_flat(&values, &paths, &indices, 4, 6).unwrap();
+        let layer_roots = commitment.layers.iter().map(|l| Fp::from_u256(l.tree.root())).collect();
+        (layer_roots, openings, Fp::from_u256(constant))
+    }
+
+    #[test]
+    fn test_verify_fri_constant_final() {
+        let params = FriParams::new(4, 4, 4, 4).unwrap();
+        let verify = |layer_roots: &[Fp], openings: &[QueryOpening], final_poly: &[Fp]| {
+            let mut channel = Channel::<KeccakTranscript>::with_transcript(Fp::from_u256(U256::from(7u64)));
+            verify_fri_openings(&mut channel, layer_roots, openings, final_poly, &params)
+        };
+
+        let (layer_roots, openings, constant) = constant_final_fixture(false);
+        assert!(verify(&layer_roots, &openings, &[constant]));
+
+        // Final layer is linear on 4 points: only queries landing on entry 0
+        // fold to the claimed constant
+        let (layer_roots, openings, constant) = constant_final_fixture(true);
+        assert!(openings.iter().any(|o| o.index % 4 != 0));
+        assert!(!verify(&layer_roots, &openings, &[constant]));
+    }
+
+    #[test]
+    fn test_fri_params_blowup() {
+        for (blowup, log) in [(2, 1), (4, 2), (8, 3), (16, 4)] {
+            let params = FriParams::new(10, 3, 4, blowup).unwrap();
+            assert_eq!(params.log_domain_size, 10 + log);
+        }
+        // Unsupported factors are rejected instead of defaulting to 4
+        assert!(FriParams::new(10, 3, 4, 32).is_none());
+        assert!(FriParams::new(10, 3, 4, 3).is_none());
+    }
+
+    #[test]
+    fn test_fri_params_layer_bound() {
+        // log_domain_size 4: up to 3 layers, leaving a final domain of size 2
+        assert_eq!(FriParams::new(2, 3, 2, 4).map(|p| p.log_domain_size), Some(4));
+        assert!(FriParams::new(2, 4, 2, 4).is_none());
+        assert!(FriParams::new(2, 5, 2, 4).is_none());
+
+        // Hand-built params past the bound are rejected, not underflowed
+        let (layer_roots, openings, final_root, final_openings) = final_tree_fixture(false);
+        let params = FriParams { num_layers: 4, ..FriParams::new(2, 1, 2, 4).unwrap() };
+        let mut channel = Channel::<KeccakTranscript>::with_transcript(Fp::from_u256(U256::from(7u64)));
+        assert!(!verify_fri_committed_final(&mut channel, &layer_roots, &openings, final_root, &final_openings, &params));
+    }
 
     #[test]
     fn test_inv_two_constant() {
@@ -257,4 +896,16 @@ mod tests {
             Fp::from_u256(U256::from(7u64))
         );
     }
+
+    #[test]
+    fn test_inv_two_limb_order() {
+        // Canonical 1/2 = (p + 1) / 2; raw limbs are its Montgomery form R/2 mod p
+        let p = crate::field::BN254_PRIME;
+        let half = (p + U256::from(1u64)) >> 1;
+        assert_eq!(INV_TWO.to_u256(), half);
+        let r = (U256::MAX % p + U256::from(1u64)) % p;
+        assert_eq!(U256::from_limbs(INV_TWO.0), r.mul_mod(half, p));
+        // Both forms are reduced, as `Fp::from_raw` requires
+        assert!(U256::from_limbs(INV_TWO.0) < p && INV_TWO.to_u256() < p);
+    }
 }
@@ -0,0 +1,126 @@
+//! Analytical gas estimate for STARK verification.
+//!
+//! Lets a frontend predict verification cost from a proof's shape alone,
+//! without an `eth_estimateGas` round trip. The model counts the dominant
+//! per-component costs of `verify_stark` and is calibrated so the benchmark
+//! proofs land on their measured figures (Bot A: ~1.25M gas with 15 trades
+//! and 4 queries, Bot B: ~1.45M with 23 trades and 4 queries).
+//!
+//! The estimate assumes the default proof layout: blowup 4, two composition
+//! segments, no leaf salt. It is a preflight figure, not a gas limit.
+
+use super::BLOWUP_FACTOR;
+
+/// Composition segments in a default Sharpe proof.
+const DEFAULT_NUM_SEGMENTS: u64 = 2;
+
+/// Intrinsic transaction cost plus the shape-independent work: channel setup,
+/// domain generators, and the AIR constraint evaluation at the OOD point.
+const BASE_GAS: u64 = 225_000;
+
+/// Calldata cost of one 32-byte word, assuming every byte is nonzero.
+const CALLDATA_WORD_GAS: u64 = 32 * 16;
+
+/// One Merkle path node: a keccak over the pair plus the sibling decode.
+const MERKLE_NODE_GAS: u64 = 2_000;
+
+/// One field inversion (Fermat exponentiation, ~380 Montgomery products).
+const INVERSION_GAS: u64 = 21_000;
+
+/// One square-and-multiply step when raising a domain generator to a query index.
+const POW_STEP_GAS: u64 = 250;
+
+/// Per query and trace column: hashing the value into the leaf and its DEEP term.
+const COLUMN_GAS: u64 = 3_000;
+
+/// Estimated gas for verifying a proof of the given shape.
+///
+/// Per query, each FRI layer costs one fold inversion, a domain point
+/// exponentiation and a Merkle path one level shorter than the previous
+/// layer's; the trace and segment openings add full-depth paths, two DEEP
+/// inversions and the per-column work. Binding `pi[3]` to the dataset column
+/// hashes one constant-leaf tree of the trace's depth. Calldata is priced
+/// from the same layout the prover's `estimate_calldata_size` counts.
+///
+/// Saturates instead of overflowing, so nonsensical shapes return a huge
+/// estimate rather than panicking.
+pub fn estimate_verify_gas(
+    log_trace_len: u64,
+    num_queries: u64,
+    num_fri_layers: u64,
+    num_columns: u64,
+) -> u64 {
+    let depth = log_trace_len.saturating_add(BLOWUP_FACTOR.trailing_zeros() as u64);
+
+    // Path lengths shrink by one per folded layer: depth + (depth - 1) + ...
+    let layers = num_fri_layers.min(depth) as u128;
+    let fri_path_nodes = layers * depth as u128 - layers * layers.saturating_sub(1) / 2;
+    let fri_path_nodes = u64::try_from(fri_path_nodes).unwrap_or(u64::MAX);
+    let opening_path_nodes = (1 + DEFAULT_NUM_SEGMENTS).saturating_mul(depth);
+
+    let per_query = [
+        (fri_path_nodes.saturating_add(opening_path_nodes), MERKLE_NODE_GAS),
+        (num_fri_layers.saturating_add(2), INVERSION_GAS),
+        (num_fri_layers.saturating_mul(depth), POW_STEP_GAS),
+        (num_columns, COLUMN_GAS),
+    ]
+    .iter()
+    .fold(0u64, |acc, &(count, cost)| acc.saturating_add(count.saturating_mul(cost)));
+
+    let calldata_words = calldata_words(depth, num_queries, num_fri_layers, num_columns)
+        .saturating_add(num_queries.saturating_mul(fri_path_nodes.saturating_add(opening_path_nodes)));
+
+    let dataset_root = log_trace_len.saturating_add(1).saturating_mul(MERKLE_NODE_GAS);
+
+    BASE_GAS
+        .saturating_add(dataset_root)
+        .saturating_add(num_queries.saturating_mul(per_query))
+        .saturating_add(calldata_words.saturating_mul(CALLDATA_WORD_GAS))
+}
+
+/// Calldata words outside the Merkle paths: public inputs, commitments, OOD
+/// values, final polynomial, query values and metadata.
+fn calldata_words(depth: u64, num_queries: u64, num_fri_layers: u64, num_columns: u64) -> u64 {
+    let public_inputs = 4;
+    let commitments = (1 + DEFAULT_NUM_SEGMENTS).saturating_add(num_fri_layers);
+    let ood_values = num_columns.saturating_mul(2).saturating_add(DEFAULT_NUM_SEGMENTS);
+    let final_poly = u32::try_from(depth.saturating_sub(num_fri_layers))
+        .ok()
+        .and_then(|log_len| 1u64.checked_shl(log_len))
+        .unwrap_or(u64::MAX);
+    let query_values = num_queries.saturating_mul(
+        num_fri_layers.saturating_mul(2).saturating_add(num_columns).saturating_add(DEFAULT_NUM_SEGMENTS),
+    );
+    let metadata = 4u64.saturating_add(num_queries);
+
+    [commitments, ood_values, final_poly, query_values, metadata]
+        .iter()
+        .fold(public_inputs, |acc, &words| acc.saturating_add(words))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_bot_b() {
+        // README benchmark: 23 trades → 32-row trace, 5 FRI layers, 4 queries, ~1.45M gas
+        let estimate = estimate_verify_gas(5, 4, 5, 7);
+        assert!((1_350_000..=1_550_000).contains(&estimate), "Bot B estimate {}", estimate);
+    }
+
+    #[test]
+    fn test_estimate_grows_with_shape() {
+        let base = estimate_verify_gas(4, 4, 4, 7);
+        assert!(estimate_verify_gas(5, 4, 4, 7) > base);
+        assert!(estimate_verify_gas(4, 8, 4, 7) > base);
+        assert!(estimate_verify_gas(4, 4, 5, 7) > base);
+        assert!(estimate_verify_gas(4, 4, 4, 8) > base);
+    }
+
+    #[test]
+    fn test_estimate_saturates() {
+        assert_eq!(estimate_verify_gas(u64::MAX, u64::MAX, u64::MAX, u64::MAX), u64::MAX);
+        assert_eq!(estimate_verify_gas(4, u64::MAX, 4, 7), u64::MAX);
+    }
+}
@@ -1,10 +1,12 @@
 //! Full STARK Verifier
 //!
 //! Integrates all components (AIR, FRI, Channel, Domain) to verify
-//! STARK proofs of Sharpe ratio computation.
+//! STARK proofs of Sharpe (or Sortino) ratio computation. Both AIRs share
+//! the pipeline below and differ only in steps 4-5.
 //!
 //! Verification pipeline:
-//! 1. Initialize Fiat-Shamir channel with public inputs
+//! 1. Initialize Fiat-Shamir channel with the public inputs, framed by
+//!    proof kind and count
 //! 2. Commit trace polynomial Merkle root
 //! 3. Draw OOD evaluation point z
 //! 4. Verify AIR constraints at OOD point
@@ -12,23 +14,31 @@
 //! 6. Verify FRI proof on composition polynomial
 //! 7. Verify query consistency via Merkle paths
 
+pub mod air;
 pub mod channel;
+pub mod deep;
+#[cfg(any(test, feature = "diagnostics"))]
+pub mod diagnose;
 pub mod domain;
 pub mod fri;
+pub mod gas;
 pub mod proof;
+pub mod self_test;
 pub mod sharpe_air;
+pub mod sortino_air;
 
 use alloy_primitives::U256;
 
 use crate::field::Fp;
-use crate::keccak_hash_two;
 use crate::field::BN254Field;
+use crate::field::field_to_signed;
 
-use self::sharpe_air::transition_zerofier_at;
-use self::channel::Channel;
+use self::channel::{Channel, ProofKind, TranscriptHash};
+use self::deep::{num_trace_deep_coeffs, opening_values_per_query, verify_query_openings, DeepPoint, MAX_DEEP_COEFFS};
 use self::domain::domain_generator;
 use self::fri::verify_fri;
-use self::proof::{parse_sharpe_proof, SharpeStarkProof};
+use self::air::define_stark_air;
+use self::proof::{unpack_proof, QueryEvals, QueryMetadata, StarkProof};
 
 /// Default FRI blowup factor
 pub const BLOWUP_FACTOR: u32 = 4;
@@ -36,17 +46,152 @@ pub const BLOWUP_FACTOR: u32 = 4;
 /// Default number of FRI queries (provides ~80-bit security)
 pub const NUM_QUERIES: usize = 20;
 
-/// Verify a full STARK proof of Sharpe ratio verification.
+/// Version of the calldata layout this verifier parses.
+pub const PROOF_FORMAT_VERSION: u64 = 1;
+
+/// Hash of the protocol parameters this verifier was built with; proofs carry
+/// the prover's copy in `query_metadata[3]` and are rejected unless it matches
+/// (see `proof::check_protocol_hash`).
 ///
-/// # Arguments
-/// * `public_inputs` - [trade_count, total_return, sharpe_sq_scaled, merkle_root]
-/// * `commitments` - Merkle commitments [trace_root, comp_root, fri_roots...]
-/// * `ood_values` - OOD evaluations [6 trace at z, 6 trace at zg, comp(z)] = 13 values
-/// * `fri_final_poly` - Final low-degree polynomial coefficients
-/// * `query_values` - Query evaluation data (flattened)
-/// * `query_paths` - Merkle authentication paths (flattened)
-/// * `query_metadata` - [num_queries, num_fri_layers, log_trace_len, indices...]
-pub fn verify_sharpe_stark(
+/// keccak256 over 32-byte big-endian words: [PROOF_FORMAT_VERSION, BN254
+/// modulus, GENERATOR_2_28, BLOWUP_FACTOR, Sharpe (columns, transition,
+/// boundary constraints), Sortino (same three), keccak_hash_two(1, 2),
+/// keccak_hash_two_tagged(tag, 1, 2) for the Merkle node, channel commit and
+/// channel draw tags, poseidon_hash_two(1, 2)]. Same value as the prover's
+/// `PROTOCOL_HASH`.
+pub const VERIFIER_PROTOCOL_HASH: U256 = U256::from_limbs([
+    0xfefa75555e8d5969,
+    0xe7556243855adcdf,
+    0xf6787155d7229c6b,
+    0x46526ea6a38c6fad,
+]);
+
+/// Upper bound on a claimed `sharpe_sq_scaled` (Sharpe^2 * 10000).
+/// |Sharpe| < 100 covers every realistic strategy; anything larger is treated
+/// as a field-wraparound forgery of BC3.
+pub const MAX_SHARPE_SQ_SCALED: U256 = U256::from_limbs([100_000_000, 0, 0, 0]);
+
+/// Check that a claimed `sharpe_sq_scaled` lies within the sane range.
+pub fn sharpe_sq_in_range(sharpe_sq_scaled: U256) -> bool {
+    sharpe_sq_scaled < MAX_SHARPE_SQ_SCALED
+}
+
+/// Upper bound on a claimed `sortino_sq_scaled` (Sortino^2 * 10000), with
+/// the same |ratio| < 100 cut-off as `MAX_SHARPE_SQ_SCALED`.
+pub const MAX_SORTINO_SQ_SCALED: U256 = U256::from_limbs([100_000_000, 0, 0, 0]);
+
+/// Check that a claimed `sortino_sq_scaled` lies within the sane range.
+pub fn sortino_sq_in_range(sortino_sq_scaled: U256) -> bool {
+    sortino_sq_scaled < MAX_SORTINO_SQ_SCALED
+}
+
+/// Check that `total_return` is a signed return in the field embedding
+/// (`signed_to_field`): a net loss of 300 bps is `p - 300`, whereas the
+/// int256 two's complement `2^256 - 300` is rejected up front.
+pub fn total_return_in_range(total_return: U256) -> bool {
+    field_to_signed(total_return).is_some()
+}
+
+/// Public inputs of a Sharpe/Sortino proof: [trade_count, total_return,
+/// ratio_sq_scaled, merkle_root].
+pub const NUM_PUBLIC_INPUTS: usize = 4;
+
+/// Check the public input count exactly: `NUM_PUBLIC_INPUTS`, or one more
+/// when the proof is bound to an `as_of_block` (`pi[4]`). No other trailing
+/// inputs are accepted; every accepted input is absorbed into the
+/// Fiat-Shamir seed, so none is ignored.
+pub fn public_input_count_ok(len: usize) -> bool {
+    air_input_count_ok(len, NUM_PUBLIC_INPUTS)
+}
+
+/// `public_input_count_ok` for an AIR with `num_air_inputs` public inputs.
+fn air_input_count_ok(len: usize, num_air_inputs: usize) -> bool {
+    len == num_air_inputs || len == num_air_inputs + 1
+}
+
+define_stark_air! {
+    kind: ProofKind::Sharpe,
+    columns: sharpe_air::NUM_COLUMNS,
+    public_inputs: NUM_PUBLIC_INPUTS,
+    min_segments: sharpe_air::MIN_SEGMENTS,
+    alphas: sharpe_air::NUM_ALPHAS,
+    immutable_columns: sharpe_air::IMMUTABLE_COLUMNS,
+    dataset_column: sharpe_air::DATASET_COLUMN,
+    claim_in_range: sharpe_sq_in_range,
+    transition: sharpe_air::evaluate_transition_ood,
+    boundary: sharpe_air::evaluate_boundary_quotients,
+    combine: sharpe_air::combine_quotients,
+    /// Sharpe proof: 7 trace columns.
+    proof: SharpeStarkProof,
+    /// Parse a Sharpe STARK proof from ABI-compatible parameters.
+    /// Expects 14 + k OOD values: 7 trace at z + 7 trace at zg + k composition
+    /// segments at z, and commitments [trace, k segment roots, FRI layers...].
+    /// See `proof::parse_stark_proof` for the layout and `QueryMetadata` for
+    /// query_metadata.
+    ///
+    /// query_values / query_paths hold the FRI layer data for all queries, followed
+    /// by the per-query trace and composition openings (see `deep`).
+    parse: parse_sharpe_proof,
+    /// Sharpe constraint quotients at z combined under the given alphas.
+    composition_with_alphas: sharpe_composition_with_alphas,
+    /// Steps 3-5 for the Sharpe AIR: 11 alphas, then C(z).
+    composition_at_z: sharpe_composition_at_z,
+    /// Verify a Sharpe STARK proof and return the Fiat-Shamir transcript digest.
+    ///
+    /// The digest is the channel state once every challenge (z, alphas, DEEP
+    /// gammas, FRI betas, query indices) has been drawn. It depends only on the
+    /// public inputs and the absorbed commitments/OOD values, so two honest proofs
+    /// of the same statement share it regardless of query count. On rejection it
+    /// is the state reached before the failing check, or zero if the proof did not
+    /// parse.
+    verify_with_transcript: verify_sharpe_stark_with_transcript,
+    /// Verify a full STARK proof of Sharpe ratio verification.
+    ///
+    /// # Arguments
+    /// * `public_inputs` - [trade_count, total_return, sharpe_sq_scaled, merkle_root, as_of_block?],
+    ///   with `total_return` signed-embedded (see `total_return_in_range`)
+    /// * `commitments` - Merkle commitments [trace_root, comp_segment_roots..., fri_roots...]
+    /// * `ood_values` - OOD evaluations [7 trace at z, 7 trace at zg, comp_segments(z)...]
+    ///   (16 values for the minimum two segments)
+    /// * `fri_final_poly` - Final low-degree polynomial coefficients
+    /// * `query_values` - Query evaluation data (flattened)
+    /// * `query_paths` - Merkle authentication paths (flattened)
+    /// * `query_metadata` - [num_queries, num_fri_layers, log_trace_len, protocol_hash, indices..., leaf_salt?];
+    ///   bits 64..128 of the first element select the transcript hash (0 = Keccak, 1 = Poseidon)
+    ///   and bits 128..192 the composition segment count (at least 2); bits 64..128 of the
+    ///   second declare the trace width (7)
+    verify: verify_sharpe_stark,
+}
+
+/// Verify a Sharpe STARK proof given in the packed binary format (see
+/// `proof::unpack_proof`); same verdict as `verify_sharpe_stark` on the
+/// unpacked arrays.
+pub fn verify_sharpe_packed(proof: &[u8]) -> bool {
+    match unpack_proof(proof) {
+        Some([pi, commitments, ood, fri_final_poly, query_values, query_paths, query_metadata]) => {
+            verify_sharpe_stark(
+                &pi,
+                &commitments,
+                &ood,
+                &fri_final_poly,
+                &query_values,
+                &query_paths,
+                &query_metadata,
+            )
+        }
+        None => false,
+    }
+}
+
+/// Verify a Sharpe STARK proof and report the work its metadata declares:
+/// (verdict, num_queries, num_fri_layers).
+///
+/// A proof opening fewer than `min_queries` queries is rejected before any
+/// hashing, so a caller can enforce its own soundness policy in the same
+/// call. The counts are reported whenever the metadata decodes, also on
+/// rejection; both are zero otherwise.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_sharpe_stark_with_stats(
     public_inputs: &[U256],
     commitments: &[U256],
     ood_values: &[U256],
@@ -54,121 +199,178 @@ pub fn verify_sharpe_stark(
     query_values: &[U256],
     query_paths: &[U256],
     query_metadata: &[U256],
-) -> bool {
-    let proof = match parse_sharpe_proof(
+    min_queries: U256,
+) -> (bool, U256, U256) {
+    let Some(metadata) = QueryMetadata::from_words(query_metadata) else {
+        return (false, U256::ZERO, U256::ZERO);
+    };
+    let num_queries = U256::from(metadata.num_queries());
+    let num_fri_layers = U256::from(metadata.num_fri_layers);
+    if num_queries < min_queries {
+        return (false, num_queries, num_fri_layers);
+    }
+
+    let valid = verify_sharpe_stark(
+        public_inputs,
         commitments,
         ood_values,
         fri_final_poly,
         query_values,
         query_paths,
         query_metadata,
-    ) {
-        Some(p) => p,
-        None => return false,
-    };
+    );
+    (valid, num_queries, num_fri_layers)
+}
 
-    if public_inputs.len() < 4 {
-        return false;
-    }
+define_stark_air! {
+    kind: ProofKind::Sortino,
+    columns: sortino_air::NUM_COLUMNS,
+    public_inputs: NUM_PUBLIC_INPUTS,
+    min_segments: sortino_air::MIN_SEGMENTS,
+    alphas: sortino_air::NUM_ALPHAS,
+    immutable_columns: sortino_air::IMMUTABLE_COLUMNS,
+    dataset_column: sortino_air::DATASET_COLUMN,
+    claim_in_range: sortino_sq_in_range,
+    transition: sortino_air::evaluate_transition_ood,
+    boundary: sortino_air::evaluate_boundary_quotients,
+    combine: sortino_air::combine_quotients,
+    /// Sortino proof: 8 trace columns.
+    proof: SortinoStarkProof,
+    /// Parse a Sortino STARK proof; same layout as `parse_sharpe_proof` over
+    /// 8 trace columns.
+    parse: parse_sortino_proof,
+    /// Sortino constraint quotients at z combined under the given alphas.
+    composition_with_alphas: sortino_composition_with_alphas,
+    /// Steps 3-5 for the Sortino AIR: 12 alphas, then C(z).
+    composition_at_z: sortino_composition_at_z,
+    /// Verify a Sortino STARK proof and return the Fiat-Shamir transcript
+    /// digest; see `verify_sharpe_stark_with_transcript`.
+    verify_with_transcript: verify_sortino_stark_with_transcript,
+    /// Verify a full STARK proof of Sortino ratio verification.
+    ///
+    /// Same calldata layout as `verify_sharpe_stark` over the 8-column Sortino
+    /// trace: `public_inputs[2]` is the claimed `sortino_sq_scaled`, `ood_values`
+    /// holds [8 trace at z, 8 trace at zg, comp_segments(z)...] and each query
+    /// opens 8 trace values. The composition has degree < 3N, so at least three
+    /// segments are required.
+    verify: verify_sortino_stark,
+}
 
-    let pub_fp = [
-        Fp::from_u256(public_inputs[0]),
-        Fp::from_u256(public_inputs[1]),
-        Fp::from_u256(public_inputs[2]),
-        Fp::from_u256(public_inputs[3]),
-    ];
+/// Check that the claimed trade count n satisfies 1 <= n <= trace length, so
+/// the last trade row g^(n-1) lies in the trace domain.
+fn trade_count_fits(trade_count: U256, log_trace_len: u32) -> bool {
+    trade_count >= U256::from(1u64) && trade_count <= U256::from(1u64 << log_trace_len)
+}
 
-    verify_sharpe_parsed_proof(&proof, &pub_fp)
+/// Check that every immutable column opens to the same value at z and zg.
+///
+/// A constant polynomial takes one value everywhere, so this holds for every
+/// honest proof and costs no hashing. Same-row constraints such as TC1
+/// (`ret_sq = ret^2`) give no such check: they hold on the trace domain only,
+/// and at z their OOD values differ by the quotient times the zerofier.
+fn immutable_ood_consistent<const W: usize>(proof: &StarkProof<W>, columns: &[usize]) -> bool {
+    columns
+        .iter()
+        .all(|&c| proof.trace_ood_evals[c] == proof.trace_ood_evals_next[c])
+}
+
+/// g^(n-1): the trace domain point of the last trade row, n = trade_count.
+fn last_trade_point(trace_gen: Fp, trade_count: Fp) -> Fp {
+    BN254Field::pow(trace_gen, trade_count.to_u256() - U256::from(1u64))
 }
 
-/// Verify a parsed Sharpe STARK proof.
-fn verify_sharpe_parsed_proof(proof: &SharpeStarkProof, public_inputs: &[Fp; 4]) -> bool {
+/// C(z) = Σ_i S_i(z) · z^(i·N) from the segment evaluations S_i(z).
+fn recombine_segments(segments_at_z: &[Fp], z_n: Fp) -> Fp {
+    segments_at_z
+        .iter()
+        .rev()
+        .fold(Fp::ZERO, |acc, s| BN254Field::add(BN254Field::mul(acc, z_n), *s))
+}
+
+/// Verify a parsed STARK proof over a `W`-column trace, returning the final
+/// channel state. `H` must match the transcript hash the prover used (selected
+/// by `proof.transcript`) and `kind` the statement it proved; `composition_at_z`
+/// runs the AIR-specific steps 3-5 and draws that AIR's alphas.
+///
+/// Checks run cheapest first: FRI parameters before any hashing, and the
+/// composition-at-z equality (OOD values only) before the query values are
+/// converted and FRI runs. `dataset_column` is the AIR's dataset-commitment
+/// column, bound to `pi[3]` last.
+fn verify_parsed_proof<H: TranscriptHash, const W: usize>(
+    proof: &StarkProof<W>,
+    kind: ProofKind,
+    public_inputs: &[Fp],
+    dataset_column: usize,
+    composition_at_z: impl FnOnce(&StarkProof<W>, &[Fp], Fp, &mut Channel<H>) -> Fp,
+) -> (bool, Fp) {
     let log_trace_len = proof.log_trace_len;
     let trace_len = 1u64 << log_trace_len;
 
-    // Step 1: Initialize Fiat-Shamir channel
-    let mut seed = public_inputs[0];
-    for i in 1..public_inputs.len() {
-        seed = keccak_hash_two(seed, public_inputs[i]);
+    let fri_params = match fri::FriParams::new(
+        log_trace_len,
+        proof.num_fri_layers,
+        proof.query_indices.len(),
+        BLOWUP_FACTOR,
+    ) {
+        Some(params) => params.with_leaf_salt(proof.leaf_salt),
+        None => return (false, Fp::ZERO),
+    };
+    if proof.fri_layer_commitments.is_empty() {
+        return (false, Fp::ZERO);
     }
-    let mut channel = Channel::new(seed);
 
-    // Step 2: Commit trace and draw OOD point
+    // Step 1: Initialize Fiat-Shamir channel with the framed public inputs
+    let mut channel = Channel::<H>::with_public_inputs(kind, public_inputs);
+
+    // Step 2: Commit (optional leaf salt and) trace, then draw OOD point
+    if let Some(salt) = proof.leaf_salt {
+        channel.commit(salt);
+    }
     channel.commit(proof.trace_commitment);
     let z = channel.draw_felt();
-
-    // Step 3: Verify AIR constraints at OOD point z
     let trace_gen = domain_generator(log_trace_len);
 
-    let transition_evals = sharpe_air::evaluate_transition_ood(
-        proof.trace_ood_evals,
-        proof.trace_ood_evals_next,
-    );
-
-    let zerofier = transition_zerofier_at(z, trace_len, trace_gen);
-
-    // Compute 5 transition quotients
-    let mut tqs = [Fp::ZERO; 5];
-    for i in 0..5 {
-        tqs[i] = BN254Field::div(transition_evals[i], zerofier);
+    // The transition zerofier and the boundary denominators z - g^k vanish
+    // only on the trace domain, where z^N = 1. Drawing such a z is negligibly
+    // likely, but with N = 2 that domain is just {1, -1}: reject it outright
+    // rather than divide by zero.
+    let z_n = BN254Field::pow(z, U256::from(trace_len));
+    if z_n == Fp::ONE {
+        return (false, channel.state());
     }
 
-    // Step 4: Verify boundary constraints
-    let trace_domain_first = Fp::ONE;
-    let trace_domain_last = BN254Field::pow(trace_gen, U256::from(trace_len - 1));
+    // Steps 3-5: AIR constraints at z, alphas and the composition value
+    let composition_at_z = composition_at_z(proof, public_inputs, z, &mut channel);
 
-    let boundary_quotients = sharpe_air::evaluate_boundary_quotients(
-        proof.trace_ood_evals,
-        z,
-        trace_domain_first,
-        trace_domain_last,
-        *public_inputs,
-    );
-
-    // Step 5: Draw 9 alphas and compose
-    let mut alphas = [Fp::ZERO; 9];
-    for i in 0..9 {
-        alphas[i] = channel.draw_felt();
+    // Step 6: Recombine the composition segments
+    if composition_at_z != recombine_segments(&proof.composition_ood_evals, z_n) {
+        return (false, channel.state());
     }
 
-    let composition_at_z = {
-        let mut comp = Fp::ZERO;
-        // 5 transition quotients
-        for i in 0..5 {
-            comp = BN254Field::add(comp, BN254Field::mul(alphas[i], tqs[i]));
-        }
-        // 4 boundary quotients
-        for i in 0..4 {
-            comp = BN254Field::add(comp, BN254Field::mul(alphas[5 + i], boundary_quotients[i]));
-        }
-        comp
-    };
-
-    // Step 6: Verify composition commitment
-    if composition_at_z != proof.composition_ood_eval {
-        return false;
+    for root in &proof.composition_commitments {
+        channel.commit(*root);
     }
 
-    channel.commit(proof.composition_commitment);
-
-    if proof.fri_layer_commitments.is_empty()
-        || proof.composition_commitment != proof.fri_layer_commitments[0]
-    {
-        return false;
+    // Step 7: Bind the OOD values and draw DEEP coefficients
+    for v in proof.trace_ood_evals.iter().chain(proof.trace_ood_evals_next.iter()) {
+        channel.commit(*v);
+    }
+    for v in &proof.composition_ood_evals {
+        channel.commit(*v);
     }
 
-    // Step 7: Verify FRI proof
-    let fri_params = fri::FriParams::new(
-        log_trace_len,
-        proof.num_fri_layers,
-        proof.query_indices.len(),
-        BLOWUP_FACTOR,
-    );
+    let num_deep_coeffs = num_trace_deep_coeffs(W) + proof.composition_ood_evals.len();
+    let mut deep_gammas = [Fp::ZERO; MAX_DEEP_COEFFS];
+    for gamma in deep_gammas[..num_deep_coeffs].iter_mut() {
+        *gamma = channel.draw_felt();
+    }
 
+    // Step 8: Verify FRI proof over the DEEP polynomial
+    let queries = proof.decode_queries();
     let fri_valid = verify_fri(
         &mut channel,
         &proof.fri_layer_commitments,
-        &proof.query_values,
+        &queries.values,
         &proof.query_paths,
         &proof.query_indices,
         &proof.fri_final_poly,
@@ -176,15 +378,51 @@ fn verify_sharpe_parsed_proof(proof: &SharpeStarkProof, public_inputs: &[Fp; 4])
     );
 
     if !fri_valid {
-        return false;
+        return (false, channel.state());
     }
 
-    true
+    // Step 9: Check the trace/composition openings against the DEEP values
+    let deep_point = DeepPoint {
+        z,
+        zg: BN254Field::mul(z, trace_gen),
+        trace_at_z: proof.trace_ood_evals,
+        trace_at_zg: proof.trace_ood_evals_next,
+        composition_at_z: proof.composition_ood_evals.clone(),
+    };
+
+    // Step 10: Bind pi[3] to the committed dataset column
+    let valid = verify_query_openings(proof, &queries, &deep_point, &deep_gammas[..num_deep_coeffs])
+        && dataset_column_bound(proof, &queries, dataset_column, public_inputs[3]);
+    (valid, channel.state())
+}
+
+/// Check that `merkle_root` (`pi[3]`) is the root of the committed dataset
+/// column: every query opens the column to its value at z, and the
+/// constant-leaf tree over that value has root `merkle_root`.
+///
+/// The immutability constraint and `immutable_ood_consistent` make the
+/// column constant, but nothing in the AIR reads `pi[3]`; without this a
+/// proof could commit one dataset and claim another's root. Call only once
+/// the openings are authenticated (`verify_query_openings`).
+fn dataset_column_bound<const W: usize>(
+    proof: &StarkProof<W>,
+    queries: &QueryEvals,
+    column: usize,
+    merkle_root: Fp,
+) -> bool {
+    let value = proof.trace_ood_evals[column];
+    let per_query = opening_values_per_query(W, proof.composition_commitments.len());
+    queries.openings.chunks_exact(per_query).all(|row| row[column] == value)
+        && crate::mpt::compute_constant_merkle_root(value, proof.log_trace_len, proof.merkle_scheme) == merkle_root
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::channel::KeccakTranscript;
+    use super::domain::transition_zerofier_at;
+    use crate::{keccak_hash_two, keccak_hash_two_tagged};
+    use stark_core::keccak::{TAG_CHANNEL_COMMIT, TAG_CHANNEL_DRAW, TAG_MERKLE_NODE};
 
     #[test]
     fn test_channel_initialization_deterministic() {
@@ -194,259 +432,2166 @@ mod tests {
             Fp::from_u256(U256::from(89u64)),
         ];
 
-        let mut seed1 = pub_inputs[0];
-        for i in 1..pub_inputs.len() {
-            seed1 = keccak_hash_two(seed1, pub_inputs[i]);
-        }
+        let seed1 = Channel::<KeccakTranscript>::with_public_inputs(ProofKind::Sharpe, &pub_inputs).state();
+        let seed2 = Channel::<KeccakTranscript>::with_public_inputs(ProofKind::Sharpe, &pub_inputs).state();
+        assert_eq!(seed1, seed2);
+    }
 
-        let mut seed2 = pub_inputs[0];
-        for i in 1..pub_inputs.len() {
-            seed2 = keccak_hash_two(seed2, pub_inputs[i]);
-        }
+    #[test]
+    fn test_seed_is_framed_by_kind_and_count() {
+        // Coincidentally equal public inputs of a Sharpe and a Sortino proof
+        let pi = [0xcu64, 0x12c, 0x20f58, 0x2a].map(|v| Fp::from_u256(U256::from(v)));
+        let seed = |kind, inputs: &[Fp]| Channel::<KeccakTranscript>::with_public_inputs(kind, inputs).state();
+        assert_ne!(seed(ProofKind::Sharpe, &pi), seed(ProofKind::Sortino, &pi));
 
-        assert_eq!(seed1, seed2);
+        // Frame, then an unframed fold over the inputs under the commit tag
+        let frame = Fp::from_u256(U256::from_limbs([4, ProofKind::Sharpe as u64, 0, 0]));
+        let expected = pi.iter().fold(frame, |acc, &v| keccak_hash_two_tagged(TAG_CHANNEL_COMMIT, acc, v));
+        assert_eq!(seed(ProofKind::Sharpe, &pi), expected);
+
+        // Binding as_of_block is not just one more fold step on the 4-input seed
+        let with_block = [pi[0], pi[1], pi[2], pi[3], Fp::from_u256(U256::from(1000u64))];
+        assert_ne!(
+            seed(ProofKind::Sharpe, &with_block),
+            keccak_hash_two_tagged(TAG_CHANNEL_COMMIT, seed(ProofKind::Sharpe, &pi), with_block[4])
+        );
     }
 
     fn u(hex: &str) -> U256 {
         U256::from_str_radix(hex, 16).unwrap()
     }
 
+    /// Real Sharpe ratio STARK proof (Bot A, 15 trades).
+    /// Proof: cargo run --features cli --release -- --bot a --num-queries 4
+    fn sharpe_proof_bot_a() -> [Vec<U256>; 7] {
+        [
+            vec![
+                U256::from(0xfu64),
+                U256::from(0xbb8u64),
+                U256::from(0xea60u64),
+                u("0329b913d70957b39cf58a4cf71990d268b08bb3e9147f9bae72183e2215273b"),
+            ],
+            vec![
+                u("12ada0401fc4f38dbfab281bb6e471b6adaabed5ff29abff42968f7adf3c5472"),
+                u("2197259e5d8a8366aa93d77a593c6acab823832a03d918889135c296641f28e6"),
+                u("015e4fe0ac336a2674d82456a1b33c420da80c880d0823832a689500553a41ad"),
+                u("1d1aec89fe135c42fdb97ed6123008cab03ce33cd701a0afc7bbf7f4ffe99b00"),
+                u("24dc4eff5473880f424b0270f1d628e76e7f215d03edfed08f2429d5b206161f"),
+                u("00a7f15f599b7075b2e69b6ddfdabf96143842a5b83654b40347eb49fa474de2"),
+                u("302a51bbd13275c5e89edd824ba13e3b5176fd8863efba0707a434333b9009be"),
+            ],
+            vec![
+                u("0c3672950839e5d296259c6c47d80367e8f018a1d6439185509906a788af69b4"),
+                u("2845e9f102c9c6afa07c16bf1271630fb91b6a5055b193b6fa183043a2f0d088"),
+                u("1e4d8b6db6cb6393a3ce3d74abce976fcf79bef67812f14c6a77438da43e5aec"),
+                u("2f5bb12bd81160909711ab48e6e5564988064f0c5e312829cd2773ac22c3f9d8"),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                u("06b31363bd8de4c7439c9b0a0c0b5471cbc9045a7b848aae3fdd834673fbe8de"),
+                u("11c8533c53dd0519dda3d5986ddf8ba5b98cff53a7480d95905b4909e24df44e"),
+                u("2c5ac85f88d2f6b0d8d6213cb5117a2d11ad0b2b3e1936ce8d4a301ecaffa026"),
+                u("0fa19cd170d524025239a837cc44214050584cdbcb3d3cd0177dddd0b69d2b72"),
+                u("03af3031e4fa846f16508b094ad287bc8f65a2c726897e6e29c6b54bbd21a84e"),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                u("21a4f3828f55b8740f38deaa70fd62905ac7d2c6adb33610348dd4a3f3d5e118"),
+                u("27d571c8bc2e6e5029965750887d47cb0435aac8af0cfe438ffa655d4b2e893b"),
+                u("2974505ee55090df9951b26ca1c55a1ff46ee9d8d5f8a30534e534a99d002beb"),
+            ],
+            vec![
+                u("292654b94042275d879b36bd468066646236b58c72d566553f2108bf9ff955db"),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("2891ba45029a616b68819a97ead45aec175747f08e16936865dc6d27f1932502"),
+                u("23a64f0fab3e20c36c4688382c5eae8ac971e676c616c2b31aa05d6cf2ac6118"),
+                u("24594fcd7cda4b81c07fcef099b5350a91c8d7f7030c7e74b09a96629b66fb25"),
+                u("2b22acd0bc51e881f05835d3c169b9e5470068e9d9464ec4fcaed5e32e2f35d5"),
+                u("08452baca1ac2ad16158fff0b8daf1e59c4cd6881ae9cd517aaeb5a8d7ca1cfa"),
+                u("0ed3eff2197dc53a3aa53efc8d4f47d060500f9e7dccfd5252f6a53c73ab5bbd"),
+                u("09974130dc0027d3c57f61640fd0082bd68364c6aa2c65527f7076340adca4e0"),
+                u("137c7ffafbbe5a996e2458c575a203f45abbea14447d34d70acce72b71abddcd"),
+                u("052a397c779ea2822b03446f2b456f905a314ebdd33208c776ca503f5612b5c7"),
+                u("2941a96b24fb995342175a0e731d8fa4e352aa087fa6432d3919d1c337a548a8"),
+                u("144da3344b4fda8c31efc029beb9d747b5969e95c0995f17164f5c33fc22d4da"),
+                u("0409ea189c18f54657af91dbbaeee4c1d097c6c37ddaad1f2e10a9a8b13af7f8"),
+                u("0ecb7a2cfcd805a4ed2204d66f470eadbe46d3324dd443902e6befd5a03015a2"),
+                u("27d179151fa1a24692401904c7fc2d19a5dd91e1be53d8d40353850f650d41a8"),
+                u("137c7ffafbbe5a996e2458c575a203f45abbea14447d34d70acce72b71abddcd"),
+                u("09974130dc0027d3c57f61640fd0082bd68364c6aa2c65527f7076340adca4e0"),
+                u("2b4110b42775e8a22e76910b76b0dbef307228615515c3a40dec72efeefdb152"),
+                u("213953c7f16d13bacf48fa9a8f0318a30e0703b9d4dd4eb84217ab3d4405d79f"),
+                u("12d62319f604e4054c6fd91623b95e744267f94e0c95b3f6ff1125eac1357195"),
+                u("265ecf37ce4c8a15e74936a21949bb7cfdb89a928f114e0759c43c5b46d2dbd7"),
+                u("15feb44b24cdb8589fd5d51b15706303514d9763ef8d12c41c3f94ed909a844b"),
+                u("003637302e3fffb397d45127b2c45397f0e14971ba02049ffd350fa4e02e4622"),
+                u("124061fa8f83317cbe927827d83521815cb127775dbbf2ba75ab52280824d5e1"),
+                u("0ad35f31483b50f075114201ad3cea9ed48e276390eda76f14920b377463accc"),
+                u("0df7cd8006b6e17b911273ab57ddd96313e654c7debb9c2d074dbe4872b204f9"),
+                u("2fd37f2e053127bee87e2a1f7d5197ac554b8071dff56d9d1ba3f3f01b550200"),
+                u("265ecf37ce4c8a15e74936a21949bb7cfdb89a928f114e0759c43c5b46d2dbd7"),
+                u("12d62319f604e4054c6fd91623b95e744267f94e0c95b3f6ff1125eac1357195"),
+                u("15feb44b24cdb8589fd5d51b15706303514d9763ef8d12c41c3f94ed909a844b"),
+                u("003637302e3fffb397d45127b2c45397f0e14971ba02049ffd350fa4e02e4622"),
+                u("124061fa8f83317cbe927827d83521815cb127775dbbf2ba75ab52280824d5e1"),
+                u("0ad35f31483b50f075114201ad3cea9ed48e276390eda76f14920b377463accc"),
+                U256::from(0x12cu64),
+                U256::from(0x15f90u64),
+                U256::from(0x258u64),
+                U256::from(0x222e0u64),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("1e719123a989c5fd4b9790a878dbfb1bbcf426df8aac42d8b99cb1da840d892d"),
+                u("06fe9cc4777802481fe05e44a107e62ae84ed1e48852e38eeeeefa4459651254"),
+                U256::from(0xc8u64),
+                U256::from(0x9c40u64),
+                U256::from(0xa8cu64),
+                U256::from(0x94ed0u64),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("2dc56c6ca5a6f44960b383d50143383d28ea9cede4fa7d159ba2e5bc086762ec"),
+                u("0c7696cc638805d2def781443bfa1800047fc30e1f4d819da0c22c6f4623448e"),
+                u("013fd8fef62bc2f80b9ea2db7920b45cf0a876544f43f363092d2e305a346167"),
+                u("07d332f72f4aadbf7560694f7330f8613c8ba1cbe57830e0de7828c91888a150"),
+                u("279a5bd3c9df5443e347afbd74110841ef79cc4e82a173923767849c5e5e10ff"),
+                u("236a3938ef849d8f348dabc3e734b128ab47b4688b65d86c195c9387a547399d"),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                u("1b3d57951dc6987a3f74b592e0dbc1193829d51f4a347681b92ead9a7c97d66f"),
+                u("202f14ca9e562829f08a5a9f3644b8a1c8dda55ef62e1d6d8e04114ca78d809f"),
+                u("034aa8886353a737bfd64c2a4c7432fd240ce1da03e7102e5ef129fd19326746"),
+                u("00319e6c5737eaeb9c3e1d69d98c7972c9b685fef862f25d996c2525e14b993c"),
+                u("19ae2f7df720b62401420726dd04704b9ab81ea5e304ea32502b0335d90863ca"),
+                u("1d494efcaec8022741218a5d1f3fed53becd2e349a662da5768c9d40701c0cb3"),
+                u("1aa3f4d89aa77907237a16ce94505e6bc52da3a38ef4536ef9b8f6984a5908d3"),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                u("041aa1c7933273edbe5a02a58d1beb7f0c36326afb6ab1e9d69aa10c7d624054"),
+                u("0e325843e84402a8af9885aab0fb4aa77622dfd0d099dd7943d70315317b68af"),
+                u("078dcc103d0fb4335b9a45a40f50a7a6d075a790e243d73326d17d940fc19f82"),
+            ],
+            vec![
+                u("0d5a5d3becc9d3593969a49db23a8ba11983414b477a3c44a2885ed97d536808"),
+                u("068385c743bd2b88442cb01a093f0f3fd2335df95b6724fe3f915b421487f907"),
+                u("25eba2d8ab2ada2bde94d9cc4d98cec235a9715bf83316460895a5509e5a3883"),
+                u("2bf54e6f7888934574c471c2dd9dc79c9ec328c0a4de6ca655a3af3cc24d7c52"),
+                u("0d726dc5a6a8ccf132bd97a095a575fb074ff82a410b2e055cc8605b90ec15d8"),
+                u("1b512e87506d73f01fa251abbc4e55b32b92d9cb7626fe5088c0634bbf846fd5"),
+                u("17d18d30834eefdfffaf8cedb25be7172b335a4e6e497604cbbabae0e9aa5c6a"),
+                u("1a5af008477dbb73935cc9fe050277623871b77c6c74eff35cb2ca65662c66c9"),
+                u("26d598ee7dc232b65ace6d1fca69a81c33594d6048c7dea503391f033c365bd8"),
+                u("20a35e2466cea616a3d97b9b28fafa94691baec3e9e4a0728de41a7643f5eb36"),
+                u("298ff31101a43d4b957c358ec9e68250812c6c63288febdac87e3004b12821f9"),
+                u("26cb5bbb6e7d14f8ea36c797ee637329331b953d01943000753074268596d7dd"),
+                u("0be2549f9bfa47c2b058e3c33669db996a50cc15c7aa9d4bef8fea0bdaccc26b"),
+                u("115d985811efaca7b4971bbc144d1483c4e5f7535d808e851d6c2b87d944506b"),
+                u("039f04b6486c97fd352321791b05de24c634456452e4f1a5f490261ae9843bfe"),
+                u("1b1dd4a6000783ab032c56ee83e1638b92f66630e90e4742bdc1c6855c962f43"),
+                u("2f8aa0a051faf364a6ece9240c9fa68c94028f8b917fd274b414570614d177c9"),
+                u("28c5200512532e1efdf0e11fc79745d513263c083a05f98d910f4bffd18e3ea6"),
+                u("186831be4efb82e4f6401f224cdd00ac5a5123b15686ea0b0ba92f3f82578cd9"),
+                u("22229ba45cc839892d397eef5767330d43467a3ed821fe5feb64adee5bd3a286"),
+                u("07d61e1b31b4b9920f0e1a70eadd8c562b3bd3f410f98c9c1aa620a18f81104f"),
+                u("0ce62b59da7e1caecddbe42d13c406289f6f9b7d92a8ed86d2b992b2b6379fea"),
+                u("2b4ac9d27d24b08777b41d6a15ba2061e19dcd6f70fbe3deb795001be9bf668d"),
+                u("0a628a14b906b99cce402b4efcb138dda70bce2a18001ac4991bd3ab3e1c4c87"),
+                u("281032f9048ef95820597b73a5bbbd02a4f7d28aff3db228b02b3e20aee0bacf"),
+                u("1177869c2885e7aab23fe34ac777e0d34902528c5887ef0d04e6dd8aee07dc09"),
+                u("2a8e552e6847639b262af4da6aeaf3cd0d7fd028c3506940c5efb9a723893ce0"),
+                u("0b981877e8b6743bf85e889e9296acf2bd5b21ef2eb33c16f1183faa60e337cb"),
+                u("2b966913f2c232e7357d9bded6c51ea51efa3c65e8ebc14ed538b42e0439b823"),
+                u("088c10a427484126947f3d05dbbf2e00509d001edf57c8bfe9187d499708f710"),
+                u("24df8770f25a2aaff8adb2414fcc1315a826ba7c4bd2eab257bbfad38db9302d"),
+                u("1e72f19fe964f6135db71199b6e4cc336e8e539c37934ba609ddb31d9ada8aad"),
+                u("13c28d25554af068b9338d4935dae51ee7a0120177005bb514dd6df8b4f2fe7e"),
+                u("01f5ec85d7b6fec23077633b0190a8949e48e8aa059b52e6cc7b96da1ff2536a"),
+                u("25f68ae3b8b5806e82a2e3165b9a3797972e5cabe8752ddfa36c262351e3bd13"),
+                u("116e706b57726685e222ea16e602dff3267367800b8d76324d8dc169c7eb654c"),
+                u("173813d29cf9ef93bd304927cb2a892dc2c7660f72d5b067c5956994f8432ff4"),
+                u("21a26a3719020018d35c8c9968ec1a0dcea3011979928ea679c3fa04c51fa796"),
+                u("1795d9475849914b6d5d54a62bab6a791ff7239886418cb45d379d304a44e482"),
+                u("2d2038f8498be76d2ae8795db5c0a219809a352775599dc30121db022c9fd162"),
+                u("236919d2af38fa6b1123ca454f747e6bcc42d5cf085b1db81768eb7a0ee0df33"),
+                u("0a628a14b906b99cce402b4efcb138dda70bce2a18001ac4991bd3ab3e1c4c87"),
+                u("15f2b7d2b4a753a1a60a5361203b524d2864967738c4b62c985769efa7706b42"),
+                u("1e96636ce59595b60481f3bdb5e4cee062b6b6e5f8142574bff7a6e22e671c4a"),
+                u("2f2d3e512910408bb8b127de7e530132271faa1841eca0b7645e22dd86f8fe1c"),
+                u("19cb9fa58654bb8415bdcbce46782a70c3b2188fd21eb8c11d6066398c905c54"),
+                u("298ff31101a43d4b957c358ec9e68250812c6c63288febdac87e3004b12821f9"),
+                u("1a72f9eb6094b7adcfefe8df4eafb0d9f971819a0803ee5c122f825514f26c52"),
+                u("1e2cd0254c45b3da47e128338c392850271e1f7d419ff748d27bccd6afbe339f"),
+                u("1e72f19fe964f6135db71199b6e4cc336e8e539c37934ba609ddb31d9ada8aad"),
+                u("13c28d25554af068b9338d4935dae51ee7a0120177005bb514dd6df8b4f2fe7e"),
+                u("15f22ff536e6412a4f9a4c173ce4d000259fb7e81bf692b7678e55166d599d7d"),
+                u("1968694b222b6a7647b710b25d3124ad9fdbd07a88a65e20caf73377a12cf72e"),
+                u("116e706b57726685e222ea16e602dff3267367800b8d76324d8dc169c7eb654c"),
+                u("05cf6a6a650060ba571f6e4ac39c04cc875200bf0873d068258a08b65f413c74"),
+                u("133628e481ddeca1b7c4810e1ea942dbc9cdcd018bc263e0acebf6c6aed0d960"),
+                u("271bbcf28f4690c343908f45874640bb9ff93cd650dcb79b909effa8a6596255"),
+                u("20d9c2a7b24e4e175448b743d6483814fa53e9498efd403a897121c8eaee775b"),
+                u("01b44cb16ff0262aeb20310cca391fc107ffba281e568eb5a0dbd8e5ae3d9398"),
+                u("1b512e87506d73f01fa251abbc4e55b32b92d9cb7626fe5088c0634bbf846fd5"),
+                u("1a2c8fd501fcfa3fb449601f82fb8576df90c56b8c15cf80fff4865750c50619"),
+                u("2de42e469d666994c6aa5cae0e4f940865d7d00a7b6c90baa4fc17dd64134ff6"),
+                u("2a8e552e6847639b262af4da6aeaf3cd0d7fd028c3506940c5efb9a723893ce0"),
+                u("0b981877e8b6743bf85e889e9296acf2bd5b21ef2eb33c16f1183faa60e337cb"),
+                u("2b966913f2c232e7357d9bded6c51ea51efa3c65e8ebc14ed538b42e0439b823"),
+                u("1a72f9eb6094b7adcfefe8df4eafb0d9f971819a0803ee5c122f825514f26c52"),
+                u("1e2cd0254c45b3da47e128338c392850271e1f7d419ff748d27bccd6afbe339f"),
+                u("1e72f19fe964f6135db71199b6e4cc336e8e539c37934ba609ddb31d9ada8aad"),
+                u("13c28d25554af068b9338d4935dae51ee7a0120177005bb514dd6df8b4f2fe7e"),
+                u("15f22ff536e6412a4f9a4c173ce4d000259fb7e81bf692b7678e55166d599d7d"),
+                u("1968694b222b6a7647b710b25d3124ad9fdbd07a88a65e20caf73377a12cf72e"),
+                u("116e706b57726685e222ea16e602dff3267367800b8d76324d8dc169c7eb654c"),
+                u("2d5ff06127ea7fc5ab97767b5ce11fadd661ddae29024f6dd5fed52850326087"),
+                u("14db91ec27627b263bc53f511a078c88ca359b2f8eeba60e6a9512376daf7fbd"),
+                u("1cad066cef4141e1bd22bec5e479cc10046b77db915cce0ce9668bc8bf210cd5"),
+                u("10e5a23fd515aadd2db2e64cfb2bb8b6263908fa311cda095639349051767bc7"),
+                u("1f164e71ced3425e0925840bcc09528ce0f5044d1750d7a7e9679b15f6a9be76"),
+                u("119f7d2a84b540658185db82d68090564ae6f5a1ae717c8ab2d80724fe1e1e43"),
+                u("230920c5522458c06968b8eb9b009b7d1e8a50f38c60de819fd616c40fc0a575"),
+                u("11eb0694215f3110229b7d0c9c10fda0a13aed24f60acda6df4da69ffc654174"),
+                u("18b2b7f39401198c6e382fc7286a8c4660233159963dc7d023299a3d6c500fd4"),
+                u("023ccbeb41aba25694d951d937ff3071e42aebd1763137d8c3ff0b6f639cfa9f"),
+                u("18752393ec3dfe6b509cd0d60540e4455ac256b62061ce9bb4b5532e963501d5"),
+                u("02af49fad4e7b02339311d0f545f60bdb918ebd493e93fc201ada3eb2e3f3d99"),
+                u("0fddb9aec98ae4ba68def611a5db7fb913002898373b359ad45ed8b07f5aa266"),
+                u("2eab9383ce6e93fcf2dce1299ee8135e05e1678d6ec4241ba2fb030a2083ef7f"),
+                u("0d5be309cff600ac7057dbf532df9fa11dc0bbb7c740f7a3ba0e19c4859695e8"),
+                u("2fbde4cdc22507293b831027ab29c413178713934b028580daa7f374d2da2ea0"),
+                u("08c232dae4fc0845dfc8c48825367483d4a2c5af250f8aa913c4f0214382fb0d"),
+                u("0b2f56734e830d1bfc324644208f22b611eafe90e97774b3b15cd1214121ad63"),
+                u("19954eaf1944118b5918fdd979bc948ae16323cbc6ae2fb784b798433aa65f2c"),
+                u("2f27cd80b30b63559e788a994a88ad5da214e6324cb27b1d5c8ef8921afc1e28"),
+                u("0efc0174d9c63ad6d25ab629d1cd37598fb2e077e0d489388991f2bdadf2e688"),
+                u("18c7f153b9f0629f6d11ee8264e1e71cbcc2e4f6b31e2f7fb210f587fbbec324"),
+                u("1d9d28c89fdad6c2758d079c46e47d42f018237612a2d58e91620d1e48831e95"),
+                u("1d888cce7c301dbb47057d8be08d685fe2b88fc9fb15ca764754afd7161b3fdb"),
+                u("0e02234ee476c4e88c72d3d763b8a17222b16a1da36d6147d9c9c34a942e4d15"),
+                u("12acc8f691c2c486501853ab73dd8063117c197f9e9b3ccb1f213a1961cc770a"),
+                u("259067903cc6c844b4a61e7efd1252d904a4b564016097664c8bec40e639e458"),
+                u("046f859f776e13aee6e7b3f18999fb232af3c27efebf09b6b71209488bd2ea71"),
+                u("1474a4f58e743827bb86a7aee55b8e03b1725f08d999d9939a3549d3d4c6d1b7"),
+                u("01d2655a7701e4d7987eec56f490e4cdc39e26f829e5b61313c2fbbadfca58ad"),
+                u("1cf7db487aac441c08c5035fdff69ca60f99dc98c9bf7782cf3eae1cfc1873c0"),
+                u("05d97aa16c670d4656b1b53e8a19802efbd6438ca8a68478f15d57fb5966c30e"),
+                u("272db69e9cb8699efd66de4166b02c24b70a1006c432ef5ce14f02289fb21d1d"),
+                u("1d09b70e46373b896ce67c4513b08d150fe28ddc37543ea7a1697810860c72da"),
+                u("2fd092916c2de55e14db72f4ae3ccdd4bd6d4caf6ca0f1f10e71dae3be2ac1c4"),
+                u("28096f6050a1cde806aa0c37434b40bcbf05cd6a07a885777a4537bfd35ed333"),
+                u("24cbdfbea5961592c2602fc91c87aa2caec7f1a0f8de88666285e1f33b9c309c"),
+                u("29045dba352f7f8a422f0824393f20452519456d16ed84ddc3d86d4181acc302"),
+                u("1ca37862382d2aab51d3292742b5cde11fd7b3a99713680eb81dd9452e8b1476"),
+                u("2a591c2e01191ad9afa93aac89fe42d903a7a7d19e291df56e8903ec46688b59"),
+                u("07502e5312e051c6e34c5fcf8f5931be0fb5cd8aa1a3c1dbc4e8c478357a05f7"),
+                u("1d888cce7c301dbb47057d8be08d685fe2b88fc9fb15ca764754afd7161b3fdb"),
+                u("22c0e3b25d931188450f32ffa0fef916415b2ccd6c5dd59acc0daa2b9ff892ac"),
+                u("28da6fcb4f586fe645c84b4ee638b839c292de6481b2df1eafbd1350a1456d06"),
+                u("0bd19d4087e01afc6cf4fc8b43a3faa470c03d4cc6e2137373782c53b1d6547d"),
+                u("046221aec77ad6afb5241e01cca4ac7d63378a865da608e1a2034ed720206ebf"),
+                u("07460b9fbdb0be899ebe339e19110f76ded81c0d965504da1fad3dceb103f3a9"),
+                u("01d2655a7701e4d7987eec56f490e4cdc39e26f829e5b61313c2fbbadfca58ad"),
+                u("2c9025a179f803b060509f5dcf53cf5b45b6cf871ea9660371f7d78d9687bc15"),
+                u("2a8e4c85c30896db3438bcc8f4b4c97a999eba16372921f49b925d20abd4613e"),
+                u("0d2a868859147a16412d535cea410a3545ce4cb2ace38a345d17367a39881807"),
+                u("2f54fbe6346d69a270d3ea3993d68a7070b4f3136c7286fa4eff0ea96edc7f97"),
+                u("212e0284240fb89a427baafb9de6638e72082e4e8a0fa854f95a8d1b7e6dbd5f"),
+                u("28096f6050a1cde806aa0c37434b40bcbf05cd6a07a885777a4537bfd35ed333"),
+                u("0c5056fb4e8da5692e1a600a6dcc7a554523b5c0f0c7fbb1759ae4b4cec8f090"),
+                u("2e0def7e823ed4e09202c3fe6d532b68c264921013c73a9d32d2724821de0acd"),
+                u("163929bf6dc3fb7e1361e0872d49c82ce516d12ecd99619b0f8974d5900c8a35"),
+                u("2c92ec7608ee5bf7f4c0c18bf1e2013ef039688ac6098c767af7c900ac417285"),
+                u("1d8b77b64d44f12dbd0226c2add9ac00dce89f1fb00295d77767bfaab6e5828b"),
+                u("119f7d2a84b540658185db82d68090564ae6f5a1ae717c8ab2d80724fe1e1e43"),
+                u("1d4863e75cd47c1bf00920dba9e339aa626cde947f838c33f67269f0cda4ac00"),
+                u("2e9082ed5220a9280c188e9833759ab76f64bf11a62b693394a657a15aa99168"),
+                u("134dea34734cd4e243a328a0e4a85e4fbbbd8b27322fdf7f5680dcb86fb85456"),
+                u("15cbdf75e5798aab0ca22a5f830b141d0c8cf6a010bc6834a32cecc18b2af362"),
+                u("0fae282b8836df983afc08d9fd6a8d228d31403395267bc2977245fb382be438"),
+                u("02af49fad4e7b02339311d0f545f60bdb918ebd493e93fc201ada3eb2e3f3d99"),
+                u("104b477843ab53d10ed2ab91efe1abc41c3106d97b764e6ab2ad6b9ec49444e9"),
+                u("27722b5b48db0570240b154cc185f986ab303a48a762c145afb58df5b34e36e4"),
+                u("1ab2bff1b199270e506ff247ed0f553c9527625e59eac62ca3180cc5049a7533"),
+                u("0cf32d3639c30c166e38899bacb506986fc5822e501940dd2efcdd15afb0692c"),
+                u("1b85ae37371bfe1d14f51cb590fd947d74bc184b9b90740243a0ff0c0c7940c3"),
+                u("0b2f56734e830d1bfc324644208f22b611eafe90e97774b3b15cd1214121ad63"),
+            ],
+            vec![
+                u("0000000000000000000000000000000200000000000000000000000000000004"),
+                u("0000000000000000000000000000000000000000000000070000000000000004"),
+                U256::from(0x4u64),
+                u("46526ea6a38c6fadf6787155d7229c6be7556243855adcdffefa75555e8d5969"),
+                U256::from(0x8u64),
+                U256::from(0x34u64),
+                U256::from(0x26u64),
+                U256::from(0x16u64),
+            ],
+        ]
+    }
+
     /// Integration test: verify a real Sharpe ratio STARK proof (Bot A).
-    /// Proof: cargo run --features cli --release -- --mode sharpe --bot a --num-queries 4
     #[test]
     fn test_verify_sharpe_proof_bot_a() {
-        use alloc::vec;
+        let proof = sharpe_proof_bot_a();
 
-        let public_inputs = vec![
-            U256::from(0x0fu64),    // trade_count = 15
-            U256::from(0x0bb8u64),  // total_return = 3000
-            U256::from(0xea60u64),  // sharpe_sq_scaled = 60000
-            u("19dcd5ea3705cc53d3063136623f6d5b1585ef6e74614338b52e74d7e138f6c0"),
-        ];
+        // Valid Sharpe proof should verify
+        assert!(verify_parts(&proof), "Valid Sharpe STARK proof should verify");
 
-        let commitments = vec![
-            u("062ed9349522508b27b7d6148f471e9b077dfcc20f1330a444244dc6e7a56030"),
-            u("244819fa40dde78f4c2748fdd2c9fa136aafb3d4fdecce74332d241a718db811"),
-            u("244819fa40dde78f4c2748fdd2c9fa136aafb3d4fdecce74332d241a718db811"),
-            u("0b60f889bfd3efdc5928b7600cc79bf7d8be2c1ec58f3161d2b01dc8008aa29c"),
-            u("226d4eb16e8e60ce7b47100b50419f83fcc289cfbbf27f191ae4fd30ea464dd2"),
-            u("1fe598f686a1a184d666ab7cc9a57388e1f439cd34b95148310bc96c9a632fbf"),
-        ];
+        // Tampered sharpe_sq_scaled should fail
+        let mut bad = proof.clone();
+        bad[0][2] = U256::from(99999u64); // wrong sharpe_sq_scaled
+        assert!(!verify_parts(&bad), "Tampered Sharpe proof should fail");
+    }
 
-        let ood_values = vec![
-            u("17fb3ac794657f70086eb82dbeb62854f5114bf61f6e37149d85836b32a33628"),
-            u("02a3f27d8b10c8dcf06d4ea547eeb46bbdd64008aa58c10e9dc0acd49c6fcebd"),
-            u("1ff66a989af152e5ff6bfd064e697c223586be4b5a6320f8fc42c3b9cd4a8b66"),
-            u("23c1263bd474e1cc6a026cc40da1c088d7387942457670acef0e3983b3274d85"),
-            u("000000000000000000000000000000000000000000000000000000000000000f"),
-            u("0000000000000000000000000000000000000000000000000000000000000000"),
-            u("09359af957ba389fcab7a6c46690d33b2cf976ec7439db494b4306b12863af43"),
-            u("1586b525e18b11cec3b07d4288f6022c7bbda82729622d29aeb1b7f2e3340088"),
-            u("2f165d3b8c18a820620b5d708b098d4fb0fb73ed5c07ecca7790bc29e4b3ddf8"),
-            u("222d899e0ad43673c1258f81062a8baca2bf4eaddfa4c42db720cc6642297c9c"),
-            u("000000000000000000000000000000000000000000000000000000000000000f"),
-            u("0000000000000000000000000000000000000000000000000000000000000000"),
-            u("0a8f238d9240981dae876615bbebbf95b01884f411650edaf12d1acc3b25843c"),
-        ];
+    #[test]
+    fn test_verify_sharpe_with_stats() {
+        let p = sharpe_proof_bot_a();
+        let with_min = |p: &[Vec<U256>; 7], min_queries: u64| {
+            verify_sharpe_stark_with_stats(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6], U256::from(min_queries))
+        };
+        let (four, zero) = (U256::from(4u64), U256::ZERO);
 
-        let fri_final_poly = vec![
-            u("0df610fe64dd1287bd92c2d7b6d96c3dfe0bcd93f13741d6f293e7a441a7229b"),
-            u("0d6c8ec87fcf8b9288267e66309337076e388d3f0aa4aeef9c70bc82c32a2683"),
-            u("11033b2d4db38fe43eed02aaf3abb0af8c2149f499ce182c613b62075bb5de73"),
-            u("11033b2d4db38fe43eed02aaf3abb0af8c2149f499ce182c613b62075bb5de73"),
-        ];
+        assert_eq!(with_min(&p, 0), (true, four, four));
+        assert_eq!(with_min(&p, 4), (true, four, four));
+        // Below the caller's query policy: rejected, counts still reported
+        assert_eq!(with_min(&p, 20), (false, four, four));
 
-        let query_values = vec![
-            u("27d9ecca997691cbed1114760d8f945690a410002dbe33753bd6ba75d954749c"),
-            u("19f8559767ad23ecfcbb09abe21fb2ebc3c3e0adf44fe574edadf0048aeabdc3"),
-            u("15aad4fe0223527912cff7f9f483f40a2ceecf1754293eb5efee74ddf9f87469"),
-            u("2a0afad4aa15b6cba4a48295e7d41e1617968a00d99605afd78af4b3e730571c"),
-            u("2b8124628d15379bad511b06ff875aaf32659177e5259a7d1ae9591968338d9f"),
-            u("300f93ff3672538ad6d8710e7d3059c54c737629de136a4d1828b75f285207b9"),
-            u("0c5e608824fbfc6f606e87d5e9bfa50ce5d6ace82e4d2506aef0620368ea3110"),
-            u("2c47880a70e59be93ddda00085e5d270094cfd73e1ed9fd1afe14479b3d5a1eb"),
-            u("131e1b1e153dfb4d054e17bfa4fa6329623298166cb8b66cbe5833e944cc242b"),
-            u("2492949530a38462d10eedce381df8a59de0f255c96142658b5e5b8f61f4a8b8"),
-            u("1ea4c0ba7ca7ada00f53ac91e610417eccb2e67a769fea17dd306cc061e18b4a"),
-            u("1befd04cac47e808ebcb4ba9b6bdb3647b1b91fabef8b054debe9178c491e26c"),
-            u("2c5398dc13bdc099c85174c67998b14ac0ac816b0b032224a4a4384eb832ba76"),
-            u("2a2804219d3d918b1b41964f7d0583f8bbe1be79c2711acb76762f643e5af0ca"),
-            u("282405a4cc79d4b4c148933d3a8c042d4875559ebbd14c879d1f444e622c86da"),
-            u("1fa121fcffea66eaea48c63ecb456f66bab5e7a154196a6d5c4f2c87a8ead5bb"),
-            u("0aa3b69f9fadb3c021b3d51713ca65c6e21ce14f5a3b16a5d89a795610bb75a7"),
-            u("14ffb81dfc59d3b6e52e1648050733a53bae1d6026f1c9af1353a07264d54b0f"),
-            u("26168c2b52fbb541dbe18e8eba8dd5c18cc1af91aeee5f509539e7339f70a4ae"),
-            u("1b90f50e46bf5384ab14e7053ea86d5103db6ab004cfceb093e08a1d615ea8dd"),
-            u("300f93ff3672538ad6d8710e7d3059c54c737629de136a4d1828b75f285207b9"),
-            u("2b8124628d15379bad511b06ff875aaf32659177e5259a7d1ae9591968338d9f"),
-            u("0c5e608824fbfc6f606e87d5e9bfa50ce5d6ace82e4d2506aef0620368ea3110"),
-            u("2c47880a70e59be93ddda00085e5d270094cfd73e1ed9fd1afe14479b3d5a1eb"),
-            u("2bce0773e35299c4ca7520cfdaed75ea65bc6ab6ec84f5cd30c3a119652e4805"),
-            u("2751e08e0e472653cc254149846317f10ca5542f59741731f165c114cb968f7b"),
-            u("0221013ddedcccca4f3392139e7d187b3a4ea0395fe850698851a7d25940921c"),
-            u("2038470de5b96af4d0466f17419505ec4f4eda770e57a82b720a2945d54c319a"),
-            u("1cf426dc8bee1be885fb7609ce65e5dff5251d13927107dca1da61a46629fbc1"),
-            u("0b039f2d33bcab1701c9d87966f687c68235c80eb48d3abb835ae20576f364d8"),
-            u("2c47880a70e59be93ddda00085e5d270094cfd73e1ed9fd1afe14479b3d5a1eb"),
-            u("0c5e608824fbfc6f606e87d5e9bfa50ce5d6ace82e4d2506aef0620368ea3110"),
-        ];
+        let mut bad = p.clone();
+        bad[0][2] = U256::from(99999u64);
+        assert_eq!(with_min(&bad, 4), (false, four, four));
+        bad[6].clear();
+        assert_eq!(with_min(&bad, 4), (false, zero, zero));
+    }
 
-        let query_paths = vec![
-            u("17fb3ac794657f70086eb82dbeb62854f5114bf61f6e37149d85836b32a33628"),
-            u("02a3f27d8b10c8dcf06d4ea547eeb46bbdd64008aa58c10e9dc0acd49c6fcebd"),
-            u("1ff66a989af152e5ff6bfd064e697c223586be4b5a6320f8fc42c3b9cd4a8b66"),
-            u("23c1263bd474e1cc6a026cc40da1c088d7387942457670acef0e3983b3274d85"),
-            u("000000000000000000000000000000000000000000000000000000000000000f"),
-            u("0000000000000000000000000000000000000000000000000000000000000000"),
-            u("09359af957ba389fcab7a6c46690d33b2cf976ec7439db494b4306b12863af43"),
-            u("1586b525e18b11cec3b07d4288f6022c7bbda82729622d29aeb1b7f2e3340088"),
-            u("2f165d3b8c18a820620b5d708b098d4fb0fb73ed5c07ecca7790bc29e4b3ddf8"),
-            u("222d899e0ad43673c1258f81062a8baca2bf4eaddfa4c42db720cc6642297c9c"),
-            u("000000000000000000000000000000000000000000000000000000000000000f"),
-            u("0000000000000000000000000000000000000000000000000000000000000000"),
-            u("0a8f238d9240981dae876615bbebbf95b01884f411650edaf12d1acc3b25843c"),
-        ];
+    #[test]
+    fn test_trade_count_must_fit_trace() {
+        // The last trade row g^(n-1) must lie in the 16-row trace domain
+        for n in [0u64, 17, 33] {
+            let mut bad = sharpe_proof_bot_a();
+            bad[0][0] = U256::from(n);
+            assert!(!verify_parts(&bad), "trade_count {} should be rejected", n);
+        }
+        // In range, a different count moves BC2-BC4 off the last trade row
+        let mut bad = sharpe_proof_bot_a();
+        bad[0][0] = U256::from(16u64);
+        assert!(!verify_parts(&bad));
+    }
 
-        let fri_final_poly = vec![
-            u("0df610fe64dd1287bd92c2d7b6d96c3dfe0bcd93f13741d6f293e7a441a7229b"),
-            u("0d6c8ec87fcf8b9288267e66309337076e388d3f0aa4aeef9c70bc82c32a2683"),
-            u("11033b2d4db38fe43eed02aaf3abb0af8c2149f499ce182c613b62075bb5de73"),
-            u("11033b2d4db38fe43eed02aaf3abb0af8c2149f499ce182c613b62075bb5de73"),
-        ];
+    #[test]
+    fn test_public_input_count_is_exact() {
+        let verify = |p: &[Vec<U256>; 7]| {
+            verify_sharpe_stark_with_transcript(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6])
+        };
+        let honest = sharpe_proof_bot_a();
+        assert_eq!(honest[0].len(), NUM_PUBLIC_INPUTS);
+        assert!(verify(&honest).0);
 
-        let query_values = vec![
-            u("27d9ecca997691cbed1114760d8f945690a410002dbe33753bd6ba75d954749c"),
-            u("19f8559767ad23ecfcbb09abe21fb2ebc3c3e0adf44fe574edadf0048aeabdc3"),
-            u("15aad4fe0223527912cff7f9f483f40a2ceecf1754293eb5efee74ddf9f87469"),
-            u("2a0afad4aa15b6cba4a48295e7d41e1617968a00d99605afd78af4b3e730571c"),
-            u("2b8124628d15379bad511b06ff875aaf32659177e5259a7d1ae9591968338d9f"),
-            u("300f93ff3672538ad6d8710e7d3059c54c737629de136a4d1828b75f285207b9"),
-            u("0c5e608824fbfc6f606e87d5e9bfa50ce5d6ace82e4d2506aef0620368ea3110"),
-            u("2c47880a70e59be93ddda00085e5d270094cfd73e1ed9fd1afe14479b3d5a1eb"),
-            u("131e1b1e153dfb4d054e17bfa4fa6329623298166cb8b66cbe5833e944cc242b"),
-            u("2492949530a38462d10eedce381df8a59de0f255c96142658b5e5b8f61f4a8b8"),
-            u("1ea4c0ba7ca7ada00f53ac91e610417eccb2e67a769fea17dd306cc061e18b4a"),
-            u("1befd04cac47e808ebcb4ba9b6bdb3647b1b91fabef8b054debe9178c491e26c"),
-            u("2c5398dc13bdc099c85174c67998b14ac0ac816b0b032224a4a4384eb832ba76"),
-            u("2a2804219d3d918b1b41964f7d0583f8bbe1be79c2711acb76762f643e5af0ca"),
-            u("282405a4cc79d4b4c148933d3a8c042d4875559ebbd14c879d1f444e622c86da"),
-            u("1fa121fcffea66eaea48c63ecb456f66bab5e7a154196a6d5c4f2c87a8ead5bb"),
-            u("0aa3b69f9fadb3c021b3d51713ca65c6e21ce14f5a3b16a5d89a795610bb75a7"),
-            u("14ffb81dfc59d3b6e52e1648050733a53bae1d6026f1c9af1353a07264d54b0f"),
-            u("26168c2b52fbb541dbe18e8eba8dd5c18cc1af91aeee5f509539e7339f70a4ae"),
-            u("1b90f50e46bf5384ab14e7053ea86d5103db6ab004cfceb093e08a1d615ea8dd"),
-            u("300f93ff3672538ad6d8710e7d3059c54c737629de136a4d1828b75f285207b9"),
-            u("2b8124628d15379bad511b06ff875aaf32659177e5259a7d1ae9591968338d9f"),
-            u("0c5e608824fbfc6f606e87d5e9bfa50ce5d6ace82e4d2506aef0620368ea3110"),
-            u("2c47880a70e59be93ddda00085e5d270094cfd73e1ed9fd1afe14479b3d5a1eb"),
-            u("2bce0773e35299c4ca7520cfdaed75ea65bc6ab6ec84f5cd30c3a119652e4805"),
-            u("2751e08e0e472653cc254149846317f10ca5542f59741731f165c114cb968f7b"),
-            u("0221013ddedcccca4f3392139e7d187b3a4ea0395fe850698851a7d25940921c"),
-            u("2038470de5b96af4d0466f17419505ec4f4eda770e57a82b720a2945d54c319a"),
-            u("1cf426dc8bee1be885fb7609ce65e5dff5251d13927107dca1da61a46629fbc1"),
-            u("0b039f2d33bcab1701c9d87966f687c68235c80eb48d3abb835ae20576f364d8"),
-            u("2c47880a70e59be93ddda00085e5d270094cfd73e1ed9fd1afe14479b3d5a1eb"),
-            u("0c5e608824fbfc6f606e87d5e9bfa50ce5d6ace82e4d2506aef0620368ea3110"),
-        ];
+        // A fifth input is read as as_of_block and changes the seed
+        let mut padded = honest.clone();
+        padded[0].push(U256::ZERO);
+        let (valid, digest) = verify(&padded);
+        assert!(!valid);
+        assert_ne!(digest, U256::ZERO);
+
+        // Anything past that is rejected before any hashing
+        padded[0].push(U256::ZERO);
+        assert_eq!(verify(&padded), (false, U256::ZERO));
+        let mut short = honest;
+        short[0].pop();
+        assert_eq!(verify(&short), (false, U256::ZERO));
+    }
+
+    /// Pack the seven arrays the way the prover's `to_bytes` does.
+    fn pack(p: &[Vec<U256>; 7]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for part in p {
+            bytes.extend_from_slice(&(part.len() as u32).to_be_bytes());
+        }
+        for word in p.iter().flatten() {
+            bytes.extend_from_slice(&word.to_be_bytes::<32>());
+        }
+        bytes
+    }
 
-        let query_paths = vec![
-            u("1417af592c5c5bf346902e5fb4bf6563d4fb3df74df428f7d5a055ef29d53530"),
-            u("020ffa5c24166879374c2fb1320cbb31e07bec811892c529df221d8456007cb2"),
-            u("162378934d8540f58ef9e5d423f0e3be0932727f12b7079410cf1c4759dd297c"),
-            u("17d51ce4ec39bb2b8472aae57a63e0c08f0a277094425de658d4416023d4603c"),
-            u("02369ea2710dd075b4a82dc9a291a1f1a545819a8d985fd0d890bd9e68992b0a"),
-            u("1a4829627ad084661419d367486ae65236c7519d77174a665518591b1ef60a89"),
-            u("1d7c1f95b249c40a6c80d48fd8c7f9f70e60ebabecc974ad65f1dbb87e0c9408"),
-            u("08f4b1c5304deafcf797bc7372a7944209901100f2b61829f44a69e51cc21e7a"),
-            u("17f0e2f12e4245824be94a2cfc4ff45e58cf554a53329592e4464a0a94c1c03f"),
-            u("22088cf77c01752b1e91f8e3baaf4314ba7493737acbf3c6e6129bf373f610be"),
-            u("141476ad3a7faffc9f8e15a2cc23e3bd7d4836ce75ceef43f46ae349a0d837d8"),
-            u("130a923edf8c75d1d1f17509558c7a92592420562353e02f5d788eb4a9122bb8"),
-            u("01e4f9d5e56b09a123e9025d4d2ac7b4b328042d2bc366ffbd64b244327c4a2e"),
-            u("02908aed1976ff6bff42999cf7e42283ae24816def60f53838f9c810c687315e"),
-            u("09d24f0b08c7539b3b88ef3df835bc33ce2f105a9ed646b9afe484ee5935e69a"),
-            u("03e04534a67b50650d06784d9bfdb522d8a51e6405d8fe44903afa885178fc57"),
-            u("2a842522baca97cc0e8cb7f755d0b4b0c5a221551aa2eac78f0ea31bdd85a62e"),
-            u("113506143eb523eb39a81a573d355f77c6d9e67ac1c8c655914f0c2115483a62"),
-            u("1c60690bce1e352eed34cf889ebb0d9af6d6cc7a849b556cb910944b5f64b743"),
-            u("050c83c1eb9b32ba25b6faee7fd3c2704f58e85f3697c63360a29a250e0cd6cb"),
-            u("148e87e9ba6ee8dba2f8e22d57390c1daf85e9efd96024c61fa8b90020983f75"),
-            u("06637a947e6d367083b725994b75bfd8cab908318bd8a335727fbde579bd904b"),
-            u("0b588a2574d36e43e1188f53db71f6ea460e7cc14f77b0632765edba103ed4af"),
-            u("23bf49ea1632eeb794eb799b18157447b64b2e3f4abe5f9f2ea6f588fd453b12"),
-            u("042de7bf27bdc26ba08e0c5c2c44f415f2d039da24905cdbde4a04e9a46bce52"),
-            u("235475f5c06d6f1ea10ec28292e7d81dffe8424c2fbcbebeb3d3d7c7da6973e3"),
-            u("2b404e068e0704783e93c971c5d15281e75489ba36b6a012f73bfc29c9ec8c1a"),
-            u("1ac60467252d8e5f6292e143d8eef976e9afc08f803a39cfde26046ecc1ed386"),
-            u("083d2d3dcd54680ef27400f82865c3ec13d13480a6fd3e699d58d0b4b965e9d0"),
-            u("1808eb36a935d55101148872c089fcf876d5d16943ac61a5775d7d86b278e4cf"),
-            u("0aef46ab89217f8d911e8c7a028368caa83bc372b45c32474b47e8e12ac37999"),
-            u("02908aed1976ff6bff42999cf7e42283ae24816def60f53838f9c810c687315e"),
-            u("09d24f0b08c7539b3b88ef3df835bc33ce2f105a9ed646b9afe484ee5935e69a"),
-            u("2d06f5eda263de2935389fce160a550a6175b63bed4d3948b033e6a4ceefd07e"),
-            u("19677d468b7f8c7e330785184f121ed5bc3c3e2e80b3959bd6a9761dcd6108a9"),
-            u("113506143eb523eb39a81a573d355f77c6d9e67ac1c8c655914f0c2115483a62"),
-            u("07d8e3b067d6f71eff78da021a66584169a6ee2bb494aec038920834e3a55da1"),
-            u("2b5506ca50109ed6b223a1c5f5fa31c84d67d0adf913ae259ef6079884082342"),
-            u("130765b0af6aad4765bb80a5dfcaf9f8c67e367dc0c87f01f3c47139abd7b242"),
-            u("152afa086cd01f7c2c2f385d7ce5e7aa2c06728c3308baa6a3ec728c4015df2a"),
-            u("0b588a2574d36e43e1188f53db71f6ea460e7cc14f77b0632765edba103ed4af"),
-            u("23bf49ea1632eeb794eb799b18157447b64b2e3f4abe5f9f2ea6f588fd453b12"),
-            u("294281ed897fd8770602e760fac1c1094291e6938fb70593d47ef79e2341c327"),
-            u("0b69007657861c46899e83c4b628fadd931366fe07a666e4ae0bc69f3575f3e8"),
-            u("00c583857a9ada85b3fadac9ec8154377c27a90d20b7c7f1a4e152e7ddb86364"),
-            u("1cd9c0e8cc6aa9ab65f69cbc2c8f9d8e111703eccf6ecc014e03eec7605a0d3c"),
-            u("083d2d3dcd54680ef27400f82865c3ec13d13480a6fd3e699d58d0b4b965e9d0"),
-            u("26d04c020998e181d9cd31c25c4d353b803d9f2e73fc0d09e8ac2c8619093b62"),
-            u("2b63a2eaa4da18982fb4864309554455a7476a1efb4c06f1362e1be432074584"),
-            u("111e4d77a245afdc9ce9596bf40c81aa1521e726f48021132823a7edcdeb1fe0"),
-            u("119ff1badb33d49dce92f7e201990a07b21d1b4bd16101e76da3afdbadcbe7ac"),
-            u("03e04534a67b50650d06784d9bfdb522d8a51e6405d8fe44903afa885178fc57"),
-            u("2a842522baca97cc0e8cb7f755d0b4b0c5a221551aa2eac78f0ea31bdd85a62e"),
-            u("113506143eb523eb39a81a573d355f77c6d9e67ac1c8c655914f0c2115483a62"),
-            u("07817a49269c70dc53b928b888390fc8756b3bbd8de1db6c90102104a2556c4c"),
-            u("1d34900505592019e6a1a2ef130f90da90047b5cfd288f702ebaf0bc812ecdc5"),
-            u("1c91d9f1017ef2ae0d78807b85a2571e027eddb288ec2e916efbc2c99c238341"),
-            u("1aacef60890faa6c86a81af5293ea14d5e380c33a132e64e9a900039574e5851"),
-            u("1f10040674f6be75802867d189856b0beae13dcbf9f21b35b4a6494dc5fd5db5"),
-            u("1a4829627ad084661419d367486ae65236c7519d77174a665518591b1ef60a89"),
-            u("0df68c060a5debf0f0fc797e6ebce692f14c4dda4f4a5dd0e0dfb02af344474b"),
-            u("1f824d458a0983062bc1ba53b19da6dd045d4e3b590a009959022af5a726fd1c"),
-            u("236a1f729d883890ef990751404628cd0e17911ad4f92d280d587f0e5307cc95"),
-            u("1ac60467252d8e5f6292e143d8eef976e9afc08f803a39cfde26046ecc1ed386"),
-            u("083d2d3dcd54680ef27400f82865c3ec13d13480a6fd3e699d58d0b4b965e9d0"),
-            u("20d5b0aa97442abae1dafef29d7d292b00c1fa4fe67d4a540ac3518992d66fcf"),
-            u("163cd3993257e412b3b88a60ac32915a94baa76cb276d348c394e5291bed6af4"),
-            u("16a39de63e62427260735eed8b67130f27e4fcee9220629726421c5ea3c81a6e"),
-            u("09d24f0b08c7539b3b88ef3df835bc33ce2f105a9ed646b9afe484ee5935e69a"),
-            u("2c70f838ee3c6ca5712db1765d477316fecc2c19e29c6715faf6160e92c4c015"),
-            u("2ac89b9f1be38d5d109688063faa294e46ec0359e098699f307931a310f3f6b9"),
-            u("2fb21e640d9e11e85512805731e60fcd507c332830309c8e0a3ad881aff2c657"),
+    #[test]
+    fn test_verify_sharpe_packed_matches_arrays() {
+        let proof = sharpe_proof_bot_a();
+        assert!(verify_sharpe_packed(&pack(&proof)));
+        assert_eq!(verify_sharpe_packed(&pack(&proof)), verify_parts(&proof));
+
+        let mut bad = proof.clone();
+        bad[0][2] = U256::from(99999u64);
+        assert!(!verify_parts(&bad));
+        assert!(!verify_sharpe_packed(&pack(&bad)));
+
+        // A trailing byte makes the blob malformed
+        let mut padded = pack(&proof);
+        padded.push(0);
+        assert!(!verify_sharpe_packed(&padded));
+    }
+
+    /// Parsing converts every calldata word except the query-metadata header,
+    /// indices, Merkle path nodes and query values into Montgomery form
+    /// exactly once; `decode_queries` converts the query values.
+    #[test]
+    fn test_parse_conversion_count_bot_a() {
+        let p = sharpe_proof_bot_a();
+        let before = Fp::from_u256_calls();
+        let proof = parse_sharpe_proof(&p[1], &p[2], &p[3], &p[4], &p[5], &p[6]).unwrap();
+        assert_eq!(Fp::from_u256_calls() - before, proof.num_field_elements());
+
+        let calldata_words: usize = p[1..].iter().map(|v| v.len()).sum();
+        let metadata_words = proof::METADATA_HEADER_LEN + proof.query_indices.len();
+        let path_words = p[5].len();
+        let query_words = p[4].len();
+        assert_eq!(proof.num_field_elements(), calldata_words - metadata_words - path_words - query_words);
+        // 4 queries, 16-row trace: 7 roots + 16 OOD + 4 final poly; the
+        // 4 * (8 FRI + 9 opened) query values wait for `decode_queries`, and
+        // the 4 * (18 FRI + 3 * 6 opening) = 144 path nodes are never converted
+        assert_eq!(proof.num_field_elements(), 7 + 16 + 4);
+        assert_eq!(query_words, 68);
+    }
+
+    #[test]
+    fn test_verifier_protocol_hash() {
+        let tagged_one_two = |tag| {
+            crate::keccak_hash_two_tagged(tag, Fp::from_u256(U256::from(1u64)), Fp::from_u256(U256::from(2u64))).to_u256()
+        };
+        let words = [
+            U256::from(PROOF_FORMAT_VERSION),
+            crate::field::BN254_PRIME,
+            domain::GENERATOR_2_28.to_u256(),
+            U256::from(BLOWUP_FACTOR),
+            U256::from(sharpe_air::NUM_COLUMNS),
+            U256::from(sharpe_air::NUM_TRANSITION_CONSTRAINTS),
+            U256::from(sharpe_air::NUM_BOUNDARY_CONSTRAINTS),
+            U256::from(sortino_air::NUM_COLUMNS),
+            U256::from(sortino_air::NUM_TRANSITION_CONSTRAINTS),
+            U256::from(sortino_air::NUM_BOUNDARY_CONSTRAINTS),
+            keccak_hash_two(Fp::from_u256(U256::from(1u64)), Fp::from_u256(U256::from(2u64))).to_u256(),
+            tagged_one_two(TAG_MERKLE_NODE),
+            tagged_one_two(TAG_CHANNEL_COMMIT),
+            tagged_one_two(TAG_CHANNEL_DRAW),
+            crate::poseidon::poseidon_hash_two(Fp::from_u256(U256::from(1u64)), Fp::from_u256(U256::from(2u64)))
+                .to_u256(),
         ];
+        let mut preimage = Vec::new();
+        for w in words {
+            preimage.extend_from_slice(&w.to_be_bytes::<32>());
+        }
+        let hash = U256::from_be_bytes(crate::keccak256(&preimage));
+        assert_eq!(VERIFIER_PROTOCOL_HASH, hash, "{:?}", hash.as_limbs());
+    }
+
+    /// Bot C with every return negated loses 1800 bps overall: `total_return`
+    /// is `p - 1800` and verifies, while the int256 encoding of -1800 is
+    /// rejected by the precheck. Sharpe^2 is sign-agnostic, so the claim is
+    /// Bot C's.
+    #[test]
+    fn test_net_negative_total_return() {
+        use crate::field::signed_to_field;
+        use stark_prover::mock_data::{bot_c_swing_trader, GmxTradeRecord, TradeReturn};
 
-        let query_metadata = vec![
-            U256::from(4u64), U256::from(4u64), U256::from(4u64),
-            U256::from(0x35u64), U256::from(0x06u64), U256::from(0x0du64), U256::from(0x21u64),
+        let bot = bot_c_swing_trader();
+        let trades: Vec<GmxTradeRecord> = bot
+            .trades
+            .iter()
+            .map(|t| GmxTradeRecord::from_return_bps(TradeReturn::new(-t.return_bps.bps()).unwrap()))
+            .collect();
+        let proof = stark_prover::prove_sharpe(&trades, U256::from(bot.expected_sharpe_sq_scaled), 4, None);
+        let mut p = [
+            proof.public_inputs,
+            proof.commitments,
+            proof.ood_values,
+            proof.fri_final_poly,
+            proof.query_values,
+            proof.query_paths,
+            proof.query_metadata,
         ];
 
-        // Valid Sharpe proof should verify
-        assert!(
-            verify_sharpe_stark(&public_inputs, &commitments, &ood_values, &fri_final_poly,
-                &query_values, &query_paths, &query_metadata),
-            "Valid Sharpe STARK proof should verify"
+        assert_eq!(p[0][1], signed_to_field(-1800));
+        assert_eq!(field_to_signed(p[0][1]), Some(-1800));
+        assert!(verify_parts(&p));
+
+        p[0][1] = U256::ZERO.wrapping_sub(U256::from(1800u64));
+        assert!(!total_return_in_range(p[0][1]));
+        assert!(!verify_parts(&p));
+    }
+
+    /// Prove Bot A in-process at every query count from 1 up to the
+    /// verifier's 64-query buffer and verify each proof; one more query than
+    /// that is rejected by the parser.
+    #[test]
+    fn test_prove_verify_query_count_matrix() {
+        use self::proof::QueryMetadata;
+
+        let bot = stark_prover::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let prove = |num_queries: usize| {
+            let proof = stark_prover::prove_sharpe(&bot.trades, claimed, num_queries, None);
+            [
+                proof.public_inputs,
+                proof.commitments,
+                proof.ood_values,
+                proof.fri_final_poly,
+                proof.query_values,
+                proof.query_paths,
+                proof.query_metadata,
+            ]
+        };
+
+        let mut widest = None;
+        for num_queries in [1, 2, 4, 8, 16, 20, 32, 64] {
+            let p = prove(num_queries);
+            assert_eq!(QueryMetadata::from_words(&p[6]).unwrap().num_queries(), num_queries);
+            assert!(verify_parts(&p), "{} queries must verify", num_queries);
+            widest = Some(p);
+        }
+
+        // 65 queries: the 64-query proof with one more index declared
+        let p = widest.unwrap();
+        assert!(parse_sharpe_proof(&p[1], &p[2], &p[3], &p[4], &p[5], &p[6]).is_some());
+        let mut metadata = QueryMetadata::from_words(&p[6]).unwrap();
+        metadata.indices.push(metadata.indices[0]);
+        let mut over = p.clone();
+        over[6] = metadata.to_words();
+        assert!(parse_sharpe_proof(&over[1], &over[2], &over[3], &over[4], &over[5], &over[6]).is_none());
+        assert!(!verify_parts(&over));
+    }
+
+    #[test]
+    fn test_rejects_proof_for_other_protocol() {
+        use self::proof::{check_protocol_hash, ProtocolCheck};
+
+        let p = sharpe_proof_bot_a();
+        assert_eq!(check_protocol_hash(&p[6]), ProtocolCheck::Match);
+        assert!(verify_parts(&p));
+
+        // Same proof from a prover whose protocol parameters differ
+        let mut other = p.clone();
+        other[6][3] = VERIFIER_PROTOCOL_HASH ^ U256::from(1u64);
+        assert_eq!(check_protocol_hash(&other[6]), ProtocolCheck::Mismatch);
+        assert!(!verify_parts(&other));
+
+        // A proof from before the hash was carried shows a query index there
+        let mut legacy = p.clone();
+        legacy[6].remove(3);
+        assert_eq!(check_protocol_hash(&legacy[6]), ProtocolCheck::Mismatch);
+        assert!(!verify_parts(&legacy));
+    }
+
+    /// Every stored proof vector's metadata decodes through `QueryMetadata`
+    /// and re-encodes to the same words.
+    #[test]
+    fn test_gas_estimate_bot_a() {
+        use self::proof::QueryMetadata;
+
+        // README benchmark: Bot A verifies for ~1.25M gas
+        let metadata = QueryMetadata::from_words(&sharpe_proof_bot_a()[6]).unwrap();
+        let estimate = gas::estimate_verify_gas(
+            metadata.log_trace_len as u64,
+            metadata.num_queries() as u64,
+            metadata.num_fri_layers as u64,
+            metadata.num_columns as u64,
         );
+        assert!((1_150_000..=1_350_000).contains(&estimate), "Bot A estimate {}", estimate);
+    }
 
-        // Tampered sharpe_sq_scaled should fail
-        let bad_inputs = vec![
-            U256::from(0x0fu64),
-            U256::from(0x0bb8u64),
-            U256::from(99999u64),  // wrong sharpe_sq_scaled
-            u("19dcd5ea3705cc53d3063136623f6d5b1585ef6e74614338b52e74d7e138f6c0"),
+    #[test]
+    fn test_rejects_halved_trace_length() {
+        use self::proof::QueryMetadata;
+
+        let p = sharpe_proof_bot_a();
+        let verify = |metadata: &[U256]| {
+            verify_sharpe_stark(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], metadata)
+        };
+        assert!(verify(&p[6]));
+
+        // Declared N/2 (and 2N): different zerofier, domain and FRI depth
+        let mut metadata = QueryMetadata::from_words(&p[6]).unwrap();
+        assert_eq!(metadata.log_trace_len, 4);
+        for log_trace_len in [3, 5] {
+            metadata.log_trace_len = log_trace_len;
+            let words = metadata.to_words();
+            assert_ne!(words[2], p[6][2]);
+            assert!(!verify(&words), "log_trace_len {} accepted", log_trace_len);
+        }
+
+        // Raw word flip, leaving the FRI layer count as committed
+        let mut words = p[6].clone();
+        words[2] -= U256::from(1u64);
+        assert!(!verify(&words));
+    }
+
+    #[test]
+    fn test_fixture_metadata_decodes() {
+        use self::proof::{QueryMetadata, METADATA_HEADER_LEN};
+
+        let fixtures = [
+            (sharpe_proof_bot_a(), sharpe_air::NUM_COLUMNS),
+            (sharpe_proof_bot_a_two_queries(), sharpe_air::NUM_COLUMNS),
+            (salted_proof_alice(), sharpe_air::NUM_COLUMNS),
+            (salted_proof_bob(), sharpe_air::NUM_COLUMNS),
+            (poseidon_proof(), sharpe_air::NUM_COLUMNS),
+            (three_segment_proof(), sharpe_air::NUM_COLUMNS),
+            (sortino_proof_bot_c(), sortino_air::NUM_COLUMNS),
         ];
-        assert!(
-            !verify_sharpe_stark(&bad_inputs, &commitments, &ood_values, &fri_final_poly,
-                &query_values, &query_paths, &query_metadata),
-            "Tampered Sharpe proof should fail"
+        for (p, num_columns) in fixtures {
+            let metadata = QueryMetadata::from_words(&p[6]).unwrap();
+            assert_eq!(metadata.num_columns, num_columns);
+            // OOD section: 2W trace values + one per composition segment
+            let num_segments = p[2].len() - 2 * num_columns;
+            assert_eq!(metadata.num_segments, num_segments);
+            assert_eq!(metadata.blowup, BLOWUP_FACTOR);
+            assert_eq!(metadata.grinding_bits, 0);
+            assert_eq!(metadata.protocol_hash, VERIFIER_PROTOCOL_HASH);
+            assert_eq!(
+                p[1].len(),
+                1 + num_segments + metadata.num_fri_layers,
+                "commitments: trace, segments, FRI layers"
+            );
+            let salt_words = metadata.leaf_salt.is_some() as usize;
+            assert_eq!(p[6].len(), METADATA_HEADER_LEN + metadata.num_queries() + salt_words);
+            assert_eq!(metadata.to_words(), p[6]);
+        }
+
+        let salted = QueryMetadata::from_words(&salted_proof_alice()[6]).unwrap();
+        assert!(salted.leaf_salt.is_some());
+        assert_eq!(QueryMetadata::from_words(&three_segment_proof()[6]).unwrap().num_segments, 3);
+        let poseidon = QueryMetadata::from_words(&poseidon_proof()[6]).unwrap();
+        assert_eq!(poseidon.transcript, channel::TranscriptKind::Poseidon);
+        assert_eq!(QueryMetadata::from_words(&sharpe_proof_bot_a_two_queries()[6]).unwrap().num_queries(), 2);
+    }
+
+    /// A wrong composition OOD value is caught from the OOD values alone: the
+    /// verifier rejects before converting a single query value, so the work
+    /// done does not depend on the number of queries.
+    #[test]
+    fn test_bad_ood_composition_rejects_before_queries() {
+        let conversions = |p: &[Vec<U256>; 7]| {
+            let before = Fp::from_u256_calls();
+            let valid = verify_parts(p);
+            (valid, Fp::from_u256_calls() - before)
+        };
+        let corrupt = |mut p: [Vec<U256>; 7]| {
+            let last_ood = p[2].len() - 1;
+            p[2][last_ood] += U256::from(1u64);
+            p
+        };
+
+        let (valid, honest_4q) = conversions(&sharpe_proof_bot_a());
+        assert!(valid);
+        let (valid, honest_2q) = conversions(&sharpe_proof_bot_a_two_queries());
+        assert!(valid);
+        assert!(honest_4q > honest_2q);
+
+        let (valid, rejected_4q) = conversions(&corrupt(sharpe_proof_bot_a()));
+        assert!(!valid);
+        let (valid, rejected_2q) = conversions(&corrupt(sharpe_proof_bot_a_two_queries()));
+        assert!(!valid);
+        assert_eq!(rejected_4q, rejected_2q);
+
+        // None of the 68 query words of the 4-query proof were converted
+        let p = sharpe_proof_bot_a();
+        assert!(rejected_4q + p[4].len() <= honest_4q);
+    }
+
+    /// An immutable column opening to different values at z and zg is
+    /// rejected before the transcript is seeded; TC1 at z is no such check.
+    #[test]
+    fn test_inconsistent_immutable_ood_rejected_up_front() {
+        let honest = sharpe_proof_bot_a();
+        let proof = parse_sharpe_proof(&honest[1], &honest[2], &honest[3], &honest[4], &honest[5], &honest[6])
+            .expect("Bot A proof parses");
+        assert!(immutable_ood_consistent(&proof, &sharpe_air::IMMUTABLE_COLUMNS));
+        let [ret, ret_sq] = [proof.trace_ood_evals[0], proof.trace_ood_evals[1]];
+        assert_ne!(ret_sq, BN254Field::mul(ret, ret), "TC1 only holds on the trace domain");
+
+        for column in sharpe_air::IMMUTABLE_COLUMNS {
+            // ood_values = [7 trace at z, 7 trace at zg, segments...]
+            let mut p = sharpe_proof_bot_a();
+            p[2][7 + column] += U256::from(1u64);
+            let (valid, digest) = verify_sharpe_stark_with_transcript(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6]);
+            assert!(!valid, "column {}", column);
+            assert_eq!(digest, U256::ZERO, "column {} got past the precheck", column);
+        }
+    }
+
+    /// 9 of Bot A's trades (16 rows, 7 of them padding) with a dataset
+    /// commitment, proved over a trace `tamper` edits before committing.
+    fn tampered_dataset_proof(tamper: impl FnOnce(&mut [Vec<U256>], &mut [U256; 4])) -> [Vec<U256>; 7] {
+        let trades = &stark_prover::mock_data::bot_a_aggressive_eth().trades[..9];
+        let claimed = stark_prover::sharpe_trace::SharpeTrace::generate(trades, None).compute_sharpe_sq_scaled();
+        let options = stark_prover::ProveOptions::default();
+        let commitment = Some(U256::from(0xc0ffeeu64));
+        let p = stark_prover::debug_trees::prove_sharpe_with_tampered_trace(
+            trades, claimed, 4, commitment, &options, tamper,
         );
+        [p.public_inputs, p.commitments, p.ood_values, p.fri_final_poly, p.query_values, p.query_paths, p.query_metadata]
+    }
+
+    /// `pi[3]` must be the root of the dataset column inside the trace
+    /// commitment, not just of some constant the prover names.
+    #[test]
+    fn test_dataset_column_bound_to_pi3() {
+        let other = U256::from(0xbadu64);
+        assert!(verify_parts(&tampered_dataset_proof(|_, _| {})));
+
+        // Another dataset committed in the trace, the original root claimed:
+        // the AIR is satisfied and only the binding rejects
+        let swapped = tampered_dataset_proof(|columns, _| columns[sharpe_air::DATASET_COLUMN].fill(other));
+        assert!(!verify_parts(&swapped));
+
+        // Claiming the root of what the trace commits verifies
+        let rebound = tampered_dataset_proof(|columns, pi| {
+            columns[sharpe_air::DATASET_COLUMN].fill(other);
+            pi[3] = crate::mpt::compute_constant_merkle_root(Fp::from_u256(other), 4, crate::merkle::MerkleScheme::Ordered)
+                .to_u256();
+        });
+        assert!(verify_parts(&rebound));
+
+        // Non-constant on the padding rows, which TC4 does not gate
+        let varying = tampered_dataset_proof(|columns, _| columns[sharpe_air::DATASET_COLUMN][12] = other);
+        assert!(!verify_parts(&varying));
+    }
+
+    /// FRI over per-query openings agrees with FRI over the flat arrays,
+    /// starting from the Bot A transcript state right before FRI.
+    #[test]
+    fn test_verify_fri_openings_matches_flat_bot_a() {
+        use self::fri::{verify_fri_openings, FriParams, QueryOpening};
+
+        let p = sharpe_proof_bot_a();
+        let proof = parse_sharpe_proof(&p[1], &p[2], &p[3], &p[4], &p[5], &p[6]).unwrap();
+        let pi: Vec<Fp> = p[0].iter().map(|v| Fp::from_u256(*v)).collect();
+
+        // Replay the commits of steps 1-7; draws in between only advance the counter
+        let pre_fri_channel = || {
+            let mut channel = Channel::<KeccakTranscript>::with_public_inputs(ProofKind::Sharpe, &pi);
+            channel.commit(proof.trace_commitment);
+            let ood = proof.trace_ood_evals.iter().chain(&proof.trace_ood_evals_next);
+            for v in proof.composition_commitments.iter().chain(ood).chain(&proof.composition_ood_evals) {
+                channel.commit(*v);
+            }
+            channel
+        };
+        let params = FriParams::new(proof.log_trace_len, proof.num_fri_layers, proof.query_indices.len(), BLOWUP_FACTOR)
+            .unwrap();
+        let query_values = proof.decode_queries().values;
+
+        let openings = QueryOpening::from_flat(
+            &query_values,
+            &proof.query_paths,
+            &proof.query_indices,
+            params.num_layers,
+            params.log_domain_size,
+        )
+        .unwrap();
+        assert_eq!(openings.len(), 4);
+        assert!(openings.iter().all(|o| o.layer_values.len() == proof.num_fri_layers));
+
+        // struct -> flat -> struct
+        let (values, paths, indices) = QueryOpening::to_flat(&openings);
+        assert_eq!(values, query_values);
+        assert_eq!(paths, proof.query_paths);
+        assert_eq!(indices, proof.query_indices);
+        let reparsed = QueryOpening::from_flat(&values, &paths, &indices, params.num_layers, params.log_domain_size);
+        assert_eq!(reparsed.as_ref(), Some(&openings));
+
+        let run_flat = |values: &[Fp]| {
+            let mut channel = pre_fri_channel();
+            let ok = verify_fri(
+                &mut channel,
+                &proof.fri_layer_commitments,
+                values,
+                &proof.query_paths,
+                &proof.query_indices,
+                &proof.fri_final_poly,
+                &params,
+            );
+            (ok, channel.state())
+        };
+        let run_openings = |openings: &[QueryOpening]| {
+            let mut channel = pre_fri_channel();
+            let ok = verify_fri_openings(
+                &mut channel,
+                &proof.fri_layer_commitments,
+                openings,
+                &proof.fri_final_poly,
+                &params,
+            );
+            (ok, channel.state())
+        };
+
+        let flat = run_flat(&query_values);
+        assert!(flat.0, "Bot A FRI should verify");
+        assert_eq!(run_openings(&openings), flat);
+
+        // A tampered f(-x) is rejected by both forms
+        let mut bad = openings.clone();
+        bad[1].layer_values[0].1 = BN254Field::add(bad[1].layer_values[0].1, Fp::ONE);
+        let (bad_values, _, _) = QueryOpening::to_flat(&bad);
+        assert!(!run_flat(&bad_values).0);
+        assert!(!run_openings(&bad).0);
+
+        // Truncated flat arrays do not split into openings
+        let short = &proof.query_paths[..proof.query_paths.len() - 1];
+        assert!(QueryOpening::from_flat(&values, short, &indices, params.num_layers, params.log_domain_size)
+            .is_none());
+    }
+
+    /// The FRI section of `query_values` is read query-major, as
+    /// `stark_core::fri::qv_index` lays it out; the same values stored
+    /// layer-major are rejected.
+    #[test]
+    fn test_transposed_fri_query_values_rejected() {
+        use self::proof::QueryMetadata;
+        use stark_core::fri::{qv_index, query_values_len, LayerValue};
+
+        let proof = sharpe_proof_bot_a();
+        assert!(verify_parts(&proof));
+        let metadata = QueryMetadata::from_words(&proof[6]).unwrap();
+        let (num_queries, num_layers) = (metadata.indices.len(), metadata.num_fri_layers);
+        let fri_len = query_values_len(num_queries, num_layers);
+
+        let mut transposed = proof.clone();
+        for query in 0..num_queries {
+            for layer in 0..num_layers {
+                for which in [LayerValue::X, LayerValue::NegX] {
+                    let layer_major = (layer * num_queries + query) * 2 + which as usize;
+                    transposed[4][layer_major] = proof[4][qv_index(num_layers, query, layer, which)];
+                }
+            }
+        }
+        assert_ne!(transposed[4][..fri_len], proof[4][..fri_len]);
+        assert_eq!(transposed[4][fri_len..], proof[4][fri_len..]);
+        assert!(!verify_parts(&transposed), "Layer-major FRI values should fail");
+    }
+
+    /// The query indices in `query_metadata` are not trusted: `verify_fri`
+    /// re-derives them from the transcript, so a prover cannot pick its own
+    /// query points for the otherwise valid proof.
+    #[test]
+    fn test_tampered_query_indices_rejected() {
+        use self::proof::{QueryMetadata, METADATA_HEADER_LEN};
+
+        let proof = sharpe_proof_bot_a();
+        assert!(verify_parts(&proof));
+        let num_queries = QueryMetadata::from_words(&proof[6]).unwrap().indices.len();
+        let indices = METADATA_HEADER_LEN..METADATA_HEADER_LEN + num_queries;
+        assert!(proof[6][indices.clone()].iter().any(|&i| i != U256::ZERO));
+
+        let mut pinned = proof.clone();
+        pinned[6][indices.clone()].fill(U256::ZERO);
+        assert!(QueryMetadata::from_words(&pinned[6]).is_some());
+        assert!(!verify_parts(&pinned), "Prover-chosen query indices should fail");
+
+        // Reordering the derived indices is rejected too
+        let mut reordered = proof.clone();
+        reordered[6][indices].reverse();
+        assert!(!verify_parts(&reordered));
+    }
+
+    /// A proof declaring a composition LDE other than 1 << log_domain_size is
+    /// rejected before any FRI indexing; Bot A's is the blowup-4 LDE.
+    #[test]
+    fn test_mismatched_composition_lde_len_rejected() {
+        use self::proof::QueryMetadata;
+
+        let proof = sharpe_proof_bot_a();
+        let metadata = QueryMetadata::from_words(&proof[6]).unwrap();
+        assert_eq!(metadata.composition_lde_len, 1 << (metadata.log_trace_len + 2));
+        // The honest length is the default and stays off the wire
+        assert_eq!(proof[6][2].as_limbs()[3], 0);
+
+        for lde_len in [1u64 << (metadata.log_trace_len + 1), 1 << (metadata.log_trace_len + 3)] {
+            let mut mismatched = proof.clone();
+            mismatched[6] = QueryMetadata { composition_lde_len: lde_len, ..metadata.clone() }.to_words();
+            assert!(parse_sharpe_proof(&mismatched[1], &mismatched[2], &mismatched[3], &mismatched[4], &mismatched[5], &mismatched[6]).is_none());
+            assert!(!verify_parts(&mismatched), "LDE length {} should fail", lde_len);
+        }
+    }
+
+    /// Trace OOD evaluations inconsistent with the committed trace are caught
+    /// by the DEEP check itself: with Bot A's own z and γ's, moving any
+    /// t_j(z) or t_j(zg) breaks D(x) against the opened rows.
+    #[test]
+    fn test_deep_check_rejects_inconsistent_trace_ood() {
+        let p = sharpe_proof_bot_a();
+        let proof = parse_sharpe_proof(&p[1], &p[2], &p[3], &p[4], &p[5], &p[6]).unwrap();
+        let pi: Vec<Fp> = p[0].iter().map(|v| Fp::from_u256(*v)).collect();
+
+        // Replay steps 1-7 of `verify_parsed_proof` for z and the γ's
+        let mut channel = Channel::<KeccakTranscript>::with_public_inputs(ProofKind::Sharpe, &pi);
+        channel.commit(proof.trace_commitment);
+        let z = channel.draw_felt();
+        sharpe_composition_at_z(&proof, &pi, z, &mut channel);
+        let ood = proof.trace_ood_evals.iter().chain(&proof.trace_ood_evals_next);
+        for v in proof.composition_commitments.iter().chain(ood).chain(&proof.composition_ood_evals) {
+            channel.commit(*v);
+        }
+        let gammas: Vec<Fp> = (0..num_trace_deep_coeffs(7) + proof.composition_ood_evals.len())
+            .map(|_| channel.draw_felt())
+            .collect();
+
+        let point = |trace_at_z: [Fp; 7], trace_at_zg: [Fp; 7]| DeepPoint {
+            z,
+            zg: BN254Field::mul(z, domain_generator(proof.log_trace_len)),
+            trace_at_z,
+            trace_at_zg,
+            composition_at_z: proof.composition_ood_evals.clone(),
+        };
+        let queries = proof.decode_queries();
+        let honest = point(proof.trace_ood_evals, proof.trace_ood_evals_next);
+        assert!(verify_query_openings(&proof, &queries, &honest, &gammas));
+
+        for j in 0..7 {
+            let mut at_z = proof.trace_ood_evals;
+            at_z[j] = BN254Field::add(at_z[j], Fp::ONE);
+            let bad = point(at_z, proof.trace_ood_evals_next);
+            assert!(!verify_query_openings(&proof, &queries, &bad, &gammas), "t_{}(z)", j);
+
+            let mut at_zg = proof.trace_ood_evals_next;
+            at_zg[j] = BN254Field::add(at_zg[j], Fp::ONE);
+            let bad = point(proof.trace_ood_evals, at_zg);
+            assert!(!verify_query_openings(&proof, &queries, &bad, &gammas), "t_{}(zg)", j);
+        }
+
+        // End to end, a shifted trace OOD value is rejected too
+        let mut bad = p.clone();
+        bad[2][0] += U256::from(1u64);
+        assert!(!verify_parts(&bad));
+        let mut bad = p;
+        bad[2][7] += U256::from(1u64);
+        assert!(!verify_parts(&bad));
+    }
+
+    #[test]
+    fn test_verify_rejects_altered_trace_opening() {
+        let proof = sharpe_proof_bot_a();
+        // 4 queries * 4 FRI layers * 2 values precede the openings
+        let openings_start = 4 * 4 * 2;
+
+        // cum_ret column of the first query's opened trace row
+        let mut bad = proof.clone();
+        bad[4][openings_start + 2] += U256::from(1u64);
+        assert!(!verify_parts(&bad), "Altered trace column opening should fail");
+
+        // Composition value of the last query
+        let mut bad = proof.clone();
+        bad[4][openings_start + 3 * 7 + 6] += U256::from(1u64);
+        assert!(!verify_parts(&bad), "Altered composition opening should fail");
+
+        // A FRI layer root replaced by the trace root is malformed
+        let mut bad = proof.clone();
+        bad[1][3] = bad[1][0];
+        assert!(!verify_parts(&bad), "FRI layer reusing the trace commitment should fail");
+
+        // Dropping the openings entirely is malformed
+        let mut bad = proof;
+        bad[4].truncate(openings_start);
+        assert!(!verify_parts(&bad), "Missing openings should fail");
+    }
+
+    /// The same Bot A statement proven independently with 2 queries: the
+    /// proof embedded for `self_test`.
+    fn sharpe_proof_bot_a_two_queries() -> [Vec<U256>; 7] {
+        self_test::proof_parts()
+    }
+
+    fn verify_with_transcript(p: &[Vec<U256>; 7]) -> (bool, U256) {
+        verify_sharpe_stark_with_transcript(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6])
+    }
+
+    #[test]
+    fn test_transcript_digest_matches_across_proofs() {
+        let (valid_4, digest_4) = verify_with_transcript(&sharpe_proof_bot_a());
+        let (valid_2, digest_2) = verify_with_transcript(&sharpe_proof_bot_a_two_queries());
+        assert!(valid_4 && valid_2);
+        assert_ne!(digest_4, U256::ZERO);
+        assert_eq!(digest_4, digest_2, "Same statement must draw the same challenges");
+
+        // A different FRI layer root is absorbed into the transcript
+        let mut bad = sharpe_proof_bot_a();
+        let last = bad[1].len() - 1;
+        bad[1][last] += U256::from(1u64);
+        let (valid, digest) = verify_with_transcript(&bad);
+        assert!(!valid);
+        assert_ne!(digest, digest_4);
+
+        // So is a different trace commitment
+        let mut bad = sharpe_proof_bot_a();
+        bad[1][0] += U256::from(1u64);
+        let (valid, digest) = verify_with_transcript(&bad);
+        assert!(!valid);
+        assert_ne!(digest, digest_4);
+    }
+
+    /// Salted proofs of the same 4-trade dataset, differing only in leaf salt.
+    /// Generated with `ProveOptions { leaf_salt: Some(0xa11ce) }` and `Some(0xb0b)`,
+    /// trades [100, 200, 300, 200] bps, 2 queries.
+    fn salted_proof_alice() -> [Vec<U256>; 7] {
+        [
+            vec![
+                U256::from(0x4u64),
+                U256::from(0x320u64),
+                U256::from(0x13880u64),
+                u("1ddf2c6800b575fa978294010f676a8f44c8009d3328352658c19e92935ba982"),
+            ],
+            vec![
+                u("0139c93892a479d692eb25990468668199f636741a403f78f5c1674c153c1103"),
+                u("0f855ce1361727d2062b1f42ee6b6bd9a39c5af54945c64f3348e8af75e9b549"),
+                u("2f468cf7577b744aea5c162d7ca261f527c7cfb389d57ca22aa9cade20f25cea"),
+                u("1138cc4792133a4cb5c15806a016d91e86d4bbf1792f75e3689222f47dfd0280"),
+                u("2cb0dfd3846c77b8d7101e73e3d6889cca7f3a11c836e0e8cee9352d877716b5"),
+            ],
+            vec![
+                u("122d6e2a7cc515e7852940f372a0a64c7f333edc9c547f5beeb0b38faab329b3"),
+                u("210308e0c5d79bc2f6d3fe2fc12fb941df29e3e194f60165f000fc9eb424bcfa"),
+                u("1d410b21298f4deb5a9eab39f2c74b405d77355b431dd9b886d5ef2b5e12a375"),
+                u("2b2eb17b33841c6d04dda0c0afef7e4a50afa4ab2d0aeeade00b44d2132c3764"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("21058f25bdeacb9b607792db26c36bda439c1ad8a6b1ceedd6875bd470bfc5f6"),
+                u("194f731bb52abbfe002409c66ff4f9be3f415fde7d34c268511c3dee8f70a800"),
+                u("0d3dcd4065187f7086df53d0c8da9be0f6551cab50cf3281debb9d0f076d2ca5"),
+                u("14b381bbffe76fe58feb95510acde4f424ccb1bf2046abd2166a644f32a27641"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("0858a9f2a2ca482f69854e2e55f5155fa54675f2f376c61933716f6624d71e13"),
+                u("08326c7070643e0551eacf81159316700a36a72e42cbb135ef5291760dbb2e25"),
+            ],
+            vec![
+                u("2f7747ab418e496be4de21aad7dc131fd26c5bcd1129872c16325dbd2cfd3e55"),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("10fe8d05e98b4b282955884ce7fd51aab783079c20ffd76127370bc00bd917ab"),
+                u("0f2454b396d0766a4332d5edeee1f9ac9c7ac7ab3c4c95e4ae2c1cdf043c5b92"),
+                u("251814568a801a1baee9f5e2621e45a5a695f01a2756763ac58d4c4c425bd22c"),
+                u("12ca5ddce6e7f6418dd7e45e5272124639af6a7854f360ec60de05a411f13267"),
+                u("1def6e9e48e2d9c13ab98382663ed81dcfa5da64e853d781bd0c58fd9596c5c6"),
+                u("02dca7ed30e9509439714e3306fd81ccbdd987813bf197e9877e29061bbc8fdc"),
+                u("0d3a8786169f7e6856930cc46c8c20c0af3cc5454df63f04db2ffe349afac35e"),
+                u("2aa7eaad5ac891f4e62ecd7c4804372b3108954d2e5398224b3b53bbb9524135"),
+                u("2b6a40da5f5a347a1aba90d46130ec676928df6f16c423cae0b041632f787fca"),
+                u("0bdbd5a7f8bb5cf65fbcf7bf645d302c5df565333ae05bb685f3555ac01585d9"),
+                u("258595acbf5ba763be36e6905e55daab0b1a7c44dc75a7baf0128895ff33625b"),
+                u("1615407058dc3e10231a2fe8cb4e2aa223161cc61335a72d22fd300b27f4061c"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("1d14415c048d7b4ff3bc985e4eea03cc48633fea3e90cccd99a09a3f93aa3720"),
+                u("223c1ef04126faee4888b5df47b51e387482dec9a480efa0d7a22f079a2e5c4e"),
+                U256::from(0x12cu64),
+                U256::from(0x15f90u64),
+                U256::from(0x258u64),
+                U256::from(0x222e0u64),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("01cef7b0f067d0a3a0a8f5a54f290547df1f978e514daadc0b9d4165c91cdbae"),
+                u("1403e0cec1bb317de73d5aa464391f9f6548a54ba5a8c435d622f7cda7a608b7"),
+            ],
+            vec![
+                u("234bb8db00a6d1103413e5ce6c99693ae976a92ecf916dca5a846fcb224c8593"),
+                u("2b8d0f49f482acac970835f70407686b276eb3cee3215c8961ac99f82d0919e1"),
+                u("2f19b30a22faee6267ab93931973461480ea9714c32bb40b0333064c874c2b67"),
+                u("223cf0ab08e9f1a48bb19aa77b5040011c50f360372d2de62483d9c544f4fe69"),
+                u("29f323b7eebc6ae2c32cb0bc5b77dcffa226f69d4917d03f1b1a81f989834fa2"),
+                u("047f516f031a8d2988cf0f36bb5826285043793e0992a276df1f2bdf81fb37b5"),
+                u("0cc721cfaedcf49052d97aea7f782bcf079d20934d3ca990f051e0e5c174cc72"),
+                u("230f06dbdff42ad092609426fcede37f5def3c64f473190bd97e92a741782ead"),
+                u("1cbf23754f3e261b766a34589b4c16472b8d20cf6d285ac989829d4103890f14"),
+                u("2f19b30a22faee6267ab93931973461480ea9714c32bb40b0333064c874c2b67"),
+                u("223cf0ab08e9f1a48bb19aa77b5040011c50f360372d2de62483d9c544f4fe69"),
+                u("02ec1bcb539f9fc6a7675ef4a289d8e6a362d1a39504d31823ecb4fe731f7abb"),
+                u("026e9c67566bf9eb4e08233ebe52fd479817a2753ad047ced64d9ecd268974e2"),
+                u("0cc721cfaedcf49052d97aea7f782bcf079d20934d3ca990f051e0e5c174cc72"),
+                u("10e04ff0230f20f3218ccc495c67a9966f5b7ac3cf1ed48ea439b07606b25fde"),
+                u("24a03695aa370ad9f9794b7ef221a3893516246616eb05b1afb97f6da1f04ca8"),
+                u("0dc03131842db81b931b5e375e55d604a5b6f15e1cfa58b8da7c85230ae3a2da"),
+                u("032de44987e6433bfe20970943a13b990af6e4b42bea338d1dfd3d39b9d75b3c"),
+                u("27e7a5884641b1b7ae05e52f251884f0e5f7c5976db7b9ef58d7841c06af82d7"),
+                u("096798a513532ccd898347eabebcdcdcc02b5d04dd3ab65f76551b031c600cdf"),
+                u("0558822114c29cb5f3a2af96ca4b451424351920616351aaa421276a8b5cc205"),
+                u("217c58e06888a5c7a8e9713ae47844f69acf571be52eedbf7e72aa63d7cc653b"),
+                u("00bd667bab42a2c32ce545f4fb7d5a6745ece6f83af87ca4c1c8071707efa917"),
+                u("23bbc531476779ecb8dd6e26a1bded5138d19c3b00965dfd05ad6545cb4db67a"),
+                u("0cbda0b386d1d8954849675aa29528a799dd37c50dd57350bf5bd6ddf42fc70d"),
+                u("07bbf6da0d4d39abc873f8de967ba4fbb8fc93397bea45176e645f41cefb5fb6"),
+                u("0d1bf2b90234a194d79688179609c965cf19a57e670af7ce0ee063199354cc06"),
+                u("157b7feb82172b4aabfefaffb4d6b2d74e926e1cc315aadc1591be3bbc876b67"),
+                u("0dc03131842db81b931b5e375e55d604a5b6f15e1cfa58b8da7c85230ae3a2da"),
+                u("032de44987e6433bfe20970943a13b990af6e4b42bea338d1dfd3d39b9d75b3c"),
+                u("22173f4e0c92b0605601c6141ab8ff1b79eca95fbbb613caeb18ea50802488c2"),
+                u("00e764905e141e58ef269fb3ac1e72e26d7cb929493eee98245b80e12d08ba2e"),
+                u("0558822114c29cb5f3a2af96ca4b451424351920616351aaa421276a8b5cc205"),
+                u("217c58e06888a5c7a8e9713ae47844f69acf571be52eedbf7e72aa63d7cc653b"),
+                u("0000567a27bb791b851b1f4b3a0cbf45fcd5af75e0ba4548a637a176ace182ae"),
+                u("0258711e20b81b77814b80e364fd372b90fb70e6cd8b652c2543e34fe642ec61"),
+                u("0cbda0b386d1d8954849675aa29528a799dd37c50dd57350bf5bd6ddf42fc70d"),
+                u("07bbf6da0d4d39abc873f8de967ba4fbb8fc93397bea45176e645f41cefb5fb6"),
+            ],
+            vec![
+                u("0000000000000000000000000000000200000000000000000000000000000002"),
+                u("0000000000000000000000000000000000000000000000070000000000000002"),
+                U256::from(0x2u64),
+                u("46526ea6a38c6fadf6787155d7229c6be7556243855adcdffefa75555e8d5969"),
+                U256::from(0xbu64),
+                U256::from(0x8u64),
+                U256::from(0xa11ceu64),
+            ],
+        ]
+    }
+
+    fn salted_proof_bob() -> [Vec<U256>; 7] {
+        [
+            vec![
+                U256::from(0x4u64),
+                U256::from(0x320u64),
+                U256::from(0x13880u64),
+                u("1ddf2c6800b575fa978294010f676a8f44c8009d3328352658c19e92935ba982"),
+            ],
+            vec![
+                u("2ebd965ae8e5bbd4aad552f471e172cd42d8cf081724bb310e115f5db9d56571"),
+                u("20c3745055fafc040d52c901ed04b7216ee2e2e494427aa0c74b288d8b54a31c"),
+                u("0e308cad420e072099221a269a7437970565764aa34e55b8ce6c90add3e340ac"),
+                u("0795b2383d0c1b456a849f8eb9939e47553d1cf7aaecbb6337794a09352cc1d9"),
+                u("2dc5c9ced9e4c36f875a83214b9080e4a1f42bcc793aea4b3f6b3a84b6ad47b7"),
+            ],
+            vec![
+                u("2f97f2beafcb3203405a74784ba7b390b48002687c88b8f88e00bb72d04f3173"),
+                u("0ddb47c4393b3bdac2ae70dd7077718fd7fa82bfa1187e52c21e43258d00b32b"),
+                u("22c006b9376fe0220055fd586199711ea13cb9eacae81c223c5e9f88e37aa06f"),
+                u("2ed00f6f578d12dee76d558a147640ef3ed26c6a85114dce9ae8d533a3a8f8f4"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("03edc2f62d19a2d42046f7b128ba65566465221decdc2a5a290a5a60325d16e4"),
+                u("1c8a661d10ee6f693c46d3eb6913c4df4fe1eef2f7aed1c18d1323f5302f41ac"),
+                u("09a67d34b2c19947602d322712a7af404473b0fdbcddbe737a1d33656e115668"),
+                u("1c714dd807c0f5877b7e5df218efd133d04c1ca6af337bc2e2a90ae1fe7d0c9b"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("1cc6082f9ca5666c69bb4d79546b8874b77b235331a41db8701300ea740b06f9"),
+                u("1678fdd3b125716f16448750b723b6f37326368fbe2d8faa4b6481f49235f209"),
+            ],
+            vec![
+                u("234cb1d306356c4c6952413991efd7eb80fdb505c9a0490e403c5bb9bfcf40d9"),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("1b74d58f484149c913bd0ab1b012364c48ce121c1907a4c5d9d16613113d22a0"),
+                u("221ffee314dbbff362b59f5d00a2a4a5aaddff0ed0295e386b9a9fba46c681e3"),
+                u("0756e5ba9773702f2801b509680a46e01827f14d9a7bfd8bbf6f6b8190567b3e"),
+                u("1e32ab953bc1be52b41b408e5de27cf7c33841f2c5fe7f28c169831fc98c5365"),
+                u("279d8053217532ce8e954863eb2a79826a2a623db531a785a76f355c3894e31c"),
+                u("1d9f592a270bb5f4924d99de341f7233c3d9d2481a0186dcf0e143daf71666dd"),
+                u("0b2ae8400d255fb27d39d1231f54cf42355402fb0d7d133de8e1a5367c2a4df9"),
+                u("1a5ea90fc60fcecf5ee32474a697f495a60c304552fd697697f7496addb880aa"),
+                u("2b6a40da5f5a347a1aba90d46130ec676928df6f16c423cae0b041632f787fca"),
+                u("0bdbd5a7f8bb5cf65fbcf7bf645d302c5df565333ae05bb685f3555ac01585d9"),
+                u("258595acbf5ba763be36e6905e55daab0b1a7c44dc75a7baf0128895ff33625b"),
+                u("1615407058dc3e10231a2fe8cb4e2aa223161cc61335a72d22fd300b27f4061c"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("124307fe1299cdb4d745bb9f1501b3c0c858118df92165c1da044dcc8ae53837"),
+                u("0f311c41c0c0e2fb90f5bd08b014f8dbf728d34afeb0aa44f790a2ea40adaa59"),
+                u("21f58ce59159b5ff17b2be4afc6da9f235ecb9b2ee46fdddb7b69fb8e853ab34"),
+                u("00f75bf7be7ac62a6f82dcd1679c47a7172cacb3c1fee165cf5ca919981d54df"),
+                u("2c7bacd72e41fb3ed41cdbd99b04cab1c43f6a69ab3ace27b5c125380d97b90c"),
+                u("1c1d8b6be6746b58c2d58456cd4be9ca2ab52eecd5168085ff07e86927c0d63e"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("02cfd91bba4b29b7fc697457b60ffaca511aa5ad5a2d8e6ab8308f18933642fa"),
+                u("186b19c2eed0d2f5ef860e402b3351725cb3f061ee2b02845d12290d00bb4dfa"),
+            ],
+            vec![
+                u("1cc7c59d60ba5505420b87f550f8fefc3584df87109de7e70e7fa3fbeca3fe7c"),
+                u("1d4b36b4226f20ea5ddd51aac8f640f0e7fafddf6ab82cb72123f22c7cd2b30c"),
+                u("0afd58f3d6927718cdd9b9220f35889432873ac404a5fd40cf871a3870bbbeb4"),
+                u("1a56c7235616a0ae15b8be6bb618fd353c39b85f2ceaff220c14854d5771975f"),
+                u("0bad2f1340f6fa816342b570a038ec4138752a12f342eb780b0802d040dcd5fd"),
+                u("2a595a90feada6b4e66ec17fa87ca6d6d8e3571206f174b6926c61ab50d29ee6"),
+                u("2b1aaf6ec0adfa655353ff2eccc461292b345cd33dd4e93e0043e621ba4d3b1d"),
+                u("13f871edb49829a5a9c6c1a54a656b761e762a7d97aeaff14c95f21374f2ff47"),
+                u("123cefc87eabe5b90d2888ff8883f527f9290f30ec4fcde0f765e3ebc7d564dd"),
+                u("0780ed4cd0cff75d79845510661b4e7e291021377164248e485f3301841e68d8"),
+                u("1a56c7235616a0ae15b8be6bb618fd353c39b85f2ceaff220c14854d5771975f"),
+                u("151e5eaf6e32e99026da318b333118fe3c1bf13cab09e1e9b3cfc30d393daec8"),
+                u("032cf3953e1c1c3b8e60dc7b23657fe3c7a774e7cecde286fc8365d9254d148d"),
+                u("058c8ce2c8c5e58949f9f7f027def84cec16315f9a437cbd28b0c65d0756f815"),
+                u("07950e3cc73f1e25e26b6104abedd67aa94386ebf34b492279bdd108c228a355"),
+                u("2a5b975abcc17750abbf6e1ffa1298054978587f190a1454fcb0a717bee9370a"),
+                u("029963c773528faa4a41bc961a110352422f6c8931d939cec5cc621eea811ec2"),
+                u("0c999c90ab00a93d93020eec98978c5e41e92b37c1c2eb876473dcbab61cb770"),
+                u("07caab5ebae42850e245e58b62e3ae93260dc5121ec236afd3a89184a53a934c"),
+                u("2d884474b521979f0f9bd7dbb4f8aa77dd44a13e18891d9371fc18ac64d250f7"),
+                u("1436ebd9d8005df965c10344df1eaa0d232a5086f458a26dcf8e2afd27e7ecd7"),
+                u("1f171fb0f25d46b4a38216f512ed56f90898cff7409198fa2877482d480ecabf"),
+                u("236d5e816fbe863121298e054cb9af382fab5c50705ece6fc9201018e16d5959"),
+                u("245c956fbe500c2a40eb919a57900f0978bb58ecf111b5855014c284bb050d3a"),
+                u("28248c7de09dd16995e751391ac6e89ea4ce4deed97a060cbdb6189d5b801033"),
+                u("27c24ddcf0ea67e6aae238d02bd137d4cfb9e5bd8c595bd47f871e57ddb7e02b"),
+                u("01b86c0d84bb5feedec6b6c6d200679c21e1ba4194f89c9934a7fdc540d057f1"),
+                u("2afc424754724cbe5ca88359f49f9c1c5c5e77f0cf098914863359055fa8973c"),
+                u("25030b170b07d1002f80659f0cced1d3ea40ac18e8a5dcfb32baa3295de273e6"),
+                u("0c999c90ab00a93d93020eec98978c5e41e92b37c1c2eb876473dcbab61cb770"),
+                u("23afe4cc362830418b5bf3d5a73594867b23964ecaae3e8c2efb42fb68e7a5db"),
+                u("04085f38dd49d4ae08255addd2cb911f78387f2469b7d3111c08bdfe2099fc9d"),
+                u("0179c1a4de581d1fdbb2b7ba2d8a8e58133235e8b3e1c326fecc86c46c1106b1"),
+                u("1f171fb0f25d46b4a38216f512ed56f90898cff7409198fa2877482d480ecabf"),
+                u("1835fad9061a1319faa2676e40ef765bf62bae7b5dec7d0ff2b77632d44f763d"),
+                u("09f0d8b83a852b3e3099d5f0054724693289ab7f08b0597a83f978ca0ebbfe4c"),
+                u("2ace16ddf1acc815ddc044a87adf993b44ccdc8eab9846b4ea12bde1245c1465"),
+                u("27c24ddcf0ea67e6aae238d02bd137d4cfb9e5bd8c595bd47f871e57ddb7e02b"),
+            ],
+            vec![
+                u("0000000000000000000000000000000200000000000000000000000000000002"),
+                u("0000000000000000000000000000000000000000000000070000000000000002"),
+                U256::from(0x2u64),
+                u("46526ea6a38c6fadf6787155d7229c6be7556243855adcdffefa75555e8d5969"),
+                U256::from(0xbu64),
+                U256::from(0xdu64),
+                U256::from(0xb0bu64),
+            ],
+        ]
+    }
+
+    fn verify_parts(p: &[Vec<U256>; 7]) -> bool {
+        verify_sharpe_stark(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6])
+    }
+
+    #[test]
+    fn test_verify_salted_sharpe_proofs() {
+        let alice = salted_proof_alice();
+        let bob = salted_proof_bob();
+
+        assert!(verify_parts(&alice), "Salted proof (0xa11ce) should verify");
+        assert!(verify_parts(&bob), "Salted proof (0xb0b) should verify");
+
+        // Same statement, unlinkable commitments
+        assert_eq!(alice[0], bob[0]);
+        assert_ne!(alice[1][0], bob[1][0]);
+        assert_ne!(alice[1][1], bob[1][1]);
+    }
+
+    #[test]
+    fn test_sharpe_sq_range_precheck() {
+        use crate::field::BN254_PRIME;
+
+        assert!(sharpe_sq_in_range(U256::from(60_000u64)));
+        assert!(sharpe_sq_in_range(MAX_SHARPE_SQ_SCALED - U256::from(1u64)));
+        assert!(!sharpe_sq_in_range(MAX_SHARPE_SQ_SCALED));
+        assert!(!sharpe_sq_in_range(BN254_PRIME - U256::from(1u64)));
+
+        // Limb 0 is the low word: |Sharpe| < 100 → Sharpe^2 * 10000 < 10^8
+        assert_eq!(MAX_SHARPE_SQ_SCALED, U256::from(100_000_000u64));
+    }
+
+    #[test]
+    fn test_verify_rejects_near_modulus_sharpe_sq() {
+        use crate::field::BN254_PRIME;
+
+        // Realistic claim (80000 = Sharpe ~2.83) passes
+        let mut proof = salted_proof_alice();
+        assert!(verify_parts(&proof));
+
+        // Near-modulus claim is rejected by the precheck
+        proof[0][2] = BN254_PRIME - U256::from(1u64);
+        assert!(!verify_parts(&proof), "Near-modulus sharpe_sq_scaled should be rejected");
+    }
+
+    #[test]
+    fn test_verify_salted_sharpe_proof_wrong_salt_fails() {
+        let mut proof = salted_proof_alice();
+        let salt_idx = proof[6].len() - 1;
+
+        proof[6][salt_idx] = U256::from(0xb0bu64);
+        assert!(!verify_parts(&proof), "Swapped leaf salt should fail");
+
+        // Dropping the salt entirely must also fail
+        proof[6].pop();
+        assert!(!verify_parts(&proof), "Stripped leaf salt should fail");
+    }
+
+    /// Same 4-trade dataset proven over the Poseidon transcript.
+    /// Generated with `ProveOptions { transcript: TranscriptKind::Poseidon }`,
+    /// trades [100, 200, 300, 200] bps, 2 queries.
+    fn poseidon_proof() -> [Vec<U256>; 7] {
+        [
+            vec![
+                U256::from(0x4u64),
+                U256::from(0x320u64),
+                U256::from(0x13880u64),
+                u("1ddf2c6800b575fa978294010f676a8f44c8009d3328352658c19e92935ba982"),
+            ],
+            vec![
+                u("25de4dfb13b1527fc59c9ed2deeb7cd25d94bcb566d909121f354ea8a4ab942d"),
+                u("03d09498609c10e9c348ae727985cc0ac33284c57c7e9d3c124e9ebdab07d3ca"),
+                u("158c40b579a3e663f92d7bbdabb2980391d332def9f27d019646767a58695478"),
+                u("12dcbbc9b0982244f43eb53a4826b64aaa32a75853a4cecf1d92cba27bc6f1fb"),
+                u("103f703d98e78dbcd5431e7023fb5c301c9f299f52c46b915b2a4d21ee642a70"),
+            ],
+            vec![
+                u("2bae5cc6e8c4b9f0ef1fb62b8feadaec0e2575572d74abbb59e65bff69b53a9e"),
+                u("2f1226beade35d44b31d87a64ff8596ccd588e0be6ac47996e692a40b73ae188"),
+                u("0fb48c84638b10b9aeaafc985ebc4573f9d811af7c668cc5a57ac7c03a35eb18"),
+                u("0399eadf05102e3c1631eb9a4a59eddb67bf314a5ebd3e53a5413705a3a23a3f"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("18455c052424e5c557df2ffe0836efb798a6ac4da7480261293df8e3f878a030"),
+                u("2252fcc8e297d4470b919a1c19e2fa7fe00fe4f63a7d872efc12456bb069ecb8"),
+                u("09220992a38610541bb27b676ad49fb3ac1397e27ffb0b9e0c7d2ded16543996"),
+                u("1239c2d5298d2e18af07bc5d249be60338a15952b70a0b74d62fb72478aa5a14"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("2fdb58aa8ec36fce03a40042340648b623176f11685076241b4b4b4c2adb7322"),
+                u("1065a71352084a8dddc6cf89762f2c9c63c108ce927be1e7f5061b48b0c7df22"),
+            ],
+            vec![
+                u("1fcd06a1b617106afbc6491eb399a0149afc6c3c7aff9a7a796414fe8a1a5b1a"),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("104e6cc5e80a77646c0c57ff28155676794e9b38871b277afd5d9b70f57eb5ff"),
+                u("05a75fc617783d9053f10082a3bac81d5abf55e8b4a41bbd8da336b01700c4fe"),
+                u("13ded6ca607b802c6243724f937e60f27c8fe0cc2a0d23805f59f6e1602cafde"),
+                u("12b1dabe785bf686d1292bbde21ed2f1c1c71b6a52b071ca35ea6b25a50c193c"),
+                u("29e6e89b79ce07b571794fa76b9bce4eb2bc2ee4549def66a0a0379401ad3ac8"),
+                u("2ff1a5e728efb26e95eff09bed60480a7496de80e8a65da2ed3b1b9c3948d0a6"),
+                u("1d9e108c418032af29d143551953b0a692187ac9edf2f0203ed63c9eff67f816"),
+                u("08f2a0fc97574404099b5ab85c49833dac3e816c8ecaa52a566e256805d0d104"),
+                u("2b6a40da5f5a347a1aba90d46130ec676928df6f16c423cae0b041632f787fca"),
+                u("0bdbd5a7f8bb5cf65fbcf7bf645d302c5df565333ae05bb685f3555ac01585d9"),
+                u("258595acbf5ba763be36e6905e55daab0b1a7c44dc75a7baf0128895ff33625b"),
+                u("1615407058dc3e10231a2fe8cb4e2aa223161cc61335a72d22fd300b27f4061c"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("2536267877babec9d0281d6502a9066f017f8c8438aa66bf25ea43bacbfbff33"),
+                u("1c2f8529d2e31c0be4d627fc0b7f79789efc80d10517848485b01f7162e7e362"),
+                u("0e6ec18d4fd7ea2aa09d876b8513ae6af2472e958b7272b38c2b55db07ac565d"),
+                u("1dd346c3e513a49701293d24dedd2f40d55c4ca65d3eab6ebab6a60872a8c591"),
+                u("1e646e1ff5741b3195f6862493ffff55ccc723ebfd8e35ac69b371e65e5a1dc7"),
+                u("2a49237110186a485bbb2aaa1ed39c0ac3f68ccc94d90e6ce832b15e6d2f2476"),
+                U256::from(0x4u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("19419b70a621c5b7fc74c8a5dc50cd264bb3c67e84b089bb8e5584445606d64a"),
+                u("2b4eafa52783dbb85a3bd9a64ea3d52fa7a9d97ed9f8eb5ac317596ffe964d94"),
+            ],
+            vec![
+                u("005cef09fb3d7682fa474b7af17f0f8ba0e1243028ca4603bc5c95069bd6bdc7"),
+                u("2f5b13ddfc722569b24e9c52a3fdff833ed6830d5d42024f915508932af3b787"),
+                u("0816319d98e9794f74ad8d09bf65bbca68626fdee44bcf04a28227373b50d2e5"),
+                u("178e18c617f7537667b4d2d45c238a7e9f864112ea542051e4a7cc18b1d4c408"),
+                u("005599a542c1bcdb6fa18dfa60d83a1dc4423212a2e10fa2eb62733520eb9357"),
+                u("2d75807dded2bff67fd5d0627411a1022e00d49b9ca44dcc8dd225bb4ac2710e"),
+                u("2fa08ae0f5b4e7a1f132b98f4d0e8b7190cc7d72106aa80d5aaa131395bb2a14"),
+                u("284d3f952f99cdd337ccd30a9a2ee39db57f11886717bd44b534f736a6206600"),
+                u("2ef06128caca7310d23847974dec231287fbfbef834973936bbd227496e5bf7c"),
+                u("218b03ded78bc728f2e6e64ff001c0d0fd808e256b8200005393e33742561895"),
+                u("303883ae6e67c4b0b47741ee4166a68b6310d61269fd3e75630ae0186316b1ae"),
+                u("13dfa4238b529a6fcb5c5f946958857235c5f8b9d6c86a365c07878133a2fcca"),
+                u("022aeaf57378611dcd26a51d2340c823d60ee478ac79469121b37a6bcbd6ead6"),
+                u("085bd6b0ab9b791734d2f39ac6cd2f0f87ff277440a579094a31f469fe9da3e0"),
+                u("1aac1c725b615b3de115ffac6800dcc38f0b3e3d5dc9c676bfed045445ae6c32"),
+                u("213ccf1b436beb7bd8d6a1781f128325d177b2ddb92c2abcb078c0a8807ec650"),
+                u("153dd0400ac9cd6c05d1b2b6bffe3b450385dc6b39ec269dfe9fe4d131fc4e31"),
+                u("2fc93eaf6b57df6b865285e3a48acd38d3d1fd792afaadd7eae126e9654d9e41"),
+                u("09ad2c4528f2f587e27868504bb71f15977a2806d83d9710176bcf48b122913f"),
+                u("2c67cb9ee9342a69b085279cdfd6ee1ad0caa9d3b430b4982f2a842232f6d3e3"),
+                u("268df25748be443cebe1f46d877a9b2f5548e65ed244f995840be700e059257b"),
+                u("1d37fd20ac9d5c9b1b205614db313304c597f998d8fb58323ec96a54a07d0837"),
+                u("1515e706f1ae4306767b78209ba9e59fe4355b618c84aa69536c84ed8aae612a"),
+                u("1611b1719bbda229c3370c9edd3cd491f82415e3496d4889150ee90c19b1b986"),
+                u("16754c179ef12175406beebaf8b557af454f58d123a5b7e7c237a4d967ad7109"),
+                u("00f99ce0a7b577cdf55d8731be3cd157d5d138da3635b4ae3e3c41bcdcc092e0"),
+                u("2798318d6890afe154b58f0347067c6a6e8510a09e9791336448ffa3642c0e34"),
+                u("136e392e9cea18dd9094d757b21458d684f9926ba33e76f3dbdb724616a9a5a0"),
+                u("0c122c95802a978122fffdb966bce9b54eba0a9ec4c73e220fba70e28736d20a"),
+                u("03205d7f2e228ce7372edbab35a6b9ff08147d9412f2a71d0a6728c66c96e3f6"),
+                u("0853c6c9f218b9bf965ecbb8fa708c6ecf14769842748c3030a104fed0d346ee"),
+                u("2d2c2292e0e183f8ba680c125cc2b2a4f1f51ad52b263c607282c358639d022e"),
+                u("1902c2b4c46fa42fedfa13249a195bf03526c9223e6f4e95581f733507d170b1"),
+                u("158c2ebc45361ea49a41def890285c3264e1e42b2264ad50477c6f677977650f"),
+                u("3035b15ae2e4ea582380c12391c0a2609e4700add719df43ee1ccd3a99c9c846"),
+                u("246210717fdc69ec2bfc97d583e6dbdfe12256c268da0a9bc9c0e2f78606a0f4"),
+                u("0153aa987f7327f9ba0caf29270511a0f457f171d83e7a59c44158b10c899857"),
+                u("05816298524a3252b63b0e8eda2215922642e7f56f0384b5478d629960ed6fba"),
+            ],
+            vec![
+                u("0000000000000000000000000000000200000000000000010000000000000002"),
+                u("0000000000000000000000000000000000000000000000070000000000000002"),
+                U256::from(0x2u64),
+                u("46526ea6a38c6fadf6787155d7229c6be7556243855adcdffefa75555e8d5969"),
+                U256::from(0xbu64),
+                U256::from(0x5u64),
+            ],
+        ]
+    }
+
+    #[test]
+    fn test_verify_poseidon_transcript_proof() {
+        let proof = poseidon_proof();
+        assert!(verify_parts(&proof), "Poseidon-transcript proof should verify");
+
+        // Same statement as the Keccak proofs, different transcript
+        assert_eq!(proof[0], salted_proof_alice()[0]);
+    }
+
+    #[test]
+    fn test_verify_rejects_mismatched_transcript_tag() {
+        // Poseidon proof replayed through the Keccak transcript
+        let mut poseidon = poseidon_proof();
+        let tag_bit: U256 = U256::from(1u64) << 64;
+        assert_ne!(poseidon[6][0] & tag_bit, U256::ZERO);
+        assert!(verify_parts(&poseidon), "Poseidon proof with its tag should verify");
+        poseidon[6][0] &= !tag_bit;
+        assert!(!verify_parts(&poseidon), "Poseidon proof under Keccak tag should fail");
+
+        // Keccak proof replayed through the Poseidon transcript
+        let mut keccak = salted_proof_alice();
+        assert!(verify_parts(&keccak), "Keccak proof with its tag should verify");
+        keccak[6][0] |= tag_bit;
+        assert!(!verify_parts(&keccak), "Keccak proof under Poseidon tag should fail");
+    }
+
+    /// Bot A proof with the composition split into 3 segments, 4 queries:
+    /// `prove_sharpe_with_options(bot_a, .., 4, None, composition_segments: 3)`
+    fn three_segment_proof() -> [Vec<U256>; 7] {
+        [
+            vec![
+                U256::from(0xfu64),
+                U256::from(0xbb8u64),
+                U256::from(0xea60u64),
+                u("0329b913d70957b39cf58a4cf71990d268b08bb3e9147f9bae72183e2215273b"),
+            ],
+            vec![
+                u("12ada0401fc4f38dbfab281bb6e471b6adaabed5ff29abff42968f7adf3c5472"),
+                u("2197259e5d8a8366aa93d77a593c6acab823832a03d918889135c296641f28e6"),
+                u("015e4fe0ac336a2674d82456a1b33c420da80c880d0823832a689500553a41ad"),
+                u("0a2a43e2bade4c31a6b6c3d7ba844a4864d984940510eea4852ca5daae2c562e"),
+                u("1fe6407e76b47df1190406e24aa59568e7b12bd44dd347ac2167bc021caa0d2f"),
+                u("283dae492e0376301b18d951e5d4e0c4f90525818fe2214c1eb343bfa4f1de88"),
+                u("2c2918415bcc32008be9dabb3cb4f107ff218c22f4b27d6fc7f73000e01d6295"),
+                u("06af542fde59f10c6cecb4e7724303f6d12d61aec2d42a8ef1ab7df733510ce8"),
+            ],
+            vec![
+                u("0c3672950839e5d296259c6c47d80367e8f018a1d6439185509906a788af69b4"),
+                u("2845e9f102c9c6afa07c16bf1271630fb91b6a5055b193b6fa183043a2f0d088"),
+                u("1e4d8b6db6cb6393a3ce3d74abce976fcf79bef67812f14c6a77438da43e5aec"),
+                u("2f5bb12bd81160909711ab48e6e5564988064f0c5e312829cd2773ac22c3f9d8"),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                u("06b31363bd8de4c7439c9b0a0c0b5471cbc9045a7b848aae3fdd834673fbe8de"),
+                u("11c8533c53dd0519dda3d5986ddf8ba5b98cff53a7480d95905b4909e24df44e"),
+                u("2c5ac85f88d2f6b0d8d6213cb5117a2d11ad0b2b3e1936ce8d4a301ecaffa026"),
+                u("0fa19cd170d524025239a837cc44214050584cdbcb3d3cd0177dddd0b69d2b72"),
+                u("03af3031e4fa846f16508b094ad287bc8f65a2c726897e6e29c6b54bbd21a84e"),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                u("21a4f3828f55b8740f38deaa70fd62905ac7d2c6adb33610348dd4a3f3d5e118"),
+                u("27d571c8bc2e6e5029965750887d47cb0435aac8af0cfe438ffa655d4b2e893b"),
+                u("2974505ee55090df9951b26ca1c55a1ff46ee9d8d5f8a30534e534a99d002beb"),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("06d3f1ea105b962e00b80bc062b5cc91ad9735f6478178d38f83169535ac3a96"),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("02278111ca5b748d55a625572db0785ef30c3289cd7a405bf1049931146a979b"),
+                u("01f5dbce80f318aa16e12a39712784bc5b274f4f37c98820c9c6d73d4a325640"),
+                u("29e980124d491e20f6eb3ff1f40ad34e1bc1c8715e8f1e358ec45d55623905a4"),
+                u("203e15e09142781741712f6bd21d5e892a72a8654b33f6db45935df52d1a2914"),
+                u("0df0bb3aa4147ad4e196a267e78fa0f5db631f6f642da57a9598a0c36400f052"),
+                u("2b402631fa6cb721effad373cbcd00989655e18d7b996bc3ed15221643319641"),
+                u("0e607dfce13968c70d39aa52c29159bc65ee7e3c1f6c8c8f48a21776c6aedf92"),
+                u("1af4c5c98527952d93d7774f57b16a69c01388555f1e4156a4719cf679832ec5"),
+                u("17dd0c6b289ced26375e998eefeef201b6ee4d5499c6d605dd62e4d1fb473347"),
+                u("2131c5ffd340dc9e6115541050d09c56fb743e71aa7f6de7e98c3407cf592566"),
+                u("2ba48a44d86c4c34d7af135d980b2b1108ccecbd416509aa4fb7eefdc53243ec"),
+                u("021d61bffc06e89c263e92f5d01bbc17bae396a0d55f493e0bbeeaff11176398"),
+                u("1b5f1ad41182f3d89d1bbbd06d486fc1b432907271d28f7aa98f11ba6c095344"),
+                u("2ddf0930b2e9098349dc3d93f7007a22703e46a8f2ebc297dc94aade927b6749"),
+                u("1aeea2e700c2eb3f86a4b413c1e52d05266deefe9f63331f4ddd9e7be4185021"),
+                u("0e66a0df659e12b51a6c6d8e585d9720ff941792df279ac69f3615f15c19be36"),
+                u("1c2ff008579e1f3ea6355ee45b970b351a5577f1b0cb0d869e16cdd8b338bfe8"),
+                u("1bdc379139b7bd8114b9723771f4682519f53d042e7413916ee5eda77e70ba3a"),
+                u("2535dd7f89a9a685096f5bc87dca6cc6299fdbd79b3864de35033735987b9f69"),
+                u("19938197e3a64eb8c5a6d6f68e0275a07382b33ce180dd502d02035615ee2031"),
+                u("0ab5244ce1b92c11bf9053781d2ed99ee004fb2f5331ffc61867f8936a44ac25"),
+                u("04603cba39ff1c2c8bace75f9d3b89b2661c991ade1724e57239c3e16fe4bee8"),
+                u("1af4c5c98527952d93d7774f57b16a69c01388555f1e4156a4719cf679832ec5"),
+                u("0e607dfce13968c70d39aa52c29159bc65ee7e3c1f6c8c8f48a21776c6aedf92"),
+                u("2554396e5a95db0777fa0e152e25eb27989cd93acdae9930ed586fbbd65de5d2"),
+                u("298e445a2baf7bbb65b732f61b1f12c277be9baeafee8b13161851519d494b0e"),
+                u("00a8ba3d70e52c52ef6d3204dae3f912b33aacc306cb3e29ca0d81d735a23b15"),
+                u("27519cb33517f19e4923159f262fcd72a863bbfa63f914fdf46a8aa0a9eec2b6"),
+                u("0fe787a3e8d116db7d66cc349694c75f786bf9af7bf460795586762f3350bca3"),
+                u("14d9be2d2736a06a6047908e737e16f3496511848d6244caa4f4d02d6e3f2238"),
+                u("1e191ee88613ceedce6015a8070ec21d62ab41b3e4a4790f8c3aeda1cabd65c6"),
+                u("0b3c24dde04d2f06d2b10bfa13340208c356c4dd99e654d660d8c6cb7574a891"),
+                U256::from(0x12cu64),
+                U256::from(0x15f90u64),
+                U256::from(0x960u64),
+                U256::from(0x88b80u64),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("28637e0475c282290f0357eeb99350c6e457adfa0525f3e78beb1a1e66846a3c"),
+                u("302b3154c0c129e4a9f1d7048ddbf7d7a9262755cbf2a02f3dc57179257ed6de"),
+                U256::from(0x0u64),
+                u("24857da68bb281d622aac36c3b91ee5d277602c0284c86a23c726fedf60ac1e2"),
+                u("01b38000556080d2c156c1e57289305f88042d78132bcebf4e6222bb4d90db05"),
+                u("0f1d4e721c9e34be2578c603c7e6947b93e241612b5e4b4c1b8425536d237cd3"),
+                u("225825bf83fd51fad56008098d5894c4963cbdbe119c64e7f9eec9a5d0d6abdc"),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                u("0f43424ea822b1f1d6a6701903522fc5b0011c1e7b66f701b10e202061ea89f3"),
+                u("0e8c5492a42729b1b42a7668b4caa28fb688ef364b8bde0ba71593f00979da1c"),
+                u("1cff68ee9c8819ec1aaaa2a1062ae8d30c33d907f33f50cfd6831e90c09381f2"),
+                U256::from(0x0u64),
+                U256::from(0xc8u64),
+                U256::from(0x9c40u64),
+                U256::from(0x384u64),
+                U256::from(0x2e630u64),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("17b6264a6ffdaab6943308d8a5e1f245411ba9a3253919c06fb093aa4cd95605"),
+                u("2bd36584855300e7187521c8f09c0f7a120b8f012c8ed985d61f5dc3651d5558"),
+                U256::from(0x0u64),
+                u("013fd8fef62bc2f80b9ea2db7920b45cf0a876544f43f363092d2e305a346167"),
+                u("07d332f72f4aadbf7560694f7330f8613c8ba1cbe57830e0de7828c91888a150"),
+                u("279a5bd3c9df5443e347afbd74110841ef79cc4e82a173923767849c5e5e10ff"),
+                u("236a3938ef849d8f348dabc3e734b128ab47b4688b65d86c195c9387a547399d"),
+                U256::from(0xfu64),
+                U256::from(0x0u64),
+                u("1b3d57951dc6987a3f74b592e0dbc1193829d51f4a347681b92ead9a7c97d66f"),
+                u("202f14ca9e562829f08a5a9f3644b8a1c8dda55ef62e1d6d8e04114ca78d809f"),
+                u("034aa8886353a737bfd64c2a4c7432fd240ce1da03e7102e5ef129fd19326746"),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("075d215ebfd4e4bf0e771b85d7d3f295c493b782bbcdadeb0b752f3187ab6f95"),
+                u("1cd460b35ef2f537c33e9230aab620cc91eed9d6e163a43ca02c79ec67cddfe9"),
+                u("13d88555695a1523f23713ed198f30027baa87ca4f23d81da0ea7ec3e21e01ee"),
+                u("0bfb7f4c2698dacc4738ad1ed0cf8a5aeb61027fb74347848bcd1df7aa6fa5b5"),
+                u("090e6148255ed44d133ea8c9884d4685fb1987bcf3a04a68ec0d08811a2b45be"),
+                u("2f9f3b947fc72e98bf827a848bb4c247ce7cc7e678879a6cb732a36da3260423"),
+                u("021d61bffc06e89c263e92f5d01bbc17bae396a0d55f493e0bbeeaff11176398"),
+                u("1ed915a3ab918986341762e4aca0b912a3d05577b88deace8057f3f51516aca5"),
+                u("149eb1c5e6c607af5fd37db8c07e310ddebae4979911b73033405bc5e58ba599"),
+                u("1d7e6fb32134758f32822e7d0fb6ff539c298f3def3fdbb65a50ed8aa33a3a97"),
+                u("14fa22aad826bdff6da29c363a82ab1f06c4e75f1bc8531648cb7215baedba85"),
+                u("1b5f1ad41182f3d89d1bbbd06d486fc1b432907271d28f7aa98f11ba6c095344"),
+                u("2a24a9fd6f8bf46f040d43c41a7d8990b2e79ae2425de72113ef754b1e92242b"),
+                u("0126708cd94e114107d84efe42f32d0ff77d1958e7e052e8c3020121d395540b"),
+                u("00c2281776d7d2d71f5c47250b4f2f463c94fb525b6d38afd8e73fe363ff8df7"),
+                u("1aeea2e700c2eb3f86a4b413c1e52d05266deefe9f63331f4ddd9e7be4185021"),
+                u("07e9f07fec94fbce537bbe917620e301d771d02490ab6c960306ea979051d5a3"),
+                u("0f2ead7db2e796f40b5403f952f310d5b94580086f82686c04445a167368a283"),
+                u("15db13a6d155b96d8c6be8c56e22db57a3f2d29428213edf0bff914380a40b43"),
+                u("1247d759c39e87963698f4e03a41fdda893a4ea1a7a314f2220be363fc0baec1"),
+                u("2045d7ee3339d60b17cb38d2c3b97b56a827a516d28aec1e4b41fb5f9013e890"),
+                u("14477f45854b950a19bfc5f3aec7c9afc88ab321fa4ccd9df58fbc38aa13b8d5"),
+                u("0bbfd3218972d1e30b92c3638cb22fa9753fda5b4fea23efc89a828f6bc88228"),
+                u("2f9f3b947fc72e98bf827a848bb4c247ce7cc7e678879a6cb732a36da3260423"),
+                u("203e15e09142781741712f6bd21d5e892a72a8654b33f6db45935df52d1a2914"),
+                u("0227181b645a109041831db8a79f8045269f099c7b0689037dedfacd064cb5c7"),
+                u("0891cce43d3d26e4b812bb0ca4c6911de6da795f69d22a7a1cd160b1be9282d9"),
+                u("12af63804f4dfe5c20e405a706b6f6cc0b9544e0297cfbb31c9937baa08f236c"),
+                u("1ae189f8b9f25bc6728645b3921aef4182a848523565f4cdea8045d64f8da754"),
+                u("0df0bb3aa4147ad4e196a267e78fa0f5db631f6f642da57a9598a0c36400f052"),
+                u("2a24a9fd6f8bf46f040d43c41a7d8990b2e79ae2425de72113ef754b1e92242b"),
+                u("0126708cd94e114107d84efe42f32d0ff77d1958e7e052e8c3020121d395540b"),
+                u("00c2281776d7d2d71f5c47250b4f2f463c94fb525b6d38afd8e73fe363ff8df7"),
+                u("0e607dfce13968c70d39aa52c29159bc65ee7e3c1f6c8c8f48a21776c6aedf92"),
+                u("07e9f07fec94fbce537bbe917620e301d771d02490ab6c960306ea979051d5a3"),
+                u("0f2ead7db2e796f40b5403f952f310d5b94580086f82686c04445a167368a283"),
+                u("102b851406a0e045e228a9e72f26ecd693cb4d5e1b113cc1e3ad8259df4ee2c4"),
+                u("0154b282ef89f08f46329c1f9098b37c6f1201b344fffa6fea9a53b20114b74c"),
+                u("02836a144939ae0cab0354be87fabbe26929bcf055957ec1da7de58aa9404211"),
+                u("00ad41ed9ee702a069375858047515eb2979db1a13b880122f9e035b5bf01664"),
+                u("1eaaaaf2c690e66ab43b52e1c5d223cea81b77d29c6a3aab7f7431fa2177619b"),
+                u("04bea4686af3dcd2f7c7b2fcc088e6d04d1e77850d32e047112bdf1d2a055988"),
+                u("27b7e613b87e204473f60dda85c779c652fc8cd0794ec53851a1271107dc651c"),
+                u("1dfd329f292e5bb42f0ffde659ba32d80c41c9d1a5b625f569e7ecab1aca901c"),
+                u("143be298d4cd2d7086d1b2712a3377906256a832d078b90ab5e3cb02cfa1be2e"),
+                u("047f289905724995e8a71d978129f995ff15d25629d6c3c27ad2ece4bb5f6782"),
+                u("1ae189f8b9f25bc6728645b3921aef4182a848523565f4cdea8045d64f8da754"),
+                u("08dd5a8c9b27254c9ab1db91898aede61044ece231c9652876a381ecd57c5d9e"),
+                u("1878d8859bae4336bded05dc2e0112849f6058e928f0cf6fc6fd12b52443818e"),
+                u("136bcf1124c2cb50087d84c2344f43b66bc25d94628477091ce63f56480fb135"),
+                u("1137f0dc14f5a9aacca1ed4fdb1687e0178b0de7b94c4f3c8ae2224778cbba87"),
+                u("0e66a0df659e12b51a6c6d8e585d9720ff941792df279ac69f3615f15c19be36"),
+                u("2c02b61ca77108332cb3afeaee9fcfc30ead77e20f78666779e86bbeaa7409bd"),
+                u("15b399055162e7ac9dd758f81e25159afda5b0ee5c9fb686762f3663f5a7218b"),
+                u("30125ccd90a8a81fcd5921a1932493208faec08b92138bb09e5cc5c8273db01b"),
+                u("280924fc04817806400af41c9334d3678dcde6e03b0f8128a6089e804a897714"),
+                u("0b7b6aeda554bec1b6c53ef0ace60f6d2e5330e337cd4ed1d785c46b938e857b"),
+                u("218dcd2a2f0ee6df3f0dd34b98261db9f5962cdff7c89f7003eb577a41bfd3e8"),
+                u("090e6148255ed44d133ea8c9884d4685fb1987bcf3a04a68ec0d08811a2b45be"),
+                u("2f9f3b947fc72e98bf827a848bb4c247ce7cc7e678879a6cb732a36da3260423"),
+                u("1421d679256571f40c2b4dbe366db225d414a6ba944e6d288cdbb8477cae36c7"),
+                u("1f66dc7a5bbc827b30c6ea59e66dd103904c81696540aa989ffcf6076150901a"),
+                u("1bc65cc65ef88c34510ae3bf1aa6279fbe86749b7a1ddaddf409af4d39329e74"),
+                u("28a0e0326bc50736f9304a2b68268e6e57e59c221ebd9e97960e059709c99c35"),
+                u("14fa22aad826bdff6da29c363a82ab1f06c4e75f1bc8531648cb7215baedba85"),
+                u("232f48e2a5e770861d40cf504a820d9c349f1de21a4f33da32c467ce3adedec7"),
+                u("0e0182fde560760b7deadae957585299201a87539b271f8b05d31020055c6532"),
+                u("1b67c14185e7d909bf0b61459a88ed66ed51a139737f7404063644a46873a1ee"),
+                u("1137f0dc14f5a9aacca1ed4fdb1687e0178b0de7b94c4f3c8ae2224778cbba87"),
+                u("2d669d3485d4ba933b5ddfefdb5cf1c8bb969046b2ffdc9f5b1f0c510d30311f"),
+                u("2a61481e80a347300e176e845dee9adaeb59e8696be428c3dad9e3d497612214"),
+                u("0f2ead7db2e796f40b5403f952f310d5b94580086f82686c04445a167368a283"),
+                u("2ed27bbc3b8d5c4cdbbe4d7cb85b47d1bb2b8ab1dc517d54a7157f4320905167"),
+                u("145241914740f432fd35f4b1099abc25c01586b1464b5599e447005bdfefb45e"),
+                u("01692f7f623041bfb58f922fab42662cf59da68c060dcf08d003eb51e97377eb"),
+                u("0406fc3158b3420f4d21c5b21d2e0635c2ff6670051cf75de42381b35d468019"),
+                u("07502e5312e051c6e34c5fcf8f5931be0fb5cd8aa1a3c1dbc4e8c478357a05f7"),
+                u("1d888cce7c301dbb47057d8be08d685fe2b88fc9fb15ca764754afd7161b3fdb"),
+                u("04ae589cbaf62dee393413d573b7d0ff013a8d95024f69c17efa95a202cfcef8"),
+                u("2d6dca5bcdb0004f1f2d4a6a9fb1326a2caba7d24d6db52105e191338e4c0595"),
+                u("2f4f79d6aa4f982a545eb2973dd78995183836999345700b8ade7112aa496005"),
+                u("26dc35f314c3300fad16ba0d67f27ebd8e7eaf970c5d47abaa3704bd764d561a"),
+                u("07460b9fbdb0be899ebe339e19110f76ded81c0d965504da1fad3dceb103f3a9"),
+                u("01d2655a7701e4d7987eec56f490e4cdc39e26f829e5b61313c2fbbadfca58ad"),
+                u("2be15a7ee2cbd0853f035fc22ab93e0982fcba78f451e42e9bba1f228866b01c"),
+                u("19946ac03f4cb0f740d1bf4b8f81f2021b1cc20c6ab1494d3a3f25b1ab680809"),
+                u("1423a986afede17664e757e117a3a000859a1b48516529fa4cd9d6363103d75f"),
+                u("00f017f245e1c8bddc6eaaec87d75277f5bb8f835583294f9b83741e596950a5"),
+                u("212e0284240fb89a427baafb9de6638e72082e4e8a0fa854f95a8d1b7e6dbd5f"),
+                u("28096f6050a1cde806aa0c37434b40bcbf05cd6a07a885777a4537bfd35ed333"),
+                U256::from(0x0u64),
+                u("2f4d3332da6b76ea16eb59dc5d0ccf91e82a4587e937a0c7b1426e81c3ef92a3"),
+                u("1ddf2c6800b575fa978294010f676a8f44c8009d3328352658c19e92935ba982"),
+                u("2337ac3e36f25b64c1deb69032fe82d73808e924641d4ded2ebedd2d9a0d3c4f"),
+                u("0329b913d70957b39cf58a4cf71990d268b08bb3e9147f9bae72183e2215273b"),
+                u("227223bcf63c7063e1d84c7be774cf7db2d1a8eb56b7dfd2692872874c23727c"),
+                u("08e4db3ebdae302129289262ae1d6e5703107c3d4d6aa7068713d5d17b0bfbb0"),
+                u("267ed0a5523f0ab662f23b6809a51b8993ff1a31c0bd81d5fdada53b6c6202a0"),
+                u("14906425e0eb1fd80f50b226e2089e19195ea5cf0ba75d0a7a5a1c728f15008c"),
+                u("16dbd05c9d4a3cab4daa59e9cf5cff369af7ce35be0fc05f70c6c4aa372d3c17"),
+                u("1d9d28c89fdad6c2758d079c46e47d42f018237612a2d58e91620d1e48831e95"),
+                u("1d888cce7c301dbb47057d8be08d685fe2b88fc9fb15ca764754afd7161b3fdb"),
+                u("303df4c60f2766fdf0ae058bf61656e0b0c222842465613eaed7640e9e8e8c32"),
+                u("0aed5de3dcb6c2e468b0ddf40c7567a1e3f7b52c262df3e605dafcdeeafb4538"),
+                u("0c584a8daedd07c270a539c7b3577e4b96d80fea65bf8f341150c36b90d1b6f6"),
+                u("2918c1d529490d955aa7b9c801113d0449a1e5d3dee7d60f1fa62ea62d13459a"),
+                u("1474a4f58e743827bb86a7aee55b8e03b1725f08d999d9939a3549d3d4c6d1b7"),
+                u("01d2655a7701e4d7987eec56f490e4cdc39e26f829e5b61313c2fbbadfca58ad"),
+                u("155a166b415793757889f4eede7ad7c9a73a8e4ef9f65ecdae31dbec4e272a58"),
+                u("1fbfc7d416501a9fa4e91095eb4fcdbfc24a156c1f53f9b46be5629668fae960"),
+                u("07fb3a04b1bf93bb1487a18f0116450bd627afef7b48421ec5a0322b348d6b41"),
+                u("07bf503b71eeccda4e3645b266b65c06dd7a11b8cb51027293ab5a76077cb332"),
+                u("2fd092916c2de55e14db72f4ae3ccdd4bd6d4caf6ca0f1f10e71dae3be2ac1c4"),
+                u("28096f6050a1cde806aa0c37434b40bcbf05cd6a07a885777a4537bfd35ed333"),
+                U256::from(0x0u64),
+                u("2f4d3332da6b76ea16eb59dc5d0ccf91e82a4587e937a0c7b1426e81c3ef92a3"),
+                u("1ddf2c6800b575fa978294010f676a8f44c8009d3328352658c19e92935ba982"),
+                u("2337ac3e36f25b64c1deb69032fe82d73808e924641d4ded2ebedd2d9a0d3c4f"),
+                u("0329b913d70957b39cf58a4cf71990d268b08bb3e9147f9bae72183e2215273b"),
+                u("227223bcf63c7063e1d84c7be774cf7db2d1a8eb56b7dfd2692872874c23727c"),
+                u("1845b4dfe120bb3d14e59911d097d31b13201e6d097a5aed18724d44d85491b9"),
+                u("26b058e390632764791e2a67bfe93a05eb7db52a2cc4057b3c7eca69ad2c16b4"),
+                u("2aad3197985ee261e14460207c588548549d76929109c605d629d1d15c77a077"),
+                u("2c92ec7608ee5bf7f4c0c18bf1e2013ef039688ac6098c767af7c900ac417285"),
+                u("1d8b77b64d44f12dbd0226c2add9ac00dce89f1fb00295d77767bfaab6e5828b"),
+                u("119f7d2a84b540658185db82d68090564ae6f5a1ae717c8ab2d80724fe1e1e43"),
+                u("2d3a76cb1fd9f4bfaf68fa711524a676ea7bb7801e36b0819140b8fd74817a46"),
+                u("0b889820f1221f0e66f1bbe19fdb0a0d337ee87435a3b38e293b5b154c5c2e73"),
+                u("2a50b0c1c5ad02588089d4172745a81f1b90ee5012cc1a8d26620c1b3f4e9542"),
+                u("15cbdf75e5798aab0ca22a5f830b141d0c8cf6a010bc6834a32cecc18b2af362"),
+                u("0fae282b8836df983afc08d9fd6a8d228d31403395267bc2977245fb382be438"),
+                u("02af49fad4e7b02339311d0f545f60bdb918ebd493e93fc201ada3eb2e3f3d99"),
+                u("02b6eee938341182134abfc98a44662c983df7d3045f5d2590b588963a9b18ce"),
+                u("1db2a4a24a140dce794225af529d19bfc038b76cdb25e82b1cc994b82ce926ae"),
+                u("06ffc3469f297ca2b1aa8bf55cc722e76814d2621be1ab91271bfb28a09ccc80"),
+                u("0cf32d3639c30c166e38899bacb506986fc5822e501940dd2efcdd15afb0692c"),
+                u("1b85ae37371bfe1d14f51cb590fd947d74bc184b9b90740243a0ff0c0c7940c3"),
+                u("0b2f56734e830d1bfc324644208f22b611eafe90e97774b3b15cd1214121ad63"),
+                U256::from(0x0u64),
+                u("2f4d3332da6b76ea16eb59dc5d0ccf91e82a4587e937a0c7b1426e81c3ef92a3"),
+                u("1ddf2c6800b575fa978294010f676a8f44c8009d3328352658c19e92935ba982"),
+                u("2337ac3e36f25b64c1deb69032fe82d73808e924641d4ded2ebedd2d9a0d3c4f"),
+                u("0329b913d70957b39cf58a4cf71990d268b08bb3e9147f9bae72183e2215273b"),
+                u("227223bcf63c7063e1d84c7be774cf7db2d1a8eb56b7dfd2692872874c23727c"),
+                u("24cbdfbea5961592c2602fc91c87aa2caec7f1a0f8de88666285e1f33b9c309c"),
+                u("29045dba352f7f8a422f0824393f20452519456d16ed84ddc3d86d4181acc302"),
+                u("1ca37862382d2aab51d3292742b5cde11fd7b3a99713680eb81dd9452e8b1476"),
+                u("2a591c2e01191ad9afa93aac89fe42d903a7a7d19e291df56e8903ec46688b59"),
+                u("07502e5312e051c6e34c5fcf8f5931be0fb5cd8aa1a3c1dbc4e8c478357a05f7"),
+                u("1d888cce7c301dbb47057d8be08d685fe2b88fc9fb15ca764754afd7161b3fdb"),
+                u("22c0e3b25d931188450f32ffa0fef916415b2ccd6c5dd59acc0daa2b9ff892ac"),
+                u("28da6fcb4f586fe645c84b4ee638b839c292de6481b2df1eafbd1350a1456d06"),
+                u("0bd19d4087e01afc6cf4fc8b43a3faa470c03d4cc6e2137373782c53b1d6547d"),
+                u("046221aec77ad6afb5241e01cca4ac7d63378a865da608e1a2034ed720206ebf"),
+                u("07460b9fbdb0be899ebe339e19110f76ded81c0d965504da1fad3dceb103f3a9"),
+                u("01d2655a7701e4d7987eec56f490e4cdc39e26f829e5b61313c2fbbadfca58ad"),
+                u("2c9025a179f803b060509f5dcf53cf5b45b6cf871ea9660371f7d78d9687bc15"),
+                u("2a8e4c85c30896db3438bcc8f4b4c97a999eba16372921f49b925d20abd4613e"),
+                u("0d2a868859147a16412d535cea410a3545ce4cb2ace38a345d17367a39881807"),
+                u("2f54fbe6346d69a270d3ea3993d68a7070b4f3136c7286fa4eff0ea96edc7f97"),
+                u("212e0284240fb89a427baafb9de6638e72082e4e8a0fa854f95a8d1b7e6dbd5f"),
+                u("28096f6050a1cde806aa0c37434b40bcbf05cd6a07a885777a4537bfd35ed333"),
+                U256::from(0x0u64),
+                u("2f4d3332da6b76ea16eb59dc5d0ccf91e82a4587e937a0c7b1426e81c3ef92a3"),
+                u("1ddf2c6800b575fa978294010f676a8f44c8009d3328352658c19e92935ba982"),
+                u("2337ac3e36f25b64c1deb69032fe82d73808e924641d4ded2ebedd2d9a0d3c4f"),
+                u("0329b913d70957b39cf58a4cf71990d268b08bb3e9147f9bae72183e2215273b"),
+                u("227223bcf63c7063e1d84c7be774cf7db2d1a8eb56b7dfd2692872874c23727c"),
+            ],
+            vec![
+                u("0000000000000000000000000000000300000000000000000000000000000004"),
+                u("0000000000000000000000000000000000000000000000070000000000000004"),
+                U256::from(0x4u64),
+                u("46526ea6a38c6fadf6787155d7229c6be7556243855adcdffefa75555e8d5969"),
+                U256::from(0x2cu64),
+                U256::from(0x3du64),
+                U256::from(0x10u64),
+                U256::from(0x26u64),
+            ],
+        ]
+    }
+
+    #[test]
+    fn test_verify_three_segment_composition_proof() {
+        let proof = three_segment_proof();
+        assert!(verify_parts(&proof), "3-segment composition proof should verify");
+        // [trace, S_0, S_1, S_2, 4 FRI layers]; 14 trace OOD values + S_0..S_2(z)
+        assert_eq!(proof[1].len(), 1 + 3 + 4);
+        assert_eq!(proof[2].len(), 17);
+
+        // Segments that no longer recombine to the AIR composition at z
+        let mut bad = proof.clone();
+        bad[2][16] += U256::from(1u64);
+        assert!(!verify_parts(&bad), "Tampered segment OOD value should fail");
+
+        // Altered opening of the second segment at the first query
+        let mut bad = proof.clone();
+        bad[4][4 * 4 * 2 + 8] += U256::from(1u64);
+        assert!(!verify_parts(&bad), "Tampered segment opening should fail");
+
+        // Read as a 2-segment composition, the layout no longer lines up
+        let mut bad = proof;
+        bad[6][0] = U256::from(4u64) | (U256::from(2u64) << 128);
+        assert!(!verify_parts(&bad), "Dropping the segment count should fail");
+    }
+
+    /// Real Sortino ratio STARK proof (Bot C, 12 trades, 3 composition segments).
+    /// Proof: prove_sortino(&bot_c_swing_trader().trades, 135000, 4, None)
+    fn sortino_proof_bot_c() -> [Vec<U256>; 7] {
+        [
+            vec![
+                U256::from(0xcu64),
+                U256::from(0x708u64),
+                U256::from(0x20f58u64),
+                u("0329b913d70957b39cf58a4cf71990d268b08bb3e9147f9bae72183e2215273b"),
+            ],
+            vec![
+                u("2781b1727fa62138503e892f49d7cfdb4e98f4274314b2e71ee1511b953d398a"),
+                u("161e8c67e3429cb407169eaef7aee85053d3f2d42d0b9f38704b2b7427af2dda"),
+                u("29d35cf13ac74902ee3da50b86b7f8792c3667acec075b2387ad20cf412063d3"),
+                u("1b3655fbfb894b03f1124d675f4f0b28405212defd1315fe411ee4a0a6efd79d"),
+                u("165678dbde7dcd61b60cb3bafb463f0a6410a9a1b97dd95d10d4e9b487c18c4f"),
+                u("2e1440ed9a1c94787c60f5c31b109cfcc1ad73946f9625a587fac0932e009f31"),
+                u("1007e3b4acb507025f2238f760df3cdf99bddd5b5763144e2e6dff6e01c4b98e"),
+                u("02ad000be066adcdccdae793e1259e607abd9ad6dd850a868ca9e6563493a112"),
+            ],
+            vec![
+                u("17da9d6b7c6266ea74720a907bab3f6abccdc6056a92b755efa3f114045fb0ba"),
+                u("2cb9f945ad234d1d432dfa797d2427e631cf4108d14d941b6149ae9c74a865c9"),
+                u("2aacebb0cad728c70bcb9e28156be879e1f78811b57692187388078185026432"),
+                u("12895e646a30895e71a7c40954a91b2843e47ce4cc794df6ecb704f0a51afb07"),
+                U256::from(0xcu64),
+                U256::from(0x0u64),
+                u("250c64c17f03b01dd09491e211b7167669c3397fcd0590a7d4969ad4691d336b"),
+                u("26c137580386933136620cb7ca06379044b789c7a457898a9ccd3dbcdb9ceb73"),
+                u("10cdd07dfd67f468cdc713fe529d3f1b8ad2a9ea8bd25e45fbc5fa48fc2ac7e5"),
+                u("1dc8c4a74810d4be3fec2f43aec633845267d4901981010126ff4e7140894002"),
+                u("0322ba0b061013498e39977ff223fc232ee8aaca93192359046fa764dd0806b0"),
+                u("1ddd7276173403f0cbdea83fcdb88881c30e2d13c0aa273119ebe41d8b23f0d6"),
+                U256::from(0xcu64),
+                U256::from(0x0u64),
+                u("13f82d7eac761d9952815fd71c3c3f999fcd6392c19605de92c54b73a1296222"),
+                u("287142f37fe5529d8ea783f7f9cf49c100b89de90c9be0328fd9836360340c85"),
+                u("2f19bc6a67964d8496ec060c221427e8bebc3e3cb065fdee73037f860625f1c2"),
+                u("2f96c164344aa8f3c92223c7d8b903d977aa00dc93ec66e6b58b41b953b79ba9"),
+                u("29cc670c24360406c6dcb64296a9057c1c63363ac7127c1927f8c62a364ed6a1"),
+            ],
+            vec![
+                u("2053caa4ab741b50b15506b4be482f010001fafc52508a178be4ae96d91b23c2"),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+            ],
+            vec![
+                u("03f678becbf10c92359bbfd6cb0e27a3d465b55d4829b42d43c67ca49ce4dcec"),
+                u("2f0c24cbfb294733f3c0e55ae725a02856b0837046083a0415800ec1a664d8cc"),
+                u("114475a8bfd2974e4c6820d4124ca11e1dc9c4358cae43de143e3bd284c37536"),
+                u("15b0bbb384efbbc0d0874b7ebde7a1d23b79cee418ddfe3cc443e339aef051f7"),
+                u("235b49c56c684e7cde67bfebc2d5795b3bdce51dc207b638d7bcbe4d5fd3dd99"),
+                u("1555c5953a0cb0dc31d00c46c7bfae6b930f7adb7129782fe38d9a19ebb5c891"),
+                u("2777a49ba60d03e4a0a921d38fdc3a4133314dbf9197662df12b49aaa0c24abe"),
+                u("2e479158e612d3937094068d980640cd7cfb71782d482db504bcf093cafc2bb6"),
+                u("115e130f342e1b5365512252b94a095c696762af04f79cd979e5a3aece0bd878"),
+                u("2190dc4b35a5e2981a5841c93b74f112aa47f30d6a1e9330bdc28d1264ed757f"),
+                u("151fc5023590c6e96219ea80bb38b9be52ca4f39c0ad4d6bb3c2b61a2fa8e98f"),
+                u("0fb5535c99ed0f9836360355129b4e38ecca87831a796bd4b45715b475f53b47"),
+                u("1b788b900e853dc872aace48ab960687e31cbd64cb9fe1dd39ff9b09b8b52b1c"),
+                u("0951fb0f13a024276476560c5c54ab8af1b505e7e17790377dd869eb511f46ec"),
+                u("27e1cdf24172a28e6b9a0df8c8bf1bf0b56c7feaeb1b464e090ee648fb12964c"),
+                u("2ddd68024aad34e9a5a31a685f235f1dfac03f4cd3c44d94ecd953f570abe028"),
+                u("1541fb009559c7252fd80dcb86aec77addf9a997529d7c107beb75dcf90a5e22"),
+                u("2656006ee3568b9c57f4fd4999eedb266e2d21c6a3f232595f4b3c585303a86c"),
+                u("04674aa1844a9fa301213d3bba26465a9a1c1b968befb983e37136048cbfcdfb"),
+                u("2b88fd15371298ef69ceef56a44c961daf965dbda269f51aa0f2347ba0ed792c"),
+                u("0def385f884e85e55bc399b74c73007d43df568edb840e44d5dba3d0c1d1a635"),
+                u("05a9db2b79f65bb92c531aefe696579ebfd2f6fb3a2c66d2bd8e91682cf72529"),
+                u("2820b1b96516bd44a42e85ffcf36f6a9099330e5dd7a4ce13ced1928b0155441"),
+                u("2d9e843b27091a336d0ea26158ab8465a6998e51e1654701b8fb2115bba92233"),
+                u("0331df78b601f40792dc872b82ce36da663ce0a1a154cade122471fd8c914fd3"),
+                u("04eb711c8bf1fbbd2a1d93faf82a9e0ec28a584329943d20d76688676f46d82c"),
+                u("2abc488e5239f5e6424e0d6663dde98de0c5437acef88a4a8dd2baa23eb7af8b"),
+                u("2feca45773090d896297be805cf7c8c2e8430e88920243374d087d0bfc10225a"),
+                u("204a610c0c740750f9561c35931d14f7c72b4ce3dbbb25d4e9ce5043473d2be7"),
+                u("255266396b78938b59065db022519de89ddf23237358ef1050aad3008210a74d"),
+                u("2e479158e612d3937094068d980640cd7cfb71782d482db504bcf093cafc2bb6"),
+                u("2777a49ba60d03e4a0a921d38fdc3a4133314dbf9197662df12b49aaa0c24abe"),
+                U256::from(0xc8u64),
+                U256::from(0x0u64),
+                U256::from(0x708u64),
+                U256::from(0x4e20u64),
+                U256::from(0xcu64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("2a3e9e6aaad88b00d06f7d612d260dc4469bfba2e42451ccd12d2fbf35a5e247"),
+                u("12e83ce1a6d640f27182439c5381f53d30549e1b48766aaef7958ff768dac0f6"),
+                u("019319ca8e6b02c71ac05f08915a553c83ea6e570612ef4bca2dc922f24abad0"),
+                u("1352a2d9df04a58ae91f059b306103a99130ed13104f2deaeb0a614f79110657"),
+                u("0be1d1d11d7c2c17f19bb35db8ea1f85f177ca8a94f56391b0d4f5fdc3d84261"),
+                u("1bd12be720224cc925a7f926d8ebc7bf507595199515d2c1c5275ad52f908094"),
+                u("118998392df3d1f3f9362ffcf5fe2d77dc67fedc4b685a5e082211db538dc241"),
+                U256::from(0xcu64),
+                U256::from(0x0u64),
+                u("1019e4cbcbec602bae57fba3e5a6ef58f085d69e86c5945c3f8fef17707b6356"),
+                u("1c5c946fbfbdd84c298c3b92ef3f7b59b20c2260d86d01f036edb828737d01d6"),
+                u("2fb9ba7961d07f9d83c10a449c8fa2664613c983f89343977d7a90c716c312e2"),
+                u("30401ef1690705badfd4357e127c3c70ddaabac056255346218420c2cb11685e"),
+                u("0901d040b045e23df4b15731c4e9c76ea9c368096266dbb7f9d93ee1c1459dd5"),
+                u("2b830298aed326b5828d50207789aa1957d5fb88581084b49d7f8bb42a0a9fed"),
+                u("2bdbcc97c240daaaf6524337fcd7c9a28027e24bcdff6c893ae111db98575a95"),
+                u("082d6e1397598c0e60c551f00fbe8bc0304d07d41878d3fa8c56426c09efdb82"),
+                u("0bbdd3bb7d5937d67c81b6308e03dc20fbab8ad32a09d0c37feed6f15239243b"),
+                U256::from(0xcu64),
+                U256::from(0x0u64),
+                u("2dbd4faa975555517b5a288c63b6a7f630af037d48f347395455a7473acd0378"),
+                u("1339a5d29417263e72850668516cd04cce5639ae7b569aad853aca23782be4b8"),
+                u("2bc37a21e41db08f058da3b819d889f84699370d49289adf9f20d7b865746ecb"),
+                u("1c050de05132bdf0863e452784da1c19b86bb46bc26151a528a24ca8d78e1b8e"),
+                u("0d5941570b7d79312bd519a8bd576d95dab586118fe0c4ca95eeda5aec92131f"),
+                U256::from(0xc8u64),
+                U256::from(0x0u64),
+                U256::from(0x2bcu64),
+                U256::from(0x2710u64),
+                U256::from(0xcu64),
+                U256::from(0x0u64),
+                U256::from(0x0u64),
+                U256::from(0x1u64),
+                u("1e6114fab5378a10d92319ce05e84af2539e369d25413bcd8baf9d57d4e2f2b0"),
+                u("004bf4ff21537b90bbf67490a6faaf02f607ffdf59df522460822cbf9b848238"),
+                u("26faf589e805f612c7d24b3f4eb4a52eb9641873f8191d4383d766236673a5cb"),
+            ],
+            vec![
+                u("1db85b1d6f78cff03b9745b5680674a8463580da848846ef0f15aa5682430632"),
+                u("1eeac35a2fd824feeaebb2609fc885f1152411fe156a455947f28bda1a2263b1"),
+                u("24695929fa3e224e1c52a2010b3fbc0f6d4e33fc1566c99130b7b08435fecfe3"),
+                u("04304809fe59c011aaf0c6290dbfa82175d7071b9ec8be1dde63636d3e4a5e89"),
+                u("241777398f26e696113502e28f4127deac3e19a19dee6f84ae7eb6c9abafd307"),
+                u("0da6b4ccf5a9728a6796ddf371f36fc5c97a0b7ba6198c837140c1dffe581e52"),
+                u("190a958557c9092a469293afa52e858a7b718623becf2e8407b5575f1bf3cec7"),
+                u("06ae7ab7dfbe7e6c76978bf4031ff88f242f44add649a8866a3a4554a9578ea3"),
+                u("277bd47d3103309a3eb27c4055196d1a39dc1791206ed6d780e52a6c9b45851b"),
+                u("1fa577d538c04ab23375ab3393dc899bb69babc7bf7df0e5bd5e7ec9558f02b0"),
+                u("1aaff7bfdb820bd3df4f5267db49f1168024e292495a001079bd736e7eb58c78"),
+                u("2b356a5656064c986ea820b1959ed7ea9729986958563d7acbee71a58ea8e00f"),
+                u("177aea616b859c1e200b22fe347f92b9cce739cd20f20193dc07367ff36fda14"),
+                u("2398bfe89a18ee041eb322794428b027ff99326f8fb5aba860fa5b5dfe79e8f2"),
+                u("28476aecd5cb82ae7bbbb20cfb418f074d9a6f0a5f39825545b99e0e6383549a"),
+                u("088da48ed6a736e53324fb925d3201af4cb37e5b1dca5dc0d03e35cacb6e807f"),
+                u("1ecbd5a70f8c0d995838d8a05f68dc3b043aeb8fb85f33e3a6ca8f56751492b3"),
+                u("1022c7266ff40b373a0aa2a2624112ee98b692b9d6fb4762dce4f1bd60f070a3"),
+                u("0073a7dd0fbdf59fe2dc3c6a1cb74228d2affa17f388580494cc7e47a907842f"),
+                u("0550c8ab2f82c308f85ff871f20cefa8d2362c625583445b5f274023e43c45a3"),
+                u("1ba9e2f5eef8f7d4f2502aaed79e366fc2dcd61a00116995e81500af35f9cd25"),
+                u("0db9d3c68489eed3eee03d5c9d1c608dd2bd2f407e13e29d2831056d10e1bd16"),
+                u("0c3e124cfbde2ed6b13d602d97df27238a0291c0b5328a9313a3325785061f54"),
+                u("0da6b4ccf5a9728a6796ddf371f36fc5c97a0b7ba6198c837140c1dffe581e52"),
+                u("24b72164b2cc078c1362fce5cebfa7221dd4ddf9661abb90a50484de608c598a"),
+                u("123ef2eac5dc34d0109da8f333bd67516889d571f971cad58247b736407a047e"),
+                u("302dd9cb2d46bcd02766aece142c6c3fb4b298635bf33424db465a1a33e695ed"),
+                u("25458fd7a5a086930aa17645e913885c13bb2ced4590a820b4e3519168b249e4"),
+                u("1501dbd9fa782274794154e6a52ed7bf1ebcb5a4ac1a15facc4bcb69844703a6"),
+                u("10f14f0aabb8c91c3328e5d5441c9dcdbab4d06ed358e696f1c1009ec4aca37a"),
+                u("0664040cde2754c6c71318fb7bc5cac5a2f79f02a66cf45c6bce7713760a852b"),
+                u("256e5887ff164c8841bfe1550b2290932e3150841d61c7fa2540e5447038d0c8"),
+                u("0e86d7e99f10201f5ebc4e6d36059896b8ed78d877640fdac554aee2ff31c2f6"),
+                u("2d9e843b27091a336d0ea26158ab8465a6998e51e1654701b8fb2115bba92233"),
+                u("12af0b073693fdeec376594ddd31350472907854170054e4e43bea7fdba81cce"),
+                u("079ce36a4529d780c947787f3926ad3c091b3efd2b51f4027f5c437721474cbd"),
+                u("02b78b80e891fe3e0dbe5ecdb802b393f5d49c3a7bf99f4c3ab8a4d874cfa31f"),
+                u("17214414fda09f886d27ed4530bb215725c64b1d99a4306bbf485b8a5273e7ec"),
+                u("2eba0ebd171be7c9ee20431e14f375bb17705a55c17513438e2f7d633fd8ecbc"),
+                u("04c02f2a38831a6d0b5b2a9d4da9a57cff47bbd1a721a14ef30fc1cfef05c6e2"),
+                u("13078ef9044c5fa4d76aeb4c968021f2459398ea4adcc4d8adeca60453c34643"),
+                u("16b5253d474d4f3834e9f38ae170b93b2354f07d6b310c296424f9fff45c1aa5"),
+                u("13fafdd4af264e953d2416a0fe4ec440aec0c33851a518291278d60aee567f58"),
+                u("1a8ed986b3f63dc316b0325656842a10f3429659008589e38aecf958d5e7da3a"),
+                u("204025b078688a92be4499da00b2392a8e65c7e26d4fed1e4b597142c36aa7b2"),
+                u("25458fd7a5a086930aa17645e913885c13bb2ced4590a820b4e3519168b249e4"),
+                u("1501dbd9fa782274794154e6a52ed7bf1ebcb5a4ac1a15facc4bcb69844703a6"),
+                u("1e97fca01f898e4221bd4b199b2e5372c707ea1c0a4f73089d6bf600d057694b"),
+                u("2633f57047dd313ac02207011a567e98ea5113728f825062ba21cf7743a000d4"),
+                u("0eacd6d188a0266f2b3a5d5724c22345db18fc337ce28608aa1940b67ae3ff8d"),
+                u("0e86d7e99f10201f5ebc4e6d36059896b8ed78d877640fdac554aee2ff31c2f6"),
+                u("2ddd68024aad34e9a5a31a685f235f1dfac03f4cd3c44d94ecd953f570abe028"),
+                u("0e7e41c3717e7ee2e696e076dbc81fe3b391d794cabbf88180879a3193c8f495"),
+                u("1022c7266ff40b373a0aa2a2624112ee98b692b9d6fb4762dce4f1bd60f070a3"),
+                u("220abff1b5810444c075a80a6e00a36127d57f885446bccd84394c18207ef7c4"),
+                u("2f8bbcfa9080932542cd33ba5d362dfc1715e2a9299d2c158d49c42cf882fb2a"),
+                u("03d3f560f80e98f7f3ef1ba6649c5ae0bc5e5aaaa298dcc2d9655388a3cadf28"),
+                u("04c02f2a38831a6d0b5b2a9d4da9a57cff47bbd1a721a14ef30fc1cfef05c6e2"),
+                u("13078ef9044c5fa4d76aeb4c968021f2459398ea4adcc4d8adeca60453c34643"),
+                u("16b5253d474d4f3834e9f38ae170b93b2354f07d6b310c296424f9fff45c1aa5"),
+                u("1199f72b2da57fa2c806a5a40c6ac7c649dd1a92206a9378c609061212f99fc6"),
+                u("17a35a720f5d420efc4775ed0514e70e4c7956e0386833eac7c2171448a18328"),
+                u("302dd9cb2d46bcd02766aece142c6c3fb4b298635bf33424db465a1a33e695ed"),
+                u("25458fd7a5a086930aa17645e913885c13bb2ced4590a820b4e3519168b249e4"),
+                u("1501dbd9fa782274794154e6a52ed7bf1ebcb5a4ac1a15facc4bcb69844703a6"),
+                u("1bcb36afb0a39a351e83e8152be88cf54d23929b484677016d9088198abcbf57"),
+                u("1328cf40b8815a11926e3106edd9417651ce59476c3dc1bd38804ee8bd57d489"),
+                u("256e5887ff164c8841bfe1550b2290932e3150841d61c7fa2540e5447038d0c8"),
+                u("0e86d7e99f10201f5ebc4e6d36059896b8ed78d877640fdac554aee2ff31c2f6"),
+                u("1ccd42f2d447006925c7e718492f21023b455894275bc590e1c80edfb04ff5f4"),
+                u("0c1ef2a8c1649dfdf2b2a4056737677fbdbfa1f322014b0f816baa0fe9c86f08"),
+                u("079ce36a4529d780c947787f3926ad3c091b3efd2b51f4027f5c437721474cbd"),
+                u("0d4203e6683d347b17baaf7895f59ada56d18202c4126506076abbd216ccab71"),
+                u("20194518e8157500f1ea79950c3db5ce9ba98581ae62e5c835c34d1941920bac"),
+                u("0e34a3fa88594dc2514d2020b86a97e7919a5cfd3777b5a9279fa535fa21dc07"),
+                u("185d468d7333540f3a41ea2d33c788dcddaf46ae426618c74577ff938d7fc090"),
+                u("2e609c67f8b2cc61310257fb7d96f9674ab828d50ad871558f3e5d0f60b0eb4c"),
+                u("27ef3654d8d26464d9b56f629731cf5c2ea5efaa898d3eba7d6d6786ac24ea08"),
+                u("22ae0cb8d587b4a90caebffba6102bf4e7b8d41193a681eb99cd6476cb1f46e5"),
+                u("18db77ff42dd6a8c7fd631775266b3b9b4545c2260f039843632a39b55ad57bc"),
+                u("2ff6a36d39f09a84ac06a2242e980ab1961a586b476b533591d110103a2c958c"),
+                u("1022b51b1863ad860e96e0c618ca6a966adf50a8ae06ca91c12e467aab1daab0"),
+                u("0be03dce2fc30cb792115df1342927c2df5372e69c23e2358d207f9c61f04a14"),
+                u("11ffda50db219496d1edcd85de9ee9aa3f3ec52df11b9f115e90d99e4a027408"),
+                u("0db0476c19ee0f1fb0d86f3f8fce6e8c87738867f8eb5d13c28fe63b2a97de2e"),
+                u("0d26f20dcde610d1484820f462bba6a6d1958e2a115fdf8fa9ec0dfa70194c2d"),
+                u("1b3edf914c6393e21d105ec8ee64450aacf0b41628a31407a165fcd0b61986d2"),
+                u("2b4828ed536cb098446809f7caac75620662a6329d0ee7253fd2fa53acfe751f"),
+                u("2026b539a987dacefc5eb82e0700993b00a797b34979b45730159deb13f860f3"),
+                u("112f0072fe12896bde6eba647053cb1a9e35a96a6a644ea3f2b41eac9db3c05e"),
+                u("19ab77d0522ee2fc6828eebcc0782d464734f30c03389e6f4a2824a56d05ffac"),
+                u("145f4115839150add781a994ef5ded5f42bf36aecd661745ba3d89c436b069c1"),
+                u("1862433fca380c2768adb96558aa7e6e845759dc984e6892b7730741946b0ea1"),
+                u("17dc06694f2367d303935977d4caac53ab8528f160f2caec76c5ed9269c78089"),
+                u("0e14eba17d2e93014ad8ec87999792fc8bfc15684b507ad53cd742cf8a504f68"),
+                u("0e7aea267f09a7a1a1f83b7be13f964e70a6e4410ca69f5e81832895f7291c6b"),
+                u("2567a44471b59669c1bce9124b1479f0cc84e042609f4fa16195abd2c29020a4"),
+                u("12226a84b9d3b9d5b713aac6768a140a54152ff59d05ed3317438316b4b59a71"),
+                u("1676ffb1b4af8a6f373dc496d1d19e690494d033b829645afa29c79d1fdf21dc"),
+                u("25dd7004a95d9709012611dfcc8c7ca25e2bdc677840e8075a42d6bb60a9a9a0"),
+                u("22993a985c9a309ef51da2158c019ab0566ee5843dc6b818a89eb7d06ae4817e"),
+                u("27ef3654d8d26464d9b56f629731cf5c2ea5efaa898d3eba7d6d6786ac24ea08"),
+                u("1c6d6b4c311514d16149793936722c9a22620bddaae3936f8998a39f64aad7db"),
+                u("034a343c877c02734e4c485abedd67a82ce6c34becdccb71f9f90a618876525a"),
+                u("1ae68a5c9d847964d91d4d48b4588d1afa178b669a9ee3a68a82dccf683aed9f"),
+                u("24f3c6100f9825a0829f83168fa2647a80400ddee8857d19c9d2b10147061d24"),
+                u("094a48db9223e22ac534dd5e17d18dba6571b352cd05cc642cdd0808d940522c"),
+                u("11ffda50db219496d1edcd85de9ee9aa3f3ec52df11b9f115e90d99e4a027408"),
+                u("1ad5aee6eeb5e2eeb0ad2914637333442afed5e38bba3f777434c26885e38a93"),
+                u("239f2e77285c8ed050d9f0d5fdf2f0bdd609ab9a437502ccb793a2076133ad5c"),
+                u("1e01f37ab29cca0cacdc08f0b946ae5f39a6b131d030e9393d180440bf59e0f5"),
+                u("25b315c468551167be10f56b8ec3e87f1cac82f4d7405c1a33a98afea79ce53c"),
+                u("2d3abc23f00dc0e1a81ac38b12e5830e91eb8654539aefb85e2f8e145d86a050"),
+                u("112f0072fe12896bde6eba647053cb1a9e35a96a6a644ea3f2b41eac9db3c05e"),
+                u("0952c6f424ab496dd5a8951e90cc84694c620996ffb079a18d500dba3c12be4c"),
+                u("0dfe68a1435f1af1a0744c14a66cd3e4a2be44454bd2c8c8e8ab44d89369258b"),
+                u("02d73337eb0e4662219f2ea7a18cf2cf8fd5f5aad99fb6f5f873c05f459e2af5"),
+                u("24d61a116b409a00580fce86046364952d4257c789fff86cb43fe9f5e7e84090"),
+                u("00bacfa627ba766be2271ce4bbe5884ed77701eadc01f57cd3e0089bc5729ea3"),
+                u("0e7aea267f09a7a1a1f83b7be13f964e70a6e4410ca69f5e81832895f7291c6b"),
+                u("1c5f65eb7e8d1cfa892e4e96fc889afb3a1d4e8266161b6d1bdf687b323d9215"),
+                u("035722aa5eece8516b0ab96fbc9c74b2876fb18a5af33831b088fec5801b0f20"),
+                u("0916eda4a81d01a0a97037ceda8d6cf415013b5ba4524c30ed506c4e22c5a916"),
+                u("0ad3fce397443c8ddc0314687291413c51204f873072ce482120403d81145dbd"),
+                u("03a09378882f162fc12fea8f29a13c4db0a4a4711d8abfb26dd2554c57506d54"),
+                u("018011a77ccce58e6382d98683c535abab63e4f951b72748b51f8d2055ecc746"),
+                u("1ece9839ad03e0f39280388e3488cd05f6cc665c378e58a21ca8b4722ed7d10d"),
+                u("0335522f20dd1b3cfd5e732dd2416a7d744d750aa162fda903a3e7db1054bee4"),
+                u("1b3ee840203d1efd51c3845c25afeaff6480f6857d53b9df5ae37c6540e946a1"),
+                u("2a7f74b7e606efb62f89274f72cd055b224e3e5ee69ed97f9e8c8cfd398f777d"),
+                u("12dba9a61f65622c0d39988b3dd1bd061e9937dc550691833c38acf0022e315b"),
+                u("02d77ee2b65627605c65dbecb9cc9b9ae27fd309b926399a7f7a5c8a3293724e"),
+                u("165f5cb8432edc15f05cf6e37c60e27187b4f5d8826140db1a87185fbac9d6a0"),
+                u("076995db8ce23296b81aef8da40291833e968ed420b62b5baa1e019d6912a193"),
+                u("0c2922d5f8492aecdabd3c73945a7e7a1a73948d6b8853d961ef4bbd7edb6113"),
+                u("2a42f123adb13d470dba822f3565fef4327a3f6b75aab77eba58b046feca3c3b"),
+                u("2f5c4241ec64f648a6eba84808a8c3eddd02ecc93051c2e69634e388e28b7a98"),
+                u("244fa20ea30e68e60c20d07a5a88b4f8587d1b5f7e59650160ffba61bdb8e8b8"),
+                u("2d6c52f304a16158ded55a6051b4e08d1c4d8f9eb30e3599f7c134110780544c"),
+                u("1f3bbea1fe2b4ef2839a5601155790590f5b4044afa905593835a4cef7a0ae49"),
+                u("2a3836a5787425dbc31fefe880bde9cbb857c233ea9cb699077a1513610c1228"),
+                u("246d9fa5efaa91cb09fec3925ea2ff385d1ba39d36f07b91683ccd3b57a762cd"),
+                u("2df8c195ab6748536a5d145f6fd3ea5e021cf5257244b873096ceb5466479cdb"),
+                u("2cab64c92df20a350171fc6d1c5d0c747f47cecded0d7213308d19c923e17226"),
+                u("231583ea39fcd4d2a4780e16d4e832bf3fe6b9e0a2ccd03f979f82bc604ccbd6"),
+                u("1391ae1a29b976744495a429bd26a095ff24313abbe897bd94858dd895c5239c"),
+                u("17ae64b0a92211436292eccb3a918d5d520700f4181258a319d1febe59b0f405"),
+                u("0ad3fce397443c8ddc0314687291413c51204f873072ce482120403d81145dbd"),
+                u("03a09378882f162fc12fea8f29a13c4db0a4a4711d8abfb26dd2554c57506d54"),
+                u("018011a77ccce58e6382d98683c535abab63e4f951b72748b51f8d2055ecc746"),
+                u("119976a299865e5db0755dd17b4fefaa2cd976ee35cc7b776cfa2401f6e00dfd"),
+                u("1f156b625ca196fbb7fb980247bd8d706ef49f6b5aedacb5eb37a20148150772"),
+                u("141befe68ee46e492a7161faf910be57221febb700c3d17038305948296a37b5"),
+                u("2a7f74b7e606efb62f89274f72cd055b224e3e5ee69ed97f9e8c8cfd398f777d"),
+                u("12dba9a61f65622c0d39988b3dd1bd061e9937dc550691833c38acf0022e315b"),
+                u("02d77ee2b65627605c65dbecb9cc9b9ae27fd309b926399a7f7a5c8a3293724e"),
+                u("15fbc8033ed1872a95617d210af112ec015f4b0c497ab7381b34fb9c0dbea532"),
+                u("02f96f082be55bfc677bd956e8f546b0afcbb9ac3188344ec7be6f7f0935d4c4"),
+                u("2e732a27f2264879bb2e8d6b470d281c7a59ca0745e4754bd813d35cb2571968"),
+                u("2a42f123adb13d470dba822f3565fef4327a3f6b75aab77eba58b046feca3c3b"),
+                u("2f5c4241ec64f648a6eba84808a8c3eddd02ecc93051c2e69634e388e28b7a98"),
+                u("244fa20ea30e68e60c20d07a5a88b4f8587d1b5f7e59650160ffba61bdb8e8b8"),
+                u("09e224c5623dde995b7cc7c899c2171641e3f477976d360fb1a8e41e8e7ba609"),
+                u("1c35a6bddf3de9cb65369a0fdfda306eec96ec5018e441bde1f56343ec04ce39"),
+                u("07f827a790911c739d19f2837691c163d26dca19cc1c596a4a3d34383f962d34"),
+                u("246d9fa5efaa91cb09fec3925ea2ff385d1ba39d36f07b91683ccd3b57a762cd"),
+                u("2df8c195ab6748536a5d145f6fd3ea5e021cf5257244b873096ceb5466479cdb"),
+                u("2cab64c92df20a350171fc6d1c5d0c747f47cecded0d7213308d19c923e17226"),
+            ],
+            vec![
+                u("0000000000000000000000000000000300000000000000000000000000000004"),
+                u("0000000000000000000000000000000000000000000000080000000000000004"),
+                U256::from(0x4u64),
+                u("46526ea6a38c6fadf6787155d7229c6be7556243855adcdffefa75555e8d5969"),
+                U256::from(0x2cu64),
+                U256::from(0x32u64),
+                U256::from(0x17u64),
+                U256::from(0x10u64),
+            ],
+        ]
+    }
+
+    fn verify_sortino_parts(p: &[Vec<U256>; 7]) -> bool {
+        verify_sortino_stark(&p[0], &p[1], &p[2], &p[3], &p[4], &p[5], &p[6])
+    }
+
+    #[test]
+    fn test_verify_sortino_proof_bot_c() {
+        let proof = sortino_proof_bot_c();
+        assert!(verify_sortino_parts(&proof), "Valid Sortino proof should verify");
+        // [trace, S_0, S_1, S_2, 4 FRI layers]; 16 trace OOD values + S_0..S_2(z)
+        assert_eq!(proof[1].len(), 1 + 3 + 4);
+        assert_eq!(proof[2].len(), 19);
+
+        // Wrong sortino_sq_scaled breaks BC3
+        let mut bad = proof.clone();
+        bad[0][2] = U256::from(135001u64);
+        assert!(!verify_sortino_parts(&bad), "Wrong Sortino claim should fail");
+
+        // Out-of-range claims are rejected before any hashing
+        let mut bad = proof.clone();
+        bad[0][2] = MAX_SORTINO_SQ_SCALED;
+        assert!(!verify_sortino_parts(&bad));
+
+        // Altered is_negative opening at the first query
+        let mut bad = proof.clone();
+        bad[4][4 * 4 * 2 + 6] += U256::from(1u64);
+        assert!(!verify_sortino_parts(&bad), "Tampered selector opening should fail");
+    }
+
+    #[test]
+    fn test_sortino_and_sharpe_proofs_are_not_interchangeable() {
+        // A Sharpe proof has 7-column OOD values and openings
+        assert!(!verify_sortino_parts(&sharpe_proof_bot_a()));
+        assert!(!verify_sortino_parts(&three_segment_proof()));
+        // A Sortino proof parses with room to spare but fails the Sharpe AIR
+        assert!(!verify_parts(&sortino_proof_bot_c()));
+    }
+
+    /// Proofs over the minimal 2-row trace (log_trace_len 1), where the trace
+    /// domain is {1, -1} and the last row is g^1 = -1.
+    #[test]
+    fn test_verify_two_row_trace() {
+        use stark_prover::mock_data::{GmxTradeRecord, TradeReturn};
+        use stark_prover::sharpe_trace::SharpeTrace;
+        use stark_prover::sortino_trace::SortinoTrace;
+
+        let trades = [300, -100].map(|bps| GmxTradeRecord::from_return_bps(TradeReturn::from(bps)));
+        let parts = |p: stark_prover::proof::SerializedProof| {
+            [p.public_inputs, p.commitments, p.ood_values, p.fri_final_poly, p.query_values, p.query_paths, p.query_metadata]
+        };
+
+        let claim = SharpeTrace::generate(&trades, None).compute_sharpe_sq_scaled();
+        let sharpe = parts(stark_prover::prove_sharpe(&trades, claim, 4, None));
+        let proof = parse_sharpe_proof(&sharpe[1], &sharpe[2], &sharpe[3], &sharpe[4], &sharpe[5], &sharpe[6]).unwrap();
+        assert_eq!(proof.log_trace_len, 1);
+        assert_eq!(proof.num_fri_layers, 1);
+
+        // At the drawn z, N = 2 and g = -1: Z_T(z) = (z^2 - 1) / (z + 1) = z - 1,
+        // and the boundary denominators are z - 1 and z + 1
+        let pi: Vec<Fp> = sharpe[0].iter().map(|&v| Fp::from_u256(v)).collect();
+        let mut channel = Channel::<KeccakTranscript>::with_public_inputs(ProofKind::Sharpe, &pi);
+        channel.commit(proof.trace_commitment);
+        let z = channel.draw_felt();
+        let g = domain_generator(1);
+        assert_eq!(g, BN254Field::sub(Fp::ZERO, Fp::ONE));
+        assert_eq!(last_trade_point(g, pi[0]), g);
+        let zerofier = transition_zerofier_at(z, 2, g);
+        assert_eq!(zerofier, BN254Field::sub(z, Fp::ONE));
+        for denominator in [zerofier, BN254Field::sub(z, Fp::ONE), BN254Field::sub(z, g)] {
+            assert_ne!(denominator, Fp::ZERO);
+        }
+
+        assert!(verify_parts(&sharpe), "2-trade Sharpe proof should verify");
+        assert!(verify_sharpe_packed(&pack(&sharpe)));
+        let mut bad = sharpe.clone();
+        bad[0][2] += U256::from(1u64);
+        assert!(!verify_parts(&bad));
+
+        let claim = SortinoTrace::generate(&trades, None).compute_sortino_sq_scaled();
+        let sortino = parts(stark_prover::prove_sortino(&trades, claim, 4, None));
+        assert!(verify_sortino_parts(&sortino), "2-trade Sortino proof should verify");
+        let mut bad = sortino.clone();
+        bad[0][2] += U256::from(1u64);
+        assert!(!verify_sortino_parts(&bad));
     }
 }
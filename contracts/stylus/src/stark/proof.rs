@@ -1,22 +1,32 @@
 //! STARK Proof Deserialization
 //!
-//! Defines the proof structure and parsing logic for Sharpe ratio STARK proofs
-//! received as calldata (flattened Vec<U256> parameters).
+//! Defines the proof structure and parsing logic for Sharpe (and Sortino)
+//! ratio STARK proofs received as calldata (flattened Vec<U256> parameters).
+//! The two AIRs share one layout and differ only in trace width.
 
 use alloy_primitives::U256;
 use alloc::vec::Vec;
 
-use crate::field::Fp;
+use crate::field::{Fp, BN254_PRIME};
+use crate::merkle::MerkleScheme;
+use super::channel::TranscriptKind;
+use super::deep::opening_values_per_query;
+use super::{BLOWUP_FACTOR, VERIFIER_PROTOCOL_HASH};
 
-/// Parsed Sharpe STARK proof structure.
-pub struct SharpeStarkProof {
+/// Upper bound on composition segments (one per LDE coset off the trace domain).
+pub const MAX_COMPOSITION_SEGMENTS: usize = 3;
+
+/// Parsed STARK proof over a `W`-column trace.
+pub struct StarkProof<const W: usize> {
     pub trace_commitment: Fp,
-    pub composition_commitment: Fp,
+    /// One root per composition segment (a single one unless split).
+    pub composition_commitments: Vec<Fp>,
     pub fri_layer_commitments: Vec<Fp>,
 
-    pub trace_ood_evals: [Fp; 6],
-    pub trace_ood_evals_next: [Fp; 6],
-    pub composition_ood_eval: Fp,
+    pub trace_ood_evals: [Fp; W],
+    pub trace_ood_evals_next: [Fp; W],
+    /// S_i(z) per composition segment; C(z) = Σ_i S_i(z) · z^(i·N).
+    pub composition_ood_evals: Vec<Fp>,
 
     pub fri_final_poly: Vec<Fp>,
 
@@ -24,112 +34,379 @@ pub struct SharpeStarkProof {
     pub num_fri_layers: usize,
     pub log_trace_len: u32,
 
-    pub query_values: Vec<Fp>,
-    pub query_paths: Vec<Fp>,
+    /// FRI layer values, kept as raw calldata words until `decode_queries`:
+    /// a proof failing the OOD composition check never pays to convert them.
+    pub query_values: Vec<U256>,
+    /// Merkle path nodes stay raw calldata words: they are only hashed.
+    pub query_paths: Vec<U256>,
+
+    /// Per query: [W trace column values, composition segment values] (DEEP
+    /// openings); raw like `query_values`.
+    pub query_openings: Vec<U256>,
+    /// Per query: [trace Merkle path, one Merkle path per composition segment].
+    pub query_opening_paths: Vec<U256>,
+
+    /// Optional per-proof Merkle leaf salt (trailing query_metadata element).
+    pub leaf_salt: Option<Fp>,
+
+    /// Fiat-Shamir transcript hash (second limb of query_metadata[0]).
+    pub transcript: TranscriptKind,
+
+    /// Pairing rule of the dataset-commitment tree behind `pi[3]`
+    /// (fourth limb of query_metadata[0]).
+    pub merkle_scheme: MerkleScheme,
+}
+
+/// Query values of a `StarkProof` in Montgomery form (see `decode_queries`).
+pub struct QueryEvals {
+    /// FRI layer values, laid out like `StarkProof::query_values`.
+    pub values: Vec<Fp>,
+    /// DEEP openings, laid out like `StarkProof::query_openings`.
+    pub openings: Vec<Fp>,
+}
+
+impl<const W: usize> StarkProof<W> {
+    /// Number of field elements held, i.e. `Fp::from_u256` conversions made
+    /// while parsing (each one Montgomery multiplication). Merkle path nodes
+    /// and the query values deferred to `decode_queries` are not counted.
+    pub fn num_field_elements(&self) -> usize {
+        1 + self.composition_commitments.len()
+            + self.fri_layer_commitments.len()
+            + 2 * W
+            + self.composition_ood_evals.len()
+            + self.fri_final_poly.len()
+            + self.leaf_salt.is_some() as usize
+    }
+
+    /// Convert the query values and openings into field elements.
+    pub fn decode_queries(&self) -> QueryEvals {
+        QueryEvals {
+            values: Fp::from_u256_slice(&self.query_values),
+            openings: Fp::from_u256_slice(&self.query_openings),
+        }
+    }
+}
+
+/// Number of calldata arrays in a proof (and word counts in a packed header).
+pub const PACKED_SECTIONS: usize = 7;
+
+/// Length of a packed proof's header: one big-endian u32 word count per array.
+pub const PACKED_HEADER_LEN: usize = 4 * PACKED_SECTIONS;
+
+/// Split a packed proof into its seven calldata arrays.
+///
+/// Layout (the prover's `SerializedProof::to_bytes`): seven big-endian u32
+/// word counts for [public_inputs, commitments, ood_values, fri_final_poly,
+/// query_values, query_paths, query_metadata], then every word as 32
+/// big-endian bytes in that order. Returns None unless the blob is exactly
+/// that long.
+pub fn unpack_proof(bytes: &[u8]) -> Option<[Vec<U256>; PACKED_SECTIONS]> {
+    if bytes.len() < PACKED_HEADER_LEN {
+        return None;
+    }
+    let (header, body) = bytes.split_at(PACKED_HEADER_LEN);
+
+    let mut counts = [0usize; PACKED_SECTIONS];
+    let mut total_words = 0u64;
+    for (count, word) in counts.iter_mut().zip(header.chunks_exact(4)) {
+        let n = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        *count = n as usize;
+        total_words += n as u64;
+    }
+    if body.len() as u64 != total_words * 32 {
+        return None;
+    }
+
+    let mut words = body.chunks_exact(32).map(U256::from_be_slice);
+    Some(counts.map(|n| words.by_ref().take(n).collect()))
+}
+
+/// Words of `query_metadata` before the query indices.
+pub const METADATA_HEADER_LEN: usize = 4;
+
+/// Decoded `query_metadata`, mirroring the prover's `proof::QueryMetadata`.
+///
+/// Word layout (`to_words` / `from_words` are the only code that knows it):
+///
+/// - `[0]`: num_queries | transcript << 64 | k << 128 | merkle_scheme << 192,
+///   where k = 0 reads as a single composition segment
+/// - `[1]`: num_fri_layers | num_columns << 64
+/// - `[2]`: log_trace_len | blowup << 64 | grinding_bits << 128 |
+///   composition_lde_len << 192, where blowup = 0 reads as `BLOWUP_FACTOR`
+///   and composition_lde_len = 0 as trace_len · blowup
+/// - `[3]`: protocol hash
+/// - then one word per query index and an optional trailing leaf salt
+///
+/// Decoding only checks the layout; `parse_stark_proof` checks the values
+/// against the AIR and this verifier's parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryMetadata {
+    pub transcript: TranscriptKind,
+    pub num_segments: usize,
+    pub merkle_scheme: MerkleScheme,
+    pub num_fri_layers: usize,
+    pub num_columns: usize,
+    pub log_trace_len: u32,
+    pub blowup: u32,
+    pub grinding_bits: u32,
+    /// Length of each committed composition LDE (the prover's LDE domain).
+    pub composition_lde_len: u64,
+    pub protocol_hash: U256,
+    pub indices: Vec<usize>,
+    pub leaf_salt: Option<U256>,
+}
+
+impl QueryMetadata {
+    pub fn num_queries(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Decode the word layout. `None` for an unknown tag, a non-zero reserved
+    /// limb, a length that disagrees with the query count, or a field that
+    /// does not fit its limb.
+    pub fn from_words(words: &[U256]) -> Option<Self> {
+        if words.len() < METADATA_HEADER_LEN {
+            return None;
+        }
+        let head = words[0].as_limbs();
+        let layers = words[1].as_limbs();
+        let trace = words[2].as_limbs();
+        if layers[2] != 0 || layers[3] != 0 {
+            return None;
+        }
+
+        let num_queries = usize::try_from(head[0]).ok()?;
+        let indices_end = METADATA_HEADER_LEN.checked_add(num_queries)?;
+        // A single trailing element after the indices carries the leaf salt
+        let leaf_salt = match words.len().checked_sub(indices_end)? {
+            0 => None,
+            1 => Some(words[indices_end]),
+            _ => return None,
+        };
+        let indices = words[METADATA_HEADER_LEN..indices_end]
+            .iter()
+            .map(|idx| idx.as_limbs()[0] as usize)
+            .collect();
+
+        let log_trace_len = u32::try_from(trace[0]).ok()?;
+        let blowup = match trace[1] {
+            0 => BLOWUP_FACTOR,
+            b => u32::try_from(b).ok()?,
+        };
+        let composition_lde_len = match trace[3] {
+            0 => default_lde_len(log_trace_len, blowup)?,
+            n => n,
+        };
+
+        Some(QueryMetadata {
+            transcript: TranscriptKind::from_tag(head[1])?,
+            num_segments: (head[2] as usize).max(1),
+            merkle_scheme: MerkleScheme::from_tag(head[3])?,
+            num_fri_layers: usize::try_from(layers[0]).ok()?,
+            num_columns: usize::try_from(layers[1]).ok()?,
+            log_trace_len,
+            blowup,
+            grinding_bits: u32::try_from(trace[2]).ok()?,
+            composition_lde_len,
+            protocol_hash: words[3],
+            indices,
+            leaf_salt,
+        })
+    }
+
+    /// Encode back into words; defaults (one segment, `BLOWUP_FACTOR`, an
+    /// LDE of trace_len · blowup) are written as zero, so
+    /// `from_words(&m.to_words()) == Some(m)`.
+    pub fn to_words(&self) -> Vec<U256> {
+        let segments = if self.num_segments > 1 { self.num_segments as u64 } else { 0 };
+        let blowup = if self.blowup == BLOWUP_FACTOR { 0 } else { self.blowup as u64 };
+        let lde_len = if Some(self.composition_lde_len) == default_lde_len(self.log_trace_len, self.blowup) {
+            0
+        } else {
+            self.composition_lde_len
+        };
+
+        let mut words = Vec::with_capacity(METADATA_HEADER_LEN + self.indices.len() + 1);
+        words.push(U256::from_limbs([
+            self.indices.len() as u64,
+            self.transcript.tag(),
+            segments,
+            self.merkle_scheme.tag(),
+        ]));
+        words.push(U256::from_limbs([self.num_fri_layers as u64, self.num_columns as u64, 0, 0]));
+        words.push(U256::from_limbs([self.log_trace_len as u64, blowup, self.grinding_bits as u64, lde_len]));
+        words.push(self.protocol_hash);
+        words.extend(self.indices.iter().map(|&idx| U256::from(idx as u64)));
+        words.extend(self.leaf_salt);
+        words
+    }
+}
+
+/// trace_len · blowup, the LDE length a zero `composition_lde_len` limb stands for.
+fn default_lde_len(log_trace_len: u32, blowup: u32) -> Option<u64> {
+    1u64.checked_shl(log_trace_len)?.checked_mul(blowup as u64)
 }
 
-/// Parse a Sharpe STARK proof from ABI-compatible parameters.
-/// Expects 13 OOD values: 6 trace at z + 6 trace at zg + 1 composition at z.
-pub fn parse_sharpe_proof(
+/// How a proof's protocol hash compares with `VERIFIER_PROTOCOL_HASH`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolCheck {
+    /// Built for this verifier.
+    Match = 0,
+    /// Metadata too short to carry a protocol hash.
+    Missing = 1,
+    /// Built by a prover with different protocol parameters.
+    Mismatch = 2,
+}
+
+/// Compare the protocol hash in `query_metadata[3]` with this verifier's.
+pub fn check_protocol_hash(query_metadata: &[U256]) -> ProtocolCheck {
+    match query_metadata.get(3) {
+        None => ProtocolCheck::Missing,
+        Some(&hash) if hash == VERIFIER_PROTOCOL_HASH => ProtocolCheck::Match,
+        Some(_) => ProtocolCheck::Mismatch,
+    }
+}
+
+/// Parse a STARK proof over a `W`-column trace.
+///
+/// The proof declares its trace width in `query_metadata[1]` bits 64..128;
+/// it must equal W, and the OOD section must then hold exactly 2W + k values
+/// (W trace at z, W trace at zg, k composition segments at z). Each query
+/// opens W + k values. A new AIR only needs its column count here.
+///
+/// Query values are only length-checked; `StarkProof::decode_queries`
+/// converts them once the cheaper checks have passed.
+pub fn parse_stark_proof<const W: usize>(
     commitments: &[U256],
     ood_values: &[U256],
     fri_final_poly: &[U256],
     query_values: &[U256],
     query_paths: &[U256],
     query_metadata: &[U256],
-) -> Option<SharpeStarkProof> {
-    if query_metadata.len() < 3 {
+) -> Option<StarkProof<W>> {
+    if check_protocol_hash(query_metadata) != ProtocolCheck::Match {
         return None;
     }
 
-    let num_queries = query_metadata[0].as_limbs()[0] as usize;
-    let num_fri_layers = query_metadata[1].as_limbs()[0] as usize;
-    let log_trace_len = query_metadata[2].as_limbs()[0] as u32;
+    let QueryMetadata {
+        transcript,
+        num_segments,
+        merkle_scheme,
+        num_fri_layers,
+        num_columns,
+        log_trace_len,
+        blowup,
+        grinding_bits,
+        composition_lde_len,
+        indices: query_indices,
+        leaf_salt,
+        ..
+    } = QueryMetadata::from_words(query_metadata)?;
+    let num_queries = query_indices.len();
+
+    if num_segments > MAX_COMPOSITION_SEGMENTS || num_columns != W {
+        return None;
+    }
+
+    // Only the fixed blowup is supported, and no proof-of-work grinding
+    if blowup != BLOWUP_FACTOR || grinding_bits != 0 {
+        return None;
+    }
 
     if log_trace_len == 0 || log_trace_len > 26 {
         return None;
     }
 
-    if num_fri_layers == 0 || num_fri_layers as u32 > log_trace_len + 2 {
+    // FRI indexes the composition commitments over the LDE domain; a proof
+    // committing a different-sized LDE would be opened at the wrong points
+    let log_domain_size = log_trace_len as usize + 2;
+    if composition_lde_len != 1u64 << log_domain_size {
         return None;
     }
 
-    if num_queries == 0 || num_queries > 64 {
+    // Fold at most down to a final domain of size 2 (LDE depth log_trace_len + 2)
+    if num_fri_layers == 0 || num_fri_layers as u32 >= log_trace_len + 2 {
         return None;
     }
 
-    if query_metadata.len() < 3 + num_queries {
+    if num_queries == 0 || num_queries > 64 {
         return None;
     }
 
-    let query_indices: Vec<usize> = (0..num_queries)
-        .map(|i| query_metadata[3 + i].as_limbs()[0] as usize)
-        .collect();
+    // The prover hashes the salt as given, so it must already be canonical
+    if leaf_salt.is_some_and(|salt| salt >= BN254_PRIME) {
+        return None;
+    }
+    let leaf_salt = leaf_salt.map(Fp::from_u256);
 
-    if commitments.len() < 2 + num_fri_layers {
+    if commitments.len() < 1 + num_segments + num_fri_layers {
         return None;
     }
 
     let trace_commitment = Fp::from_u256(commitments[0]);
-    let composition_commitment = Fp::from_u256(commitments[1]);
-    let fri_layer_commitments: Vec<Fp> = commitments[2..2 + num_fri_layers]
-        .iter()
-        .map(|v| Fp::from_u256(*v))
-        .collect();
-
-    // Sharpe: 6 + 6 + 1 = 13 OOD values
-    if ood_values.len() < 13 {
+    let composition_commitments = Fp::from_u256_slice(&commitments[1..1 + num_segments]);
+    let fri_layer_commitments =
+        Fp::from_u256_slice(&commitments[1 + num_segments..1 + num_segments + num_fri_layers]);
+
+    // Each root commits a different polynomial; a FRI layer (or composition
+    // segment) reusing the trace root marks a malformed or adversarial proof
+    if composition_commitments.contains(&trace_commitment)
+        || fri_layer_commitments
+            .iter()
+            .any(|root| *root == trace_commitment || composition_commitments.contains(root))
+    {
         return None;
     }
 
-    let trace_ood_evals = [
-        Fp::from_u256(ood_values[0]),
-        Fp::from_u256(ood_values[1]),
-        Fp::from_u256(ood_values[2]),
-        Fp::from_u256(ood_values[3]),
-        Fp::from_u256(ood_values[4]),
-        Fp::from_u256(ood_values[5]),
-    ];
-    let trace_ood_evals_next = [
-        Fp::from_u256(ood_values[6]),
-        Fp::from_u256(ood_values[7]),
-        Fp::from_u256(ood_values[8]),
-        Fp::from_u256(ood_values[9]),
-        Fp::from_u256(ood_values[10]),
-        Fp::from_u256(ood_values[11]),
-    ];
-    let composition_ood_eval = Fp::from_u256(ood_values[12]);
-
-    // Validate query_values length
-    let expected_qv = num_queries * num_fri_layers * 2;
-    if query_values.len() < expected_qv {
+    // W + W trace OOD values + one per composition segment
+    if ood_values.len() != 2 * W + num_segments {
+        return None;
+    }
+
+    let ood = Fp::from_u256_slice(ood_values);
+    let mut trace_ood_evals = [Fp::ZERO; W];
+    let mut trace_ood_evals_next = [Fp::ZERO; W];
+    trace_ood_evals.copy_from_slice(&ood[..W]);
+    trace_ood_evals_next.copy_from_slice(&ood[W..2 * W]);
+    let composition_ood_evals = ood[2 * W..].to_vec();
+
+    // Validate query_values length (FRI pairs, then W + k opened values per query)
+    let expected_qv = stark_core::fri::query_values_len(num_queries, num_fri_layers);
+    let expected_openings = num_queries * opening_values_per_query(W, num_segments);
+    if query_values.len() < expected_qv + expected_openings {
         return None;
     }
 
     // Validate query_paths length
-    let log_domain_size = log_trace_len as usize + 2;
     let mut path_elements_per_query = 0usize;
     for layer in 0..num_fri_layers {
         path_elements_per_query += log_domain_size - layer;
     }
     let expected_qp = num_queries * path_elements_per_query;
-    if query_paths.len() < expected_qp {
+    // Trace + segment opening paths, each over the full LDE domain
+    let expected_opening_paths = num_queries * (1 + num_segments) * log_domain_size;
+    if query_paths.len() < expected_qp + expected_opening_paths {
         return None;
     }
 
-    Some(SharpeStarkProof {
+    Some(StarkProof {
         trace_commitment,
-        composition_commitment,
+        composition_commitments,
         fri_layer_commitments,
         trace_ood_evals,
         trace_ood_evals_next,
-        composition_ood_eval,
-        fri_final_poly: fri_final_poly.iter().map(|v| Fp::from_u256(*v)).collect(),
+        composition_ood_evals,
+        fri_final_poly: Fp::from_u256_slice(fri_final_poly),
         query_indices,
         num_fri_layers,
         log_trace_len,
-        query_values: query_values.iter().map(|v| Fp::from_u256(*v)).collect(),
-        query_paths: query_paths.iter().map(|v| Fp::from_u256(*v)).collect(),
+        query_values: query_values[..expected_qv].to_vec(),
+        query_paths: query_paths[..expected_qp].to_vec(),
+        query_openings: query_values[expected_qv..expected_qv + expected_openings].to_vec(),
+        query_opening_paths: query_paths[expected_qp..expected_qp + expected_opening_paths].to_vec(),
+        leaf_salt,
+        transcript,
+        merkle_scheme,
     })
 }
 
@@ -137,6 +414,72 @@ pub fn parse_sharpe_proof(
 mod tests {
     use super::*;
     use alloc::vec;
+    use crate::stark::parse_sharpe_proof;
+
+    /// `query_metadata[1]`: FRI layer count with the declared trace width.
+    fn layers(num_fri_layers: u64, num_columns: u64) -> U256 {
+        U256::from(num_fri_layers) | (U256::from(num_columns) << 64)
+    }
+
+    /// One-query, one-segment proof sections over a `num_columns`-wide trace
+    /// (2 FRI layers, log_trace_len 6), declaring `declared` columns.
+    fn columnar_sections(num_columns: usize, declared: u64) -> [Vec<U256>; 6] {
+        let commitments = (1..5).map(|i| U256::from(i as u64)).collect();
+        let ood_values = (0..2 * num_columns + 1).map(|i| U256::from(10 + i as u64)).collect();
+        let mut query_values = vec![U256::from(30u64); 4];
+        query_values.extend((0..num_columns + 1).map(|i| U256::from(50 + i as u64)));
+        let query_paths = vec![U256::from(40u64); 15 + 16];
+        let query_metadata = vec![
+            U256::from(1u64), layers(2, declared), U256::from(6u64), VERIFIER_PROTOCOL_HASH,
+            U256::from(5u64),
+        ];
+        [commitments, ood_values, vec![], query_values, query_paths, query_metadata]
+    }
+
+    fn parse_sections<const W: usize>(s: &[Vec<U256>; 6]) -> Option<StarkProof<W>> {
+        parse_stark_proof(&s[0], &s[1], &s[2], &s[3], &s[4], &s[5])
+    }
+
+    /// The prover's `new_sharpe` and `parse_sharpe_proof` agree on the OOD
+    /// layout: every value lands back where it was passed in.
+    #[test]
+    fn test_new_sharpe_ood_layout_round_trips() {
+        use stark_prover::proof::SerializedProof;
+
+        let words = |start: u64, n: usize| (start..start + n as u64).map(U256::from).collect::<Vec<_>>();
+        let (at_z, at_zg, segments) = (words(10, 7), words(20, 7), words(30, 2));
+        let serialized = SerializedProof::new_sharpe(
+            &words(0, 4),
+            U256::from(1u64),
+            &words(2, 2),
+            &words(4, 2),
+            &at_z,
+            &at_zg,
+            &segments,
+            &words(100, 2),
+            &[5],
+            &words(40, 4),
+            &words(50, 15),
+            2,
+            6,
+            None,
+        )
+        // 7 trace + 2 segment values, and three 8-node opening paths
+        .with_trace_openings(&words(70, 9), &words(80, 24));
+
+        let proof = parse_sharpe_proof(
+            &serialized.commitments,
+            &serialized.ood_values,
+            &serialized.fri_final_poly,
+            &serialized.query_values,
+            &serialized.query_paths,
+            &serialized.query_metadata,
+        )
+        .expect("new_sharpe output parses");
+        assert_eq!(proof.trace_ood_evals.to_vec(), Fp::from_u256_slice(&at_z));
+        assert_eq!(proof.trace_ood_evals_next.to_vec(), Fp::from_u256_slice(&at_zg));
+        assert_eq!(proof.composition_ood_evals, Fp::from_u256_slice(&segments));
+    }
 
     #[test]
     fn test_parse_sharpe_proof_basic() {
@@ -147,16 +490,19 @@ mod tests {
             U256::from(4u64),
         ];
 
-        // 13 OOD values: 6 trace at z + 6 trace at zg + 1 composition
-        let ood_values: Vec<U256> = (10..23).map(|i| U256::from(i as u64)).collect();
+        // 15 OOD values: 7 trace at z + 7 trace at zg + 1 composition
+        let ood_values: Vec<U256> = (10..25).map(|i| U256::from(i as u64)).collect();
 
         let fri_final = vec![U256::from(100u64), U256::from(101u64)];
-        // 1 query * 2 layers * 2 = 4 values
-        let query_values = vec![U256::from(30u64); 4];
-        // 1 query * ((8-0) + (8-1)) = 15 path elements (log_domain_size = 6+2 = 8)
-        let query_paths = vec![U256::from(40u64); 15];
+        // 1 query * 2 layers * 2 = 4 FRI values + 8 opened values
+        let mut query_values = vec![U256::from(30u64); 4];
+        query_values.extend((0..8).map(|i| U256::from(50 + i as u64)));
+        // 1 query * ((8-0) + (8-1)) = 15 FRI path elements (log_domain_size = 6+2 = 8)
+        // + 2 * 8 trace/composition opening path elements
+        let mut query_paths = vec![U256::from(40u64); 15];
+        query_paths.extend(vec![U256::from(60u64); 16]);
         let query_metadata = vec![
-            U256::from(1u64), U256::from(2u64), U256::from(6u64),
+            U256::from(1u64), layers(2, 7), U256::from(6u64), VERIFIER_PROTOCOL_HASH,
             U256::from(5u64),
         ];
 
@@ -169,21 +515,363 @@ mod tests {
         let proof = proof.unwrap();
 
         assert_eq!(proof.trace_ood_evals[0], Fp::from_u256(U256::from(10u64)));
-        assert_eq!(proof.trace_ood_evals[5], Fp::from_u256(U256::from(15u64)));
-        assert_eq!(proof.trace_ood_evals_next[0], Fp::from_u256(U256::from(16u64)));
-        assert_eq!(proof.trace_ood_evals_next[5], Fp::from_u256(U256::from(21u64)));
-        assert_eq!(proof.composition_ood_eval, Fp::from_u256(U256::from(22u64)));
+        assert_eq!(proof.trace_ood_evals[6], Fp::from_u256(U256::from(16u64)));
+        assert_eq!(proof.trace_ood_evals_next[0], Fp::from_u256(U256::from(17u64)));
+        assert_eq!(proof.trace_ood_evals_next[6], Fp::from_u256(U256::from(23u64)));
+        assert_eq!(proof.composition_ood_evals, vec![Fp::from_u256(U256::from(24u64))]);
+        assert_eq!(proof.composition_commitments, vec![Fp::from_u256(U256::from(2u64))]);
         assert_eq!(proof.log_trace_len, 6);
+        assert!(proof.leaf_salt.is_none());
+        assert_eq!(proof.transcript, TranscriptKind::Keccak);
+
+        // FRI data and DEEP openings are split apart
+        assert_eq!(proof.query_values.len(), 4);
+        assert_eq!(proof.query_openings.len(), 8);
+        assert_eq!(proof.query_openings[7], U256::from(57u64));
+        assert_eq!(proof.query_paths.len(), 15);
+        assert_eq!(proof.query_opening_paths.len(), 16);
+
+        // Openings are mandatory
+        assert!(parse_sharpe_proof(
+            &commitments, &ood_values, &fri_final,
+            &query_values[..4], &query_paths, &query_metadata,
+        ).is_none());
+        assert!(parse_sharpe_proof(
+            &commitments, &ood_values, &fri_final,
+            &query_values, &query_paths[..15], &query_metadata,
+        ).is_none());
+    }
+
+    #[test]
+    fn test_parse_columnar_proofs() {
+        fn check<const W: usize>() {
+            let proof = parse_sections::<W>(&columnar_sections(W, W as u64)).unwrap();
+            assert_eq!(proof.trace_ood_evals[W - 1], Fp::from_u256(U256::from(10 + W as u64 - 1)));
+            assert_eq!(proof.trace_ood_evals_next[0], Fp::from_u256(U256::from(10 + W as u64)));
+            assert_eq!(proof.composition_ood_evals, vec![Fp::from_u256(U256::from(10 + 2 * W as u64))]);
+            assert_eq!(proof.query_openings.len(), W + 1);
+        }
+        check::<2>();
+        check::<5>();
+        check::<6>();
+    }
+
+    #[test]
+    fn test_parse_rejects_mismatched_column_count() {
+        // 6-column OOD section under a 5-column declaration, and vice versa
+        assert!(parse_sections::<5>(&columnar_sections(6, 5)).is_none());
+        assert!(parse_sections::<6>(&columnar_sections(5, 6)).is_none());
+
+        // Declared width must match the AIR being verified
+        assert!(parse_sections::<5>(&columnar_sections(5, 6)).is_none());
+        assert!(parse_sections::<5>(&columnar_sections(5, 0)).is_none());
+
+        // Trailing OOD words beyond 2W + k are rejected, not ignored
+        let mut sections = columnar_sections(5, 5);
+        sections[1].push(U256::from(99u64));
+        assert!(parse_sections::<5>(&sections).is_none());
+    }
+
+    #[test]
+    fn test_protocol_hash_mismatch() {
+        let mut sections = columnar_sections(7, 7);
+        assert_eq!(check_protocol_hash(&sections[5]), ProtocolCheck::Match);
+        assert!(parse_sections::<7>(&sections).is_some());
+
+        // A prover built with other protocol parameters
+        sections[5][3] = VERIFIER_PROTOCOL_HASH ^ U256::from(1u64);
+        assert_eq!(check_protocol_hash(&sections[5]), ProtocolCheck::Mismatch);
+        assert_eq!(ProtocolCheck::Mismatch as u8, 2);
+        assert!(parse_sections::<7>(&sections).is_none());
+
+        assert_eq!(check_protocol_hash(&sections[5][..3]), ProtocolCheck::Missing);
+    }
+
+    #[test]
+    fn test_query_metadata_round_trip() {
+        let metadata = QueryMetadata {
+            transcript: TranscriptKind::Poseidon,
+            num_segments: 3,
+            merkle_scheme: MerkleScheme::Sorted,
+            num_fri_layers: 5,
+            num_columns: 8,
+            log_trace_len: 6,
+            blowup: 8,
+            grinding_bits: 16,
+            composition_lde_len: 512,
+            protocol_hash: VERIFIER_PROTOCOL_HASH,
+            indices: vec![3, 250, 17],
+            leaf_salt: Some(U256::from(0xabcdu64)),
+        };
+        let words = metadata.to_words();
+        assert_eq!(words.len(), METADATA_HEADER_LEN + 3 + 1);
+        assert_eq!(words[0], U256::from_limbs([3, 1, 3, 1]));
+        assert_eq!(words[1], layers(5, 8));
+        assert_eq!(words[2], U256::from_limbs([6, 8, 16, 0]));
+        assert_eq!(QueryMetadata::from_words(&words), Some(metadata.clone()));
+
+        // Defaults are written as zero limbs and read back
+        let defaults = QueryMetadata {
+            num_segments: 1,
+            blowup: BLOWUP_FACTOR,
+            grinding_bits: 0,
+            composition_lde_len: 256,
+            leaf_salt: None,
+            ..metadata
+        };
+        let words = defaults.to_words();
+        assert_eq!(words[0].as_limbs()[2], 0);
+        assert_eq!(words[2], U256::from(6u64));
+        assert_eq!(QueryMetadata::from_words(&words), Some(defaults.clone()));
+        let wide = QueryMetadata { composition_lde_len: 512, ..defaults };
+        assert_eq!(wide.to_words()[2], U256::from_limbs([6, 0, 0, 512]));
+        assert_eq!(QueryMetadata::from_words(&wide.to_words()), Some(wide));
+
+        // Layout violations
+        let mut reserved = words.clone();
+        reserved[1] |= U256::from(1u64) << 128;
+        assert_eq!(QueryMetadata::from_words(&reserved), None);
+        let mut unknown_transcript = words.clone();
+        unknown_transcript[0] |= U256::from(2u64) << 64;
+        assert_eq!(QueryMetadata::from_words(&unknown_transcript), None);
+        let mut extra = words.clone();
+        extra.extend([U256::ZERO, U256::ZERO]);
+        assert_eq!(QueryMetadata::from_words(&extra), None);
+        assert_eq!(QueryMetadata::from_words(&words[..METADATA_HEADER_LEN + 2]), None);
+        assert_eq!(QueryMetadata::from_words(&words[..3]), None);
+        let mut huge = words.clone();
+        huge[0] = U256::from(u64::MAX);
+        assert_eq!(QueryMetadata::from_words(&huge), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_unsupported_blowup_and_grinding() {
+        let sections = columnar_sections(7, 7);
+        let mut metadata = QueryMetadata::from_words(&sections[5]).unwrap();
+        assert_eq!(metadata.blowup, BLOWUP_FACTOR);
+        assert_eq!(metadata.grinding_bits, 0);
+
+        for (blowup, grinding_bits) in [(8, 0), (BLOWUP_FACTOR, 1)] {
+            metadata.blowup = blowup;
+            metadata.grinding_bits = grinding_bits;
+            let mut bad = sections.clone();
+            bad[5] = metadata.to_words();
+            assert!(parse_sections::<7>(&bad).is_none());
+        }
+    }
+
+    #[test]
+    fn test_parse_fri_layer_bound() {
+        // One-query, one-segment sections with `num_fri_layers` layers over a
+        // 7-column trace of 2^log_trace_len rows (LDE depth log_trace_len + 2)
+        let sections = |log_trace_len: u64, num_fri_layers: usize| {
+            let depth = log_trace_len as usize + 2;
+            let commitments = (1..=2 + num_fri_layers).map(|i| U256::from(i as u64)).collect();
+            let ood_values = (0..15).map(|i| U256::from(10 + i as u64)).collect();
+            let mut query_values = vec![U256::from(30u64); 2 * num_fri_layers];
+            query_values.extend((0..8).map(|i| U256::from(50 + i as u64)));
+            let fri_path_len: usize = (0..num_fri_layers).map(|layer| depth - layer).sum();
+            let query_paths = vec![U256::from(40u64); fri_path_len + 2 * depth];
+            let query_metadata = vec![
+                U256::from(1u64), layers(num_fri_layers as u64, 7), U256::from(log_trace_len),
+                VERIFIER_PROTOCOL_HASH, U256::from(5u64),
+            ];
+            [commitments, ood_values, vec![], query_values, query_paths, query_metadata]
+        };
+
+        // Depth 3: two layers leave a final domain of size 2, three would fold it to 1
+        assert!(parse_sections::<7>(&sections(1, 2)).is_some());
+        assert!(parse_sections::<7>(&sections(1, 3)).is_none());
+        assert!(parse_sections::<7>(&sections(4, 5)).is_some());
+        assert!(parse_sections::<7>(&sections(4, 6)).is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_reused_commitments() {
+        let ood_values: Vec<U256> = (10..25).map(|i| U256::from(i as u64)).collect();
+        let fri_final = vec![U256::from(100u64), U256::from(101u64)];
+        let query_values = vec![U256::from(30u64); 4 + 8];
+        let query_paths = vec![U256::from(40u64); 15 + 16];
+        let query_metadata = vec![
+            U256::from(1u64), layers(2, 7), U256::from(6u64), VERIFIER_PROTOCOL_HASH,
+            U256::from(5u64),
+        ];
+        let parse = |commitments: &[u64]| {
+            let commitments: Vec<U256> = commitments.iter().map(|&c| U256::from(c)).collect();
+            parse_sharpe_proof(
+                &commitments, &ood_values, &fri_final,
+                &query_values, &query_paths, &query_metadata,
+            )
+        };
+
+        assert!(parse(&[1, 2, 3, 4]).is_some());
+        // Later FRI layer equal to the trace commitment
+        assert!(parse(&[1, 2, 3, 1]).is_none());
+        // FRI layer 0 equal to the trace or composition commitment
+        assert!(parse(&[1, 2, 1, 4]).is_none());
+        assert!(parse(&[1, 2, 2, 4]).is_none());
+        // Composition commitment equal to the trace commitment
+        assert!(parse(&[1, 1, 3, 4]).is_none());
+    }
+
+    #[test]
+    fn test_parse_sharpe_proof_transcript_kind() {
+        let commitments = vec![U256::from(1u64), U256::from(2u64), U256::from(3u64), U256::from(4u64)];
+        let ood_values: Vec<U256> = (10..25).map(|i| U256::from(i as u64)).collect();
+        let query_values = vec![U256::from(30u64); 4 + 8];
+        let query_paths = vec![U256::from(40u64); 15 + 16];
+        let mut query_metadata = vec![
+            U256::from(1u64) | (U256::from(1u64) << 64), // 1 query, Poseidon transcript
+            layers(2, 7), U256::from(6u64), VERIFIER_PROTOCOL_HASH,
+            U256::from(5u64),
+        ];
+
+        let proof = parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values, &query_paths, &query_metadata,
+        ).unwrap();
+        assert_eq!(proof.transcript, TranscriptKind::Poseidon);
+        assert_eq!(proof.query_indices.len(), 1);
+
+        assert_eq!(proof.merkle_scheme, MerkleScheme::Ordered);
+
+        // Sorted dataset-commitment pairing rides in the top limb
+        query_metadata[0] = U256::from(1u64) | (U256::from(1u64) << 192);
+        let proof = parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values, &query_paths, &query_metadata,
+        ).unwrap();
+        assert_eq!(proof.merkle_scheme, MerkleScheme::Sorted);
+        query_metadata[0] = U256::from(1u64) | (U256::from(2u64) << 192);
+        assert!(parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values, &query_paths, &query_metadata,
+        ).is_none());
+
+        // Unknown transcript tag is rejected
+        query_metadata[0] = U256::from(1u64) | (U256::from(7u64) << 64);
+        assert!(parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values, &query_paths, &query_metadata,
+        ).is_none());
+    }
+
+    #[test]
+    fn test_parse_sharpe_proof_leaf_salt() {
+        let commitments = vec![U256::from(1u64), U256::from(2u64), U256::from(3u64), U256::from(4u64)];
+        let ood_values: Vec<U256> = (10..25).map(|i| U256::from(i as u64)).collect();
+        let query_values = vec![U256::from(30u64); 4 + 8];
+        let query_paths = vec![U256::from(40u64); 15 + 16];
+        let mut query_metadata = vec![
+            U256::from(1u64), layers(2, 7), U256::from(6u64), VERIFIER_PROTOCOL_HASH,
+            U256::from(5u64),
+            U256::from(0xa11ceu64), // leaf salt
+        ];
+
+        let proof = parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values, &query_paths, &query_metadata,
+        ).unwrap();
+        assert_eq!(proof.leaf_salt, Some(Fp::from_u256(U256::from(0xa11ceu64))));
+
+        // A salt >= p would be reduced here but hashed unreduced by the prover
+        for salt in [BN254_PRIME, BN254_PRIME + U256::from(0xa11ceu64)] {
+            let mut non_canonical = query_metadata.clone();
+            *non_canonical.last_mut().unwrap() = salt;
+            assert!(parse_sharpe_proof(
+                &commitments, &ood_values, &[],
+                &query_values, &query_paths, &non_canonical,
+            ).is_none());
+        }
+
+        // More than one trailing element is malformed
+        query_metadata.push(U256::from(1u64));
+        assert!(parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values, &query_paths, &query_metadata,
+        ).is_none());
+    }
+
+    #[test]
+    fn test_parse_sharpe_proof_composition_segments() {
+        // trace, 2 segment roots, 2 FRI layers
+        let commitments: Vec<U256> = (1..6).map(|i| U256::from(i as u64)).collect();
+        // 14 trace OOD values + 2 segments
+        let ood_values: Vec<U256> = (10..26).map(|i| U256::from(i as u64)).collect();
+        let query_values = vec![U256::from(30u64); 4 + 9];
+        let query_paths = vec![U256::from(40u64); 15 + 3 * 8];
+        let mut query_metadata = vec![
+            U256::from(1u64) | (U256::from(2u64) << 128), // 1 query, 2 segments
+            layers(2, 7), U256::from(6u64), VERIFIER_PROTOCOL_HASH,
+            U256::from(5u64),
+        ];
+
+        let proof = parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values, &query_paths, &query_metadata,
+        ).unwrap();
+        assert_eq!(
+            proof.composition_commitments,
+            vec![Fp::from_u256(U256::from(2u64)), Fp::from_u256(U256::from(3u64))]
+        );
+        assert_eq!(proof.fri_layer_commitments[0], Fp::from_u256(U256::from(4u64)));
+        assert_eq!(proof.composition_ood_evals.len(), 2);
+        assert_eq!(proof.composition_ood_evals[1], Fp::from_u256(U256::from(25u64)));
+        assert_eq!(proof.query_openings.len(), 9);
+        assert_eq!(proof.query_opening_paths.len(), 24);
+
+        // Segment openings are mandatory
+        assert!(parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values[..12], &query_paths, &query_metadata,
+        ).is_none());
+
+        // More segments than the LDE has off-trace cosets
+        query_metadata[0] = U256::from(1u64) | (U256::from(4u64) << 128);
+        assert!(parse_sharpe_proof(
+            &commitments, &ood_values, &[],
+            &query_values, &query_paths, &query_metadata,
+        ).is_none());
+    }
+
+    #[test]
+    fn test_unpack_proof_layout() {
+        // [1 public input, 0, 2 OOD values, 0, 0, 0, 1 metadata word]
+        let mut bytes = Vec::new();
+        for count in [1u32, 0, 2, 0, 0, 0, 1] {
+            bytes.extend_from_slice(&count.to_be_bytes());
+        }
+        for word in [7u64, 8, 9, 10] {
+            bytes.extend_from_slice(&U256::from(word).to_be_bytes::<32>());
+        }
+
+        let [pi, commitments, ood, _, _, _, metadata] = unpack_proof(&bytes).unwrap();
+        assert_eq!(pi, vec![U256::from(7u64)]);
+        assert!(commitments.is_empty());
+        assert_eq!(ood, vec![U256::from(8u64), U256::from(9u64)]);
+        assert_eq!(metadata, vec![U256::from(10u64)]);
+
+        // Truncated body, trailing bytes and a short header are all malformed
+        assert!(unpack_proof(&bytes[..bytes.len() - 1]).is_none());
+        let mut padded = bytes.clone();
+        padded.push(0);
+        assert!(unpack_proof(&padded).is_none());
+        assert!(unpack_proof(&bytes[..PACKED_HEADER_LEN - 1]).is_none());
+
+        // Word counts that overflow 32 bits in sum do not wrap around
+        let mut huge = vec![0xffu8; PACKED_HEADER_LEN];
+        huge.extend_from_slice(&[0u8; 32]);
+        assert!(unpack_proof(&huge).is_none());
     }
 
     #[test]
     fn test_parse_sharpe_proof_insufficient_ood() {
         let commitments = vec![U256::from(1u64), U256::from(2u64), U256::from(3u64)];
-        // Only 5 OOD values (need 13)
+        // Only 5 OOD values (need 15)
         let ood_values = vec![U256::ZERO; 5];
         let result = parse_sharpe_proof(
             &commitments, &ood_values, &[], &[], &[],
-            &[U256::from(1u64), U256::from(1u64), U256::from(3u64), U256::from(0u64)],
+            &[U256::from(1u64), layers(1, 7), U256::from(3u64), VERIFIER_PROTOCOL_HASH, U256::from(0u64)],
         );
         assert!(result.is_none());
     }
@@ -0,0 +1,218 @@
+//! Embedded known-good proof for the post-deploy health check.
+//!
+//! The Bot A Sharpe proof (15 trades, 2 queries), the smallest of the test
+//! vectors, kept as const data so `StarkVerifier::self_test` can check a
+//! deployment with a single `eth_call`. Regenerate with
+//! `cargo run --features cli --release -- --bot a --num-queries 2` whenever
+//! the protocol hash changes.
+
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+
+/// [trade_count, total_return, sharpe_sq_scaled, merkle_root]
+pub const PUBLIC_INPUTS: [U256; 4] = [
+    U256::from_limbs([0xf, 0, 0, 0]),
+    U256::from_limbs([0xbb8, 0, 0, 0]),
+    U256::from_limbs([0xea60, 0, 0, 0]),
+    U256::from_limbs([0xae72183e2215273b, 0x68b08bb3e9147f9b, 0x9cf58a4cf71990d2, 0x0329b913d70957b3]),
+];
+
+/// [trace_root, 2 composition segment roots, 4 FRI layer roots]
+pub const COMMITMENTS: [U256; 7] = [
+    U256::from_limbs([0x42968f7adf3c5472, 0xadaabed5ff29abff, 0xbfab281bb6e471b6, 0x12ada0401fc4f38d]),
+    U256::from_limbs([0x9135c296641f28e6, 0xb823832a03d91888, 0xaa93d77a593c6aca, 0x2197259e5d8a8366]),
+    U256::from_limbs([0x2a689500553a41ad, 0x0da80c880d082383, 0x74d82456a1b33c42, 0x015e4fe0ac336a26]),
+    U256::from_limbs([0xc7bbf7f4ffe99b00, 0xb03ce33cd701a0af, 0xfdb97ed6123008ca, 0x1d1aec89fe135c42]),
+    U256::from_limbs([0x8f2429d5b206161f, 0x6e7f215d03edfed0, 0x424b0270f1d628e7, 0x24dc4eff5473880f]),
+    U256::from_limbs([0x0347eb49fa474de2, 0x143842a5b83654b4, 0xb2e69b6ddfdabf96, 0x00a7f15f599b7075]),
+    U256::from_limbs([0x07a434333b9009be, 0x5176fd8863efba07, 0xe89edd824ba13e3b, 0x302a51bbd13275c5]),
+];
+
+/// [7 trace at z, 7 trace at zg, 2 composition segments at z]
+pub const OOD_VALUES: [U256; 16] = [
+    U256::from_limbs([0x509906a788af69b4, 0xe8f018a1d6439185, 0x96259c6c47d80367, 0x0c3672950839e5d2]),
+    U256::from_limbs([0xfa183043a2f0d088, 0xb91b6a5055b193b6, 0xa07c16bf1271630f, 0x2845e9f102c9c6af]),
+    U256::from_limbs([0x6a77438da43e5aec, 0xcf79bef67812f14c, 0xa3ce3d74abce976f, 0x1e4d8b6db6cb6393]),
+    U256::from_limbs([0xcd2773ac22c3f9d8, 0x88064f0c5e312829, 0x9711ab48e6e55649, 0x2f5bb12bd8116090]),
+    U256::from_limbs([0xf, 0, 0, 0]),
+    U256::from_limbs([0x0, 0, 0, 0]),
+    U256::from_limbs([0x3fdd834673fbe8de, 0xcbc9045a7b848aae, 0x439c9b0a0c0b5471, 0x06b31363bd8de4c7]),
+    U256::from_limbs([0x905b4909e24df44e, 0xb98cff53a7480d95, 0xdda3d5986ddf8ba5, 0x11c8533c53dd0519]),
+    U256::from_limbs([0x8d4a301ecaffa026, 0x11ad0b2b3e1936ce, 0xd8d6213cb5117a2d, 0x2c5ac85f88d2f6b0]),
+    U256::from_limbs([0x177dddd0b69d2b72, 0x50584cdbcb3d3cd0, 0x5239a837cc442140, 0x0fa19cd170d52402]),
+    U256::from_limbs([0x29c6b54bbd21a84e, 0x8f65a2c726897e6e, 0x16508b094ad287bc, 0x03af3031e4fa846f]),
+    U256::from_limbs([0xf, 0, 0, 0]),
+    U256::from_limbs([0x0, 0, 0, 0]),
+    U256::from_limbs([0x348dd4a3f3d5e118, 0x5ac7d2c6adb33610, 0x0f38deaa70fd6290, 0x21a4f3828f55b874]),
+    U256::from_limbs([0x8ffa655d4b2e893b, 0x0435aac8af0cfe43, 0x29965750887d47cb, 0x27d571c8bc2e6e50]),
+    U256::from_limbs([0x34e534a99d002beb, 0xf46ee9d8d5f8a305, 0x9951b26ca1c55a1f, 0x2974505ee55090df]),
+];
+
+/// Final FRI layer coefficients
+pub const FRI_FINAL_POLY: [U256; 4] = [
+    U256::from_limbs([0x3f2108bf9ff955db, 0x6236b58c72d56655, 0x879b36bd46806664, 0x292654b94042275d]),
+    U256::from_limbs([0x0, 0, 0, 0]),
+    U256::from_limbs([0x0, 0, 0, 0]),
+    U256::from_limbs([0x0, 0, 0, 0]),
+];
+
+/// FRI layer pairs per query, followed by each query's trace and segment openings
+pub const QUERY_VALUES: [U256; 34] = [
+    U256::from_limbs([0x65dc6d27f1932502, 0x175747f08e169368, 0x68819a97ead45aec, 0x2891ba45029a616b]),
+    U256::from_limbs([0x1aa05d6cf2ac6118, 0xc971e676c616c2b3, 0x6c4688382c5eae8a, 0x23a64f0fab3e20c3]),
+    U256::from_limbs([0xb09a96629b66fb25, 0x91c8d7f7030c7e74, 0xc07fcef099b5350a, 0x24594fcd7cda4b81]),
+    U256::from_limbs([0xfcaed5e32e2f35d5, 0x470068e9d9464ec4, 0xf05835d3c169b9e5, 0x2b22acd0bc51e881]),
+    U256::from_limbs([0x7aaeb5a8d7ca1cfa, 0x9c4cd6881ae9cd51, 0x6158fff0b8daf1e5, 0x08452baca1ac2ad1]),
+    U256::from_limbs([0x52f6a53c73ab5bbd, 0x60500f9e7dccfd52, 0x3aa53efc8d4f47d0, 0x0ed3eff2197dc53a]),
+    U256::from_limbs([0x7f7076340adca4e0, 0xd68364c6aa2c6552, 0xc57f61640fd0082b, 0x09974130dc0027d3]),
+    U256::from_limbs([0x0acce72b71abddcd, 0x5abbea14447d34d7, 0x6e2458c575a203f4, 0x137c7ffafbbe5a99]),
+    U256::from_limbs([0x76ca503f5612b5c7, 0x5a314ebdd33208c7, 0x2b03446f2b456f90, 0x052a397c779ea282]),
+    U256::from_limbs([0x3919d1c337a548a8, 0xe352aa087fa6432d, 0x42175a0e731d8fa4, 0x2941a96b24fb9953]),
+    U256::from_limbs([0x164f5c33fc22d4da, 0xb5969e95c0995f17, 0x31efc029beb9d747, 0x144da3344b4fda8c]),
+    U256::from_limbs([0x2e10a9a8b13af7f8, 0xd097c6c37ddaad1f, 0x57af91dbbaeee4c1, 0x0409ea189c18f546]),
+    U256::from_limbs([0x2e6befd5a03015a2, 0xbe46d3324dd44390, 0xed2204d66f470ead, 0x0ecb7a2cfcd805a4]),
+    U256::from_limbs([0x0353850f650d41a8, 0xa5dd91e1be53d8d4, 0x92401904c7fc2d19, 0x27d179151fa1a246]),
+    U256::from_limbs([0x0acce72b71abddcd, 0x5abbea14447d34d7, 0x6e2458c575a203f4, 0x137c7ffafbbe5a99]),
+    U256::from_limbs([0x7f7076340adca4e0, 0xd68364c6aa2c6552, 0xc57f61640fd0082b, 0x09974130dc0027d3]),
+    U256::from_limbs([0x12c, 0, 0, 0]),
+    U256::from_limbs([0x15f90, 0, 0, 0]),
+    U256::from_limbs([0x258, 0, 0, 0]),
+    U256::from_limbs([0x222e0, 0, 0, 0]),
+    U256::from_limbs([0xf, 0, 0, 0]),
+    U256::from_limbs([0x0, 0, 0, 0]),
+    U256::from_limbs([0x1, 0, 0, 0]),
+    U256::from_limbs([0xb99cb1da840d892d, 0xbcf426df8aac42d8, 0x4b9790a878dbfb1b, 0x1e719123a989c5fd]),
+    U256::from_limbs([0xeeeefa4459651254, 0xe84ed1e48852e38e, 0x1fe05e44a107e62a, 0x06fe9cc477780248]),
+    U256::from_limbs([0xc8, 0, 0, 0]),
+    U256::from_limbs([0x9c40, 0, 0, 0]),
+    U256::from_limbs([0xa8c, 0, 0, 0]),
+    U256::from_limbs([0x94ed0, 0, 0, 0]),
+    U256::from_limbs([0xf, 0, 0, 0]),
+    U256::from_limbs([0x0, 0, 0, 0]),
+    U256::from_limbs([0x1, 0, 0, 0]),
+    U256::from_limbs([0x9ba2e5bc086762ec, 0x28ea9cede4fa7d15, 0x60b383d50143383d, 0x2dc56c6ca5a6f449]),
+    U256::from_limbs([0xa0c22c6f4623448e, 0x047fc30e1f4d819d, 0xdef781443bfa1800, 0x0c7696cc638805d2]),
+];
+
+/// Merkle authentication paths
+pub const QUERY_PATHS: [U256; 72] = [
+    U256::from_limbs([0xa2885ed97d536808, 0x1983414b477a3c44, 0x3969a49db23a8ba1, 0x0d5a5d3becc9d359]),
+    U256::from_limbs([0x3f915b421487f907, 0xd2335df95b6724fe, 0x442cb01a093f0f3f, 0x068385c743bd2b88]),
+    U256::from_limbs([0x0895a5509e5a3883, 0x35a9715bf8331646, 0xde94d9cc4d98cec2, 0x25eba2d8ab2ada2b]),
+    U256::from_limbs([0x55a3af3cc24d7c52, 0x9ec328c0a4de6ca6, 0x74c471c2dd9dc79c, 0x2bf54e6f78889345]),
+    U256::from_limbs([0x5cc8605b90ec15d8, 0x074ff82a410b2e05, 0x32bd97a095a575fb, 0x0d726dc5a6a8ccf1]),
+    U256::from_limbs([0x88c0634bbf846fd5, 0x2b92d9cb7626fe50, 0x1fa251abbc4e55b3, 0x1b512e87506d73f0]),
+    U256::from_limbs([0xcbbabae0e9aa5c6a, 0x2b335a4e6e497604, 0xffaf8cedb25be717, 0x17d18d30834eefdf]),
+    U256::from_limbs([0x5cb2ca65662c66c9, 0x3871b77c6c74eff3, 0x935cc9fe05027762, 0x1a5af008477dbb73]),
+    U256::from_limbs([0x03391f033c365bd8, 0x33594d6048c7dea5, 0x5ace6d1fca69a81c, 0x26d598ee7dc232b6]),
+    U256::from_limbs([0x8de41a7643f5eb36, 0x691baec3e9e4a072, 0xa3d97b9b28fafa94, 0x20a35e2466cea616]),
+    U256::from_limbs([0xc87e3004b12821f9, 0x812c6c63288febda, 0x957c358ec9e68250, 0x298ff31101a43d4b]),
+    U256::from_limbs([0x753074268596d7dd, 0x331b953d01943000, 0xea36c797ee637329, 0x26cb5bbb6e7d14f8]),
+    U256::from_limbs([0xef8fea0bdaccc26b, 0x6a50cc15c7aa9d4b, 0xb058e3c33669db99, 0x0be2549f9bfa47c2]),
+    U256::from_limbs([0x1d6c2b87d944506b, 0xc4e5f7535d808e85, 0xb4971bbc144d1483, 0x115d985811efaca7]),
+    U256::from_limbs([0xf490261ae9843bfe, 0xc634456452e4f1a5, 0x352321791b05de24, 0x039f04b6486c97fd]),
+    U256::from_limbs([0xbdc1c6855c962f43, 0x92f66630e90e4742, 0x032c56ee83e1638b, 0x1b1dd4a6000783ab]),
+    U256::from_limbs([0xb414570614d177c9, 0x94028f8b917fd274, 0xa6ece9240c9fa68c, 0x2f8aa0a051faf364]),
+    U256::from_limbs([0x910f4bffd18e3ea6, 0x13263c083a05f98d, 0xfdf0e11fc79745d5, 0x28c5200512532e1e]),
+    U256::from_limbs([0x0ba92f3f82578cd9, 0x5a5123b15686ea0b, 0xf6401f224cdd00ac, 0x186831be4efb82e4]),
+    U256::from_limbs([0xeb64adee5bd3a286, 0x43467a3ed821fe5f, 0x2d397eef5767330d, 0x22229ba45cc83989]),
+    U256::from_limbs([0x1aa620a18f81104f, 0x2b3bd3f410f98c9c, 0x0f0e1a70eadd8c56, 0x07d61e1b31b4b992]),
+    U256::from_limbs([0xd2b992b2b6379fea, 0x9f6f9b7d92a8ed86, 0xcddbe42d13c40628, 0x0ce62b59da7e1cae]),
+    U256::from_limbs([0xb795001be9bf668d, 0xe19dcd6f70fbe3de, 0x77b41d6a15ba2061, 0x2b4ac9d27d24b087]),
+    U256::from_limbs([0x991bd3ab3e1c4c87, 0xa70bce2a18001ac4, 0xce402b4efcb138dd, 0x0a628a14b906b99c]),
+    U256::from_limbs([0xb02b3e20aee0bacf, 0xa4f7d28aff3db228, 0x20597b73a5bbbd02, 0x281032f9048ef958]),
+    U256::from_limbs([0x04e6dd8aee07dc09, 0x4902528c5887ef0d, 0xb23fe34ac777e0d3, 0x1177869c2885e7aa]),
+    U256::from_limbs([0xc5efb9a723893ce0, 0x0d7fd028c3506940, 0x262af4da6aeaf3cd, 0x2a8e552e6847639b]),
+    U256::from_limbs([0xf1183faa60e337cb, 0xbd5b21ef2eb33c16, 0xf85e889e9296acf2, 0x0b981877e8b6743b]),
+    U256::from_limbs([0xd538b42e0439b823, 0x1efa3c65e8ebc14e, 0x357d9bded6c51ea5, 0x2b966913f2c232e7]),
+    U256::from_limbs([0xe9187d499708f710, 0x509d001edf57c8bf, 0x947f3d05dbbf2e00, 0x088c10a427484126]),
+    U256::from_limbs([0x57bbfad38db9302d, 0xa826ba7c4bd2eab2, 0xf8adb2414fcc1315, 0x24df8770f25a2aaf]),
+    U256::from_limbs([0x09ddb31d9ada8aad, 0x6e8e539c37934ba6, 0x5db71199b6e4cc33, 0x1e72f19fe964f613]),
+    U256::from_limbs([0x14dd6df8b4f2fe7e, 0xe7a0120177005bb5, 0xb9338d4935dae51e, 0x13c28d25554af068]),
+    U256::from_limbs([0xcc7b96da1ff2536a, 0x9e48e8aa059b52e6, 0x3077633b0190a894, 0x01f5ec85d7b6fec2]),
+    U256::from_limbs([0xa36c262351e3bd13, 0x972e5cabe8752ddf, 0x82a2e3165b9a3797, 0x25f68ae3b8b5806e]),
+    U256::from_limbs([0x4d8dc169c7eb654c, 0x267367800b8d7632, 0xe222ea16e602dff3, 0x116e706b57726685]),
+    U256::from_limbs([0xd5fed52850326087, 0xd661ddae29024f6d, 0xab97767b5ce11fad, 0x2d5ff06127ea7fc5]),
+    U256::from_limbs([0x6a9512376daf7fbd, 0xca359b2f8eeba60e, 0x3bc53f511a078c88, 0x14db91ec27627b26]),
+    U256::from_limbs([0xe9668bc8bf210cd5, 0x046b77db915cce0c, 0xbd22bec5e479cc10, 0x1cad066cef4141e1]),
+    U256::from_limbs([0x5639349051767bc7, 0x263908fa311cda09, 0x2db2e64cfb2bb8b6, 0x10e5a23fd515aadd]),
+    U256::from_limbs([0xe9679b15f6a9be76, 0xe0f5044d1750d7a7, 0x0925840bcc09528c, 0x1f164e71ced3425e]),
+    U256::from_limbs([0xb2d80724fe1e1e43, 0x4ae6f5a1ae717c8a, 0x8185db82d6809056, 0x119f7d2a84b54065]),
+    U256::from_limbs([0x9fd616c40fc0a575, 0x1e8a50f38c60de81, 0x6968b8eb9b009b7d, 0x230920c5522458c0]),
+    U256::from_limbs([0xdf4da69ffc654174, 0xa13aed24f60acda6, 0x229b7d0c9c10fda0, 0x11eb0694215f3110]),
+    U256::from_limbs([0x23299a3d6c500fd4, 0x60233159963dc7d0, 0x6e382fc7286a8c46, 0x18b2b7f39401198c]),
+    U256::from_limbs([0xc3ff0b6f639cfa9f, 0xe42aebd1763137d8, 0x94d951d937ff3071, 0x023ccbeb41aba256]),
+    U256::from_limbs([0xb4b5532e963501d5, 0x5ac256b62061ce9b, 0x509cd0d60540e445, 0x18752393ec3dfe6b]),
+    U256::from_limbs([0x01ada3eb2e3f3d99, 0xb918ebd493e93fc2, 0x39311d0f545f60bd, 0x02af49fad4e7b023]),
+    U256::from_limbs([0xd45ed8b07f5aa266, 0x13002898373b359a, 0x68def611a5db7fb9, 0x0fddb9aec98ae4ba]),
+    U256::from_limbs([0xa2fb030a2083ef7f, 0x05e1678d6ec4241b, 0xf2dce1299ee8135e, 0x2eab9383ce6e93fc]),
+    U256::from_limbs([0xba0e19c4859695e8, 0x1dc0bbb7c740f7a3, 0x7057dbf532df9fa1, 0x0d5be309cff600ac]),
+    U256::from_limbs([0xdaa7f374d2da2ea0, 0x178713934b028580, 0x3b831027ab29c413, 0x2fbde4cdc2250729]),
+    U256::from_limbs([0x13c4f0214382fb0d, 0xd4a2c5af250f8aa9, 0xdfc8c48825367483, 0x08c232dae4fc0845]),
+    U256::from_limbs([0xb15cd1214121ad63, 0x11eafe90e97774b3, 0xfc324644208f22b6, 0x0b2f56734e830d1b]),
+    U256::from_limbs([0x84b798433aa65f2c, 0xe16323cbc6ae2fb7, 0x5918fdd979bc948a, 0x19954eaf1944118b]),
+    U256::from_limbs([0x5c8ef8921afc1e28, 0xa214e6324cb27b1d, 0x9e788a994a88ad5d, 0x2f27cd80b30b6355]),
+    U256::from_limbs([0x8991f2bdadf2e688, 0x8fb2e077e0d48938, 0xd25ab629d1cd3759, 0x0efc0174d9c63ad6]),
+    U256::from_limbs([0xb210f587fbbec324, 0xbcc2e4f6b31e2f7f, 0x6d11ee8264e1e71c, 0x18c7f153b9f0629f]),
+    U256::from_limbs([0x91620d1e48831e95, 0xf018237612a2d58e, 0x758d079c46e47d42, 0x1d9d28c89fdad6c2]),
+    U256::from_limbs([0x4754afd7161b3fdb, 0xe2b88fc9fb15ca76, 0x47057d8be08d685f, 0x1d888cce7c301dbb]),
+    U256::from_limbs([0xd9c9c34a942e4d15, 0x22b16a1da36d6147, 0x8c72d3d763b8a172, 0x0e02234ee476c4e8]),
+    U256::from_limbs([0x1f213a1961cc770a, 0x117c197f9e9b3ccb, 0x501853ab73dd8063, 0x12acc8f691c2c486]),
+    U256::from_limbs([0x4c8bec40e639e458, 0x04a4b56401609766, 0xb4a61e7efd1252d9, 0x259067903cc6c844]),
+    U256::from_limbs([0xb71209488bd2ea71, 0x2af3c27efebf09b6, 0xe6e7b3f18999fb23, 0x046f859f776e13ae]),
+    U256::from_limbs([0x9a3549d3d4c6d1b7, 0xb1725f08d999d993, 0xbb86a7aee55b8e03, 0x1474a4f58e743827]),
+    U256::from_limbs([0x13c2fbbadfca58ad, 0xc39e26f829e5b613, 0x987eec56f490e4cd, 0x01d2655a7701e4d7]),
+    U256::from_limbs([0xcf3eae1cfc1873c0, 0x0f99dc98c9bf7782, 0x08c5035fdff69ca6, 0x1cf7db487aac441c]),
+    U256::from_limbs([0xf15d57fb5966c30e, 0xfbd6438ca8a68478, 0x56b1b53e8a19802e, 0x05d97aa16c670d46]),
+    U256::from_limbs([0xe14f02289fb21d1d, 0xb70a1006c432ef5c, 0xfd66de4166b02c24, 0x272db69e9cb8699e]),
+    U256::from_limbs([0xa1697810860c72da, 0x0fe28ddc37543ea7, 0x6ce67c4513b08d15, 0x1d09b70e46373b89]),
+    U256::from_limbs([0x0e71dae3be2ac1c4, 0xbd6d4caf6ca0f1f1, 0x14db72f4ae3ccdd4, 0x2fd092916c2de55e]),
+    U256::from_limbs([0x7a4537bfd35ed333, 0xbf05cd6a07a88577, 0x06aa0c37434b40bc, 0x28096f6050a1cde8]),
+];
+
+/// [num_queries, num_fri_layers, log_trace_len, protocol_hash, query indices...]
+pub const QUERY_METADATA: [U256; 6] = [
+    U256::from_limbs([0x0000000000000002, 0x0000000000000000, 0x0000000000000002, 0x0000000000000000]),
+    U256::from_limbs([0x0000000000000004, 0x0000000000000007, 0x0000000000000000, 0x0000000000000000]),
+    U256::from_limbs([0x4, 0, 0, 0]),
+    U256::from_limbs([0xfefa75555e8d5969, 0xe7556243855adcdf, 0xf6787155d7229c6b, 0x46526ea6a38c6fad]),
+    U256::from_limbs([0x8, 0, 0, 0]),
+    U256::from_limbs([0x34, 0, 0, 0]),
+];
+
+/// The embedded proof as the seven calldata arrays of `verify_sharpe_stark`.
+pub fn proof_parts() -> [Vec<U256>; 7] {
+    [
+        PUBLIC_INPUTS.to_vec(),
+        COMMITMENTS.to_vec(),
+        OOD_VALUES.to_vec(),
+        FRI_FINAL_POLY.to_vec(),
+        QUERY_VALUES.to_vec(),
+        QUERY_PATHS.to_vec(),
+        QUERY_METADATA.to_vec(),
+    ]
+}
+
+/// Verify the embedded proof; `false` means this build's verifier is broken.
+pub fn verify_embedded_proof() -> bool {
+    super::verify_sharpe_stark(
+        &PUBLIC_INPUTS,
+        &COMMITMENTS,
+        &OOD_VALUES,
+        &FRI_FINAL_POLY,
+        &QUERY_VALUES,
+        &QUERY_PATHS,
+        &QUERY_METADATA,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_proof_verifies() {
+        assert!(verify_embedded_proof());
+        assert_eq!(QUERY_METADATA[3], super::super::VERIFIER_PROTOCOL_HASH);
+    }
+}
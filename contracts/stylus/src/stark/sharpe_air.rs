@@ -1,59 +1,67 @@
 //! Sharpe Ratio AIR (Algebraic Intermediate Representation)
 //!
 //! Defines the constraint system for Sharpe ratio verification.
-//! The trace has 6 columns:
-//!   [return, return_sq, cum_ret, cum_sq, trade_count, dataset_commitment]
+//! The trace has 7 columns:
+//!   [return, return_sq, cum_ret, cum_sq, trade_count, dataset_commitment,
+//!    is_real_row]
 //!
-//! Transition constraints (5):
-//!   TC0: cum_ret_next = cum_ret + ret_next
-//!   TC1: ret_sq = ret * ret
-//!   TC2: cum_sq_next = cum_sq + ret_sq_next
-//!   TC3: trade_count_next = trade_count (immutability)
-//!   TC4: dataset_commitment_next = dataset_commitment (immutability)
+//! Transition constraints (6), TC0-TC4 gated by the current row's is_real_row:
+//!   TC0: is_real_row * (cum_ret_next - cum_ret - ret_next) = 0
+//!   TC1: is_real_row * (ret_sq - ret * ret) = 0
+//!   TC2: is_real_row * (cum_sq_next - cum_sq - ret_sq_next) = 0
+//!   TC3: is_real_row * (trade_count_next - trade_count) = 0 (immutability)
+//!   TC4: is_real_row * (dataset_commitment_next - dataset_commitment) = 0 (immutability)
+//!   TC5: is_real_row_next * (1 - is_real_row) = 0 (no real row after padding)
 //!
-//! Boundary constraints (4):
+//! Boundary constraints (5), n = trade_count:
 //!   BC0: cum_ret[0] = ret[0]                                          (at first row)
 //!   BC1: cum_sq[0] = ret_sq[0]                                        (at first row)
-//!   BC2: cum_ret[N-1] = total_return                                  (at last row)
-//!   BC3: cum_ret^2 * SCALE - sharpe_sq * (n * cum_sq - cum_ret^2) = 0 (at last row)
+//!   BC2: cum_ret[n-1] = total_return                                  (at last trade row)
+//!   BC3: cum_ret^2 * SCALE - sharpe_sq * (n * cum_sq - cum_ret^2) = 0 (at last trade row)
+//!   BC4: is_real_row[n-1] = 1                                         (at last trade row)
+//!
+//! TC5 and BC4 pin is_real_row to 1 on the trade rows, so padding rows are
+//! exempt from TC0-TC4 without the prover being able to exempt a trade.
+//! Gated TC1 has degree 3: proofs commit at least two composition segments.
 
 use crate::field::Fp;
 use crate::field::BN254Field;
 use alloy_primitives::U256;
 
 /// Number of columns in the Sharpe trace
-pub const NUM_COLUMNS: usize = 6;
+pub const NUM_COLUMNS: usize = 7;
 
 /// Number of transition constraints
-pub const NUM_TRANSITION_CONSTRAINTS: usize = 5;
+pub const NUM_TRANSITION_CONSTRAINTS: usize = 6;
 
 /// Number of boundary constraints
-pub const NUM_BOUNDARY_CONSTRAINTS: usize = 4;
+pub const NUM_BOUNDARY_CONSTRAINTS: usize = 5;
 
 /// Total number of alphas needed (transition + boundary)
 pub const NUM_ALPHAS: usize = NUM_TRANSITION_CONSTRAINTS + NUM_BOUNDARY_CONSTRAINTS;
 
-/// SHARPE_SCALE = 10000 in Montgomery form
-fn sharpe_scale_fp() -> Fp {
-    Fp::from_u256(U256::from(10000u64))
-}
+/// Composition segments a Sharpe proof must commit (composition degree < 2N)
+pub const MIN_SEGMENTS: usize = 2;
 
-/// Compute the transition constraint zerofier at OOD point z.
-/// Z_T(z) = (z^n - 1) / (z - g^{n-1})
-pub fn transition_zerofier_at(z: Fp, trace_len: u64, trace_generator: Fp) -> Fp {
-    let z_n = BN254Field::pow(z, U256::from(trace_len));
-    let numerator = BN254Field::sub(z_n, Fp::ONE);
+/// Columns held by TC3/TC4 (trade_count, dataset_commitment). The prover pads
+/// them with their one value, so each interpolates to a constant polynomial.
+pub const IMMUTABLE_COLUMNS: [usize; 2] = [4, 5];
 
-    let g_last = BN254Field::pow(trace_generator, U256::from(trace_len - 1));
-    let denominator = BN254Field::sub(z, g_last);
+/// Dataset-commitment column, whose constant-leaf Merkle root is `pi[3]`.
+pub const DATASET_COLUMN: usize = 5;
 
-    BN254Field::div(numerator, denominator)
+/// SHARPE_SCALE = 10000 in Montgomery form
+fn sharpe_scale_fp() -> Fp {
+    Fp::from_u256(U256::from(10000u64))
 }
 
 /// Evaluate transition constraints at a given point.
 ///
-/// current/next: [return, return_sq, cum_ret, cum_sq, trade_count, dataset_commitment]
-pub fn evaluate_transition(current: [Fp; 6], next: [Fp; 6]) -> [Fp; 5] {
+/// current/next: [return, return_sq, cum_ret, cum_sq, trade_count, dataset_commitment,
+///                is_real_row]
+pub fn evaluate_transition(current: [Fp; 7], next: [Fp; 7]) -> [Fp; 6] {
+    let is_real_row = current[6];
+
     // TC0: cum_ret_next - cum_ret - ret_next = 0
     let tc0 = BN254Field::sub(next[2], BN254Field::add(current[2], next[0]));
 
@@ -69,26 +77,38 @@ pub fn evaluate_transition(current: [Fp; 6], next: [Fp; 6]) -> [Fp; 5] {
     // TC4: dataset_commitment_next - dataset_commitment = 0 (immutability)
     let tc4 = BN254Field::sub(next[5], current[5]);
 
-    [tc0, tc1, tc2, tc3, tc4]
+    // TC5: is_real_row_next * (1 - is_real_row) = 0
+    let tc5 = BN254Field::mul(next[6], BN254Field::sub(Fp::ONE, is_real_row));
+
+    [
+        BN254Field::mul(is_real_row, tc0),
+        BN254Field::mul(is_real_row, tc1),
+        BN254Field::mul(is_real_row, tc2),
+        BN254Field::mul(is_real_row, tc3),
+        BN254Field::mul(is_real_row, tc4),
+        tc5,
+    ]
 }
 
 /// Evaluate transition constraints at an out-of-domain (OOD) point.
-pub fn evaluate_transition_ood(trace_at_z: [Fp; 6], trace_at_zg: [Fp; 6]) -> [Fp; 5] {
+pub fn evaluate_transition_ood(trace_at_z: [Fp; 7], trace_at_zg: [Fp; 7]) -> [Fp; 6] {
     evaluate_transition(trace_at_z, trace_at_zg)
 }
 
 /// Compute the boundary constraint quotient evaluations at OOD point z.
 ///
+/// `last_trade_point` is g^(n-1) for n = trade_count.
+///
 /// public_inputs: [trade_count, total_return, sharpe_sq_scaled, merkle_root]
 pub fn evaluate_boundary_quotients(
-    trace_at_z: [Fp; 6],
+    trace_at_z: [Fp; 7],
     z: Fp,
     trace_domain_first: Fp,
-    trace_domain_last: Fp,
+    last_trade_point: Fp,
     public_inputs: [Fp; 4],
-) -> [Fp; 4] {
+) -> [Fp; 5] {
     let den_first = BN254Field::sub(z, trace_domain_first);
-    let den_last = BN254Field::sub(z, trace_domain_last);
+    let den_last = BN254Field::sub(z, last_trade_point);
     let scale = sharpe_scale_fp();
 
     // BC0: (cum_ret - ret) / (z - g^0) at first row
@@ -99,11 +119,11 @@ pub fn evaluate_boundary_quotients(
     let num1 = BN254Field::sub(trace_at_z[3], trace_at_z[1]);
     let bq1 = BN254Field::div(num1, den_first);
 
-    // BC2: (cum_ret - total_return) / (z - g^(N-1)) at last row
+    // BC2: (cum_ret - total_return) / (z - g^(n-1)) at last trade row
     let num2 = BN254Field::sub(trace_at_z[2], public_inputs[1]);
     let bq2 = BN254Field::div(num2, den_last);
 
-    // BC3: (cum_ret^2 * SCALE - sharpe_sq * (n * cum_sq - cum_ret^2)) / (z - g^(N-1))
+    // BC3: (cum_ret^2 * SCALE - sharpe_sq * (n * cum_sq - cum_ret^2)) / (z - g^(n-1))
     let cum_ret = trace_at_z[2];
     let cum_sq = trace_at_z[3];
     let cum_ret_sq = BN254Field::mul(cum_ret, cum_ret);
@@ -114,28 +134,54 @@ pub fn evaluate_boundary_quotients(
     let num3 = BN254Field::sub(lhs, rhs);
     let bq3 = BN254Field::div(num3, den_last);
 
-    [bq0, bq1, bq2, bq3]
+    // BC4: (is_real_row - 1) / (z - g^(n-1)) at last trade row
+    let num4 = BN254Field::sub(trace_at_z[6], Fp::ONE);
+    let bq4 = BN254Field::div(num4, den_last);
+
+    [bq0, bq1, bq2, bq3, bq4]
+}
+
+/// Combine the constraint quotients into the composition value.
+///
+/// alphas[0..6] weight TQ0..TQ5 and alphas[6..11] weight BQ0..BQ4; the prover's
+/// `combine_sharpe_quotients` must use the same mapping.
+pub fn combine_quotients(
+    transition_quotients: [Fp; NUM_TRANSITION_CONSTRAINTS],
+    boundary_quotients: [Fp; NUM_BOUNDARY_CONSTRAINTS],
+    alphas: &[Fp; NUM_ALPHAS],
+) -> Fp {
+    let mut comp = Fp::ZERO;
+    for i in 0..NUM_TRANSITION_CONSTRAINTS {
+        comp = BN254Field::add(comp, BN254Field::mul(alphas[i], transition_quotients[i]));
+    }
+    for i in 0..NUM_BOUNDARY_CONSTRAINTS {
+        comp = BN254Field::add(
+            comp,
+            BN254Field::mul(alphas[NUM_TRANSITION_CONSTRAINTS + i], boundary_quotients[i]),
+        );
+    }
+    comp
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn make_valid_sharpe_pair() -> ([Fp; 6], [Fp; 6]) {
+    fn make_valid_sharpe_pair() -> ([Fp; 7], [Fp; 7]) {
         // Row i: return=100, return_sq=10000, cum_ret=100, cum_sq=10000, n=2, commit=0
         let ret = Fp::from_u256(U256::from(100u64));
         let ret_sq = Fp::from_u256(U256::from(10000u64));
         let cum_ret = Fp::from_u256(U256::from(100u64));
         let cum_sq = Fp::from_u256(U256::from(10000u64));
         let n = Fp::from_u256(U256::from(2u64));
-        let current = [ret, ret_sq, cum_ret, cum_sq, n, Fp::ZERO];
+        let current = [ret, ret_sq, cum_ret, cum_sq, n, Fp::ZERO, Fp::ONE];
 
         // Row i+1: return=200, return_sq=40000, cum_ret=300, cum_sq=50000, n=2, commit=0
         let ret_next = Fp::from_u256(U256::from(200u64));
         let ret_sq_next = Fp::from_u256(U256::from(40000u64));
         let cum_ret_next = Fp::from_u256(U256::from(300u64));
         let cum_sq_next = Fp::from_u256(U256::from(50000u64));
-        let next = [ret_next, ret_sq_next, cum_ret_next, cum_sq_next, n, Fp::ZERO];
+        let next = [ret_next, ret_sq_next, cum_ret_next, cum_sq_next, n, Fp::ZERO, Fp::ONE];
 
         (current, next)
     }
@@ -202,7 +248,7 @@ mod tests {
         let cum_ret = ret; // BC0: cum_ret = ret at first row
         let cum_sq = ret_sq; // BC1: cum_sq = ret_sq at first row
         let n = Fp::from_u256(U256::from(15u64));
-        let trace_at_z = [ret, ret_sq, cum_ret, cum_sq, n, Fp::ZERO];
+        let trace_at_z = [ret, ret_sq, cum_ret, cum_sq, n, Fp::ZERO, Fp::ONE];
 
         let z = Fp::from_u256(U256::from(12345u64));
         let first = Fp::ONE;
@@ -216,6 +262,7 @@ mod tests {
         // BC0 and BC1 numerators are zero since cum_ret=ret and cum_sq=ret_sq
         assert_eq!(bqs[0], Fp::ZERO, "BC0 should be zero");
         assert_eq!(bqs[1], Fp::ZERO, "BC1 should be zero");
+        assert_eq!(bqs[4], Fp::ZERO, "BC4 should be zero on a real row");
     }
 
     #[test]
@@ -225,12 +272,64 @@ mod tests {
         let cum_ret = Fp::from_u256(U256::from(3000u64));
         let cum_sq = Fp::from_u256(U256::from(700000u64));
 
-        let current = [Fp::ZERO, Fp::ZERO, cum_ret, cum_sq, n, Fp::ZERO];
-        let next = [Fp::ZERO, Fp::ZERO, cum_ret, cum_sq, n, Fp::ZERO];
+        let current = [Fp::ZERO, Fp::ZERO, cum_ret, cum_sq, n, Fp::ZERO, Fp::ZERO];
+        let next = [Fp::ZERO, Fp::ZERO, cum_ret, cum_sq, n, Fp::ZERO, Fp::ZERO];
 
         let constraints = evaluate_transition(current, next);
         for (i, c) in constraints.iter().enumerate() {
             assert_eq!(*c, Fp::ZERO, "TC{} should be zero for padding rows", i);
         }
     }
+
+    #[test]
+    fn test_sharpe_padding_rows_exempt() {
+        // A padding row may hold anything: TC0-TC4 are gated off
+        let junk = |seed: u64| -> [Fp; 7] {
+            core::array::from_fn(|c| if c == 6 { Fp::ZERO } else { Fp::from(seed * 31 + c as u64) })
+        };
+        let constraints = evaluate_transition(junk(6), junk(7));
+        for (i, c) in constraints.iter().enumerate() {
+            assert_eq!(*c, Fp::ZERO, "TC{} should be zero past the last trade", i);
+        }
+
+        // Selected, the same rows violate the cumulative constraints
+        let mut current = junk(6);
+        current[6] = Fp::ONE;
+        assert_ne!(evaluate_transition(current, junk(7))[0], Fp::ZERO);
+    }
+
+    #[test]
+    fn test_sharpe_trade_row_cannot_be_deselected() {
+        // TC5: padding followed by a real row
+        let (mut current, next) = make_valid_sharpe_pair();
+        current[6] = Fp::ZERO;
+        assert_ne!(evaluate_transition(current, next)[5], Fp::ZERO, "TC5 should be nonzero");
+
+        // BC4: a deselected last trade row
+        let z = Fp::from(12345u64);
+        let pi = [Fp::from(2u64), Fp::from(300u64), Fp::ZERO, Fp::ZERO];
+        let bqs = evaluate_boundary_quotients(current, z, Fp::ONE, Fp::from(99u64), pi);
+        assert_ne!(bqs[4], Fp::ZERO, "BC4 should be nonzero");
+    }
+
+    #[test]
+    fn test_combine_quotients_alpha_mapping() {
+        // Label quotient k (TQ0..TQ5, BQ0..BQ4) with k + 1 and weight it by
+        // alpha_k = 100^k: each label gets its own two-digit slot, so the
+        // result's digit pairs read back the mapping.
+        let label = |k: u64| Fp::from(k + 1);
+        let tqs = [label(0), label(1), label(2), label(3), label(4), label(5)];
+        let bqs = [label(6), label(7), label(8), label(9), label(10)];
+        let mut alphas = [Fp::ZERO; NUM_ALPHAS];
+        for (k, alpha) in alphas.iter_mut().enumerate() {
+            *alpha = Fp::from_u256(U256::from(100u128.pow(k as u32)));
+        }
+
+        // Pinned against the prover's `combine_sharpe_quotients`
+        assert_eq!(combine_quotients(tqs, bqs, &alphas), Fp::from_u256(U256::from(11_10_09_08_07_06_05_04_03_02_01u128)));
+
+        // Any swapped coefficient changes the digit pairs
+        alphas.swap(5, 6);
+        assert_ne!(combine_quotients(tqs, bqs, &alphas), Fp::from_u256(U256::from(11_10_09_08_07_06_05_04_03_02_01u128)));
+    }
 }
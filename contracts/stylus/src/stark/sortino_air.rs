@@ -0,0 +1,289 @@
+//! Sortino Ratio AIR (Algebraic Intermediate Representation)
+//!
+//! Defines the constraint system for Sortino ratio verification: the Sharpe
+//! AIR with the variance replaced by the downside deviation.
+//! The trace has 8 columns:
+//!   [return, downside_sq, cum_ret, cum_downside_sq, trade_count,
+//!    dataset_commitment, is_negative, is_real_row]
+//!
+//! Transition constraints (7), TC0-TC5 gated by the current row's is_real_row:
+//!   TC0: cum_ret_next = cum_ret + ret_next
+//!   TC1: downside_sq = is_negative * ret * ret
+//!   TC2: cum_downside_sq_next = cum_downside_sq + downside_sq_next
+//!   TC3: trade_count_next = trade_count (immutability)
+//!   TC4: dataset_commitment_next = dataset_commitment (immutability)
+//!   TC5: is_negative * (is_negative - 1) = 0 (boolean selector)
+//!   TC6: is_real_row_next * (1 - is_real_row) = 0 (no real row after padding)
+//!
+//! Boundary constraints (5), n = trade_count:
+//!   BC0: cum_ret[0] = ret[0]                                        (at first row)
+//!   BC1: cum_downside_sq[0] = downside_sq[0]                        (at first row)
+//!   BC2: cum_ret[n-1] = total_return                                (at last trade row)
+//!   BC3: cum_ret^2 * SCALE - sortino_sq * n * cum_downside_sq = 0   (at last trade row)
+//!   BC4: is_real_row[n-1] = 1                                       (at last trade row)
+//!
+//! The is_negative selector is range-checked to {0, 1} but not tied to the
+//! sign of the return: like the returns themselves, it is a prover witness.
+//! Gated TC1 has degree 4, so proofs commit at least three composition
+//! segments.
+
+use crate::field::Fp;
+use crate::field::BN254Field;
+use alloy_primitives::U256;
+
+/// Number of columns in the Sortino trace
+pub const NUM_COLUMNS: usize = 8;
+
+/// Number of transition constraints
+pub const NUM_TRANSITION_CONSTRAINTS: usize = 7;
+
+/// Number of boundary constraints
+pub const NUM_BOUNDARY_CONSTRAINTS: usize = 5;
+
+/// Total number of alphas needed (transition + boundary)
+pub const NUM_ALPHAS: usize = NUM_TRANSITION_CONSTRAINTS + NUM_BOUNDARY_CONSTRAINTS;
+
+/// Composition segments a Sortino proof must commit (composition degree < 3N)
+pub const MIN_SEGMENTS: usize = 3;
+
+/// Columns held by TC3/TC4 (trade_count, dataset_commitment), constant
+/// polynomials as in `sharpe_air::IMMUTABLE_COLUMNS`.
+pub const IMMUTABLE_COLUMNS: [usize; 2] = [4, 5];
+
+/// Dataset-commitment column, whose constant-leaf Merkle root is `pi[3]`.
+pub const DATASET_COLUMN: usize = 5;
+
+/// SORTINO_SCALE = 10000 in Montgomery form (same scale as Sharpe)
+fn sortino_scale_fp() -> Fp {
+    Fp::from_u256(U256::from(10000u64))
+}
+
+/// Evaluate transition constraints at a given point.
+///
+/// current/next: [return, downside_sq, cum_ret, cum_downside_sq, trade_count,
+///                dataset_commitment, is_negative, is_real_row]
+pub fn evaluate_transition(current: [Fp; 8], next: [Fp; 8]) -> [Fp; 7] {
+    let is_real_row = current[7];
+
+    // TC0: cum_ret_next - cum_ret - ret_next = 0
+    let tc0 = BN254Field::sub(next[2], BN254Field::add(current[2], next[0]));
+
+    // TC1: downside_sq - is_negative * ret * ret = 0
+    let ret_sq = BN254Field::mul(current[0], current[0]);
+    let tc1 = BN254Field::sub(current[1], BN254Field::mul(current[6], ret_sq));
+
+    // TC2: cum_downside_sq_next - cum_downside_sq - downside_sq_next = 0
+    let tc2 = BN254Field::sub(next[3], BN254Field::add(current[3], next[1]));
+
+    // TC3: trade_count_next - trade_count = 0 (immutability)
+    let tc3 = BN254Field::sub(next[4], current[4]);
+
+    // TC4: dataset_commitment_next - dataset_commitment = 0 (immutability)
+    let tc4 = BN254Field::sub(next[5], current[5]);
+
+    // TC5: is_negative^2 - is_negative = 0 (boolean)
+    let tc5 = BN254Field::sub(BN254Field::mul(current[6], current[6]), current[6]);
+
+    // TC6: is_real_row_next * (1 - is_real_row) = 0
+    let tc6 = BN254Field::mul(next[7], BN254Field::sub(Fp::ONE, is_real_row));
+
+    [
+        BN254Field::mul(is_real_row, tc0),
+        BN254Field::mul(is_real_row, tc1),
+        BN254Field::mul(is_real_row, tc2),
+        BN254Field::mul(is_real_row, tc3),
+        BN254Field::mul(is_real_row, tc4),
+        BN254Field::mul(is_real_row, tc5),
+        tc6,
+    ]
+}
+
+/// Evaluate transition constraints at an out-of-domain (OOD) point.
+pub fn evaluate_transition_ood(trace_at_z: [Fp; 8], trace_at_zg: [Fp; 8]) -> [Fp; 7] {
+    evaluate_transition(trace_at_z, trace_at_zg)
+}
+
+/// Compute the boundary constraint quotient evaluations at OOD point z.
+///
+/// `last_trade_point` is g^(n-1) for n = trade_count.
+///
+/// public_inputs: [trade_count, total_return, sortino_sq_scaled, merkle_root]
+pub fn evaluate_boundary_quotients(
+    trace_at_z: [Fp; 8],
+    z: Fp,
+    trace_domain_first: Fp,
+    last_trade_point: Fp,
+    public_inputs: [Fp; 4],
+) -> [Fp; 5] {
+    let den_first = BN254Field::sub(z, trace_domain_first);
+    let den_last = BN254Field::sub(z, last_trade_point);
+    let scale = sortino_scale_fp();
+
+    // BC0: (cum_ret - ret) / (z - g^0) at first row
+    let num0 = BN254Field::sub(trace_at_z[2], trace_at_z[0]);
+    let bq0 = BN254Field::div(num0, den_first);
+
+    // BC1: (cum_downside_sq - downside_sq) / (z - g^0) at first row
+    let num1 = BN254Field::sub(trace_at_z[3], trace_at_z[1]);
+    let bq1 = BN254Field::div(num1, den_first);
+
+    // BC2: (cum_ret - total_return) / (z - g^(n-1)) at last trade row
+    let num2 = BN254Field::sub(trace_at_z[2], public_inputs[1]);
+    let bq2 = BN254Field::div(num2, den_last);
+
+    // BC3: (cum_ret^2 * SCALE - sortino_sq * n * cum_downside_sq) / (z - g^(n-1))
+    let cum_ret = trace_at_z[2];
+    let cum_ret_sq = BN254Field::mul(cum_ret, cum_ret);
+    let lhs = BN254Field::mul(cum_ret_sq, scale);
+    let n_cum_downside_sq = BN254Field::mul(public_inputs[0], trace_at_z[3]);
+    let rhs = BN254Field::mul(public_inputs[2], n_cum_downside_sq);
+    let num3 = BN254Field::sub(lhs, rhs);
+    let bq3 = BN254Field::div(num3, den_last);
+
+    // BC4: (is_real_row - 1) / (z - g^(n-1)) at last trade row
+    let num4 = BN254Field::sub(trace_at_z[7], Fp::ONE);
+    let bq4 = BN254Field::div(num4, den_last);
+
+    [bq0, bq1, bq2, bq3, bq4]
+}
+
+/// Combine the constraint quotients into the composition value.
+///
+/// alphas[0..7] weight TQ0..TQ6 and alphas[7..12] weight BQ0..BQ4; the prover's
+/// `combine_sortino_quotients` must use the same mapping.
+pub fn combine_quotients(
+    transition_quotients: [Fp; NUM_TRANSITION_CONSTRAINTS],
+    boundary_quotients: [Fp; NUM_BOUNDARY_CONSTRAINTS],
+    alphas: &[Fp; NUM_ALPHAS],
+) -> Fp {
+    let mut comp = Fp::ZERO;
+    for i in 0..NUM_TRANSITION_CONSTRAINTS {
+        comp = BN254Field::add(comp, BN254Field::mul(alphas[i], transition_quotients[i]));
+    }
+    for i in 0..NUM_BOUNDARY_CONSTRAINTS {
+        comp = BN254Field::add(
+            comp,
+            BN254Field::mul(alphas[NUM_TRANSITION_CONSTRAINTS + i], boundary_quotients[i]),
+        );
+    }
+    comp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_valid_sortino_pair() -> ([Fp; 8], [Fp; 8]) {
+        // Row i: return=-100, downside_sq=10000, cum_ret=-100, cum_down=10000, n=2, neg=1
+        let ret = BN254Field::sub(Fp::ZERO, Fp::from(100u64));
+        let downside_sq = Fp::from(10000u64);
+        let n = Fp::from(2u64);
+        let current = [ret, downside_sq, ret, downside_sq, n, Fp::ZERO, Fp::ONE, Fp::ONE];
+
+        // Row i+1: return=300, downside_sq=0, cum_ret=200, cum_down=10000, n=2, neg=0
+        let ret_next = Fp::from(300u64);
+        let cum_ret_next = Fp::from(200u64);
+        let next = [ret_next, Fp::ZERO, cum_ret_next, downside_sq, n, Fp::ZERO, Fp::ZERO, Fp::ONE];
+
+        (current, next)
+    }
+
+    #[test]
+    fn test_sortino_transition_valid() {
+        let (current, next) = make_valid_sortino_pair();
+        let constraints = evaluate_transition(current, next);
+
+        for (i, c) in constraints.iter().enumerate() {
+            assert_eq!(*c, Fp::ZERO, "TC{} should be zero for valid trace", i);
+        }
+    }
+
+    #[test]
+    fn test_sortino_transition_tc1_selector_gates_downside() {
+        // A winning row may not contribute downside
+        let (current, mut next) = make_valid_sortino_pair();
+        next[1] = Fp::from(90000u64);
+        let constraints = evaluate_transition(next, current);
+        assert_ne!(constraints[1], Fp::ZERO, "TC1 should be nonzero");
+    }
+
+    #[test]
+    fn test_sortino_padding_rows_exempt() {
+        // A padding row may hold anything, even a non-boolean is_negative
+        let junk = |seed: u64| -> [Fp; 8] {
+            core::array::from_fn(|c| if c == 7 { Fp::ZERO } else { Fp::from(seed * 31 + c as u64 + 2) })
+        };
+        let constraints = evaluate_transition(junk(13), junk(14));
+        for (i, c) in constraints.iter().enumerate() {
+            assert_eq!(*c, Fp::ZERO, "TC{} should be zero past the last trade", i);
+        }
+
+        // Selected, the junk violates TC5 (boolean is_negative)
+        let mut current = junk(13);
+        current[7] = Fp::ONE;
+        assert_ne!(evaluate_transition(current, junk(14))[5], Fp::ZERO);
+    }
+
+    #[test]
+    fn test_sortino_transition_tc5_boolean_selector() {
+        let (mut current, next) = make_valid_sortino_pair();
+        // A selector of 2 would double-count the downside
+        current[6] = Fp::from(2u64);
+        current[1] = BN254Field::mul(Fp::from(2u64), current[1]);
+        let constraints = evaluate_transition(current, next);
+        assert_eq!(constraints[1], Fp::ZERO);
+        assert_ne!(constraints[5], Fp::ZERO, "TC5 should be nonzero");
+    }
+
+    #[test]
+    fn test_sortino_transition_tc2_violated() {
+        let (current, mut next) = make_valid_sortino_pair();
+        next[3] = Fp::from(999u64);
+        let constraints = evaluate_transition(current, next);
+        assert_ne!(constraints[2], Fp::ZERO, "TC2 should be nonzero");
+    }
+
+    #[test]
+    fn test_sortino_boundary_bc3() {
+        // Bot D-style last row: 20 trades, cum_ret = 2000, cum_down = 40000
+        // 2000^2 * 10000 = 50000 * 20 * 40000
+        let n = Fp::from(20u64);
+        let cum_ret = Fp::from(2000u64);
+        let cum_down = Fp::from(40000u64);
+        let trace_at_z = [Fp::ZERO, Fp::ZERO, cum_ret, cum_down, n, Fp::ZERO, Fp::ZERO, Fp::ONE];
+
+        let z = Fp::from(12345u64);
+        let first = Fp::ONE;
+        let last = Fp::from(99u64);
+        let pi = [n, cum_ret, Fp::from(50000u64), Fp::ZERO];
+        let bqs = evaluate_boundary_quotients(trace_at_z, z, first, last, pi);
+        assert_eq!(bqs[2], Fp::ZERO, "BC2 should be zero");
+        assert_eq!(bqs[3], Fp::ZERO, "BC3 should be zero");
+        assert_eq!(bqs[4], Fp::ZERO, "BC4 should be zero");
+
+        let pi = [n, cum_ret, Fp::from(50001u64), Fp::ZERO];
+        let bqs = evaluate_boundary_quotients(trace_at_z, z, first, last, pi);
+        assert_ne!(bqs[3], Fp::ZERO, "BC3 should be nonzero for a wrong claim");
+    }
+
+    #[test]
+    fn test_combine_quotients_alpha_mapping() {
+        // Label quotient k (TQ0..TQ6, BQ0..BQ4) with k + 1 and weight it by
+        // alpha_k = 100^k: each label gets its own two-digit slot, so the
+        // result's digit pairs read back the mapping.
+        let label = |k: u64| Fp::from(k + 1);
+        let tqs = [label(0), label(1), label(2), label(3), label(4), label(5), label(6)];
+        let bqs = [label(7), label(8), label(9), label(10), label(11)];
+        let mut alphas = [Fp::ZERO; NUM_ALPHAS];
+        for (k, alpha) in alphas.iter_mut().enumerate() {
+            *alpha = Fp::from_u256(U256::from(100u128.pow(k as u32)));
+        }
+
+        // Pinned against the prover's `combine_sortino_quotients`
+        assert_eq!(combine_quotients(tqs, bqs, &alphas), Fp::from_u256(U256::from(12_11_10_09_08_07_06_05_04_03_02_01u128)));
+
+        // Any swapped coefficient changes the digit pairs
+        alphas.swap(6, 7);
+        assert_ne!(combine_quotients(tqs, bqs, &alphas), Fp::from_u256(U256::from(12_11_10_09_08_07_06_05_04_03_02_01u128)));
+    }
+}
@@ -0,0 +1,117 @@
+//! Fiat-Shamir Transcript Conventions
+//!
+//! The prover's and verifier's channels differ in element representation
+//! (canonical U256 vs Montgomery `Fp`) but must serialize the transcript
+//! hash and map challenges to query indices identically.
+
+use alloy_primitives::U256;
+
+/// Transcript hash selector, serialized into `query_metadata[0]` bits 64..128.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TranscriptKind {
+    #[default]
+    Keccak = 0,
+    Poseidon = 1,
+}
+
+impl TranscriptKind {
+    /// Decode a metadata tag; unknown tags are rejected.
+    pub fn from_tag(tag: u64) -> Option<Self> {
+        match tag {
+            0 => Some(TranscriptKind::Keccak),
+            1 => Some(TranscriptKind::Poseidon),
+            _ => None,
+        }
+    }
+
+    /// Inverse of `from_tag`.
+    pub fn tag(self) -> u64 {
+        self as u64
+    }
+}
+
+/// Statement a proof is for, framed into its Fiat-Shamir seed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofKind {
+    Sharpe = 1,
+    Sortino = 2,
+}
+
+impl ProofKind {
+    /// First word of the seed fold: the public input count in limb 0 and the
+    /// proof kind in limb 1. Equal public inputs of different kinds, or a
+    /// vector and its extension by one input, thus seed different transcripts.
+    pub fn seed_frame(self, num_public_inputs: usize) -> U256 {
+        U256::from_limbs([num_public_inputs as u64, self as u64, 0, 0])
+    }
+}
+
+/// Fiat-Shamir seed: `h = frame`, then `h = hash_two(h, pi)` for each public
+/// input. Prover and verifier both seed their channel through this fold, with
+/// `frame` from `ProofKind::seed_frame` in their element representation.
+pub fn public_input_seed<T>(
+    frame: T,
+    public_inputs: impl IntoIterator<Item = T>,
+    hash_two: impl FnMut(T, T) -> T,
+) -> T {
+    public_inputs.into_iter().fold(frame, hash_two)
+}
+
+/// Query index drawn from a challenge: its low bits, for a power-of-two
+/// `domain_size`.
+///
+/// `challenge` is the canonical field element (the verifier converts out of
+/// Montgomery form first), so the index is the low `log2(domain_size)` bits
+/// of its `Fp::to_canonical_bytes` encoding on both sides. A uniform
+/// challenge in [0, p) biases an index by at most `domain_size / p` (below
+/// 2^-220 for any LDE domain), so there is no rejection sampling. Callers
+/// redraw duplicates, prover and verifier alike (`Channel::draw_queries`,
+/// `draw_queries_into`).
+#[inline]
+pub fn query_index(challenge: U256, domain_size: usize) -> usize {
+    let mask = U256::from((domain_size - 1) as u64);
+    (challenge & mask).as_limbs()[0] as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_tags_roundtrip() {
+        for kind in [TranscriptKind::Keccak, TranscriptKind::Poseidon] {
+            assert_eq!(TranscriptKind::from_tag(kind.tag()), Some(kind));
+        }
+        assert_eq!(TranscriptKind::from_tag(2), None);
+        assert_eq!(TranscriptKind::default(), TranscriptKind::Keccak);
+    }
+
+    #[test]
+    fn test_seed_frame_binds_kind_and_count() {
+        let sharpe = ProofKind::Sharpe.seed_frame(4);
+        assert_ne!(sharpe, ProofKind::Sortino.seed_frame(4));
+        assert_ne!(sharpe, ProofKind::Sharpe.seed_frame(5));
+
+        // The frame is folded in first, ahead of every input
+        let order = |a: U256, b: U256| (a << 8) | b;
+        let inputs = [U256::from(7u64), U256::from(9u64)];
+        let seed = public_input_seed(U256::from(1u64), inputs, order);
+        assert_eq!(seed, U256::from(0x01_07_09u64));
+    }
+
+    #[test]
+    fn test_query_index_masks_low_bits() {
+        let challenge = U256::from(0x1234_5678u64) | (U256::from(1u64) << 200);
+        assert_eq!(query_index(challenge, 64), 0x38);
+        assert_eq!(query_index(challenge, 1), 0);
+    }
+
+    #[test]
+    fn test_query_index_is_low_bits_of_be_bytes() {
+        let challenge = U256::from_be_bytes([0xa7; 32]);
+        let bytes = challenge.to_be_bytes::<32>();
+        assert_eq!(query_index(challenge, 64), (bytes[31] & 0x3f) as usize);
+        let low = u16::from_be_bytes([bytes[30], bytes[31]]);
+        assert_eq!(query_index(challenge, 1 << 12), (low & 0x0fff) as usize);
+    }
+}
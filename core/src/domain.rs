@@ -0,0 +1,81 @@
+//! Evaluation Domain Constants
+//!
+//! Roots of unity for the BN254 scalar field.
+//! BN254 scalar field order: p - 1 = 2^28 × 3^2 × 13 × 29 × ...
+//! This gives us a multiplicative subgroup of order 2^28.
+
+use crate::field::Fp;
+
+/// Generator of the 2^28 multiplicative subgroup of BN254 scalar field (Montgomery form).
+/// g = 5^((p-1)/2^28) mod p
+pub const GENERATOR_2_28: Fp = Fp::from_raw([
+    0x636e735580d13d9c,
+    0xa22bf3742445ffd6,
+    0x56452ac01eb203d8,
+    0x1860ef942963f9e7,
+]);
+
+/// TWO_ADICITY: The largest k such that 2^k divides (p-1).
+/// For BN254 scalar field, this is 28.
+pub const TWO_ADICITY: u32 = 28;
+
+/// log2 of a supported LDE blowup factor (2, 4, 8 or 16); `None` for anything
+/// else.
+pub fn log_blowup(blowup: u32) -> Option<u32> {
+    match blowup {
+        2 | 4 | 8 | 16 => Some(blowup.trailing_zeros()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::BN254_PRIME;
+    use alloy_primitives::U256;
+
+    #[test]
+    fn test_log_blowup() {
+        for (blowup, log) in [(2, 1), (4, 2), (8, 3), (16, 4)] {
+            assert_eq!(log_blowup(blowup), Some(log));
+        }
+        for unsupported in [0, 1, 3, 6, 32] {
+            assert_eq!(log_blowup(unsupported), None);
+        }
+    }
+
+    #[test]
+    fn test_generator_2_28_is_root_of_unity() {
+        let mut val = GENERATOR_2_28;
+        for _ in 0..28 {
+            val = Fp::mul(val, val);
+        }
+        assert_eq!(val, Fp::ONE, "g^(2^28) != 1");
+    }
+
+    #[test]
+    fn test_generator_2_28_is_primitive() {
+        let mut val = GENERATOR_2_28;
+        for _ in 0..27 {
+            val = Fp::mul(val, val);
+        }
+        assert_ne!(val, Fp::ONE, "g^(2^27) == 1, not primitive");
+    }
+
+    #[test]
+    fn test_generator_2_28_derivation() {
+        // g = 5^((p-1)/2^28), recomputed from scratch
+        let exp = (BN254_PRIME - U256::from(1u64)) >> TWO_ADICITY as usize;
+        let derived = Fp::pow(Fp::from_u256(U256::from(5u64)), exp);
+        assert_eq!(derived, GENERATOR_2_28);
+
+        // Canonical form, as the prover uses it
+        let canonical = U256::from_limbs([
+            0x9bd61b6e725b19f0,
+            0x402d111e41112ed4,
+            0x00e0a7eb8ef62abc,
+            0x2a3c09f0a58a7e85,
+        ]);
+        assert_eq!(GENERATOR_2_28.to_u256(), canonical);
+    }
+}
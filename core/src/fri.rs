@@ -0,0 +1,47 @@
+//! FRI Query-Value Layout
+//!
+//! The FRI section of `query_values` is query-major: for each query, for each
+//! layer, f(x) then f(-x). The prover writes it and the verifier reads it
+//! through `qv_index`, so the ordering is defined only here.
+
+/// Values opened per query and FRI layer: f(x) and f(-x).
+pub const VALUES_PER_LAYER: usize = 2;
+
+/// Which value of a layer's opened pair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerValue {
+    /// f(x) at the query point.
+    X = 0,
+    /// f(-x), its sibling in the fold.
+    NegX = 1,
+}
+
+/// Length of the FRI section for `num_queries` queries over `num_layers` layers.
+pub const fn query_values_len(num_queries: usize, num_layers: usize) -> usize {
+    num_queries * num_layers * VALUES_PER_LAYER
+}
+
+/// Position of `which` value of (`query`, `layer`) in the FRI section.
+pub const fn qv_index(num_layers: usize, query: usize, layer: usize, which: LayerValue) -> usize {
+    (query * num_layers + layer) * VALUES_PER_LAYER + which as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qv_index_enumerates_section_in_order() {
+        let (num_queries, num_layers) = (3, 4);
+        let mut expected = 0;
+        for query in 0..num_queries {
+            for layer in 0..num_layers {
+                for which in [LayerValue::X, LayerValue::NegX] {
+                    assert_eq!(qv_index(num_layers, query, layer, which), expected);
+                    expected += 1;
+                }
+            }
+        }
+        assert_eq!(expected, query_values_len(num_queries, num_layers));
+    }
+}
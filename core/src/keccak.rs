@@ -0,0 +1,157 @@
+//! Keccak Encoding of Field Elements
+//!
+//! Merkle nodes and the Keccak transcript hash a pair of field elements as:
+//!   1. Each element's canonical value serialized as 32-byte **big-endian**.
+//!   2. The two 32-byte chunks concatenated into a 64-byte buffer.
+//!   3. keccak256 applied to the 64-byte buffer.
+//!   4. The 32-byte digest read as a big-endian U256 and reduced mod BN254_PRIME.
+//!
+//! The verifier runs step 3 through the Stylus keccak host function; the
+//! prover uses the software implementation behind the `keccak` feature.
+//!
+//! A multi-column trace row is combined into one leaf by `fold_row`: the
+//! sequential pair hash `hash(...hash(hash(c0, c1), c2)..., cN)`.
+//!
+//! Merkle nodes and the Keccak transcript use the *tagged* pair hash: a
+//! one-byte domain tag (`TAG_*`) before the two words, 65 bytes in all, so a
+//! node hash, a transcript commit and a challenge draw over the same pair
+//! never coincide. Leaves, row folds and dataset commitments stay untagged.
+
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+
+/// The 64-byte keccak input for the pair `(a, b)` (steps 1-2).
+#[inline]
+pub fn hash_two_preimage(a: &[u8; 32], b: &[u8; 32]) -> [u8; 64] {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(a);
+    buf[32..].copy_from_slice(b);
+    buf
+}
+
+/// Tag of internal nodes in STARK commitment trees and `Ordered` dataset trees.
+pub const TAG_MERKLE_NODE: u8 = 1;
+
+/// Tag of a Keccak transcript absorbing a value (commits and the public-input seed).
+pub const TAG_CHANNEL_COMMIT: u8 = 2;
+
+/// Tag of a Keccak transcript deriving a challenge or query index.
+pub const TAG_CHANNEL_DRAW: u8 = 3;
+
+/// The 65-byte keccak input for the pair `(a, b)` under domain `tag`: the
+/// tag byte, then the 64-byte `hash_two_preimage`.
+#[inline]
+pub fn hash_two_tagged_preimage(tag: u8, a: &[u8; 32], b: &[u8; 32]) -> [u8; 65] {
+    let mut buf = [0u8; 65];
+    buf[0] = tag;
+    buf[1..33].copy_from_slice(a);
+    buf[33..].copy_from_slice(b);
+    buf
+}
+
+/// Words as 32-byte big-endian chunks back to back: the keccak input of a
+/// word-array hash, laid out like Solidity's `abi.encodePacked(uint256[])`.
+pub fn packed_words(words: &[U256]) -> Vec<u8> {
+    words.iter().flat_map(|w| w.to_be_bytes::<32>()).collect()
+}
+
+/// Combine a trace row's columns into its leaf value: `h = c0`, then
+/// `h = hash_two(h, c)` for each further column. A one-column row is its
+/// value, as a single-column commitment's leaf is.
+///
+/// Prover and verifier both commit and open trace rows through this fold
+/// with their `keccak_hash_two`; leaf salting is applied on top.
+pub fn fold_row<T>(row: impl IntoIterator<Item = T>, hash_two: impl FnMut(T, T) -> T) -> T {
+    let mut columns = row.into_iter();
+    let first = columns.next().expect("trace row has no columns");
+    columns.fold(first, hash_two)
+}
+
+#[cfg(feature = "keccak")]
+pub use software::{keccak256, keccak_hash_two, keccak_hash_two_tagged};
+
+#[cfg(feature = "keccak")]
+mod software {
+    use alloy_primitives::U256;
+    use tiny_keccak::{Hasher, Keccak};
+
+    use super::{hash_two_preimage, hash_two_tagged_preimage};
+    use crate::field::Fp;
+
+    /// Keccak256 hash of a byte slice.
+    pub fn keccak256(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Keccak::v256();
+        let mut output = [0u8; 32];
+        hasher.update(data);
+        hasher.finalize(&mut output);
+        output
+    }
+
+    /// Hash two canonical field elements (steps 1-4).
+    pub fn keccak_hash_two(a: U256, b: U256) -> U256 {
+        let hash = keccak256(&hash_two_preimage(&a.to_be_bytes::<32>(), &b.to_be_bytes::<32>()));
+        // Same reduction as the verifier's `Fp::from_u256` of the digest
+        Fp::from_u256(U256::from_be_bytes(hash)).to_u256()
+    }
+
+    /// `keccak_hash_two` over the tagged 65-byte preimage.
+    pub fn keccak_hash_two_tagged(tag: u8, a: U256, b: U256) -> U256 {
+        let hash = keccak256(&hash_two_tagged_preimage(tag, &a.to_be_bytes::<32>(), &b.to_be_bytes::<32>()));
+        Fp::from_u256(U256::from_be_bytes(hash)).to_u256()
+    }
+}
+
+#[cfg(all(test, feature = "keccak"))]
+mod tests {
+    use super::*;
+    use crate::field::BN254_PRIME;
+
+    #[test]
+    fn test_fold_row_order() {
+        let row = [U256::from(1u64), U256::from(2u64), U256::from(3u64)];
+        let chained = keccak_hash_two(keccak_hash_two(row[0], row[1]), row[2]);
+        assert_eq!(fold_row(row, keccak_hash_two), chained);
+        assert_eq!(fold_row([row[0]], keccak_hash_two), row[0]);
+    }
+
+    #[test]
+    fn test_keccak_hash_two_is_reduced() {
+        for i in 0..64u64 {
+            assert!(keccak_hash_two(U256::from(i), U256::from(i + 1)) < BN254_PRIME);
+        }
+    }
+
+    #[test]
+    fn test_preimage_layout() {
+        let buf = hash_two_preimage(&[1u8; 32], &[2u8; 32]);
+        assert_eq!(keccak256(&buf), keccak256(&[[1u8; 32], [2u8; 32]].concat()));
+    }
+
+    #[test]
+    fn test_tagged_preimage_layout() {
+        let buf = hash_two_tagged_preimage(TAG_MERKLE_NODE, &[1u8; 32], &[2u8; 32]);
+        assert_eq!(buf[0], TAG_MERKLE_NODE);
+        assert_eq!(buf[1..], hash_two_preimage(&[1u8; 32], &[2u8; 32])[..]);
+    }
+
+    #[test]
+    fn test_tags_separate_domains() {
+        let (a, b) = (U256::from(1u64), U256::from(2u64));
+        let tags = [TAG_MERKLE_NODE, TAG_CHANNEL_COMMIT, TAG_CHANNEL_DRAW];
+        let hashes = tags.map(|tag| keccak_hash_two_tagged(tag, a, b));
+        for (i, h) in hashes.iter().enumerate() {
+            assert_ne!(*h, keccak_hash_two(a, b));
+            assert!(*h < BN254_PRIME);
+            assert!(hashes[i + 1..].iter().all(|other| other != h));
+        }
+    }
+
+    #[test]
+    fn test_packed_words_matches_pair_preimage() {
+        let (a, b) = (U256::from(7u64), BN254_PRIME - U256::from(1u64));
+        let packed = packed_words(&[a, b]);
+        assert_eq!(packed.len(), 64);
+        assert_eq!(packed[..], hash_two_preimage(&a.to_be_bytes(), &b.to_be_bytes())[..]);
+        assert!(packed_words(&[]).is_empty());
+    }
+}
@@ -0,0 +1,23 @@
+//! STARK Core - primitives shared by the prover and the on-chain verifier
+//!
+//! The prover (`stark-prover`) and the Stylus verifier (`stark-verifier`)
+//! must agree bit-for-bit on field arithmetic, roots of unity, the keccak
+//! encoding of field elements, the Poseidon permutation, the transcript tags
+//! and the receipt-trie RLP rules. Both depend on this crate for them instead
+//! of carrying their own copies.
+//!
+//! `no_std` (with `alloc`) so it builds into the Stylus contract.
+
+#![no_std]
+extern crate alloc;
+#[cfg(any(test, feature = "call-counters"))]
+extern crate std;
+
+pub mod channel;
+pub mod domain;
+pub mod field;
+pub mod fri;
+pub mod keccak;
+pub mod mpt;
+pub mod poseidon;
+pub mod rlp;
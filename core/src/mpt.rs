@@ -0,0 +1,325 @@
+//! MPT (Merkle Patricia Trie) Proof Traversal
+//!
+//! Ethereum's receipt trie maps the RLP-encoded transaction index to the
+//! receipt. The prover checks its fetched proofs and the verifier checks
+//! calldata with the same rules, so a key or leaf accepted off-chain is
+//! accepted on-chain and vice versa.
+//!
+//! Node hashes go through a caller-supplied keccak256: the verifier passes
+//! the Stylus host function, the prover the software one.
+
+use alloc::vec::Vec;
+
+use crate::rlp;
+
+/// Walk an MPT proof from `root` along `key`, returning the leaf value.
+///
+/// `proof_nodes` are the RLP-encoded nodes from root to leaf that are
+/// referenced by hash; children shorter than 32 bytes are embedded in their
+/// parent and have no proof node of their own.
+///
+/// Returns `Some(leaf_value)` if the proof is valid, `None` otherwise.
+pub fn verify_proof(
+    root: &[u8; 32],
+    key: &[u8],
+    proof_nodes: &[Vec<u8>],
+    keccak256: impl Fn(&[u8]) -> [u8; 32],
+) -> Option<Vec<u8>> {
+    if proof_nodes.is_empty() {
+        return None;
+    }
+
+    let key_nibbles = bytes_to_nibbles(key);
+    let mut key_offset = 0;
+    let mut remaining = proof_nodes.iter();
+    // The root is always referenced by hash, whatever its size
+    let mut next = ChildRef::Hash(*root);
+
+    loop {
+        // Hashed children consume the next proof node; inline children are the node
+        let inline_node;
+        let node_rlp: &[u8] = match next {
+            ChildRef::Hash(expected_hash) => {
+                let node = remaining.next()?;
+                if keccak256(node) != expected_hash {
+                    return None;
+                }
+                node
+            }
+            ChildRef::Inline(node) => {
+                inline_node = node;
+                &inline_node
+            }
+        };
+
+        let items = rlp::decode_list(node_rlp)?;
+
+        match items.len() {
+            17 => {
+                // Branch node: 16 children + value
+                if key_offset >= key_nibbles.len() {
+                    // We're at the end of the key, return the value
+                    return Some(items[16].clone());
+                }
+                let nibble = key_nibbles[key_offset] as usize;
+                if nibble >= 16 {
+                    return None;
+                }
+                key_offset += 1;
+
+                next = ChildRef::decode(&items[nibble])?;
+            }
+            2 => {
+                // Extension or Leaf node
+                let (prefix_nibbles, is_leaf) = decode_hp_prefix(&items[0])?;
+
+                // Verify key path matches
+                for nibble in &prefix_nibbles {
+                    if key_offset >= key_nibbles.len() || key_nibbles[key_offset] != *nibble {
+                        return None;
+                    }
+                    key_offset += 1;
+                }
+
+                if is_leaf {
+                    // Leaf node — return the value
+                    if key_offset == key_nibbles.len() {
+                        return Some(items[1].clone());
+                    }
+                    return None;
+                }
+
+                // Extension node — follow the child
+                next = ChildRef::decode(&items[1])?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Reference from a trie node to one of its children.
+enum ChildRef {
+    /// keccak256 of the child node, supplied as the next proof node.
+    Hash([u8; 32]),
+    /// Child node RLP embedded directly in the parent (encoding < 32 bytes).
+    Inline(Vec<u8>),
+}
+
+impl ChildRef {
+    /// Classify a decoded child item; empty or malformed references are rejected.
+    fn decode(child: &[u8]) -> Option<Self> {
+        if child.len() == 32 {
+            let mut hash = [0u8; 32];
+            hash.copy_from_slice(child);
+            Some(ChildRef::Hash(hash))
+        } else if !child.is_empty() && child.len() < 32 && child[0] >= 0xc0 {
+            Some(ChildRef::Inline(child.to_vec()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Convert bytes to nibbles (half-bytes).
+pub fn bytes_to_nibbles(data: &[u8]) -> Vec<u8> {
+    let mut nibbles = Vec::with_capacity(data.len() * 2);
+    for byte in data {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+    nibbles
+}
+
+/// Decode hex prefix encoding used in MPT leaf/extension nodes.
+/// Returns (nibbles, is_leaf).
+fn decode_hp_prefix(encoded: &[u8]) -> Option<(Vec<u8>, bool)> {
+    if encoded.is_empty() {
+        return None;
+    }
+    let first_nibble = encoded[0] >> 4;
+    let is_leaf = first_nibble >= 2;
+    let is_odd = first_nibble & 1 == 1;
+
+    let mut nibbles = Vec::new();
+    if is_odd {
+        // Odd: first byte's low nibble is part of the path
+        nibbles.push(encoded[0] & 0x0f);
+    }
+    // Remaining bytes
+    for byte in &encoded[1..] {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+
+    Some((nibbles, is_leaf))
+}
+
+/// Decode a receipt trie key back into a transaction index.
+///
+/// Inverse of `rlp::encode_uint`; rejects non-canonical encodings (leading
+/// zeros, single bytes wrapped in a string prefix, 0x00 for zero) so one
+/// index cannot be addressed by two keys.
+pub fn decode_tx_index_key(key: &[u8]) -> Option<u64> {
+    match key {
+        [0x80] => Some(0),
+        [b @ 0x01..=0x7f] => Some(*b as u64),
+        [prefix, rest @ ..] if (0x81..=0x88).contains(prefix) => {
+            if rest.len() != (*prefix - 0x80) as usize || rest[0] == 0 {
+                return None;
+            }
+            if rest.len() == 1 && rest[0] <= 0x7f {
+                return None;
+            }
+            Some(rest.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+        }
+        _ => None,
+    }
+}
+
+/// Check that a trie leaf decodes as a receipt: an optional EIP-2718 type
+/// byte followed by the 4-item list [status, cumulativeGasUsed, logsBloom, logs].
+pub fn is_well_formed_receipt(leaf: &[u8]) -> bool {
+    let body = match leaf.first() {
+        Some(&t) if t <= 0x7f => &leaf[1..],
+        Some(_) => leaf,
+        None => return false,
+    };
+    if !matches!(body.first(), Some(&b) if b >= 0xc0) {
+        return false;
+    }
+    match rlp::decode_list(body) {
+        Some(items) => items.len() == 4 && items[2].len() == 256,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use rlp::{encode_bytes, encode_list, encode_uint};
+
+    /// Legacy receipt with a distinguishing cumulativeGasUsed.
+    fn test_receipt(gas: u64) -> Vec<u8> {
+        encode_list(&[encode_uint(1), encode_uint(gas), encode_bytes(&[0u8; 256]), encode_list(&[])])
+    }
+
+    #[test]
+    fn test_decode_tx_index_key() {
+        assert_eq!(decode_tx_index_key(&[0x80]), Some(0));
+        assert_eq!(decode_tx_index_key(&[0x01]), Some(1));
+        assert_eq!(decode_tx_index_key(&[0x7f]), Some(127));
+        assert_eq!(decode_tx_index_key(&[0x81, 0x80]), Some(128));
+        assert_eq!(decode_tx_index_key(&[0x82, 0x01, 0x00]), Some(256));
+    }
+
+    #[test]
+    fn test_decode_tx_index_key_roundtrip() {
+        for i in [0u64, 1, 0x7f, 0x80, 0xff, 0x100, 0xdead_beef, u64::MAX] {
+            assert_eq!(decode_tx_index_key(&encode_uint(i)), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_decode_tx_index_key_non_canonical() {
+        assert_eq!(decode_tx_index_key(&[]), None);
+        assert_eq!(decode_tx_index_key(&[0x00]), None);
+        assert_eq!(decode_tx_index_key(&[0x81, 0x05]), None);
+        assert_eq!(decode_tx_index_key(&[0x82, 0x00, 0x80]), None);
+        assert_eq!(decode_tx_index_key(&[0x82, 0x01]), None);
+    }
+
+    #[test]
+    fn test_is_well_formed_receipt() {
+        assert!(is_well_formed_receipt(&test_receipt(0x10)));
+        // EIP-1559 typed receipt
+        assert!(is_well_formed_receipt(&[&[0x02][..], &test_receipt(0x10)].concat()));
+        assert!(!is_well_formed_receipt(&[]));
+        assert!(!is_well_formed_receipt(b"test_receipt"));
+        assert!(!is_well_formed_receipt(&encode_list(&[encode_bytes(&[0x01])])));
+    }
+
+    #[test]
+    fn test_bytes_to_nibbles() {
+        assert_eq!(bytes_to_nibbles(&[0xab, 0xcd]), vec![0xa, 0xb, 0xc, 0xd]);
+        assert_eq!(bytes_to_nibbles(&[0x01]), vec![0x0, 0x1]);
+    }
+
+    #[test]
+    fn test_decode_hp_prefix_leaf_even() {
+        // 0x20 prefix = leaf, even length
+        let (nibbles, is_leaf) = decode_hp_prefix(&[0x20, 0xab]).unwrap();
+        assert!(is_leaf);
+        assert_eq!(nibbles, vec![0xa, 0xb]);
+    }
+
+    #[test]
+    fn test_decode_hp_prefix_leaf_odd() {
+        // 0x3a prefix = leaf, odd length, first nibble = a
+        let (nibbles, is_leaf) = decode_hp_prefix(&[0x3a, 0xbc]).unwrap();
+        assert!(is_leaf);
+        assert_eq!(nibbles, vec![0xa, 0xb, 0xc]);
+    }
+
+    #[test]
+    fn test_decode_hp_prefix_extension_even() {
+        // 0x00 prefix = extension, even length
+        let (nibbles, is_leaf) = decode_hp_prefix(&[0x00, 0xab]).unwrap();
+        assert!(!is_leaf);
+        assert_eq!(nibbles, vec![0xa, 0xb]);
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_verify_proof_inline_children() {
+        use crate::keccak::keccak256;
+
+        // Extension root whose branch child and both leaves are embedded inline;
+        // keys 0xab10 / 0xab20 share the extension path [a, b]
+        let leaf_1 = encode_list(&[encode_bytes(&[0x30]), encode_bytes(&[0x01, 0x11])]);
+        let leaf_2 = encode_list(&[encode_bytes(&[0x30]), encode_bytes(&[0x02, 0x22])]);
+        let mut children = vec![encode_bytes(&[]); 17];
+        children[1] = leaf_1;
+        children[2] = leaf_2;
+        let branch = encode_list(&children);
+        assert!(branch.len() < 32);
+        let extension = encode_list(&[encode_bytes(&[0x00, 0xab]), branch]);
+        let root = keccak256(&extension);
+        let nodes = vec![extension];
+
+        assert_eq!(verify_proof(&root, &[0xab, 0x10], &nodes, keccak256), Some(vec![0x01, 0x11]));
+        assert_eq!(verify_proof(&root, &[0xab, 0x20], &nodes, keccak256), Some(vec![0x02, 0x22]));
+        assert_eq!(verify_proof(&root, &[0xab, 0x30], &nodes, keccak256), None);
+
+        // The root node is hash-checked even though it is shorter than 32 bytes
+        let mut bad_root = root;
+        bad_root[0] ^= 1;
+        assert_eq!(verify_proof(&bad_root, &[0xab, 0x10], &nodes, keccak256), None);
+        assert_eq!(verify_proof(&root, &[0xab, 0x10], &[], keccak256), None);
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_verify_proof_hashed_receipt_leaf() {
+        use crate::keccak::keccak256;
+
+        // Two-receipt trie: key 0x80 → nibbles [8, 0]; key 0x01 → nibbles [0, 1]
+        let leaf_0 = encode_list(&[encode_bytes(&[0x30]), encode_bytes(&test_receipt(0x10))]);
+        let leaf_1 = encode_list(&[encode_bytes(&[0x31]), encode_bytes(&test_receipt(0x20))]);
+        let mut children = vec![encode_bytes(&[]); 17];
+        children[0] = encode_bytes(&keccak256(&leaf_1));
+        children[8] = encode_bytes(&keccak256(&leaf_0));
+        let branch = encode_list(&children);
+        let root = keccak256(&branch);
+
+        let nodes = vec![branch, leaf_1];
+        let leaf = verify_proof(&root, &encode_uint(1), &nodes, keccak256).unwrap();
+        assert_eq!(leaf, test_receipt(0x20));
+        assert!(is_well_formed_receipt(&leaf));
+
+        // Proof nodes are for tx 1, but the key points at tx 0
+        assert_eq!(verify_proof(&root, &encode_uint(0), &nodes, keccak256), None);
+        // Hashed children need their proof node
+        assert_eq!(verify_proof(&root, &encode_uint(1), &nodes[..1], keccak256), None);
+    }
+}
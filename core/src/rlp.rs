@@ -0,0 +1,239 @@
+//! RLP Encoding and Decoding
+//!
+//! The subset of Recursive Length Prefix the receipt proofs need: byte
+//! strings, lists and minimal big-endian integers. The prover encodes
+//! receipts and trie keys with it; both sides decode trie nodes with it.
+//!
+//! Decoding a list returns each item's payload for strings and the whole
+//! encoding for nested lists, so an embedded trie node can be decoded again.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// RLP-encode an integer as its minimal big-endian byte string (zero is empty).
+pub fn encode_uint(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    encode_bytes(&bytes[start..])
+}
+
+/// RLP-encode a byte string.
+pub fn encode_bytes(data: &[u8]) -> Vec<u8> {
+    if data.len() == 1 && data[0] <= 0x7f {
+        return data.to_vec();
+    }
+    let mut encoded = encode_length(data.len(), 0x80);
+    encoded.extend_from_slice(data);
+    encoded
+}
+
+/// RLP-encode a list from its already-encoded items.
+pub fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    let mut encoded = encode_length(payload.len(), 0xc0);
+    encoded.extend_from_slice(&payload);
+    encoded
+}
+
+/// Length prefix for a string (`offset` 0x80) or list (`offset` 0xc0) payload.
+fn encode_length(len: usize, offset: u8) -> Vec<u8> {
+    if len <= 55 {
+        return vec![offset + len as u8];
+    }
+    let len_bytes = (len as u64).to_be_bytes();
+    let start = len_bytes.iter().position(|&b| b != 0).unwrap_or(len_bytes.len());
+    let mut encoded = vec![offset + 55 + (len_bytes.len() - start) as u8];
+    encoded.extend_from_slice(&len_bytes[start..]);
+    encoded
+}
+
+/// Decode an RLP list into its items (raw bytes).
+/// Returns None if the data is not a valid RLP list.
+pub fn decode_list(data: &[u8]) -> Option<Vec<Vec<u8>>> {
+    if data.is_empty() {
+        return None;
+    }
+
+    let (payload, _) = decode_length(data)?;
+    let mut items = Vec::new();
+    let mut offset = 0;
+
+    while offset < payload.len() {
+        let (item, consumed) = decode_item(&payload[offset..])?;
+        items.push(item);
+        offset += consumed;
+    }
+
+    Some(items)
+}
+
+/// Decode the length prefix of an RLP item.
+/// Returns (payload_slice, total_consumed).
+fn decode_length(data: &[u8]) -> Option<(&[u8], usize)> {
+    if data.is_empty() {
+        return None;
+    }
+
+    let prefix = data[0];
+
+    if prefix <= 0x7f {
+        // Single byte
+        Some((&data[0..1], 1))
+    } else if prefix <= 0xb7 {
+        // Short string (0-55 bytes)
+        let len = (prefix - 0x80) as usize;
+        if data.len() < 1 + len {
+            return None;
+        }
+        Some((&data[1..1 + len], 1 + len))
+    } else if prefix <= 0xbf {
+        // Long string
+        let len_of_len = (prefix - 0xb7) as usize;
+        if data.len() < 1 + len_of_len {
+            return None;
+        }
+        let mut len = 0usize;
+        for i in 0..len_of_len {
+            len = (len << 8) | (data[1 + i] as usize);
+        }
+        if data.len() < 1 + len_of_len + len {
+            return None;
+        }
+        Some((&data[1 + len_of_len..1 + len_of_len + len], 1 + len_of_len + len))
+    } else if prefix <= 0xf7 {
+        // Short list (0-55 bytes payload)
+        let len = (prefix - 0xc0) as usize;
+        if data.len() < 1 + len {
+            return None;
+        }
+        Some((&data[1..1 + len], 1 + len))
+    } else {
+        // Long list
+        let len_of_len = (prefix - 0xf7) as usize;
+        if data.len() < 1 + len_of_len {
+            return None;
+        }
+        let mut len = 0usize;
+        for i in 0..len_of_len {
+            len = (len << 8) | (data[1 + i] as usize);
+        }
+        if data.len() < 1 + len_of_len + len {
+            return None;
+        }
+        Some((&data[1 + len_of_len..1 + len_of_len + len], 1 + len_of_len + len))
+    }
+}
+
+/// Decode a single RLP item from data, returning (decoded_bytes, bytes_consumed).
+fn decode_item(data: &[u8]) -> Option<(Vec<u8>, usize)> {
+    if data.is_empty() {
+        return None;
+    }
+
+    let prefix = data[0];
+
+    if prefix <= 0x7f {
+        // Single byte
+        Some((vec![prefix], 1))
+    } else if prefix <= 0xb7 {
+        // Short string (0-55 bytes)
+        let len = (prefix - 0x80) as usize;
+        if data.len() < 1 + len {
+            return None;
+        }
+        Some((data[1..1 + len].to_vec(), 1 + len))
+    } else if prefix <= 0xbf {
+        // Long string
+        let len_of_len = (prefix - 0xb7) as usize;
+        if data.len() < 1 + len_of_len {
+            return None;
+        }
+        let mut len = 0usize;
+        for i in 0..len_of_len {
+            len = (len << 8) | (data[1 + i] as usize);
+        }
+        if data.len() < 1 + len_of_len + len {
+            return None;
+        }
+        Some((data[1 + len_of_len..1 + len_of_len + len].to_vec(), 1 + len_of_len + len))
+    } else if prefix <= 0xf7 {
+        // Short list — return the whole encoded list as raw bytes
+        let len = (prefix - 0xc0) as usize;
+        if data.len() < 1 + len {
+            return None;
+        }
+        Some((data[..1 + len].to_vec(), 1 + len))
+    } else {
+        // Long list — return the whole encoded list as raw bytes
+        let len_of_len = (prefix - 0xf7) as usize;
+        if data.len() < 1 + len_of_len {
+            return None;
+        }
+        let mut len = 0usize;
+        for i in 0..len_of_len {
+            len = (len << 8) | (data[1 + i] as usize);
+        }
+        if data.len() < 1 + len_of_len + len {
+            return None;
+        }
+        Some((data[..1 + len_of_len + len].to_vec(), 1 + len_of_len + len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_uint() {
+        assert_eq!(encode_uint(0), vec![0x80]);
+        assert_eq!(encode_uint(1), vec![0x01]);
+        assert_eq!(encode_uint(127), vec![0x7f]);
+        assert_eq!(encode_uint(128), vec![0x81, 0x80]);
+        assert_eq!(encode_uint(0x5208), vec![0x82, 0x52, 0x08]);
+    }
+
+    #[test]
+    fn test_encode_long_prefixes() {
+        let long = encode_bytes(&[0u8; 256]);
+        assert_eq!(long[..3], [0xb9, 0x01, 0x00]);
+        assert_eq!(long.len(), 259);
+
+        let list = encode_list(&[long]);
+        assert_eq!(list[..3], [0xf9, 0x01, 0x03]);
+        assert_eq!(decode_list(&list), Some(vec![vec![0u8; 256]]));
+    }
+
+    #[test]
+    fn test_decode_list_simple() {
+        // RLP: [0x01, 0x02] → list prefix 0xc2, items 0x01, 0x02
+        let data = vec![0xc2, 0x01, 0x02];
+        let items = decode_list(&data).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0], vec![0x01]);
+        assert_eq!(items[1], vec![0x02]);
+    }
+
+    #[test]
+    fn test_decode_list_empty_string() {
+        // RLP: [""] → list prefix 0xc1, empty string 0x80
+        let data = vec![0xc1, 0x80];
+        let items = decode_list(&data).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0], Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_decode_list_keeps_nested_encoding() {
+        let inner = encode_list(&[encode_bytes(&[0xaa, 0xbb])]);
+        let outer = encode_list(&[encode_uint(5), inner.clone()]);
+        assert_eq!(decode_list(&outer), Some(vec![vec![0x05], inner]));
+    }
+
+    #[test]
+    fn test_decode_list_truncated() {
+        assert_eq!(decode_list(&[]), None);
+        assert_eq!(decode_list(&[0xc3, 0x01, 0x02]), None);
+        assert_eq!(decode_list(&[0xc2, 0x82, 0x01]), None);
+    }
+}
@@ -0,0 +1,89 @@
+//! AIR Registration (prover side)
+//!
+//! `define_prover_air!` is the prover's counterpart of the verifier's
+//! `define_stark_air!`: given the AIR's `Air` variant, trace type and
+//! composition segment minimum, it generates the proof constructor
+//!
+//! - the trace step (`generate` over the bound dataset commitment, with the
+//!   segment minimum applied to the options),
+//! - the `ProverState` constructor from that trace,
+//! - the public `ProverState` constructor and `prove_*_with_options`.
+//!
+//! The trace type supplies `generate(trades, Option<U256>)`,
+//! `public_inputs_with_scheme(claim, scheme)`, `log_len()`, `len`,
+//! `actual_trade_count` and `into_columns()`, as `SharpeTrace` does. The
+//! `Air` variant carries the proof kind, alpha count and composition.
+
+/// Generate the proof constructor of one AIR; see the module docs. Each
+/// public item takes the doc comments written before its name.
+macro_rules! define_prover_air {
+    (
+        air: $air:expr,
+        trace: $trace:ty,
+        min_segments: $min_segments:expr,
+        trace_with_options: $trace_with_options:ident,
+        from_trace: $from_trace:ident,
+        $(#[$new_meta:meta])* new: $new:ident,
+        $(#[$prove_meta:meta])* prove_with_options: $prove_with_options:ident $(,)?
+    ) => {
+        impl $crate::ProverState {
+            $(#[$new_meta])*
+            pub fn $new(
+                trades: &[$crate::mock_data::GmxTradeRecord],
+                claim: alloy_primitives::U256,
+                num_queries: usize,
+                dataset_commitment: Option<alloy_primitives::U256>,
+                options: &$crate::ProveOptions,
+            ) -> Self {
+                let (trace, options) = Self::$trace_with_options(trades, dataset_commitment, options);
+                Self::$from_trace(trace, claim, num_queries, &options)
+            }
+
+            /// Trace for the constructor, with the segment minimum applied.
+            fn $trace_with_options(
+                trades: &[$crate::mock_data::GmxTradeRecord],
+                dataset_commitment: Option<alloy_primitives::U256>,
+                options: &$crate::ProveOptions,
+            ) -> ($trace, $crate::ProveOptions) {
+                let mut options = options.clone();
+                options.composition_segments = options.composition_segments.max($min_segments);
+                let trace = <$trace>::generate(trades, $crate::bound_dataset_commitment(dataset_commitment, &options));
+                (trace, options)
+            }
+
+            fn $from_trace(
+                trace: $trace,
+                claim: alloy_primitives::U256,
+                num_queries: usize,
+                options: &$crate::ProveOptions,
+            ) -> Self {
+                let traced = $crate::TracedColumns {
+                    air: $air,
+                    air_inputs: trace.public_inputs_with_scheme(claim, options.merkle_scheme),
+                    log_trace_len: trace.log_len(),
+                    trace_len: trace.len,
+                    padding_warning: options
+                        .warn_padding_ratio
+                        .and_then(|r| $crate::padding::padding_warning(trace.actual_trade_count, r)),
+                    columns: trace.into_columns(),
+                };
+                $crate::ProverState::traced(traced, num_queries, options)
+            }
+        }
+
+        $(#[$prove_meta])*
+        pub fn $prove_with_options(
+            trades: &[$crate::mock_data::GmxTradeRecord],
+            claim: alloy_primitives::U256,
+            num_queries: usize,
+            dataset_commitment: Option<alloy_primitives::U256>,
+            options: &$crate::ProveOptions,
+            on_progress: impl Fn($crate::ProveProgress),
+        ) -> $crate::proof::SerializedProof {
+            let state = $crate::ProverState::$new(trades, claim, num_queries, dataset_commitment, options);
+            $crate::run_to_completion(state, &on_progress)
+        }
+    };
+}
+
+pub(crate) use define_prover_air;
@@ -1,32 +1,93 @@
 //! Fiat-Shamir Channel (prover side)
 //!
 //! Must produce identical output to the on-chain verifier's channel
-//! given the same inputs. Both use Keccak256 hash.
+//! given the same inputs and the same `TranscriptHash` (Keccak256 by
+//! default, or Poseidon).
+//!
+//! Absorbed values are hashed under `TAG_CHANNEL_COMMIT` and challenges
+//! drawn under `TAG_CHANNEL_DRAW`, so no transcript step shares a Keccak
+//! preimage with a Merkle node (`TAG_MERKLE_NODE`).
+
+use std::marker::PhantomData;
 
 use alloy_primitives::U256;
-use crate::keccak::keccak_hash_two;
+use crate::keccak::{keccak_hash_two, keccak_hash_two_tagged, TAG_CHANNEL_COMMIT, TAG_CHANNEL_DRAW};
+use crate::poseidon::poseidon_hash_two;
+
+pub use stark_core::channel::{ProofKind, TranscriptKind};
+use stark_core::channel::{public_input_seed, query_index};
+
+/// Two-to-one hash driving the Fiat-Shamir transcript.
+pub trait TranscriptHash {
+    fn hash_two(a: U256, b: U256) -> U256;
+
+    /// `hash_two` under a domain tag. Defaults to ignoring the tag.
+    fn hash_two_tagged(_tag: u8, a: U256, b: U256) -> U256 {
+        Self::hash_two(a, b)
+    }
+}
+
+/// Keccak256 transcript (default).
+pub struct KeccakTranscript;
+
+impl TranscriptHash for KeccakTranscript {
+    fn hash_two(a: U256, b: U256) -> U256 {
+        keccak_hash_two(a, b)
+    }
+
+    fn hash_two_tagged(tag: u8, a: U256, b: U256) -> U256 {
+        keccak_hash_two_tagged(tag, a, b)
+    }
+}
+
+/// Poseidon transcript. Untagged: Poseidon proofs still commit their trees
+/// with Keccak, so the transcript never shares a preimage with a node.
+pub struct PoseidonTranscript;
+
+impl TranscriptHash for PoseidonTranscript {
+    fn hash_two(a: U256, b: U256) -> U256 {
+        poseidon_hash_two(a, b)
+    }
+}
 
 /// Fiat-Shamir channel for deterministic challenge generation.
-pub struct Channel {
+pub struct Channel<H: TranscriptHash = KeccakTranscript> {
     state: U256,
     counter: u64,
+    _hash: PhantomData<H>,
 }
 
 impl Channel {
     pub fn new(seed: U256) -> Self {
+        Channel::with_transcript(seed)
+    }
+}
+
+impl<H: TranscriptHash> Channel<H> {
+    pub fn with_transcript(seed: U256) -> Self {
         Channel {
             state: seed,
             counter: 0,
+            _hash: PhantomData,
         }
     }
 
+    /// Channel seeded with a `kind` proof's public inputs, framed by kind and
+    /// count (`stark_core::channel::public_input_seed`).
+    pub fn with_public_inputs(kind: ProofKind, public_inputs: &[U256]) -> Self {
+        let frame = kind.seed_frame(public_inputs.len());
+        Self::with_transcript(public_input_seed(frame, public_inputs.iter().copied(), |h, pi| {
+            H::hash_two_tagged(TAG_CHANNEL_COMMIT, h, pi)
+        }))
+    }
+
     pub fn commit(&mut self, value: U256) {
-        self.state = keccak_hash_two(self.state, value);
+        self.state = H::hash_two_tagged(TAG_CHANNEL_COMMIT, self.state, value);
         self.counter = 0;
     }
 
     pub fn draw_felt(&mut self) -> U256 {
-        let challenge = keccak_hash_two(self.state, U256::from(self.counter));
+        let challenge = H::hash_two_tagged(TAG_CHANNEL_DRAW, self.state, U256::from(self.counter));
         self.counter += 1;
         challenge
     }
@@ -35,9 +96,7 @@ impl Channel {
         let mut indices = Vec::with_capacity(count);
 
         while indices.len() < count {
-            let raw = self.draw_felt();
-            let mask = U256::from((domain_size - 1) as u64);
-            let index = (raw & mask).as_limbs()[0] as usize;
+            let index = query_index(self.draw_felt(), domain_size);
 
             if !indices.contains(&index) {
                 indices.push(index);
@@ -50,6 +109,20 @@ impl Channel {
     pub fn state(&self) -> U256 {
         self.state
     }
+
+    /// Transcript position `(state, counter)`, for resuming with `resume`.
+    pub fn snapshot(&self) -> (U256, u64) {
+        (self.state, self.counter)
+    }
+
+    /// Continue a transcript from a `snapshot`.
+    pub fn resume((state, counter): (U256, u64)) -> Self {
+        Channel {
+            state,
+            counter,
+            _hash: PhantomData,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -70,4 +143,16 @@ mod tests {
 
         assert_eq!(v1, v2);
     }
+
+    #[test]
+    fn test_poseidon_channel_diverges_from_keccak() {
+        let seed = U256::from(42u64);
+
+        let mut keccak = Channel::new(seed);
+        let mut poseidon = Channel::<PoseidonTranscript>::with_transcript(seed);
+        keccak.commit(U256::from(100u64));
+        poseidon.commit(U256::from(100u64));
+
+        assert_ne!(keccak.draw_felt(), poseidon.draw_felt());
+    }
 }
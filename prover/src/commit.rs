@@ -4,9 +4,59 @@
 //! for the STARK prover.
 
 use alloy_primitives::U256;
-use crate::keccak::keccak_hash_two;
+use crate::keccak::{keccak_hash_one, keccak_hash_two, keccak_hash_two_tagged, TAG_MERKLE_NODE};
+use stark_core::keccak::fold_row;
+
+/// Pairing rule for the dataset-commitment column tree behind `pi[3]`; must
+/// match the verifier's `merkle::MerkleScheme`.
+///
+/// STARK trees always use `Ordered`. `Sorted` follows OpenZeppelin's
+/// `MerkleProof` convention: leaves are hashed (`keccak(value, 0)`) and each
+/// parent hashes its two children in ascending order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MerkleScheme {
+    #[default]
+    Ordered,
+    Sorted,
+}
+
+impl MerkleScheme {
+    /// Tag carried in bits 192..256 of `query_metadata[0]`.
+    pub fn tag(self) -> u64 {
+        match self {
+            MerkleScheme::Ordered => 0,
+            MerkleScheme::Sorted => 1,
+        }
+    }
+
+    /// Inverse of `tag`; unknown tags are `None`.
+    pub fn from_tag(tag: u64) -> Option<Self> {
+        match tag {
+            0 => Some(MerkleScheme::Ordered),
+            1 => Some(MerkleScheme::Sorted),
+            _ => None,
+        }
+    }
+
+    /// Parent of two child nodes: `hash_node` for `Ordered`; `Sorted` keeps
+    /// the untagged pair hash of the OpenZeppelin convention.
+    pub fn hash_pair(self, left: U256, right: U256) -> U256 {
+        match self {
+            MerkleScheme::Ordered => hash_node(left, right),
+            MerkleScheme::Sorted if left > right => keccak_hash_two(right, left),
+            MerkleScheme::Sorted => keccak_hash_two(left, right),
+        }
+    }
+}
+
+/// Parent of two nodes in a STARK commitment tree: the pair hash under
+/// `TAG_MERKLE_NODE`. Must match the verifier's `merkle::hash_nodes`.
+pub fn hash_node(left: U256, right: U256) -> U256 {
+    keccak_hash_two_tagged(TAG_MERKLE_NODE, left, right)
+}
 
 /// A Keccak256 Merkle tree for committing to polynomial evaluations.
+#[derive(Clone)]
 pub struct MerkleTree {
     /// All tree nodes, stored level by level from leaves to root.
     /// nodes[0..n] = leaves, nodes[n..n+n/2] = level 1, etc.
@@ -20,12 +70,69 @@ pub struct MerkleTree {
 impl MerkleTree {
     /// Build a Merkle tree from leaf values.
     ///
+    /// With the `parallel` feature, each level is hashed across threads;
+    /// the resulting tree is identical to the serial build.
+    ///
     /// # Arguments
     /// * `leaves` - Leaf values (length must be power of 2)
     pub fn build(leaves: &[U256]) -> Self {
+        #[cfg(feature = "parallel")]
+        {
+            Self::build_parallel(leaves)
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            Self::build_serial(leaves)
+        }
+    }
+
+    /// Build a Merkle tree under the given pairing scheme.
+    ///
+    /// `Ordered` is `build`; `Sorted` hashes each leaf first and sorts every
+    /// pair before hashing (the stored leaves are the hashed ones).
+    pub fn build_with_scheme(leaves: &[U256], scheme: MerkleScheme) -> Self {
+        match scheme {
+            MerkleScheme::Ordered => Self::build(leaves),
+            MerkleScheme::Sorted => {
+                let hashed: Vec<U256> = leaves.iter().map(|&v| keccak_hash_one(v)).collect();
+                Self::build_with(&hashed, |level| {
+                    level
+                        .chunks(2)
+                        .map(|pair| scheme.hash_pair(pair[0], pair[1]))
+                        .collect()
+                })
+            }
+        }
+    }
+
+    /// Build a Merkle tree on the current thread.
+    pub fn build_serial(leaves: &[U256]) -> Self {
+        Self::build_with(leaves, |level| {
+            level
+                .chunks(2)
+                .map(|pair| hash_node(pair[0], pair[1]))
+                .collect()
+        })
+    }
+
+    /// Build a Merkle tree, hashing the node pairs of each level in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn build_parallel(leaves: &[U256]) -> Self {
+        use rayon::prelude::*;
+
+        Self::build_with(leaves, |level| {
+            level
+                .par_chunks(2)
+                .map(|pair| hash_node(pair[0], pair[1]))
+                .collect()
+        })
+    }
+
+    /// Shared level-by-level construction; `hash_level` maps one level to the next.
+    fn build_with(leaves: &[U256], hash_level: impl Fn(&[U256]) -> Vec<U256>) -> Self {
         let n = leaves.len();
         assert!(n.is_power_of_two(), "Number of leaves must be power of 2");
-        let depth = (n as f64).log2() as usize;
+        let depth = n.trailing_zeros() as usize;
 
         // Total nodes = 2*n - 1 (all levels)
         let mut nodes = Vec::with_capacity(2 * n);
@@ -38,14 +145,10 @@ impl MerkleTree {
         let mut level_size = n;
 
         while level_size > 1 {
-            let next_size = level_size / 2;
-            for i in 0..next_size {
-                let left = nodes[level_start + 2 * i];
-                let right = nodes[level_start + 2 * i + 1];
-                nodes.push(keccak_hash_two(left, right));
-            }
+            let next_level = hash_level(&nodes[level_start..level_start + level_size]);
             level_start += level_size;
-            level_size = next_size;
+            level_size = next_level.len();
+            nodes.extend_from_slice(&next_level);
         }
 
         MerkleTree {
@@ -92,6 +195,16 @@ impl MerkleTree {
         (path, indices)
     }
 
+    /// Open the leaf at `index`: its value and the sibling hashes from leaf
+    /// to root.
+    ///
+    /// The left/right position at each level is implied by the bits of
+    /// `index`, matching the verifier's `MerkleVerifier::verify_inclusion`.
+    pub fn open(&self, index: usize) -> (U256, Vec<U256>) {
+        let (path, _) = self.auth_path(index);
+        (self.leaf(index), path)
+    }
+
     /// Get the leaf value at a given index.
     pub fn leaf(&self, index: usize) -> U256 {
         self.nodes[index]
@@ -108,30 +221,80 @@ impl MerkleTree {
     }
 }
 
-/// Build a Merkle tree from multiple columns of trace evaluations.
-/// Each leaf is the chain-hash of all columns: keccak(keccak(...keccak(c0, c1), c2)..., cN).
-pub fn commit_trace_multi(cols: &[&[U256]]) -> MerkleTree {
+/// Leaf encoding for committed evaluations; must match the verifier's
+/// `merkle::hash_leaf`.
+///
+/// Unsalted leaves are the value itself; salted leaves are `keccak(salt, value)`.
+/// Two proofs over the same data but with different salts commit to
+/// unrelated leaves, so their Merkle subtrees cannot be correlated.
+pub fn hash_leaf(value: U256, salt: Option<U256>) -> U256 {
+    match salt {
+        Some(s) => keccak_hash_two(s, value),
+        None => value,
+    }
+}
+
+/// Combine one trace row into its (unsalted) leaf value:
+/// keccak(keccak(...keccak(c0, c1), c2)..., cN), i.e. `stark_core::keccak::fold_row`.
+///
+/// The verifier's `merkle::combine_row_leaf` is the same fold; trace openings
+/// check `hash_leaf(combine_row_leaf(row), salt)` against the trace root.
+pub fn combine_row_leaf(row: &[U256]) -> U256 {
+    fold_row(row.iter().copied(), keccak_hash_two)
+}
+
+/// Build a Merkle tree from multiple columns of trace evaluations, any number
+/// of columns. Each leaf is the row's `combine_row_leaf`, encoded through
+/// `hash_leaf`.
+///
+/// With the `parallel` feature, leaf hashing is spread over rows.
+pub fn commit_trace_multi(cols: &[&[U256]], salt: Option<U256>) -> MerkleTree {
+    let n = check_columns(cols);
+
+    #[cfg(feature = "parallel")]
+    let leaves: Vec<U256> = {
+        use rayon::prelude::*;
+        (0..n).into_par_iter().map(|i| trace_row_leaf(cols, i, salt)).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let leaves: Vec<U256> = (0..n).map(|i| trace_row_leaf(cols, i, salt)).collect();
+
+    MerkleTree::build(&leaves)
+}
+
+/// Serial variant of `commit_trace_multi` (reference for the parallel build).
+pub fn commit_trace_multi_serial(cols: &[&[U256]], salt: Option<U256>) -> MerkleTree {
+    let n = check_columns(cols);
+    let leaves: Vec<U256> = (0..n).map(|i| trace_row_leaf(cols, i, salt)).collect();
+    MerkleTree::build_serial(&leaves)
+}
+
+/// Assert all columns are non-empty in number and equal in length; returns the row count.
+fn check_columns(cols: &[&[U256]]) -> usize {
     assert!(!cols.is_empty());
     let n = cols[0].len();
     for c in cols {
         assert_eq!(c.len(), n);
     }
+    n
+}
 
-    let leaves: Vec<U256> = (0..n)
-        .map(|i| {
-            let mut h = keccak_hash_two(cols[0][i], cols[1][i]);
-            for col in &cols[2..] {
-                h = keccak_hash_two(h, col[i]);
-            }
-            h
-        })
-        .collect();
-    MerkleTree::build(&leaves)
+/// `combine_row_leaf` of row `i`, read across the columns, then salted.
+fn trace_row_leaf(cols: &[&[U256]], i: usize, salt: Option<U256>) -> U256 {
+    hash_leaf(fold_row(cols.iter().map(|col| col[i]), keccak_hash_two), salt)
 }
 
 /// Build a Merkle tree from a single column of evaluations.
-pub fn commit_column(values: &[U256]) -> MerkleTree {
-    MerkleTree::build(values)
+///
+/// Each leaf is `hash_leaf(value, salt)`.
+pub fn commit_column(values: &[U256], salt: Option<U256>) -> MerkleTree {
+    match salt {
+        Some(_) => {
+            let leaves: Vec<U256> = values.iter().map(|&v| hash_leaf(v, salt)).collect();
+            MerkleTree::build(&leaves)
+        }
+        None => MerkleTree::build(values),
+    }
 }
 
 #[cfg(test)]
@@ -146,7 +309,7 @@ mod tests {
         assert_eq!(tree.num_leaves(), 2);
         assert_eq!(tree.depth(), 1);
 
-        let expected_root = keccak_hash_two(U256::from(1u64), U256::from(2u64));
+        let expected_root = hash_node(U256::from(1u64), U256::from(2u64));
         assert_eq!(tree.root(), expected_root);
     }
 
@@ -166,9 +329,9 @@ mod tests {
         assert_eq!(indices.len(), 2);
 
         // Verify: manually compute
-        let h01 = keccak_hash_two(U256::from(1u64), U256::from(2u64));
-        let h23 = keccak_hash_two(U256::from(3u64), U256::from(4u64));
-        let root = keccak_hash_two(h01, h23);
+        let h01 = hash_node(U256::from(1u64), U256::from(2u64));
+        let h23 = hash_node(U256::from(3u64), U256::from(4u64));
+        let root = hash_node(h01, h23);
 
         assert_eq!(tree.root(), root);
         assert_eq!(path[0], U256::from(2u64)); // sibling of leaf 0 is leaf 1
@@ -192,9 +355,121 @@ mod tests {
         assert_eq!(path[0], U256::from(30u64)); // sibling is leaf 2
         assert!(indices[0]); // leaf 3 is right child
 
-        let h01 = keccak_hash_two(U256::from(10u64), U256::from(20u64));
+        let h01 = hash_node(U256::from(10u64), U256::from(20u64));
         assert_eq!(path[1], h01); // sibling of h23 is h01
         assert!(indices[1]); // h23 is right child
     }
 
+    #[test]
+    fn test_merkle_tree_open() {
+        let leaves: Vec<U256> = (0..8u64).map(|i| U256::from(i * 11)).collect();
+        let tree = MerkleTree::build(&leaves);
+
+        let (leaf, path) = tree.open(5);
+        assert_eq!(leaf, leaves[5]);
+        assert_eq!(path.len(), 3);
+
+        // Index bits pick the side at each level: 5 = 0b101 → right, left, right
+        let mut current = leaf;
+        for (level, sibling) in path.iter().enumerate() {
+            current = if (5 >> level) & 1 == 1 {
+                hash_node(*sibling, current)
+            } else {
+                hash_node(current, *sibling)
+            };
+        }
+        assert_eq!(current, tree.root());
+    }
+
+    #[test]
+    fn test_salted_column_commitment() {
+        let values: Vec<U256> = (0..8u64).map(U256::from).collect();
+        let salt_a = U256::from(0xa11ceu64);
+        let salt_b = U256::from(0xb0bu64);
+
+        let plain = commit_column(&values, None);
+        let salted_a = commit_column(&values, Some(salt_a));
+        let salted_b = commit_column(&values, Some(salt_b));
+
+        assert_eq!(plain.root(), MerkleTree::build(&values).root());
+        assert_ne!(salted_a.root(), plain.root());
+        assert_ne!(salted_a.root(), salted_b.root());
+        assert_eq!(salted_a.leaf(3), hash_leaf(values[3], Some(salt_a)));
+    }
+
+    #[test]
+    fn test_commit_column_opens_through_hash_leaf() {
+        let values: Vec<U256> = (0..8u64).map(|i| U256::from(i * 31 + 5)).collect();
+
+        for salt in [None, Some(U256::from(0xa11ceu64))] {
+            let tree = commit_column(&values, salt);
+            let (path, indices) = tree.auth_path(5);
+
+            // Recompute the root from the raw value as the verifier does
+            let mut current = hash_leaf(values[5], salt);
+            for (sibling, is_right) in path.iter().zip(indices.iter()) {
+                current = if *is_right {
+                    hash_node(*sibling, current)
+                } else {
+                    hash_node(current, *sibling)
+                };
+            }
+            assert_eq!(current, tree.root());
+        }
+    }
+
+    #[test]
+    fn test_trace_opening_recovers_combined_leaf() {
+        // 6 columns; trees need a power-of-two row count, so 4 rows
+        let cols: Vec<Vec<U256>> = (0..6u64)
+            .map(|c| (0..4u64).map(|r| U256::from(10 * c + r)).collect())
+            .collect();
+        let col_refs: Vec<&[U256]> = cols.iter().map(|c| c.as_slice()).collect();
+        let tree = commit_trace_multi(&col_refs, None);
+
+        let row: Vec<U256> = cols.iter().map(|c| c[1]).collect();
+        let (leaf, path) = tree.open(1);
+        assert_eq!(leaf, combine_row_leaf(&row));
+        let node = hash_node(tree.leaf(0), leaf);
+        assert_eq!(hash_node(node, path[1]), tree.root());
+
+        // A one-column trace commits exactly like `commit_column`
+        assert_eq!(commit_trace_multi(&[&cols[0]], None).root(), commit_column(&cols[0], None).root());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_serial_1024x6() {
+        let cols: Vec<Vec<U256>> = (0..6u64)
+            .map(|c| (0..1024u64).map(|i| U256::from(c * 1_000_003 + i * 7919)).collect())
+            .collect();
+        let col_refs: Vec<&[U256]> = cols.iter().map(|c| c.as_slice()).collect();
+
+        let parallel = commit_trace_multi(&col_refs, None);
+        let serial = commit_trace_multi_serial(&col_refs, None);
+
+        assert_eq!(parallel.num_leaves(), 1024);
+        assert_eq!(parallel.depth(), 10);
+        assert_eq!(parallel.root(), serial.root());
+        for idx in [0usize, 1, 511, 512, 1023] {
+            assert_eq!(parallel.leaf(idx), serial.leaf(idx));
+            assert_eq!(parallel.auth_path(idx), serial.auth_path(idx));
+        }
+    }
+
+    #[test]
+    fn test_build_with_sorted_scheme() {
+        let leaves: Vec<U256> = [9u64, 4, 7, 1].iter().map(|&v| U256::from(v)).collect();
+        let h: Vec<U256> = leaves.iter().map(|&v| keccak_hash_one(v)).collect();
+        let sort2 = |a: U256, b: U256| if a <= b { keccak_hash_two(a, b) } else { keccak_hash_two(b, a) };
+        let expected = sort2(sort2(h[0], h[1]), sort2(h[2], h[3]));
+
+        let tree = MerkleTree::build_with_scheme(&leaves, MerkleScheme::Sorted);
+        assert_eq!(tree.root(), expected);
+        assert_eq!(MerkleTree::build_with_scheme(&leaves, MerkleScheme::Ordered).root(), MerkleTree::build(&leaves).root());
+
+        // Sibling order does not matter under sorted pairing
+        let swapped = [leaves[1], leaves[0], leaves[3], leaves[2]];
+        assert_eq!(MerkleTree::build_with_scheme(&swapped, MerkleScheme::Sorted).root(), expected);
+    }
 }
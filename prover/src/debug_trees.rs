@@ -0,0 +1,73 @@
+//! Committed Merkle Trees for Debugging
+//!
+//! When a proof fails on-chain, the first question is usually which
+//! commitment the verifier disagrees with. `prove_sharpe_with_trees` returns
+//! the trees behind a proof's trace and composition commitments alongside it,
+//! so their leaves and authentication paths can be checked against what the
+//! verifier recomputes. Behind the `debug-trees` feature: the normal proving
+//! path never keeps a copy of its trees.
+//!
+//! `prove_sharpe_with_tampered_trace` edits the trace before it is committed,
+//! for verifier tests that need a proof the honest prover never makes.
+
+use alloy_primitives::U256;
+
+use crate::commit::MerkleTree;
+use crate::mock_data::GmxTradeRecord;
+use crate::proof::SerializedProof;
+use crate::{prove_sharpe_step, run_to_completion, ProveOptions, ProverStage, ProverState, StepResult};
+
+/// The Merkle trees a proof committed to, in `commitments` order.
+pub struct DebugTrees {
+    /// Tree over the trace rows: `commitments[0]`.
+    pub trace: MerkleTree,
+    /// One tree per composition segment: `commitments[1..=composition.len()]`.
+    pub composition: Vec<MerkleTree>,
+    /// FRI layer roots: the remaining commitments.
+    pub fri_layer_roots: Vec<U256>,
+}
+
+/// `prove_sharpe_with_options` without progress reporting, also returning the
+/// trees behind the proof's commitments.
+pub fn prove_sharpe_with_trees(
+    trades: &[GmxTradeRecord],
+    claimed_sharpe_sq_scaled: U256,
+    num_queries: usize,
+    dataset_commitment: Option<U256>,
+    options: &ProveOptions,
+) -> (SerializedProof, DebugTrees) {
+    let mut state = ProverState::new(trades, claimed_sharpe_sq_scaled, num_queries, dataset_commitment, options);
+    let mut trees = None;
+    loop {
+        // The last stage consumes the committed trees, so copy them first
+        if let ProverStage::Composed(composed) = &state.stage {
+            trees = Some((composed.committed.trace_tree.clone(), composed.composition_trees.clone()));
+        }
+        state = match prove_sharpe_step(state) {
+            StepResult::InProgress(next) => next,
+            StepResult::Done(proof) => {
+                let (trace, composition) = trees.expect("proof finished without a compose stage");
+                let fri_layer_roots = proof.commitments[1 + composition.len()..].to_vec();
+                return (proof, DebugTrees { trace, composition, fri_layer_roots });
+            }
+        };
+    }
+}
+
+/// `prove_sharpe_with_options` without progress reporting, with `tamper`
+/// applied to the generated trace columns and the four AIR public inputs
+/// before anything is committed.
+pub fn prove_sharpe_with_tampered_trace(
+    trades: &[GmxTradeRecord],
+    claimed_sharpe_sq_scaled: U256,
+    num_queries: usize,
+    dataset_commitment: Option<U256>,
+    options: &ProveOptions,
+    tamper: impl FnOnce(&mut [Vec<U256>], &mut [U256; 4]),
+) -> SerializedProof {
+    let mut state = ProverState::new(trades, claimed_sharpe_sq_scaled, num_queries, dataset_commitment, options);
+    if let ProverStage::Traced(traced) = &mut state.stage {
+        tamper(&mut traced.columns, &mut traced.air_inputs);
+    }
+    run_to_completion(state, &|_| {})
+}
@@ -0,0 +1,148 @@
+//! DEEP Composition
+//!
+//! Combines the trace columns and the composition segments into a single
+//! DEEP polynomial. FRI proves it low-degree, which ties the committed trace
+//! and composition to their claimed out-of-domain evaluations:
+//!
+//!   D(x) = Σ_j γ_j        (t_j(x) - t_j(z))  / (x - z)
+//!        + Σ_j γ_{W+j}    (t_j(x) - t_j(zg)) / (x - zg)
+//!        + Σ_i γ_{2W+i}   (S_i(x) - S_i(z))  / (x - z)
+//!
+//! W trace columns at z + W at zg + one per composition segment S_i
+//! (16 coefficients for the 7-column Sharpe trace with a two-segment
+//! composition).
+
+use alloy_primitives::U256;
+use crate::field::BN254Field;
+
+/// Number of DEEP combination coefficients for a `num_columns`-wide trace
+/// (each column at z and zg) and `num_segments` composition segments.
+pub fn num_deep_coeffs(num_columns: usize, num_segments: usize) -> usize {
+    2 * num_columns + num_segments
+}
+
+/// Out-of-domain point and the evaluations claimed there.
+pub struct DeepPoint {
+    pub z: U256,
+    pub zg: U256,
+    pub trace_at_z: Vec<U256>,
+    pub trace_at_zg: Vec<U256>,
+    /// S_i(z) for each composition segment.
+    pub composition_at_z: Vec<U256>,
+}
+
+impl DeepPoint {
+    /// Evaluate D(x) from the trace row and composition segment values at x.
+    pub fn evaluate(
+        &self,
+        x: U256,
+        trace_at_x: &[U256],
+        composition_at_x: &[U256],
+        gammas: &[U256],
+    ) -> U256 {
+        let inv_x_z = BN254Field::inv(BN254Field::sub(x, self.z));
+        let inv_x_zg = BN254Field::inv(BN254Field::sub(x, self.zg));
+        self.combine(trace_at_x, composition_at_x, inv_x_z, inv_x_zg, gammas)
+    }
+
+    /// D(x) given precomputed 1/(x - z) and 1/(x - zg).
+    fn combine(
+        &self,
+        trace_at_x: &[U256],
+        composition_at_x: &[U256],
+        inv_x_z: U256,
+        inv_x_zg: U256,
+        gammas: &[U256],
+    ) -> U256 {
+        let width = self.trace_at_z.len();
+        let mut at_z = U256::ZERO;
+        for (i, (&s_x, &s_z)) in composition_at_x.iter().zip(&self.composition_at_z).enumerate() {
+            let ds = BN254Field::sub(s_x, s_z);
+            at_z = BN254Field::add(at_z, BN254Field::mul(gammas[2 * width + i], ds));
+        }
+        let mut at_zg = U256::ZERO;
+        for j in 0..width {
+            let dz = BN254Field::sub(trace_at_x[j], self.trace_at_z[j]);
+            let dzg = BN254Field::sub(trace_at_x[j], self.trace_at_zg[j]);
+            at_z = BN254Field::add(at_z, BN254Field::mul(gammas[j], dz));
+            at_zg = BN254Field::add(at_zg, BN254Field::mul(gammas[width + j], dzg));
+        }
+        BN254Field::add(
+            BN254Field::mul(at_z, inv_x_z),
+            BN254Field::mul(at_zg, inv_x_zg),
+        )
+    }
+}
+
+/// Evaluate the DEEP polynomial on the LDE domain.
+///
+/// Batch-inverts all (x - z) and (x - zg) denominators up front.
+pub fn evaluate_deep_on_lde(
+    trace_lde: &[&[U256]],
+    composition_ldes: &[Vec<U256>],
+    lde_domain: &[U256],
+    point: &DeepPoint,
+    gammas: &[U256],
+) -> Vec<U256> {
+    let lde_size = lde_domain.len();
+
+    // Per point i: [x - z, x - zg]
+    let mut denoms = vec![U256::ZERO; lde_size * 2];
+    for (i, &x) in lde_domain.iter().enumerate() {
+        denoms[2 * i] = BN254Field::sub(x, point.z);
+        denoms[2 * i + 1] = BN254Field::sub(x, point.zg);
+    }
+    BN254Field::batch_invert(&mut denoms);
+
+    let mut row = vec![U256::ZERO; trace_lde.len()];
+    let mut segments_at_x = vec![U256::ZERO; composition_ldes.len()];
+    (0..lde_size)
+        .map(|i| {
+            for (v, col) in row.iter_mut().zip(trace_lde) {
+                *v = col[i];
+            }
+            for (s, segment) in segments_at_x.iter_mut().zip(composition_ldes) {
+                *s = segment[i];
+            }
+            point.combine(&row, &segments_at_x, denoms[2 * i], denoms[2 * i + 1], gammas)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::get_domain;
+
+    #[test]
+    fn test_deep_lde_matches_pointwise() {
+        let log_size = 4;
+        let domain = get_domain(log_size);
+        let cols: Vec<Vec<U256>> = (0..6u64)
+            .map(|c| (0..16u64).map(|i| U256::from(c * 101 + i * i)).collect())
+            .collect();
+        let segments: Vec<Vec<U256>> = (0..2u64)
+            .map(|s| (0..16u64).map(|i| U256::from(7 * i + 3 + s)).collect())
+            .collect();
+        let col_refs: Vec<&[U256]> = cols.iter().map(|c| c.as_slice()).collect();
+
+        let point = DeepPoint {
+            z: U256::from(0xdeadu64),
+            zg: U256::from(0xbeefu64),
+            trace_at_z: vec![U256::from(1u64); 6],
+            trace_at_zg: vec![U256::from(2u64); 6],
+            composition_at_z: vec![U256::from(3u64), U256::from(4u64)],
+        };
+        let mut gammas = vec![U256::ZERO; num_deep_coeffs(6, 2)];
+        for (k, g) in gammas.iter_mut().enumerate() {
+            *g = U256::from(k as u64 + 11);
+        }
+
+        let deep = evaluate_deep_on_lde(&col_refs, &segments, &domain, &point, &gammas);
+        for i in [0usize, 5, 15] {
+            let row = [cols[0][i], cols[1][i], cols[2][i], cols[3][i], cols[4][i], cols[5][i]];
+            let at_x = [segments[0][i], segments[1][i]];
+            assert_eq!(deep[i], point.evaluate(domain[i], &row, &at_x, &gammas));
+        }
+    }
+}
@@ -1,27 +1,53 @@
 //! Evaluation Domain (prover side)
 //!
-//! Same roots of unity as the on-chain verifier.
+//! Same roots of unity as the on-chain verifier: the 2^28 generator,
+//! two-adicity and supported blowups come from `stark_core::domain`.
+
+use std::sync::OnceLock;
 
 use alloy_primitives::U256;
 use crate::field::BN254Field;
 
-/// Generator of the 2^28 multiplicative subgroup of BN254 scalar field.
-/// g = 5^((p-1)/2^28) mod p
-pub const GENERATOR_2_28: U256 = U256::from_limbs([
-    0x9bd61b6e725b19f0,
-    0x402d111e41112ed4,
-    0x00e0a7eb8ef62abc,
-    0x2a3c09f0a58a7e85,
-]);
+pub use stark_core::domain::{log_blowup, TWO_ADICITY};
+
+/// Generator of the 2^28 multiplicative subgroup of BN254 scalar field,
+/// in canonical form. g = 5^((p-1)/2^28) mod p
+pub const GENERATOR_2_28: U256 = stark_core::domain::GENERATOR_2_28.to_u256();
+
+/// One entry per subgroup size 2^0..=2^28, indexed by log size.
+type GeneratorTable = [U256; TWO_ADICITY as usize + 1];
 
-pub const TWO_ADICITY: u32 = 28;
+/// `table[k] = top^(2^(28-k))`, filled by repeated squaring from `table[28] = top`.
+fn squaring_chain(top: U256) -> GeneratorTable {
+    let mut table = [U256::ZERO; TWO_ADICITY as usize + 1];
+    table[TWO_ADICITY as usize] = top;
+    for k in (0..TWO_ADICITY as usize).rev() {
+        table[k] = BN254Field::mul(table[k + 1], table[k + 1]);
+    }
+    table
+}
+
+fn generator_table() -> &'static GeneratorTable {
+    static TABLE: OnceLock<GeneratorTable> = OnceLock::new();
+    TABLE.get_or_init(|| squaring_chain(GENERATOR_2_28))
+}
+
+fn inverse_generator_table() -> &'static GeneratorTable {
+    static TABLE: OnceLock<GeneratorTable> = OnceLock::new();
+    TABLE.get_or_init(|| squaring_chain(BN254Field::inv(GENERATOR_2_28)))
+}
 
 /// Get generator for a 2^k-sized domain.
 pub fn domain_generator(log_size: u32) -> U256 {
     assert!(log_size <= TWO_ADICITY, "log_size exceeds two-adicity");
-    let exp_power = TWO_ADICITY - log_size;
-    let exp = U256::from(1u64) << exp_power;
-    BN254Field::pow(GENERATOR_2_28, exp)
+    generator_table()[log_size as usize]
+}
+
+/// Inverse of `domain_generator(log_size)`, from a table built with a single
+/// field inversion.
+pub fn inverse_domain_generator(log_size: u32) -> U256 {
+    assert!(log_size <= TWO_ADICITY, "log_size exceeds two-adicity");
+    inverse_generator_table()[log_size as usize]
 }
 
 /// Evaluate g^index.
@@ -104,7 +130,7 @@ pub fn ifft(evals: &mut [U256], log_size: u32) {
         let m = 1usize << (s + 1);
         let half_m = m / 2;
         // Use inverse generator for IFFT
-        let w_m = BN254Field::inv(domain_generator(s + 1));
+        let w_m = inverse_domain_generator(s + 1);
 
         let mut k = 0;
         while k < n {
@@ -127,9 +153,124 @@ pub fn ifft(evals: &mut [U256], log_size: u32) {
     }
 }
 
+/// Inverse transition zerofier (x - g^(N-1)) / (x^N - 1) at every point of
+/// the LDE domain of size `blowup * trace_len` (`lde_domain[i]` = ω^i).
+///
+/// x_i^N = (ω^N)^i cycles through the blowup-th roots of unity, so only
+/// `blowup` distinct numerators x^N - 1 are batch-inverted and no point is
+/// exponentiated. Zero on the trace domain itself, where x^N - 1 vanishes.
+pub fn inverse_transition_zerofier_on_lde(lde_domain: &[U256], trace_len: u64) -> Vec<U256> {
+    let blowup = (lde_domain.len() as u64 / trace_len) as usize;
+    let one = U256::from(1u64);
+
+    let omega_n = domain_generator(blowup.trailing_zeros());
+    let mut inv_numerators = Vec::with_capacity(blowup);
+    let mut x_n = one;
+    for _ in 0..blowup {
+        inv_numerators.push(BN254Field::sub(x_n, one));
+        x_n = BN254Field::mul(x_n, omega_n);
+    }
+    BN254Field::batch_invert(&mut inv_numerators);
+
+    // g^(N-1) = g^-1 on the N-element trace domain
+    let trace_domain_last = inverse_domain_generator(trace_len.trailing_zeros());
+    lde_domain
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| BN254Field::mul(BN254Field::sub(x, trace_domain_last), inv_numerators[i % blowup]))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::field::BN254_PRIME;
+
+    #[test]
+    fn test_generator_2_28_derivation() {
+        // g = 5^((p-1)/2^28), recomputed from scratch
+        let exp = (BN254_PRIME - U256::from(1u64)) >> TWO_ADICITY as usize;
+        assert_eq!(BN254Field::pow(U256::from(5u64), exp), GENERATOR_2_28);
+    }
+
+    #[test]
+    fn test_generator_tables() {
+        for log_size in 0..=TWO_ADICITY {
+            let exp = U256::from(1u64) << (TWO_ADICITY - log_size);
+            let gen = BN254Field::pow(GENERATOR_2_28, exp);
+            assert_eq!(domain_generator(log_size), gen);
+            assert_eq!(inverse_domain_generator(log_size), BN254Field::inv(gen));
+        }
+    }
+
+    /// `ifft` as it was before the inverse-generator table: one generator
+    /// `pow` and one Fermat inversion per stage.
+    fn ifft_uncached(evals: &mut [U256], log_size: u32) {
+        let n = evals.len();
+        bit_reverse_permutation(evals, log_size);
+        for s in 0..log_size {
+            let half_m = 1usize << s;
+            let exp = U256::from(1u64) << (TWO_ADICITY - (s + 1));
+            let w_m = BN254Field::inv(BN254Field::pow(GENERATOR_2_28, exp));
+            for k in (0..n).step_by(2 * half_m) {
+                let mut w = U256::from(1u64);
+                for j in 0..half_m {
+                    let u = evals[k + j];
+                    let t = BN254Field::mul(w, evals[k + j + half_m]);
+                    evals[k + j] = BN254Field::add(u, t);
+                    evals[k + j + half_m] = BN254Field::sub(u, t);
+                    w = BN254Field::mul(w, w_m);
+                }
+            }
+        }
+        let n_inv = BN254Field::inv(U256::from(n as u64));
+        for val in evals.iter_mut() {
+            *val = BN254Field::mul(*val, n_inv);
+        }
+    }
+
+    #[test]
+    fn test_ifft_cached_generators_match_uncached() {
+        inverse_domain_generator(0); // build the table outside the count
+        for log_size in 1..=10u32 {
+            let evals: Vec<U256> = (0..1u64 << log_size).map(|i| U256::from(i * i * 7 + 3)).collect();
+            let mut expected = evals.clone();
+            ifft_uncached(&mut expected, log_size);
+
+            let mut actual = evals;
+            let pows_before = BN254Field::pow_calls();
+            ifft(&mut actual, log_size);
+            assert_eq!(actual, expected, "log_size {}", log_size);
+
+            // The stage loop only looks generators up; the one exponentiation
+            // left is the final 1/n scaling
+            assert_eq!(BN254Field::pow_calls() - pows_before, 1, "log_size {}", log_size);
+        }
+    }
+
+    #[test]
+    fn test_inverse_transition_zerofier_matches_per_point() {
+        let (log_trace_len, blowup) = (4u32, 4u64);
+        let trace_len = 1u64 << log_trace_len;
+        let lde_domain = get_domain(log_trace_len + 2);
+        assert_eq!(lde_domain.len() as u64, trace_len * blowup);
+        let g_last = BN254Field::pow(domain_generator(log_trace_len), U256::from(trace_len - 1));
+
+        let pows_before = BN254Field::pow_calls();
+        let batched = inverse_transition_zerofier_on_lde(&lde_domain, trace_len);
+        // The only exponentiation is the batch inversion's one Fermat inverse
+        assert_eq!(BN254Field::pow_calls() - pows_before, 1);
+
+        for (i, &x) in lde_domain.iter().enumerate() {
+            let numerator = BN254Field::sub(BN254Field::pow(x, U256::from(trace_len)), U256::from(1u64));
+            let expected = if numerator == U256::ZERO {
+                U256::ZERO
+            } else {
+                BN254Field::div(BN254Field::sub(x, g_last), numerator)
+            };
+            assert_eq!(batched[i], expected, "LDE point {}", i);
+        }
+    }
 
     #[test]
     fn test_fft_ifft_roundtrip() {
@@ -1,20 +1,22 @@
 //! BN254 Field Arithmetic (shared with on-chain verifier)
 //!
-//! Exact same implementation as contracts/stylus/src/field.rs
-//! to ensure prover and verifier are compatible.
+//! Works on canonical U256 values. The modulus and the multiplicative core
+//! (products, powers, inverses) are `stark_core::field`'s Montgomery `Fp`,
+//! the same code the on-chain verifier runs.
 
 use alloy_primitives::U256;
+use stark_core::field::Fp;
 
-/// BN254 field prime
-pub const BN254_PRIME: U256 = U256::from_limbs([
-    0x43e1f593f0000001,
-    0x2833e84879b97091,
-    0xb85045b68181585d,
-    0x30644e72e131a029,
-]);
+pub use stark_core::field::{field_to_signed, signed_to_field, BN254_PRIME};
 
 pub struct BN254Field;
 
+#[cfg(test)]
+thread_local! {
+    /// `BN254Field::pow` calls made on this thread (inversions included).
+    static POW_CALLS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
 impl BN254Field {
     #[inline(always)]
     pub fn add(a: U256, b: U256) -> U256 {
@@ -37,25 +39,20 @@ impl BN254Field {
 
     #[inline(always)]
     pub fn mul(a: U256, b: U256) -> U256 {
-        a.mul_mod(b, BN254_PRIME)
+        Fp::mul(Fp::from_u256(a), Fp::from_u256(b)).to_u256()
     }
 
     #[inline]
     pub fn pow(base: U256, exp: U256) -> U256 {
-        if exp == U256::ZERO {
-            return U256::from(1u64);
-        }
-        let mut result = U256::from(1u64);
-        let mut b = base;
-        let mut e = exp;
-        while e > U256::ZERO {
-            if e & U256::from(1u64) == U256::from(1u64) {
-                result = Self::mul(result, b);
-            }
-            b = Self::mul(b, b);
-            e >>= 1;
-        }
-        result
+        #[cfg(test)]
+        POW_CALLS.with(|calls| calls.set(calls.get() + 1));
+        Fp::pow(Fp::from_u256(base), exp).to_u256()
+    }
+
+    /// Number of `pow` calls (and so inversions) made so far on this thread.
+    #[cfg(test)]
+    pub(crate) fn pow_calls() -> usize {
+        POW_CALLS.with(|calls| calls.get())
     }
 
     #[inline]
@@ -81,6 +78,20 @@ impl BN254Field {
         }
     }
 
+    /// Low `n` bits of a canonical field element (a mod 2^n).
+    pub fn low_bits(a: U256, n: usize) -> U256 {
+        if n >= 256 {
+            a
+        } else {
+            a & ((U256::from(1u64) << n) - U256::from(1u64))
+        }
+    }
+
+    /// Low `n` bits of a canonical field element, least significant first.
+    pub fn to_bits_le(a: U256, n: usize) -> Vec<bool> {
+        (0..n).map(|i| i < 256 && a.bit(i)).collect()
+    }
+
     #[inline(always)]
     pub fn reduce(a: U256) -> U256 {
         if a >= BN254_PRIME {
@@ -122,3 +133,17 @@ impl BN254Field {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Limb arrays are little-endian: limb 0 holds the least significant word.
+    #[test]
+    fn test_bn254_prime_limb_order() {
+        let p = U256::from_str_radix("21888242871839275222246405745257275088548364400416034343698204186575808495617", 10).unwrap();
+        assert_eq!(BN254_PRIME, p);
+        assert_eq!(U256::from_str_radix("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001", 16).unwrap(), p);
+        assert_eq!(BN254_PRIME.as_limbs()[0], 0x43e1f593f0000001);
+    }
+}
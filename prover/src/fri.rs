@@ -7,9 +7,10 @@
 
 use alloy_primitives::U256;
 use crate::field::BN254Field;
-use crate::channel::Channel;
-use crate::commit::MerkleTree;
+use crate::channel::{Channel, TranscriptHash};
+use crate::commit::{commit_column, MerkleTree};
 use crate::domain;
+use stark_core::fri::{qv_index, query_values_len, LayerValue};
 
 /// Data for a single FRI layer produced by the prover.
 pub struct FriLayer {
@@ -41,20 +42,97 @@ pub struct FriCommitment {
 /// * `channel` - Fiat-Shamir channel for drawing challenges
 /// * `log_domain_size` - Log2 of the initial domain size
 /// * `num_layers` - Number of folding layers
-pub fn fri_commit(
+/// * `leaf_salt` - Optional per-proof salt applied to every layer's leaves
+pub fn fri_commit<H: TranscriptHash>(
     evaluations: &[U256],
-    channel: &mut Channel,
+    channel: &mut Channel<H>,
     log_domain_size: u32,
     num_layers: usize,
+    leaf_salt: Option<U256>,
 ) -> FriCommitment {
+    fri_commit_with_progress(evaluations, channel, log_domain_size, num_layers, leaf_salt, |_| {})
+}
+
+/// `fri_commit`, calling `on_layer(k)` once the k-th of `num_layers` layers
+/// has been committed and folded (k = 1..=num_layers).
+pub fn fri_commit_with_progress<H: TranscriptHash>(
+    evaluations: &[U256],
+    channel: &mut Channel<H>,
+    log_domain_size: u32,
+    num_layers: usize,
+    leaf_salt: Option<U256>,
+    on_layer: impl Fn(usize),
+) -> FriCommitment {
+    let (layers, alphas, final_evals, final_log_domain) =
+        fri_fold_layers(evaluations, channel, log_domain_size, num_layers, leaf_salt, &on_layer);
+
+    // Convert final evaluations to polynomial coefficients via IFFT
+    let mut final_poly = final_evals;
+    domain::ifft(&mut final_poly, final_log_domain);
+
+    // Commit final polynomial to channel
+    for coeff in &final_poly {
+        channel.commit(*coeff);
+    }
+
+    FriCommitment {
+        layers,
+        final_poly,
+        alphas,
+    }
+}
+
+/// Like `fri_commit`, but for maximal folding: once `num_layers` folds have
+/// brought the degree bound down to 1, the final layer is a constant and only
+/// that single coefficient is sent (and absorbed) as the final polynomial.
+///
+/// Errors if the final layer is not constant, i.e. `evaluations` was not of
+/// low enough degree for `num_layers` folds.
+pub fn fri_commit_constant_final<H: TranscriptHash>(
+    evaluations: &[U256],
+    channel: &mut Channel<H>,
+    log_domain_size: u32,
+    num_layers: usize,
+    leaf_salt: Option<U256>,
+) -> Result<FriCommitment, String> {
+    let (layers, alphas, final_evals, final_log_domain) =
+        fri_fold_layers(evaluations, channel, log_domain_size, num_layers, leaf_salt, &|_| {});
+
+    let constant = final_evals[0];
+    if final_evals.iter().any(|&v| v != constant) {
+        return Err(format!(
+            "final FRI layer (log size {}) is not constant after {} folds",
+            final_log_domain, num_layers
+        ));
+    }
+    channel.commit(constant);
+
+    Ok(FriCommitment {
+        layers,
+        final_poly: vec![constant],
+        alphas,
+    })
+}
+
+/// Commit and fold `num_layers` layers, returning the layers, the alphas and
+/// the final layer's evaluations with its log domain size. `on_layer` gets
+/// the number of layers done after each one.
+fn fri_fold_layers<H: TranscriptHash>(
+    evaluations: &[U256],
+    channel: &mut Channel<H>,
+    log_domain_size: u32,
+    num_layers: usize,
+    leaf_salt: Option<U256>,
+    on_layer: &impl Fn(usize),
+) -> (Vec<FriLayer>, Vec<U256>, Vec<U256>, u32) {
     let mut layers = Vec::with_capacity(num_layers);
     let mut alphas = Vec::with_capacity(num_layers);
     let mut current_evals = evaluations.to_vec();
     let mut current_log_domain = log_domain_size;
 
-    for _layer in 0..num_layers {
+    for layer in 0..num_layers {
         // Commit to current evaluations
-        let tree = MerkleTree::build(&current_evals);
+        let tree = commit_column(&current_evals, leaf_salt);
         let root = tree.root();
 
         // Send commitment to channel
@@ -103,68 +181,471 @@ pub fn fri_commit(
 
         current_evals = next_evals;
         current_log_domain -= 1;
+        on_layer(layer + 1);
     }
 
-    // Convert final evaluations to polynomial coefficients via IFFT
-    let mut final_poly = current_evals.clone();
-    domain::ifft(&mut final_poly, current_log_domain);
+    (layers, alphas, current_evals, current_log_domain)
+}
 
-    // Commit final polynomial to channel
-    for coeff in &final_poly {
-        channel.commit(*coeff);
-    }
+/// FRI commitment whose final layer is sent as a Merkle root instead of
+/// coefficients in the clear.
+pub struct CommittedFinalFri {
+    /// The usual commitment; `final_poly` is kept for `fri_self_check` but is
+    /// not sent.
+    pub fri: FriCommitment,
+    /// Final-layer evaluations on the final domain (the committed leaves).
+    pub final_evaluations: Vec<U256>,
+    /// Merkle tree over `final_evaluations`; its root replaces the
+    /// coefficients in the transcript.
+    pub final_tree: MerkleTree,
+}
 
-    FriCommitment {
-        layers,
-        final_poly,
-        alphas,
+/// Like `fri_commit`, but Merkle-commits the final layer's evaluations and
+/// absorbs only the root.
+///
+/// Each query then opens one final-layer value (`fri_final_openings`), so
+/// calldata grows with the number of queries rather than the final degree.
+/// Tradeoff: the verifier no longer sees the whole final layer, so it cannot
+/// check its degree; unopened entries are unconstrained. Only worth it for
+/// long final polynomials.
+pub fn fri_commit_final_tree<H: TranscriptHash>(
+    evaluations: &[U256],
+    channel: &mut Channel<H>,
+    log_domain_size: u32,
+    num_layers: usize,
+    leaf_salt: Option<U256>,
+) -> CommittedFinalFri {
+    let (layers, alphas, final_evaluations, final_log_domain) =
+        fri_fold_layers(evaluations, channel, log_domain_size, num_layers, leaf_salt, &|_| {});
+
+    let final_tree = commit_column(&final_evaluations, leaf_salt);
+    channel.commit(final_tree.root());
+
+    let mut final_poly = final_evaluations.clone();
+    domain::ifft(&mut final_poly, final_log_domain);
+
+    CommittedFinalFri {
+        fri: FriCommitment { layers, final_poly, alphas },
+        final_evaluations,
+        final_tree,
     }
 }
 
-/// Generate FRI query proofs.
+/// One query's opening of the committed final layer, mirroring the
+/// verifier's `FinalLayerOpening`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinalLayerOpening {
+    pub value: U256,
+    /// Merkle path of `value`, `log_domain_size - num_layers` nodes long.
+    pub path: Vec<U256>,
+}
+
+/// Open the committed final layer at each query index.
+pub fn fri_final_openings(
+    commitment: &CommittedFinalFri,
+    query_indices: &[usize],
+) -> Vec<FinalLayerOpening> {
+    let final_size = commitment.final_evaluations.len();
+    query_indices
+        .iter()
+        .map(|&idx| {
+            let idx = idx % final_size;
+            FinalLayerOpening {
+                value: commitment.final_evaluations[idx],
+                path: commitment.final_tree.auth_path(idx).0,
+            }
+        })
+        .collect()
+}
+
+/// FRI over a random linear combination of several committed polynomials.
+pub struct BatchedFriCommitment {
+    /// Evaluations of each input polynomial on the LDE domain.
+    pub inputs: Vec<Vec<U256>>,
+    /// Merkle tree over each input's evaluations (roots absorbed before λ).
+    pub input_trees: Vec<MerkleTree>,
+    /// λ: FRI runs on Σ_i λ^i · f_i.
+    pub batching_challenge: U256,
+    pub fri: FriCommitment,
+}
+
+/// Commit several polynomials into a single FRI instance.
 ///
-/// For each query index, produces the values and authentication paths
-/// at each FRI layer.
+/// Commits each input's evaluations and absorbs the roots, draws the
+/// batching challenge λ, then runs `fri_commit` on Σ_i λ^i · f_i. One set of
+/// FRI layer roots covers every input; the verifier side is
+/// `verify_fri_batched`. All inputs must be evaluated on the same domain.
+pub fn fri_commit_batched<H: TranscriptHash>(
+    polys: &[&[U256]],
+    channel: &mut Channel<H>,
+    log_domain_size: u32,
+    num_layers: usize,
+    leaf_salt: Option<U256>,
+) -> BatchedFriCommitment {
+    assert!(!polys.is_empty(), "nothing to batch");
+    let domain_size = 1usize << log_domain_size;
+    assert!(polys.iter().all(|p| p.len() == domain_size), "inputs must cover the LDE domain");
+
+    let input_trees: Vec<MerkleTree> = polys.iter().map(|p| commit_column(p, leaf_salt)).collect();
+    for tree in &input_trees {
+        channel.commit(tree.root());
+    }
+    let lambda = channel.draw_felt();
+
+    // Horner in λ: f = f_0 + λ(f_1 + λ(f_2 + ...))
+    let mut combined = polys[polys.len() - 1].to_vec();
+    for poly in polys[..polys.len() - 1].iter().rev() {
+        for (acc, &v) in combined.iter_mut().zip(poly.iter()) {
+            *acc = BN254Field::add(BN254Field::mul(*acc, lambda), v);
+        }
+    }
+
+    let fri = fri_commit(&combined, channel, log_domain_size, num_layers, leaf_salt);
+    BatchedFriCommitment {
+        inputs: polys.iter().map(|p| p.to_vec()).collect(),
+        input_trees,
+        batching_challenge: lambda,
+        fri,
+    }
+}
+
+/// One query's openings of every batched input at x and -x, mirroring the
+/// verifier's `BatchedInputOpening`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchedInputOpening {
+    /// (f_i(x), f_i(-x)) per input polynomial.
+    pub values: Vec<(U256, U256)>,
+    /// Merkle paths of f_i(x) and f_i(-x) per input polynomial.
+    pub paths: Vec<(Vec<U256>, Vec<U256>)>,
+}
+
+/// Open every batched input at each query index and its negation.
+pub fn fri_batched_input_openings(
+    commitment: &BatchedFriCommitment,
+    query_indices: &[usize],
+) -> Vec<BatchedInputOpening> {
+    query_indices
+        .iter()
+        .map(|&idx| {
+            let mut opening = BatchedInputOpening { values: Vec::new(), paths: Vec::new() };
+            for (evals, tree) in commitment.inputs.iter().zip(&commitment.input_trees) {
+                let neg = (idx + evals.len() / 2) % evals.len();
+                opening.values.push((evals[idx], evals[neg]));
+                opening.paths.push((tree.auth_path(idx).0, tree.auth_path(neg).0));
+            }
+            opening
+        })
+        .collect()
+}
+
+/// One query's FRI decommitment, mirroring the verifier's `QueryOpening`.
 ///
-/// # Arguments
-/// * `commitment` - FRI commitment data
-/// * `query_indices` - Indices in the initial LDE domain
+/// `layer_values[l]` is (f(x), f(-x)) on layer l and `layer_paths[l]` the Merkle
+/// path of f(x), `log_domain_size - l` nodes long.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryOpening {
+    pub index: usize,
+    pub layer_values: Vec<(U256, U256)>,
+    pub layer_paths: Vec<Vec<U256>>,
+}
+
+impl QueryOpening {
+    /// Flatten openings into the (`query_values`, `query_paths`, `query_indices`)
+    /// arrays the proof serializes.
+    pub fn to_flat(openings: &[Self]) -> (Vec<U256>, Vec<U256>, Vec<usize>) {
+        let num_layers = openings.first().map_or(0, |o| o.layer_values.len());
+        let mut query_values = vec![U256::ZERO; query_values_len(openings.len(), num_layers)];
+        let mut query_paths = Vec::new();
+        let mut query_indices = Vec::with_capacity(openings.len());
+        for (query, opening) in openings.iter().enumerate() {
+            assert_eq!(opening.layer_values.len(), num_layers, "openings differ in layer count");
+            query_indices.push(opening.index);
+            for (layer, &(fx, f_neg_x)) in opening.layer_values.iter().enumerate() {
+                query_values[qv_index(num_layers, query, layer, LayerValue::X)] = fx;
+                query_values[qv_index(num_layers, query, layer, LayerValue::NegX)] = f_neg_x;
+            }
+            for path in &opening.layer_paths {
+                query_paths.extend_from_slice(path);
+            }
+        }
+        (query_values, query_paths, query_indices)
+    }
+
+    /// Split flat arrays back into one opening per entry of `query_indices`.
+    pub fn from_flat(
+        query_values: &[U256],
+        query_paths: &[U256],
+        query_indices: &[usize],
+        num_layers: usize,
+        log_domain_size: u32,
+    ) -> Result<Vec<Self>, String> {
+        if num_layers > log_domain_size as usize {
+            return Err(format!(
+                "{} FRI layers exceed log domain size {}",
+                num_layers, log_domain_size
+            ));
+        }
+        let depths = (0..num_layers).map(|layer| log_domain_size as usize - layer);
+        let path_elements_per_query: usize = depths.clone().sum();
+        let num_queries = query_indices.len();
+        let expected_values = query_values_len(num_queries, num_layers);
+        if query_values.len() != expected_values {
+            return Err(format!(
+                "expected {} query values, got {}",
+                expected_values,
+                query_values.len()
+            ));
+        }
+        if query_paths.len() != num_queries * path_elements_per_query {
+            return Err(format!(
+                "expected {} query path nodes, got {}",
+                num_queries * path_elements_per_query,
+                query_paths.len()
+            ));
+        }
+
+        let mut paths = query_paths;
+        let mut openings = Vec::with_capacity(num_queries);
+        for (query, &index) in query_indices.iter().enumerate() {
+            let mut opening = QueryOpening {
+                index,
+                layer_values: Vec::with_capacity(num_layers),
+                layer_paths: Vec::with_capacity(num_layers),
+            };
+            for (layer, depth) in depths.clone().enumerate() {
+                opening.layer_values.push((
+                    query_values[qv_index(num_layers, query, layer, LayerValue::X)],
+                    query_values[qv_index(num_layers, query, layer, LayerValue::NegX)],
+                ));
+                let (path, rest) = paths.split_at(depth);
+                opening.layer_paths.push(path.to_vec());
+                paths = rest;
+            }
+            openings.push(opening);
+        }
+        Ok(openings)
+    }
+}
+
+/// Open every FRI layer at each query index.
 ///
-/// # Returns
-/// (query_values, query_paths, query_path_indices) all flattened
-pub fn fri_query_proofs(
+/// Per layer, records (f(x), f(-x)) and the Merkle path of f(x), then halves
+/// the index for the next (folded) layer.
+pub fn fri_query_openings(
     commitment: &FriCommitment,
     query_indices: &[usize],
-) -> (Vec<U256>, Vec<U256>, Vec<bool>) {
-    let mut all_values = Vec::new();
-    let mut all_paths = Vec::new();
-    let mut all_indices = Vec::new();
+) -> Vec<QueryOpening> {
+    query_indices
+        .iter()
+        .map(|&initial_idx| {
+            let mut idx = initial_idx;
+            let mut opening = QueryOpening {
+                index: initial_idx,
+                layer_values: Vec::with_capacity(commitment.layers.len()),
+                layer_paths: Vec::with_capacity(commitment.layers.len()),
+            };
+
+            for layer in &commitment.layers {
+                let layer_size = layer.evaluations.len();
+                let half = layer_size / 2;
+                idx %= layer_size;
+
+                // Value at index and its symmetric value
+                let fx = layer.evaluations[idx];
+                let f_neg_x = layer.evaluations[(idx + half) % layer_size];
+                opening.layer_values.push((fx, f_neg_x));
+
+                // Merkle authentication path for fx
+                opening.layer_paths.push(layer.tree.auth_path(idx).0);
+
+                // Update index for next layer (halved domain)
+                idx %= half;
+            }
+            opening
+        })
+        .collect()
+}
+
+/// Fold a single evaluation pair: (f(x) + f(-x))/2 + alpha * (f(x) - f(-x))/(2x).
+fn fri_fold(fx: U256, f_neg_x: U256, alpha: U256, x: U256) -> U256 {
+    let two = U256::from(2u64);
+    let even = BN254Field::div(BN254Field::add(fx, f_neg_x), two);
+    let odd = BN254Field::div(BN254Field::sub(fx, f_neg_x), BN254Field::mul(two, x));
+    BN254Field::add(even, BN254Field::mul(alpha, odd))
+}
+
+/// Re-run the verifier's cross-layer folding check against the prover's own layers.
+///
+/// For every query index, folds layer i at (x, -x) and checks the result
+/// against layer i+1 (or the final polynomial after the last layer).
+/// Catches folding/ordering bugs at prove time instead of on-chain.
+pub fn fri_self_check(commitment: &FriCommitment, query_indices: &[usize]) -> bool {
+    let num_layers = commitment.layers.len();
+    if num_layers == 0 || commitment.alphas.len() != num_layers {
+        return false;
+    }
+
+    let last = &commitment.layers[num_layers - 1];
+    let final_gen = domain::domain_generator(last.log_domain_size - 1);
 
     for &initial_idx in query_indices {
         let mut idx = initial_idx;
 
-        for layer in &commitment.layers {
+        for (i, layer) in commitment.layers.iter().enumerate() {
             let layer_size = layer.evaluations.len();
             let half = layer_size / 2;
+            idx %= layer_size;
 
-            // Value at index
-            let fx = layer.evaluations[idx % layer_size];
-            // Symmetric value
-            let sym_idx = (idx + half) % layer_size;
-            let f_neg_x = layer.evaluations[sym_idx];
+            let fx = layer.evaluations[idx];
+            let f_neg_x = layer.evaluations[(idx + half) % layer_size];
+            let x = domain::evaluate_at(domain::domain_generator(layer.log_domain_size), idx as u64);
+            let folded = fri_fold(fx, f_neg_x, commitment.alphas[i], x);
 
-            all_values.push(fx);
-            all_values.push(f_neg_x);
+            idx %= half;
+            let expected = match commitment.layers.get(i + 1) {
+                Some(next) => next.evaluations[idx],
+                None => {
+                    let final_x = domain::evaluate_at(final_gen, idx as u64);
+                    let mut acc = U256::ZERO;
+                    for &c in commitment.final_poly.iter().rev() {
+                        acc = BN254Field::add(BN254Field::mul(acc, final_x), c);
+                    }
+                    acc
+                }
+            };
 
-            // Merkle authentication path for fx
-            let (path, path_indices) = layer.tree.auth_path(idx % layer_size);
-            all_paths.extend_from_slice(&path);
-            all_indices.extend_from_slice(&path_indices);
+            if folded != expected {
+                return false;
+            }
+        }
+    }
 
-            // Update index for next layer (halved domain)
-            idx = idx % half;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Low-degree polynomial evaluated on a 2^log_size domain.
+    fn low_degree_evals(log_size: u32) -> Vec<U256> {
+        let size = 1usize << log_size;
+        let mut evals = vec![U256::ZERO; size];
+        for (i, c) in evals.iter_mut().take(size / 4).enumerate() {
+            *c = U256::from(3 * i as u64 + 7);
         }
+        domain::fft(&mut evals, log_size);
+        evals
     }
 
-    (all_values, all_paths, all_indices)
+    #[test]
+    fn test_fri_self_check_passes() {
+        let mut channel = Channel::new(U256::from(42u64));
+        let commitment = fri_commit(&low_degree_evals(6), &mut channel, 6, 4, None);
+        let query_indices = channel.draw_queries(8, 64);
+
+        assert!(fri_self_check(&commitment, &query_indices));
+    }
+
+    #[test]
+    fn test_fri_self_check_detects_bad_fold() {
+        let mut channel = Channel::new(U256::from(42u64));
+        let mut commitment = fri_commit(&low_degree_evals(6), &mut channel, 6, 4, None);
+        let query_indices = channel.draw_queries(8, 64);
+
+        // Perturb the folded value the first query lands on in layer 1
+        let idx = query_indices[0] % 32;
+        let v = commitment.layers[1].evaluations[idx];
+        commitment.layers[1].evaluations[idx] = BN254Field::add(v, U256::from(1u64));
+
+        assert!(!fri_self_check(&commitment, &query_indices));
+    }
+
+    #[test]
+    fn test_fri_commit_batched() {
+        let f0 = low_degree_evals(6);
+        let f1: Vec<U256> = low_degree_evals(6).iter().map(|&v| BN254Field::mul(v, v)).collect();
+        let mut channel = Channel::new(U256::from(42u64));
+        let batched = fri_commit_batched(&[&f0, &f1], &mut channel, 6, 4, None);
+        let query_indices = channel.draw_queries(8, 64);
+
+        // Layer 0 is f_0 + λ·f_1, and folds consistently
+        let lambda = batched.batching_challenge;
+        for (j, &v) in batched.fri.layers[0].evaluations.iter().enumerate() {
+            assert_eq!(v, BN254Field::add(f0[j], BN254Field::mul(lambda, f1[j])));
+        }
+        assert!(fri_self_check(&batched.fri, &query_indices));
+        // One FRI instance for both inputs
+        assert_eq!(batched.fri.layers.len(), 4);
+
+        let openings = fri_batched_input_openings(&batched, &query_indices);
+        let idx = query_indices[0];
+        assert_eq!(openings[0].values[1], (f1[idx], f1[(idx + 32) % 64]));
+        assert_eq!(openings[0].paths[0].0, batched.input_trees[0].auth_path(idx).0);
+
+        // λ depends on the committed inputs
+        let mut other = f1.clone();
+        other[0] = BN254Field::add(other[0], U256::from(1u64));
+        let mut channel = Channel::new(U256::from(42u64));
+        assert_ne!(fri_commit_batched(&[&f0, &other], &mut channel, 6, 4, None).batching_challenge, lambda);
+    }
+
+    #[test]
+    fn test_fri_commit_final_tree() {
+        let mut channel = Channel::new(U256::from(42u64));
+        let committed = fri_commit_final_tree(&low_degree_evals(6), &mut channel, 6, 2, None);
+        let query_indices = channel.draw_queries(8, 64);
+        assert!(fri_self_check(&committed.fri, &query_indices));
+
+        // Same layers as the clear variant; only the final absorb differs
+        let mut clear_channel = Channel::new(U256::from(42u64));
+        let clear = fri_commit(&low_degree_evals(6), &mut clear_channel, 6, 2, None);
+        assert_eq!(committed.fri.final_poly, clear.final_poly);
+        assert_ne!(clear_channel.draw_queries(8, 64), query_indices);
+
+        let openings = fri_final_openings(&committed, &query_indices);
+        for (opening, &idx) in openings.iter().zip(&query_indices) {
+            assert_eq!(opening.value, committed.final_evaluations[idx % 16]);
+            assert_eq!(opening.path.len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_fri_commit_constant_final() {
+        // Degree < 16 on 64 points: four folds leave a constant on 4 points
+        let mut channel = Channel::new(U256::from(42u64));
+        let commitment = fri_commit_constant_final(&low_degree_evals(6), &mut channel, 6, 4, None).unwrap();
+        assert_eq!(commitment.final_poly.len(), 1);
+        let query_indices = channel.draw_queries(8, 64);
+        assert!(fri_self_check(&commitment, &query_indices));
+
+        // Same layers and constant as the clear variant's [c, 0, 0, 0]
+        let clear = fri_commit(&low_degree_evals(6), &mut Channel::new(U256::from(42u64)), 6, 4, None);
+        assert_eq!(clear.final_poly[0], commitment.final_poly[0]);
+        assert!(clear.final_poly[1..].iter().all(|&c| c == U256::ZERO));
+
+        // Three folds leave a degree < 2 layer, which is not constant
+        let mut channel = Channel::new(U256::from(42u64));
+        assert!(fri_commit_constant_final(&low_degree_evals(6), &mut channel, 6, 3, None).is_err());
+    }
+
+    #[test]
+    fn test_query_openings_flat_round_trip() {
+        let mut channel = Channel::new(U256::from(42u64));
+        let commitment = fri_commit(&low_degree_evals(6), &mut channel, 6, 4, None);
+        let query_indices = channel.draw_queries(8, 64);
+        let openings = fri_query_openings(&commitment, &query_indices);
+
+        // Layer l's path has 6 - l nodes: 6 + 5 + 4 + 3 per query
+        let (values, paths, indices) = QueryOpening::to_flat(&openings);
+        assert_eq!(values.len(), 8 * 4 * 2);
+        assert_eq!(paths.len(), 8 * 18);
+        assert_eq!(indices, query_indices);
+
+        let reparsed = QueryOpening::from_flat(&values, &paths, &indices, 4, 6).unwrap();
+        assert_eq!(reparsed, openings);
+
+        assert!(QueryOpening::from_flat(&values, &paths[1..], &indices, 4, 6).is_err());
+        assert!(QueryOpening::from_flat(&values, &paths, &indices, 3, 6).is_err());
+    }
 }
@@ -3,6 +3,8 @@
 //! Fetches PositionDecrease events from GMX v2 EventEmitter on Arbitrum
 //! via `eth_getLogs` JSON-RPC calls. Parses ABI-encoded EventLogData
 //! to extract trade details and compute return_bps for STARK proving.
+//! PositionIncrease events are fetched alongside and paired with the
+//! decreases by position key to report holding-period returns.
 //!
 //! Also provides receipt proof fetching for dataset commitment binding.
 //!
@@ -13,7 +15,10 @@ use alloy_sol_types::sol;
 use serde::{Deserialize, Serialize};
 use tiny_keccak::{Hasher, Keccak};
 
-use crate::receipt_proof::{ReceiptProofData, compute_dataset_commitment, rlp_encode_tx_index};
+use crate::receipt_proof::{
+    DatasetCommitment, ReceiptDerived, ReceiptLog, ReceiptProofData, rlp_encode_receipt, rlp_encode_tx_index,
+};
+use crate::mock_data::TradeReturn;
 
 /// GMX v2 EventEmitter contract address on Arbitrum One.
 pub const GMX_EVENT_EMITTER: &str = "0xC8ee91A54287DB53897056e12D9819156D3822Fb";
@@ -25,7 +30,33 @@ pub const DEFAULT_ARBITRUM_RPC: &str = "https://arb1.arbitrum.io/rpc";
 const BLOCK_CHUNK: u64 = 100_000;
 
 /// Approximately 30 days of Arbitrum blocks (~250ms block time).
-const DEFAULT_LOOKBACK_BLOCKS: u64 = 10_000_000;
+pub const DEFAULT_LOOKBACK_BLOCKS: u64 = 10_000_000;
+
+/// Where a fetch starts; it always runs up to `to_block` (default: latest).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchMode {
+    /// The given number of blocks before `to_block`.
+    Lookback(u64),
+    /// Everything from the given block on, e.g. the block after a wallet's
+    /// last proof, so only new activity is fetched.
+    SinceBlock(u64),
+}
+
+impl Default for FetchMode {
+    fn default() -> Self {
+        FetchMode::Lookback(DEFAULT_LOOKBACK_BLOCKS)
+    }
+}
+
+impl FetchMode {
+    /// First block of the range ending at `to_block`.
+    pub fn from_block(self, to_block: u64) -> u64 {
+        match self {
+            FetchMode::Lookback(blocks) => to_block.saturating_sub(blocks),
+            FetchMode::SinceBlock(block) => block,
+        }
+    }
+}
 
 // ── ABI Types ──────────────────────────────────────────────
 
@@ -162,7 +193,34 @@ pub struct GmxFetchedTrade {
     pub size_delta_usd: String,
     pub base_pnl_usd: String,
     pub is_long: bool,
-    pub return_bps: i64,
+    pub return_bps: TradeReturn,
+    /// Hex `positionKey` of the closed position, if the event carried one.
+    #[serde(default)]
+    pub position_key: Option<String>,
+    /// Block of the PositionIncrease paired with this decrease, if any.
+    #[serde(default)]
+    pub open_block: Option<u64>,
+    /// Block of the PositionDecrease (same as `block_number`).
+    #[serde(default)]
+    pub close_block: u64,
+    /// basePnlUsd over the paired increase's sizeDeltaUsd, in bps.
+    #[serde(default)]
+    pub holding_period_return_bps: Option<TradeReturn>,
+}
+
+impl GmxFetchedTrade {
+    /// Blocks between the paired PositionIncrease and this decrease.
+    pub fn holding_period_blocks(&self) -> Option<u64> {
+        self.open_block.map(|open| self.close_block.saturating_sub(open))
+    }
+}
+
+/// A PositionIncrease event, kept until it is paired with a decrease.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PositionOpen {
+    block_number: u64,
+    position_key: String,
+    size_delta_usd: U256,
 }
 
 /// Result of fetching GMX trades.
@@ -185,9 +243,74 @@ struct JsonRpcRequest {
     id: u64,
 }
 
+/// A JSON-RPC `error` object.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// Why a JSON-RPC call failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    /// The node answered `method` with an `error` object (rate limit, block
+    /// range too large, ...), as opposed to an empty `result`.
+    Rpc { method: String, error: JsonRpcError },
+    /// The request failed or the response was not JSON-RPC.
+    Transport(String),
+    /// A PositionDecrease log decoded as `EventLogData` but lacks a key the
+    /// fetcher reads: the EventEmitter's event schema has likely changed.
+    SchemaMismatch { tx_hash: String, missing_key: &'static str },
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::Rpc { method, error } => {
+                write!(f, "{} failed with RPC error {}: {}", method, error.code, error.message)
+            }
+            FetchError::Transport(msg) => write!(f, "{}", msg),
+            FetchError::SchemaMismatch { tx_hash, missing_key } => write!(
+                f,
+                "GMX event schema mismatch: PositionDecrease log in {} has no `{}` key",
+                tx_hash, missing_key
+            ),
+        }
+    }
+}
+
+impl From<FetchError> for String {
+    fn from(e: FetchError) -> String {
+        e.to_string()
+    }
+}
+
 #[derive(Deserialize)]
 struct JsonRpcResponse {
+    /// Absent from error responses; `null` for e.g. an unknown receipt.
+    #[serde(default)]
     result: serde_json::Value,
+    error: Option<JsonRpcError>,
+}
+
+/// The `result` of a raw `method` response, or its `error` as `FetchError::Rpc`.
+fn rpc_result(response: serde_json::Value, method: &str) -> Result<serde_json::Value, FetchError> {
+    let response: JsonRpcResponse = serde_json::from_value(response)
+        .map_err(|e| FetchError::Transport(format!("Failed to parse {} response: {}", method, e)))?;
+    match response.error {
+        Some(error) => Err(FetchError::Rpc { method: method.to_string(), error }),
+        None => Ok(response.result),
+    }
+}
+
+/// Send `request` over `transport` and return its `result`.
+async fn rpc_call<T: RpcTransport>(transport: &T, request: JsonRpcRequest) -> Result<serde_json::Value, FetchError> {
+    let body = serde_json::to_value(&request).map_err(|e| FetchError::Transport(e.to_string()))?;
+    let response = transport
+        .request(body)
+        .await
+        .map_err(|e| FetchError::Transport(format!("{} failed: {}", request.method, e)))?;
+    rpc_result(response, request.method)
 }
 
 #[derive(Deserialize)]
@@ -223,7 +346,7 @@ fn keccak256(data: &[u8]) -> [u8; 32] {
 }
 
 /// Fetch the current block number from the RPC.
-async fn get_block_number(client: &reqwest::Client, rpc_url: &str) -> Result<u64, String> {
+async fn get_block_number<T: RpcTransport>(transport: &T) -> Result<u64, FetchError> {
     let req = JsonRpcRequest {
         jsonrpc: "2.0",
         method: "eth_blockNumber",
@@ -231,30 +354,24 @@ async fn get_block_number(client: &reqwest::Client, rpc_url: &str) -> Result<u64
         id: 1,
     };
 
-    let resp: JsonRpcResponse = client
-        .post(rpc_url)
-        .json(&req)
-        .send()
-        .await
-        .map_err(|e| format!("RPC request failed: {e}"))?
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {e}"))?;
-
-    let hex_str = resp.result.as_str().ok_or("Invalid block number response")?;
+    let result = rpc_call(transport, req).await?;
+    let hex_str = result
+        .as_str()
+        .ok_or_else(|| FetchError::Transport("Invalid block number response".to_string()))?;
     u64::from_str_radix(hex_str.trim_start_matches("0x"), 16)
-        .map_err(|e| format!("Failed to parse block number: {e}"))
+        .map_err(|e| FetchError::Transport(format!("Failed to parse block number: {e}")))
 }
 
 /// Fetch logs for a specific block range.
-async fn get_logs(
-    client: &reqwest::Client,
-    rpc_url: &str,
+///
+/// An RPC `error` is returned as such, never as an empty log list.
+async fn get_logs<T: RpcTransport>(
+    transport: &T,
     address: &str,
     topics: &[Option<String>],
     from_block: u64,
     to_block: u64,
-) -> Result<Vec<LogEntry>, String> {
+) -> Result<Vec<LogEntry>, FetchError> {
     let topics_json: Vec<serde_json::Value> = topics
         .iter()
         .map(|t| match t {
@@ -275,23 +392,25 @@ async fn get_logs(
         id: 1,
     };
 
-    let resp: JsonRpcResponse = client
-        .post(rpc_url)
-        .json(&req)
-        .send()
-        .await
-        .map_err(|e| format!("eth_getLogs failed: {e}"))?
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse getLogs response: {e}"))?;
+    let result = rpc_call(transport, req).await?;
+    serde_json::from_value(result)
+        .map_err(|e| FetchError::Transport(format!("Failed to parse log entries: {e}")))
+}
 
-    serde_json::from_value(resp.result)
-        .map_err(|e| format!("Failed to parse log entries: {e}"))
+/// Fields read from a PositionIncrease or PositionDecrease event.
+struct PositionEventFields {
+    /// `None` when the key is absent, as opposed to zero-valued.
+    size_delta_usd: Option<U256>,
+    base_pnl_usd: i128,
+    is_long: bool,
+    /// bytes32Items `positionKey`, shared by every event of one position.
+    position_key: Option<[u8; 32]>,
 }
 
 /// Decode ABI-encoded EventLogData from raw log data hex string.
-/// Extracts sizeDeltaUsd, basePnlUsd, and isLong from the nested key-value structure.
-fn decode_event_log_data(data_hex: &str) -> Option<(U256, i128, bool)> {
+/// Extracts sizeDeltaUsd, basePnlUsd, isLong and positionKey from the nested
+/// key-value structure.
+fn decode_event_log_data(data_hex: &str) -> Option<PositionEventFields> {
     let data_hex = data_hex.trim_start_matches("0x");
     let data = hex::decode(data_hex).ok()?;
 
@@ -299,10 +418,10 @@ fn decode_event_log_data(data_hex: &str) -> Option<(U256, i128, bool)> {
     let decoded = <EventLogData as SolType>::abi_decode(&data, false).ok()?;
 
     // Extract from uintItems.items: sizeDeltaUsd
-    let mut size_delta_usd = U256::ZERO;
+    let mut size_delta_usd = None;
     for item in &decoded.uintItems.items {
         if item.key == "sizeDeltaUsd" {
-            size_delta_usd = item.value;
+            size_delta_usd = Some(item.value);
         }
     }
 
@@ -323,7 +442,15 @@ fn decode_event_log_data(data_hex: &str) -> Option<(U256, i128, bool)> {
         }
     }
 
-    Some((size_delta_usd, base_pnl_usd, is_long))
+    // Extract from bytes32Items.items: positionKey
+    let mut position_key = None;
+    for item in &decoded.bytes32Items.items {
+        if item.key == "positionKey" {
+            position_key = Some(item.value.0);
+        }
+    }
+
+    Some(PositionEventFields { size_delta_usd, base_pnl_usd, is_long, position_key })
 }
 
 /// Convert a signed 256-bit integer (two's complement in alloy_primitives::I256 form)
@@ -340,9 +467,11 @@ fn i256_to_i128(value: alloy_primitives::I256) -> i128 {
 
 /// Compute return_bps from basePnlUsd and sizeDeltaUsd.
 /// return_bps = (basePnlUsd * 10000) / sizeDeltaUsd
-fn compute_return_bps(base_pnl_usd: i128, size_delta_usd: U256) -> i64 {
+///
+/// Returns `None` when the result does not fit a `TradeReturn`.
+fn compute_return_bps(base_pnl_usd: i128, size_delta_usd: U256) -> Option<TradeReturn> {
     if size_delta_usd.is_zero() {
-        return 0;
+        return Some(TradeReturn::ZERO);
     }
 
     // Convert size_delta_usd to i128 (safe for typical position sizes)
@@ -350,11 +479,11 @@ fn compute_return_bps(base_pnl_usd: i128, size_delta_usd: U256) -> i64 {
         | ((size_delta_usd.as_limbs()[1] as i128) << 64);
 
     if size_i128 == 0 {
-        return 0;
+        return Some(TradeReturn::ZERO);
     }
 
-    let bps = (base_pnl_usd * 10000) / size_i128;
-    bps as i64
+    let bps = base_pnl_usd.checked_mul(10000)? / size_i128;
+    TradeReturn::new(i64::try_from(bps).ok()?).ok()
 }
 
 // ── GMX Trade Fetcher ─────────────────────────────────────
@@ -363,21 +492,34 @@ fn compute_return_bps(base_pnl_usd: i128, size_delta_usd: U256) -> i64 {
 ///
 /// Uses Arbitrum One RPC to query EventEmitter logs with topic filters:
 /// - topic0: EventLog1 or EventLog2 function selector
-/// - topic1: keccak256("PositionDecrease")
+/// - topic1: keccak256("PositionDecrease"), or keccak256("PositionIncrease")
+///   for the opens paired with each decrease
 /// - topic2: wallet address (zero-padded to 32 bytes)
 pub async fn fetch_gmx_trades(
     wallet: &str,
     rpc_url: Option<&str>,
-    from_block: Option<u64>,
+    mode: FetchMode,
     to_block: Option<u64>,
 ) -> Result<GmxFetchResult, String> {
     let rpc_url = rpc_url.unwrap_or(DEFAULT_ARBITRUM_RPC);
-    let client = reqwest::Client::new();
+    let transport = HttpTransport::new(reqwest::Client::new(), rpc_url);
+    fetch_gmx_trades_with(&transport, wallet, mode, to_block).await
+}
 
+/// `fetch_gmx_trades` over an arbitrary transport.
+///
+/// Any RPC error response fails the whole fetch, so a rejected query is
+/// never reported as a wallet without trades.
+pub async fn fetch_gmx_trades_with<T: RpcTransport>(
+    transport: &T,
+    wallet: &str,
+    mode: FetchMode,
+    to_block: Option<u64>,
+) -> Result<GmxFetchResult, String> {
     // Get current block number for defaults
-    let current_block = get_block_number(&client, rpc_url).await?;
+    let current_block = get_block_number(transport).await?;
     let to_block = to_block.unwrap_or(current_block);
-    let from_block = from_block.unwrap_or(to_block.saturating_sub(DEFAULT_LOOKBACK_BLOCKS));
+    let from_block = mode.from_block(to_block);
 
     // Event topic hashes
     let event_log1_selector = format!("0x{}", hex::encode(keccak256_str(
@@ -387,66 +529,27 @@ pub async fn fetch_gmx_trades(
         "EventLog2(address,string,string,(((string,address)[],(string,address[])[]),((string,uint256)[],(string,uint256[])[]),((string,int256)[],(string,int256[])[]),((string,bool)[],(string,bool[])[]),((string,bytes32)[],(string,bytes32[])[]),((string,bytes)[],(string,bytes[])[]),((string,string)[],(string,string[])[])))"
     )));
 
-    let position_decrease_hash = format!("0x{}", hex::encode(keccak256_str("PositionDecrease")));
+    let selectors = [event_log1_selector, event_log2_selector];
 
-    // Normalize wallet address to zero-padded 32-byte topic
-    let wallet_clean = wallet.trim_start_matches("0x").to_lowercase();
-    let wallet_topic = format!("0x000000000000000000000000{}", wallet_clean);
+    let position_decrease_hash = format!("0x{}", hex::encode(keccak256_str("PositionDecrease")));
+    let position_increase_hash = format!("0x{}", hex::encode(keccak256_str("PositionIncrease")));
 
     let mut all_trades = Vec::new();
+    let mut opens = Vec::new();
 
     // Fetch in chunks
     let mut current_from = from_block;
     while current_from <= to_block {
         let current_to = std::cmp::min(current_from + BLOCK_CHUNK - 1, to_block);
 
-        // Try EventLog1: topic0=EventLog1, topic1=PositionDecrease
-        let logs1 = get_logs(
-            &client,
-            rpc_url,
-            GMX_EVENT_EMITTER,
-            &[
-                Some(event_log1_selector.clone()),
-                Some(position_decrease_hash.clone()),
-            ],
-            current_from,
-            current_to,
-        )
-        .await
-        .unwrap_or_default();
-
-        // EventLog2: topic0=selector, topic1=eventNameHash, topic2=account
-        let logs2 = get_logs(
-            &client,
-            rpc_url,
-            GMX_EVENT_EMITTER,
-            &[
-                Some(event_log2_selector.clone()),
-                Some(position_decrease_hash.clone()),
-                Some(wallet_topic.clone()),
-            ],
-            current_from,
-            current_to,
-        )
-        .await
-        .unwrap_or_default();
-
-        // Process EventLog1 logs (filter by account in data)
-        for log in &logs1 {
-            let data_lower = log.data.to_lowercase();
-            if !data_lower.contains(&wallet_clean) {
-                continue;
-            }
-
-            if let Some(trade) = parse_log_entry(log) {
+        for log in &get_position_logs(transport, &selectors, &position_decrease_hash, wallet, current_from, current_to).await? {
+            if let Some(trade) = parse_log_entry(log)? {
                 all_trades.push(trade);
             }
         }
-
-        // Process EventLog2 logs (already filtered by topic2=account)
-        for log in &logs2 {
-            if let Some(trade) = parse_log_entry(log) {
-                all_trades.push(trade);
+        for log in &get_position_logs(transport, &selectors, &position_increase_hash, wallet, current_from, current_to).await? {
+            if let Some(open) = parse_increase_entry(log)? {
+                opens.push(open);
             }
         }
 
@@ -460,8 +563,9 @@ pub async fn fetch_gmx_trades(
 
     // Sort by block number
     all_trades.sort_by_key(|t| t.block_number);
+    pair_position_opens(&mut all_trades, opens);
 
-    let total_return_bps: i64 = all_trades.iter().map(|t| t.return_bps).sum();
+    let total_return_bps: i64 = all_trades.iter().map(|t| t.return_bps.bps()).sum();
 
     Ok(GmxFetchResult {
         wallet: wallet.to_string(),
@@ -472,8 +576,144 @@ pub async fn fetch_gmx_trades(
     })
 }
 
+/// `event_hash` logs of `wallet` in one block range.
+///
+/// EventLog1 (topic0=selector, topic1=eventNameHash) carries the account only
+/// in its data, so those logs are filtered by it; EventLog2 is filtered by
+/// topic2=account at the RPC.
+async fn get_position_logs<T: RpcTransport>(
+    transport: &T,
+    [event_log1_selector, event_log2_selector]: &[String; 2],
+    event_hash: &str,
+    wallet: &str,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<LogEntry>, FetchError> {
+    // Normalize wallet address to zero-padded 32-byte topic
+    let wallet_clean = wallet.trim_start_matches("0x").to_lowercase();
+    let wallet_topic = format!("0x000000000000000000000000{}", wallet_clean);
+
+    let logs1 = get_logs(
+        transport,
+        GMX_EVENT_EMITTER,
+        &[Some(event_log1_selector.clone()), Some(event_hash.to_string())],
+        from_block,
+        to_block,
+    )
+    .await?;
+
+    let logs2 = get_logs(
+        transport,
+        GMX_EVENT_EMITTER,
+        &[Some(event_log2_selector.clone()), Some(event_hash.to_string()), Some(wallet_topic)],
+        from_block,
+        to_block,
+    )
+    .await?;
+
+    Ok(logs1
+        .into_iter()
+        .filter(|log| log.data.to_lowercase().contains(&wallet_clean))
+        .chain(logs2)
+        .collect())
+}
+
+/// Pair each decrease with the earliest unpaired increase of the same
+/// position at or before it, and fill in the holding-period fields.
+///
+/// `trades` must be sorted by block. Pairing is first-in first-out per
+/// position key; decreases without a key or a preceding increase stay
+/// unpaired.
+fn pair_position_opens(trades: &mut [GmxFetchedTrade], mut opens: Vec<PositionOpen>) {
+    opens.sort_by_key(|open| open.block_number);
+    let mut by_key: std::collections::HashMap<String, std::collections::VecDeque<PositionOpen>> =
+        std::collections::HashMap::new();
+    for open in opens {
+        by_key.entry(open.position_key.clone()).or_default().push_back(open);
+    }
+
+    for trade in trades.iter_mut() {
+        let Some(queue) = trade.position_key.as_ref().and_then(|key| by_key.get_mut(key)) else {
+            continue;
+        };
+        if queue.front().is_none_or(|open| open.block_number > trade.close_block) {
+            continue;
+        }
+        let open = queue.pop_front().expect("front checked above");
+        trade.open_block = Some(open.block_number);
+        // base_pnl_usd is the decimal i128 parse_log_entry formatted
+        trade.holding_period_return_bps = trade
+            .base_pnl_usd
+            .parse::<i128>()
+            .ok()
+            .and_then(|pnl| compute_return_bps(pnl, open.size_delta_usd));
+    }
+}
+
 /// Parse a single log entry into a GmxFetchedTrade.
-fn parse_log_entry(log: &LogEntry) -> Option<GmxFetchedTrade> {
+///
+/// `Ok(None)` skips a log that is not a usable trade (undecodable, zero size,
+/// out-of-range return); a decoded log without `sizeDeltaUsd` is a
+/// `FetchError::SchemaMismatch`.
+fn parse_log_entry(log: &LogEntry) -> Result<Option<GmxFetchedTrade>, FetchError> {
+    let Some((block_number, fields)) = decode_log_entry(log) else {
+        return Ok(None);
+    };
+    let PositionEventFields { size_delta_usd, base_pnl_usd, is_long, position_key } = fields;
+    let size_delta_usd = size_delta_usd.ok_or_else(|| FetchError::SchemaMismatch {
+        tx_hash: log.transaction_hash.clone(),
+        missing_key: "sizeDeltaUsd",
+    })?;
+
+    if size_delta_usd.is_zero() {
+        return Ok(None); // Skip zero-size events
+    }
+
+    // Out-of-range returns are dropped rather than clamped.
+    let Some(return_bps) = compute_return_bps(base_pnl_usd, size_delta_usd) else {
+        return Ok(None);
+    };
+
+    Ok(Some(GmxFetchedTrade {
+        tx_hash: log.transaction_hash.clone(),
+        block_number,
+        size_delta_usd: format!("{}", size_delta_usd),
+        base_pnl_usd: format!("{}", base_pnl_usd),
+        is_long,
+        return_bps,
+        position_key: position_key.map(hex::encode),
+        open_block: None,
+        close_block: block_number,
+        holding_period_return_bps: None,
+    }))
+}
+
+/// Parse a PositionIncrease log entry.
+///
+/// `Ok(None)` skips an undecodable, zero-size or keyless log, since it cannot
+/// be paired; a decoded log without `sizeDeltaUsd` is a
+/// `FetchError::SchemaMismatch`, as for decreases.
+fn parse_increase_entry(log: &LogEntry) -> Result<Option<PositionOpen>, FetchError> {
+    let Some((block_number, fields)) = decode_log_entry(log) else {
+        return Ok(None);
+    };
+    let size_delta_usd = fields.size_delta_usd.ok_or_else(|| FetchError::SchemaMismatch {
+        tx_hash: log.transaction_hash.clone(),
+        missing_key: "sizeDeltaUsd",
+    })?;
+    let Some(position_key) = fields.position_key else {
+        return Ok(None);
+    };
+    if size_delta_usd.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(PositionOpen { block_number, position_key: hex::encode(position_key), size_delta_usd }))
+}
+
+/// Block number and decoded EventLogData fields of a log, `None` if either
+/// does not parse.
+fn decode_log_entry(log: &LogEntry) -> Option<(u64, PositionEventFields)> {
     let block_number = u64::from_str_radix(
         log.block_number.trim_start_matches("0x"),
         16,
@@ -496,28 +736,51 @@ fn parse_log_entry(log: &LogEntry) -> Option<GmxFetchedTrade> {
     }
 
     let event_data_bytes = &data[offset..];
-    let (size_delta_usd, base_pnl_usd, is_long) =
-        decode_event_log_data(&format!("0x{}", hex::encode(event_data_bytes)))?;
+    let fields = decode_event_log_data(&format!("0x{}", hex::encode(event_data_bytes)))?;
+    Some((block_number, fields))
+}
 
-    if size_delta_usd.is_zero() {
-        return None; // Skip zero-size events
-    }
+/// Convert fetched trades to return_bps vector for STARK proving.
+pub fn trades_to_returns_bps(trades: &[GmxFetchedTrade]) -> Vec<TradeReturn> {
+    trades.iter().map(|t| t.return_bps).collect()
+}
 
-    let return_bps = compute_return_bps(base_pnl_usd, size_delta_usd);
+// ── RPC Transport ─────────────────────────────────────────
 
-    Some(GmxFetchedTrade {
-        tx_hash: log.transaction_hash.clone(),
-        block_number,
-        size_delta_usd: format!("{}", size_delta_usd),
-        base_pnl_usd: format!("{}", base_pnl_usd),
-        is_long,
-        return_bps,
-    })
+/// JSON-RPC transport: posts a request body and returns the parsed response.
+///
+/// Abstracted so log and receipt fetching can be driven by a mock in tests.
+pub trait RpcTransport: Send + Sync + 'static {
+    fn request(
+        &self,
+        body: serde_json::Value,
+    ) -> impl std::future::Future<Output = Result<serde_json::Value, String>> + Send;
 }
 
-/// Convert fetched trades to return_bps vector for STARK proving.
-pub fn trades_to_returns_bps(trades: &[GmxFetchedTrade]) -> Vec<i64> {
-    trades.iter().map(|t| t.return_bps).collect()
+/// HTTP transport over a shared `reqwest::Client`.
+pub struct HttpTransport {
+    client: reqwest::Client,
+    rpc_url: String,
+}
+
+impl HttpTransport {
+    pub fn new(client: reqwest::Client, rpc_url: &str) -> Self {
+        HttpTransport { client, rpc_url: rpc_url.to_string() }
+    }
+}
+
+impl RpcTransport for HttpTransport {
+    async fn request(&self, body: serde_json::Value) -> Result<serde_json::Value, String> {
+        self.client
+            .post(&self.rpc_url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("RPC error: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))
+    }
 }
 
 // ── Receipt Proof Fetcher ─────────────────────────────────
@@ -533,7 +796,7 @@ impl SimpleMptTrie {
     }
 
     pub fn insert(&mut self, key: &[u8], value: Vec<u8>) {
-        let nibbles = bytes_to_nibbles(key);
+        let nibbles = stark_core::mpt::bytes_to_nibbles(key);
         self.nodes.push((nibbles, value));
     }
 
@@ -542,20 +805,68 @@ impl SimpleMptTrie {
     }
 }
 
-fn bytes_to_nibbles(data: &[u8]) -> Vec<u8> {
-    let mut nibbles = Vec::with_capacity(data.len() * 2);
-    for byte in data {
-        nibbles.push(byte >> 4);
-        nibbles.push(byte & 0x0f);
-    }
-    nibbles
-}
-
 /// Fetch receipt proof data for a transaction from an RPC endpoint.
 pub async fn fetch_receipt_proof(
     client: &reqwest::Client,
     rpc_url: &str,
     tx_hash: &str,
+) -> Result<ReceiptProofData, String> {
+    fetch_receipt_proof_with(&HttpTransport::new(client.clone(), rpc_url), tx_hash).await
+}
+
+/// Fetch receipt proofs for many transactions, at most `max_concurrent` in flight.
+///
+/// Results are returned in the order of `tx_hashes`.
+pub async fn fetch_receipt_proofs(
+    tx_hashes: &[String],
+    rpc_url: &str,
+    max_concurrent: usize,
+) -> Vec<Result<ReceiptProofData, String>> {
+    let transport = std::sync::Arc::new(HttpTransport::new(reqwest::Client::new(), rpc_url));
+    fetch_receipt_proofs_with(transport, tx_hashes, max_concurrent).await
+}
+
+/// Batch receipt-proof fetch over an arbitrary transport.
+pub async fn fetch_receipt_proofs_with<T: RpcTransport>(
+    transport: std::sync::Arc<T>,
+    tx_hashes: &[String],
+    max_concurrent: usize,
+) -> Vec<Result<ReceiptProofData, String>> {
+    let permits = std::sync::Arc::new(tokio::sync::Semaphore::new(max_concurrent.max(1)));
+    let mut tasks = tokio::task::JoinSet::new();
+    let mut slots = std::collections::HashMap::with_capacity(tx_hashes.len());
+
+    for (i, tx_hash) in tx_hashes.iter().enumerate() {
+        let transport = transport.clone();
+        let permits = permits.clone();
+        let tx_hash = tx_hash.clone();
+        let handle = tasks.spawn(async move {
+            match permits.acquire_owned().await {
+                Ok(_permit) => fetch_receipt_proof_with(transport.as_ref(), &tx_hash).await,
+                Err(e) => Err(format!("Semaphore closed: {}", e)),
+            }
+        });
+        slots.insert(handle.id(), i);
+    }
+
+    let mut results: Vec<Result<ReceiptProofData, String>> = (0..tx_hashes.len())
+        .map(|_| Err("Fetch task did not complete".to_string()))
+        .collect();
+    // A panicked or cancelled task reports its JoinError in its own slot
+    while let Some(joined) = tasks.join_next_with_id().await {
+        let (id, result) = match joined {
+            Ok((id, result)) => (id, result),
+            Err(e) => (e.id(), Err(format!("Fetch task failed: {}", e))),
+        };
+        results[slots[&id]] = result;
+    }
+    results
+}
+
+/// Fetch receipt proof data for a transaction over `transport`.
+pub async fn fetch_receipt_proof_with<T: RpcTransport>(
+    transport: &T,
+    tx_hash: &str,
 ) -> Result<ReceiptProofData, String> {
     // Step 1: Get transaction receipt
     let receipt_body = serde_json::json!({
@@ -565,17 +876,9 @@ pub async fn fetch_receipt_proof(
         "id": 1
     });
 
-    let receipt_resp: serde_json::Value = client
-        .post(rpc_url)
-        .json(&receipt_body)
-        .send()
-        .await
-        .map_err(|e| format!("RPC error: {}", e))?
-        .json()
-        .await
-        .map_err(|e| format!("JSON parse error: {}", e))?;
+    let receipt_resp = rpc_result(transport.request(receipt_body).await?, "eth_getTransactionReceipt")?;
 
-    let receipt = receipt_resp["result"]
+    let receipt = receipt_resp
         .as_object()
         .ok_or("No receipt found")?;
 
@@ -599,17 +902,9 @@ pub async fn fetch_receipt_proof(
         "id": 2
     });
 
-    let block_resp: serde_json::Value = client
-        .post(rpc_url)
-        .json(&block_body)
-        .send()
-        .await
-        .map_err(|e| format!("RPC error: {}", e))?
-        .json()
-        .await
-        .map_err(|e| format!("JSON parse error: {}", e))?;
+    let block_resp = rpc_result(transport.request(block_body).await?, "eth_getBlockByNumber")?;
 
-    let block = block_resp["result"]
+    let block = block_resp
         .as_object()
         .ok_or("No block found")?;
 
@@ -619,6 +914,12 @@ pub async fn fetch_receipt_proof(
     let block_hash = U256::from_str_radix(block_hash_hex.trim_start_matches("0x"), 16)
         .map_err(|e| format!("Invalid block hash: {}", e))?;
 
+    let tx_count = block
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .map(|txs| txs.len() as u64)
+        .ok_or("No transactions in block")?;
+
     let receipts_root_hex = block["receiptsRoot"]
         .as_str()
         .ok_or("No receiptsRoot")?;
@@ -631,46 +932,42 @@ pub async fn fetch_receipt_proof(
         return Err("receiptsRoot is not 32 bytes".to_string());
     }
 
-    // Step 3: Build receipt data for commitment
-    let status_hex = receipt.get("status")
-        .and_then(|v| v.as_str())
-        .unwrap_or("0x1");
-    let status = u64::from_str_radix(status_hex.trim_start_matches("0x"), 16).unwrap_or(1);
-
-    let cumulative_gas_hex = receipt.get("cumulativeGasUsed")
-        .and_then(|v| v.as_str())
-        .unwrap_or("0x0");
-    let cumulative_gas = u64::from_str_radix(cumulative_gas_hex.trim_start_matches("0x"), 16)
-        .unwrap_or(0);
-
-    let logs_bloom_hex = receipt.get("logsBloom")
-        .and_then(|v| v.as_str())
-        .unwrap_or("0x");
-    let logs_bloom = hex::decode(logs_bloom_hex.trim_start_matches("0x"))
-        .unwrap_or_default();
-
-    let mut receipt_data = Vec::new();
-    receipt_data.extend_from_slice(&status.to_be_bytes());
-    receipt_data.extend_from_slice(&cumulative_gas.to_be_bytes());
-    receipt_data.extend_from_slice(&logs_bloom);
-
-    // Include logs data for stronger binding
-    if let Some(logs) = receipt.get("logs").and_then(|v| v.as_array()) {
-        for log in logs {
-            if let Some(data) = log.get("data").and_then(|v| v.as_str()) {
-                let log_bytes = hex::decode(data.trim_start_matches("0x")).unwrap_or_default();
-                receipt_data.extend_from_slice(&log_bytes);
-            }
-            if let Some(topics) = log.get("topics").and_then(|v| v.as_array()) {
-                for topic in topics {
-                    if let Some(t) = topic.as_str() {
-                        let topic_bytes = hex::decode(t.trim_start_matches("0x")).unwrap_or_default();
-                        receipt_data.extend_from_slice(&topic_bytes);
-                    }
-                }
-            }
-        }
-    }
+    // Step 3: Re-encode the receipt exactly as it sits in the receipt trie
+    // Pre-EIP-2718 nodes omit "type"; those receipts are legacy
+    let tx_type = match receipt.get("type") {
+        Some(_) => hex_u64_field(receipt, "type")?,
+        None => 0,
+    };
+    let tx_type = u8::try_from(tx_type).map_err(|_| format!("Invalid type: {}", tx_type))?;
+    let status = hex_u64_field(receipt, "status")?;
+    let cumulative_gas = hex_u64_field(receipt, "cumulativeGasUsed")?;
+    let logs_bloom: [u8; 256] = hex_bytes_field(receipt, "logsBloom")?;
+
+    let logs = receipt
+        .get("logs")
+        .and_then(|v| v.as_array())
+        .ok_or("No logs")?
+        .iter()
+        .map(|log| {
+            let log = log.as_object().ok_or("Invalid log entry")?;
+            let topics = log
+                .get("topics")
+                .and_then(|v| v.as_array())
+                .ok_or("No log topics")?
+                .iter()
+                .map(|t| hex_to_array(t.as_str().ok_or("Invalid log topic")?, "log topic"))
+                .collect::<Result<Vec<[u8; 32]>, String>>()?;
+            let data = log.get("data").and_then(|v| v.as_str()).ok_or("No log data")?;
+            Ok(ReceiptLog {
+                address: hex_bytes_field(log, "address")?,
+                topics,
+                data: hex::decode(data.trim_start_matches("0x"))
+                    .map_err(|e| format!("Invalid log data hex: {}", e))?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let receipt_rlp = rlp_encode_receipt(tx_type, status, cumulative_gas, &logs_bloom, &logs);
 
     let receipt_key = rlp_encode_tx_index(tx_index);
 
@@ -680,17 +977,36 @@ pub async fn fetch_receipt_proof(
         receipts_root,
         receipt_proof_nodes: Vec::new(), // Simplified for hackathon
         receipt_key,
-        receipt_rlp: receipt_data,
+        tx_count,
+        receipt_rlp,
     })
 }
 
+/// Parse a required `0x`-prefixed quantity field of a JSON-RPC object.
+fn hex_u64_field(obj: &serde_json::Map<String, serde_json::Value>, name: &str) -> Result<u64, String> {
+    let hex_str = obj.get(name).and_then(|v| v.as_str()).ok_or(format!("No {}", name))?;
+    u64::from_str_radix(hex_str.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Invalid {}: {}", name, e))
+}
+
+/// Parse a required fixed-length `0x`-prefixed data field of a JSON-RPC object.
+fn hex_bytes_field<const N: usize>(
+    obj: &serde_json::Map<String, serde_json::Value>,
+    name: &str,
+) -> Result<[u8; N], String> {
+    let hex_str = obj.get(name).and_then(|v| v.as_str()).ok_or(format!("No {}", name))?;
+    hex_to_array(hex_str, name)
+}
+
+fn hex_to_array<const N: usize>(hex_str: &str, name: &str) -> Result<[u8; N], String> {
+    let bytes = hex::decode(hex_str.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid {} hex: {}", name, e))?;
+    bytes.try_into().map_err(|_| format!("{} is not {} bytes", name, N))
+}
+
 /// Compute the dataset commitment from fetched receipt proof data.
 pub fn commitment_from_proof(proof: &ReceiptProofData) -> U256 {
-    compute_dataset_commitment(
-        proof.block_hash,
-        &proof.receipts_root,
-        &proof.receipt_rlp,
-    )
+    ReceiptDerived(proof).compute()
 }
 
 #[cfg(test)]
@@ -703,7 +1019,7 @@ mod tests {
         let pnl: i128 = 500;
         let size = U256::from(10_000u64);
         let bps = compute_return_bps(pnl, size);
-        assert_eq!(bps, 500);
+        assert_eq!(bps, Some(TradeReturn::from(500)));
     }
 
     #[test]
@@ -712,13 +1028,24 @@ mod tests {
         let pnl: i128 = -200;
         let size = U256::from(10_000u64);
         let bps = compute_return_bps(pnl, size);
-        assert_eq!(bps, -200);
+        assert_eq!(bps, Some(TradeReturn::from(-200)));
+    }
+
+    #[test]
+    fn test_compute_return_bps_out_of_range() {
+        // 2^31 + 1 bps: one step past the TradeReturn bound
+        let size = U256::from(10_000u64);
+        assert_eq!(compute_return_bps(1 << 31, size), Some(TradeReturn::new(1 << 31).unwrap()));
+        assert_eq!(compute_return_bps((1 << 31) + 1, size), None);
+        assert_eq!(compute_return_bps(-(1 << 31) - 1, size), None);
+        // PnL * 10000 overflowing i128 is rejected, not wrapped
+        assert_eq!(compute_return_bps(i128::MAX / 2, size), None);
     }
 
     #[test]
     fn test_compute_return_bps_zero_size() {
         let bps = compute_return_bps(100, U256::ZERO);
-        assert_eq!(bps, 0);
+        assert_eq!(bps, Some(TradeReturn::ZERO));
     }
 
     #[test]
@@ -751,7 +1078,11 @@ mod tests {
                 size_delta_usd: "1000".into(),
                 base_pnl_usd: "50".into(),
                 is_long: true,
-                return_bps: 500,
+                return_bps: 500.into(),
+                position_key: None,
+                open_block: None,
+                close_block: 100,
+                holding_period_return_bps: None,
             },
             GmxFetchedTrade {
                 tx_hash: "0x2".into(),
@@ -759,11 +1090,15 @@ mod tests {
                 size_delta_usd: "2000".into(),
                 base_pnl_usd: "-100".into(),
                 is_long: false,
-                return_bps: -200,
+                return_bps: (-200).into(),
+                position_key: None,
+                open_block: None,
+                close_block: 200,
+                holding_period_return_bps: None,
             },
         ];
         let bps = trades_to_returns_bps(&trades);
-        assert_eq!(bps, vec![500, -200]);
+        assert_eq!(bps, vec![TradeReturn::from(500), TradeReturn::from(-200)]);
     }
 
     #[test]
@@ -774,6 +1109,7 @@ mod tests {
             receipts_root: [0xab; 32],
             receipt_proof_nodes: Vec::new(),
             receipt_key: rlp_encode_tx_index(0),
+            tx_count: 1,
             receipt_rlp: b"test_receipt".to_vec(),
         };
 
@@ -781,4 +1117,290 @@ mod tests {
         let c2 = commitment_from_proof(&proof);
         assert_eq!(c1, c2);
     }
+
+    /// Serves canned receipts/blocks and records peak request concurrency.
+    struct MockTransport {
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+    }
+
+    impl RpcTransport for MockTransport {
+        async fn request(&self, body: serde_json::Value) -> Result<serde_json::Value, String> {
+            use std::sync::atomic::Ordering;
+
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let param = body["params"][0].as_str().unwrap_or_default().to_string();
+            let result = match body["method"].as_str() {
+                // tx hash "0x<i>" is the i-th transaction of block 0x10
+                Some("eth_getTransactionReceipt") => serde_json::json!({
+                    "blockNumber": "0x10",
+                    "transactionIndex": param,
+                    "type": "0x2",
+                    "status": "0x1",
+                    "cumulativeGasUsed": "0x5208",
+                    "logsBloom": format!("0x{}", "00".repeat(256)),
+                    "logs": [{
+                        "address": format!("0x{}", "ab".repeat(20)),
+                        "topics": [format!("0x{}", "cd".repeat(32))],
+                        "data": "0x0102",
+                    }],
+                }),
+                Some("eth_getBlockByNumber") => serde_json::json!({
+                    "hash": format!("0x{}", "ab".repeat(32)),
+                    "transactions": ["0x0", "0x1", "0x2", "0x3", "0x4", "0x5", "0x6", "0x7"],
+                    "receiptsRoot": format!("0x{}", "11".repeat(32)),
+                }),
+                _ => return Err("unexpected method".into()),
+            };
+            Ok(serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_receipt_proofs_bounded_and_ordered() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let transport = std::sync::Arc::new(MockTransport {
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        });
+        let tx_hashes: Vec<String> = (0..8).map(|i| format!("0x{:x}", i)).collect();
+
+        let results = fetch_receipt_proofs_with(transport.clone(), &tx_hashes, 3).await;
+
+        assert_eq!(results.len(), tx_hashes.len());
+        for (i, result) in results.iter().enumerate() {
+            let proof = result.as_ref().expect("receipt proof should be fetched");
+            assert_eq!(proof.receipt_key, rlp_encode_tx_index(i as u64), "result {} out of order", i);
+            assert_eq!(proof.block_number, 0x10);
+            assert_eq!(proof.tx_count, 8);
+        }
+
+        let log = ReceiptLog { address: [0xab; 20], topics: vec![[0xcd; 32]], data: vec![1, 2] };
+        let expected_rlp = rlp_encode_receipt(2, 1, 0x5208, &[0u8; 256], &[log]);
+        assert!(results.iter().all(|r| r.as_ref().unwrap().receipt_rlp == expected_rlp));
+
+        // Each fetch is sequential internally, so in-flight requests == in-flight fetches
+        let peak = transport.max_in_flight.load(Ordering::SeqCst);
+        assert!(peak <= 3, "concurrency cap exceeded: {}", peak);
+        assert!(peak > 1, "fetches did not overlap");
+    }
+
+    /// `MockTransport` that panics while fetching tx hash `0x2`.
+    struct PanickingTransport(MockTransport);
+
+    impl RpcTransport for PanickingTransport {
+        async fn request(&self, body: serde_json::Value) -> Result<serde_json::Value, String> {
+            assert_ne!(body["params"][0], "0x2", "transport panicked");
+            self.0.request(body).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_receipt_proofs_reports_panicked_task() {
+        use std::sync::atomic::AtomicUsize;
+
+        let transport = std::sync::Arc::new(PanickingTransport(MockTransport {
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        }));
+        let tx_hashes: Vec<String> = (0..4).map(|i| format!("0x{:x}", i)).collect();
+
+        let results = fetch_receipt_proofs_with(transport, &tx_hashes, 2).await;
+
+        // The panic lands in its own slot, carrying the JoinError
+        let Err(err) = &results[2] else { panic!("panicked fetch should fail") };
+        assert!(err.starts_with("Fetch task failed") && err.contains("panic"), "{}", err);
+        for i in [0, 1, 3] {
+            assert_eq!(results[i].as_ref().unwrap().receipt_key, rlp_encode_tx_index(i as u64));
+        }
+    }
+
+    /// A PositionDecrease log whose EventLogData carries `uint_keys` (all
+    /// 1000 USD) and basePnlUsd = 50 USD.
+    fn position_decrease_log(uint_keys: &[&str]) -> LogEntry {
+        position_log(uint_keys, None, 0x10)
+    }
+
+    /// A position event log at `block`, carrying `uint_keys` (all 1000 USD),
+    /// basePnlUsd = 50 USD and, if given, a positionKey.
+    fn position_log(uint_keys: &[&str], position_key: Option<[u8; 32]>, block: u64) -> LogEntry {
+        use alloy_sol_types::SolType;
+
+        let usd = U256::from(10u64).pow(U256::from(30u64));
+        let event = EventLogData {
+            uintItems: UintItems {
+                items: uint_keys
+                    .iter()
+                    .map(|key| UintKeyValue { key: key.to_string(), value: usd * U256::from(1000u64) })
+                    .collect(),
+                arrayItems: vec![],
+            },
+            intItems: IntItems {
+                items: vec![IntKeyValue {
+                    key: "basePnlUsd".to_string(),
+                    value: alloy_primitives::I256::from_raw(usd * U256::from(50u64)),
+                }],
+                arrayItems: vec![],
+            },
+            addressItems: AddressItems { items: vec![], arrayItems: vec![] },
+            boolItems: BoolItems { items: vec![], arrayItems: vec![] },
+            bytes32Items: Bytes32Items {
+                items: position_key
+                    .map(|key| Bytes32KeyValue { key: "positionKey".to_string(), value: key.into() })
+                    .into_iter()
+                    .collect(),
+                arrayItems: vec![],
+            },
+            bytesItems: BytesItems { items: vec![], arrayItems: vec![] },
+            stringItems: StringItems { items: vec![], arrayItems: vec![] },
+        };
+
+        // Three head words, then the offset of the EventLogData parameter
+        let mut data = vec![0u8; 128];
+        data[96..128].copy_from_slice(&U256::from(128u64).to_be_bytes::<32>());
+        data.extend(<EventLogData as SolType>::abi_encode(&event));
+        LogEntry {
+            block_number: format!("0x{:x}", block),
+            data: format!("0x{}", hex::encode(data)),
+            topics: vec![],
+            transaction_hash: "0xabc".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_log_entry_schema_mismatch() {
+        let trade = parse_log_entry(&position_decrease_log(&["sizeInUsd", "sizeDeltaUsd"]))
+            .unwrap()
+            .expect("trade decodes");
+        assert_eq!(trade.return_bps, TradeReturn::from(500));
+
+        // A well-formed EventLogData whose keys were renamed is not "no trade"
+        let err = parse_log_entry(&position_decrease_log(&["sizeInUsd", "sizeDeltaUsdV3"])).unwrap_err();
+        assert_eq!(
+            err,
+            FetchError::SchemaMismatch { tx_hash: "0xabc".to_string(), missing_key: "sizeDeltaUsd" }
+        );
+        assert!(err.to_string().contains("schema mismatch"));
+    }
+
+    #[test]
+    fn test_pair_position_increase_with_decrease() {
+        let key = [0x42; 32];
+        // Increase of 2000 USD, of which the decrease closes 1000 USD
+        let mut open = parse_increase_entry(&position_log(&["sizeDeltaUsd"], Some(key), 1_000))
+            .unwrap()
+            .expect("increase decodes");
+        open.size_delta_usd *= U256::from(2u64);
+        // A later increase of the same position is left for a later decrease
+        let late_open = parse_increase_entry(&position_log(&["sizeDeltaUsd"], Some(key), 9_000))
+            .unwrap()
+            .expect("increase decodes");
+
+        let mut trades = vec![
+            parse_log_entry(&position_log(&["sizeDeltaUsd"], Some(key), 5_000)).unwrap().unwrap(),
+            parse_log_entry(&position_log(&["sizeDeltaUsd"], Some([0x07; 32]), 6_000)).unwrap().unwrap(),
+        ];
+        pair_position_opens(&mut trades, vec![late_open, open]);
+
+        assert_eq!(trades[0].position_key.as_deref(), Some(hex::encode(key).as_str()));
+        assert_eq!(trades[0].open_block, Some(1_000));
+        assert_eq!(trades[0].close_block, 5_000);
+        assert_eq!(trades[0].holding_period_blocks(), Some(4_000));
+        // 50 USD on the 1000 USD decrease, 50 USD on the 2000 USD opened
+        assert_eq!(trades[0].return_bps, TradeReturn::from(500));
+        assert_eq!(trades[0].holding_period_return_bps, Some(TradeReturn::from(250)));
+
+        // No increase for this key, so nothing to pair with
+        assert_eq!(trades[1].open_block, None);
+        assert_eq!(trades[1].holding_period_return_bps, None);
+    }
+
+    /// Answers `eth_blockNumber`, and every `eth_getLogs` with a JSON-RPC error.
+    struct RejectingLogsTransport;
+
+    impl RpcTransport for RejectingLogsTransport {
+        async fn request(&self, body: serde_json::Value) -> Result<serde_json::Value, String> {
+            match body["method"].as_str() {
+                Some("eth_blockNumber") => Ok(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "0x64" })),
+                Some("eth_getLogs") => Ok(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": { "code": -32005, "message": "query returned more than 10000 results" },
+                })),
+                _ => Err("unexpected method".into()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_rpc_result_distinguishes_error_from_empty() {
+        let empty = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": [] });
+        assert_eq!(rpc_result(empty, "eth_getLogs"), Ok(serde_json::json!([])));
+
+        let error = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32000, "message": "header not found" },
+        });
+        assert_eq!(
+            rpc_result(error, "eth_getLogs"),
+            Err(FetchError::Rpc {
+                method: "eth_getLogs".to_string(),
+                error: JsonRpcError { code: -32000, message: "header not found".to_string() },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_gmx_trades_surfaces_rpc_error() {
+        let wallet = format!("0x{}", "12".repeat(20));
+        let err = fetch_gmx_trades_with(&RejectingLogsTransport, &wallet, FetchMode::SinceBlock(1), Some(10))
+            .await
+            .expect_err("an RPC error must not read as zero trades");
+        assert_eq!(err, "eth_getLogs failed with RPC error -32005: query returned more than 10000 results");
+    }
+
+    /// At block 0x64 with no logs; records the `fromBlock` of every
+    /// `eth_getLogs` query.
+    #[derive(Default)]
+    struct RecordingTransport {
+        log_ranges_from: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl RpcTransport for RecordingTransport {
+        async fn request(&self, body: serde_json::Value) -> Result<serde_json::Value, String> {
+            match body["method"].as_str() {
+                Some("eth_blockNumber") => Ok(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "0x64" })),
+                Some("eth_getLogs") => {
+                    let from = body["params"][0]["fromBlock"].as_str().unwrap_or_default().to_string();
+                    self.log_ranges_from.lock().unwrap().push(from);
+                    Ok(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": [] }))
+                }
+                _ => Err("unexpected method".into()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_mode_sets_from_block() {
+        let wallet = format!("0x{}", "12".repeat(20));
+        let transport = RecordingTransport::default();
+        let result = fetch_gmx_trades_with(&transport, &wallet, FetchMode::SinceBlock(0x50), None)
+            .await
+            .unwrap();
+        assert_eq!((result.from_block, result.to_block), (0x50, 0x64));
+        // Decrease and increase logs, EventLog1 and EventLog2 each
+        assert_eq!(*transport.log_ranges_from.lock().unwrap(), vec!["0x50"; 4]);
+
+        // The default still spans the full lookback, clamped at genesis
+        assert_eq!(FetchMode::default(), FetchMode::Lookback(DEFAULT_LOOKBACK_BLOCKS));
+        assert_eq!(FetchMode::default().from_block(30_000_000), 30_000_000 - DEFAULT_LOOKBACK_BLOCKS);
+        assert_eq!(FetchMode::default().from_block(0x64), 0);
+        assert_eq!(FetchMode::Lookback(0x10).from_block(0x64), 0x54);
+    }
 }
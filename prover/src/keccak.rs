@@ -1,49 +1,17 @@
 //! Keccak256 hash for the prover
 //!
 //! Produces identical output to the
-//! on-chain verifier's `keccak_hash_two` given the same field element inputs.
-//!
-//! Encoding contract (must match verifier exactly):
-//!   1. Each U256 value is serialized as 32-byte **big-endian**.
-//!   2. Two 32-byte chunks are concatenated into a 64-byte buffer.
-//!   3. keccak256 is applied to the 64-byte buffer.
-//!   4. The 32-byte output is interpreted as a big-endian U256.
-//!
-//! Note: The output is NOT reduced mod BN254_PRIME here because `U256::from_be_bytes`
-//! returns a full 256-bit value. Callers that need a field element should reduce
-//! as needed (the verifier's `Fp::from_u256` handles this via Montgomery conversion).
-//! For the prover, keccak output is used directly as U256 in Merkle trees and
-//! Fiat-Shamir channels — matching the verifier's behavior since `Fp::from_u256`
-//! followed by `Fp::to_u256` on a value < 2^256 applies mod BN254_PRIME.
+//! on-chain verifier's `keccak_hash_two` given the same field element inputs:
+//! both use the encoding in `stark_core::keccak` (big-endian 32-byte words,
+//! concatenated, hashed, digest reduced mod BN254_PRIME).
 
 use alloy_primitives::U256;
-use tiny_keccak::{Hasher, Keccak};
-
-use crate::field::BN254_PRIME;
-
-/// Keccak256 hash of a byte slice.
-fn keccak256(data: &[u8]) -> [u8; 32] {
-    let mut hasher = Keccak::v256();
-    let mut output = [0u8; 32];
-    hasher.update(data);
-    hasher.finalize(&mut output);
-    output
-}
 
 /// Hash two U256 field elements using keccak256.
-///
-/// Encoding: big-endian 32 bytes per element, concatenated, hashed, reduced mod BN254.
-/// This MUST produce identical output to the on-chain verifier's `keccak_hash_two`.
-pub fn keccak_hash_two(a: U256, b: U256) -> U256 {
-    let mut buf = [0u8; 64];
-    buf[..32].copy_from_slice(&a.to_be_bytes::<32>());
-    buf[32..].copy_from_slice(&b.to_be_bytes::<32>());
-    let hash = keccak256(&buf);
-    let raw = U256::from_be_bytes(hash);
-    // Full reduction mod BN254 prime using mul_mod identity: x mod p = x * 1 mod p
-    // This matches the verifier's Fp::from_u256 which applies Montgomery conversion (mod p).
-    raw.mul_mod(U256::from(1u64), BN254_PRIME)
-}
+pub use stark_core::keccak::keccak_hash_two;
+
+/// `keccak_hash_two` under a one-byte domain tag, and the tags in use.
+pub use stark_core::keccak::{keccak_hash_two_tagged, TAG_CHANNEL_COMMIT, TAG_CHANNEL_DRAW, TAG_MERKLE_NODE};
 
 /// Hash a single element: keccak_hash_two(a, 0).
 pub fn keccak_hash_one(a: U256) -> U256 {
@@ -53,6 +21,8 @@ pub fn keccak_hash_one(a: U256) -> U256 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::field::BN254_PRIME;
+    use stark_core::keccak::keccak256;
 
     // =====================================================================
     // Cross-validation test vectors — must match verifier's keccak_hash_two
@@ -142,6 +112,44 @@ mod tests {
         assert_ne!(h2, U256::ZERO);
     }
 
+    /// Tagged vectors, pinned on both sides: the verifier's
+    /// `test_keccak_tagged_cross_validate` expects the same digests.
+    #[test]
+    fn test_keccak_tagged_cross_validate() {
+        let (a, b) = (U256::from(1u64), U256::from(2u64));
+        for (tag, expected) in TAGGED_ONE_TWO {
+            assert_eq!(keccak_hash_two_tagged(tag, a, b), U256::from_str_radix(expected, 16).unwrap(), "tag {}", tag);
+        }
+
+        // Manually constructed preimage: tag byte, then the two words
+        let mut buf = [0u8; 65];
+        buf[0] = TAG_MERKLE_NODE;
+        buf[32] = 1;
+        buf[64] = 2;
+        let expected = U256::from_be_bytes(keccak256(&buf)).reduce_mod(BN254_PRIME);
+        assert_eq!(keccak_hash_two_tagged(TAG_MERKLE_NODE, a, b), expected);
+    }
+
+    /// The same pair under different tags (or none) hashes differently.
+    #[test]
+    fn test_keccak_tags_differ() {
+        let (a, b) = (U256::from(7u64), U256::from(9u64));
+        let node = keccak_hash_two_tagged(TAG_MERKLE_NODE, a, b);
+        let commit = keccak_hash_two_tagged(TAG_CHANNEL_COMMIT, a, b);
+        let draw = keccak_hash_two_tagged(TAG_CHANNEL_DRAW, a, b);
+        assert_ne!(node, commit);
+        assert_ne!(node, draw);
+        assert_ne!(commit, draw);
+        assert_ne!(node, keccak_hash_two(a, b));
+    }
+
+    /// keccak_hash_two_tagged(tag, 1, 2) for each tag, as hex.
+    const TAGGED_ONE_TWO: [(u8, &str); 3] = [
+        (TAG_MERKLE_NODE, "24b0b80b6d5d449b8bb22be5c97de764f49121eacb0f202f5e8a6d54b706e674"),
+        (TAG_CHANNEL_COMMIT, "1f45aac4afbb2ef5b88e133eac3a50d4530ca4334591c40b860475ba688e045b"),
+        (TAG_CHANNEL_DRAW, "9e67384c1b91f16f8b39611930c4a54475b247e1da760f49537f8e0c5e34cd"),
+    ];
+
     /// Field range: 100 consecutive hashes all < BN254_PRIME
     #[test]
     fn test_keccak_output_in_field() {
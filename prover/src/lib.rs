@@ -1,19 +1,25 @@
 //! STARK Prover Library
 //!
-//! Generates STARK proofs for Sharpe ratio verification.
+//! Generates STARK proofs for Sharpe (and Sortino) ratio verification.
 //! Can be used as a library (native or WASM) or via the CLI binary.
 
+mod air;
 pub mod channel;
 pub mod commit;
+pub mod deep;
 pub mod domain;
 pub mod field;
 pub mod fri;
 pub mod keccak;
 pub mod mock_data;
+pub mod padding;
+pub mod poseidon;
 pub mod proof;
 pub mod receipt_proof;
 pub mod sharpe_compose;
 pub mod sharpe_trace;
+pub mod sortino_compose;
+pub mod sortino_trace;
 
 #[cfg(feature = "cli")]
 pub mod gmx_fetcher;
@@ -21,18 +27,32 @@ pub mod gmx_fetcher;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
+#[cfg(feature = "debug-trees")]
+pub mod debug_trees;
+
 use alloy_primitives::U256;
 
-use crate::channel::Channel;
-use crate::commit::{commit_column, commit_trace_multi};
+use crate::air::define_prover_air;
+use crate::channel::{Channel, KeccakTranscript, PoseidonTranscript, ProofKind, TranscriptHash, TranscriptKind};
+use crate::commit::{commit_column, commit_trace_multi, MerkleScheme, MerkleTree};
+use crate::deep::{evaluate_deep_on_lde, num_deep_coeffs, DeepPoint};
 use crate::domain::{domain_generator, get_domain};
 use crate::field::BN254Field;
-use crate::fri::{fri_commit, fri_query_proofs};
-use crate::keccak::keccak_hash_two;
-use crate::mock_data::{GmxTradeRecord, SHARPE_SCALE};
-use crate::proof::SerializedProof;
-use crate::sharpe_compose::evaluate_sharpe_composition_on_lde;
+use crate::fri::{fri_commit_with_progress, fri_query_openings, fri_self_check, QueryOpening};
+use crate::mock_data::GmxTradeRecord;
+use crate::padding::{padding_warning, PaddingWarning};
+use crate::proof::{calldata_bytes, SerializedProof};
+use crate::receipt_proof::{bind_as_of_block, DatasetCommitment};
+use crate::sharpe_compose::{
+    compute_sharpe_composition_at_z, evaluate_sharpe_composition_on_lde, sharpe_constraint_violations,
+    split_composition, ConstraintViolation, MIN_SHARPE_SEGMENTS,
+};
 use crate::sharpe_trace::SharpeTrace;
+use crate::sortino_compose::{
+    compute_sortino_composition_at_z, evaluate_sortino_composition_on_lde, sortino_constraint_violations,
+    MIN_SORTINO_SEGMENTS,
+};
+use crate::sortino_trace::SortinoTrace;
 
 /// Progress stage during proof generation.
 pub struct ProveProgress {
@@ -41,6 +61,125 @@ pub struct ProveProgress {
     pub percent: u8,
 }
 
+/// Optional knobs for proof generation.
+///
+/// `Default` reproduces the plain `prove_sharpe` behaviour.
+#[derive(Clone, Debug, Default)]
+pub struct ProveOptions {
+    /// Per-proof salt absorbed into the transcript and into every committed
+    /// Merkle leaf (`keccak(salt, value)`), so proofs over overlapping data
+    /// cannot be correlated by their Merkle subtrees. Must be a canonical
+    /// field element (below p): the verifier rejects any other salt.
+    pub leaf_salt: Option<U256>,
+    /// Block the dataset is "as of". When set, it is appended as `pi[4]`,
+    /// absorbed into the Fiat-Shamir seed, and bound into the dataset
+    /// commitment column so verifiers can enforce a freshness window.
+    pub as_of_block: Option<u64>,
+    /// Hash driving the Fiat-Shamir transcript (Keccak by default). Poseidon
+    /// transcripts are cheaper to re-verify inside a recursive SNARK.
+    pub transcript: TranscriptKind,
+    /// Number of trace-length segments the composition polynomial is split
+    /// into, each committed separately. Raised to the AIR's minimum
+    /// (`MIN_SHARPE_SEGMENTS` / `MIN_SORTINO_SEGMENTS`), since the gated
+    /// constraints push the composition degree past the trace length.
+    pub composition_segments: usize,
+    /// Pairing scheme of the dataset-commitment tree behind `pi[3]`
+    /// (ordered by default; sorted for OpenZeppelin-style verifiers).
+    pub merkle_scheme: MerkleScheme,
+    /// Warn (a `"padding"` progress event) when more than this fraction of
+    /// the power-of-2 trace is padding rows. `None` never warns.
+    pub warn_padding_ratio: Option<f32>,
+    /// Make the padding warning an error in `ProverState::try_new` and the
+    /// `try_prove_*` entrypoints.
+    pub strict_padding: bool,
+}
+
+impl ProveOptions {
+    /// Effective composition segment count (at least 1).
+    pub fn num_composition_segments(&self) -> usize {
+        self.composition_segments.max(1)
+    }
+}
+
+/// LDE blowup factor used by the prover (the verifier assumes the same).
+pub const BLOWUP: u32 = 4;
+
+/// Upper bound on queries per proof (size of the verifier's query buffer).
+pub const MAX_NUM_QUERIES: usize = 64;
+
+/// Upper bound on composition segments: one per LDE coset off the trace domain.
+pub const MAX_COMPOSITION_SEGMENTS: usize = BLOWUP as usize - 1;
+
+/// Version of the calldata layout (see `SerializedProof::new_sharpe`).
+pub const PROOF_FORMAT_VERSION: u64 = 1;
+
+/// Protocol hash carried in `query_metadata[3]` of every proof; must equal
+/// the verifier's `VERIFIER_PROTOCOL_HASH`.
+///
+/// keccak256 over 32-byte big-endian words: [PROOF_FORMAT_VERSION, BN254
+/// modulus, GENERATOR_2_28, BLOWUP, Sharpe (columns, transition, boundary
+/// constraints), Sortino (same three), keccak_hash_two(1, 2),
+/// keccak_hash_two_tagged(tag, 1, 2) for the Merkle node, channel commit and
+/// channel draw tags, poseidon_hash_two(1, 2)]. Changing any of them changes
+/// the hash.
+pub const PROTOCOL_HASH: U256 = U256::from_limbs([
+    0xfefa75555e8d5969,
+    0xe7556243855adcdf,
+    0xf6787155d7229c6b,
+    0x46526ea6a38c6fad,
+]);
+
+/// Conjectured FRI soundness in bits: each query contributes log2(blowup).
+pub fn security_bits(num_queries: usize, blowup: u32) -> u32 {
+    num_queries as u32 * blowup.trailing_zeros()
+}
+
+/// Calldata size of a Sharpe proof over `num_trades` trades, without proving.
+///
+/// Mirrors the layout produced by `prove_sharpe_with_options`, so it matches
+/// `SerializedProof::calldata_size()` exactly.
+pub fn estimate_calldata_size(num_trades: usize, num_queries: usize, options: &ProveOptions) -> usize {
+    let log_trace_len = num_trades.next_power_of_two().trailing_zeros() as usize;
+    let log_lde_size = log_trace_len + BLOWUP.trailing_zeros() as usize;
+    let num_fri_layers = log_lde_size - 2;
+    let num_segments = options.num_composition_segments().max(MIN_SHARPE_SEGMENTS);
+
+    let public_inputs = 4 + options.as_of_block.is_some() as usize;
+    let commitments = 1 + num_segments + num_fri_layers;
+    let ood_values = num_deep_coeffs(sharpe_trace::NUM_COLUMNS, num_segments);
+    let fri_final_poly = 1 << (log_lde_size - num_fri_layers);
+    // Per query: FRI (fx, f(-x)) pairs, then the trace row + composition segments
+    let query_values = num_queries * (num_fri_layers * 2 + sharpe_trace::NUM_COLUMNS + num_segments);
+    // Per query: one path per FRI layer, then the trace and segment paths
+    let fri_path_len: usize = (0..num_fri_layers).map(|layer| log_lde_size - layer).sum();
+    let query_paths = num_queries * (fri_path_len + (1 + num_segments) * log_lde_size);
+    let query_metadata = 4 + num_queries + options.leaf_salt.is_some() as usize;
+
+    calldata_bytes(
+        public_inputs + commitments + ood_values + fri_final_poly
+            + query_values + query_paths + query_metadata,
+    )
+}
+
+/// Generate the most secure Sharpe proof whose calldata fits `max_calldata_bytes`.
+///
+/// The verifier fixes the blowup at 4 and has no proof-of-work grinding, so
+/// the query count is the only security knob: this picks the largest
+/// `num_queries` (up to `MAX_NUM_QUERIES`) whose estimated calldata fits.
+/// Returns `None` if even a single-query proof exceeds the budget.
+pub fn prove_sharpe_within_budget(
+    trades: &[GmxTradeRecord],
+    claimed_sharpe_sq_scaled: U256,
+    max_calldata_bytes: usize,
+) -> Option<SerializedProof> {
+    let options = ProveOptions::default();
+    let lde_size = trades.len().next_power_of_two() * BLOWUP as usize;
+    let num_queries = (1..=MAX_NUM_QUERIES.min(lde_size))
+        .rev()
+        .find(|&nq| estimate_calldata_size(trades.len(), nq, &options) <= max_calldata_bytes)?;
+    Some(prove_sharpe(trades, claimed_sharpe_sq_scaled, num_queries, None))
+}
+
 /// Horner's method: evaluate polynomial at a single point.
 /// O(n) with only mul/add — no inversions.
 fn eval_poly_at(coeffs: &[U256], x: U256) -> U256 {
@@ -52,15 +191,30 @@ fn eval_poly_at(coeffs: &[U256], x: U256) -> U256 {
 }
 
 /// Generate a STARK proof for Sharpe ratio verification.
+///
+/// `dataset_commitment` is any `DatasetCommitment`: a precomputed
+/// `Option<U256>`, or the derivation matching the on-chain entrypoint
+/// (`ReceiptDerived`, `ReceiptHashChain`).
 pub fn prove_sharpe(
     trades: &[GmxTradeRecord],
     claimed_sharpe_sq_scaled: U256,
     num_queries: usize,
-    dataset_commitment: Option<U256>,
+    dataset_commitment: impl DatasetCommitment,
 ) -> SerializedProof {
+    let dataset_commitment = Some(dataset_commitment.compute());
     prove_sharpe_with_progress(trades, claimed_sharpe_sq_scaled, num_queries, dataset_commitment, |_| {})
 }
 
+/// Public inputs `prove_sharpe` would embed for an honest claim over
+/// `trades`: [trade_count, total_return, sharpe_sq_scaled, merkle_root].
+///
+/// Builds the trace and its dataset-commitment tree only, with no LDE or FRI
+/// work, so a frontend can show the result before starting the proof.
+pub fn compute_sharpe_public_inputs(trades: &[GmxTradeRecord], dataset_commitment: impl DatasetCommitment) -> [U256; 4] {
+    let trace = SharpeTrace::generate(trades, Some(dataset_commitment.compute()));
+    trace.public_inputs(trace.compute_sharpe_sq_scaled())
+}
+
 /// Generate a STARK proof for Sharpe ratio verification with progress callbacks.
 pub fn prove_sharpe_with_progress(
     trades: &[GmxTradeRecord],
@@ -69,77 +223,448 @@ pub fn prove_sharpe_with_progress(
     dataset_commitment: Option<U256>,
     on_progress: impl Fn(ProveProgress),
 ) -> SerializedProof {
-    let blowup: u32 = 4;
+    prove_sharpe_with_options(
+        trades,
+        claimed_sharpe_sq_scaled,
+        num_queries,
+        dataset_commitment,
+        &ProveOptions::default(),
+        on_progress,
+    )
+}
+
+define_prover_air! {
+    air: Air::Sharpe,
+    trace: SharpeTrace,
+    min_segments: MIN_SHARPE_SEGMENTS,
+    trace_with_options: sharpe_trace,
+    from_trace: from_sharpe_trace,
+    /// Start a proof: generates the trace, nothing is committed yet. The
+    /// composition gets at least `MIN_SHARPE_SEGMENTS` segments.
+    new: new,
+    /// Generate a STARK proof for Sharpe ratio verification with explicit options.
+    prove_with_options: prove_sharpe_with_options,
+}
+
+/// `prove_sharpe`, but errors before any proving work if the claim does not
+/// match the trace's own `compute_sharpe_sq_scaled`. A wrong claim would
+/// otherwise cost a full proving run and yield a proof failing BC3.
+pub fn try_prove_sharpe(
+    trades: &[GmxTradeRecord],
+    claimed_sharpe_sq_scaled: U256,
+    num_queries: usize,
+    dataset_commitment: Option<U256>,
+) -> Result<SerializedProof, String> {
+    try_prove_sharpe_with_options(
+        trades,
+        claimed_sharpe_sq_scaled,
+        num_queries,
+        dataset_commitment,
+        &ProveOptions::default(),
+        |_| {},
+    )
+}
 
-    // Step 1: Generate Sharpe trace
+/// `prove_sharpe_with_options` with the claim check of `try_prove_sharpe`.
+pub fn try_prove_sharpe_with_options(
+    trades: &[GmxTradeRecord],
+    claimed_sharpe_sq_scaled: U256,
+    num_queries: usize,
+    dataset_commitment: Option<U256>,
+    options: &ProveOptions,
+    on_progress: impl Fn(ProveProgress),
+) -> Result<SerializedProof, String> {
+    let state = ProverState::try_new(trades, claimed_sharpe_sq_scaled, num_queries, dataset_commitment, options)?;
+    Ok(run_to_completion(state, &on_progress))
+}
+
+/// Generate a STARK proof for Sortino ratio verification.
+///
+/// Needs at least one losing trade (see `SortinoTrace::generate`).
+pub fn prove_sortino(
+    trades: &[GmxTradeRecord],
+    claimed_sortino_sq_scaled: U256,
+    num_queries: usize,
+    dataset_commitment: Option<U256>,
+) -> SerializedProof {
+    prove_sortino_with_options(
+        trades,
+        claimed_sortino_sq_scaled,
+        num_queries,
+        dataset_commitment,
+        &ProveOptions::default(),
+        |_| {},
+    )
+}
+
+define_prover_air! {
+    air: Air::Sortino,
+    trace: SortinoTrace,
+    min_segments: MIN_SORTINO_SEGMENTS,
+    trace_with_options: sortino_trace,
+    from_trace: from_sortino_trace,
+    /// Start a Sortino proof; the composition gets at least
+    /// `MIN_SORTINO_SEGMENTS` segments.
+    new: new_sortino,
+    /// Generate a STARK proof for Sortino ratio verification with explicit options.
+    ///
+    /// The composition is always split into at least `MIN_SORTINO_SEGMENTS`
+    /// segments, whatever `options.composition_segments` asks for.
+    prove_with_options: prove_sortino_with_options,
+}
+
+fn run_to_completion(mut state: ProverState, on_progress: &impl Fn(ProveProgress)) -> SerializedProof {
+    loop {
+        state = match step_with_progress(state, on_progress) {
+            StepResult::InProgress(next) => next,
+            StepResult::Done(proof) => return proof,
+        };
+    }
+}
+
+/// Checkpointed proof generation, advanced one stage at a time by
+/// `prove_sharpe_step`.
+///
+/// Stages: trace generated → trace committed → composition committed → done.
+/// The state owns everything later stages need (LDEs, Merkle trees and the
+/// Fiat-Shamir transcript position), so a caller such as the WASM bindings can
+/// yield to its event loop between steps without redoing work.
+pub struct ProverState {
+    num_queries: usize,
+    options: ProveOptions,
+    stage: ProverStage,
+}
+
+/// Outcome of a single `prove_sharpe_step`.
+pub enum StepResult {
+    InProgress(ProverState),
+    Done(SerializedProof),
+}
+
+enum ProverStage {
+    Traced(TracedColumns),
+    Committed(Box<CommittedTrace>),
+    Composed(Box<ComposedTrace>),
+}
+
+/// Constraint system a proof is generated for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Air {
+    Sharpe,
+    Sortino,
+}
+
+impl Air {
+    /// Kind framed into the Fiat-Shamir seed.
+    fn proof_kind(self) -> ProofKind {
+        match self {
+            Air::Sharpe => ProofKind::Sharpe,
+            Air::Sortino => ProofKind::Sortino,
+        }
+    }
+
+    /// Random coefficients drawn for the composition polynomial.
+    fn num_alphas(self) -> usize {
+        match self {
+            Air::Sharpe => sharpe_compose::NUM_ALPHAS,
+            Air::Sortino => sortino_compose::NUM_ALPHAS,
+        }
+    }
+
+    /// Constraints the raw trace columns violate.
+    fn constraint_violations(self, columns: &[Vec<U256>], air_inputs: &[U256; 4]) -> Vec<ConstraintViolation> {
+        let refs = column_refs(columns);
+        match self {
+            Air::Sharpe => sharpe_constraint_violations(as_array(&refs), air_inputs),
+            Air::Sortino => sortino_constraint_violations(as_array(&refs), air_inputs),
+        }
+    }
+}
+
+/// Output of trace generation: the trace columns in commitment order and the
+/// AIR's public inputs.
+struct TracedColumns {
+    air: Air,
+    columns: Vec<Vec<U256>>,
+    air_inputs: [U256; 4],
+    log_trace_len: u32,
+    trace_len: usize,
+    padding_warning: Option<PaddingWarning>,
+}
+
+/// Output of the commit stage: trace LDE, its tree, and the transcript after
+/// absorbing the trace commitment.
+struct CommittedTrace {
+    air: Air,
+    public_inputs: Vec<U256>,
+    air_inputs: [U256; 4],
+    log_trace_len: u32,
+    trace_len: usize,
+    log_lde_size: u32,
+    trace_coeffs: Vec<Vec<U256>>,
+    trace_ldes: Vec<Vec<U256>>,
+    trace_tree: MerkleTree,
+    transcript: (U256, u64),
+}
+
+/// Output of the compose stage: OOD values, committed composition segments
+/// and DEEP coefficients.
+struct ComposedTrace {
+    committed: CommittedTrace,
+    z: U256,
+    zg: U256,
+    trace_ood_evals: Vec<U256>,
+    trace_ood_evals_next: Vec<U256>,
+    composition_ldes: Vec<Vec<U256>>,
+    composition_trees: Vec<MerkleTree>,
+    composition_commitments: Vec<U256>,
+    composition_ood_evals: Vec<U256>,
+    deep_gammas: Vec<U256>,
+    transcript: (U256, u64),
+}
+
+impl ProverState {
+    /// Like `new`, but rejects a `claimed_sharpe_sq_scaled` that differs from
+    /// the trace's `compute_sharpe_sq_scaled` instead of proving it.
+    pub fn try_new(
+        trades: &[GmxTradeRecord],
+        claimed_sharpe_sq_scaled: U256,
+        num_queries: usize,
+        dataset_commitment: Option<U256>,
+        options: &ProveOptions,
+    ) -> Result<Self, String> {
+        if let Some(salt) = options.leaf_salt.filter(|salt| !leaf_salt_is_canonical(*salt)) {
+            return Err(format!("Leaf salt {:#x} is not below the field modulus", salt));
+        }
+        let (trace, options) = Self::sharpe_trace(trades, dataset_commitment, options);
+        if options.strict_padding {
+            if let Some(warning) = options.warn_padding_ratio.and_then(|r| padding_warning(trades.len(), r)) {
+                return Err(format!("Trace padding over threshold: {}", warning));
+            }
+        }
+        let actual = trace.compute_sharpe_sq_scaled();
+        if claimed_sharpe_sq_scaled != actual {
+            return Err(format!(
+                "Claimed sharpe_sq_scaled {} does not match the trace's {}",
+                claimed_sharpe_sq_scaled, actual
+            ));
+        }
+        Ok(Self::from_sharpe_trace(trace, claimed_sharpe_sq_scaled, num_queries, &options))
+    }
+
+    fn traced(traced: TracedColumns, num_queries: usize, options: &ProveOptions) -> Self {
+        assert!(
+            options.num_composition_segments() <= MAX_COMPOSITION_SEGMENTS,
+            "too many composition segments"
+        );
+        assert!(
+            options.leaf_salt.is_none_or(leaf_salt_is_canonical),
+            "leaf salt must be below the field modulus"
+        );
+        ProverState {
+            num_queries,
+            options: options.clone(),
+            stage: ProverStage::Traced(traced),
+        }
+    }
+
+    /// Debugging aid: every transition and boundary constraint the generated
+    /// trace violates, evaluated on the trace domain before any LDE (so a
+    /// proof that will not verify points at a constraint and row). `None`
+    /// once the trace has been committed.
+    pub fn constraint_violations(&self) -> Option<Vec<ConstraintViolation>> {
+        match &self.stage {
+            ProverStage::Traced(traced) => {
+                Some(traced.air.constraint_violations(&traced.columns, &traced.air_inputs))
+            }
+            _ => None,
+        }
+    }
+
+    /// The trade count's padding warning under `options.warn_padding_ratio`.
+    /// `None` once the trace has been committed.
+    pub fn padding_warning(&self) -> Option<&PaddingWarning> {
+        match &self.stage {
+            ProverStage::Traced(traced) => traced.padding_warning.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Name of the stage the next `prove_sharpe_step` will run.
+    pub fn next_stage(&self) -> &'static str {
+        match self.stage {
+            ProverStage::Traced(_) => "commit",
+            ProverStage::Committed(_) => "compose",
+            ProverStage::Composed(_) => "fri",
+        }
+    }
+}
+
+/// Whether `salt` is a canonical field element, as the verifier requires.
+fn leaf_salt_is_canonical(salt: U256) -> bool {
+    salt < crate::field::BN254_PRIME
+}
+
+/// Dataset commitment with `options.as_of_block` bound in, if set.
+fn bound_dataset_commitment(dataset_commitment: Option<U256>, options: &ProveOptions) -> Option<U256> {
+    match options.as_of_block {
+        Some(block) => Some(bind_as_of_block(dataset_commitment.unwrap_or(U256::ZERO), block)),
+        None => dataset_commitment,
+    }
+}
+
+/// Run the next proving stage.
+///
+/// Stepping a fresh `ProverState` until `Done` yields the same proof as
+/// `prove_sharpe_with_options`.
+pub fn prove_sharpe_step(state: ProverState) -> StepResult {
+    step_with_progress(state, &|_| {})
+}
+
+fn step_with_progress(state: ProverState, on_progress: &impl Fn(ProveProgress)) -> StepResult {
+    match state.options.transcript {
+        TranscriptKind::Keccak => step_over::<KeccakTranscript>(state, on_progress),
+        TranscriptKind::Poseidon => step_over::<PoseidonTranscript>(state, on_progress),
+    }
+}
+
+/// One proving stage over a fixed transcript hash `H`.
+fn step_over<H: TranscriptHash>(state: ProverState, on_progress: &impl Fn(ProveProgress)) -> StepResult {
+    let ProverState { num_queries, options, stage } = state;
+    let stage = match stage {
+        ProverStage::Traced(traced) => ProverStage::Committed(Box::new(
+            commit_trace_stage::<H>(traced, &options, on_progress),
+        )),
+        ProverStage::Committed(committed) => ProverStage::Composed(Box::new(
+            compose_stage::<H>(*committed, &options, on_progress),
+        )),
+        ProverStage::Composed(composed) => {
+            let proof = fri_stage::<H>(*composed, num_queries, &options, on_progress);
+            return StepResult::Done(
+                proof
+                    .with_transcript(options.transcript)
+                    .with_merkle_scheme(options.merkle_scheme),
+            );
+        }
+    };
+    StepResult::InProgress(ProverState { num_queries, options, stage })
+}
+
+/// Borrow each column as a slice.
+fn column_refs(columns: &[Vec<U256>]) -> Vec<&[U256]> {
+    columns.iter().map(Vec::as_slice).collect()
+}
+
+/// View per-column (or per-alpha) values as the fixed-size array an AIR expects.
+fn as_array<T, const N: usize>(values: &[T]) -> &[T; N] {
+    values.try_into().expect("value count does not match the AIR")
+}
+
+/// Zero-pad coefficients and FFT → LDE evaluations.
+fn lde_from_coeffs(coeffs: &[U256], log_lde_size: u32) -> Vec<U256> {
+    let mut padded = coeffs.to_vec();
+    padded.resize(1usize << log_lde_size, U256::ZERO);
+    domain::fft(&mut padded, log_lde_size);
+    padded
+}
+
+/// Steps 1-3: public inputs, trace LDE and trace commitment.
+fn commit_trace_stage<H: TranscriptHash>(
+    traced: TracedColumns,
+    options: &ProveOptions,
+    on_progress: &impl Fn(ProveProgress),
+) -> CommittedTrace {
+    let blowup = BLOWUP;
+    let leaf_salt = options.leaf_salt;
+    let TracedColumns { air, columns, air_inputs, log_trace_len, trace_len, padding_warning } = traced;
+
+    // Step 1: trace (generated by `ProverState::new` / `new_sortino`)
     on_progress(ProveProgress {
         stage: "trace",
-        detail: "Generating Sharpe ratio trace",
+        detail: match air {
+            Air::Sharpe => "Generating Sharpe ratio trace",
+            Air::Sortino => "Generating Sortino ratio trace",
+        },
         percent: 0,
     });
+    if padding_warning.is_some() {
+        on_progress(ProveProgress {
+            stage: "padding",
+            detail: "Trace is mostly padding: batch trades to a count just under a power of two",
+            percent: 0,
+        });
+    }
 
-    let trace = SharpeTrace::generate(trades, dataset_commitment);
-    let public_inputs = trace.public_inputs(claimed_sharpe_sq_scaled);
-    let log_trace_len = trace.log_len();
-    let trace_len = trace.len;
+    // The AIR sees the first four; pi[4] (as_of_block) only feeds the seed
+    let mut public_inputs = air_inputs.to_vec();
+    if let Some(block) = options.as_of_block {
+        public_inputs.push(U256::from(block));
+    }
 
-    // Step 2: Compute LDE (6 columns)
+    // Step 2: Compute LDE (7 or 8 columns)
     on_progress(ProveProgress {
         stage: "trace",
-        detail: "Computing Low Degree Extension (6 columns)",
+        detail: match air {
+            Air::Sharpe => "Computing Low Degree Extension (7 columns)",
+            Air::Sortino => "Computing Low Degree Extension (8 columns)",
+        },
         percent: 10,
     });
 
-    let log_blowup: u32 = match blowup {
-        2 => 1,
-        4 => 2,
-        8 => 3,
-        _ => 2,
-    };
+    let log_blowup = domain::log_blowup(blowup).expect("unsupported blowup factor");
     let log_lde_size = log_trace_len + log_blowup;
-    let lde_size = 1usize << log_lde_size;
-    let lde_domain = get_domain(log_lde_size);
 
     // IFFT each trace column → polynomial coefficients (cached for OOD eval later)
-    let mut coeffs_0 = trace.col_return.clone();
-    domain::ifft(&mut coeffs_0, log_trace_len);
-    let mut coeffs_1 = trace.col_return_sq.clone();
-    domain::ifft(&mut coeffs_1, log_trace_len);
-    let mut coeffs_2 = trace.col_cumulative_return.clone();
-    domain::ifft(&mut coeffs_2, log_trace_len);
-    let mut coeffs_3 = trace.col_cumulative_sq.clone();
-    domain::ifft(&mut coeffs_3, log_trace_len);
-    let mut coeffs_4 = trace.col_trade_count.clone();
-    domain::ifft(&mut coeffs_4, log_trace_len);
-    let mut coeffs_5 = trace.col_dataset_commitment.clone();
-    domain::ifft(&mut coeffs_5, log_trace_len);
-
-    // Zero-pad coefficients and FFT → LDE evaluations
-    let lde_from_coeffs = |coeffs: &[U256]| -> Vec<U256> {
-        let mut padded = coeffs.to_vec();
-        padded.resize(lde_size, U256::ZERO);
-        domain::fft(&mut padded, log_lde_size);
-        padded
-    };
-    let trace_lde_0 = lde_from_coeffs(&coeffs_0);
-    let trace_lde_1 = lde_from_coeffs(&coeffs_1);
-    let trace_lde_2 = lde_from_coeffs(&coeffs_2);
-    let trace_lde_3 = lde_from_coeffs(&coeffs_3);
-    let trace_lde_4 = lde_from_coeffs(&coeffs_4);
-    let trace_lde_5 = lde_from_coeffs(&coeffs_5);
-
-    // Step 3: Commit to trace (6-column Merkle)
+    let mut trace_coeffs = columns;
+    for coeffs in trace_coeffs.iter_mut() {
+        domain::ifft(coeffs, log_trace_len);
+    }
+    let trace_ldes: Vec<Vec<U256>> = trace_coeffs.iter()
+        .map(|coeffs| lde_from_coeffs(coeffs, log_lde_size))
+        .collect();
+
+    // Step 3: Commit to trace (one multi-column Merkle tree)
     on_progress(ProveProgress {
         stage: "commit",
         detail: "Committing to trace polynomials",
         percent: 30,
     });
 
-    let trace_tree = commit_trace_multi(&[
-        &trace_lde_0, &trace_lde_1, &trace_lde_2,
-        &trace_lde_3, &trace_lde_4, &trace_lde_5,
-    ]);
-    let trace_commitment = trace_tree.root();
+    let trace_tree = commit_trace_multi(&column_refs(&trace_ldes), leaf_salt);
+
+    let mut channel = Channel::<H>::with_public_inputs(air.proof_kind(), &public_inputs);
+    // The leaf salt is bound into the transcript before any commitment
+    if let Some(salt) = leaf_salt {
+        channel.commit(salt);
+    }
+    channel.commit(trace_tree.root());
+
+    CommittedTrace {
+        air,
+        public_inputs,
+        air_inputs,
+        log_trace_len,
+        trace_len,
+        log_lde_size,
+        trace_coeffs,
+        trace_ldes,
+        trace_tree,
+        transcript: channel.snapshot(),
+    }
+}
+
+/// Steps 4-6: OOD evaluation, composition segments and DEEP coefficients.
+fn compose_stage<H: TranscriptHash>(
+    committed: CommittedTrace,
+    options: &ProveOptions,
+    on_progress: &impl Fn(ProveProgress),
+) -> ComposedTrace {
+    let leaf_salt = options.leaf_salt;
+    let num_segments = options.num_composition_segments();
+    let log_trace_len = committed.log_trace_len;
+    let trace_len = committed.trace_len;
+    let log_lde_size = committed.log_lde_size;
+    let lde_domain = get_domain(log_lde_size);
 
     // Step 4: Fiat-Shamir + OOD evaluation
     on_progress(ProveProgress {
@@ -148,45 +673,45 @@ pub fn prove_sharpe_with_progress(
         percent: 40,
     });
 
-    let mut seed = public_inputs[0];
-    for i in 1..4 {
-        seed = keccak_hash_two(seed, public_inputs[i]);
-    }
-    let mut channel = Channel::new(seed);
-    channel.commit(trace_commitment);
+    let mut channel = Channel::<H>::resume(committed.transcript);
     let z = channel.draw_felt();
 
     let trace_gen = domain_generator(log_trace_len);
     let zg = BN254Field::mul(z, trace_gen);
 
-    // Evaluate 6 columns at z and zg using Horner on cached coefficients
-    let all_coeffs: [&[U256]; 6] = [
-        &coeffs_0, &coeffs_1, &coeffs_2,
-        &coeffs_3, &coeffs_4, &coeffs_5,
-    ];
-
-    let mut trace_ood_evals = [U256::ZERO; 6];
-    let mut trace_ood_evals_next = [U256::ZERO; 6];
-    for (j, coeffs) in all_coeffs.iter().enumerate() {
-        trace_ood_evals[j] = eval_poly_at(coeffs, z);
-        trace_ood_evals_next[j] = eval_poly_at(coeffs, zg);
-    }
+    // Evaluate every column at z and zg using Horner on cached coefficients
+    let trace_ood_evals: Vec<U256> = committed.trace_coeffs.iter()
+        .map(|coeffs| eval_poly_at(coeffs, z))
+        .collect();
+    let trace_ood_evals_next: Vec<U256> = committed.trace_coeffs.iter()
+        .map(|coeffs| eval_poly_at(coeffs, zg))
+        .collect();
 
-    // Draw 9 alphas
-    let mut alphas = [U256::ZERO; 9];
-    for i in 0..9 {
-        alphas[i] = channel.draw_felt();
-    }
+    // Draw 11 (Sharpe) or 12 (Sortino) alphas
+    let alphas: Vec<U256> = (0..committed.air.num_alphas())
+        .map(|_| channel.draw_felt())
+        .collect();
 
-    let composition_ood_eval = compute_sharpe_composition_at_z(
-        &trace_ood_evals,
-        &trace_ood_evals_next,
-        z,
-        trace_gen,
-        trace_len as u64,
-        &public_inputs,
-        &alphas,
-    );
+    let composition_ood_eval = match committed.air {
+        Air::Sharpe => compute_sharpe_composition_at_z(
+            as_array(&trace_ood_evals),
+            as_array(&trace_ood_evals_next),
+            z,
+            trace_gen,
+            trace_len as u64,
+            &committed.air_inputs,
+            as_array(&alphas),
+        ),
+        Air::Sortino => compute_sortino_composition_at_z(
+            as_array(&trace_ood_evals),
+            as_array(&trace_ood_evals_next),
+            z,
+            trace_gen,
+            trace_len as u64,
+            &committed.air_inputs,
+            as_array(&alphas),
+        ),
+    };
 
     // Step 5: Composition polynomial on LDE
     on_progress(ProveProgress {
@@ -195,21 +720,108 @@ pub fn prove_sharpe_with_progress(
         percent: 50,
     });
 
-    let composition_lde = evaluate_sharpe_composition_on_lde(
-        &[&trace_lde_0, &trace_lde_1, &trace_lde_2,
-          &trace_lde_3, &trace_lde_4, &trace_lde_5],
+    let trace_ldes = column_refs(&committed.trace_ldes);
+    let composition_lde = match committed.air {
+        Air::Sharpe => evaluate_sharpe_composition_on_lde(
+            as_array(&trace_ldes),
+            &lde_domain,
+            trace_gen,
+            trace_len as u64,
+            &committed.air_inputs,
+            as_array(&alphas),
+        ),
+        Air::Sortino => evaluate_sortino_composition_on_lde(
+            as_array(&trace_ldes),
+            &lde_domain,
+            trace_gen,
+            trace_len as u64,
+            &committed.air_inputs,
+            as_array(&alphas),
+        ),
+    };
+
+    // C(x) = Σ_i S_i(x) · x^(i·N); one segment commits C itself
+    let (composition_ldes, composition_ood_evals) = if num_segments == 1 {
+        (vec![composition_lde], vec![composition_ood_eval])
+    } else {
+        let segments = split_composition(&composition_lde, log_lde_size, log_trace_len, num_segments);
+        let segment_ood_evals: Vec<U256> = segments.iter().map(|s| eval_poly_at(s, z)).collect();
+        let z_n = BN254Field::pow(z, U256::from(trace_len as u64));
+        debug_assert_eq!(
+            eval_poly_at(&segment_ood_evals, z_n),
+            composition_ood_eval,
+            "composition segments do not recombine at z"
+        );
+        (segments.iter().map(|s| lde_from_coeffs(s, log_lde_size)).collect(), segment_ood_evals)
+    };
+
+    let composition_trees: Vec<_> = composition_ldes.iter()
+        .map(|lde| commit_column(lde, leaf_salt))
+        .collect();
+    let composition_commitments: Vec<U256> = composition_trees.iter().map(|t| t.root()).collect();
+    for root in &composition_commitments {
+        channel.commit(*root);
+    }
+
+    // Step 6: DEEP coefficients, bound to the OOD values
+    for v in trace_ood_evals.iter().chain(trace_ood_evals_next.iter()) {
+        channel.commit(*v);
+    }
+    for v in &composition_ood_evals {
+        channel.commit(*v);
+    }
+
+    let deep_gammas: Vec<U256> = (0..num_deep_coeffs(trace_ood_evals.len(), num_segments))
+        .map(|_| channel.draw_felt())
+        .collect();
+
+    ComposedTrace {
+        committed,
+        z,
+        zg,
+        trace_ood_evals,
+        trace_ood_evals_next,
+        composition_ldes,
+        composition_trees,
+        composition_commitments,
+        composition_ood_evals,
+        deep_gammas,
+        transcript: channel.snapshot(),
+    }
+}
+
+/// Steps 6-8: DEEP composition, FRI, openings and serialization.
+fn fri_stage<H: TranscriptHash>(
+    composed: ComposedTrace,
+    num_queries: usize,
+    options: &ProveOptions,
+    on_progress: &impl Fn(ProveProgress),
+) -> SerializedProof {
+    let leaf_salt = options.leaf_salt;
+    let committed = &composed.committed;
+    let log_lde_size = committed.log_lde_size;
+    let lde_size = 1usize << log_lde_size;
+    let lde_domain = get_domain(log_lde_size);
+    let mut channel = Channel::<H>::resume(composed.transcript);
+
+    // DEEP composition over trace + composition
+    let trace_ldes = column_refs(&committed.trace_ldes);
+    let deep_point = DeepPoint {
+        z: composed.z,
+        zg: composed.zg,
+        trace_at_z: composed.trace_ood_evals.clone(),
+        trace_at_zg: composed.trace_ood_evals_next.clone(),
+        composition_at_z: composed.composition_ood_evals.clone(),
+    };
+    let deep_lde = evaluate_deep_on_lde(
+        &trace_ldes,
+        &composed.composition_ldes,
         &lde_domain,
-        trace_gen,
-        trace_len as u64,
-        &public_inputs,
-        &alphas,
+        &deep_point,
+        &composed.deep_gammas,
     );
 
-    let composition_tree = commit_column(&composition_lde);
-    let composition_commitment = composition_tree.root();
-    channel.commit(composition_commitment);
-
-    // Step 6: FRI protocol
+    // Step 7: FRI protocol, advancing 65% -> 80% as the layers are folded
     on_progress(ProveProgress {
         stage: "fri",
         detail: "Running FRI protocol",
@@ -217,14 +829,24 @@ pub fn prove_sharpe_with_progress(
     });
 
     let num_fri_layers = log_lde_size as usize - 2;
-    let fri_commitment = fri_commit(
-        &composition_lde,
+    let fri_commitment = fri_commit_with_progress(
+        &deep_lde,
         &mut channel,
         log_lde_size,
         num_fri_layers,
+        leaf_salt,
+        |done| on_progress(ProveProgress {
+            stage: "fri",
+            detail: "Folding FRI layers",
+            percent: 65 + (15 * done / num_fri_layers) as u8,
+        }),
     );
 
     let query_indices = channel.draw_queries(num_queries, lde_size);
+    debug_assert!(
+        fri_self_check(&fri_commitment, &query_indices),
+        "FRI folding self-check failed"
+    );
 
     on_progress(ProveProgress {
         stage: "fri",
@@ -232,16 +854,28 @@ pub fn prove_sharpe_with_progress(
         percent: 80,
     });
 
-    let (query_values, query_paths, _query_path_indices) = fri_query_proofs(
-        &fri_commitment,
-        &query_indices,
+    let (query_values, query_paths, _) = QueryOpening::to_flat(
+        &fri_query_openings(&fri_commitment, &query_indices),
     );
 
     let fri_layer_roots: Vec<U256> = fri_commitment.layers.iter()
         .map(|l| l.tree.root())
         .collect();
 
-    // Step 7: Serialize proof
+    // Open the trace row and composition segments behind each query's DEEP value
+    let num_segments = composed.composition_ldes.len();
+    let mut opening_values = Vec::with_capacity(query_indices.len() * (trace_ldes.len() + num_segments));
+    let mut opening_paths = Vec::new();
+    for &q in &query_indices {
+        opening_values.extend(trace_ldes.iter().map(|col| col[q]));
+        opening_values.extend(composed.composition_ldes.iter().map(|lde| lde[q]));
+        opening_paths.extend(committed.trace_tree.auth_path(q).0);
+        for tree in &composed.composition_trees {
+            opening_paths.extend(tree.auth_path(q).0);
+        }
+    }
+
+    // Step 8: Serialize proof
     on_progress(ProveProgress {
         stage: "done",
         detail: "Serializing proof",
@@ -249,20 +883,23 @@ pub fn prove_sharpe_with_progress(
     });
 
     let serialized = SerializedProof::new_sharpe(
-        public_inputs,
-        trace_commitment,
-        composition_commitment,
+        &committed.public_inputs,
+        committed.trace_tree.root(),
+        &composed.composition_commitments,
         &fri_layer_roots,
-        trace_ood_evals,
-        trace_ood_evals_next,
-        composition_ood_eval,
+        &composed.trace_ood_evals,
+        &composed.trace_ood_evals_next,
+        &composed.composition_ood_evals,
         &fri_commitment.final_poly,
         &query_indices,
         &query_values,
         &query_paths,
         num_fri_layers,
-        log_trace_len,
-    );
+        committed.log_trace_len,
+        leaf_salt,
+    )
+    .with_trace_openings(&opening_values, &opening_paths)
+    .with_composition_lde_len(composed.composition_ldes[0].len());
 
     on_progress(ProveProgress {
         stage: "done",
@@ -273,102 +910,6 @@ pub fn prove_sharpe_with_progress(
     serialized
 }
 
-/// Compute Sharpe composition polynomial value at OOD point z.
-fn compute_sharpe_composition_at_z(
-    trace_ood_evals: &[U256; 6],
-    trace_ood_evals_next: &[U256; 6],
-    z: U256,
-    trace_gen: U256,
-    trace_len: u64,
-    public_inputs: &[U256; 4],
-    alphas: &[U256; 9],
-) -> U256 {
-    let one = U256::from(1u64);
-    let scale = U256::from(SHARPE_SCALE);
-
-    // TC0: cum_ret_next - cum_ret - ret_next
-    let tc0 = BN254Field::sub(
-        trace_ood_evals_next[2],
-        BN254Field::add(trace_ood_evals[2], trace_ood_evals_next[0]),
-    );
-
-    // TC1: ret_sq - ret * ret
-    let tc1 = BN254Field::sub(
-        trace_ood_evals[1],
-        BN254Field::mul(trace_ood_evals[0], trace_ood_evals[0]),
-    );
-
-    // TC2: cum_sq_next - cum_sq - ret_sq_next
-    let tc2 = BN254Field::sub(
-        trace_ood_evals_next[3],
-        BN254Field::add(trace_ood_evals[3], trace_ood_evals_next[1]),
-    );
-
-    // TC3: trade_count_next - trade_count (immutability)
-    let tc3 = BN254Field::sub(trace_ood_evals_next[4], trace_ood_evals[4]);
-
-    // TC4: dataset_commitment_next - dataset_commitment = 0 (immutability)
-    let tc4 = BN254Field::sub(trace_ood_evals_next[5], trace_ood_evals[5]);
-
-    // Transition zerofier at z
-    let z_n = BN254Field::pow(z, U256::from(trace_len));
-    let zerofier_num = BN254Field::sub(z_n, one);
-    let g_last = BN254Field::pow(trace_gen, U256::from(trace_len - 1));
-    let zerofier_den = BN254Field::sub(z, g_last);
-    let zerofier = BN254Field::div(zerofier_num, zerofier_den);
-
-    let tq0 = BN254Field::div(tc0, zerofier);
-    let tq1 = BN254Field::div(tc1, zerofier);
-    let tq2 = BN254Field::div(tc2, zerofier);
-    let tq3 = BN254Field::div(tc3, zerofier);
-    let tq4 = BN254Field::div(tc4, zerofier);
-
-    // Boundary constraints
-    let trace_first = one;
-    let den_first = BN254Field::sub(z, trace_first);
-    let den_last = BN254Field::sub(z, g_last);
-
-    // BC0: (cum_ret - ret) / (z - 1)
-    let bq0 = BN254Field::div(
-        BN254Field::sub(trace_ood_evals[2], trace_ood_evals[0]),
-        den_first,
-    );
-
-    // BC1: (cum_sq - ret_sq) / (z - 1)
-    let bq1 = BN254Field::div(
-        BN254Field::sub(trace_ood_evals[3], trace_ood_evals[1]),
-        den_first,
-    );
-
-    // BC2: (cum_ret - total_return) / (z - g^(N-1))
-    let bq2 = BN254Field::div(
-        BN254Field::sub(trace_ood_evals[2], public_inputs[1]),
-        den_last,
-    );
-
-    // BC3: (cum_ret^2 * SCALE - sharpe_sq * (n * cum_sq - cum_ret^2)) / (z - g^(N-1))
-    let cum_ret_sq = BN254Field::mul(trace_ood_evals[2], trace_ood_evals[2]);
-    let bc3_lhs = BN254Field::mul(cum_ret_sq, scale);
-    let n_cum_sq = BN254Field::mul(public_inputs[0], trace_ood_evals[3]);
-    let denom_inner = BN254Field::sub(n_cum_sq, cum_ret_sq);
-    let bc3_rhs = BN254Field::mul(public_inputs[2], denom_inner);
-    let bc3_num = BN254Field::sub(bc3_lhs, bc3_rhs);
-    let bq3 = BN254Field::div(bc3_num, den_last);
-
-    // Combine: 5 TC + 4 BC = 9 alphas
-    let mut comp = BN254Field::mul(alphas[0], tq0);
-    comp = BN254Field::add(comp, BN254Field::mul(alphas[1], tq1));
-    comp = BN254Field::add(comp, BN254Field::mul(alphas[2], tq2));
-    comp = BN254Field::add(comp, BN254Field::mul(alphas[3], tq3));
-    comp = BN254Field::add(comp, BN254Field::mul(alphas[4], tq4));
-    comp = BN254Field::add(comp, BN254Field::mul(alphas[5], bq0));
-    comp = BN254Field::add(comp, BN254Field::mul(alphas[6], bq1));
-    comp = BN254Field::add(comp, BN254Field::mul(alphas[7], bq2));
-    comp = BN254Field::add(comp, BN254Field::mul(alphas[8], bq3));
-
-    comp
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,9 +919,9 @@ mod tests {
 
     #[test]
     fn test_200_trades_perf() {
-        let pattern: [i64; 5] = [100, -50, 200, -100, 150];
+        let pattern: [i32; 5] = [100, -50, 200, -100, 150];
         let trades: Vec<GmxTradeRecord> = (0..200)
-            .map(|i| GmxTradeRecord::from_return_bps(pattern[i % 5]))
+            .map(|i| GmxTradeRecord::from_return_bps(pattern[i % 5].into()))
             .collect();
 
         let trace = SharpeTrace::generate(&trades, None);
@@ -397,14 +938,14 @@ mod tests {
         assert_eq!(proof.public_inputs[0], U256::from(200u64));
         assert_eq!(proof.public_inputs[2], claimed_sharpe_sq_scaled);
         assert!(proof.commitments.len() >= 2);
-        assert_eq!(proof.ood_values.len(), 13);
+        assert_eq!(proof.ood_values.len(), 16);
     }
 
     #[test]
     fn test_5000_trades_perf() {
-        let pattern: [i64; 5] = [100, -50, 200, -100, 150];
+        let pattern: [i32; 5] = [100, -50, 200, -100, 150];
         let trades: Vec<GmxTradeRecord> = (0..5000)
-            .map(|i| GmxTradeRecord::from_return_bps(pattern[i % 5]))
+            .map(|i| GmxTradeRecord::from_return_bps(pattern[i % 5].into()))
             .collect();
 
         let trace = SharpeTrace::generate(&trades, None);
@@ -420,6 +961,485 @@ mod tests {
         assert_eq!(proof.public_inputs[0], U256::from(5000u64));
         assert_eq!(proof.public_inputs[2], claimed_sharpe_sq_scaled);
         assert!(proof.commitments.len() >= 2);
-        assert_eq!(proof.ood_values.len(), 13);
+        assert_eq!(proof.ood_values.len(), 16);
+    }
+
+    #[test]
+    fn test_non_canonical_leaf_salt_rejected() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+
+        for salt in [crate::field::BN254_PRIME, U256::MAX] {
+            let options = ProveOptions { leaf_salt: Some(salt), ..Default::default() };
+            let err = try_prove_sharpe_with_options(&bot.trades, claimed, 4, None, &options, |_| {}).unwrap_err();
+            assert!(err.contains("field modulus"), "{}", err);
+            let proving = std::panic::catch_unwind(|| ProverState::new(&bot.trades, claimed, 4, None, &options));
+            assert!(proving.is_err(), "salt {:#x} should not be proven", salt);
+        }
+
+        // p - 1 is still a valid salt
+        let options = ProveOptions { leaf_salt: Some(crate::field::BN254_PRIME - U256::from(1u64)), ..Default::default() };
+        assert!(try_prove_sharpe_with_options(&bot.trades, claimed, 4, None, &options, |_| {}).is_ok());
+    }
+
+    #[test]
+    fn test_leaf_salt_changes_commitments() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let prove = |salt: Option<U256>| {
+            let options = ProveOptions { leaf_salt: salt, ..Default::default() };
+            prove_sharpe_with_options(&bot.trades, claimed, 4, None, &options, |_| {})
+        };
+
+        let plain = prove(None);
+        let salted_a = prove(Some(U256::from(0xa11ceu64)));
+        let salted_b = prove(Some(U256::from(0xb0bu64)));
+
+        // Same data, different salts → unrelated trace and composition roots
+        assert_ne!(salted_a.commitments[0], salted_b.commitments[0]);
+        assert_ne!(salted_a.commitments[0], plain.commitments[0]);
+        assert_ne!(salted_a.commitments[1], salted_b.commitments[1]);
+
+        // Public inputs (incl. the dataset-column root) are salt-independent
+        assert_eq!(salted_a.public_inputs, plain.public_inputs);
+
+        // Salt rides at the end of query_metadata only for salted proofs
+        assert_eq!(plain.query_metadata.len(), 4 + 4);
+        assert_eq!(salted_a.query_metadata.len(), 4 + 4 + 1);
+        assert_eq!(*salted_a.query_metadata.last().unwrap(), U256::from(0xa11ceu64));
+
+        // Deterministic for a fixed salt
+        assert_eq!(prove(Some(U256::from(0xb0bu64))).commitments, salted_b.commitments);
+    }
+
+    #[test]
+    fn test_as_of_block_binds_public_inputs() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let commitment = Some(U256::from(0xc0ffeeu64));
+        let prove = |as_of_block: Option<u64>| {
+            let options = ProveOptions { as_of_block, ..Default::default() };
+            prove_sharpe_with_options(&bot.trades, claimed, 4, commitment, &options, |_| {})
+        };
+
+        let plain = prove(None);
+        let fresh = prove(Some(1_000));
+        let later = prove(Some(1_001));
+
+        assert_eq!(plain.public_inputs.len(), 4);
+        assert_eq!(fresh.public_inputs.len(), 5);
+        assert_eq!(fresh.public_inputs[4], U256::from(1_000u64));
+
+        // pi[3] commits to keccak(commitment, as_of_block)
+        let bound = receipt_proof::bind_as_of_block(U256::from(0xc0ffeeu64), 1_000);
+        let expected_root = SharpeTrace::generate(&bot.trades, Some(bound))
+            .public_inputs(claimed)[3];
+        assert_eq!(fresh.public_inputs[3], expected_root);
+        assert_ne!(fresh.public_inputs[3], plain.public_inputs[3]);
+        assert_ne!(fresh.public_inputs[3], later.public_inputs[3]);
+
+        // Different seed → different transcript
+        assert_ne!(fresh.query_metadata, later.query_metadata);
+    }
+
+    #[test]
+    fn test_poseidon_transcript_tags_metadata() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let prove = |transcript: TranscriptKind| {
+            let options = ProveOptions { transcript, ..Default::default() };
+            prove_sharpe_with_options(&bot.trades, claimed, 4, None, &options, |_| {})
+        };
+
+        let keccak = prove(TranscriptKind::Keccak);
+        let poseidon = prove(TranscriptKind::Poseidon);
+
+        // Trace root is transcript-independent; everything drawn after it is not
+        assert_eq!(keccak.commitments[0], poseidon.commitments[0]);
+        assert_ne!(keccak.ood_values, poseidon.ood_values);
+
+        // query_metadata[0] = num_queries | kind << 64 | segments << 128
+        let segments = U256::from(MIN_SHARPE_SEGMENTS) << 128;
+        assert_eq!(keccak.query_metadata[0], U256::from(4u64) | segments);
+        assert_eq!(poseidon.query_metadata[0], U256::from(4u64) | (U256::from(1u64) << 64) | segments);
+    }
+
+    #[test]
+    fn test_batched_composition_matches_pointwise() {
+        // 16-row trace, blowup 4 → 64-point LDE domain
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let trace = SharpeTrace::generate(&bot.trades, Some(U256::from(0xc0ffeeu64)));
+        let log_trace_len = trace.log_len();
+        let log_lde_size = log_trace_len + 2;
+        let lde_domain = get_domain(log_lde_size);
+        assert_eq!(lde_domain.len(), 64);
+
+        let columns = [
+            &trace.col_return, &trace.col_return_sq, &trace.col_cumulative_return,
+            &trace.col_cumulative_sq, &trace.col_trade_count, &trace.col_dataset_commitment,
+            &trace.col_is_real_row,
+        ];
+        let ldes: Vec<Vec<U256>> = columns.iter()
+            .map(|col| {
+                let mut coeffs = col.to_vec();
+                domain::ifft(&mut coeffs, log_trace_len);
+                coeffs.resize(lde_domain.len(), U256::ZERO);
+                domain::fft(&mut coeffs, log_lde_size);
+                coeffs
+            })
+            .collect();
+        let lde_refs: [&[U256]; 7] = core::array::from_fn(|c| ldes[c].as_slice());
+
+        let trace_gen = domain_generator(log_trace_len);
+        let public_inputs = trace.public_inputs(U256::from(bot.expected_sharpe_sq_scaled));
+        let mut alphas = [U256::ZERO; sharpe_compose::NUM_ALPHAS];
+        for (k, alpha) in alphas.iter_mut().enumerate() {
+            *alpha = U256::from(0x1000u64 + k as u64);
+        }
+
+        let batched = evaluate_sharpe_composition_on_lde(
+            &lde_refs, &lde_domain, trace_gen, trace.len as u64, &public_inputs, &alphas,
+        );
+
+        // Off the trace domain (where the zerofier vanishes), each point must
+        // match the direct per-point evaluation with its own inversions
+        for i in (0..lde_domain.len()).filter(|i| i % 4 != 0) {
+            let next = (i + 4) % lde_domain.len();
+            let row: [U256; 7] = core::array::from_fn(|c| ldes[c][i]);
+            let row_next: [U256; 7] = core::array::from_fn(|c| ldes[c][next]);
+            let pointwise = compute_sharpe_composition_at_z(
+                &row, &row_next, lde_domain[i], trace_gen, trace.len as u64, &public_inputs, &alphas,
+            );
+            assert_eq!(batched[i], pointwise, "mismatch at LDE point {}", i);
+        }
+    }
+
+    #[test]
+    fn test_stepwise_proof_matches_single_shot() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let commitment = Some(U256::from(0xc0ffeeu64));
+
+        let step_through = |options: &ProveOptions| {
+            let mut state = ProverState::new(&bot.trades, claimed, 4, commitment, options);
+            let mut stages = Vec::new();
+            loop {
+                stages.push(state.next_stage());
+                state = match prove_sharpe_step(state) {
+                    StepResult::InProgress(next) => next,
+                    StepResult::Done(proof) => return (proof, stages),
+                };
+            }
+        };
+
+        let (stepped, stages) = step_through(&ProveOptions::default());
+        assert_eq!(stages, ["commit", "compose", "fri"]);
+        assert_eq!(stepped, prove_sharpe(&bot.trades, claimed, 4, commitment));
+
+        let options = ProveOptions {
+            leaf_salt: Some(U256::from(0xa11ceu64)),
+            transcript: TranscriptKind::Poseidon,
+            composition_segments: 3,
+            ..Default::default()
+        };
+        let (stepped, _) = step_through(&options);
+        let single_shot = prove_sharpe_with_options(&bot.trades, claimed, 4, commitment, &options, |_| {});
+        assert_eq!(stepped, single_shot);
+    }
+
+    #[test]
+    fn test_progress_advances_across_fri_layers() {
+        let bot = crate::mock_data::bot_b_safe_hedger();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let percents = std::cell::RefCell::new(Vec::new());
+        prove_sharpe_with_progress(&bot.trades, claimed, 4, None, |p| percents.borrow_mut().push(p.percent));
+
+        let percents = percents.into_inner();
+        assert!(percents.windows(2).all(|w| w[0] <= w[1]), "{:?}", percents);
+        assert_eq!(percents.last(), Some(&100));
+
+        let mut fri_range: Vec<u8> = percents.into_iter().filter(|p| (65..=80).contains(p)).collect();
+        fri_range.dedup();
+        assert!(fri_range.len() > 2, "FRI progress stalls: {:?}", fri_range);
+    }
+
+    #[test]
+    fn test_prove_sharpe_with_dataset_commitment() {
+        use crate::receipt_proof::ReceiptHashChain;
+
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let chain = ReceiptHashChain(vec![U256::from(0xaau64), U256::from(0xbbu64)]);
+        let expected = keccak::keccak_hash_two(U256::from(0xaau64), U256::from(0xbbu64));
+        assert_eq!(chain.compute(), expected);
+        assert_eq!(prove_sharpe(&bot.trades, claimed, 2, chain), prove_sharpe(&bot.trades, claimed, 2, Some(expected)));
+        assert_eq!(prove_sharpe(&bot.trades, claimed, 2, None), prove_sharpe(&bot.trades, claimed, 2, Some(U256::ZERO)));
+    }
+
+    #[test]
+    fn test_compute_sharpe_public_inputs_matches_proof() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+
+        for commitment in [None, Some(U256::from(0xabcdu64))] {
+            let inputs = compute_sharpe_public_inputs(&bot.trades, commitment);
+            assert_eq!(inputs[2], claimed);
+            assert_eq!(inputs.to_vec(), prove_sharpe(&bot.trades, claimed, 4, commitment).public_inputs);
+        }
+    }
+
+    #[test]
+    fn test_try_prove_sharpe_rejects_wrong_claim() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+
+        for wrong in [claimed + U256::from(1u64), U256::ZERO] {
+            let err = try_prove_sharpe(&bot.trades, wrong, 4, None).unwrap_err();
+            assert!(err.contains(&wrong.to_string()), "{}", err);
+            assert!(ProverState::try_new(&bot.trades, wrong, 4, None, &ProveOptions::default()).is_err());
+        }
+
+        let proof = try_prove_sharpe(&bot.trades, claimed, 4, None).unwrap();
+        assert_eq!(proof.query_metadata, prove_sharpe(&bot.trades, claimed, 4, None).query_metadata);
+    }
+
+    #[test]
+    fn test_padding_warning_progress() {
+        let trades = |n: usize| -> Vec<GmxTradeRecord> {
+            (0..n).map(|i| GmxTradeRecord::from_return_bps(((i % 3) as i32 * 100 - 50).into())).collect()
+        };
+        let options = ProveOptions { warn_padding_ratio: Some(0.4), ..Default::default() };
+        let padding_events = |trades: &[GmxTradeRecord]| {
+            let claimed = SharpeTrace::generate(trades, None).compute_sharpe_sq_scaled();
+            let stages = std::cell::RefCell::new(Vec::new());
+            prove_sharpe_with_options(trades, claimed, 2, None, &options, |p| stages.borrow_mut().push(p.stage));
+            stages.into_inner().iter().filter(|&&s| s == "padding").count()
+        };
+
+        // 17 -> 32 rows warns, 15 -> 16 rows does not
+        assert_eq!(padding_events(&trades(17)), 1);
+        assert_eq!(padding_events(&trades(15)), 0);
+        let state = ProverState::new(&trades(17), U256::ZERO, 2, None, &options);
+        assert_eq!(state.padding_warning().map(|w| w.trace_len), Some(32));
+
+        // Strict: an error before any proving work
+        let strict = ProveOptions { strict_padding: true, ..options.clone() };
+        let t17 = trades(17);
+        let claimed = SharpeTrace::generate(&t17, None).compute_sharpe_sq_scaled();
+        let err = try_prove_sharpe_with_options(&t17, claimed, 2, None, &strict, |_| {}).unwrap_err();
+        assert!(err.contains("17 trades pad to 32 rows"), "{}", err);
+        let t15 = trades(15);
+        let claimed = SharpeTrace::generate(&t15, None).compute_sharpe_sq_scaled();
+        assert!(try_prove_sharpe_with_options(&t15, claimed, 2, None, &strict, |_| {}).is_ok());
+    }
+
+    #[test]
+    fn test_constraint_violations_before_commit() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let options = ProveOptions::default();
+
+        let state = ProverState::new(&bot.trades, claimed, 4, None, &options);
+        assert_eq!(state.constraint_violations(), Some(Vec::new()));
+        let StepResult::InProgress(committed) = prove_sharpe_step(state) else { unreachable!() };
+        assert_eq!(committed.constraint_violations(), None);
+
+        // An overclaimed ratio fails BC3 at the last of the 15 trades
+        let state = ProverState::new(&bot.trades, claimed + U256::from(1u64), 4, None, &options);
+        assert_eq!(
+            state.constraint_violations(),
+            Some(vec![ConstraintViolation::Boundary { index: 3, row: 14 }])
+        );
+
+        let bot_c = crate::mock_data::bot_c_swing_trader();
+        let sortino = U256::from(bot_c.expected_sortino_sq_scaled.unwrap());
+        let state = ProverState::new_sortino(&bot_c.trades, sortino, 4, None, &options);
+        assert_eq!(state.constraint_violations(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_sorted_merkle_scheme_tags_pi3() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let commitment = U256::from(0xc0ffeeu64);
+        let prove = |merkle_scheme: MerkleScheme| {
+            let options = ProveOptions { merkle_scheme, ..Default::default() };
+            prove_sharpe_with_options(&bot.trades, claimed, 4, Some(commitment), &options, |_| {})
+        };
+
+        let ordered = prove(MerkleScheme::Ordered);
+        let sorted = prove(MerkleScheme::Sorted);
+        assert_eq!(ordered.public_inputs[..3], sorted.public_inputs[..3]);
+
+        // Constant 16-leaf column: hashed leaf, then 4 self-pairings
+        let mut root = keccak::keccak_hash_one(commitment);
+        for _ in 0..4 {
+            root = keccak::keccak_hash_two(root, root);
+        }
+        assert_eq!(sorted.public_inputs[3], root);
+        assert_ne!(ordered.public_inputs[3], root);
+
+        let segments = U256::from(MIN_SHARPE_SEGMENTS) << 128;
+        assert_eq!(ordered.query_metadata[0], U256::from(4u64) | segments);
+        assert_eq!(sorted.query_metadata[0], U256::from(4u64) | segments | (U256::from(1u64) << 192));
+    }
+
+    #[test]
+    fn test_composition_segments_layout() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let prove = |composition_segments: usize| {
+            let options = ProveOptions { composition_segments, ..Default::default() };
+            prove_sharpe_with_options(&bot.trades, claimed, 4, None, &options, |_| {})
+        };
+
+        // The gated TC1 quotient needs at least two segments
+        let default = prove(0);
+        assert_eq!(prove(1), default);
+        assert_eq!(prove(2), default);
+
+        // 16 rows → log_lde 6 → 4 FRI layers
+        assert_eq!(default.commitments.len(), 1 + 2 + 4);
+        assert_eq!(default.ood_values.len(), 14 + 2);
+        assert_eq!(default.query_metadata[0], U256::from(4u64) | (U256::from(2u64) << 128));
+        // 4 FRI layers over a declared 7-column trace
+        assert_eq!(default.query_metadata[1], U256::from(4u64) | (U256::from(7u64) << 64));
+
+        let split = prove(3);
+        assert_eq!(split.commitments.len(), 1 + 3 + 4);
+        assert_eq!(split.ood_values.len(), 14 + 3);
+        assert_eq!(split.commitments[0], default.commitments[0]);
+        assert_eq!(split.ood_values[..14], default.ood_values[..14]);
+        assert_eq!(split.query_metadata[0], U256::from(4u64) | (U256::from(3u64) << 128));
+        // Per query: 7 trace values + 3 segment values after the FRI pairs
+        assert_eq!(split.query_values.len(), 4 * (4 * 2) + 4 * 10);
+    }
+
+    #[test]
+    fn test_sortino_proof_layout() {
+        let bot = crate::mock_data::bot_c_swing_trader();
+        let claimed = U256::from(bot.expected_sortino_sq_scaled.unwrap());
+        let proof = prove_sortino(&bot.trades, claimed, 4, None);
+
+        // 12 trades → 16 rows → log_lde 6 → 4 FRI layers; always 3 segments
+        assert_eq!(proof.public_inputs[2], claimed);
+        assert_eq!(proof.commitments.len(), 1 + 3 + 4);
+        assert_eq!(proof.ood_values.len(), 16 + 3);
+        assert_eq!(proof.query_metadata[0], U256::from(4u64) | (U256::from(3u64) << 128));
+        assert_eq!(proof.query_metadata[1], U256::from(4u64) | (U256::from(8u64) << 64));
+        // Per query: 8 trace values + 3 segment values after the FRI pairs
+        assert_eq!(proof.query_values.len(), 4 * (4 * 2) + 4 * 11);
+
+        // Fewer segments cannot hold the degree-4 gated TC1 quotient
+        let options = ProveOptions { composition_segments: 1, ..Default::default() };
+        let forced = prove_sortino_with_options(&bot.trades, claimed, 4, None, &options, |_| {});
+        assert_eq!(forced, proof);
+    }
+
+    #[test]
+    fn test_estimate_calldata_size_matches_proof() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let options = ProveOptions {
+            leaf_salt: Some(U256::from(0xa11ceu64)),
+            as_of_block: Some(1_000),
+            ..Default::default()
+        };
+        for nq in [1, 4, 9] {
+            let proof = prove_sharpe_with_options(&bot.trades, claimed, nq, None, &options, |_| {});
+            assert_eq!(estimate_calldata_size(bot.trades.len(), nq, &options), proof.calldata_size());
+        }
+
+        let options = ProveOptions { composition_segments: 3, ..options };
+        let proof = prove_sharpe_with_options(&bot.trades, claimed, 4, None, &options, |_| {});
+        assert_eq!(estimate_calldata_size(bot.trades.len(), 4, &options), proof.calldata_size());
+    }
+
+    #[test]
+    fn test_protocol_hash() {
+        let words = [
+            U256::from(PROOF_FORMAT_VERSION),
+            crate::field::BN254_PRIME,
+            crate::domain::GENERATOR_2_28,
+            U256::from(BLOWUP),
+            U256::from(sharpe_trace::NUM_COLUMNS),
+            U256::from(crate::sharpe_compose::NUM_TRANSITION_CONSTRAINTS),
+            U256::from(crate::sharpe_compose::NUM_BOUNDARY_CONSTRAINTS),
+            U256::from(sortino_trace::NUM_COLUMNS),
+            U256::from(crate::sortino_compose::NUM_TRANSITION_CONSTRAINTS),
+            U256::from(crate::sortino_compose::NUM_BOUNDARY_CONSTRAINTS),
+            crate::keccak::keccak_hash_two(U256::from(1u64), U256::from(2u64)),
+            crate::keccak::keccak_hash_two_tagged(crate::keccak::TAG_MERKLE_NODE, U256::from(1u64), U256::from(2u64)),
+            crate::keccak::keccak_hash_two_tagged(crate::keccak::TAG_CHANNEL_COMMIT, U256::from(1u64), U256::from(2u64)),
+            crate::keccak::keccak_hash_two_tagged(crate::keccak::TAG_CHANNEL_DRAW, U256::from(1u64), U256::from(2u64)),
+            crate::poseidon::poseidon_hash_two(U256::from(1u64), U256::from(2u64)),
+        ];
+        let preimage: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes::<32>()).collect();
+        let hash = U256::from_be_bytes(alloy_primitives::keccak256(&preimage).0);
+        assert_eq!(PROTOCOL_HASH, hash, "{:?}", hash.as_limbs());
+
+        // Every proof carries it after the metadata header
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let proof = prove_sharpe(&bot.trades, U256::from(bot.expected_sharpe_sq_scaled), 4, None);
+        assert_eq!(proof.query_metadata[3], PROTOCOL_HASH);
+        let sortino = crate::mock_data::bot_c_swing_trader();
+        let claimed = U256::from(sortino.expected_sortino_sq_scaled.unwrap());
+        assert_eq!(prove_sortino(&sortino.trades, claimed, 4, None).query_metadata[3], PROTOCOL_HASH);
+    }
+
+    #[test]
+    fn test_prove_within_budget() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let budget = 16 * 1024;
+
+        let proof = prove_sharpe_within_budget(&bot.trades, claimed, budget).unwrap();
+        assert!(proof.calldata_size() <= budget);
+
+        // Maximal: one more query would not fit
+        let nq = proof.query_metadata[0].as_limbs()[0] as usize;
+        assert!(estimate_calldata_size(bot.trades.len(), nq + 1, &ProveOptions::default()) > budget);
+        assert!(security_bits(nq, BLOWUP) > security_bits(4, BLOWUP));
+
+        // Same proof `prove_sharpe` emits at that query count (see the
+        // verifier's `test_verify_sharpe_proof_bot_a` for the 4-query case)
+        let direct = prove_sharpe(&bot.trades, claimed, nq, None);
+        assert_eq!(proof.query_values, direct.query_values);
+        assert_eq!(proof.query_metadata, direct.query_metadata);
+
+        // Nothing fits in a tiny budget
+        assert!(prove_sharpe_within_budget(&bot.trades, claimed, 1024).is_none());
+    }
+
+    /// Packed bytes of `prove_sharpe(bot A, 4 queries, unbound)`.
+    ///
+    /// Any change to the Fiat-Shamir flow, query drawing or serialization
+    /// shows up as a mismatch here. When such a change is intentional,
+    /// regenerate the file and commit it with the change:
+    ///
+    ///     UPDATE_GOLDEN=1 cargo test test_golden_proof_bot_a
+    ///
+    /// then update the verifier fixtures the same change invalidates.
+    const GOLDEN_PROOF_BOT_A: &str = "testdata/sharpe_bot_a.bin";
+
+    #[test]
+    fn test_golden_proof_bot_a() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let proof = prove_sharpe(&bot.trades, U256::from(bot.expected_sharpe_sq_scaled), 4, None);
+        let bytes = proof.to_bytes();
+
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(GOLDEN_PROOF_BOT_A);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &bytes).unwrap();
+        }
+
+        let golden = std::fs::read(&path).unwrap_or_else(|e| panic!("reading {}: {}", path.display(), e));
+        let golden = SerializedProof::from_bytes(&golden).expect("golden proof is malformed");
+        assert_eq!(
+            proof, golden,
+            "Bot A proof differs from {}; if intentional, regenerate with UPDATE_GOLDEN=1",
+            GOLDEN_PROOF_BOT_A
+        );
     }
 }
@@ -6,6 +6,9 @@
 //!   cargo run --features cli -- --bot a
 //!   cargo run --features cli -- --bot b --num-queries 20
 //!   cargo run --features cli -- --wallet 0x... --tx-hash 0x... --num-queries 4
+//!   cargo run --features cli -- --wallet 0x... --bind-as-of-block
+//!   cargo run --features cli -- --bot a --debug-constraints
+//!   cargo run --features cli -- --wallet 0x... --warn-padding-ratio 0.3 --strict-padding
 
 #[cfg(feature = "cli")]
 use clap::Parser;
@@ -38,14 +41,36 @@ struct Args {
     #[arg(long)]
     rpc_url: Option<String>,
 
-    /// Start block for trade fetching (default: latest - 10M)
+    /// Start block for trade fetching, e.g. the block after the last proof
+    /// (default: --lookback-blocks before --to-block)
     #[arg(long)]
     from_block: Option<u64>,
 
+    /// Blocks to look back from --to-block when --from-block is not given
+    #[arg(long, default_value_t = stark_prover::gmx_fetcher::DEFAULT_LOOKBACK_BLOCKS)]
+    lookback_blocks: u64,
+
     /// End block for trade fetching (default: latest)
     #[arg(long)]
     to_block: Option<u64>,
 
+    /// Bind the latest fetched trade's block as `as_of_block` (pi[4], wallet mode)
+    #[arg(long)]
+    bind_as_of_block: bool,
+
+    /// Check every AIR constraint on the raw trace before proving and list
+    /// the violated ones (constraint index and row) instead of proving
+    #[arg(long)]
+    debug_constraints: bool,
+
+    /// Warn when more than this fraction of the power-of-2 trace is padding
+    #[arg(long, default_value_t = stark_prover::padding::DEFAULT_WARN_PADDING_RATIO)]
+    warn_padding_ratio: f32,
+
+    /// Fail instead of warning when padding exceeds --warn-padding-ratio
+    #[arg(long)]
+    strict_padding: bool,
+
     /// Output format: json or hex
     #[arg(long, default_value = "json")]
     format: String,
@@ -79,6 +104,25 @@ fn output_proof(serialized: &stark_prover::proof::SerializedProof, format: &str)
     }
 }
 
+/// Print the constraints the trace for this statement violates; true if none.
+#[cfg(feature = "cli")]
+fn check_constraints(
+    trades: &[stark_prover::mock_data::GmxTradeRecord],
+    claimed: alloy_primitives::U256,
+    dataset_commitment: Option<alloy_primitives::U256>,
+    options: &stark_prover::ProveOptions,
+) -> bool {
+    let state = stark_prover::ProverState::new(trades, claimed, 0, dataset_commitment, options);
+    let violations = state.constraint_violations().unwrap_or_default();
+    if violations.is_empty() {
+        println!("[debug] All constraints satisfied on the trace domain");
+    }
+    for violation in &violations {
+        eprintln!("[debug] {}", violation);
+    }
+    violations.is_empty()
+}
+
 #[cfg(feature = "cli")]
 #[tokio::main]
 async fn main() {
@@ -115,13 +159,28 @@ fn run_bot_mode(args: &Args) {
     println!();
 
     let claimed = alloy_primitives::U256::from(bot.expected_sharpe_sq_scaled);
-    let serialized = stark_prover::prove_sharpe_with_progress(
+    let options = stark_prover::ProveOptions {
+        warn_padding_ratio: Some(args.warn_padding_ratio),
+        strict_padding: args.strict_padding,
+        ..Default::default()
+    };
+    if args.debug_constraints && !check_constraints(&bot.trades, claimed, None, &options) {
+        return;
+    }
+    let serialized = match stark_prover::try_prove_sharpe_with_options(
         &bot.trades,
         claimed,
         args.num_queries,
         None,
+        &options,
         make_progress_cb(args.verbose),
-    );
+    ) {
+        Ok(proof) => proof,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
 
     println!();
     println!("{}", serialized.summary());
@@ -133,7 +192,7 @@ fn run_bot_mode(args: &Args) {
 #[cfg(feature = "cli")]
 async fn run_wallet_mode(args: &Args) {
     use stark_prover::gmx_fetcher;
-    use stark_prover::mock_data::GmxTradeRecord;
+    use stark_prover::mock_data::{GmxTradeRecord, TradeReturn};
     use stark_prover::sharpe_trace::SharpeTrace;
 
     let wallet = args.wallet.as_deref().unwrap();
@@ -147,10 +206,14 @@ async fn run_wallet_mode(args: &Args) {
 
     // Step 1: Fetch trades from Arbitrum RPC
     println!("[fetch] Fetching GMX PositionDecrease events...");
+    let mode = match args.from_block {
+        Some(block) => gmx_fetcher::FetchMode::SinceBlock(block),
+        None => gmx_fetcher::FetchMode::Lookback(args.lookback_blocks),
+    };
     let result = gmx_fetcher::fetch_gmx_trades(
         wallet,
         Some(rpc_url),
-        args.from_block,
+        mode,
         args.to_block,
     )
     .await;
@@ -180,13 +243,16 @@ async fn run_wallet_mode(args: &Args) {
         println!(
             "  Trade {}: return_bps={:+}, is_long={}, tx={}",
             i + 1,
-            trade.return_bps,
+            trade.return_bps.bps(),
             trade.is_long,
             &trade.tx_hash[..10]
         );
+        if let (Some(blocks), Some(hpr)) = (trade.holding_period_blocks(), trade.holding_period_return_bps) {
+            println!("           held {} blocks, holding-period return_bps={:+}", blocks, hpr.bps());
+        }
     }
 
-    let returns_bps: Vec<i64> = gmx_fetcher::trades_to_returns_bps(&result.trades);
+    let returns_bps: Vec<TradeReturn> = gmx_fetcher::trades_to_returns_bps(&result.trades);
     println!();
     println!("Total return: {:+} bps", result.total_return_bps);
 
@@ -227,14 +293,40 @@ async fn run_wallet_mode(args: &Args) {
     println!("Claimed Sharpe^2 * SCALE: {}", claimed);
     println!();
 
+    // Freshness: bind the block of the most recent trade
+    let as_of_block = if args.bind_as_of_block {
+        result.trades.iter().map(|t| t.block_number).max()
+    } else {
+        None
+    };
+    if let Some(block) = as_of_block {
+        println!("As-of block: #{} (bound into pi[3] and pi[4])", block);
+    }
+
     // Generate proof
-    let serialized = stark_prover::prove_sharpe_with_progress(
+    let options = stark_prover::ProveOptions {
+        as_of_block,
+        warn_padding_ratio: Some(args.warn_padding_ratio),
+        strict_padding: args.strict_padding,
+        ..Default::default()
+    };
+    if args.debug_constraints && !check_constraints(&trades, claimed, dataset_commitment, &options) {
+        return;
+    }
+    let serialized = match stark_prover::try_prove_sharpe_with_options(
         &trades,
         claimed,
         args.num_queries,
         dataset_commitment,
+        &options,
         make_progress_cb(args.verbose),
-    );
+    ) {
+        Ok(proof) => proof,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
 
     println!();
     println!("{}", serialized.summary());
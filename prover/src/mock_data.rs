@@ -1,21 +1,99 @@
 //! Mock GMX Trade Data for Sharpe Ratio Proof
 //!
 //! Provides hardcoded trade data for testing the Sharpe AIR.
-//! Two mock bots with different trading profiles:
+//! Mock bots with different trading profiles:
 //!   - Bot A: Aggressive ETH trader (15 trades, Sharpe ~ 2.45)
 //!   - Bot B: Conservative hedger (23 trades, Sharpe ~ 1.37)
+//!   - Bot C: Swing trader with occasional losses (12 trades, Sortino ~ 3.67)
+//!   - Bot D: Steady scalper with small losses (20 trades, Sortino ~ 2.24)
 //!
-//! Return values are chosen so that sharpe_sq_scaled is an exact integer
-//! (no field-division rounding needed for the public input).
+//! Return values are chosen so that sharpe_sq_scaled (and, for the bots with
+//! losing trades, sortino_sq_scaled) is an exact integer (no field-division
+//! rounding needed for the public input).
 
 use alloy_primitives::U256;
-use crate::field::BN254Field;
+use crate::field::signed_to_field;
 use crate::keccak::keccak_hash_two;
 
 /// Scale factor for Sharpe^2 to avoid field division in the public input.
 /// claimed_sharpe_sq_scaled = Sharpe^2 * SHARPE_SCALE
 pub const SHARPE_SCALE: u64 = 10000;
 
+/// Largest accepted |return| in basis points (2^31). Keeps squares within
+/// i64 and sums of squares over any realistic trade count within i128, so
+/// the field encoding and the integer accumulation never disagree.
+pub const MAX_RETURN_BPS: i64 = 1 << 31;
+
+/// Signed trade return in basis points. Negative for losing trades.
+///
+/// The single place the sign/scale convention lives: the field encoding
+/// (`to_field`), the integer square used by the SP1 guest (`checked_square`)
+/// and the GMX return computation all go through this type. Construction
+/// rejects |bps| > `MAX_RETURN_BPS` instead of clamping or wrapping.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "cli",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "i64", into = "i64")
+)]
+pub struct TradeReturn(i64);
+
+impl TradeReturn {
+    pub const ZERO: TradeReturn = TradeReturn(0);
+
+    /// Validate a signed basis-point return.
+    pub fn new(bps: i64) -> Result<Self, String> {
+        if bps.unsigned_abs() > MAX_RETURN_BPS as u64 {
+            return Err(format!(
+                "Trade return {} bps is outside +/-{} bps",
+                bps, MAX_RETURN_BPS
+            ));
+        }
+        Ok(TradeReturn(bps))
+    }
+
+    /// The raw signed basis-point value.
+    pub fn bps(self) -> i64 {
+        self.0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// BN254 encoding: non-negative values map to themselves, negative
+    /// values to BN254_PRIME - |bps| (`signed_to_field`).
+    pub fn to_field(self) -> U256 {
+        signed_to_field(self.0)
+    }
+
+    /// bps^2 as an integer. Always `Some` for validated values; kept checked
+    /// so accumulators never silently overflow.
+    pub fn checked_square(self) -> Option<i128> {
+        (self.0 as i128).checked_mul(self.0 as i128)
+    }
+}
+
+impl From<i32> for TradeReturn {
+    fn from(bps: i32) -> Self {
+        TradeReturn(bps as i64)
+    }
+}
+
+impl TryFrom<i64> for TradeReturn {
+    type Error = String;
+
+    fn try_from(bps: i64) -> Result<Self, String> {
+        TradeReturn::new(bps)
+    }
+}
+
+impl From<TradeReturn> for i64 {
+    fn from(r: TradeReturn) -> i64 {
+        r.0
+    }
+}
+
 /// A single GMX trade record with realistic fields.
 pub struct GmxTradeRecord {
     pub size_in_usd: U256,
@@ -29,13 +107,13 @@ pub struct GmxTradeRecord {
     pub funding_fee: U256,
     pub duration_seconds: u64,
     /// Signed return in basis points. Negative for losing trades.
-    pub return_bps: i64,
+    pub return_bps: TradeReturn,
 }
 
 impl GmxTradeRecord {
     /// Create a minimal GmxTradeRecord with only return_bps set.
     /// Used by WASM interface when only return values are provided.
-    pub fn from_return_bps(bps: i64) -> Self {
+    pub fn from_return_bps(bps: TradeReturn) -> Self {
         GmxTradeRecord {
             size_in_usd: U256::ZERO,
             size_in_tokens: U256::ZERO,
@@ -58,16 +136,16 @@ pub struct MockBot {
     pub trades: Vec<GmxTradeRecord>,
     /// Pre-computed sharpe_sq_scaled = Sharpe^2 * SHARPE_SCALE (exact integer).
     pub expected_sharpe_sq_scaled: u64,
+    /// Pre-computed sortino_sq_scaled = Sortino^2 * SHARPE_SCALE (exact
+    /// integer); `None` when no trade lost money (zero downside deviation).
+    pub expected_sortino_sq_scaled: Option<u64>,
 }
 
 /// Convert signed basis points to a BN254 field element.
 /// Negative values become BN254_PRIME - |bp| (modular negation).
+/// Prefer `TradeReturn::to_field` for trade returns.
 pub fn basis_points_to_field(bp: i64) -> U256 {
-    if bp >= 0 {
-        U256::from(bp as u64)
-    } else {
-        BN254Field::neg(U256::from((-bp) as u64))
-    }
+    signed_to_field(bp)
 }
 
 /// Compute a chained keccak hash of trade fields for dataset commitment.
@@ -103,7 +181,7 @@ fn make_trade(
         borrowing_fee: U256::from(borrow_fee),
         funding_fee: U256::from(fund_fee),
         duration_seconds: duration,
-        return_bps,
+        return_bps: TradeReturn::new(return_bps).expect("mock return in range"),
     }
 }
 
@@ -138,6 +216,7 @@ pub fn bot_a_aggressive_eth() -> MockBot {
         name: "bot_a_aggressive_eth",
         trades,
         expected_sharpe_sq_scaled: 60000,
+        expected_sortino_sq_scaled: None,
     }
 }
 
@@ -188,13 +267,90 @@ pub fn bot_b_safe_hedger() -> MockBot {
         name: "bot_b_safe_hedger",
         trades,
         expected_sharpe_sq_scaled: 18750,
+        expected_sortino_sq_scaled: None,
+    }
+}
+
+/// Bot C: Swing trader. 12 trades: 10 winners of 200bp, 2 losers of -100bp.
+///
+/// cum_ret = 1800, cum_sq = 420000, cum_downside_sq = 20000, N = 12
+/// sharpe_sq_scaled = 1800^2 * 10000 / (12 * 420000 - 1800^2) = 18000
+/// sortino_sq_scaled = 1800^2 * 10000 / (12 * 20000) = 135000
+/// Sortino = sqrt(13.5) ~ 3.674
+pub fn bot_c_swing_trader() -> MockBot {
+    let trades: Vec<GmxTradeRecord> = (0..12)
+        .map(|i| {
+            // Losses on the 4th and 10th trade
+            let bp: i64 = if i % 6 == 3 { -100 } else { 200 };
+            make_trade(
+                30000 + i as u64 * 1500,    // size_in_usd: $30k-$46.5k
+                15 + i as u64,              // size_in_tokens
+                8000 + i as u64 * 400,      // collateral
+                i % 3 == 0,                 // mostly short
+                210_000 + i as u64 * 40,    // entry_price ($2100+)
+                (210_000 + i as u64 * 40).saturating_add_signed(bp * 20), // exit_price
+                bp.unsigned_abs() * 30,     // realized_pnl (absolute)
+                12 + i as u64,              // borrowing_fee
+                6 + i as u64,               // funding_fee
+                14400 + i as u64 * 1800,    // duration: 4h to 9.5h
+                bp,
+            )
+        })
+        .collect();
+
+    MockBot {
+        name: "bot_c_swing_trader",
+        trades,
+        expected_sharpe_sq_scaled: 18000,
+        expected_sortino_sq_scaled: Some(135000),
+    }
+}
+
+/// Bot D: Steady scalper. 20 trades: 16 winners of 150bp, 4 losers of -100bp.
+///
+/// cum_ret = 2000, cum_sq = 400000, cum_downside_sq = 40000, N = 20
+/// sharpe_sq_scaled = 2000^2 * 10000 / (20 * 400000 - 2000^2) = 10000
+/// sortino_sq_scaled = 2000^2 * 10000 / (20 * 40000) = 50000
+/// Sortino = sqrt(5) ~ 2.236
+pub fn bot_d_steady_scalper() -> MockBot {
+    let trades: Vec<GmxTradeRecord> = (0..20)
+        .map(|i| {
+            // Every fifth trade is a small loss
+            let bp: i64 = if i % 5 == 4 { -100 } else { 150 };
+            make_trade(
+                10000 + i as u64 * 250,     // size_in_usd: $10k-$14.75k
+                5 + i as u64,               // size_in_tokens
+                2500 + i as u64 * 50,       // collateral
+                i % 2 == 1,                 // alternating short/long
+                195_000 + i as u64 * 10,    // entry_price ($1950+)
+                (195_000 + i as u64 * 10).saturating_add_signed(bp * 20), // exit_price
+                bp.unsigned_abs() * 10,     // realized_pnl (absolute)
+                4 + i as u64,               // borrowing_fee
+                2 + i as u64,               // funding_fee
+                900 + i as u64 * 120,       // duration: 15min to 53min
+                bp,
+            )
+        })
+        .collect();
+
+    MockBot {
+        name: "bot_d_steady_scalper",
+        trades,
+        expected_sharpe_sq_scaled: 10000,
+        expected_sortino_sq_scaled: Some(50000),
     }
 }
 
+/// The SP1 benchmark guest's Sharpe formula, compiled from its source so the
+/// tests can hold it to the same mock bots.
+#[cfg(test)]
+#[path = "../../benchmark/sp1-sharpe/program/src/sharpe.rs"]
+mod sp1_guest;
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::field::BN254_PRIME;
+    use crate::field::{BN254Field, BN254_PRIME};
 
     #[test]
     fn test_basis_points_to_field_positive() {
@@ -225,6 +381,37 @@ mod tests {
         assert_eq!(pos_sq, neg_sq);
     }
 
+    #[test]
+    fn test_trade_return_field_matches_basis_points() {
+        let bound = MAX_RETURN_BPS;
+        for bps in [0, 1, -1, 100, -100, 9999, -12345, bound - 1, -(bound - 1), bound, -bound] {
+            let r = TradeReturn::new(bps).unwrap();
+            assert_eq!(r.to_field(), basis_points_to_field(bps), "bps = {}", bps);
+            assert_eq!(r.bps(), bps);
+            assert_eq!(r.is_negative(), bps < 0);
+        }
+    }
+
+    #[test]
+    fn test_trade_return_rejects_out_of_range() {
+        let bound = MAX_RETURN_BPS;
+        for bps in [bound + 1, -(bound + 1), i64::MAX, i64::MIN, i64::MIN + 1] {
+            assert!(TradeReturn::new(bps).is_err(), "bps = {} must be rejected", bps);
+            assert!(TradeReturn::try_from(bps).is_err());
+        }
+        assert_eq!(TradeReturn::from(i32::MIN).bps(), i32::MIN as i64);
+    }
+
+    #[test]
+    fn test_trade_return_checked_square() {
+        let r = TradeReturn::new(-MAX_RETURN_BPS).unwrap();
+        assert_eq!(r.checked_square(), Some(1i128 << 62));
+        assert_eq!(TradeReturn::from(-300).checked_square(), Some(90000));
+        // Field square agrees with the integer square.
+        let sq = BN254Field::mul(r.to_field(), r.to_field());
+        assert_eq!(sq, U256::from(1u64 << 62));
+    }
+
     #[test]
     fn test_bot_a_trade_count() {
         let bot = bot_a_aggressive_eth();
@@ -247,7 +434,7 @@ mod tests {
         let mut cum_ret = U256::ZERO;
         let mut cum_sq = U256::ZERO;
         for trade in &bot.trades {
-            let ret_field = basis_points_to_field(trade.return_bps);
+            let ret_field = trade.return_bps.to_field();
             let ret_sq = BN254Field::mul(ret_field, ret_field);
             cum_ret = BN254Field::add(cum_ret, ret_field);
             cum_sq = BN254Field::add(cum_sq, ret_sq);
@@ -274,7 +461,7 @@ mod tests {
         let mut cum_ret = U256::ZERO;
         let mut cum_sq = U256::ZERO;
         for trade in &bot.trades {
-            let ret_field = basis_points_to_field(trade.return_bps);
+            let ret_field = trade.return_bps.to_field();
             let ret_sq = BN254Field::mul(ret_field, ret_field);
             cum_ret = BN254Field::add(cum_ret, ret_field);
             cum_sq = BN254Field::add(cum_sq, ret_sq);
@@ -292,6 +479,67 @@ mod tests {
         assert_eq!(lhs, rhs, "Sharpe equation must hold exactly in the field");
     }
 
+    /// Hand-computed claim, SP1 guest integer math and STARK trace field
+    /// math must agree on the winning bots' Sharpe values.
+    #[test]
+    fn test_sp1_guest_sharpe_matches() {
+        assert_eq!(sp1_guest::SHARPE_SCALE, SHARPE_SCALE as i128);
+        for bot in [bot_a_aggressive_eth(), bot_b_safe_hedger()] {
+            let returns: Vec<i64> = bot.trades.iter().map(|t| t.return_bps.bps()).collect();
+            let (trade_count, total_return, sharpe_sq_scaled) = sp1_guest::compute(&returns);
+            assert_eq!(trade_count, bot.trades.len() as u64, "{}", bot.name);
+            assert_eq!(total_return, returns.iter().sum::<i64>(), "{}", bot.name);
+            assert_eq!(sharpe_sq_scaled, bot.expected_sharpe_sq_scaled, "{}", bot.name);
+
+            let trace = crate::sharpe_trace::SharpeTrace::generate(&bot.trades, None);
+            assert_eq!(trace.compute_sharpe_sq_scaled(), U256::from(sharpe_sq_scaled), "{}", bot.name);
+        }
+    }
+
+    /// (cum_ret, cum_sq, cum_downside_sq) over the bot's returns as field elements.
+    fn cumulative_sums(bot: &MockBot) -> (U256, U256, U256) {
+        let mut sums = (U256::ZERO, U256::ZERO, U256::ZERO);
+        for trade in &bot.trades {
+            let ret_field = trade.return_bps.to_field();
+            let ret_sq = BN254Field::mul(ret_field, ret_field);
+            sums.0 = BN254Field::add(sums.0, ret_field);
+            sums.1 = BN254Field::add(sums.1, ret_sq);
+            if trade.return_bps.is_negative() {
+                sums.2 = BN254Field::add(sums.2, ret_sq);
+            }
+        }
+        sums
+    }
+
+    #[test]
+    fn test_losing_bots_ratio_equations() {
+        let scale = U256::from(SHARPE_SCALE);
+        for (bot, expected_len) in [(bot_c_swing_trader(), 12u64), (bot_d_steady_scalper(), 20)] {
+            assert_eq!(bot.trades.len() as u64, expected_len);
+            let n = U256::from(expected_len);
+            let (cum_ret, cum_sq, cum_downside_sq) = cumulative_sums(&bot);
+            let lhs = BN254Field::mul(BN254Field::mul(cum_ret, cum_ret), scale);
+
+            // Sharpe: cum_ret^2 * SCALE = claimed * (N * cum_sq - cum_ret^2)
+            let denom = BN254Field::sub(BN254Field::mul(n, cum_sq), BN254Field::mul(cum_ret, cum_ret));
+            let rhs = BN254Field::mul(U256::from(bot.expected_sharpe_sq_scaled), denom);
+            assert_eq!(lhs, rhs, "{}: Sharpe equation must hold exactly", bot.name);
+
+            // Sortino: cum_ret^2 * SCALE = claimed * N * cum_downside_sq
+            let claimed = U256::from(bot.expected_sortino_sq_scaled.unwrap());
+            let rhs = BN254Field::mul(claimed, BN254Field::mul(n, cum_downside_sq));
+            assert_eq!(lhs, rhs, "{}: Sortino equation must hold exactly", bot.name);
+        }
+    }
+
+    #[test]
+    fn test_winning_bots_have_no_sortino() {
+        for bot in [bot_a_aggressive_eth(), bot_b_safe_hedger()] {
+            assert!(bot.trades.iter().all(|t| !t.return_bps.is_negative()));
+            assert_eq!(bot.expected_sortino_sq_scaled, None);
+        }
+    }
+
     #[test]
     fn test_trade_leaf_hash_deterministic() {
         let bot = bot_a_aggressive_eth();
@@ -0,0 +1,183 @@
+//! Trace Padding Policies
+//!
+//! Traces are padded from the actual trade count to the next power of 2.
+//! Each AIR declares, per column, how its padding rows are filled
+//! (`sharpe_compose::PADDING`, `sortino_compose::PADDING`) to match what its
+//! transition constraints assume. The trace generators apply that declaration
+//! instead of hardcoding it, and check the padded rows against the AIR's
+//! transition constraints, so a policy/constraint mismatch fails at trace
+//! generation instead of producing a proof the verifier rejects.
+
+use alloy_primitives::U256;
+
+use crate::sharpe_compose::ConstraintViolation;
+
+/// How a trace column is filled on padding rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaddingPolicy {
+    /// Repeat the last real row's value (running accumulators).
+    CarryForward,
+    /// Repeat the column's value, which must already be the same on every
+    /// real row (columns under an immutability constraint).
+    HoldConstant,
+    /// Fill with zero (per-row inputs and selectors).
+    Zero,
+}
+
+impl PaddingPolicy {
+    /// Extend `column`, holding the real rows, to `trace_len` rows.
+    pub fn pad(self, column: &mut Vec<U256>, trace_len: usize) {
+        let fill = match self {
+            PaddingPolicy::Zero => U256::ZERO,
+            PaddingPolicy::CarryForward => *column.last().expect("column has no real rows"),
+            PaddingPolicy::HoldConstant => {
+                let value = *column.first().expect("column has no real rows");
+                assert!(
+                    column.iter().all(|&v| v == value),
+                    "HoldConstant column varies across real rows"
+                );
+                value
+            }
+        };
+        column.resize(trace_len, fill);
+    }
+}
+
+/// Pad every column to `trace_len` under its declared policy.
+pub fn pad_columns<const W: usize>(
+    columns: [&mut Vec<U256>; W],
+    policies: &[PaddingPolicy; W],
+    trace_len: usize,
+) {
+    for (column, policy) in columns.into_iter().zip(policies) {
+        policy.pad(column, trace_len);
+    }
+}
+
+/// Transition constraints that fail on the padded part of a trace: the step
+/// from the last real row into padding, and every step between padding rows.
+/// Empty when the padding policies agree with the constraints.
+pub(crate) fn padding_violations<const W: usize, const T: usize>(
+    columns: &[&[U256]; W],
+    actual_count: usize,
+    transition: impl Fn([U256; W], [U256; W]) -> [U256; T],
+) -> Vec<ConstraintViolation> {
+    let trace_len = columns[0].len();
+    let row = |r: usize| -> [U256; W] { core::array::from_fn(|c| columns[c][r]) };
+    let mut violations = Vec::new();
+
+    for r in actual_count.saturating_sub(1)..trace_len.saturating_sub(1) {
+        for (index, tc) in transition(row(r), row(r + 1)).iter().enumerate() {
+            if *tc != U256::ZERO {
+                violations.push(ConstraintViolation::Transition { index, row: r });
+            }
+        }
+    }
+    violations
+}
+
+/// Padding threshold the CLI and WASM bindings warn at: more than half the
+/// trace is padding.
+pub const DEFAULT_WARN_PADDING_RATIO: f32 = 0.5;
+
+/// A trade count whose power-of-2 trace is mostly padding rows, which the
+/// prover still pays for in LDE, commitment and FRI work.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaddingWarning {
+    pub actual_count: usize,
+    pub trace_len: usize,
+}
+
+impl PaddingWarning {
+    /// Fraction of the trace that is padding.
+    pub fn ratio(&self) -> f32 {
+        padding_ratio(self.actual_count)
+    }
+}
+
+impl core::fmt::Display for PaddingWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} trades pad to {} rows ({:.0}% padding); batch trades to just under a power of two, e.g. {}",
+            self.actual_count,
+            self.trace_len,
+            self.ratio() * 100.0,
+            self.trace_len / 2
+        )
+    }
+}
+
+/// Fraction of the padded trace for `actual_count` trades that is padding.
+pub fn padding_ratio(actual_count: usize) -> f32 {
+    let trace_len = actual_count.next_power_of_two();
+    (trace_len - actual_count) as f32 / trace_len as f32
+}
+
+/// `Some` when padding `actual_count` trades wastes more than `max_ratio`
+/// of the trace.
+pub fn padding_warning(actual_count: usize, max_ratio: f32) -> Option<PaddingWarning> {
+    (padding_ratio(actual_count) > max_ratio).then(|| PaddingWarning {
+        actual_count,
+        trace_len: actual_count.next_power_of_two(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::BN254Field;
+
+    fn col(values: &[u64]) -> Vec<U256> {
+        values.iter().map(|&v| U256::from(v)).collect()
+    }
+
+    #[test]
+    fn test_pad_policies() {
+        let mut zero = col(&[3, 5]);
+        PaddingPolicy::Zero.pad(&mut zero, 4);
+        assert_eq!(zero, col(&[3, 5, 0, 0]));
+
+        let mut carried = col(&[3, 8]);
+        PaddingPolicy::CarryForward.pad(&mut carried, 4);
+        assert_eq!(carried, col(&[3, 8, 8, 8]));
+
+        let mut constant = col(&[7, 7, 7]);
+        PaddingPolicy::HoldConstant.pad(&mut constant, 4);
+        assert_eq!(constant, col(&[7, 7, 7, 7]));
+    }
+
+    #[test]
+    #[should_panic(expected = "HoldConstant column varies")]
+    fn test_hold_constant_rejects_varying_column() {
+        PaddingPolicy::HoldConstant.pad(&mut col(&[7, 8]), 4);
+    }
+
+    #[test]
+    fn test_padding_warning_threshold() {
+        // 17 -> 32 rows is ~47% padding, 15 -> 16 rows ~6%
+        let warning = padding_warning(17, 0.4).unwrap();
+        assert_eq!(warning, PaddingWarning { actual_count: 17, trace_len: 32 });
+        assert!(warning.to_string().contains("17 trades pad to 32 rows (47% padding)"));
+        assert!(padding_warning(15, 0.4).is_none());
+
+        // Bot B: 23 -> 32 rows, ~28%
+        assert!(padding_warning(23, DEFAULT_WARN_PADDING_RATIO).is_none());
+        assert!(padding_warning(23, 0.25).is_some());
+        assert_eq!(padding_ratio(16), 0.0);
+    }
+
+    #[test]
+    fn test_padding_violations_start_at_last_real_row() {
+        // Toy AIR: next = current + 1 on every step
+        let transition = |cur: [U256; 1], next: [U256; 1]| {
+            [BN254Field::sub(next[0], BN254Field::add(cur[0], U256::from(1u64)))]
+        };
+        let column = col(&[0, 1, 2, 2]);
+        // The real rows are consistent; only the step into padding breaks
+        assert_eq!(
+            padding_violations(&[&column[..]], 3, transition),
+            vec![ConstraintViolation::Transition { index: 0, row: 2 }]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! Poseidon hash for the prover
+//!
+//! circomlib-compatible Poseidon (t = 3, x^5, 8 full + 57 partial rounds).
+//! The permutation is `stark_core::poseidon`, the same code the on-chain
+//! verifier runs; this module adapts it to canonical U256 values.
+
+use alloy_primitives::U256;
+use stark_core::field::Fp;
+
+/// Poseidon hash of two field elements: state = [0, a, b], output state[0].
+///
+/// Inputs are reduced mod p; the output is canonical.
+pub fn poseidon_hash_two(a: U256, b: U256) -> U256 {
+    stark_core::poseidon::poseidon_hash_two(Fp::from_u256(a), Fp::from_u256(b)).to_u256()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::BN254_PRIME;
+
+    /// circomlib: poseidon([1, 2])
+    #[test]
+    fn test_poseidon_circomlib_vector() {
+        let h = poseidon_hash_two(U256::from(1u64), U256::from(2u64));
+        let expected = U256::from_str_radix(
+            "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+            16,
+        )
+        .unwrap();
+        assert_eq!(h, expected);
+    }
+
+    #[test]
+    fn test_poseidon_reduces_inputs() {
+        let one = U256::from(1u64);
+        let two = U256::from(2u64);
+        assert_eq!(poseidon_hash_two(one + BN254_PRIME, two), poseidon_hash_two(one, two));
+    }
+}
@@ -5,55 +5,243 @@
 
 use alloy_primitives::U256;
 
+use crate::channel::TranscriptKind;
+use crate::commit::MerkleScheme;
+use stark_core::keccak::{keccak256, packed_words};
+
 /// Serialized proof ready for on-chain submission.
+///
+/// With the `cli` feature it deserializes from the `to_json` format
+/// (camelCase keys, `0x`-prefixed hex words).
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "cli", derive(serde::Deserialize))]
+#[cfg_attr(feature = "cli", serde(rename_all = "camelCase", deny_unknown_fields))]
 pub struct SerializedProof {
+    #[cfg_attr(feature = "cli", serde(deserialize_with = "hex_words"))]
     pub public_inputs: Vec<U256>,
+    #[cfg_attr(feature = "cli", serde(deserialize_with = "hex_words"))]
     pub commitments: Vec<U256>,
+    #[cfg_attr(feature = "cli", serde(deserialize_with = "hex_words"))]
     pub ood_values: Vec<U256>,
+    #[cfg_attr(feature = "cli", serde(deserialize_with = "hex_words"))]
     pub fri_final_poly: Vec<U256>,
+    #[cfg_attr(feature = "cli", serde(deserialize_with = "hex_words"))]
     pub query_values: Vec<U256>,
+    #[cfg_attr(feature = "cli", serde(deserialize_with = "hex_words"))]
     pub query_paths: Vec<U256>,
+    #[cfg_attr(feature = "cli", serde(deserialize_with = "hex_words"))]
     pub query_metadata: Vec<U256>,
 }
 
+/// Deserialize a JSON array of `0x`-prefixed hex strings into field words.
+#[cfg(feature = "cli")]
+fn hex_words<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<U256>, D::Error> {
+    use serde::de::Error;
+    let words: Vec<String> = serde::Deserialize::deserialize(deserializer)?;
+    words
+        .iter()
+        .map(|w| {
+            let digits = w
+                .strip_prefix("0x")
+                .ok_or_else(|| D::Error::custom(format!("missing 0x prefix: {}", w)))?;
+            U256::from_str_radix(digits, 16).map_err(D::Error::custom)
+        })
+        .collect()
+}
+
+/// Words of `query_metadata` before the query indices.
+pub const METADATA_HEADER_LEN: usize = 4;
+
+/// Decoded `query_metadata`, mirroring the verifier's `proof::QueryMetadata`.
+///
+/// Word layout (`to_words` / `from_words` are the only code that knows it):
+///
+/// - `[0]`: num_queries | transcript << 64 | k << 128 | merkle_scheme << 192,
+///   where k = 0 reads as a single composition segment
+/// - `[1]`: num_fri_layers | num_columns << 64
+/// - `[2]`: log_trace_len | blowup << 64 | grinding_bits << 128 |
+///   composition_lde_len << 192, where blowup = 0 reads as `BLOWUP` and
+///   composition_lde_len = 0 as trace_len · blowup
+/// - `[3]`: protocol hash (`PROTOCOL_HASH`)
+/// - then one word per query index and an optional trailing leaf salt
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryMetadata {
+    pub transcript: TranscriptKind,
+    pub num_segments: usize,
+    pub merkle_scheme: MerkleScheme,
+    pub num_fri_layers: usize,
+    pub num_columns: usize,
+    pub log_trace_len: u32,
+    pub blowup: u32,
+    pub grinding_bits: u32,
+    /// Length of each committed composition LDE (the LDE domain size).
+    pub composition_lde_len: u64,
+    pub protocol_hash: U256,
+    pub indices: Vec<usize>,
+    pub leaf_salt: Option<U256>,
+}
+
+impl QueryMetadata {
+    pub fn num_queries(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Encode into words; defaults (one segment, `BLOWUP`, an LDE of
+    /// trace_len · blowup) are written as zero, so
+    /// `from_words(&m.to_words()) == Ok(m)`.
+    pub fn to_words(&self) -> Vec<U256> {
+        let segments = if self.num_segments > 1 { self.num_segments as u64 } else { 0 };
+        let blowup = if self.blowup == crate::BLOWUP { 0 } else { self.blowup as u64 };
+        let lde_len = if Some(self.composition_lde_len) == default_lde_len(self.log_trace_len, self.blowup) {
+            0
+        } else {
+            self.composition_lde_len
+        };
+
+        let mut words = Vec::with_capacity(METADATA_HEADER_LEN + self.indices.len() + 1);
+        words.push(U256::from_limbs([
+            self.indices.len() as u64,
+            self.transcript.tag(),
+            segments,
+            self.merkle_scheme.tag(),
+        ]));
+        words.push(U256::from_limbs([self.num_fri_layers as u64, self.num_columns as u64, 0, 0]));
+        words.push(U256::from_limbs([self.log_trace_len as u64, blowup, self.grinding_bits as u64, lde_len]));
+        words.push(self.protocol_hash);
+        words.extend(self.indices.iter().map(|&idx| U256::from(idx as u64)));
+        words.extend(self.leaf_salt);
+        words
+    }
+
+    /// Decode the word layout (no check of the values themselves).
+    pub fn from_words(words: &[U256]) -> Result<Self, String> {
+        if words.len() < METADATA_HEADER_LEN {
+            return Err(format!("query_metadata has {} words, need at least {}", words.len(), METADATA_HEADER_LEN));
+        }
+        let head = words[0].as_limbs();
+        let layers = words[1].as_limbs();
+        let trace = words[2].as_limbs();
+        if layers[2] != 0 || layers[3] != 0 {
+            return Err("query_metadata reserved limbs must be zero".to_string());
+        }
+
+        let num_queries = usize::try_from(head[0]).map_err(|e| e.to_string())?;
+        let indices_end = METADATA_HEADER_LEN
+            .checked_add(num_queries)
+            .filter(|&end| end <= words.len())
+            .ok_or_else(|| format!("query_metadata too short for {} query indices", num_queries))?;
+        let leaf_salt = match words.len() - indices_end {
+            0 => None,
+            1 => Some(words[indices_end]),
+            extra => return Err(format!("query_metadata has {} trailing words, expected at most 1", extra)),
+        };
+        let narrow = |limb: u64, name: &str| {
+            u32::try_from(limb).map_err(|_| format!("query_metadata {} {} out of range", name, limb))
+        };
+
+        let log_trace_len = narrow(trace[0], "log_trace_len")?;
+        let blowup = match trace[1] {
+            0 => crate::BLOWUP,
+            b => narrow(b, "blowup")?,
+        };
+        let composition_lde_len = match trace[3] {
+            0 => default_lde_len(log_trace_len, blowup)
+                .ok_or_else(|| format!("query_metadata LDE length overflows for log_trace_len {}", log_trace_len))?,
+            n => n,
+        };
+
+        Ok(QueryMetadata {
+            transcript: TranscriptKind::from_tag(head[1])
+                .ok_or_else(|| format!("unknown transcript tag {}", head[1]))?,
+            num_segments: (head[2] as usize).max(1),
+            merkle_scheme: MerkleScheme::from_tag(head[3])
+                .ok_or_else(|| format!("unknown merkle scheme tag {}", head[3]))?,
+            num_fri_layers: layers[0] as usize,
+            num_columns: layers[1] as usize,
+            log_trace_len,
+            blowup,
+            grinding_bits: narrow(trace[2], "grinding_bits")?,
+            composition_lde_len,
+            protocol_hash: words[3],
+            indices: words[METADATA_HEADER_LEN..indices_end]
+                .iter()
+                .map(|idx| idx.as_limbs()[0] as usize)
+                .collect(),
+            leaf_salt,
+        })
+    }
+}
+
+/// trace_len · blowup, the LDE length a zero `composition_lde_len` limb stands for.
+fn default_lde_len(log_trace_len: u32, blowup: u32) -> Option<u64> {
+    1u64.checked_shl(log_trace_len)?.checked_mul(blowup as u64)
+}
+
 impl SerializedProof {
     /// Create a new serialized Sharpe proof.
     ///
-    /// OOD values layout: [6 trace at z, 6 trace at zg, 1 composition] = 13 elements.
+    /// public_inputs: [trade_count, total_return, sharpe_sq_scaled, merkle_root, as_of_block?]
+    ///
+    /// commitments layout: [trace, composition segments..., FRI layers...].
+    ///
+    /// OOD values layout: [W trace at z, W trace at zg, 1 per composition segment]
+    /// for a W-column trace (16 elements for the 7-column Sharpe trace with its
+    /// minimum two segments). Sortino proofs use the same layout with W = 8.
+    ///
+    /// query_metadata is `QueryMetadata::to_words`, declaring the trace width
+    /// W so the verifier can check the OOD length against it. The trailing
+    /// leaf salt is present only for salted proofs. Keccak-transcript proofs
+    /// are emitted; see `with_transcript`.
     pub fn new_sharpe(
-        public_inputs: [U256; 4],
+        public_inputs: &[U256],
         trace_commitment: U256,
-        composition_commitment: U256,
+        composition_commitments: &[U256],
         fri_layer_roots: &[U256],
-        trace_ood_evals: [U256; 6],
-        trace_ood_evals_next: [U256; 6],
-        composition_ood_eval: U256,
+        trace_ood_evals: &[U256],
+        trace_ood_evals_next: &[U256],
+        composition_ood_evals: &[U256],
         fri_final_poly: &[U256],
         query_indices: &[usize],
         query_values: &[U256],
         query_paths: &[U256],
         num_fri_layers: usize,
         log_trace_len: u32,
+        leaf_salt: Option<U256>,
     ) -> Self {
-        let mut commitments = Vec::with_capacity(2 + fri_layer_roots.len());
+        let num_segments = composition_commitments.len();
+        let mut commitments = Vec::with_capacity(1 + num_segments + fri_layer_roots.len());
         commitments.push(trace_commitment);
-        commitments.push(composition_commitment);
+        commitments.extend_from_slice(composition_commitments);
         commitments.extend_from_slice(fri_layer_roots);
 
-        // ood_values: 6 trace at z + 6 trace at zg + 1 comp = 13
-        let mut ood_values = Vec::with_capacity(13);
-        ood_values.extend_from_slice(&trace_ood_evals);
-        ood_values.extend_from_slice(&trace_ood_evals_next);
-        ood_values.push(composition_ood_eval);
-
-        let num_queries = query_indices.len();
-        let mut query_metadata = Vec::with_capacity(3 + num_queries);
-        query_metadata.push(U256::from(num_queries as u64));
-        query_metadata.push(U256::from(num_fri_layers as u64));
-        query_metadata.push(U256::from(log_trace_len as u64));
-        for &idx in query_indices {
-            query_metadata.push(U256::from(idx as u64));
+        // ood_values: W trace at z + W trace at zg + 1 per composition segment
+        let mut ood_values = Vec::with_capacity(2 * trace_ood_evals.len() + num_segments);
+        ood_values.extend_from_slice(trace_ood_evals);
+        ood_values.extend_from_slice(trace_ood_evals_next);
+        ood_values.extend_from_slice(composition_ood_evals);
+        // The verifier's parse_stark_proof splits at W and 2W and reads one
+        // value per committed segment
+        let width = trace_ood_evals.len();
+        debug_assert_eq!(trace_ood_evals_next.len(), width, "OOD values at z and zg differ in width");
+        debug_assert_eq!(composition_ood_evals.len(), num_segments, "one composition OOD value per segment");
+        debug_assert_eq!(ood_values.len(), 2 * width + num_segments);
+        debug_assert_eq!(&ood_values[width..2 * width], trace_ood_evals_next);
+
+        let query_metadata = QueryMetadata {
+            transcript: TranscriptKind::Keccak,
+            num_segments,
+            merkle_scheme: MerkleScheme::Ordered,
+            num_fri_layers,
+            num_columns: trace_ood_evals.len(),
+            log_trace_len,
+            blowup: crate::BLOWUP,
+            grinding_bits: 0,
+            composition_lde_len: (1u64 << log_trace_len) * crate::BLOWUP as u64,
+            protocol_hash: crate::PROTOCOL_HASH,
+            indices: query_indices.to_vec(),
+            leaf_salt,
         }
+        .to_words();
 
         SerializedProof {
             public_inputs: public_inputs.to_vec(),
@@ -66,6 +254,50 @@ impl SerializedProof {
         }
     }
 
+    /// Append the per-query trace/composition openings after the FRI data.
+    ///
+    /// query_values gains, per query: [W trace column values, composition segment values];
+    /// query_paths gains, per query: [trace Merkle path, one path per composition segment].
+    pub fn with_trace_openings(mut self, values: &[U256], paths: &[U256]) -> Self {
+        self.query_values.extend_from_slice(values);
+        self.query_paths.extend_from_slice(paths);
+        self
+    }
+
+    /// Tag the proof with its Fiat-Shamir transcript hash.
+    ///
+    /// Recorded in `QueryMetadata::transcript`.
+    pub fn with_transcript(self, kind: TranscriptKind) -> Self {
+        self.map_metadata(|m| m.transcript = kind)
+    }
+
+    /// Tag the proof with the pairing scheme of its dataset-commitment tree.
+    ///
+    /// Recorded in `QueryMetadata::merkle_scheme`.
+    pub fn with_merkle_scheme(self, scheme: MerkleScheme) -> Self {
+        self.map_metadata(|m| m.merkle_scheme = scheme)
+    }
+
+    /// Record the length of the committed composition LDEs.
+    ///
+    /// Recorded in `QueryMetadata::composition_lde_len`; the verifier rejects
+    /// any length but its own LDE domain size.
+    pub fn with_composition_lde_len(self, len: usize) -> Self {
+        self.map_metadata(|m| m.composition_lde_len = len as u64)
+    }
+
+    /// Decoded `query_metadata`.
+    pub fn metadata(&self) -> Result<QueryMetadata, String> {
+        QueryMetadata::from_words(&self.query_metadata)
+    }
+
+    fn map_metadata(mut self, f: impl FnOnce(&mut QueryMetadata)) -> Self {
+        let mut metadata = self.metadata().expect("prover emits well-formed metadata");
+        f(&mut metadata);
+        self.query_metadata = metadata.to_words();
+        self
+    }
+
     /// Serialize to JSON for easy transport.
     pub fn to_json(&self) -> String {
         let fmt_vec = |v: &[U256]| -> String {
@@ -85,6 +317,76 @@ impl SerializedProof {
         )
     }
 
+    /// Parse a proof previously written by `to_json`.
+    #[cfg(feature = "cli")]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("invalid proof JSON: {}", e))
+    }
+
+    /// The seven arrays in calldata order.
+    fn sections(&self) -> [&[U256]; PACKED_SECTIONS] {
+        [
+            &self.public_inputs,
+            &self.commitments,
+            &self.ood_values,
+            &self.fri_final_poly,
+            &self.query_values,
+            &self.query_paths,
+            &self.query_metadata,
+        ]
+    }
+
+    /// Serialize to the packed binary format taken by `verify_sharpe_packed`.
+    ///
+    /// Layout: seven big-endian u32 word counts (public inputs, commitments,
+    /// OOD values, FRI final poly, query values, query paths, query metadata),
+    /// then every word as 32 big-endian bytes in the same order. Nothing else
+    /// may follow, so each proof has exactly one encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let sections = self.sections();
+        let total_words: usize = sections.iter().map(|s| s.len()).sum();
+        let mut out = Vec::with_capacity(PACKED_HEADER_LEN + 32 * total_words);
+        for section in &sections {
+            out.extend_from_slice(&(section.len() as u32).to_be_bytes());
+        }
+        for word in sections.iter().flat_map(|s| s.iter()) {
+            out.extend_from_slice(&word.to_be_bytes::<32>());
+        }
+        out
+    }
+
+    /// Parse a proof previously written by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < PACKED_HEADER_LEN {
+            return Err("packed proof shorter than its header".into());
+        }
+        let (header, body) = bytes.split_at(PACKED_HEADER_LEN);
+        let counts: Vec<usize> = header
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]) as usize)
+            .collect();
+        let total_words: u64 = counts.iter().map(|&c| c as u64).sum();
+        if body.len() as u64 != 32 * total_words {
+            return Err(format!(
+                "packed proof body is {} bytes, header declares {} words",
+                body.len(),
+                total_words
+            ));
+        }
+
+        let mut words = body.chunks_exact(32).map(U256::from_be_slice);
+        let mut next = |n: usize| words.by_ref().take(n).collect::<Vec<U256>>();
+        Ok(SerializedProof {
+            public_inputs: next(counts[0]),
+            commitments: next(counts[1]),
+            ood_values: next(counts[2]),
+            fri_final_poly: next(counts[3]),
+            query_values: next(counts[4]),
+            query_paths: next(counts[5]),
+            query_metadata: next(counts[6]),
+        })
+    }
+
     /// Total calldata size estimate in bytes.
     pub fn calldata_size(&self) -> usize {
         let total_words = self.public_inputs.len()
@@ -94,16 +396,23 @@ impl SerializedProof {
             + self.query_values.len()
             + self.query_paths.len()
             + self.query_metadata.len();
-        // Each U256 = 32 bytes, plus ABI overhead (~7 * 64 bytes for array pointers/lengths)
-        total_words * 32 + 7 * 64
+        calldata_bytes(total_words)
+    }
+
+    /// keccak256 of the public inputs as packed words, the `input_hash` the
+    /// verifier's `verify_sharpe_by_input_hash` checks them against
+    /// (Solidity: `keccak256(abi.encodePacked(publicInputs))`).
+    pub fn public_input_hash(&self) -> U256 {
+        U256::from_be_bytes(keccak256(&packed_words(&self.public_inputs)))
     }
 
     /// Print a human-readable summary.
     pub fn summary(&self) -> String {
+        let num_segments = self.metadata().map_or(1, |m| m.num_segments);
         format!(
             "STARK Proof Summary:\n\
              - Public inputs: {} elements\n\
-             - Commitments: {} (trace + comp + {} FRI layers)\n\
+             - Commitments: {} (trace + {} comp + {} FRI layers)\n\
              - OOD values: {} elements\n\
              - FRI final poly: {} coefficients\n\
              - Query values: {} elements\n\
@@ -112,7 +421,8 @@ impl SerializedProof {
              - Estimated calldata: {} bytes ({:.1} KB)",
             self.public_inputs.len(),
             self.commitments.len(),
-            self.commitments.len() - 2,
+            num_segments,
+            self.commitments.len() - 1 - num_segments,
             self.ood_values.len(),
             self.fri_final_poly.len(),
             self.query_values.len(),
@@ -124,6 +434,18 @@ impl SerializedProof {
     }
 }
 
+/// Number of arrays in a proof (and of word counts in the packed header).
+pub const PACKED_SECTIONS: usize = 7;
+
+/// Length of the packed format's header: one u32 word count per array.
+pub const PACKED_HEADER_LEN: usize = 4 * PACKED_SECTIONS;
+
+/// Calldata bytes for a proof totalling `total_words` field elements.
+pub(crate) fn calldata_bytes(total_words: usize) -> usize {
+    // Each U256 = 32 bytes, plus ABI overhead (~7 * 64 bytes for array pointers/lengths)
+    total_words * 32 + 7 * 64
+}
+
 /// Convert proof data to hex-encoded calldata for direct contract call.
 pub fn encode_calldata_hex(proof: &SerializedProof) -> String {
     // Simple hex encoding of all U256 values
@@ -133,3 +455,132 @@ pub fn encode_calldata_hex(proof: &SerializedProof) -> String {
     }
     hex
 }
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use super::*;
+    use crate::channel::TranscriptKind;
+    use crate::{prove_sharpe_with_options, ProveOptions};
+
+    #[test]
+    fn test_json_roundtrip() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let options = ProveOptions {
+            leaf_salt: Some(U256::from(0xa11ceu64)),
+            transcript: TranscriptKind::Poseidon,
+            ..Default::default()
+        };
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let proof = prove_sharpe_with_options(&bot.trades, claimed, 2, None, &options, |_| {});
+
+        let parsed = SerializedProof::from_json(&proof.to_json()).unwrap();
+        assert_eq!(parsed, proof);
+        // Transcript tag survives in query_metadata[0]
+        assert_eq!(parsed.query_metadata[0].as_limbs()[1], TranscriptKind::Poseidon.tag());
+    }
+
+    #[test]
+    fn test_query_metadata_round_trip() {
+        let metadata = QueryMetadata {
+            transcript: TranscriptKind::Poseidon,
+            num_segments: 3,
+            merkle_scheme: MerkleScheme::Sorted,
+            num_fri_layers: 5,
+            num_columns: 8,
+            log_trace_len: 6,
+            blowup: 8,
+            grinding_bits: 16,
+            composition_lde_len: 512,
+            protocol_hash: crate::PROTOCOL_HASH,
+            indices: vec![3, 250, 17],
+            leaf_salt: Some(U256::from(0xabcdu64)),
+        };
+        let words = metadata.to_words();
+        assert_eq!(words[0], U256::from_limbs([3, 1, 3, 1]));
+        assert_eq!(words[2], U256::from_limbs([6, 8, 16, 0]));
+        assert_eq!(QueryMetadata::from_words(&words), Ok(metadata.clone()));
+
+        // Defaults encode as zero limbs
+        let defaults = QueryMetadata {
+            num_segments: 1,
+            blowup: crate::BLOWUP,
+            grinding_bits: 0,
+            composition_lde_len: 256,
+            ..metadata
+        };
+        let words = defaults.to_words();
+        assert_eq!(words[0].as_limbs()[2], 0);
+        assert_eq!(words[2], U256::from(6u64));
+        assert_eq!(QueryMetadata::from_words(&words), Ok(defaults));
+
+        let mut extra = words.clone();
+        extra.push(U256::ZERO);
+        assert!(QueryMetadata::from_words(&extra).is_err());
+        assert!(QueryMetadata::from_words(&words[..5]).is_err());
+        let mut unknown_scheme = words.clone();
+        unknown_scheme[0] |= U256::from(2u64) << 192;
+        assert!(QueryMetadata::from_words(&unknown_scheme).is_err());
+    }
+
+    #[test]
+    fn test_proof_metadata_decodes() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let options = ProveOptions {
+            leaf_salt: Some(U256::from(0xa11ceu64)),
+            transcript: TranscriptKind::Poseidon,
+            ..Default::default()
+        };
+        let proof = prove_sharpe_with_options(&bot.trades, claimed, 2, None, &options, |_| {});
+        let metadata = proof.metadata().unwrap();
+        assert_eq!(metadata.transcript, TranscriptKind::Poseidon);
+        assert_eq!(metadata.num_columns, 7);
+        assert_eq!(metadata.blowup, crate::BLOWUP);
+        assert_eq!(metadata.composition_lde_len, 1 << (metadata.log_trace_len + 2));
+        assert_eq!(metadata.protocol_hash, crate::PROTOCOL_HASH);
+        assert_eq!(metadata.num_queries(), 2);
+        assert_eq!(metadata.leaf_salt, Some(U256::from(0xa11ceu64)));
+        assert_eq!(proof.commitments.len(), 1 + metadata.num_segments + metadata.num_fri_layers);
+        assert_eq!(metadata.to_words(), proof.query_metadata);
+
+        let sorted = proof.with_merkle_scheme(MerkleScheme::Sorted);
+        assert_eq!(sorted.metadata().unwrap(), QueryMetadata { merkle_scheme: MerkleScheme::Sorted, ..metadata });
+    }
+
+    #[test]
+    fn test_packed_bytes_roundtrip() {
+        let bot = crate::mock_data::bot_a_aggressive_eth();
+        let claimed = U256::from(bot.expected_sharpe_sq_scaled);
+        let proof = crate::prove_sharpe(&bot.trades, claimed, 4, None);
+
+        let bytes = proof.to_bytes();
+        let total_words = proof.sections().iter().map(|s| s.len()).sum::<usize>();
+        assert_eq!(bytes.len(), PACKED_HEADER_LEN + 32 * total_words);
+        // Header starts with the public input count (4, big-endian)
+        assert_eq!(bytes[..4], [0, 0, 0, 4]);
+        assert_eq!(SerializedProof::from_bytes(&bytes).unwrap(), proof);
+
+        // Truncated or padded blobs are rejected
+        assert!(SerializedProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut padded = bytes.clone();
+        padded.extend_from_slice(&[0u8; 32]);
+        assert!(SerializedProof::from_bytes(&padded).is_err());
+        assert!(SerializedProof::from_bytes(&bytes[..PACKED_HEADER_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn test_from_json_rejects_malformed() {
+        assert!(SerializedProof::from_json("{").is_err());
+        assert!(SerializedProof::from_json("{\"publicInputs\": [\"0x1\"]}").is_err());
+
+        let fields = ["publicInputs", "commitments", "oodValues", "friFinalPoly",
+            "queryValues", "queryPaths", "queryMetadata"];
+        let json_with = |word: &str| {
+            let parts: Vec<String> = fields.iter().map(|f| format!("\"{}\": [{}]", f, word)).collect();
+            format!("{{{}}}", parts.join(","))
+        };
+        assert!(SerializedProof::from_json(&json_with("\"0x1f\"")).is_ok());
+        assert!(SerializedProof::from_json(&json_with("\"1f\"")).is_err());
+        assert!(SerializedProof::from_json(&json_with("\"0xzz\"")).is_err());
+    }
+}
@@ -1,140 +1,594 @@
 //! Sharpe Ratio Composition Polynomial
 //!
 //! Combines Sharpe AIR constraint quotients into a single composition polynomial.
-//! 5 transition constraints + 4 boundary constraints = 9 alphas.
+//! 6 transition constraints + 5 boundary constraints = 11 alphas.
 //!
-//! Trace columns: [return, return_sq, cum_ret, cum_sq, trade_count, dataset_commitment]
+//! Trace columns: [return, return_sq, cum_ret, cum_sq, trade_count, dataset_commitment,
+//!                 is_real_row]
 //!
-//! Transition constraints (applied to consecutive rows):
-//!   TC0: cum_ret_next = cum_ret + ret_next
-//!   TC1: ret_sq = ret * ret
-//!   TC2: cum_sq_next = cum_sq + ret_sq_next
-//!   TC3: trade_count_next = trade_count (immutability)
-//!   TC4: dataset_commitment_next = dataset_commitment (immutability)
+//! Transition constraints (applied to consecutive rows), TC0-TC4 gated by
+//! the current row's is_real_row so padding rows are exempt:
+//!   TC0: is_real_row * (cum_ret_next - cum_ret - ret_next) = 0
+//!   TC1: is_real_row * (ret_sq - ret * ret) = 0
+//!   TC2: is_real_row * (cum_sq_next - cum_sq - ret_sq_next) = 0
+//!   TC3: is_real_row * (trade_count_next - trade_count) = 0 (immutability)
+//!   TC4: is_real_row * (dataset_commitment_next - dataset_commitment) = 0 (immutability)
+//!   TC5: is_real_row_next * (1 - is_real_row) = 0 (no real row after padding)
 //!
-//! Boundary constraints:
-//!   BC0: cum_ret[0] = ret[0]                                    (at first row)
-//!   BC1: cum_sq[0] = ret_sq[0]                                  (at first row)
-//!   BC2: cum_ret[N-1] = total_return                            (at last row)
-//!   BC3: cum_ret^2 * SCALE = sharpe_sq * (n * cum_sq - cum_ret^2)  (at last row)
+//! Boundary constraints (n = trade_count):
+//!   BC0: cum_ret[0] = ret[0]                                       (at first row)
+//!   BC1: cum_sq[0] = ret_sq[0]                                     (at first row)
+//!   BC2: cum_ret[n-1] = total_return                               (at last trade row)
+//!   BC3: cum_ret^2 * SCALE = sharpe_sq * (n * cum_sq - cum_ret^2)  (at last trade row)
+//!   BC4: is_real_row[n-1] = 1                                      (at last trade row)
+//!
+//! TC5 and BC4 pin is_real_row to 1 on rows 0..n-1; its value on padding
+//! rows only decides which of their constraints are checked. Gated TC1 has
+//! degree 3, so the composition polynomial has degree below 2N and is
+//! committed as (at least) two segments; see `MIN_SHARPE_SEGMENTS`.
 
 use alloy_primitives::U256;
 use crate::field::BN254Field;
-use crate::domain::domain_generator;
+use crate::domain::{self, domain_generator};
 use crate::mock_data::SHARPE_SCALE;
+use crate::padding::{padding_violations, PaddingPolicy};
+
+/// Number of transition constraints.
+pub const NUM_TRANSITION_CONSTRAINTS: usize = 6;
+
+/// Number of boundary constraints.
+pub const NUM_BOUNDARY_CONSTRAINTS: usize = 5;
+
+/// Number of random coefficients (transition + boundary).
+pub const NUM_ALPHAS: usize = NUM_TRANSITION_CONSTRAINTS + NUM_BOUNDARY_CONSTRAINTS;
+
+/// Composition segments a Sharpe proof needs (composition degree < 2N).
+pub const MIN_SHARPE_SEGMENTS: usize = 2;
+
+/// Padding each column needs for TC0-TC4 to hold from the last trade row
+/// into padding: zero returns keep the cumulative columns' steps valid, and
+/// trade_count and dataset_commitment stay immutable.
+pub const PADDING: [PaddingPolicy; 7] = [
+    PaddingPolicy::Zero,         // return
+    PaddingPolicy::Zero,         // return_sq
+    PaddingPolicy::CarryForward, // cum_ret
+    PaddingPolicy::CarryForward, // cum_sq
+    PaddingPolicy::HoldConstant, // trade_count
+    PaddingPolicy::HoldConstant, // dataset_commitment
+    PaddingPolicy::Zero,         // is_real_row
+];
+
+/// Transition constraint numerators for a (current, next) row pair.
+fn transition_constraints(current: [U256; 7], next: [U256; 7]) -> [U256; NUM_TRANSITION_CONSTRAINTS] {
+    let is_real_row = current[6];
+    let gated = [
+        BN254Field::sub(next[2], BN254Field::add(current[2], next[0])),
+        BN254Field::sub(current[1], BN254Field::mul(current[0], current[0])),
+        BN254Field::sub(next[3], BN254Field::add(current[3], next[1])),
+        BN254Field::sub(next[4], current[4]),
+        BN254Field::sub(next[5], current[5]),
+    ]
+    .map(|tc| BN254Field::mul(is_real_row, tc));
+    [
+        gated[0],
+        gated[1],
+        gated[2],
+        gated[3],
+        gated[4],
+        BN254Field::mul(next[6], BN254Field::sub(U256::from(1u64), is_real_row)),
+    ]
+}
+
+/// Boundary constraint numerators; BC0-BC1 apply at the first row, BC2-BC4
+/// at the last trade row.
+///
+/// public_inputs: [trade_count, total_return, sharpe_sq_scaled, merkle_root]
+fn boundary_constraints(row: [U256; 7], public_inputs: &[U256; 4]) -> [U256; NUM_BOUNDARY_CONSTRAINTS] {
+    let cum_ret_sq = BN254Field::mul(row[2], row[2]);
+    let lhs = BN254Field::mul(cum_ret_sq, U256::from(SHARPE_SCALE));
+    let n_cum_sq = BN254Field::mul(public_inputs[0], row[3]);
+    let rhs = BN254Field::mul(public_inputs[2], BN254Field::sub(n_cum_sq, cum_ret_sq));
+    [
+        BN254Field::sub(row[2], row[0]),
+        BN254Field::sub(row[3], row[1]),
+        BN254Field::sub(row[2], public_inputs[1]),
+        BN254Field::sub(lhs, rhs),
+        BN254Field::sub(row[6], U256::from(1u64)),
+    ]
+}
+
+/// A constraint that does not vanish on the raw trace domain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintViolation {
+    /// Transition constraint `index` on the row pair (`row`, `row + 1`).
+    Transition { index: usize, row: usize },
+    /// Boundary constraint `index` at `row`.
+    Boundary { index: usize, row: usize },
+}
+
+impl core::fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConstraintViolation::Transition { index, row } => {
+                write!(f, "transition constraint {} nonzero at row {}", index, row)
+            }
+            ConstraintViolation::Boundary { index, row } => {
+                write!(f, "boundary constraint {} nonzero at row {}", index, row)
+            }
+        }
+    }
+}
+
+/// Evaluate an AIR's constraints on the trace rows themselves (no LDE) and
+/// list every nonzero one. Transitions are checked on rows 0..N-1 paired with
+/// their successor; boundary constraints 0-1 at row 0 and the rest at the
+/// last trade row n-1 (n = `trade_count`).
+pub(crate) fn find_violations<const W: usize, const T: usize, const B: usize>(
+    columns: &[&[U256]; W],
+    trade_count: U256,
+    transition: impl Fn([U256; W], [U256; W]) -> [U256; T],
+    boundary: impl Fn([U256; W]) -> [U256; B],
+) -> Vec<ConstraintViolation> {
+    let trace_len = columns[0].len();
+    let row = |r: usize| -> [U256; W] { core::array::from_fn(|c| columns[c][r]) };
+    let mut violations = Vec::new();
+
+    for r in 0..trace_len.saturating_sub(1) {
+        for (index, tc) in transition(row(r), row(r + 1)).iter().enumerate() {
+            if *tc != U256::ZERO {
+                violations.push(ConstraintViolation::Transition { index, row: r });
+            }
+        }
+    }
+
+    let last_trade = (trade_count.saturating_to::<usize>().max(1) - 1).min(trace_len - 1);
+    let (first_bcs, last_bcs) = (boundary(row(0)), boundary(row(last_trade)));
+    for index in 0..B {
+        let (bc, r) = if index < 2 { (first_bcs[index], 0) } else { (last_bcs[index], last_trade) };
+        if bc != U256::ZERO {
+            violations.push(ConstraintViolation::Boundary { index, row: r });
+        }
+    }
+    violations
+}
+
+/// Sharpe constraints that the trace `columns` violate, in (row, constraint)
+/// order for transitions followed by the boundary constraints. Empty for a
+/// trace that proves `public_inputs`.
+pub fn sharpe_constraint_violations(
+    columns: &[&[U256]; 7],
+    public_inputs: &[U256; 4],
+) -> Vec<ConstraintViolation> {
+    find_violations(columns, public_inputs[0], transition_constraints, |row| {
+        boundary_constraints(row, public_inputs)
+    })
+}
+
+/// Sharpe transition constraints violated on the padded rows of `columns`
+/// (see `padding::padding_violations`).
+pub fn sharpe_padding_violations(columns: &[&[U256]; 7], actual_count: usize) -> Vec<ConstraintViolation> {
+    padding_violations(columns, actual_count, transition_constraints)
+}
+
+/// Domain point of the last trade row, g^(n-1) for n = `trade_count`.
+pub fn last_trade_point(trace_gen: U256, trade_count: U256) -> U256 {
+    BN254Field::pow(trace_gen, trade_count - U256::from(1u64))
+}
 
 /// Evaluate the Sharpe composition polynomial at LDE domain points.
 ///
 /// Uses batch inversion (Montgomery's trick) to eliminate per-point
-/// modular inversions: ~98K inversions → 1 inversion + ~300K muls.
+/// modular inversions: ~98K inversions → 2 inversions + ~200K muls.
 pub fn evaluate_sharpe_composition_on_lde(
-    trace_lde: &[&[U256]; 6],
+    trace_lde: &[&[U256]; 7],
     lde_domain: &[U256],
     trace_gen: U256,
     trace_len: u64,
     public_inputs: &[U256; 4],
-    alphas: &[U256; 9],
+    alphas: &[U256; NUM_ALPHAS],
 ) -> Vec<U256> {
     let lde_size = lde_domain.len();
-    let blowup = (lde_size as u64) / trace_len;
+    let blowup = (lde_size as u64 / trace_len) as usize;
+    let one = U256::from(1u64);
+    let last_trade = last_trade_point(trace_gen, public_inputs[0]);
+
+    // (x - g^(N-1)) / (x^N - 1) per point, from `blowup` distinct x^N values
+    let inv_zerofier = domain::inverse_transition_zerofier_on_lde(lde_domain, trace_len);
+
+    // Per point i: [x - 1, x - g^(n-1)], batch-inverted once
+    let mut denoms = vec![U256::ZERO; lde_size * 2];
+    for (i, &x) in lde_domain.iter().enumerate() {
+        denoms[2 * i] = BN254Field::sub(x, one);
+        denoms[2 * i + 1] = BN254Field::sub(x, last_trade);
+    }
+    BN254Field::batch_invert(&mut denoms);
+
+    (0..lde_size)
+        .map(|i| {
+            let next_i = (i + blowup) % lde_size;
+            let current: [U256; 7] = core::array::from_fn(|c| trace_lde[c][i]);
+            let next: [U256; 7] = core::array::from_fn(|c| trace_lde[c][next_i]);
+
+            // tq = tc * (x - g^(N-1)) / (x^N - 1)
+            let tqs = transition_constraints(current, next).map(|tc| BN254Field::mul(tc, inv_zerofier[i]));
+
+            let bcs = boundary_constraints(current, public_inputs);
+            let bqs: [U256; NUM_BOUNDARY_CONSTRAINTS] = core::array::from_fn(|k| {
+                let inv_den = if k < 2 { denoms[2 * i] } else { denoms[2 * i + 1] };
+                BN254Field::mul(bcs[k], inv_den)
+            });
+
+            combine_sharpe_quotients(tqs, bqs, alphas)
+        })
+        .collect()
+}
+
+/// Compute the Sharpe composition polynomial value at OOD point z.
+pub fn compute_sharpe_composition_at_z(
+    trace_ood_evals: &[U256; 7],
+    trace_ood_evals_next: &[U256; 7],
+    z: U256,
+    trace_gen: U256,
+    trace_len: u64,
+    public_inputs: &[U256; 4],
+    alphas: &[U256; NUM_ALPHAS],
+) -> U256 {
     let one = U256::from(1u64);
-    let scale = U256::from(SHARPE_SCALE);
-    let trace_domain_last = BN254Field::pow(trace_gen, U256::from(trace_len - 1));
 
-    // Precompute x^N using cyclic property.
-    // x_i = ω^i, so x_i^N = (ω^N)^i with period = blowup.
-    // ω^N = domain_generator(log_blowup).
-    let log_blowup = blowup.trailing_zeros();
+    // Transition zerofier at z: (z^N - 1) / (z - g^(N-1))
+    let z_n = BN254Field::pow(z, U256::from(trace_len));
+    let g_last = BN254Field::pow(trace_gen, U256::from(trace_len - 1));
+    let zerofier = BN254Field::div(BN254Field::sub(z_n, one), BN254Field::sub(z, g_last));
+
+    let tqs = transition_constraints(*trace_ood_evals, *trace_ood_evals_next)
+        .map(|tc| BN254Field::div(tc, zerofier));
+
+    let den_first = BN254Field::sub(z, one);
+    let den_last_trade = BN254Field::sub(z, last_trade_point(trace_gen, public_inputs[0]));
+    let bcs = boundary_constraints(*trace_ood_evals, public_inputs);
+    let bqs: [U256; NUM_BOUNDARY_CONSTRAINTS] = core::array::from_fn(|k| {
+        BN254Field::div(bcs[k], if k < 2 { den_first } else { den_last_trade })
+    });
+
+    combine_sharpe_quotients(tqs, bqs, alphas)
+}
+
+/// Split the composition polynomial into `num_segments` trace-length segments.
+///
+/// C(x) = Σ_i S_i(x) · x^(i·N) with deg S_i < N, so C must have degree below
+/// `num_segments · N`. The LDE domain contains the trace domain, where the
+/// zerofier vanishes and `composition_lde` holds no quotient values, so C is
+/// recovered from the other cosets ω^j·H (j = 1..=num_segments): there x^N is
+/// the constant c_j = ω^(jN), the coset interpolant is Σ_i c_j^i · S_i, and a
+/// small Vandermonde solve separates the segments. At most `blowup - 1`.
+pub fn split_composition(
+    composition_lde: &[U256],
+    log_lde_size: u32,
+    log_trace_len: u32,
+    num_segments: usize,
+) -> Vec<Vec<U256>> {
+    let trace_len = 1usize << log_trace_len;
+    let log_blowup = log_lde_size - log_trace_len;
+    let blowup = 1usize << log_blowup;
+    assert!(num_segments < blowup, "need a non-trace coset per segment");
+
+    let omega = domain_generator(log_lde_size);
     let omega_n = domain_generator(log_blowup);
-    let mut x_n_cycle = Vec::with_capacity(blowup as usize);
-    let mut cur = one;
-    for _ in 0..blowup {
-        x_n_cycle.push(cur);
-        cur = BN254Field::mul(cur, omega_n);
+
+    // Coset j: points ω^j · g^m sit at LDE index j + m·blowup
+    let mut coset_polys = Vec::with_capacity(num_segments);
+    let mut nodes = Vec::with_capacity(num_segments);
+    for j in 1..=num_segments {
+        let mut coeffs: Vec<U256> = (0..trace_len)
+            .map(|m| composition_lde[j + m * blowup])
+            .collect();
+        domain::ifft(&mut coeffs, log_trace_len);
+        // Interpolant in y = x / ω^j → rescale coefficient t by ω^(-j·t)
+        let inv_shift = BN254Field::inv(BN254Field::pow(omega, U256::from(j as u64)));
+        let mut scale = U256::from(1u64);
+        for c in coeffs.iter_mut() {
+            *c = BN254Field::mul(*c, scale);
+            scale = BN254Field::mul(scale, inv_shift);
+        }
+        coset_polys.push(coeffs);
+        nodes.push(BN254Field::pow(omega_n, U256::from(j as u64)));
     }
 
-    // Phase 1: Collect all denominators for batch inversion.
-    // Per point i: [zerofier_num, den_first, den_last]
-    let mut denoms = vec![U256::ZERO; lde_size * 3];
-    for i in 0..lde_size {
-        let x = lde_domain[i];
-        let x_n = x_n_cycle[i % blowup as usize];
-        denoms[3 * i] = BN254Field::sub(x_n, one);              // x^N - 1
-        denoms[3 * i + 1] = BN254Field::sub(x, one);            // x - 1
-        denoms[3 * i + 2] = BN254Field::sub(x, trace_domain_last); // x - g^(N-1)
+    let inv_vandermonde = invert_vandermonde(&nodes);
+    (0..num_segments)
+        .map(|i| {
+            (0..trace_len)
+                .map(|t| {
+                    coset_polys.iter().enumerate().fold(U256::ZERO, |acc, (j, poly)| {
+                        BN254Field::add(acc, BN254Field::mul(inv_vandermonde[i][j], poly[t]))
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Invert the Vandermonde matrix V[j][i] = nodes[j]^i (Gauss-Jordan; nodes distinct).
+fn invert_vandermonde(nodes: &[U256]) -> Vec<Vec<U256>> {
+    let k = nodes.len();
+    let one = U256::from(1u64);
+    // Augmented [V | I]
+    let mut rows: Vec<Vec<U256>> = nodes.iter().enumerate()
+        .map(|(j, &node)| {
+            let mut row = Vec::with_capacity(2 * k);
+            let mut power = one;
+            for _ in 0..k {
+                row.push(power);
+                power = BN254Field::mul(power, node);
+            }
+            row.extend((0..k).map(|c| if c == j { one } else { U256::ZERO }));
+            row
+        })
+        .collect();
+
+    for col in 0..k {
+        let pivot = (col..k)
+            .find(|&r| rows[r][col] != U256::ZERO)
+            .expect("Vandermonde nodes must be distinct");
+        rows.swap(col, pivot);
+        let inv_pivot = BN254Field::inv(rows[col][col]);
+        for v in rows[col].iter_mut() {
+            *v = BN254Field::mul(*v, inv_pivot);
+        }
+        let pivot_row = rows[col].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != col && factor != U256::ZERO {
+                for (v, &p) in row.iter_mut().zip(&pivot_row) {
+                    *v = BN254Field::sub(*v, BN254Field::mul(factor, p));
+                }
+            }
+        }
     }
 
-    // Phase 2: Batch invert (1 inversion + ~3n multiplications)
-    BN254Field::batch_invert(&mut denoms);
+    rows.into_iter().map(|row| row[k..].to_vec()).collect()
+}
 
-    // Phase 3: Evaluate constraints using multiplications only
-    let mut composition = vec![U256::ZERO; lde_size];
-
-    for i in 0..lde_size {
-        let x = lde_domain[i];
-        let inv_zerofier_num = denoms[3 * i];
-        let inv_den_first = denoms[3 * i + 1];
-        let inv_den_last = denoms[3 * i + 2];
-        let den_last = BN254Field::sub(x, trace_domain_last);
-
-        // Current row
-        let c0 = trace_lde[0][i];
-        let c1 = trace_lde[1][i];
-        let c2 = trace_lde[2][i];
-        let c3 = trace_lde[3][i];
-        let c4 = trace_lde[4][i];
-        let c5 = trace_lde[5][i];
-
-        // Next row
-        let next_i = (i + blowup as usize) % lde_size;
-        let c0_next = trace_lde[0][next_i];
-        let c1_next = trace_lde[1][next_i];
-        let c2_next = trace_lde[2][next_i];
-        let c3_next = trace_lde[3][next_i];
-        let c4_next = trace_lde[4][next_i];
-        let c5_next = trace_lde[5][next_i];
-
-        // Transition constraints
-        let tc0 = BN254Field::sub(c2_next, BN254Field::add(c2, c0_next));
-        let tc1 = BN254Field::sub(c1, BN254Field::mul(c0, c0));
-        let tc2 = BN254Field::sub(c3_next, BN254Field::add(c3, c1_next));
-        let tc3 = BN254Field::sub(c4_next, c4);
-        let tc4 = BN254Field::sub(c5_next, c5);
-
-        // tq_i = tc_i / zerofier = tc_i * den_last * inv(zerofier_num)
-        let tq_factor = BN254Field::mul(den_last, inv_zerofier_num);
-        let tq0 = BN254Field::mul(tc0, tq_factor);
-        let tq1 = BN254Field::mul(tc1, tq_factor);
-        let tq2 = BN254Field::mul(tc2, tq_factor);
-        let tq3 = BN254Field::mul(tc3, tq_factor);
-        let tq4 = BN254Field::mul(tc4, tq_factor);
-
-        // Boundary constraints: bc / den = bc * inv(den)
-        let bq0 = BN254Field::mul(BN254Field::sub(c2, c0), inv_den_first);
-        let bq1 = BN254Field::mul(BN254Field::sub(c3, c1), inv_den_first);
-        let bq2 = BN254Field::mul(BN254Field::sub(c2, public_inputs[1]), inv_den_last);
-
-        let cum_ret_sq = BN254Field::mul(c2, c2);
-        let bc3_lhs = BN254Field::mul(cum_ret_sq, scale);
-        let n_cum_sq = BN254Field::mul(public_inputs[0], c3);
-        let denom_inner = BN254Field::sub(n_cum_sq, cum_ret_sq);
-        let bc3_rhs = BN254Field::mul(public_inputs[2], denom_inner);
-        let bc3_num = BN254Field::sub(bc3_lhs, bc3_rhs);
-        let bq3 = BN254Field::mul(bc3_num, inv_den_last);
-
-        // Combine with random coefficients (5 TC + 4 BC = 9 alphas)
-        let mut comp = BN254Field::mul(alphas[0], tq0);
-        comp = BN254Field::add(comp, BN254Field::mul(alphas[1], tq1));
-        comp = BN254Field::add(comp, BN254Field::mul(alphas[2], tq2));
-        comp = BN254Field::add(comp, BN254Field::mul(alphas[3], tq3));
-        comp = BN254Field::add(comp, BN254Field::mul(alphas[4], tq4));
-        comp = BN254Field::add(comp, BN254Field::mul(alphas[5], bq0));
-        comp = BN254Field::add(comp, BN254Field::mul(alphas[6], bq1));
-        comp = BN254Field::add(comp, BN254Field::mul(alphas[7], bq2));
-        comp = BN254Field::add(comp, BN254Field::mul(alphas[8], bq3));
-
-        composition[i] = comp;
+/// Combine the constraint quotients into the composition value.
+///
+/// alphas[0..6] weight TQ0..TQ5 and alphas[6..11] weight BQ0..BQ4; the
+/// verifier's `sharpe_air::combine_quotients` must use the same mapping.
+pub fn combine_sharpe_quotients(
+    transition_quotients: [U256; NUM_TRANSITION_CONSTRAINTS],
+    boundary_quotients: [U256; NUM_BOUNDARY_CONSTRAINTS],
+    alphas: &[U256; NUM_ALPHAS],
+) -> U256 {
+    transition_quotients.iter()
+        .chain(boundary_quotients.iter())
+        .zip(alphas.iter())
+        .fold(U256::ZERO, |comp, (q, alpha)| BN254Field::add(comp, BN254Field::mul(*alpha, *q)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::get_domain;
+    use crate::mock_data::{bot_a_aggressive_eth, GmxTradeRecord, TradeReturn};
+    use crate::sharpe_trace::SharpeTrace;
+
+    #[test]
+    fn test_combine_sharpe_quotients_alpha_mapping() {
+        // Label quotient k (TQ0..TQ5, BQ0..BQ4) with k + 1 and weight it by
+        // alpha_k = 100^k: each label gets its own two-digit slot, so the
+        // result's digit pairs read back the mapping.
+        let label = |k: u64| U256::from(k + 1);
+        let tqs = [label(0), label(1), label(2), label(3), label(4), label(5)];
+        let bqs = [label(6), label(7), label(8), label(9), label(10)];
+        let mut alphas = [U256::ZERO; NUM_ALPHAS];
+        for (k, alpha) in alphas.iter_mut().enumerate() {
+            *alpha = U256::from(100u128.pow(k as u32));
+        }
+
+        // Pinned against the verifier's `combine_quotients`
+        assert_eq!(combine_sharpe_quotients(tqs, bqs, &alphas), U256::from(11_10_09_08_07_06_05_04_03_02_01u128));
+
+        alphas.swap(5, 6);
+        assert_ne!(combine_sharpe_quotients(tqs, bqs, &alphas), U256::from(11_10_09_08_07_06_05_04_03_02_01u128));
     }
 
-    composition
+    /// Trace rows of `trace` as (current-row) arrays.
+    fn trace_rows(trace: SharpeTrace) -> Vec<[U256; 7]> {
+        let len = trace.len;
+        let columns = trace.into_columns();
+        (0..len).map(|r| core::array::from_fn(|c| columns[c][r])).collect()
+    }
+
+    #[test]
+    fn test_constraint_violations_locate_corrupted_cell() {
+        let bot = bot_a_aggressive_eth();
+        let trace = SharpeTrace::generate(&bot.trades, None);
+        let public_inputs = trace.public_inputs(U256::from(bot.expected_sharpe_sq_scaled));
+        let mut columns = trace.into_columns();
+        fn refs(columns: &[Vec<U256>]) -> [&[U256]; 7] {
+            core::array::from_fn(|c| columns[c].as_slice())
+        }
+        assert!(sharpe_constraint_violations(&refs(&columns), &public_inputs).is_empty());
+
+        // A wrong cum_ret at row 7 breaks TC0 into and out of that row
+        columns[2][7] = BN254Field::add(columns[2][7], U256::from(1u64));
+        let violations = sharpe_constraint_violations(&refs(&columns), &public_inputs);
+        assert_eq!(
+            violations,
+            [
+                ConstraintViolation::Transition { index: 0, row: 6 },
+                ConstraintViolation::Transition { index: 0, row: 7 },
+            ]
+        );
+        assert_eq!(violations[0].to_string(), "transition constraint 0 nonzero at row 6");
+
+        // A wrong claim only shows up in BC3 at the last trade row
+        columns[2][7] = BN254Field::sub(columns[2][7], U256::from(1u64));
+        let mut wrong_claim = public_inputs;
+        wrong_claim[2] = BN254Field::add(wrong_claim[2], U256::from(1u64));
+        assert_eq!(
+            sharpe_constraint_violations(&refs(&columns), &wrong_claim),
+            [ConstraintViolation::Boundary { index: 3, row: 14 }]
+        );
+    }
+
+    #[test]
+    fn test_padding_rows_exempt_from_transitions() {
+        // Five trades alternating 100/200 bps → rows 5..8 are padding
+        let trades: Vec<GmxTradeRecord> =
+            [100, 200, 100, 200, 100].iter().map(|&bps| GmxTradeRecord::from_return_bps(TradeReturn::from(bps))).collect();
+        let trace = SharpeTrace::generate(&trades, None);
+        let public_inputs = trace.public_inputs(trace.compute_sharpe_sq_scaled());
+        let mut rows = trace_rows(trace);
+        assert_eq!(rows.len(), 8);
+
+        // A constraint that does not vanish on zero rows ("every return is
+        // 100 or 200 bps") fails on padding unless gated by is_real_row
+        let two_sizes = |row: &[U256; 7]| {
+            BN254Field::mul(
+                BN254Field::sub(row[0], U256::from(100u64)),
+                BN254Field::sub(row[0], U256::from(200u64)),
+            )
+        };
+        assert_ne!(two_sizes(&rows[5]), U256::ZERO);
+        for row in &rows {
+            assert_eq!(BN254Field::mul(row[6], two_sizes(row)), U256::ZERO);
+        }
+
+        // Past the step into the first padding row, padding may hold anything
+        let junk = |seed: u64| -> [U256; 7] {
+            core::array::from_fn(|c| if c == 6 { U256::ZERO } else { U256::from(seed * 31 + c as u64) })
+        };
+        rows[6] = junk(6);
+        rows[7] = junk(7);
+        for r in 0..7 {
+            assert_eq!(transition_constraints(rows[r], rows[r + 1]), [U256::ZERO; NUM_TRANSITION_CONSTRAINTS]);
+        }
+        assert_eq!(boundary_constraints(rows[0], &public_inputs)[..2], [U256::ZERO; 2]);
+        assert_eq!(boundary_constraints(rows[4], &public_inputs)[2..], [U256::ZERO; 3]);
+
+        // A trade row cannot be deselected: TC5 catches padding followed by a
+        // real row, BC4 a deselected last trade
+        let mut deselected = rows.clone();
+        deselected[2][6] = U256::ZERO;
+        assert_ne!(transition_constraints(deselected[2], deselected[3])[5], U256::ZERO);
+        deselected[4][6] = U256::ZERO;
+        assert_ne!(boundary_constraints(deselected[4], &public_inputs)[4], U256::ZERO);
+
+        // Selected, the junk rows violate the cumulative constraints again
+        rows[6][6] = U256::from(1u64);
+        assert_ne!(transition_constraints(rows[6], rows[7])[0], U256::ZERO);
+    }
+
+    /// Check that the composition LDE is a polynomial of degree < 2N, using
+    /// the coset (j = 3) that `split_composition` leaves unused.
+    fn composition_has_degree_below_2n(composition: &[U256], log_trace_len: u32, log_lde_size: u32) -> bool {
+        let segments = split_composition(composition, log_lde_size, log_trace_len, MIN_SHARPE_SEGMENTS);
+        let horner = |coeffs: &[U256], x: U256| coeffs.iter().rev()
+            .fold(U256::ZERO, |acc, &c| BN254Field::add(BN254Field::mul(acc, x), c));
+        let lde_domain = get_domain(log_lde_size);
+        let trace_len = 1u64 << log_trace_len;
+        (3..lde_domain.len()).step_by(4).all(|i| {
+            let x = lde_domain[i];
+            let x_n = BN254Field::pow(x, U256::from(trace_len));
+            let recombined = BN254Field::add(
+                horner(&segments[0], x),
+                BN254Field::mul(x_n, horner(&segments[1], x)),
+            );
+            recombined == composition[i]
+        })
+    }
+
+    #[test]
+    fn test_composition_degree_bot_a() {
+        let bot = bot_a_aggressive_eth();
+        let trace = SharpeTrace::generate(&bot.trades, None);
+        let public_inputs = trace.public_inputs(U256::from(bot.expected_sharpe_sq_scaled));
+        let (log_trace_len, log_lde_size) = (trace.log_len(), trace.log_len() + 2);
+        let trace_len = trace.len as u64;
+
+        let ldes: Vec<Vec<U256>> = trace.into_columns().into_iter()
+            .map(|mut col| {
+                domain::ifft(&mut col, log_trace_len);
+                col.resize(1 << log_lde_size, U256::ZERO);
+                domain::fft(&mut col, log_lde_size);
+                col
+            })
+            .collect();
+        let refs: [&[U256]; 7] = core::array::from_fn(|c| ldes[c].as_slice());
+        let alphas: [U256; NUM_ALPHAS] = core::array::from_fn(|k| U256::from(k as u64 * 7 + 3));
+        let lde_domain = get_domain(log_lde_size);
+        let trace_gen = domain_generator(log_trace_len);
+
+        let composition = evaluate_sharpe_composition_on_lde(
+            &refs, &lde_domain, trace_gen, trace_len, &public_inputs, &alphas,
+        );
+        assert!(composition_has_degree_below_2n(&composition, log_trace_len, log_lde_size));
+
+        // A wrong claim leaves BC3 with a pole at the last trade row
+        let mut bad_inputs = public_inputs;
+        bad_inputs[2] = BN254Field::add(bad_inputs[2], U256::from(1u64));
+        let bad = evaluate_sharpe_composition_on_lde(
+            &refs, &lde_domain, trace_gen, trace_len, &bad_inputs, &alphas,
+        );
+        assert!(!composition_has_degree_below_2n(&bad, log_trace_len, log_lde_size));
+    }
+
+    #[test]
+    fn test_split_composition_recovers_segments() {
+        // deg C < 3N over a 4N LDE (N = 4), with junk on the trace domain
+        let (log_trace_len, log_lde_size) = (2, 4);
+        let trace_len = 4;
+        let mut coeffs: Vec<U256> = (0..12u64).map(|i| U256::from(i * i + 5)).collect();
+        coeffs.resize(16, U256::ZERO);
+        let mut lde = coeffs.clone();
+        domain::fft(&mut lde, log_lde_size);
+        for i in (0..16).step_by(4) {
+            lde[i] = U256::from(0xbadu64);
+        }
+
+        let segments = split_composition(&lde, log_lde_size, log_trace_len, 3);
+        assert_eq!(segments.len(), 3);
+        for (i, segment) in segments.iter().enumerate() {
+            assert_eq!(segment[..], coeffs[i * trace_len..(i + 1) * trace_len]);
+        }
+
+        // Two segments suffice once deg C < 2N
+        coeffs[8..].fill(U256::ZERO);
+        let mut lde = coeffs.clone();
+        domain::fft(&mut lde, log_lde_size);
+        let segments = split_composition(&lde, log_lde_size, log_trace_len, 2);
+        assert_eq!(segments[0][..], coeffs[..4]);
+        assert_eq!(segments[1][..], coeffs[4..8]);
+    }
+
+    /// Re-pad Bot A's trade rows under `policies` and list what breaks.
+    fn repadded_violations(policies: &[PaddingPolicy; 7]) -> Vec<ConstraintViolation> {
+        let trace = SharpeTrace::generate(&bot_a_aggressive_eth().trades, Some(U256::from(42u64)));
+        let (actual_count, trace_len) = (trace.actual_trade_count, trace.len);
+        let mut columns: [Vec<U256>; 7] = trace.into_columns().try_into().unwrap();
+        for column in columns.iter_mut() {
+            column.truncate(actual_count);
+        }
+        crate::padding::pad_columns(columns.each_mut(), policies, trace_len);
+        sharpe_padding_violations(&columns.each_ref().map(|c| &c[..]), actual_count)
+    }
+
+    #[test]
+    fn test_padding_policy_matches_air() {
+        assert!(repadded_violations(&PADDING).is_empty());
+
+        // Bot A's last trade is row 14; each mismatch breaks the step into padding
+        let transition_at_14 = |index| vec![ConstraintViolation::Transition { index, row: 14 }];
+        for (column, policy, tc) in [
+            (2, PaddingPolicy::Zero, 0),         // cum_ret reset
+            (0, PaddingPolicy::CarryForward, 0), // return repeated into cum_ret
+            (3, PaddingPolicy::Zero, 2),         // cum_sq reset
+            (4, PaddingPolicy::Zero, 3),         // trade_count not immutable
+            (5, PaddingPolicy::Zero, 4),         // dataset_commitment not immutable
+        ] {
+            let mut policies = PADDING;
+            policies[column] = policy;
+            assert_eq!(repadded_violations(&policies), transition_at_14(tc), "column {}", column);
+        }
+    }
 }
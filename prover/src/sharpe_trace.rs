@@ -1,22 +1,28 @@
 //! Sharpe Ratio Trace Generation
 //!
 //! Generates the execution trace for Sharpe ratio verification.
-//! The trace has 6 columns:
-//!   [return, return_sq, cumulative_return, cumulative_sq, trade_count, dataset_commitment]
+//! The trace has 7 columns:
+//!   [return, return_sq, cumulative_return, cumulative_sq, trade_count,
+//!    dataset_commitment, is_real_row]
 //!
-//! Actual trade rows are followed by zero-padded rows to the next power of 2.
-//! Padding rows: return=0, return_sq=0, cumulative values carry forward,
-//! trade_count=N(actual), dataset_commitment=0.
+//! Actual trade rows are followed by padding rows to the next power of 2,
+//! filled per `sharpe_compose::PADDING`: return=0, return_sq=0, cumulative
+//! values carry forward, trade_count and dataset_commitment held constant,
+//! is_real_row=0. The AIR gates
+//! its transition constraints by the current row's is_real_row, so only the
+//! step from the last trade into the first padding row is still checked.
 
 use alloy_primitives::U256;
 use crate::field::BN254Field;
-use crate::mock_data::{GmxTradeRecord, basis_points_to_field, SHARPE_SCALE};
-use crate::commit::MerkleTree;
+use crate::mock_data::{GmxTradeRecord, TradeReturn, SHARPE_SCALE};
+use crate::commit::{MerkleScheme, MerkleTree};
+use crate::padding::pad_columns;
+use crate::sharpe_compose::{sharpe_padding_violations, PADDING};
 
 /// Number of trace columns.
-pub const NUM_COLUMNS: usize = 6;
+pub const NUM_COLUMNS: usize = 7;
 
-/// A 6-column execution trace for Sharpe ratio verification.
+/// A 7-column execution trace for Sharpe ratio verification.
 pub struct SharpeTrace {
     pub col_return: Vec<U256>,             // Col 0: return_i
     pub col_return_sq: Vec<U256>,          // Col 1: return_i^2
@@ -24,6 +30,7 @@ pub struct SharpeTrace {
     pub col_cumulative_sq: Vec<U256>,      // Col 3: sum of return_sq up to row i
     pub col_trade_count: Vec<U256>,        // Col 4: constant N (actual trade count)
     pub col_dataset_commitment: Vec<U256>, // Col 5: dataset commitment (constant per trace)
+    pub col_is_real_row: Vec<U256>,        // Col 6: 1 for trade rows, 0 for padding
     pub len: usize,                        // Padded power-of-2 length
     pub actual_trade_count: usize,         // Actual number of trades
 }
@@ -49,13 +56,14 @@ impl SharpeTrace {
         let mut col_cumulative_sq = Vec::with_capacity(trace_len);
         let mut col_trade_count = Vec::with_capacity(trace_len);
         let mut col_dataset_commitment = Vec::with_capacity(trace_len);
+        let mut col_is_real_row = Vec::with_capacity(trace_len);
 
         let mut cum_ret = U256::ZERO;
         let mut cum_sq = U256::ZERO;
 
         // Fill actual trade rows
         for trade in trades {
-            let ret_field = basis_points_to_field(trade.return_bps);
+            let ret_field = trade.return_bps.to_field();
             let ret_sq = BN254Field::mul(ret_field, ret_field);
 
             cum_ret = BN254Field::add(cum_ret, ret_field);
@@ -67,27 +75,131 @@ impl SharpeTrace {
             col_cumulative_sq.push(cum_sq);
             col_trade_count.push(n_field);
             col_dataset_commitment.push(commitment_val);
+            col_is_real_row.push(U256::from(1u64));
         }
 
-        // Fill padding rows (zero return, cumulative values preserved)
-        for _ in actual_count..trace_len {
-            col_return.push(U256::ZERO);
-            col_return_sq.push(U256::ZERO);
-            col_cumulative_return.push(cum_ret); // carry forward
-            col_cumulative_sq.push(cum_sq);      // carry forward
-            col_trade_count.push(n_field);
-            col_dataset_commitment.push(commitment_val);
-        }
+        pad_columns(
+            [
+                &mut col_return,
+                &mut col_return_sq,
+                &mut col_cumulative_return,
+                &mut col_cumulative_sq,
+                &mut col_trade_count,
+                &mut col_dataset_commitment,
+                &mut col_is_real_row,
+            ],
+            &PADDING,
+            trace_len,
+        );
+
+        let trace = SharpeTrace {
+            col_return,
+            col_return_sq,
+            col_cumulative_return,
+            col_cumulative_sq,
+            col_trade_count,
+            col_dataset_commitment,
+            col_is_real_row,
+            len: trace_len,
+            actual_trade_count: actual_count,
+        };
+        trace.assert_padding_valid();
+        trace
+    }
+
+    /// Generate a Sharpe trace from run-length encoded returns: each
+    /// `(bps, count)` pair stands for `count` consecutive trades returning
+    /// `bps`. Identical to `generate` on the expanded trades.
+    ///
+    /// Panics if a return is outside `TradeReturn`'s range.
+    pub fn generate_from_counts(value_count_pairs: &[(i64, usize)], dataset_commitment: Option<U256>) -> Self {
+        let returns: Vec<U256> = value_count_pairs
+            .iter()
+            .flat_map(|&(bps, count)| {
+                let ret = TradeReturn::new(bps).unwrap_or_else(|e| panic!("{}", e));
+                std::iter::repeat_n(ret.to_field(), count)
+            })
+            .collect();
+        Self::generate_from_field_returns(&returns, dataset_commitment)
+    }
+
+    /// Generate a Sharpe trace from returns already converted to field elements.
+    ///
+    /// Skips the per-trade basis-point conversion; return_sq is computed in one
+    /// batch and the cumulative columns as prefix sums over it.
+    pub fn generate_from_field_returns(returns: &[U256], dataset_commitment: Option<U256>) -> Self {
+        let actual_count = returns.len();
+        assert!(actual_count >= 2, "need at least 2 trades");
+
+        // Pad to next power of 2
+        let trace_len = actual_count.next_power_of_two();
+
+        let n_field = U256::from(actual_count as u64);
+        let commitment_val = dataset_commitment.unwrap_or(U256::ZERO);
+
+        let mut col_return = Vec::with_capacity(trace_len);
+        col_return.extend_from_slice(returns);
+
+        let mut col_return_sq: Vec<U256> = Vec::with_capacity(trace_len);
+        col_return_sq.extend(returns.iter().map(|&r| BN254Field::mul(r, r)));
+
+        let prefix_sums = |col: &[U256]| -> Vec<U256> {
+            let mut out = Vec::with_capacity(trace_len);
+            out.extend(col.iter().scan(U256::ZERO, |acc, &v| {
+                *acc = BN254Field::add(*acc, v);
+                Some(*acc)
+            }));
+            out
+        };
+        let mut col_cumulative_return = prefix_sums(&col_return);
+        let mut col_cumulative_sq = prefix_sums(&col_return_sq);
+        let mut col_trade_count = vec![n_field; actual_count];
+        let mut col_dataset_commitment = vec![commitment_val; actual_count];
+        let mut col_is_real_row = vec![U256::from(1u64); actual_count];
+
+        pad_columns(
+            [
+                &mut col_return,
+                &mut col_return_sq,
+                &mut col_cumulative_return,
+                &mut col_cumulative_sq,
+                &mut col_trade_count,
+                &mut col_dataset_commitment,
+                &mut col_is_real_row,
+            ],
+            &PADDING,
+            trace_len,
+        );
 
-        SharpeTrace {
+        let trace = SharpeTrace {
             col_return,
             col_return_sq,
             col_cumulative_return,
             col_cumulative_sq,
             col_trade_count,
             col_dataset_commitment,
+            col_is_real_row,
             len: trace_len,
             actual_trade_count: actual_count,
+        };
+        trace.assert_padding_valid();
+        trace
+    }
+
+    /// Panic if the padded rows break a transition constraint, i.e. if
+    /// `PADDING` disagrees with the AIR.
+    fn assert_padding_valid(&self) {
+        let columns = [
+            &self.col_return[..],
+            &self.col_return_sq,
+            &self.col_cumulative_return,
+            &self.col_cumulative_sq,
+            &self.col_trade_count,
+            &self.col_dataset_commitment,
+            &self.col_is_real_row,
+        ];
+        if let Some(violation) = sharpe_padding_violations(&columns, self.actual_trade_count).first() {
+            panic!("padding policy does not match the Sharpe AIR: {}", violation);
         }
     }
 
@@ -95,11 +207,20 @@ impl SharpeTrace {
     ///
     /// Returns [trade_count, total_return, sharpe_sq_scaled, merkle_root]
     pub fn public_inputs(&self, claimed_sharpe_sq_scaled: U256) -> [U256; 4] {
+        self.public_inputs_with_scheme(claimed_sharpe_sq_scaled, MerkleScheme::Ordered)
+    }
+
+    /// Public inputs with `merkle_root` built under the given pairing scheme.
+    pub fn public_inputs_with_scheme(
+        &self,
+        claimed_sharpe_sq_scaled: U256,
+        scheme: MerkleScheme,
+    ) -> [U256; 4] {
         let trade_count = U256::from(self.actual_trade_count as u64);
         let total_return = self.col_cumulative_return[self.actual_trade_count - 1];
 
         // Merkle root of dataset_commitment column
-        let merkle_root = MerkleTree::build(&self.col_dataset_commitment).root();
+        let merkle_root = MerkleTree::build_with_scheme(&self.col_dataset_commitment, scheme).root();
 
         [trade_count, total_return, claimed_sharpe_sq_scaled, merkle_root]
     }
@@ -124,6 +245,19 @@ impl SharpeTrace {
 
         BN254Field::div(numerator, denominator)
     }
+
+    /// The columns in commitment order.
+    pub fn into_columns(self) -> Vec<Vec<U256>> {
+        vec![
+            self.col_return,
+            self.col_return_sq,
+            self.col_cumulative_return,
+            self.col_cumulative_sq,
+            self.col_trade_count,
+            self.col_dataset_commitment,
+            self.col_is_real_row,
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +281,27 @@ mod tests {
         assert_eq!(trace.col_cumulative_sq.len(), 16);
         assert_eq!(trace.col_trade_count.len(), 16);
         assert_eq!(trace.col_dataset_commitment.len(), 16);
+        assert_eq!(trace.col_is_real_row.len(), 16);
+    }
+
+    #[test]
+    fn test_generate_from_counts_matches_expanded() {
+        let counts = [(100, 5), (200, 5), (300, 5)];
+        let trades: Vec<GmxTradeRecord> = counts
+            .iter()
+            .flat_map(|&(bps, count)| (0..count).map(move |_| GmxTradeRecord::from_return_bps(TradeReturn::from(bps))))
+            .collect();
+        let commitment = Some(U256::from(0xc0ffeeu64));
+
+        let compressed = SharpeTrace::generate_from_counts(&counts.map(|(bps, count)| (bps as i64, count)), commitment);
+        let expanded = SharpeTrace::generate(&trades, commitment);
+        assert_eq!(compressed.actual_trade_count, 15);
+        assert_eq!(compressed.into_columns(), expanded.into_columns());
+
+        // Same multiset of returns as Bot A, so the same Sharpe value
+        let bot = bot_a_aggressive_eth();
+        let trace = SharpeTrace::generate_from_counts(&[(100, 5), (200, 5), (300, 5)], None);
+        assert_eq!(trace.compute_sharpe_sq_scaled(), U256::from(bot.expected_sharpe_sq_scaled));
     }
 
     #[test]
@@ -180,6 +335,34 @@ mod tests {
 
         // Trade count should be constant
         assert_eq!(trace.col_trade_count[15], trace.col_trade_count[0]);
+
+        // Only the padding row is deselected
+        assert!(trace.col_is_real_row[..15].iter().all(|&s| s == U256::from(1u64)));
+        assert_eq!(trace.col_is_real_row[15], U256::ZERO);
+    }
+
+    #[test]
+    fn test_padding_carries_totals_to_last_row() {
+        // BC2 anchors on the last actual row g^(n-1), which
+        // `compute_sharpe_sq_scaled` reads too; padding still carries the
+        // totals to the last padded row, whether or not the trace needed it.
+        let padded = bot_a_aggressive_eth().trades; // 15 → 16
+        let mut unpadded = bot_a_aggressive_eth().trades;
+        unpadded.push(GmxTradeRecord::from_return_bps(120.into())); // exactly 16
+
+        for (trades, expected_len) in [(padded, 16), (unpadded, 16)] {
+            let trace = SharpeTrace::generate(&trades, None);
+            assert_eq!(trace.len, expected_len);
+            let last_actual = trace.actual_trade_count - 1;
+            let last_row = trace.len - 1;
+
+            assert_eq!(trace.col_cumulative_return[last_row], trace.col_cumulative_return[last_actual]);
+            assert_eq!(trace.col_cumulative_sq[last_row], trace.col_cumulative_sq[last_actual]);
+
+            let total_return = trace.public_inputs(U256::ZERO)[1];
+            assert_eq!(total_return, trace.col_cumulative_return[last_actual]);
+            assert_eq!(total_return, trace.col_cumulative_return[last_row]);
+        }
     }
 
     #[test]
@@ -281,4 +464,26 @@ mod tests {
         assert_eq!(pi[2], claimed); // sharpe_sq_scaled
         // pi[1] = total_return, pi[3] = merkle_root (computed)
     }
+
+    #[test]
+    fn test_generate_from_field_returns_matches_generate() {
+        let bot = bot_a_aggressive_eth();
+        let commitment = Some(U256::from(0xc0ffeeu64));
+        let returns: Vec<U256> = bot.trades.iter()
+            .map(|t| t.return_bps.to_field())
+            .collect();
+
+        let expected = SharpeTrace::generate(&bot.trades, commitment);
+        let fast = SharpeTrace::generate_from_field_returns(&returns, commitment);
+
+        assert_eq!(fast.len, expected.len);
+        assert_eq!(fast.actual_trade_count, expected.actual_trade_count);
+        assert_eq!(fast.col_return, expected.col_return);
+        assert_eq!(fast.col_return_sq, expected.col_return_sq);
+        assert_eq!(fast.col_cumulative_return, expected.col_cumulative_return);
+        assert_eq!(fast.col_cumulative_sq, expected.col_cumulative_sq);
+        assert_eq!(fast.col_trade_count, expected.col_trade_count);
+        assert_eq!(fast.col_dataset_commitment, expected.col_dataset_commitment);
+        assert_eq!(fast.col_is_real_row, expected.col_is_real_row);
+    }
 }
@@ -0,0 +1,342 @@
+//! Sortino Ratio Composition Polynomial
+//!
+//! Combines Sortino AIR constraint quotients into a single composition polynomial.
+//! 7 transition constraints + 5 boundary constraints = 12 alphas.
+//!
+//! Trace columns: [return, downside_sq, cum_ret, cum_downside_sq, trade_count,
+//!                 dataset_commitment, is_negative, is_real_row]
+//!
+//! Transition constraints (applied to consecutive rows), TC0-TC5 gated by
+//! the current row's is_real_row exactly as in the Sharpe AIR:
+//!   TC0: cum_ret_next = cum_ret + ret_next
+//!   TC1: downside_sq = is_negative * ret * ret
+//!   TC2: cum_downside_sq_next = cum_downside_sq + downside_sq_next
+//!   TC3: trade_count_next = trade_count (immutability)
+//!   TC4: dataset_commitment_next = dataset_commitment (immutability)
+//!   TC5: is_negative * (is_negative - 1) = 0 (boolean selector)
+//!   TC6: is_real_row_next * (1 - is_real_row) = 0 (ungated)
+//!
+//! Boundary constraints (n = trade_count):
+//!   BC0: cum_ret[0] = ret[0]                                        (at first row)
+//!   BC1: cum_downside_sq[0] = downside_sq[0]                        (at first row)
+//!   BC2: cum_ret[n-1] = total_return                                (at last trade row)
+//!   BC3: cum_ret^2 * SCALE = sortino_sq * n * cum_downside_sq       (at last trade row)
+//!   BC4: is_real_row[n-1] = 1                                       (at last trade row)
+//!
+//! Gated TC1 has degree 4, so the composition polynomial has degree below 3N
+//! and is committed as (at least) three segments; see `MIN_SORTINO_SEGMENTS`.
+//!
+//! The AIR checks that the selector is boolean and gates downside_sq; that it
+//! matches the sign of each return is up to the trace generator, as the
+//! returns themselves are prover witnesses.
+
+use alloy_primitives::U256;
+use crate::field::BN254Field;
+use crate::domain;
+use crate::mock_data::SHARPE_SCALE;
+use crate::padding::{padding_violations, PaddingPolicy};
+use crate::sharpe_compose::{find_violations, last_trade_point, ConstraintViolation};
+
+/// Number of transition constraints.
+pub const NUM_TRANSITION_CONSTRAINTS: usize = 7;
+
+/// Number of boundary constraints.
+pub const NUM_BOUNDARY_CONSTRAINTS: usize = 5;
+
+/// Number of random coefficients (transition + boundary).
+pub const NUM_ALPHAS: usize = NUM_TRANSITION_CONSTRAINTS + NUM_BOUNDARY_CONSTRAINTS;
+
+/// Composition segments a Sortino proof needs (composition degree < 3N).
+pub const MIN_SORTINO_SEGMENTS: usize = 3;
+
+/// Padding each column needs for TC0-TC5 to hold from the last trade row
+/// into padding; as `sharpe_compose::PADDING`, plus a zero is_negative.
+pub const PADDING: [PaddingPolicy; 8] = [
+    PaddingPolicy::Zero,         // return
+    PaddingPolicy::Zero,         // downside_sq
+    PaddingPolicy::CarryForward, // cum_ret
+    PaddingPolicy::CarryForward, // cum_downside_sq
+    PaddingPolicy::HoldConstant, // trade_count
+    PaddingPolicy::HoldConstant, // dataset_commitment
+    PaddingPolicy::Zero,         // is_negative
+    PaddingPolicy::Zero,         // is_real_row
+];
+
+/// Transition constraint numerators for a (current, next) row pair.
+fn transition_constraints(current: [U256; 8], next: [U256; 8]) -> [U256; NUM_TRANSITION_CONSTRAINTS] {
+    let is_real_row = current[7];
+    let ret_sq = BN254Field::mul(current[0], current[0]);
+    let gated = [
+        BN254Field::sub(next[2], BN254Field::add(current[2], next[0])),
+        BN254Field::sub(current[1], BN254Field::mul(current[6], ret_sq)),
+        BN254Field::sub(next[3], BN254Field::add(current[3], next[1])),
+        BN254Field::sub(next[4], current[4]),
+        BN254Field::sub(next[5], current[5]),
+        BN254Field::sub(BN254Field::mul(current[6], current[6]), current[6]),
+    ]
+    .map(|tc| BN254Field::mul(is_real_row, tc));
+    [
+        gated[0],
+        gated[1],
+        gated[2],
+        gated[3],
+        gated[4],
+        gated[5],
+        BN254Field::mul(next[7], BN254Field::sub(U256::from(1u64), is_real_row)),
+    ]
+}
+
+/// Boundary constraint numerators; BC0-BC1 apply at the first row, BC2-BC4
+/// at the last trade row.
+///
+/// public_inputs: [trade_count, total_return, sortino_sq_scaled, merkle_root]
+fn boundary_constraints(row: [U256; 8], public_inputs: &[U256; 4]) -> [U256; NUM_BOUNDARY_CONSTRAINTS] {
+    let cum_ret_sq = BN254Field::mul(row[2], row[2]);
+    let lhs = BN254Field::mul(cum_ret_sq, U256::from(SHARPE_SCALE));
+    let n_cum_downside_sq = BN254Field::mul(public_inputs[0], row[3]);
+    let rhs = BN254Field::mul(public_inputs[2], n_cum_downside_sq);
+    [
+        BN254Field::sub(row[2], row[0]),
+        BN254Field::sub(row[3], row[1]),
+        BN254Field::sub(row[2], public_inputs[1]),
+        BN254Field::sub(lhs, rhs),
+        BN254Field::sub(row[7], U256::from(1u64)),
+    ]
+}
+
+/// Sortino constraints that the trace `columns` violate; see
+/// `sharpe_constraint_violations`.
+pub fn sortino_constraint_violations(
+    columns: &[&[U256]; 8],
+    public_inputs: &[U256; 4],
+) -> Vec<ConstraintViolation> {
+    find_violations(columns, public_inputs[0], transition_constraints, |row| {
+        boundary_constraints(row, public_inputs)
+    })
+}
+
+/// Sortino transition constraints violated on the padded rows of `columns`.
+pub fn sortino_padding_violations(columns: &[&[U256]; 8], actual_count: usize) -> Vec<ConstraintViolation> {
+    padding_violations(columns, actual_count, transition_constraints)
+}
+
+/// Evaluate the Sortino composition polynomial at LDE domain points.
+///
+/// Same batching as `evaluate_sharpe_composition_on_lde`: the transition
+/// zerofier numerator repeats with period `blowup`, and the boundary
+/// denominators are batch-inverted once.
+pub fn evaluate_sortino_composition_on_lde(
+    trace_lde: &[&[U256]; 8],
+    lde_domain: &[U256],
+    trace_gen: U256,
+    trace_len: u64,
+    public_inputs: &[U256; 4],
+    alphas: &[U256; NUM_ALPHAS],
+) -> Vec<U256> {
+    let lde_size = lde_domain.len();
+    let blowup = (lde_size as u64 / trace_len) as usize;
+    let one = U256::from(1u64);
+    let last_trade = last_trade_point(trace_gen, public_inputs[0]);
+
+    // (x - g^(N-1)) / (x^N - 1) per point, from `blowup` distinct x^N values
+    let inv_zerofier = domain::inverse_transition_zerofier_on_lde(lde_domain, trace_len);
+
+    // Per point i: [x - 1, x - g^(n-1)]
+    let mut denoms = vec![U256::ZERO; lde_size * 2];
+    for (i, &x) in lde_domain.iter().enumerate() {
+        denoms[2 * i] = BN254Field::sub(x, one);
+        denoms[2 * i + 1] = BN254Field::sub(x, last_trade);
+    }
+    BN254Field::batch_invert(&mut denoms);
+
+    (0..lde_size)
+        .map(|i| {
+            let next_i = (i + blowup) % lde_size;
+            let current: [U256; 8] = core::array::from_fn(|c| trace_lde[c][i]);
+            let next: [U256; 8] = core::array::from_fn(|c| trace_lde[c][next_i]);
+
+            // tq = tc * (x - g^(N-1)) / (x^N - 1)
+            let tqs = transition_constraints(current, next).map(|tc| BN254Field::mul(tc, inv_zerofier[i]));
+
+            let bcs = boundary_constraints(current, public_inputs);
+            let bqs: [U256; NUM_BOUNDARY_CONSTRAINTS] = core::array::from_fn(|k| {
+                let inv_den = if k < 2 { denoms[2 * i] } else { denoms[2 * i + 1] };
+                BN254Field::mul(bcs[k], inv_den)
+            });
+
+            combine_sortino_quotients(tqs, bqs, alphas)
+        })
+        .collect()
+}
+
+/// Compute the Sortino composition polynomial value at OOD point z.
+pub fn compute_sortino_composition_at_z(
+    trace_ood_evals: &[U256; 8],
+    trace_ood_evals_next: &[U256; 8],
+    z: U256,
+    trace_gen: U256,
+    trace_len: u64,
+    public_inputs: &[U256; 4],
+    alphas: &[U256; NUM_ALPHAS],
+) -> U256 {
+    let one = U256::from(1u64);
+
+    // Transition zerofier at z: (z^N - 1) / (z - g^(N-1))
+    let z_n = BN254Field::pow(z, U256::from(trace_len));
+    let g_last = BN254Field::pow(trace_gen, U256::from(trace_len - 1));
+    let zerofier = BN254Field::div(BN254Field::sub(z_n, one), BN254Field::sub(z, g_last));
+
+    let tqs = transition_constraints(*trace_ood_evals, *trace_ood_evals_next)
+        .map(|tc| BN254Field::div(tc, zerofier));
+
+    let den_first = BN254Field::sub(z, one);
+    let den_last_trade = BN254Field::sub(z, last_trade_point(trace_gen, public_inputs[0]));
+    let bcs = boundary_constraints(*trace_ood_evals, public_inputs);
+    let bqs: [U256; NUM_BOUNDARY_CONSTRAINTS] = core::array::from_fn(|k| {
+        BN254Field::div(bcs[k], if k < 2 { den_first } else { den_last_trade })
+    });
+
+    combine_sortino_quotients(tqs, bqs, alphas)
+}
+
+/// Combine the constraint quotients into the composition value.
+///
+/// alphas[0..7] weight TQ0..TQ6 and alphas[7..12] weight BQ0..BQ4; the
+/// verifier's `sortino_air::combine_quotients` must use the same mapping.
+pub fn combine_sortino_quotients(
+    transition_quotients: [U256; NUM_TRANSITION_CONSTRAINTS],
+    boundary_quotients: [U256; NUM_BOUNDARY_CONSTRAINTS],
+    alphas: &[U256; NUM_ALPHAS],
+) -> U256 {
+    transition_quotients.iter()
+        .chain(boundary_quotients.iter())
+        .zip(alphas.iter())
+        .fold(U256::ZERO, |comp, (q, alpha)| BN254Field::add(comp, BN254Field::mul(*alpha, *q)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{self, domain_generator, get_domain};
+    use crate::mock_data::bot_c_swing_trader;
+    use crate::sharpe_compose::split_composition;
+    use crate::sortino_trace::SortinoTrace;
+
+    #[test]
+    fn test_combine_sortino_quotients_alpha_mapping() {
+        // Label quotient k (TQ0..TQ6, BQ0..BQ4) with k + 1 and weight it by
+        // alpha_k = 100^k: each label gets its own two-digit slot, so the
+        // result's digit pairs read back the mapping.
+        let label = |k: u64| U256::from(k + 1);
+        let tqs = [label(0), label(1), label(2), label(3), label(4), label(5), label(6)];
+        let bqs = [label(7), label(8), label(9), label(10), label(11)];
+        let mut alphas = [U256::ZERO; NUM_ALPHAS];
+        for (k, alpha) in alphas.iter_mut().enumerate() {
+            *alpha = U256::from(100u128.pow(k as u32));
+        }
+
+        // Pinned against the verifier's `combine_quotients`
+        assert_eq!(combine_sortino_quotients(tqs, bqs, &alphas), U256::from(12_11_10_09_08_07_06_05_04_03_02_01u128));
+
+        alphas.swap(6, 7);
+        assert_ne!(combine_sortino_quotients(tqs, bqs, &alphas), U256::from(12_11_10_09_08_07_06_05_04_03_02_01u128));
+    }
+
+    #[test]
+    fn test_padding_rows_exempt_from_transitions() {
+        // Bot C: 12 trades → rows 12..16 are padding
+        let bot = bot_c_swing_trader();
+        let trace = SortinoTrace::generate(&bot.trades, None);
+        let public_inputs = trace.public_inputs(U256::from(bot.expected_sortino_sq_scaled.unwrap()));
+        let columns = trace.into_columns();
+        let mut rows: Vec<[U256; 8]> = (0..16).map(|r| core::array::from_fn(|c| columns[c][r])).collect();
+
+        // Junk past the first padding row, including a non-boolean is_negative
+        for (r, row) in rows.iter_mut().enumerate().skip(13) {
+            *row = core::array::from_fn(|c| if c == 7 { U256::ZERO } else { U256::from(r as u64 * 31 + c as u64 + 2) });
+        }
+        for r in 0..15 {
+            assert_eq!(transition_constraints(rows[r], rows[r + 1]), [U256::ZERO; NUM_TRANSITION_CONSTRAINTS]);
+        }
+        assert_eq!(boundary_constraints(rows[0], &public_inputs)[..2], [U256::ZERO; 2]);
+        assert_eq!(boundary_constraints(rows[11], &public_inputs)[2..], [U256::ZERO; 3]);
+
+        // Selected, the junk violates TC5 (boolean is_negative)
+        rows[13][7] = U256::from(1u64);
+        assert_ne!(transition_constraints(rows[13], rows[14])[5], U256::ZERO);
+    }
+
+    /// Check that the composition LDE (blowup 8) is a polynomial of degree
+    /// < 3N, using the cosets j = 4..8 that `split_composition` leaves unused.
+    fn composition_has_degree_below_3n(composition: &[U256], log_trace_len: u32, log_lde_size: u32) -> bool {
+        let segments = split_composition(composition, log_lde_size, log_trace_len, MIN_SORTINO_SEGMENTS);
+        let horner = |coeffs: &[U256], x: U256| coeffs.iter().rev()
+            .fold(U256::ZERO, |acc, &c| BN254Field::add(BN254Field::mul(acc, x), c));
+        let lde_domain = get_domain(log_lde_size);
+        let trace_len = 1u64 << log_trace_len;
+        (0..lde_domain.len()).filter(|i| i % 8 >= 4).all(|i| {
+            let x = lde_domain[i];
+            let x_n = BN254Field::pow(x, U256::from(trace_len));
+            let recombined = segments.iter().rev()
+                .fold(U256::ZERO, |acc, s| BN254Field::add(BN254Field::mul(acc, x_n), horner(s, x)));
+            recombined == composition[i]
+        })
+    }
+
+    #[test]
+    fn test_composition_degree_bot_c() {
+        let bot = bot_c_swing_trader();
+        let trace = SortinoTrace::generate(&bot.trades, None);
+        let public_inputs = trace.public_inputs(U256::from(bot.expected_sortino_sq_scaled.unwrap()));
+        let (log_trace_len, log_lde_size) = (trace.log_len(), trace.log_len() + 3);
+        let trace_len = trace.len as u64;
+
+        let ldes: Vec<Vec<U256>> = trace.into_columns().into_iter()
+            .map(|mut col| {
+                domain::ifft(&mut col, log_trace_len);
+                col.resize(1 << log_lde_size, U256::ZERO);
+                domain::fft(&mut col, log_lde_size);
+                col
+            })
+            .collect();
+        let refs: [&[U256]; 8] = core::array::from_fn(|c| ldes[c].as_slice());
+        let alphas: [U256; NUM_ALPHAS] = core::array::from_fn(|k| U256::from(k as u64 * 7 + 3));
+        let lde_domain = get_domain(log_lde_size);
+        let trace_gen = domain_generator(log_trace_len);
+
+        let composition = evaluate_sortino_composition_on_lde(
+            &refs, &lde_domain, trace_gen, trace_len, &public_inputs, &alphas,
+        );
+        assert!(composition_has_degree_below_3n(&composition, log_trace_len, log_lde_size));
+
+        // A wrong claim leaves BC3 with a pole at the last trade row
+        let mut bad_inputs = public_inputs;
+        bad_inputs[2] = BN254Field::add(bad_inputs[2], U256::from(1u64));
+        let bad = evaluate_sortino_composition_on_lde(
+            &refs, &lde_domain, trace_gen, trace_len, &bad_inputs, &alphas,
+        );
+        assert!(!composition_has_degree_below_3n(&bad, log_trace_len, log_lde_size));
+    }
+
+    #[test]
+    fn test_padding_policy_matches_air() {
+        let trace = SortinoTrace::generate(&bot_c_swing_trader().trades, None);
+        let (actual_count, trace_len) = (trace.actual_trade_count, trace.len);
+        let mut real_rows: [Vec<U256>; 8] = trace.into_columns().try_into().unwrap();
+        for column in real_rows.iter_mut() {
+            column.truncate(actual_count);
+        }
+        let violations = |policies: &[PaddingPolicy; 8]| {
+            let mut columns = real_rows.clone();
+            crate::padding::pad_columns(columns.each_mut(), policies, trace_len);
+            sortino_padding_violations(&columns.each_ref().map(|c| &c[..]), actual_count)
+        };
+
+        assert!(violations(&PADDING).is_empty());
+        // Resetting cum_downside_sq breaks TC2 from the last trade (row 11)
+        let mut policies = PADDING;
+        policies[3] = PaddingPolicy::Zero;
+        assert_eq!(violations(&policies), vec![ConstraintViolation::Transition { index: 2, row: 11 }]);
+    }
+}
+
@@ -0,0 +1,289 @@
+//! Sortino Ratio Trace Generation
+//!
+//! Generates the execution trace for Sortino ratio verification.
+//! The trace has 8 columns:
+//!   [return, downside_sq, cumulative_return, cumulative_downside_sq,
+//!    trade_count, dataset_commitment, is_negative, is_real_row]
+//!
+//! downside_sq = min(return, 0)^2, selected by the boolean is_negative column.
+//! Actual trade rows are followed by padding rows to the next power of 2,
+//! filled per `sortino_compose::PADDING`: return=0, downside_sq=0,
+//! is_negative=0, cumulative values carry forward, trade_count and
+//! dataset_commitment held constant, is_real_row=0 (the selector gating the
+//! transition constraints, as in `SharpeTrace`).
+
+use alloy_primitives::U256;
+use crate::field::BN254Field;
+use crate::mock_data::{GmxTradeRecord, SHARPE_SCALE};
+use crate::commit::{MerkleScheme, MerkleTree};
+use crate::padding::pad_columns;
+use crate::sortino_compose::{sortino_padding_violations, PADDING};
+
+/// Number of trace columns.
+pub const NUM_COLUMNS: usize = 8;
+
+/// An 8-column execution trace for Sortino ratio verification.
+pub struct SortinoTrace {
+    pub col_return: Vec<U256>,                  // Col 0: return_i
+    pub col_downside_sq: Vec<U256>,             // Col 1: min(return_i, 0)^2
+    pub col_cumulative_return: Vec<U256>,       // Col 2: sum of returns up to row i
+    pub col_cumulative_downside_sq: Vec<U256>,  // Col 3: sum of downside_sq up to row i
+    pub col_trade_count: Vec<U256>,             // Col 4: constant N (actual trade count)
+    pub col_dataset_commitment: Vec<U256>,      // Col 5: dataset commitment (constant per trace)
+    pub col_is_negative: Vec<U256>,             // Col 6: 1 if return_i < 0, else 0
+    pub col_is_real_row: Vec<U256>,             // Col 7: 1 for trade rows, 0 for padding
+    pub len: usize,                             // Padded power-of-2 length
+    pub actual_trade_count: usize,              // Actual number of trades
+}
+
+impl SortinoTrace {
+    /// Generate a Sortino trace from trade records.
+    ///
+    /// The selector comes from the sign of `return_bps`; at least one trade
+    /// must lose money, otherwise the downside deviation (and BC3's
+    /// denominator) is zero.
+    pub fn generate(trades: &[GmxTradeRecord], dataset_commitment: Option<U256>) -> Self {
+        let actual_count = trades.len();
+        assert!(actual_count >= 2, "need at least 2 trades");
+        assert!(
+            trades.iter().any(|t| t.return_bps.is_negative()),
+            "need at least one losing trade"
+        );
+
+        // Pad to next power of 2
+        let trace_len = actual_count.next_power_of_two();
+
+        let n_field = U256::from(actual_count as u64);
+        let commitment_val = dataset_commitment.unwrap_or(U256::ZERO);
+
+        let mut col_return = Vec::with_capacity(trace_len);
+        let mut col_downside_sq = Vec::with_capacity(trace_len);
+        let mut col_cumulative_return = Vec::with_capacity(trace_len);
+        let mut col_cumulative_downside_sq = Vec::with_capacity(trace_len);
+        let mut col_is_negative = Vec::with_capacity(trace_len);
+        let mut col_trade_count = Vec::with_capacity(trace_len);
+        let mut col_dataset_commitment = Vec::with_capacity(trace_len);
+        let mut col_is_real_row = Vec::with_capacity(trace_len);
+
+        let mut cum_ret = U256::ZERO;
+        let mut cum_downside_sq = U256::ZERO;
+
+        // Fill actual trade rows
+        for trade in trades {
+            let ret_field = trade.return_bps.to_field();
+            let is_negative = trade.return_bps.is_negative();
+            let downside_sq = if is_negative {
+                BN254Field::mul(ret_field, ret_field)
+            } else {
+                U256::ZERO
+            };
+
+            cum_ret = BN254Field::add(cum_ret, ret_field);
+            cum_downside_sq = BN254Field::add(cum_downside_sq, downside_sq);
+
+            col_return.push(ret_field);
+            col_downside_sq.push(downside_sq);
+            col_cumulative_return.push(cum_ret);
+            col_cumulative_downside_sq.push(cum_downside_sq);
+            col_is_negative.push(U256::from(is_negative as u64));
+            col_trade_count.push(n_field);
+            col_dataset_commitment.push(commitment_val);
+            col_is_real_row.push(U256::from(1u64));
+        }
+
+        pad_columns(
+            [
+                &mut col_return,
+                &mut col_downside_sq,
+                &mut col_cumulative_return,
+                &mut col_cumulative_downside_sq,
+                &mut col_trade_count,
+                &mut col_dataset_commitment,
+                &mut col_is_negative,
+                &mut col_is_real_row,
+            ],
+            &PADDING,
+            trace_len,
+        );
+
+        let trace = SortinoTrace {
+            col_return,
+            col_downside_sq,
+            col_cumulative_return,
+            col_cumulative_downside_sq,
+            col_trade_count,
+            col_dataset_commitment,
+            col_is_negative,
+            col_is_real_row,
+            len: trace_len,
+            actual_trade_count: actual_count,
+        };
+        trace.assert_padding_valid();
+        trace
+    }
+
+    /// Panic if the padded rows break a transition constraint, i.e. if
+    /// `PADDING` disagrees with the AIR.
+    fn assert_padding_valid(&self) {
+        let columns = [
+            &self.col_return[..],
+            &self.col_downside_sq,
+            &self.col_cumulative_return,
+            &self.col_cumulative_downside_sq,
+            &self.col_trade_count,
+            &self.col_dataset_commitment,
+            &self.col_is_negative,
+            &self.col_is_real_row,
+        ];
+        if let Some(violation) = sortino_padding_violations(&columns, self.actual_trade_count).first() {
+            panic!("padding policy does not match the Sortino AIR: {}", violation);
+        }
+    }
+
+    /// Get the public inputs for verification.
+    ///
+    /// Returns [trade_count, total_return, sortino_sq_scaled, merkle_root]
+    pub fn public_inputs(&self, claimed_sortino_sq_scaled: U256) -> [U256; 4] {
+        self.public_inputs_with_scheme(claimed_sortino_sq_scaled, MerkleScheme::Ordered)
+    }
+
+    /// Public inputs with `merkle_root` built under the given pairing scheme.
+    pub fn public_inputs_with_scheme(
+        &self,
+        claimed_sortino_sq_scaled: U256,
+        scheme: MerkleScheme,
+    ) -> [U256; 4] {
+        let trade_count = U256::from(self.actual_trade_count as u64);
+        let total_return = self.col_cumulative_return[self.actual_trade_count - 1];
+
+        // Merkle root of dataset_commitment column
+        let merkle_root = MerkleTree::build_with_scheme(&self.col_dataset_commitment, scheme).root();
+
+        [trade_count, total_return, claimed_sortino_sq_scaled, merkle_root]
+    }
+
+    /// Get log2 of padded trace length.
+    pub fn log_len(&self) -> u32 {
+        self.len.trailing_zeros()
+    }
+
+    /// Compute the expected sortino_sq_scaled from the trace data.
+    /// Uses field division: cum_ret^2 * SCALE / (N * cum_downside_sq)
+    pub fn compute_sortino_sq_scaled(&self) -> U256 {
+        let cum_ret = self.col_cumulative_return[self.actual_trade_count - 1];
+        let cum_downside_sq = self.col_cumulative_downside_sq[self.actual_trade_count - 1];
+        let n = U256::from(self.actual_trade_count as u64);
+        let scale = U256::from(SHARPE_SCALE);
+
+        let cum_ret_sq = BN254Field::mul(cum_ret, cum_ret);
+        let numerator = BN254Field::mul(cum_ret_sq, scale);
+        let denominator = BN254Field::mul(n, cum_downside_sq);
+
+        BN254Field::div(numerator, denominator)
+    }
+
+    /// The columns in commitment order.
+    pub fn into_columns(self) -> Vec<Vec<U256>> {
+        vec![
+            self.col_return,
+            self.col_downside_sq,
+            self.col_cumulative_return,
+            self.col_cumulative_downside_sq,
+            self.col_trade_count,
+            self.col_dataset_commitment,
+            self.col_is_negative,
+            self.col_is_real_row,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_data::{bot_a_aggressive_eth, bot_c_swing_trader, bot_d_steady_scalper};
+
+    #[test]
+    fn test_bot_c_trace_generation() {
+        let bot = bot_c_swing_trader();
+        let trace = SortinoTrace::generate(&bot.trades, None);
+
+        assert_eq!(trace.actual_trade_count, 12);
+        assert_eq!(trace.len, 16); // 12 padded to 16
+        assert_eq!(trace.log_len(), 4);
+
+        let columns = trace.into_columns();
+        assert_eq!(columns.len(), NUM_COLUMNS);
+        assert!(columns.iter().all(|c| c.len() == 16));
+    }
+
+    #[test]
+    fn test_downside_sq_follows_selector() {
+        let bot = bot_c_swing_trader();
+        let trace = SortinoTrace::generate(&bot.trades, None);
+        let one = U256::from(1u64);
+
+        for i in 0..trace.len {
+            let is_negative = trace.col_is_negative[i];
+            assert!(is_negative == U256::ZERO || is_negative == one, "selector not boolean at row {}", i);
+
+            // downside_sq = is_negative * return^2
+            let ret_sq = BN254Field::mul(trace.col_return[i], trace.col_return[i]);
+            assert_eq!(trace.col_downside_sq[i], BN254Field::mul(is_negative, ret_sq));
+        }
+
+        // The selector matches the sign of the trade return
+        for (i, trade) in bot.trades.iter().enumerate() {
+            assert_eq!(trace.col_is_negative[i] == one, trade.return_bps.is_negative());
+        }
+
+        // Padding rows (12..16) are deselected
+        assert!(trace.col_is_real_row[..12].iter().all(|&s| s == one));
+        assert!(trace.col_is_real_row[12..].iter().all(|&s| s == U256::ZERO));
+    }
+
+    #[test]
+    fn test_cumulative_downside_sq_consistency() {
+        let bot = bot_d_steady_scalper();
+        let trace = SortinoTrace::generate(&bot.trades, None);
+
+        // BC1: cum_downside_sq[0] = downside_sq[0]
+        assert_eq!(trace.col_cumulative_downside_sq[0], trace.col_downside_sq[0]);
+        for i in 1..trace.len {
+            let expected = BN254Field::add(
+                trace.col_cumulative_downside_sq[i - 1],
+                trace.col_downside_sq[i],
+            );
+            assert_eq!(
+                trace.col_cumulative_downside_sq[i], expected,
+                "Cumulative downside mismatch at row {}", i
+            );
+        }
+    }
+
+    #[test]
+    fn test_sortino_sq_scaled_bot_c() {
+        let bot = bot_c_swing_trader();
+        let trace = SortinoTrace::generate(&bot.trades, None);
+        assert_eq!(
+            trace.compute_sortino_sq_scaled(),
+            U256::from(bot.expected_sortino_sq_scaled.unwrap())
+        );
+    }
+
+    #[test]
+    fn test_sortino_sq_scaled_bot_d() {
+        let bot = bot_d_steady_scalper();
+        let trace = SortinoTrace::generate(&bot.trades, None);
+        assert_eq!(trace.len, 32); // 20 padded to 32
+        assert_eq!(
+            trace.compute_sortino_sq_scaled(),
+            U256::from(bot.expected_sortino_sq_scaled.unwrap())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "need at least one losing trade")]
+    fn test_rejects_trades_without_losses() {
+        SortinoTrace::generate(&bot_a_aggressive_eth().trades, None);
+    }
+}
@@ -5,6 +5,8 @@
 
 use wasm_bindgen::prelude::*;
 
+use crate::{prove_sharpe_step, ProveOptions, ProverState, StepResult};
+
 /// WASM-accessible STARK prover.
 #[wasm_bindgen]
 pub struct StarkProverWasm;
@@ -67,7 +69,8 @@ impl StarkProverWasm {
     /// returns_bps: array of trade returns in basis points
     /// dataset_commitment_hex: "0x..." hex string of the dataset commitment (or empty for no commitment)
     /// num_queries: number of FRI queries
-    /// callback: JS function(stage, detail, percent) for progress updates
+    /// callback: JS function(stage, detail, percent) for progress updates; a
+    /// "padding" stage warns when over half the trace is padding
     #[wasm_bindgen(js_name = "generateSharpeProofWithCommitment")]
     pub fn generate_sharpe_proof_with_commitment(
         &self,
@@ -90,7 +93,7 @@ impl StarkProverWasm {
         // Convert returns_bps to GmxTradeRecord (only return_bps matters for Sharpe)
         let trades: Vec<crate::mock_data::GmxTradeRecord> = returns_bps
             .iter()
-            .map(|&bps| crate::mock_data::GmxTradeRecord::from_return_bps(bps as i64))
+            .map(|&bps| crate::mock_data::GmxTradeRecord::from_return_bps(bps.into()))
             .collect();
 
         if trades.len() < 2 {
@@ -101,11 +104,16 @@ impl StarkProverWasm {
         let trace = crate::sharpe_trace::SharpeTrace::generate(&trades, commitment);
         let claimed = trace.compute_sharpe_sq_scaled();
 
-        let proof = crate::prove_sharpe_with_progress(
+        let options = ProveOptions {
+            warn_padding_ratio: Some(crate::padding::DEFAULT_WARN_PADDING_RATIO),
+            ..Default::default()
+        };
+        let proof = crate::prove_sharpe_with_options(
             &trades,
             claimed,
             num_queries as usize,
             commitment,
+            &options,
             |progress| {
                 let this = JsValue::null();
                 let stage = JsValue::from_str(progress.stage);
@@ -132,3 +140,68 @@ impl StarkProverWasm {
         self.generate_sharpe_proof_with_commitment(returns_bps, "", num_queries, callback)
     }
 }
+
+/// Resumable Sharpe proof generation.
+///
+/// Call `step()` once per event-loop turn (e.g. from `setTimeout` or
+/// `requestIdleCallback`) until it returns `true`, then read `proofJson()`.
+#[wasm_bindgen]
+pub struct SharpeProofSession {
+    state: Option<ProverState>,
+    proof_json: Option<String>,
+}
+
+#[wasm_bindgen]
+impl SharpeProofSession {
+    /// Start a session from return_bps (no commitment). Proving work starts on
+    /// the first `step()`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(returns_bps: &[i32], num_queries: u32) -> SharpeProofSession {
+        let trades: Vec<crate::mock_data::GmxTradeRecord> = returns_bps
+            .iter()
+            .map(|&bps| crate::mock_data::GmxTradeRecord::from_return_bps(bps.into()))
+            .collect();
+        let state = (trades.len() >= 2).then(|| {
+            let claimed = crate::sharpe_trace::SharpeTrace::generate(&trades, None).compute_sharpe_sq_scaled();
+            let options = ProveOptions {
+                warn_padding_ratio: Some(crate::padding::DEFAULT_WARN_PADDING_RATIO),
+                ..Default::default()
+            };
+            ProverState::new(&trades, claimed, num_queries as usize, None, &options)
+        });
+        SharpeProofSession { state, proof_json: None }
+    }
+
+    /// Why the trade count wastes over half the trace on padding, or "" if
+    /// it does not. Only available before the first `step()`.
+    #[wasm_bindgen(js_name = "paddingWarning")]
+    pub fn padding_warning(&self) -> String {
+        self.state
+            .as_ref()
+            .and_then(|s| s.padding_warning())
+            .map_or_else(String::new, |w| w.to_string())
+    }
+
+    /// Name of the stage the next `step()` runs, or "done".
+    #[wasm_bindgen(js_name = "nextStage")]
+    pub fn next_stage(&self) -> String {
+        self.state.as_ref().map_or("done", |s| s.next_stage()).to_string()
+    }
+
+    /// Run one proving stage. Returns `true` once the proof is ready.
+    pub fn step(&mut self) -> bool {
+        if let Some(state) = self.state.take() {
+            match prove_sharpe_step(state) {
+                StepResult::InProgress(next) => self.state = Some(next),
+                StepResult::Done(proof) => self.proof_json = Some(proof.to_json()),
+            }
+        }
+        self.state.is_none()
+    }
+
+    /// The serialized proof, or "{}" until `step()` has returned `true`.
+    #[wasm_bindgen(js_name = "proofJson")]
+    pub fn proof_json(&self) -> String {
+        self.proof_json.clone().unwrap_or_else(|| "{}".to_string())
+    }
+}
@@ -74,10 +74,15 @@ echo -e "${BLUE}Deploying to Arbitrum Sepolia...${NC}"
 echo -e "${YELLOW}RPC: $RPC_ENDPOINT${NC}"
 echo ""
 
+# Groth16 key for verify_sharpe_wrapped, as groth16::VerifyingKey::to_words
+# ("[w0,w1,...]"); the default empty key leaves wrapped proofs disabled
+WRAPPED_SHARPE_VK="${WRAPPED_SHARPE_VK:-[]}"
+
 # Deploy and capture output
 DEPLOY_OUTPUT=$(cargo stylus deploy \
     --endpoint="$RPC_ENDPOINT" \
     --private-key="$PRIVATE_KEY" \
+    --constructor-args="$WRAPPED_SHARPE_VK" \
     2>&1)
 
 echo "$DEPLOY_OUTPUT"